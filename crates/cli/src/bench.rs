@@ -0,0 +1,220 @@
+//! In-process throughput measurement for configured routes.
+//!
+//! This module drives the application router directly, without opening a
+//! socket, to give a quick indication of how expensive a route is to serve.
+//! It is a diagnostic aid for spotting slow routes such as large array
+//! serialization, not a replacement for a load testing tool.
+//!
+//! ## What
+//!
+//! The module defines:
+//! - `BenchReport`: Aggregated throughput and latency figures of a run
+//! - `run_bench`: Fires a number of sequential requests at a route
+//!
+//! ## How
+//!
+//! A run works by:
+//! 1. Building a GET request for the route for every iteration
+//! 2. Dispatching it through `tower::ServiceExt::oneshot` on a router clone
+//! 3. Reading the full response body so serialization cost is included
+//! 4. Recording per-request latency and computing percentiles at the end
+//!
+//! ## Why
+//!
+//! Measuring in-process exercises the complete handler path, including
+//! lookups and serialization, while excluding network noise from the results.
+//!
+//! # Examples
+//!
+//! ```bash
+//! json-echo --config db.json bench --route /users --requests 10000
+//! ```
+
+use axum::{
+    Router,
+    body::{Body, to_bytes},
+    http::Request,
+};
+use std::{
+    fmt,
+    io::{Error as IOError, ErrorKind},
+    time::{Duration, Instant},
+};
+use tower::ServiceExt;
+
+/// Aggregated results of a benchmark run.
+///
+/// # Fields
+///
+/// * `route` - The route path that was requested
+/// * `requests` - The number of requests sent
+/// * `failures` - The number of responses with a non-success status
+/// * `elapsed` - The total wall-clock duration of the run
+/// * `p50` - The median request latency
+/// * `p99` - The 99th percentile request latency
+#[derive(Debug, Clone)]
+pub struct BenchReport {
+    /// The route path that was requested
+    pub route: String,
+    /// The number of requests sent
+    pub requests: usize,
+    /// The number of responses with a non-success status
+    pub failures: usize,
+    /// The total wall-clock duration of the run
+    pub elapsed: Duration,
+    /// The median request latency
+    pub p50: Duration,
+    /// The 99th percentile request latency
+    pub p99: Duration,
+}
+
+impl BenchReport {
+    /// Returns the measured throughput in requests per second.
+    #[allow(clippy::cast_precision_loss)]
+    pub fn requests_per_second(&self) -> f64 {
+        let seconds = self.elapsed.as_secs_f64();
+        if seconds > 0.0 {
+            self.requests as f64 / seconds
+        } else {
+            0.0
+        }
+    }
+}
+
+impl fmt::Display for BenchReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Route:      {}", self.route)?;
+        writeln!(
+            f,
+            "Requests:   {} ({} failed)",
+            self.requests, self.failures
+        )?;
+        writeln!(f, "Elapsed:    {:.3?}", self.elapsed)?;
+        writeln!(f, "Throughput: {:.0} req/s", self.requests_per_second())?;
+        writeln!(f, "Latency:    p50 {:.3?}, p99 {:.3?}", self.p50, self.p99)
+    }
+}
+
+/// Sends `requests` sequential GET requests to `route` through the router.
+///
+/// # Parameters
+///
+/// * `router` - The fully configured application router
+/// * `route` - The request path, e.g. `/users` or `/users/1`
+/// * `requests` - The number of requests to send, at least one
+///
+/// # Returns
+///
+/// * `Ok(BenchReport)` - The aggregated results of the run
+/// * `Err(IOError)` - If the route is not a valid URI or no requests were asked for
+///
+/// # Examples
+///
+/// ```rust
+/// let report = run_bench(router, "/users", 1000).await?;
+/// println!("{report}");
+/// ```
+pub async fn run_bench(
+    router: Router,
+    route: &str,
+    requests: usize,
+) -> Result<BenchReport, IOError> {
+    if requests == 0 {
+        return Err(IOError::new(
+            ErrorKind::InvalidInput,
+            "At least one request is required",
+        ));
+    }
+
+    let mut latencies = Vec::with_capacity(requests);
+    let mut failures = 0;
+    let started = Instant::now();
+
+    for _ in 0..requests {
+        let request = Request::builder()
+            .uri(route)
+            .body(Body::empty())
+            .map_err(|error| IOError::new(ErrorKind::InvalidInput, error))?;
+
+        let request_started = Instant::now();
+        let response = router
+            .clone()
+            .oneshot(request)
+            .await
+            .map_err(|error| IOError::other(error.to_string()))?;
+        let status = response.status();
+        to_bytes(response.into_body(), usize::MAX)
+            .await
+            .map_err(|error| IOError::other(error.to_string()))?;
+        latencies.push(request_started.elapsed());
+
+        if !status.is_success() {
+            failures += 1;
+        }
+    }
+
+    let elapsed = started.elapsed();
+    latencies.sort_unstable();
+
+    Ok(BenchReport {
+        route: route.to_string(),
+        requests,
+        failures,
+        elapsed,
+        p50: percentile(&latencies, 50),
+        p99: percentile(&latencies, 99),
+    })
+}
+
+/// Returns the nearest-rank percentile of sorted latencies.
+fn percentile(sorted: &[Duration], percent: usize) -> Duration {
+    let rank = (sorted.len() * percent).div_ceil(100).max(1);
+    sorted.get(rank - 1).copied().unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::server::{AppState, create_router};
+    use json_echo_core::{ConfigManager, Database, FileSystemManager};
+    use std::sync::Arc;
+
+    /// Tests a tiny benchmark over a small inline configuration.
+    ///
+    /// Verifies that every request succeeds and a positive throughput is reported.
+    #[tokio::test]
+    async fn test_run_bench_reports_throughput() {
+        let file_system_manager =
+            FileSystemManager::new(None).expect("Failed to create filesystem manager");
+        let mut config_manager = ConfigManager::new(file_system_manager);
+        config_manager
+            .load_config_from_bytes(
+                br#"{"routes": {"/users": {"response": {"status": 200, "body": [{"id": 1}]}}}}"#,
+            )
+            .await
+            .expect("Failed to load test config");
+
+        let mut db = Database::new();
+        db.populate(config_manager.config.routes.clone());
+        let router = create_router(Arc::new(AppState::new(db)), &config_manager);
+
+        let report = run_bench(router, "/users", 20)
+            .await
+            .expect("Bench should run");
+
+        assert_eq!(report.requests, 20);
+        assert_eq!(report.failures, 0, "All requests should succeed");
+        assert!(report.requests_per_second() > 0.0);
+        assert!(report.p50 <= report.p99);
+    }
+
+    /// Tests nearest-rank percentile selection.
+    #[test]
+    fn test_percentile() {
+        let latencies: Vec<Duration> = (1..=100).map(Duration::from_millis).collect();
+
+        assert_eq!(percentile(&latencies, 50), Duration::from_millis(50));
+        assert_eq!(percentile(&latencies, 99), Duration::from_millis(99));
+        assert_eq!(percentile(&latencies[..1], 99), Duration::from_millis(1));
+    }
+}
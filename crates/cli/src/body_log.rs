@@ -0,0 +1,225 @@
+//! Body logging for `serve --log-bodies`.
+//!
+//! This module logs request and response bodies as they stream between the
+//! client and the handlers, without ever holding back, reading ahead or
+//! refusing a body because it is being logged.
+//!
+//! ## What
+//!
+//! The module defines:
+//! - `CAPTURE_LIMIT`: The largest body kept in memory to be logged, in bytes
+//! - `LoggedBody`: A body passing its frames through while keeping a copy of the first bytes
+//!
+//! ## How
+//!
+//! Logging a body works by:
+//! 1. Wrapping it, so every data frame is handed on as soon as it is read
+//! 2. Copying the frames into a buffer until `CAPTURE_LIMIT` bytes are kept
+//! 3. Logging the body once the wrapper is dropped: redacted and cut at the
+//!    `--log-body-limit` when it was read whole within `CAPTURE_LIMIT`, or by
+//!    its size alone otherwise
+//! 4. Emitting the event with the span and subscriber of the request, so the
+//!    route's `log_level` and `request_id` apply even when the response body
+//!    is dropped after the handler returned
+//!
+//! ## Why
+//!
+//! Reading a body whole before the handler would turn body logging into a
+//! body limit, breaking uploads streamed to disk, and would buffer every
+//! response. Redaction needs the whole JSON document, so a body seen only in
+//! part is never logged as text, which could show a masked field in clear.
+//!
+//! # Examples
+//!
+//! ```rust
+//! let body = LoggedBody::request(body, Arc::clone(&state), limit, span.clone());
+//! let request = Request::from_parts(parts, Body::new(body));
+//! ```
+
+use crate::server::AppState;
+use axum::{
+    body::{Body, Bytes, HttpBody},
+    http::StatusCode,
+};
+use http_body::{Frame, SizeHint};
+use std::{
+    pin::Pin,
+    sync::Arc,
+    task::{Context, Poll},
+};
+use tracing::{Dispatch, Span, debug};
+
+/// The largest body kept in memory to be logged, in bytes; larger bodies are logged by size.
+pub const CAPTURE_LIMIT: usize = 2 * 1024 * 1024;
+
+/// A body passing its frames through while keeping a copy of the first bytes for the log.
+///
+/// The body is logged when the wrapper is dropped, whether it was read to
+/// its end or not.
+///
+/// # Fields
+///
+/// * `inner` - The wrapped body
+/// * `label` - What the log event calls the body, e.g. `Request body`
+/// * `log_empty` - Whether an empty body is logged
+/// * `state` - Shared application state holding the redactor
+/// * `limit` - The number of bytes logged, from `--log-body-limit`
+/// * `captured` - The first bytes of the body, at most `CAPTURE_LIMIT`
+/// * `size` - The number of bytes read so far
+/// * `ended` - Whether the body was read to its end
+/// * `span` - The span of the request, parent of the log event
+/// * `dispatch` - The subscriber active when the body was wrapped
+pub struct LoggedBody {
+    /// The wrapped body
+    inner: Body,
+    /// What the log event calls the body
+    label: String,
+    /// Whether an empty body is logged
+    log_empty: bool,
+    /// Shared application state holding the redactor
+    state: Arc<AppState>,
+    /// The number of bytes logged
+    limit: usize,
+    /// The first bytes of the body
+    captured: Vec<u8>,
+    /// The number of bytes read so far
+    size: usize,
+    /// Whether the body was read to its end
+    ended: bool,
+    /// The span of the request
+    span: Span,
+    /// The subscriber active when the body was wrapped
+    dispatch: Dispatch,
+}
+
+impl LoggedBody {
+    /// Wraps a request body to log it once the handler has read it.
+    ///
+    /// An empty request body is not logged.
+    ///
+    /// # Parameters
+    ///
+    /// * `inner` - The body to pass through
+    /// * `state` - Shared application state holding the redactor
+    /// * `limit` - The number of bytes logged
+    /// * `span` - The span of the request
+    ///
+    /// # Returns
+    ///
+    /// A body yielding the same frames as `inner`
+    pub fn request(inner: Body, state: Arc<AppState>, limit: usize, span: Span) -> Self {
+        Self::new(inner, "Request body".to_string(), false, state, limit, span)
+    }
+
+    /// Wraps a response body to log it once it has been sent.
+    ///
+    /// # Parameters
+    ///
+    /// * `inner` - The body to pass through
+    /// * `status` - The status of the response, named in the log event
+    /// * `state` - Shared application state holding the redactor
+    /// * `limit` - The number of bytes logged
+    /// * `span` - The span of the request
+    ///
+    /// # Returns
+    ///
+    /// A body yielding the same frames as `inner`
+    pub fn response(
+        inner: Body,
+        status: StatusCode,
+        state: Arc<AppState>,
+        limit: usize,
+        span: Span,
+    ) -> Self {
+        let label = format!("Response {} body", status.as_u16());
+        Self::new(inner, label, true, state, limit, span)
+    }
+
+    /// Wraps a body, capturing the subscriber active at the call.
+    fn new(
+        inner: Body,
+        label: String,
+        log_empty: bool,
+        state: Arc<AppState>,
+        limit: usize,
+        span: Span,
+    ) -> Self {
+        Self {
+            inner,
+            label,
+            log_empty,
+            state,
+            limit,
+            captured: Vec::new(),
+            size: 0,
+            ended: false,
+            span,
+            dispatch: tracing::dispatcher::get_default(Dispatch::clone),
+        }
+    }
+
+    /// Returns the log line describing the body read so far.
+    fn logged(&self) -> String {
+        if self.ended && self.size <= CAPTURE_LIMIT {
+            self.state.redactor().body(&self.captured, self.limit)
+        } else if self.ended {
+            format!("{} bytes, too large to log", self.size)
+        } else {
+            format!(
+                "{} bytes read before the body was dropped, not logged",
+                self.size
+            )
+        }
+    }
+}
+
+impl HttpBody for LoggedBody {
+    type Data = Bytes;
+    type Error = axum::Error;
+
+    fn poll_frame(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Result<Frame<Self::Data>, Self::Error>>> {
+        let this = self.get_mut();
+        let polled = Pin::new(&mut this.inner).poll_frame(cx);
+
+        match &polled {
+            Poll::Ready(Some(Ok(frame))) => {
+                if let Some(data) = frame.data_ref() {
+                    this.size += data.len();
+                    let room = CAPTURE_LIMIT.saturating_sub(this.captured.len());
+                    this.captured
+                        .extend_from_slice(&data[..data.len().min(room)]);
+                }
+            }
+            Poll::Ready(None) => this.ended = true,
+            Poll::Ready(Some(Err(_))) | Poll::Pending => {}
+        }
+
+        polled
+    }
+
+    fn is_end_stream(&self) -> bool {
+        self.inner.is_end_stream()
+    }
+
+    fn size_hint(&self) -> SizeHint {
+        self.inner.size_hint()
+    }
+}
+
+impl Drop for LoggedBody {
+    fn drop(&mut self) {
+        // A body known to be empty may never be polled to its end
+        self.ended |= self.inner.is_end_stream();
+        if self.size == 0 && !self.log_empty {
+            return;
+        }
+
+        let logged = self.logged();
+        tracing::dispatcher::with_default(&self.dispatch, || {
+            debug!(parent: &self.span, "{}: {}", self.label, logged);
+        });
+    }
+}
@@ -0,0 +1,201 @@
+//! Bundling of a JSON Echo configuration into a single self-contained file.
+//!
+//! This module turns a loaded configuration, whose routes may answer with
+//! external response files, into one that carries every response inline, so
+//! a mock API can be deployed as a single portable artifact.
+//!
+//! ## What
+//!
+//! The module defines:
+//! - `Bundle`: The self-contained configuration and the files it still depends on
+//! - `bundle_config`: Inlines the remaining external files of a loaded configuration
+//!
+//! ## How
+//!
+//! A bundle is built by:
+//! 1. Loading the configuration as for serving, which inlines the response
+//!    files of eager routes and applies the `seed_file`
+//! 2. Reading the response files of `lazy` routes, which loading keeps as
+//!    references, and inlining them as well
+//! 3. Dropping the `seed_file`, `routes_dir` and `lazy` settings, which no longer refer to anything
+//! 4. Listing the files that cannot be inlined: SQLite databases and the static folder,
+//!    the databases referenced relative to the root again rather than by the
+//!    absolute path loading resolved them to
+//!
+//! ## Why
+//!
+//! The loading done by `ConfigManager` is reused rather than repeated, so a
+//! bundle serves exactly what the original configuration serves. SQLite
+//! databases and static files are binary or whole directories that a JSON
+//! configuration cannot hold; they are reported instead of silently left
+//! behind, so the artifact is known to need them.
+//!
+//! # Examples
+//!
+//! ```bash
+//! json-echo --config json-echo.json bundle -o bundle.json
+//! ```
+
+use json_echo_core::{
+    Config, ConfigResponse, FileSystemManager, FileSystemResult, load_route_response,
+};
+use std::path::Path;
+
+/// A configuration carrying its responses inline.
+///
+/// # Fields
+///
+/// * `config` - The self-contained configuration
+/// * `external` - The files the configuration still depends on, described for the user
+#[derive(Debug, Clone)]
+pub struct Bundle {
+    /// The self-contained configuration
+    pub config: Config,
+    /// The files the configuration still depends on
+    pub external: Vec<String>,
+}
+
+/// Inlines the external files a loaded configuration still refers to.
+///
+/// # Parameters
+///
+/// * `files` - The filesystem manager the configuration was loaded with
+/// * `config` - A configuration loaded by `ConfigManager`, its references resolved
+///
+/// # Returns
+///
+/// * `Ok(Bundle)` - The configuration with every response inline
+/// * `Err(FileSystemError)` - If the response file of a lazy route cannot be read
+///
+/// # Examples
+///
+/// ```rust
+/// config_manager.load_config("json-echo.json").await?;
+/// let bundle = bundle_config(&file_system_manager, config_manager.config.clone()).await?;
+/// assert!(bundle.external.is_empty());
+/// ```
+pub async fn bundle_config(
+    files: &FileSystemManager,
+    mut config: Config,
+) -> FileSystemResult<Bundle> {
+    let mut external = Vec::new();
+    // Loading resolves `sql` files canonically, so they are made relative to the canonical root
+    let root = files.resolve_within_root(".").await?;
+
+    for (path, route) in &mut config.routes {
+        // Loading resolves lazy references to an absolute path inside the root
+        if let ConfigResponse::String(file) = &route.response {
+            let response = load_route_response(files, Path::new(file)).await?;
+            route.response = ConfigResponse::ConfigRouteResponse(response);
+        }
+        route.lazy = false;
+
+        if let Some(sql) = &mut route.sql
+            && let Ok(relative) = Path::new(&sql.file).strip_prefix(&root)
+        {
+            sql.file = relative.to_string_lossy().into_owned();
+        }
+        if let Some(sql) = &route.sql {
+            external.push(format!("Route {path} queries the SQLite file {}", sql.file));
+        }
+    }
+
+    // The seed bodies and route files were applied while loading
+    config.seed_file = None;
+    config.routes_dir = None;
+
+    if let Some(static_folder) = &config.static_folder {
+        external.push(format!("Static files are served from {static_folder}"));
+    }
+
+    Ok(Bundle { config, external })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::diff::load_config_file;
+    use crate::merge::save_config_file;
+    use serde_json::json;
+
+    /// Tests bundling a configuration that refers to external response files.
+    ///
+    /// Verifies that the saved bundle has no file reference left, eager or
+    /// lazy, that the inlined bodies match the files, that seeded bodies are
+    /// kept without the seed file, and that SQLite files stay relative to the root.
+    #[tokio::test]
+    async fn test_bundle_inlines_external_files() {
+        let dir = tempfile::tempdir().expect("Failed to create temporary directory");
+        let write = |name: &str, content: &serde_json::Value| {
+            std::fs::write(dir.path().join(name), content.to_string())
+                .expect("Failed to write file");
+        };
+        let users = json!({"status": 200, "body": [{"id": 1, "name": "Ada"}]});
+        let orders = json!({"status": 200, "body": [{"id": 7}]});
+        write("users.json", &users);
+        write("orders.json", &orders);
+        write("seed.json", &json!({"/health": {"ok": true}}));
+        std::fs::create_dir(dir.path().join("db")).expect("Failed to create db");
+        std::fs::write(dir.path().join("db").join("fixtures.db"), b"")
+            .expect("Failed to write database");
+        write(
+            "json-echo.json",
+            &json!({
+                "seed_file": "seed.json",
+                "routes": {
+                    "/users": {"response": "users.json"},
+                    "/orders": {"lazy": true, "response": "orders.json"},
+                    "/health": {"response": {"status": 200, "body": {}}},
+                    "/reports": {"sql": {"file": "db/fixtures.db", "query": "SELECT 1"}}
+                }
+            }),
+        );
+
+        let config = load_config_file(&dir.path().join("json-echo.json"))
+            .await
+            .expect("Failed to load config");
+        let files = FileSystemManager::new(Some(dir.path().to_path_buf()))
+            .expect("Failed to create filesystem manager");
+        let bundle = bundle_config(&files, config)
+            .await
+            .expect("Bundling should succeed");
+        assert_eq!(
+            bundle.external,
+            ["Route [GET] /reports queries the SQLite file db/fixtures.db"]
+        );
+
+        let output = dir.path().join("dist").join("bundle.json");
+        std::fs::create_dir(dir.path().join("dist")).expect("Failed to create dist");
+        save_config_file(&output, &bundle.config)
+            .await
+            .expect("Failed to save bundle");
+
+        let saved: serde_json::Value =
+            serde_json::from_slice(&std::fs::read(&output).expect("Failed to read bundle"))
+                .expect("Bundle should be JSON");
+        assert_eq!(saved.get("seed_file"), None);
+        assert_eq!(
+            saved["routes"]["[GET] /reports"]["sql"]["file"],
+            "db/fixtures.db"
+        );
+        for route in saved["routes"].as_object().expect("Routes object").values() {
+            assert!(!route["response"].is_string(), "No file reference remains");
+            assert_eq!(route.get("lazy"), None);
+        }
+        assert_eq!(saved["routes"]["[GET] /users"]["response"], users);
+        assert_eq!(saved["routes"]["[GET] /orders"]["response"], orders);
+        assert_eq!(
+            saved["routes"]["[GET] /health"]["response"]["body"],
+            json!({"ok": true})
+        );
+
+        // The bundle loads away from the original files, its database deployed next to it
+        std::fs::create_dir(dir.path().join("dist").join("db")).expect("Failed to create db");
+        std::fs::write(dir.path().join("dist").join("db").join("fixtures.db"), b"")
+            .expect("Failed to write database");
+        let reloaded = load_config_file(&output)
+            .await
+            .expect("Bundle should load without the original files");
+        assert_eq!(reloaded.routes.len(), 4);
+    }
+}
@@ -0,0 +1,97 @@
+//! Caching header presets for JSON Echo routes.
+//!
+//! This module expands a route's `cache` shorthand into the `Cache-Control`
+//! header, and a far-future `Expires` for immutable responses, so routes can
+//! pick a caching behavior without spelling out the directives by hand.
+//!
+//! ## What
+//!
+//! The module defines:
+//! - `cache_headers`: Middleware adding the preset headers to successful responses
+//!
+//! ## How
+//!
+//! Presets expand as follows:
+//!
+//! | `cache` | `Cache-Control` | `Expires` |
+//! |---------|-----------------|-----------|
+//! | `"no-store"` | `no-store` | - |
+//! | `"immutable"` | `public, max-age=31536000, immutable` | one year from now |
+//! | `{"max_age": 60}` | `max-age=60` | - |
+//!
+//! ## Why
+//!
+//! Only successful and `304 Not Modified` responses get the headers, so an
+//! error answered by a cached route is never stored for a year. Headers set
+//! through the route's `headers` are kept, letting a raw `Cache-Control`
+//! override the preset.
+//!
+//! # Examples
+//!
+//! ```json
+//! {"/assets/logo": {"cache": "immutable", "response": {"body": {}}}}
+//! ```
+
+use crate::server::AppState;
+use crate::timestamp::format_http_date;
+use axum::{
+    extract::{MatchedPath, Request, State},
+    http::{
+        HeaderValue, StatusCode,
+        header::{CACHE_CONTROL, EXPIRES},
+    },
+    middleware::Next,
+    response::Response,
+};
+use std::{
+    sync::Arc,
+    time::{Duration, SystemTime},
+};
+
+/// Middleware adding the headers of the route's `cache` preset to its responses.
+///
+/// # Parameters
+///
+/// * `State(state)` - Shared application state holding the route configurations
+/// * `request` - The incoming request
+/// * `next` - The remaining middleware and the route handler
+///
+/// # Returns
+///
+/// The response of the inner layers, carrying `Cache-Control`, and `Expires`
+/// for immutable responses, unless the route sets them itself
+pub async fn cache_headers(
+    State(state): State<Arc<AppState>>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let preset = request.extensions().get::<MatchedPath>().and_then(|path| {
+        state
+            .read()
+            .get_route(path.as_str(), Some(request.method().to_string()))
+            .and_then(|route| route.cache)
+    });
+
+    let mut response = next.run(request).await;
+
+    let Some(preset) = preset else {
+        return response;
+    };
+    let status = response.status();
+    if !status.is_success() && status != StatusCode::NOT_MODIFIED {
+        return response;
+    }
+
+    let headers = response.headers_mut();
+    if let Ok(value) = HeaderValue::from_str(&preset.cache_control()) {
+        headers.entry(CACHE_CONTROL).or_insert(value);
+    }
+    if let Some(seconds) = preset.expires_in() {
+        let expires = format_http_date(SystemTime::now() + Duration::from_secs(seconds));
+        if let Ok(value) = HeaderValue::from_str(&expires) {
+            headers.entry(EXPIRES).or_insert(value);
+        }
+    }
+
+    response
+}
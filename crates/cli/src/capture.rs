@@ -0,0 +1,245 @@
+//! Capture of a running API into a JSON Echo configuration.
+//!
+//! This module issues GET requests against a live service and turns the
+//! responses into routes, so a real API can be mocked in one step instead of
+//! copying bodies by hand.
+//!
+//! ## What
+//!
+//! The module defines:
+//! - `Captured`: A response read from the service
+//! - `fetch`: Sends one GET request and reads the response
+//! - `capture_route`: Turns a captured response into a route configuration
+//! - `capture_config`: Captures every path into a new configuration
+//!
+//! ## How
+//!
+//! A capture works by:
+//! 1. Joining every path to the base URL and sending a GET with the shared client
+//! 2. Reading the status, the `Content-Type` and the body, giving up after the timeout
+//! 3. Keeping JSON bodies as JSON and any other body as a string
+//! 4. Recording the status as is, error statuses included, and the content type as a header
+//!
+//! ## Why
+//!
+//! The capture is a one-shot snapshot: nothing is proxied afterwards, and the
+//! generated file is an ordinary configuration to edit and serve. Redirects
+//! are not followed, so a `3xx` is captured as the service sent it.
+//!
+//! # Examples
+//!
+//! ```bash
+//! json-echo capture --url https://api.example.com --paths /users,/orders -o users.json
+//! ```
+
+use axum::http::{
+    StatusCode,
+    header::{ACCEPT, CONTENT_TYPE},
+};
+use json_echo_core::{
+    BodyResponse, Config, ConfigResponse, ConfigRoute, ConfigRouteResponse, FileSystemError,
+    FileSystemResult,
+};
+use reqwest::Client;
+use serde_json::Value;
+use std::collections::HashMap;
+use tracing::{info, warn};
+
+/// A response read from the captured service.
+///
+/// # Fields
+///
+/// * `status` - The status code of the response
+/// * `content_type` - The `Content-Type` header, if the response has one
+/// * `body` - The decoded response body
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Captured {
+    /// The status code of the response
+    pub status: u16,
+    /// The `Content-Type` header, if the response has one
+    pub content_type: Option<String>,
+    /// The decoded response body
+    pub body: Vec<u8>,
+}
+
+/// Sends a GET request and reads the whole response.
+///
+/// # Parameters
+///
+/// * `client` - The client the request is sent with, bounding it by its timeout
+/// * `url` - The absolute `http://` or `https://` URL to request
+///
+/// # Returns
+///
+/// * `Ok(Captured)` - The response, whatever its status
+/// * `Err(FileSystemError)` - If the URL is invalid, the connection fails or
+///   times out, or the body cannot be read
+pub async fn fetch(client: &Client, url: &str) -> FileSystemResult<Captured> {
+    let failed = |error: reqwest::Error| {
+        FileSystemError::Operation(format!("Failed to capture {url}: {error}"))
+    };
+
+    let response = client
+        .get(url)
+        .header(ACCEPT, "application/json, */*")
+        .send()
+        .await
+        .map_err(failed)?;
+    let status = response.status().as_u16();
+    let content_type = response
+        .headers()
+        .get(CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .map(str::to_string);
+    let body = response.bytes().await.map_err(failed)?.to_vec();
+
+    Ok(Captured {
+        status,
+        content_type,
+        body,
+    })
+}
+
+/// Turns a captured response into a route configuration.
+///
+/// # Parameters
+///
+/// * `captured` - The response read from the service
+///
+/// # Returns
+///
+/// A route answering with the captured status and body, and the captured
+/// content type as its `Content-Type` header
+///
+/// # Examples
+///
+/// ```rust
+/// let route = capture_route(Captured {
+///     status: 404,
+///     content_type: Some("application/json".to_string()),
+///     body: br#"{"error":"not found"}"#.to_vec(),
+/// });
+/// ```
+pub fn capture_route(captured: Captured) -> ConfigRoute {
+    let text = String::from_utf8_lossy(&captured.body).into_owned();
+    let is_json = captured
+        .content_type
+        .as_deref()
+        .is_none_or(|content_type| content_type.to_ascii_lowercase().contains("json"));
+
+    let body = if is_json {
+        serde_json::from_str(&text).unwrap_or(Value::String(text))
+    } else {
+        Value::String(text)
+    };
+
+    let headers = captured
+        .content_type
+        .map(|content_type| HashMap::from([("Content-Type".to_string(), content_type)]));
+
+    ConfigRoute {
+        method: Some("GET".to_string()),
+        headers,
+        response: ConfigResponse::ConfigRouteResponse(ConfigRouteResponse {
+            status: Some(captured.status),
+            body: BodyResponse::Value(body),
+        }),
+        ..ConfigRoute::default()
+    }
+}
+
+/// Captures the responses of a running API into a new configuration.
+///
+/// # Parameters
+///
+/// * `client` - The client every request is sent with
+/// * `base_url` - The base URL of the service, e.g. `http://localhost:8080`
+/// * `paths` - The paths to request, each becoming a route of the same name
+///
+/// # Returns
+///
+/// * `Ok(Config)` - A default configuration with one route per path
+/// * `Err(FileSystemError)` - If a request fails; error statuses are captured, not failures
+pub async fn capture_config(
+    client: &Client,
+    base_url: &str,
+    paths: &[String],
+) -> FileSystemResult<Config> {
+    let base_url = base_url.trim_end_matches('/');
+    let mut config = Config::default();
+
+    for path in paths {
+        let path = if path.starts_with('/') {
+            path.clone()
+        } else {
+            format!("/{path}")
+        };
+
+        let captured = fetch(client, &format!("{base_url}{path}")).await?;
+        let status = StatusCode::from_u16(captured.status);
+        if status.is_ok_and(|status| status.is_success()) {
+            info!("Captured {} with status {}", path, captured.status);
+        } else {
+            warn!("⚠︎ Captured {} with status {}", path, captured.status);
+        }
+
+        config.routes.insert(path, capture_route(captured));
+    }
+
+    Ok(config)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::{DEFAULT_TIMEOUT, http_client};
+    use axum::{Json, Router, response::Redirect, routing::get};
+    use serde_json::json;
+
+    /// Tests capturing routes from a local stub server.
+    ///
+    /// Verifies that JSON and text bodies, error statuses, content types and
+    /// unfollowed redirects are recorded on the generated routes.
+    #[tokio::test]
+    async fn test_capture_config() {
+        let stub = Router::new()
+            .route("/users", get(|| async { Json(json!([{"id": 1}])) }))
+            .route(
+                "/missing",
+                get(|| async { (StatusCode::NOT_FOUND, Json(json!({"error": "gone"}))) }),
+            )
+            .route("/health", get(|| async { "ok" }))
+            .route("/old", get(|| async { Redirect::permanent("/users") }));
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0")
+            .await
+            .expect("Failed to bind stub server");
+        let address = listener.local_addr().expect("Stub server has no address");
+        tokio::spawn(async move { axum::serve(listener, stub).await });
+
+        let paths = ["/users", "missing", "/health", "/old"].map(str::to_string);
+        let client = http_client(DEFAULT_TIMEOUT).expect("Client should build");
+        let config = capture_config(&client, &format!("http://{address}/"), &paths)
+            .await
+            .expect("Capture should succeed");
+
+        let routes = serde_json::to_value(&config.routes).expect("Routes should serialize");
+        assert_eq!(
+            routes["/users"]["response"],
+            json!({"status": 200, "body": [{"id": 1}]})
+        );
+        assert_eq!(
+            routes["/users"]["headers"],
+            json!({"Content-Type": "application/json"})
+        );
+        assert_eq!(
+            routes["/missing"]["response"],
+            json!({"status": 404, "body": {"error": "gone"}})
+        );
+        assert_eq!(routes["/health"]["response"]["body"], "ok");
+        assert_eq!(
+            routes["/health"]["headers"]["Content-Type"],
+            "text/plain; charset=utf-8"
+        );
+        assert_eq!(routes["/old"]["response"]["status"], 308);
+    }
+}
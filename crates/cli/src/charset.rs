@@ -0,0 +1,186 @@
+//! Response charsets for JSON Echo.
+//!
+//! This module resolves the `charset` configured globally or per route, labels
+//! the response content type with it and encodes response bodies accordingly,
+//! for legacy clients that expect an explicit or non-UTF-8 charset.
+//!
+//! ## What
+//!
+//! The module defines:
+//! - `Charset`: The supported response charsets, re-exported from the core crate
+//! - `with_charset`: Sets the `charset` parameter of a content type
+//! - `encode_text`: Encodes a text body in a charset
+//! - `encode_json`: Serializes a JSON body in a charset
+//!
+//! ## How
+//!
+//! Encoding works by:
+//! 1. Parsing the configured name, accepting common aliases such as `latin-1`;
+//!    the configuration is rejected at load when a name is not supported
+//! 2. Writing the canonical name into the `Content-Type` header
+//! 3. Encoding text bodies byte for byte, replacing unrepresentable characters with `?`
+//! 4. Escaping every non-ASCII character of JSON bodies as `\uXXXX`
+//!
+//! ## Why
+//!
+//! Escaping JSON instead of replacing characters keeps the document lossless
+//! and valid in any ASCII-compatible charset, while plain text has no escape
+//! mechanism and must fall back to a replacement character.
+//!
+//! # Examples
+//!
+//! ```text
+//! "charset": "utf-8"      -> Content-Type: application/json; charset=utf-8
+//! "charset": "latin-1"    -> Content-Type: text/plain; charset=iso-8859-1
+//! ```
+
+pub use json_echo_core::Charset;
+use serde_json::Value;
+use std::fmt::Write;
+
+/// Sets the charset parameter of a content type, replacing any existing one.
+///
+/// # Parameters
+///
+/// * `content_type` - The content type, possibly with parameters
+/// * `charset` - The charset to declare
+///
+/// # Returns
+///
+/// The content type with a single `charset` parameter
+///
+/// # Examples
+///
+/// ```rust
+/// assert_eq!(
+///     with_charset("text/plain; charset=utf-8", Charset::Latin1),
+///     "text/plain; charset=iso-8859-1"
+/// );
+/// ```
+pub fn with_charset(content_type: &str, charset: Charset) -> String {
+    let mut labeled = content_type
+        .split(';')
+        .map(str::trim)
+        .filter(|parameter| {
+            !parameter
+                .split_once('=')
+                .is_some_and(|(name, _)| name.trim().eq_ignore_ascii_case("charset"))
+        })
+        .collect::<Vec<_>>()
+        .join("; ");
+
+    let _ = write!(labeled, "; charset={}", charset.label());
+    labeled
+}
+
+/// Encodes a text body in the given charset.
+///
+/// Characters that latin-1 cannot represent are replaced with `?`.
+///
+/// # Parameters
+///
+/// * `text` - The body to encode
+/// * `charset` - The charset of the response
+///
+/// # Returns
+///
+/// The encoded body bytes
+pub fn encode_text(text: &str, charset: Charset) -> Vec<u8> {
+    match charset {
+        Charset::Utf8 => text.as_bytes().to_vec(),
+        Charset::Latin1 => text
+            .chars()
+            .map(|character| u8::try_from(u32::from(character)).unwrap_or(b'?'))
+            .collect(),
+    }
+}
+
+/// Serializes a JSON body in the given charset.
+///
+/// For latin-1 every non-ASCII character is written as a `\uXXXX` escape, so
+/// the document stays lossless and decodes the same in any ASCII-compatible
+/// charset.
+///
+/// # Parameters
+///
+/// * `data` - The JSON body to serialize
+/// * `charset` - The charset of the response
+///
+/// # Returns
+///
+/// The serialized body bytes
+pub fn encode_json(data: &Value, charset: Charset) -> Vec<u8> {
+    let serialized = data.to_string();
+
+    match charset {
+        Charset::Utf8 => serialized.into_bytes(),
+        Charset::Latin1 => {
+            let mut escaped = String::with_capacity(serialized.len());
+            let mut units = [0u16; 2];
+
+            // Non-ASCII characters only occur inside JSON strings, where escapes are valid
+            for character in serialized.chars() {
+                if character.is_ascii() {
+                    escaped.push(character);
+                } else {
+                    for unit in character.encode_utf16(&mut units) {
+                        let _ = write!(escaped, "\\u{unit:04x}");
+                    }
+                }
+            }
+
+            escaped.into_bytes()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    /// Tests charset name parsing and content type labeling.
+    #[test]
+    fn test_charset_labels() {
+        assert_eq!(Charset::parse("UTF-8"), Some(Charset::Utf8));
+        assert_eq!(Charset::parse("latin-1"), Some(Charset::Latin1));
+        assert_eq!(Charset::parse("shift_jis"), None);
+
+        assert_eq!(
+            with_charset("application/json", Charset::Utf8),
+            "application/json; charset=utf-8"
+        );
+        assert_eq!(
+            with_charset("text/html; Charset=utf-8; level=1", Charset::Latin1),
+            "text/html; level=1; charset=iso-8859-1"
+        );
+        assert_eq!(
+            Charset::from_content_type("text/plain; charset=\"ISO-8859-1\""),
+            Some(Charset::Latin1)
+        );
+        assert_eq!(Charset::from_content_type("text/plain"), None);
+    }
+
+    /// Tests latin-1 encoding of text and JSON bodies.
+    ///
+    /// Verifies that text is encoded byte for byte with `?` replacements and
+    /// that JSON escapes non-ASCII characters, including surrogate pairs.
+    #[test]
+    fn test_latin1_encoding() {
+        assert_eq!(
+            encode_text("café €", Charset::Latin1),
+            b"caf\xe9 ?".to_vec()
+        );
+        assert_eq!(
+            encode_text("café", Charset::Utf8),
+            "café".as_bytes().to_vec()
+        );
+
+        let encoded = encode_json(&json!({"name": "Zoë 😀"}), Charset::Latin1);
+        assert_eq!(encoded, br#"{"name":"Zo\u00eb \ud83d\ude00"}"#.to_vec());
+        assert_eq!(
+            serde_json::from_slice::<Value>(&encoded).expect("Escaped JSON should parse"),
+            json!({"name": "Zoë 😀"})
+        );
+    }
+}
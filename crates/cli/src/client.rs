@@ -0,0 +1,60 @@
+//! The HTTP client JSON Echo uses to reach real services.
+//!
+//! This module builds the client shared by every feature that talks to a
+//! live upstream, such as proxied routes, so they all speak HTTP the same
+//! way and give up on unresponsive services alike.
+//!
+//! ## What
+//!
+//! The module defines:
+//! - `DEFAULT_TIMEOUT`: How long a request may take when nothing else is configured
+//! - `http_client`: Builds a client with a timeout and without following redirects
+//!
+//! ## How
+//!
+//! A client is built by:
+//! 1. Using reqwest with rustls, so both `http://` and `https://` services are reached
+//! 2. Bounding the whole exchange, connecting included, by the timeout
+//! 3. Disabling redirects, so a `3xx` is answered or recorded as the service sent it
+//!
+//! ## Why
+//!
+//! A mock stands in for a service, so what the upstream answers is what
+//! matters, redirects included. The client pools its connections, so it is
+//! built once and cloned, clones sharing the pool.
+//!
+//! # Examples
+//!
+//! ```rust
+//! let client = http_client(DEFAULT_TIMEOUT)?;
+//! let response = client.get("https://api.example.com/users").send().await?;
+//! ```
+
+use reqwest::{Client, redirect::Policy};
+use std::time::Duration;
+
+/// How long a request to a real service may take when no timeout is configured.
+pub const DEFAULT_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Builds the client used to reach real services.
+///
+/// # Parameters
+///
+/// * `timeout` - How long a request may take, from connecting to reading the last byte
+///
+/// # Returns
+///
+/// * `Ok(Client)` - A client following no redirects
+/// * `Err(reqwest::Error)` - If the TLS backend cannot be initialized
+///
+/// # Examples
+///
+/// ```rust
+/// let client = http_client(Duration::from_secs(5))?;
+/// ```
+pub fn http_client(timeout: Duration) -> reqwest::Result<Client> {
+    Client::builder()
+        .timeout(timeout)
+        .redirect(Policy::none())
+        .build()
+}
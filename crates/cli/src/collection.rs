@@ -0,0 +1,465 @@
+//! Filtering, sorting, pagination and slicing of the collections served by JSON Echo.
+//!
+//! This module lets a GET on a collection narrow, order and page through the
+//! items it serves with query parameters, so list screens can be exercised
+//! against a fixture without a route per page or per filter.
+//!
+//! ## What
+//!
+//! The module defines:
+//! - `CollectionQuery`: The filters, sort, page and slice a request asks for
+//! - `CollectionQuery::from_params`: Takes the collection parameters out of a query
+//! - `CollectionQuery::apply`: Filters, sorts and pages or slices an array, reporting a slice as a `Slice`
+//! - `Slice`: The number of matching items and whether a slice left some out
+//! - `TOTAL_COUNT_HEADER`: The `X-Total-Count` header sent with slices
+//!
+//! ## How
+//!
+//! A collection is shaped by:
+//! 1. Keeping the items whose fields equal every `field=value` filter, `a.b` reaching nested fields,
+//!    filters naming a field no item has being ignored
+//! 2. Sorting them by `_sort`, ascending unless `_order=desc`, items missing the field last
+//! 3. Cutting the `_limit` items of the one-based `_page`, 10 per page when only `_page` is given,
+//!    or the half-open slice `_start..._end`, `_limit` items from `_start` when `_end` is left out
+//!
+//! ## Why
+//!
+//! Parameters declared in the route's `query_params` are never used as
+//! filters: they describe the API's own parameters, which are validated but
+//! do not narrow the fixture. Likewise a parameter naming no field of the
+//! items, such as a cache-busting `?t=123`, is not a filter at all, so
+//! clients sending their own parameters keep getting the whole collection.
+//! Filters compare the text of a field, the way it
+//! was sent in the query, so `?id=1` matches both `1` and `"1"`. The
+//! collection of a route with a `results_field` is the array under that
+//! field, so the query always shapes the items rather than their envelope.
+//! Slices past the last item are empty rather than an error, as a cursor
+//! walking off the end of a list expects.
+//!
+//! # Examples
+//!
+//! ```bash
+//! curl 'localhost:3001/api/users?role=admin&_sort=name&_order=desc&_page=2&_limit=5'
+//! curl -i 'localhost:3001/api/users?_start=10&_end=20'
+//! # HTTP/1.1 206 Partial Content, X-Total-Count: 42
+//! ```
+
+use axum::http::HeaderName;
+use serde_json::Value;
+use std::{cmp::Ordering, collections::HashMap};
+
+/// The number of items of a page when `_page` is given without `_limit`.
+const DEFAULT_PAGE_LIMIT: usize = 10;
+
+/// The header carrying the number of items a sliced collection holds in all.
+pub const TOTAL_COUNT_HEADER: HeaderName = HeaderName::from_static("x-total-count");
+
+/// The filters, sort, page and slice a request asks of a collection.
+///
+/// # Fields
+///
+/// * `filters` - The `field=value` pairs every served item must match
+/// * `sort` - The field items are sorted by, if any
+/// * `descending` - Whether items are sorted in descending order
+/// * `page` - The one-based page served, if any
+/// * `limit` - The number of items per page, if any
+/// * `start` - The zero-based index the served slice starts at, if any
+/// * `end` - The index the served slice ends before, if any
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CollectionQuery {
+    /// The `field=value` pairs every served item must match
+    filters: Vec<(String, String)>,
+    /// The field items are sorted by, if any
+    sort: Option<String>,
+    /// Whether items are sorted in descending order
+    descending: bool,
+    /// The one-based page served, if any
+    page: Option<usize>,
+    /// The number of items per page, if any
+    limit: Option<usize>,
+    /// The zero-based index the served slice starts at, if any
+    start: Option<usize>,
+    /// The index the served slice ends before, if any
+    end: Option<usize>,
+}
+
+/// How much of the matching items a sliced collection serves.
+///
+/// # Fields
+///
+/// * `total` - The number of items matching the filters, sent as `X-Total-Count`
+/// * `partial` - Whether the slice served some, but not all, of them
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Slice {
+    /// The number of items matching the filters
+    pub total: usize,
+    /// Whether the slice served some, but not all, of them
+    pub partial: bool,
+}
+
+impl CollectionQuery {
+    /// Takes the collection parameters out of a request query.
+    ///
+    /// `_sort`, `_order`, `_page`, `_limit`, `_start` and `_end` are removed
+    /// from the query, so they never fail its validation. Other parameters stay in the query and
+    /// become filters, unless the route declares them or they start with `_`;
+    /// `apply` ignores the filters naming a field no item has.
+    ///
+    /// # Parameters
+    ///
+    /// * `query` - The query parameters of the request
+    /// * `declared` - Whether the route declares a parameter in its `query_params`
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(CollectionQuery)` - The requested filters, sort and page
+    /// * `Err(String)` - If a collection parameter has an invalid value, or
+    ///   `_page` is combined with `_start` or `_end`
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// let mut query = HashMap::from([("_page".to_string(), "2".to_string())]);
+    /// let collection = CollectionQuery::from_params(&mut query, |_| false)?;
+    /// assert!(query.is_empty());
+    /// ```
+    pub fn from_params(
+        query: &mut HashMap<String, String>,
+        declared: impl Fn(&str) -> bool,
+    ) -> Result<Self, String> {
+        let positive = |name: &str, value: Option<String>| {
+            value
+                .map(|value| {
+                    value
+                        .parse::<usize>()
+                        .ok()
+                        .filter(|number| *number > 0)
+                        .ok_or_else(|| format!("{name} must be a positive integer"))
+                })
+                .transpose()
+        };
+        let index = |name: &str, value: Option<String>| {
+            value
+                .map(|value| {
+                    value
+                        .parse::<usize>()
+                        .map_err(|_| format!("{name} must be a non-negative integer"))
+                })
+                .transpose()
+        };
+
+        let sort = query.remove("_sort");
+        let descending = match query.remove("_order").as_deref() {
+            None | Some("asc") => false,
+            Some("desc") => true,
+            Some(_) => return Err("_order must be asc or desc".to_string()),
+        };
+        let page = positive("_page", query.remove("_page"))?;
+        let limit = positive("_limit", query.remove("_limit"))?;
+        let start = index("_start", query.remove("_start"))?;
+        let end = index("_end", query.remove("_end"))?;
+        if page.is_some() && (start.is_some() || end.is_some()) {
+            return Err("_page cannot be combined with _start or _end".to_string());
+        }
+
+        let mut filters: Vec<(String, String)> = query
+            .iter()
+            .filter(|(name, _)| !name.starts_with('_') && !declared(name))
+            .map(|(name, value)| (name.clone(), value.clone()))
+            .collect();
+        filters.sort();
+
+        Ok(Self {
+            filters,
+            sort,
+            descending,
+            page,
+            limit,
+            start,
+            end,
+        })
+    }
+
+    /// Returns whether the query leaves a collection as it is.
+    pub fn is_empty(&self) -> bool {
+        self.filters.is_empty()
+            && self.sort.is_none()
+            && self.page.is_none()
+            && self.limit.is_none()
+            && !self.is_sliced()
+    }
+
+    /// Returns whether the query asks for a `_start` / `_end` slice.
+    pub fn is_sliced(&self) -> bool {
+        self.start.is_some() || self.end.is_some()
+    }
+
+    /// Filters, sorts and pages or slices a collection.
+    ///
+    /// # Parameters
+    ///
+    /// * `data` - The collection, its `results_field` already extracted
+    ///
+    /// # Returns
+    ///
+    /// The matching items of the requested page or slice, along with a
+    /// `Slice` when `_start` or `_end` is given, or `None` if the data is not
+    /// an array and is served as it is
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// let mut params = HashMap::from([("_start".to_string(), "1".to_string())]);
+    /// let query = CollectionQuery::from_params(&mut params, |_| false)?;
+    /// let (items, slice) = query.apply(&json!([1, 2, 3])).expect("An array");
+    /// assert_eq!(items, json!([2, 3]));
+    /// assert_eq!(slice, Some(Slice { total: 3, partial: true }));
+    /// ```
+    pub fn apply(&self, data: &Value) -> Option<(Value, Option<Slice>)> {
+        let items = data.as_array()?;
+
+        // Parameters naming no field of the items belong to the client, not the fixture
+        let filters: Vec<&(String, String)> = self
+            .filters
+            .iter()
+            .filter(|(field, _)| items.iter().any(|item| field_value(item, field).is_some()))
+            .collect();
+        let mut items: Vec<&Value> = items
+            .iter()
+            .filter(|item| {
+                filters.iter().all(|(field, value)| {
+                    field_text(item, field).is_some_and(|text| text == *value)
+                })
+            })
+            .collect();
+
+        if let Some(sort) = &self.sort {
+            items.sort_by(|a, b| {
+                match (field_value(a, sort), field_value(b, sort)) {
+                    (Some(a), Some(b)) if self.descending => compare_values(b, a),
+                    (Some(a), Some(b)) => compare_values(a, b),
+                    // Items missing the field come last in either order
+                    (Some(_), None) => Ordering::Less,
+                    (None, Some(_)) => Ordering::Greater,
+                    (None, None) => Ordering::Equal,
+                }
+            });
+        }
+
+        let total = items.len();
+        let (skip, take) = if self.is_sliced() {
+            let start = self.start.unwrap_or(0);
+            let end = self
+                .end
+                .or(self.limit.map(|limit| start.saturating_add(limit)))
+                .unwrap_or(total);
+            (start, end.saturating_sub(start))
+        } else {
+            let limit = self
+                .limit
+                .or(self.page.map(|_| DEFAULT_PAGE_LIMIT))
+                .unwrap_or(total);
+            (
+                self.page.map_or(0, |page| (page - 1).saturating_mul(limit)),
+                limit,
+            )
+        };
+
+        let served: Vec<Value> = items.into_iter().skip(skip).take(take).cloned().collect();
+        let slice = self.is_sliced().then_some(Slice {
+            total,
+            partial: !served.is_empty() && served.len() < total,
+        });
+
+        Some((Value::Array(served), slice))
+    }
+}
+
+/// Returns the value of a field of an item, `a.b` reaching into nested objects.
+fn field_value<'a>(item: &'a Value, field: &str) -> Option<&'a Value> {
+    field
+        .split('.')
+        .try_fold(item, |value, segment| value.get(segment))
+}
+
+/// Returns the text a filter compares a field with: strings as they are, other values as JSON.
+fn field_text(item: &Value, field: &str) -> Option<String> {
+    field_value(item, field).map(|value| match value {
+        Value::String(text) => text.clone(),
+        other => other.to_string(),
+    })
+}
+
+/// Orders two field values: numbers numerically, strings lexically, mixed types by type.
+fn compare_values(a: &Value, b: &Value) -> Ordering {
+    match (a, b) {
+        (Value::Number(a), Value::Number(b)) => match (a.as_i128(), b.as_i128()) {
+            // Integers past the 53 bits of a float, such as 64-bit ids, stay exact
+            (Some(a), Some(b)) => a.cmp(&b),
+            _ => a
+                .as_f64()
+                .partial_cmp(&b.as_f64())
+                .unwrap_or(Ordering::Equal),
+        },
+        (Value::String(a), Value::String(b)) => a.cmp(b),
+        (Value::Bool(a), Value::Bool(b)) => a.cmp(b),
+        (a, b) => type_rank(a).cmp(&type_rank(b)),
+    }
+}
+
+/// Returns the position of a value's type when values of different types are sorted.
+fn type_rank(value: &Value) -> u8 {
+    match value {
+        Value::Null => 0,
+        Value::Bool(_) => 1,
+        Value::Number(_) => 2,
+        Value::String(_) => 3,
+        Value::Array(_) => 4,
+        Value::Object(_) => 5,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    /// Builds the collection query of a query string's parameters.
+    fn query(params: &[(&str, &str)]) -> Result<CollectionQuery, String> {
+        let mut params = params
+            .iter()
+            .map(|(name, value)| ((*name).to_string(), (*value).to_string()))
+            .collect();
+        CollectionQuery::from_params(&mut params, |name| name == "page")
+    }
+
+    /// Tests filtering, sorting and paging a collection together.
+    #[test]
+    fn test_apply_filters_sorts_and_pages() {
+        let data = json!([
+            {"id": 1, "role": "admin", "name": "Ada"},
+            {"id": 2, "role": "user", "name": "Bob"},
+            {"id": 3, "role": "admin", "name": "Cy"},
+            {"id": 4, "role": "admin"}
+        ]);
+
+        let shaped = query(&[("role", "admin"), ("_sort", "name"), ("_order", "desc")])
+            .expect("Query should parse")
+            .apply(&data)
+            .map(|(items, _)| items);
+        assert_eq!(
+            shaped,
+            Some(json!([
+                {"id": 3, "role": "admin", "name": "Cy"},
+                {"id": 1, "role": "admin", "name": "Ada"},
+                {"id": 4, "role": "admin"}
+            ]))
+        );
+
+        let paged = query(&[("_page", "2"), ("_limit", "3"), ("page", "9")])
+            .expect("Query should parse")
+            .apply(&data)
+            .map(|(items, _)| items);
+        assert_eq!(paged, Some(json!([{"id": 4, "role": "admin"}])));
+        assert_eq!(
+            query(&[("id", "2")])
+                .expect("Query should parse")
+                .apply(&json!({"id": 2})),
+            None
+        );
+    }
+
+    /// Tests that parameters naming no field of the items leave the collection unchanged.
+    ///
+    /// Verifies that a cache-busting parameter is ignored on its own and next
+    /// to a filter, while a filter on an existing field still narrows.
+    #[test]
+    fn test_apply_ignores_unknown_fields() {
+        let data = json!([
+            {"id": 1, "role": "admin"},
+            {"id": 2, "role": "user"},
+            {"id": 3}
+        ]);
+        let shaped = |params: &[(&str, &str)]| {
+            query(params)
+                .expect("Query should parse")
+                .apply(&data)
+                .map(|(items, _)| items)
+        };
+
+        assert_eq!(shaped(&[("t", "123")]), Some(data.clone()));
+        assert_eq!(
+            shaped(&[("x", "1"), ("profile.name", "Ada")]),
+            Some(data.clone())
+        );
+        assert_eq!(
+            shaped(&[("t", "123"), ("role", "user")]),
+            Some(json!([{"id": 2, "role": "user"}]))
+        );
+    }
+
+    /// Tests slicing a collection with `_start` and `_end`.
+    ///
+    /// Verifies a slice inside the collection, one covering all of it, one
+    /// starting past its end, and `_limit` standing in for `_end`.
+    #[test]
+    fn test_apply_sliced() {
+        let data = json!([{"id": 1}, {"id": 2}, {"id": 3}, {"id": 4}]);
+        let sliced = |params: &[(&str, &str)]| {
+            query(params)
+                .expect("Query should parse")
+                .apply(&data)
+                .expect("The data is an array")
+        };
+
+        assert_eq!(
+            sliced(&[("_start", "1"), ("_end", "3")]),
+            (
+                json!([{"id": 2}, {"id": 3}]),
+                Some(Slice {
+                    total: 4,
+                    partial: true
+                })
+            )
+        );
+        assert_eq!(
+            sliced(&[("_start", "0"), ("_end", "10")]),
+            (
+                data.clone(),
+                Some(Slice {
+                    total: 4,
+                    partial: false
+                })
+            )
+        );
+        assert_eq!(
+            sliced(&[("_start", "9")]),
+            (
+                json!([]),
+                Some(Slice {
+                    total: 4,
+                    partial: false
+                })
+            )
+        );
+        assert_eq!(
+            sliced(&[("_start", "3"), ("_limit", "2")]).0,
+            json!([{"id": 4}])
+        );
+    }
+
+    /// Tests that invalid collection parameters are rejected.
+    #[test]
+    fn test_from_params_rejects_invalid_values() {
+        assert!(query(&[("_start", "-1")]).is_err());
+        assert!(query(&[("_page", "2"), ("_end", "5")]).is_err());
+        assert!(query(&[("_page", "0")]).is_err());
+        assert!(query(&[("_limit", "ten")]).is_err());
+        assert!(query(&[("_order", "up")]).is_err());
+        assert!(
+            query(&[("page", "2")])
+                .expect("Query should parse")
+                .is_empty()
+        );
+    }
+}
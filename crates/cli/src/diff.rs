@@ -0,0 +1,406 @@
+//! Comparison of two JSON Echo configurations.
+//!
+//! This module loads two configuration files and reports what changed between
+//! them, so configuration changes can be reviewed route by route instead of
+//! as a raw text diff of reformatted JSON.
+//!
+//! ## What
+//!
+//! The module defines:
+//! - `ConfigDiff`: The server setting and route differences of two configurations
+//! - `SettingChange`: A server setting whose value changed
+//! - `RouteChange`: A route present in both configurations whose definition changed
+//! - `diff_configs`: Compares two loaded configurations
+//! - `load_config_file`: Loads a configuration file from any directory
+//! - `file_manager`: A configuration manager rooted next to a configuration file
+//! - `settings`: The top-level settings of a configuration, without its routes
+//!
+//! ## How
+//!
+//! A comparison works by:
+//! 1. Loading both files through `ConfigManager`, which normalizes route keys to
+//!    `[METHOD] path` and inlines external response files
+//! 2. Comparing every top-level setting except `routes` by its JSON value
+//! 3. Reporting routes only present in one configuration as added or removed
+//! 4. Comparing the status, body, headers and remaining fields of shared routes
+//!
+//! ## Why
+//!
+//! Comparing normalized keys means `"/users"` and `"[GET] /users"` are the same
+//! route, and comparing loaded values means a body moved into an external file
+//! is not reported as a change.
+//!
+//! # Examples
+//!
+//! ```bash
+//! json-echo diff old.json new.json
+//! json-echo diff old.json new.json --json
+//! ```
+
+use json_echo_core::{Config, ConfigManager, ConfigRoute, FileSystemManager, FileSystemResult};
+use serde::Serialize;
+use serde_json::{Map, Value};
+use std::{
+    collections::BTreeSet,
+    fmt,
+    path::{Path, PathBuf},
+};
+
+/// The differences between two configurations.
+///
+/// # Fields
+///
+/// * `settings` - Server settings whose values differ
+/// * `added` - Routes only present in the new configuration
+/// * `removed` - Routes only present in the old configuration
+/// * `changed` - Routes present in both whose definitions differ
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct ConfigDiff {
+    /// Server settings whose values differ
+    pub settings: Vec<SettingChange>,
+    /// Routes only present in the new configuration
+    pub added: Vec<String>,
+    /// Routes only present in the old configuration
+    pub removed: Vec<String>,
+    /// Routes present in both whose definitions differ
+    pub changed: Vec<RouteChange>,
+}
+
+impl ConfigDiff {
+    /// Returns whether the two configurations are equivalent.
+    pub fn is_empty(&self) -> bool {
+        self.settings.is_empty()
+            && self.added.is_empty()
+            && self.removed.is_empty()
+            && self.changed.is_empty()
+    }
+}
+
+/// A server setting whose value changed.
+///
+/// # Fields
+///
+/// * `name` - The setting name, e.g. `port`
+/// * `old` - The previous value, `null` when unset
+/// * `new` - The new value, `null` when unset
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SettingChange {
+    /// The setting name, e.g. `port`
+    pub name: String,
+    /// The previous value, `null` when unset
+    pub old: Value,
+    /// The new value, `null` when unset
+    pub new: Value,
+}
+
+/// A route whose definition changed.
+///
+/// # Fields
+///
+/// * `route` - The normalized `[METHOD] path` identifier
+/// * `status` - The old and new status codes, when they differ
+/// * `body` - Whether the response body differs
+/// * `headers` - Whether the response headers differ
+/// * `other` - Other route fields that differ, e.g. `delay`
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct RouteChange {
+    /// The normalized `[METHOD] path` identifier
+    pub route: String,
+    /// The old and new status codes, when they differ
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub status: Option<(Value, Value)>,
+    /// Whether the response body differs
+    pub body: bool,
+    /// Whether the response headers differ
+    pub headers: bool,
+    /// Other route fields that differ, e.g. `delay`
+    pub other: Vec<String>,
+}
+
+impl fmt::Display for ConfigDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_empty() {
+            return writeln!(f, "No differences");
+        }
+
+        if !self.settings.is_empty() {
+            writeln!(f, "Settings:")?;
+            for setting in &self.settings {
+                writeln!(
+                    f,
+                    "  ~ {}: {} -> {}",
+                    setting.name, setting.old, setting.new
+                )?;
+            }
+        }
+
+        if !(self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()) {
+            writeln!(f, "Routes:")?;
+        }
+        for route in &self.added {
+            writeln!(f, "  + {route}")?;
+        }
+        for route in &self.removed {
+            writeln!(f, "  - {route}")?;
+        }
+        for change in &self.changed {
+            let mut notes = Vec::new();
+            if let Some((old, new)) = &change.status {
+                notes.push(format!("status {old} -> {new}"));
+            }
+            if change.body {
+                notes.push("body".to_string());
+            }
+            if change.headers {
+                notes.push("headers".to_string());
+            }
+            notes.extend(change.other.iter().cloned());
+
+            writeln!(f, "  ~ {}: {}", change.route, notes.join(", "))?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Compares two loaded configurations.
+///
+/// # Parameters
+///
+/// * `old` - The previous configuration
+/// * `new` - The configuration to compare against it
+///
+/// # Returns
+///
+/// The differences, with settings and routes sorted by name
+///
+/// # Examples
+///
+/// ```rust
+/// let diff = diff_configs(&old_manager.config, &new_manager.config);
+/// println!("{diff}");
+/// ```
+pub fn diff_configs(old: &Config, new: &Config) -> ConfigDiff {
+    let old_settings = settings(old);
+    let new_settings = settings(new);
+
+    let settings = union(old_settings.keys(), new_settings.keys())
+        .into_iter()
+        .filter_map(|name| {
+            let old = old_settings.get(name).cloned().unwrap_or(Value::Null);
+            let new = new_settings.get(name).cloned().unwrap_or(Value::Null);
+            (old != new).then(|| SettingChange {
+                name: name.to_string(),
+                old,
+                new,
+            })
+        })
+        .collect();
+
+    let mut diff = ConfigDiff {
+        settings,
+        ..ConfigDiff::default()
+    };
+
+    for route in union(old.routes.keys(), new.routes.keys()) {
+        match (old.routes.get(route), new.routes.get(route)) {
+            (Some(_), None) => diff.removed.push(route.to_string()),
+            (None, Some(_)) => diff.added.push(route.to_string()),
+            (Some(old_route), Some(new_route)) => {
+                if let Some(change) = diff_route(route, old_route, new_route) {
+                    diff.changed.push(change);
+                }
+            }
+            (None, None) => {}
+        }
+    }
+
+    diff
+}
+
+/// Loads a configuration file, resolving its external files next to it.
+///
+/// # Parameters
+///
+/// * `path` - The configuration file, absolute or relative to the current directory
+///
+/// # Returns
+///
+/// * `Ok(Config)` - The loaded and normalized configuration
+/// * `Err(FileSystemError)` - If the file cannot be loaded or is invalid
+pub async fn load_config_file(path: &Path) -> FileSystemResult<Config> {
+    let (mut config_manager, file_name) = file_manager(path)?;
+    config_manager.load_config(&file_name).await?;
+
+    Ok(config_manager.config)
+}
+
+/// Returns a configuration manager rooted in the directory of a configuration file.
+///
+/// Loading through the manager normalizes route keys to `[METHOD] path` and
+/// resolves external files next to the configuration, so every command
+/// reading or writing configuration files sees them alike.
+///
+/// # Parameters
+///
+/// * `path` - The configuration file, absolute or relative to the current directory
+///
+/// # Returns
+///
+/// * `Ok((ConfigManager, String))` - The manager and the file name relative to its root
+/// * `Err(FileSystemError)` - If the directory cannot be used as a root
+pub fn file_manager(path: &Path) -> FileSystemResult<(ConfigManager, String)> {
+    let directory = match path.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent.to_path_buf(),
+        _ => PathBuf::from("."),
+    };
+    let file_name = path
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default();
+
+    Ok((
+        ConfigManager::new(FileSystemManager::new(Some(directory))?),
+        file_name,
+    ))
+}
+
+/// Compares two definitions of the same route.
+fn diff_route(route: &str, old: &ConfigRoute, new: &ConfigRoute) -> Option<RouteChange> {
+    let mut old = as_object(old);
+    let mut new = as_object(new);
+
+    let mut old_response = take_object(&mut old, "response");
+    let mut new_response = take_object(&mut new, "response");
+
+    let old_status = old_response.remove("status").unwrap_or(Value::Null);
+    let new_status = new_response.remove("status").unwrap_or(Value::Null);
+    let body = old_response.remove("body") != new_response.remove("body");
+    let headers = old.remove("headers") != new.remove("headers");
+
+    let other = union(old.keys(), new.keys())
+        .into_iter()
+        .filter(|field| old.get(*field) != new.get(*field))
+        .map(str::to_string)
+        .collect::<Vec<_>>();
+
+    let status = (old_status != new_status).then_some((old_status, new_status));
+
+    (status.is_some() || body || headers || !other.is_empty()).then(|| RouteChange {
+        route: route.to_string(),
+        status,
+        body,
+        headers,
+        other,
+    })
+}
+
+/// Returns the top-level settings of a configuration, without its routes.
+pub fn settings(config: &Config) -> Map<String, Value> {
+    let mut settings = as_object(config);
+    settings.remove("routes");
+    settings
+}
+
+/// Serializes a configuration value to a JSON object.
+fn as_object(value: &impl Serialize) -> Map<String, Value> {
+    match serde_json::to_value(value) {
+        Ok(Value::Object(map)) => map,
+        _ => Map::new(),
+    }
+}
+
+/// Removes a nested object from a JSON object, or returns an empty one.
+fn take_object(map: &mut Map<String, Value>, key: &str) -> Map<String, Value> {
+    match map.remove(key) {
+        Some(Value::Object(object)) => object,
+        _ => Map::new(),
+    }
+}
+
+/// Returns the sorted union of two sets of keys.
+fn union<'a>(
+    left: impl Iterator<Item = &'a String>,
+    right: impl Iterator<Item = &'a String>,
+) -> BTreeSet<&'a str> {
+    left.chain(right).map(String::as_str).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    /// Loads an inline configuration the way the diff command would.
+    async fn load(content: &Value) -> Config {
+        let file_system_manager =
+            FileSystemManager::new(None).expect("Failed to create filesystem manager");
+        let mut config_manager = ConfigManager::new(file_system_manager);
+        config_manager
+            .load_config_from_bytes(content.to_string().as_bytes())
+            .await
+            .expect("Failed to load test config");
+        config_manager.config
+    }
+
+    /// Tests diffing two small configurations.
+    ///
+    /// Verifies settings changes, added and removed routes, normalized route
+    /// keys and the status, body, header and other field differences of a
+    /// changed route, both in the structured and in the readable report.
+    #[tokio::test]
+    async fn test_diff_configs() {
+        let old = load(&json!({
+            "port": 3000,
+            "routes": {
+                "/users": {"response": {"status": 200, "body": [{"id": 1}]}},
+                "[GET] /health": {"response": {"status": 200, "body": "ok"}},
+                "/legacy": {"response": {"status": 200, "body": []}}
+            }
+        }))
+        .await;
+        let new = load(&json!({
+            "port": 4000,
+            "routes": {
+                "[GET] /users": {
+                    "headers": {"x-version": "2"},
+                    "delay": 50,
+                    "response": {"status": 201, "body": [{"id": 2}]}
+                },
+                "/health": {"response": {"status": 200, "body": "ok"}},
+                "[POST] /users": {"response": {"status": 201, "body": {}}}
+            }
+        }))
+        .await;
+
+        let diff = diff_configs(&old, &new);
+
+        assert_eq!(
+            diff.settings,
+            [SettingChange {
+                name: "port".to_string(),
+                old: json!(3000),
+                new: json!(4000),
+            }]
+        );
+        assert_eq!(diff.added, ["[POST] /users"]);
+        assert_eq!(diff.removed, ["[GET] /legacy"]);
+        assert_eq!(
+            diff.changed,
+            [RouteChange {
+                route: "[GET] /users".to_string(),
+                status: Some((json!(200), json!(201))),
+                body: true,
+                headers: true,
+                other: vec!["delay".to_string()],
+            }]
+        );
+
+        assert_eq!(
+            diff.to_string(),
+            "Settings:\n  ~ port: 3000 -> 4000\nRoutes:\n  + [POST] /users\n  - [GET] /legacy\n  \
+             ~ [GET] /users: status 200 -> 201, body, headers, delay\n"
+        );
+        assert_eq!(diff_configs(&old, &old).to_string(), "No differences\n");
+    }
+}
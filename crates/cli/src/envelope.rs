@@ -0,0 +1,170 @@
+//! Response envelopes for JSON Echo.
+//!
+//! This module wraps the JSON bodies of route responses in the configured
+//! `envelope` template, so API conventions such as a `{"data": ..., "meta": ...}`
+//! wrapper are declared once instead of being repeated in every route.
+//!
+//! ## What
+//!
+//! The module defines:
+//! - `media_type`: The media type of a content type, without its parameters
+//! - `is_json`: Whether a content type carries a JSON body
+//! - `wrap_envelope`: Middleware wrapping JSON response bodies in the envelope
+//!
+//! ## How
+//!
+//! Wrapping works by:
+//! 1. Letting the route handler build its response
+//! 2. Skipping responses that are not JSON, and errors unless `include_errors` is set
+//! 3. Parsing the body and replacing every `"{{body}}"` string of the template with it
+//! 4. Serializing the envelope in the response charset, keeping status and headers
+//!
+//! ## Why
+//!
+//! Running after the handlers keeps every route, sequence and alias wrapped the
+//! same way, and running before the `ETag` and range middleware means those
+//! describe the body the client actually receives.
+//!
+//! # Examples
+//!
+//! ```json
+//! {
+//!   "envelope": {"template": {"data": "{{body}}", "meta": {"version": 1}}},
+//!   "routes": {"/users": {"response": {"body": [{"id": 1}]}}}
+//! }
+//! ```
+
+use crate::charset::{Charset, encode_json};
+use crate::server::AppState;
+use axum::{
+    body::{Body, to_bytes},
+    extract::{Request, State},
+    http::header::{CONTENT_LENGTH, CONTENT_TYPE},
+    middleware::Next,
+    response::Response,
+};
+use serde_json::Value;
+use std::sync::Arc;
+
+/// Returns the media type of a content type, lowercased and without its parameters.
+///
+/// # Parameters
+///
+/// * `content_type` - The `Content-Type` value, possibly with parameters
+///
+/// # Returns
+///
+/// The `type/subtype` part, e.g. `application/json` for `Application/JSON; charset=utf-8`
+pub fn media_type(content_type: &str) -> String {
+    content_type
+        .split(';')
+        .next()
+        .unwrap_or_default()
+        .trim()
+        .to_ascii_lowercase()
+}
+
+/// Returns whether a content type carries a JSON body.
+///
+/// # Parameters
+///
+/// * `content_type` - The `Content-Type` value, possibly with parameters
+///
+/// # Returns
+///
+/// `true` for `application/json` and structured `+json` types
+///
+/// # Examples
+///
+/// ```rust
+/// assert!(is_json("application/json; charset=utf-8"));
+/// assert!(is_json("application/problem+json"));
+/// assert!(!is_json("text/plain"));
+/// ```
+pub fn is_json(content_type: &str) -> bool {
+    let essence = media_type(content_type);
+
+    essence == "application/json" || essence.ends_with("+json")
+}
+
+/// Middleware wrapping JSON response bodies in the configured envelope.
+///
+/// Responses without a parsable JSON body, such as `204 No Content`, are sent
+/// unchanged.
+///
+/// # Parameters
+///
+/// * `State(state)` - Shared application state holding the envelope
+/// * `request` - The incoming request
+/// * `next` - The remaining middleware and the route handler
+///
+/// # Returns
+///
+/// The response, its body wrapped in the envelope when it applies
+pub async fn wrap_envelope(
+    State(state): State<Arc<AppState>>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let response = next.run(request).await;
+
+    let Some(envelope) = state.envelope() else {
+        return response;
+    };
+
+    let content_type = response
+        .headers()
+        .get(CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .map(str::to_string);
+    let Some(content_type) = content_type.filter(|content_type| is_json(content_type)) else {
+        return response;
+    };
+    if !envelope.applies_to(response.status().as_u16()) {
+        return response;
+    }
+
+    let (mut parts, body) = response.into_parts();
+    let bytes = match to_bytes(body, usize::MAX).await {
+        Ok(bytes) => bytes,
+        Err(error) => return state.internal_error(&format!("Failed to read body: {error}")),
+    };
+
+    let Ok(data) = serde_json::from_slice::<Value>(&bytes) else {
+        return Response::from_parts(parts, Body::from(bytes));
+    };
+
+    let charset = Charset::from_content_type(&content_type).unwrap_or(Charset::Utf8);
+    parts.headers.remove(CONTENT_LENGTH);
+
+    Response::from_parts(
+        parts,
+        Body::from(encode_json(&envelope.wrap(&data), charset)),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Tests reading the media type of content types.
+    #[test]
+    fn test_media_type() {
+        assert_eq!(media_type("application/json"), "application/json");
+        assert_eq!(
+            media_type(" Application/GRPC+proto ; charset=utf-8"),
+            "application/grpc+proto"
+        );
+        assert_eq!(media_type(""), "");
+    }
+
+    /// Tests the detection of JSON content types.
+    #[test]
+    fn test_is_json() {
+        assert!(is_json("application/json"));
+        assert!(is_json("Application/JSON; charset=iso-8859-1"));
+        assert!(is_json("application/vnd.api+json"));
+        assert!(!is_json("text/html"));
+        assert!(!is_json("application/x-www-form-urlencoded"));
+    }
+}
@@ -0,0 +1,468 @@
+//! Entity tags and conditional GET handling for JSON Echo responses.
+//!
+//! This module tags successful GET responses with an `ETag` and answers
+//! `If-None-Match` requests whose validators still match with `304 Not
+//! Modified`, so clients polling a mock endpoint can revalidate cached data
+//! the same way they would against a real API. Writes to an entry carrying
+//! `If-Match` are refused with `412 Precondition Failed` once the entry has
+//! changed, for testing optimistic locking.
+//!
+//! ## What
+//!
+//! The module defines:
+//! - `EntityTag`: A parsed entity tag, either strong or weak
+//! - `if_none_match`: The RFC 7232 `If-None-Match` evaluation
+//! - `if_match`: The RFC 7232 `If-Match` evaluation
+//! - `entry_tag`: The entity tag of a single collection entry
+//! - `write_precondition`: Whether a write's `If-Match` validators match the entry it targets
+//! - `precondition_failed`: The `412 Precondition Failed` response refusing a stale write
+//! - `conditional_get`: Middleware tagging responses and answering 304
+//!
+//! ## How
+//!
+//! Conditional handling works by:
+//! 1. Looking up the entry addressed by the path parameters, if any, in the GET route's data
+//! 2. Letting the route handler build the full response
+//! 3. Keeping a configured `ETag` header, or else using the tag of the entry, or
+//!    deriving a strong one from the body
+//! 4. Comparing the tag with the `If-None-Match` validators of the request
+//! 5. Replacing the response with an empty `304 Not Modified` when one matches
+//!
+//! A PUT or PATCH carrying `If-Match` is checked by the write handler, inside
+//! the database update applying it, and refused when no validator matches.
+//!
+//! ## Why
+//!
+//! RFC 7232 requires `If-None-Match` to use the weak comparison function: two
+//! tags match when their opaque values are equal, whatever their weakness. The
+//! `*` validator matches any current representation. Handling this after the
+//! handler keeps every route eligible without changing how bodies are built.
+//!
+//! `If-Match` uses the strong comparison instead, so weak tags never match.
+//! Entries are tagged from their stored JSON rather than the response body,
+//! which makes the tag a GET hands out comparable with the entry a write is
+//! about to change, whatever envelope or charset the response is sent with.
+//! Checking them in the update that writes the entry leaves no gap for a
+//! concurrent write to change it, so of two writes sent with the same tag
+//! only the first is applied.
+//!
+//! # Examples
+//!
+//! ```text
+//! ETag: "5c7ea4d02f8b1a96"
+//! If-None-Match: W/"5c7ea4d02f8b1a96"   -> 304 Not Modified
+//! If-None-Match: *                      -> 304 Not Modified
+//! If-None-Match: "0000000000000000"     -> 200 OK
+//! ```
+
+use crate::server::AppState;
+use crate::stream::is_streamed;
+use axum::{
+    Json, RequestExt,
+    body::{Body, to_bytes},
+    extract::{MatchedPath, Path, Request, State},
+    http::{
+        HeaderValue, Method, StatusCode,
+        header::{CONTENT_LENGTH, CONTENT_TYPE, ETAG, IF_NONE_MATCH},
+    },
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use json_echo_core::Database;
+use serde_json::{Value, json};
+use std::{collections::HashMap, sync::Arc};
+use tracing::debug;
+
+/// A parsed entity tag.
+///
+/// # Fields
+///
+/// * `weak` - Whether the tag carries the `W/` weakness indicator
+/// * `opaque` - The quoted value of the tag, without the quotes
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EntityTag {
+    /// Whether the tag carries the `W/` weakness indicator
+    pub weak: bool,
+    /// The quoted value of the tag, without the quotes
+    pub opaque: String,
+}
+
+impl EntityTag {
+    /// Parses a single entity tag such as `"abc"` or `W/"abc"`.
+    ///
+    /// # Parameters
+    ///
+    /// * `value` - The textual entity tag, surrounding whitespace allowed
+    ///
+    /// # Returns
+    ///
+    /// * `Some(EntityTag)` - If the value is a well-formed entity tag
+    /// * `None` - If the value is not quoted
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// let tag = EntityTag::parse(r#"W/"v1""#).unwrap();
+    /// assert!(tag.weak);
+    /// assert_eq!(tag.opaque, "v1");
+    /// ```
+    pub fn parse(value: &str) -> Option<Self> {
+        let value = value.trim();
+        let (weak, quoted) = match value.strip_prefix("W/") {
+            Some(quoted) => (true, quoted),
+            None => (false, value),
+        };
+
+        let opaque = quoted.strip_prefix('"')?.strip_suffix('"')?;
+        if opaque.contains('"') {
+            return None;
+        }
+
+        Some(Self {
+            weak,
+            opaque: opaque.to_string(),
+        })
+    }
+
+    /// Returns whether two tags match under the RFC 7232 weak comparison.
+    ///
+    /// Weak comparison ignores the weakness indicator: `W/"a"` matches both
+    /// `"a"` and `W/"a"`.
+    pub fn weak_eq(&self, other: &EntityTag) -> bool {
+        self.opaque == other.opaque
+    }
+
+    /// Returns whether two tags match under the RFC 7232 strong comparison.
+    ///
+    /// Strong comparison requires both tags to be strong: `"a"` matches `"a"`
+    /// but neither `W/"a"` nor `"b"`.
+    pub fn strong_eq(&self, other: &EntityTag) -> bool {
+        !self.weak && !other.weak && self.opaque == other.opaque
+    }
+}
+
+/// Returns the strong entity tag of a collection entry.
+///
+/// # Parameters
+///
+/// * `entry` - The entry, as stored in the route's data
+///
+/// # Returns
+///
+/// A tag derived from the entry's JSON, changing whenever the entry does
+pub fn entry_tag(entry: &Value) -> EntityTag {
+    EntityTag {
+        weak: false,
+        opaque: format!("{:016x}", fnv1a(entry.to_string().as_bytes())),
+    }
+}
+
+/// Evaluates an `If-None-Match` header against the current entity tag.
+///
+/// # Parameters
+///
+/// * `header` - The raw `If-None-Match` value, `*` or a comma separated list
+/// * `current` - The entity tag of the current representation
+///
+/// # Returns
+///
+/// `true` when a validator matches, meaning the client's copy is current and
+/// a GET should be answered with `304 Not Modified`
+///
+/// # Examples
+///
+/// ```rust
+/// let current = EntityTag::parse(r#""v1""#).unwrap();
+///
+/// assert!(if_none_match(r#"W/"v1""#, &current));
+/// assert!(if_none_match("*", &current));
+/// assert!(!if_none_match(r#""v0", "v2""#, &current));
+/// ```
+pub fn if_none_match(header: &str, current: &EntityTag) -> bool {
+    if header.trim() == "*" {
+        return true;
+    }
+
+    header
+        .split(',')
+        .filter_map(EntityTag::parse)
+        .any(|validator| validator.weak_eq(current))
+}
+
+/// Evaluates an `If-Match` header against the entity tag of the current entry.
+///
+/// # Parameters
+///
+/// * `header` - The raw `If-Match` value, `*` or a comma separated list
+/// * `current` - The entity tag of the current entry, or `None` if it does not exist
+///
+/// # Returns
+///
+/// `true` when a validator matches the entry, meaning the client's copy is
+/// current and the write may proceed
+///
+/// # Examples
+///
+/// ```rust
+/// let current = EntityTag::parse(r#""v1""#).unwrap();
+///
+/// assert!(if_match(r#""v0", "v1""#, Some(&current)));
+/// assert!(if_match("*", Some(&current)));
+/// assert!(!if_match(r#"W/"v1""#, Some(&current)));
+/// assert!(!if_match("*", None));
+/// ```
+pub fn if_match(header: &str, current: Option<&EntityTag>) -> bool {
+    let Some(current) = current else {
+        return false;
+    };
+
+    if header.trim() == "*" {
+        return true;
+    }
+
+    header
+        .split(',')
+        .filter_map(EntityTag::parse)
+        .any(|validator| validator.strong_eq(current))
+}
+
+/// Returns whether a write's `If-Match` validators match the entry it targets.
+///
+/// The check is meant to run inside the database update applying the write,
+/// so no other write can change the entry in between. The entry is read from
+/// the GET route of the matched path, whose data writes are synced into.
+///
+/// # Parameters
+///
+/// * `db` - The database the write is about to be applied to
+/// * `path` - The matched path of the write, e.g. `/api/users/{id}`
+/// * `params` - The path parameters addressing the entry
+/// * `header` - The raw `If-Match` value of the request
+///
+/// # Returns
+///
+/// `true` when a validator matches the entry's tag, `false` when none does
+/// or the entry does not exist
+pub fn write_precondition(
+    db: &Database,
+    path: &str,
+    params: &HashMap<String, String>,
+    header: &str,
+) -> bool {
+    let identifier = format!("[GET] {path}");
+    let entry = db
+        .get_model(db.resolve_alias(&identifier))
+        .and_then(|model| model.find_entry_by_hashmap(params.clone()));
+    let current = entry.as_ref().map(entry_tag);
+
+    let holds = if_match(header, current.as_ref());
+    if !holds {
+        debug!(
+            "If-Match {:?} does not match {:?}, answering 412",
+            header, current
+        );
+    }
+    holds
+}
+
+/// Returns the response refusing a write whose `If-Match` validators are stale.
+pub fn precondition_failed() -> Response {
+    (
+        StatusCode::PRECONDITION_FAILED,
+        Json(json!({"error": "Precondition failed: the entry has changed"})),
+    )
+        .into_response()
+}
+
+/// Middleware tagging GET responses and answering 304 for them.
+///
+/// Only successful GET and HEAD responses are tagged. A route that configures
+/// its own `ETag` header keeps it, which allows weak tags to be served; an
+/// entry addressed by path parameters gets the tag of its stored JSON, and
+/// all other responses get a strong tag derived from their body.
+///
+/// # Parameters
+///
+/// * `State(state)` - Shared application state, holding the route data and used
+///   to report internal failures
+/// * `request` - The incoming request
+/// * `next` - The remaining middleware and the route handler
+///
+/// # Returns
+///
+/// The tagged response, or an empty `304 Not Modified` carrying the tag when
+/// one of the request's `If-None-Match` validators matches
+pub async fn conditional_get(
+    State(state): State<Arc<AppState>>,
+    mut request: Request,
+    next: Next,
+) -> Response {
+    let is_get = matches!(*request.method(), Method::GET | Method::HEAD);
+    let validators = request
+        .headers()
+        .get(IF_NONE_MATCH)
+        .and_then(|value| value.to_str().ok())
+        .map(str::to_string);
+
+    let entry = if is_get {
+        current_entry(&state, &mut request).await
+    } else {
+        None
+    };
+
+    let response = next.run(request).await;
+
+    if !is_get || !response.status().is_success() || is_streamed(&response) {
+        return response;
+    }
+
+    let (mut parts, body) = response.into_parts();
+    let bytes = match to_bytes(body, usize::MAX).await {
+        Ok(bytes) => bytes,
+        Err(error) => return state.internal_error(&format!("Failed to read body: {error}")),
+    };
+
+    let configured = parts
+        .headers
+        .get(ETAG)
+        .and_then(|value| value.to_str().ok())
+        .and_then(EntityTag::parse);
+    let current = configured
+        .or_else(|| entry.flatten().as_ref().map(entry_tag))
+        .unwrap_or_else(|| EntityTag {
+            weak: false,
+            opaque: format!("{:016x}", fnv1a(&bytes)),
+        });
+
+    if let Ok(value) = HeaderValue::from_str(&render(&current)) {
+        parts.headers.insert(ETAG, value);
+    }
+
+    if validators.is_some_and(|header| if_none_match(&header, &current)) {
+        debug!("If-None-Match matched {:?}, answering 304", current);
+        parts.status = StatusCode::NOT_MODIFIED;
+        parts.headers.remove(CONTENT_TYPE);
+        parts.headers.remove(CONTENT_LENGTH);
+        return Response::from_parts(parts, Body::empty());
+    }
+
+    Response::from_parts(parts, Body::from(bytes))
+}
+
+/// Looks up the entry addressed by the path parameters of a request.
+///
+/// The entry is read from the GET route of the matched path, whose data
+/// writes to the path are synced into.
+///
+/// # Parameters
+///
+/// * `state` - Shared application state holding the route data
+/// * `request` - The request, routed so its path parameters are known
+///
+/// # Returns
+///
+/// * `Some(Some(Value))` - The current entry
+/// * `Some(None)` - If the path has parameters but no entry matches them
+/// * `None` - If the path has no parameters, so it addresses no single entry
+async fn current_entry(state: &AppState, request: &mut Request) -> Option<Option<Value>> {
+    let path = request
+        .extensions()
+        .get::<MatchedPath>()?
+        .as_str()
+        .to_string();
+    let Path(params) = request
+        .extract_parts::<Path<HashMap<String, String>>>()
+        .await
+        .ok()?;
+    if params.is_empty() {
+        return None;
+    }
+
+    let db = state.read();
+    let identifier = format!("[GET] {path}");
+    let model = db.get_model(db.resolve_alias(&identifier));
+    Some(model.and_then(|model| model.find_entry_by_hashmap(params)))
+}
+
+/// Renders an entity tag in its header form.
+fn render(tag: &EntityTag) -> String {
+    if tag.weak {
+        format!("W/\"{}\"", tag.opaque)
+    } else {
+        format!("\"{}\"", tag.opaque)
+    }
+}
+
+/// Hashes the body with 64-bit FNV-1a, which is stable across runs and builds.
+fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+        (hash ^ u64::from(*byte)).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Tests parsing of strong, weak and malformed entity tags.
+    #[test]
+    fn test_entity_tag_parse() {
+        assert_eq!(
+            EntityTag::parse(r#" "abc" "#),
+            Some(EntityTag {
+                weak: false,
+                opaque: "abc".to_string()
+            })
+        );
+        assert_eq!(
+            EntityTag::parse(r#"W/"abc""#),
+            Some(EntityTag {
+                weak: true,
+                opaque: "abc".to_string()
+            })
+        );
+        assert_eq!(EntityTag::parse("abc"), None);
+        assert_eq!(EntityTag::parse(r#"w/"abc""#), None, "W/ is case-sensitive");
+    }
+
+    /// Tests the weak comparison used by If-None-Match.
+    ///
+    /// Verifies weak-weak and strong-weak matches, lists of validators, the
+    /// `*` wildcard and mismatching values.
+    #[test]
+    fn test_if_none_match() {
+        let strong = EntityTag::parse(r#""v1""#).expect("Valid tag");
+        let weak = EntityTag::parse(r#"W/"v1""#).expect("Valid tag");
+
+        assert!(if_none_match(r#"W/"v1""#, &weak), "weak-weak should match");
+        assert!(
+            if_none_match(r#"W/"v1""#, &strong),
+            "weak-strong should match"
+        );
+        assert!(if_none_match(r#""v1""#, &weak), "strong-weak should match");
+        assert!(if_none_match(r#""v0", W/"v1""#, &strong));
+        assert!(if_none_match(" * ", &strong));
+        assert!(!if_none_match(r#"W/"v2", "v3""#, &strong));
+    }
+
+    /// Tests the strong comparison used by If-Match.
+    ///
+    /// Verifies that only strong validators match, that `*` requires an
+    /// existing entry, and that entry tags change with the entry.
+    #[test]
+    fn test_if_match() {
+        let current = entry_tag(&serde_json::json!({"id": 1, "name": "John"}));
+        let header = render(&current);
+        let header = header.as_str();
+
+        assert!(if_match(header, Some(&current)));
+        assert!(if_match(&format!(r#""stale", {header}"#), Some(&current)));
+        assert!(!if_match(&format!("W/{header}"), Some(&current)));
+        assert!(if_match("*", Some(&current)));
+        assert!(!if_match("*", None));
+        assert!(!if_match(header, None));
+
+        let changed = entry_tag(&serde_json::json!({"id": 1, "name": "Jane"}));
+        assert!(!if_match(header, Some(&changed)));
+    }
+}
@@ -0,0 +1,447 @@
+//! Replay of browser HAR exports for JSON Echo.
+//!
+//! This module turns the request/response pairs recorded in a HAR file into
+//! routes, so a session exported from the browser's network panel can be
+//! served back without writing a configuration by hand.
+//!
+//! ## What
+//!
+//! The module defines:
+//! - `Har`: The parts of a HAR file the replay reads
+//! - `har_config`: Turns the recorded entries into a configuration
+//! - `load_har_config`: Reads a HAR file and turns it into a configuration
+//!
+//! ## How
+//!
+//! A replay works by:
+//! 1. Grouping the entries by method and URL path, query strings left out, in recorded order
+//! 2. Answering a request recorded once with its status, body and headers
+//! 3. Answering a request recorded several times with a `sequence` of its responses, in order
+//! 4. Keeping JSON bodies as JSON, other text bodies as strings, and decoding base64 text content
+//!
+//! ## Why
+//!
+//! Recorded headers describing the transfer, such as `Content-Length`,
+//! `Content-Encoding` or `Transfer-Encoding`, no longer match the decoded
+//! body the HAR holds, so they are dropped and the server computes its own.
+//! Binary bodies cannot be served from a configuration and are replaced by an
+//! empty one with a warning, while base64 content that does not decode fails
+//! the replay, naming its entry, rather than serving a corrupted body.
+//!
+//! # Examples
+//!
+//! ```bash
+//! json-echo replay session.har --port 3001
+//! ```
+
+use axum::http::Uri;
+use base64::{Engine, engine::general_purpose::STANDARD};
+use indexmap::IndexMap;
+use json_echo_core::{
+    BodyResponse, Config, ConfigResponse, ConfigRoute, ConfigRouteResponse, FileSystemError,
+    FileSystemResult, SequenceMode,
+};
+use serde::Deserialize;
+use serde_json::Value;
+use std::{collections::HashMap, path::Path};
+use tracing::warn;
+
+/// Response headers describing the recorded transfer rather than the body,
+/// dropped because the HAR holds the decoded content.
+const DROPPED_HEADERS: [&str; 5] = [
+    "connection",
+    "content-encoding",
+    "content-length",
+    "keep-alive",
+    "transfer-encoding",
+];
+
+/// The parts of a HAR file the replay reads.
+///
+/// # Fields
+///
+/// * `log` - The HAR log holding the recorded entries
+#[derive(Debug, Clone, Deserialize)]
+pub struct Har {
+    /// The HAR log holding the recorded entries
+    pub log: HarLog,
+}
+
+/// The log of a HAR file.
+#[derive(Debug, Clone, Deserialize)]
+pub struct HarLog {
+    /// The recorded request/response pairs, in the order they were sent
+    #[serde(default)]
+    pub entries: Vec<HarEntry>,
+}
+
+/// A recorded request/response pair.
+#[derive(Debug, Clone, Deserialize)]
+pub struct HarEntry {
+    /// The recorded request
+    pub request: HarRequest,
+    /// The recorded response
+    pub response: HarResponse,
+}
+
+/// A recorded request.
+#[derive(Debug, Clone, Deserialize)]
+pub struct HarRequest {
+    /// The request method, e.g. `GET`
+    pub method: String,
+    /// The absolute URL of the request
+    pub url: String,
+}
+
+/// A recorded response.
+#[derive(Debug, Clone, Deserialize)]
+pub struct HarResponse {
+    /// The response status code
+    pub status: u16,
+    /// The response headers, in recorded order
+    #[serde(default)]
+    pub headers: Vec<HarHeader>,
+    /// The decoded response body
+    #[serde(default)]
+    pub content: HarContent,
+}
+
+/// A recorded header.
+#[derive(Debug, Clone, Deserialize)]
+pub struct HarHeader {
+    /// The header name
+    pub name: String,
+    /// The header value
+    pub value: String,
+}
+
+/// The decoded body of a recorded response.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HarContent {
+    /// The MIME type of the body, e.g. `application/json`
+    #[serde(default)]
+    pub mime_type: Option<String>,
+    /// The body text, absent when the browser did not keep it
+    #[serde(default)]
+    pub text: Option<String>,
+    /// `base64` when the text is base64 encoded
+    #[serde(default)]
+    pub encoding: Option<String>,
+}
+
+/// Returns the `[METHOD] path` key a recorded request is served under.
+fn route_key(request: &HarRequest) -> Option<String> {
+    let uri: Uri = request.url.parse().ok()?;
+    Some(format!(
+        "[{}] {}",
+        request.method.to_ascii_uppercase(),
+        uri.path()
+    ))
+}
+
+/// Turns a recorded response into a route response.
+///
+/// # Parameters
+///
+/// * `index` - The position of the entry in the HAR log, used in errors
+/// * `key` - The route the response is served by, used in warnings
+/// * `response` - The recorded response
+///
+/// # Returns
+///
+/// * `Ok(ConfigRouteResponse)` - The recorded status with the body as JSON when it
+///   parses under a JSON MIME type, as a string otherwise, or empty when it is binary or absent
+/// * `Err(FileSystemError::Operation)` - If base64 content does not decode
+fn recorded_response(
+    index: usize,
+    key: &str,
+    response: &HarResponse,
+) -> FileSystemResult<ConfigRouteResponse> {
+    let content = &response.content;
+    let text = match (content.text.as_deref(), content.encoding.as_deref()) {
+        (Some(text), Some("base64")) => {
+            let bytes = STANDARD.decode(text).map_err(|error| {
+                FileSystemError::Operation(format!(
+                    "HAR entry {index} ({key}) has invalid base64 content: {error}"
+                ))
+            })?;
+            String::from_utf8(bytes).unwrap_or_else(|_| {
+                warn!("⚠︎ Skipping the binary body of {}", key);
+                String::new()
+            })
+        }
+        (Some(text), _) => text.to_string(),
+        (None, _) => String::new(),
+    };
+
+    let is_json = content
+        .mime_type
+        .as_deref()
+        .is_some_and(|mime_type| mime_type.to_ascii_lowercase().contains("json"));
+    let body = if is_json {
+        serde_json::from_str(&text).unwrap_or(Value::String(text))
+    } else {
+        Value::String(text)
+    };
+
+    Ok(ConfigRouteResponse {
+        status: Some(response.status),
+        body: BodyResponse::Value(body),
+    })
+}
+
+/// Returns the recorded headers worth replaying.
+fn recorded_headers(response: &HarResponse) -> Option<HashMap<String, String>> {
+    let headers: HashMap<String, String> = response
+        .headers
+        .iter()
+        // HTTP/2 exports list pseudo-headers such as `:status`
+        .filter(|header| !header.name.starts_with(':'))
+        .filter(|header| !DROPPED_HEADERS.contains(&header.name.to_ascii_lowercase().as_str()))
+        .map(|header| (header.name.clone(), header.value.clone()))
+        .collect();
+
+    (!headers.is_empty()).then_some(headers)
+}
+
+/// Turns the entries recorded in a HAR file into a configuration.
+///
+/// # Parameters
+///
+/// * `har` - The parsed HAR file
+///
+/// # Returns
+///
+/// * `Ok(Config)` - A default configuration with one route per recorded method and
+///   path, answering with a `sequence` of the responses when it was recorded more
+///   than once. The headers of the first recorded response are used for the route.
+/// * `Err(FileSystemError::Operation)` - Naming the first entry whose base64 content does not decode
+///
+/// # Examples
+///
+/// ```rust
+/// let har: Har = serde_json::from_str(&std::fs::read_to_string("session.har")?)?;
+/// let config = har_config(&har)?;
+/// ```
+pub fn har_config(har: &Har) -> FileSystemResult<Config> {
+    let mut recorded: IndexMap<String, Vec<(usize, &HarResponse)>> = IndexMap::new();
+
+    for (index, entry) in har.log.entries.iter().enumerate() {
+        match route_key(&entry.request) {
+            Some(key) => recorded
+                .entry(key)
+                .or_default()
+                .push((index, &entry.response)),
+            None => warn!("⚠︎ Skipping entry with invalid URL {}", entry.request.url),
+        }
+    }
+
+    let mut config = Config::default();
+    for (key, responses) in recorded {
+        let Some((_, first)) = responses.first() else {
+            continue;
+        };
+        let mut responses = responses
+            .iter()
+            .map(|(index, response)| recorded_response(*index, &key, response))
+            .collect::<FileSystemResult<Vec<_>>>()?;
+
+        let method = key
+            .split(']')
+            .next()
+            .map(|method| method.trim_start_matches('[').to_string());
+        let mut route = ConfigRoute {
+            method,
+            headers: recorded_headers(first),
+            ..ConfigRoute::default()
+        };
+
+        if responses.len() > 1 {
+            route.sequence = Some(responses);
+            route.sequence_mode = Some(SequenceMode::Once);
+        } else if let Some(response) = responses.pop() {
+            route.response = ConfigResponse::ConfigRouteResponse(response);
+        }
+
+        config.routes.insert(key, route);
+    }
+
+    Ok(config)
+}
+
+/// Reads a HAR file and turns its entries into a configuration.
+///
+/// # Parameters
+///
+/// * `path` - The HAR file exported from the browser
+///
+/// # Returns
+///
+/// * `Ok(Config)` - The configuration built by `har_config`
+/// * `Err(FileSystemError)` - If the file cannot be read, is not a HAR file, records no
+///   entries or holds base64 content that does not decode
+pub async fn load_har_config(path: &Path) -> FileSystemResult<Config> {
+    let content = tokio::fs::read(path)
+        .await
+        .map_err(|error| FileSystemError::from_io(&error, path))?;
+    let har: Har = serde_json::from_slice(&content).map_err(|error| {
+        FileSystemError::Operation(format!(
+            "Failed to parse HAR file {}: {error}",
+            path.display()
+        ))
+    })?;
+
+    if har.log.entries.is_empty() {
+        return Err(FileSystemError::Operation(format!(
+            "HAR file {} records no requests",
+            path.display()
+        )));
+    }
+
+    har_config(&har)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::server::{AppState, create_router};
+    use axum::{body::Body, http::Request};
+    use json_echo_core::{ConfigManager, Database, FileSystemManager};
+    use serde_json::json;
+    use std::sync::Arc;
+    use tower::ServiceExt;
+
+    /// A small HAR export with a repeated request and a base64 encoded body.
+    fn fixture() -> Value {
+        json!({
+            "log": {
+                "version": "1.2",
+                "entries": [
+                    {
+                        "request": {"method": "GET", "url": "https://api.example.com/api/users?page=1"},
+                        "response": {
+                            "status": 200,
+                            "headers": [
+                                {"name": "Content-Type", "value": "application/json"},
+                                {"name": "Content-Length", "value": "28"},
+                                {"name": "X-Total-Count", "value": "1"}
+                            ],
+                            "content": {"mimeType": "application/json", "text": "[{\"id\":1,\"name\":\"Ada\"}]"}
+                        }
+                    },
+                    {
+                        "request": {"method": "POST", "url": "https://api.example.com/api/users"},
+                        "response": {
+                            "status": 201,
+                            "headers": [],
+                            "content": {"mimeType": "application/json", "text": "eyJpZCI6Mn0=", "encoding": "base64"}
+                        }
+                    },
+                    {
+                        "request": {"method": "GET", "url": "https://api.example.com/api/users?page=2"},
+                        "response": {
+                            "status": 404,
+                            "headers": [{"name": "Content-Type", "value": "application/json"}],
+                            "content": {"mimeType": "application/json", "text": "{\"error\":\"gone\"}"}
+                        }
+                    }
+                ]
+            }
+        })
+    }
+
+    /// Tests building routes from the recorded entries.
+    ///
+    /// Verifies that repeated requests become a sequence in recorded order,
+    /// that base64 bodies are decoded and that transfer headers are dropped.
+    #[test]
+    fn test_har_config() {
+        let har: Har = serde_json::from_value(fixture()).expect("The fixture is a HAR file");
+        let config = har_config(&har).expect("The fixture converts");
+
+        let routes = serde_json::to_value(&config.routes).expect("Routes should serialize");
+        assert_eq!(
+            routes["[GET] /api/users"]["sequence"],
+            json!([
+                {"status": 200, "body": [{"id": 1, "name": "Ada"}]},
+                {"status": 404, "body": {"error": "gone"}}
+            ])
+        );
+        assert_eq!(
+            routes["[GET] /api/users"]["headers"],
+            json!({"Content-Type": "application/json", "X-Total-Count": "1"})
+        );
+        assert_eq!(
+            routes["[POST] /api/users"]["response"],
+            json!({"status": 201, "body": {"id": 2}})
+        );
+    }
+
+    /// Tests serving the replayed routes.
+    ///
+    /// Verifies that the first GET answers with the first recorded response
+    /// and its headers, and the second one with the next recorded response.
+    #[tokio::test]
+    async fn test_replayed_responses_match_recording() {
+        let har: Har = serde_json::from_value(fixture()).expect("The fixture is a HAR file");
+        let content = serde_json::to_vec(&har_config(&har).expect("The fixture converts"))
+            .expect("Config should serialize");
+
+        let mut config_manager =
+            ConfigManager::new(FileSystemManager::new(None).expect("Failed to create manager"));
+        config_manager
+            .load_config_from_bytes(&content)
+            .await
+            .expect("The replayed config should load");
+        let mut db = Database::new();
+        db.populate(config_manager.config.routes.clone());
+        let router = create_router(Arc::new(AppState::new(db)), &config_manager);
+
+        let get = || {
+            Request::builder()
+                .uri("/api/users")
+                .body(Body::empty())
+                .expect("Failed to build request")
+        };
+
+        let response = router
+            .clone()
+            .oneshot(get())
+            .await
+            .expect("Router responds");
+        assert_eq!(response.status().as_u16(), 200);
+        assert_eq!(response.headers()["x-total-count"], "1");
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .expect("Failed to read body");
+        assert_eq!(
+            serde_json::from_slice::<Value>(&body).expect("The body is JSON"),
+            json!([{"id": 1, "name": "Ada"}])
+        );
+
+        let response = router.oneshot(get()).await.expect("Router responds");
+        assert_eq!(response.status().as_u16(), 404);
+    }
+
+    /// Tests that base64 content that does not decode fails the replay.
+    ///
+    /// Verifies that the error names the entry's position and route instead
+    /// of the body being served corrupted.
+    #[test]
+    fn test_invalid_base64_names_the_entry() {
+        let mut har = fixture();
+        har["log"]["entries"][1]["response"]["content"]["text"] = json!("eyJpZCI6M!0=");
+        let har: Har = serde_json::from_value(har).expect("The fixture is a HAR file");
+
+        match har_config(&har) {
+            Err(FileSystemError::Operation(message)) => {
+                assert!(
+                    message.contains("HAR entry 1 ([POST] /api/users)"),
+                    "Unexpected error: {message}"
+                );
+            }
+            other => panic!("Expected a decode error, got {other:?}"),
+        }
+    }
+}
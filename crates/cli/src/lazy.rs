@@ -0,0 +1,109 @@
+//! Loading of lazy route fixtures for JSON Echo.
+//!
+//! This module reads the external file of a route configured with
+//! `lazy: true` on its first request, so configurations referencing many large
+//! fixtures start without parsing files that may never be requested.
+//!
+//! ## What
+//!
+//! The module defines:
+//! - `load_lazy_fixture`: Middleware loading the pending files of a route before its handler runs
+//!
+//! ## How
+//!
+//! Lazy loading works by:
+//! 1. Keeping the resolved file of every lazy route as the pending state of its model
+//! 2. Reading and parsing the file through the filesystem manager when a request
+//!    reaches a model that is still pending, or writes to a path whose GET model is
+//! 3. Storing the response in the database through the state's writer, which clears the pending state
+//! 4. Serving every later request from the stored response, without touching the file
+//!
+//! ## Why
+//!
+//! Writes are synced into the GET model of their path, so that model is loaded
+//! before any write reaches it; otherwise a write sent before the first read
+//! would be overwritten by the file. The file is read outside of the database
+//! update, so slow disks never hold up other writers; the update only keeps the first response stored when
+//! concurrent requests load the same file. A file that fails to load answers
+//! the configured internal error and stays pending, so it is retried once fixed.
+//!
+//! # Examples
+//!
+//! ```json
+//! {"/api/catalog": {"lazy": true, "response": "fixtures/catalog.json"}}
+//! ```
+
+use crate::server::AppState;
+use axum::{
+    extract::{MatchedPath, Request, State},
+    http::Method,
+    middleware::Next,
+    response::Response,
+};
+use json_echo_core::load_route_response;
+use std::sync::Arc;
+use tracing::info;
+
+/// Middleware loading the pending files of a lazy route before its handler runs.
+///
+/// The model of the request's method is loaded, along with the GET model of
+/// the path, which writes are synced into.
+///
+/// # Parameters
+///
+/// * `State(state)` - Shared application state holding the route models and
+///   the filesystem manager the files are read through
+/// * `request` - The incoming request
+/// * `next` - The remaining middleware and the route handler
+///
+/// # Returns
+///
+/// The response of the route, served from its loaded file, or the internal
+/// error response if a file cannot be read or parsed
+pub async fn load_lazy_fixture(
+    State(state): State<Arc<AppState>>,
+    request: Request,
+    next: Next,
+) -> Response {
+    // HEAD requests are answered by the GET route
+    let method = if request.method() == Method::HEAD {
+        Method::GET
+    } else {
+        request.method().clone()
+    };
+
+    let mut pending = Vec::new();
+    if let Some(path) = request.extensions().get::<MatchedPath>() {
+        let db = state.read();
+        for method in [method, Method::GET] {
+            let identifier = format!("[{method}] {}", path.as_str());
+            let identifier = db.resolve_alias(&identifier);
+            if let Some(file) = db
+                .get_model(identifier)
+                .and_then(|model| model.get_pending_file())
+                && !pending.iter().any(|(other, _)| other == identifier)
+            {
+                pending.push((identifier.to_string(), file.to_path_buf()));
+            }
+        }
+    }
+    if pending.is_empty() {
+        return next.run(request).await;
+    }
+
+    let Some(files) = state.files() else {
+        return state.internal_error("No file system to load lazy fixtures from");
+    };
+    for (identifier, file) in pending {
+        match load_route_response(files, &file).await {
+            Ok(data) => {
+                if state.update(|db| db.load_pending(&identifier, data.clone())) {
+                    info!("Loaded lazy fixture {} for {}", file.display(), identifier);
+                }
+            }
+            Err(error) => return state.internal_error(&error.to_string()),
+        }
+    }
+
+    next.run(request).await
+}
@@ -9,7 +9,7 @@
 //!
 //! The module defines:
 //! - `Cli`: Main command-line interface structure with global options
-//! - `Commands`: Available subcommands (Init, Serve)
+//! - `Commands`: Available subcommands (Init, Serve, Bench, Selftest, Diff, Merge, Capture, Replay)
 //! - Main function that orchestrates application startup and command execution
 //!
 //! ## How
@@ -44,19 +44,102 @@
 //!
 //! # Serve with custom log level
 //! json-echo --log-level debug serve
+//!
+//! # Serve with two runtime worker threads
+//! json-echo --workers 2 serve
+//!
+//! # Serve with the interactive console on stdin
+//! json-echo serve --repl
+//!
+//! # Serve and open the default browser at the server URL
+//! json-echo serve --open
+//!
+//! # Serve with a configuration piped through stdin
+//! cat db.json | json-echo --config-stdin serve
+//!
+//! # Measure the throughput of a route
+//! json-echo --config db.json bench --route /users --requests 10000
+//!
+//! # Check that every GET route serves without failing
+//! json-echo --config db.json selftest
+//!
+//! # Compare two configurations
+//! json-echo diff old.json new.json
+//!
+//! # Combine configurations into a single file
+//! json-echo merge users.json orders.json -o merged.json
+//!
+//! # Serve the requests recorded in a browser HAR export
+//! json-echo replay session.har
 //! ```
 
-use crate::server::{create_router, run_server};
+use crate::bench::run_bench;
+use crate::bundle::bundle_config;
+use crate::capture::capture_config;
+use crate::client::{DEFAULT_TIMEOUT, http_client};
+use crate::diff::{diff_configs, load_config_file};
+use crate::har::load_har_config;
+use crate::merge::{merge_configs, save_config_file};
+use crate::redact::DEFAULT_LOG_BODY_LIMIT;
+#[cfg(unix)]
+use crate::reload::spawn_sighup_reload;
+use crate::repl::spawn_repl;
+use crate::selftest::run_selftest;
+use crate::server::{configured_state, create_router, run_server};
+use crate::summary::{StartupSummary, SummaryFormat};
+use crate::tls::tls_acceptor;
 use clap::{Parser, Subcommand};
 use json_echo_core::{
-    ConfigManager, Database, FileSystemError, FileSystemManager, FileSystemResult,
+    Config, ConfigManager, Database, FileSystemError, FileSystemManager, FileSystemResult,
+    PathUtils, TagFilter,
 };
-use std::{env, path::PathBuf};
-use tracing::{error, info};
+use std::{
+    env,
+    fmt::Display,
+    io::{self, Read},
+    num::NonZeroUsize,
+    path::PathBuf,
+    sync::Arc,
+    time::Duration,
+};
+use tokio::runtime::{self, Runtime};
+use tracing::{error, info, warn};
 use tracing_subscriber::prelude::*;
-use tracing_subscriber::{EnvFilter, fmt};
+use tracing_subscriber::{EnvFilter, fmt, fmt::writer::BoxMakeWriter};
 
+mod bench;
+mod body_log;
+mod bundle;
+mod cache;
+mod capture;
+mod charset;
+mod client;
+mod collection;
+mod diff;
+mod envelope;
+mod etag;
+mod har;
+mod lazy;
+mod maintenance;
+mod merge;
+mod proxy;
+mod range;
+mod recorder;
+mod redact;
+mod reload;
+mod repl;
+mod request_id;
+mod retry;
+mod route_log;
+mod selftest;
 mod server;
+mod sql;
+mod stream;
+mod summary;
+mod template;
+mod timestamp;
+mod tls;
+mod upload;
 
 /// Main command-line interface structure for the JSON Echo application.
 ///
@@ -68,7 +151,11 @@ mod server;
 ///
 /// * `log_level` - Global logging level configuration (default: "info")
 /// * `config` - Path to the configuration file (default: "json-echo.json")
+/// * `config_stdin` - Read the configuration from standard input instead of a file
+/// * `root` - Directory used to resolve external file references
+/// * `workers` - Number of runtime worker threads (default: CPU count)
 /// * `protocol` - Network protocol to use (default: "http")
+/// * `quiet` - Print neither the banner nor logs below error level
 /// * `command` - The subcommand to execute
 ///
 /// # Examples
@@ -90,10 +177,26 @@ struct Cli {
     #[arg(long, global = true, default_value_t = String::from("json-echo.json"))]
     config: String,
 
+    /// Read the configuration from standard input instead of the config file
+    #[arg(long, global = true)]
+    config_stdin: bool,
+
+    /// Root directory used to resolve external file references
+    #[arg(long, global = true)]
+    root: Option<PathBuf>,
+
+    /// Number of runtime worker threads (defaults to the number of CPU cores)
+    #[arg(long, global = true)]
+    workers: Option<NonZeroUsize>,
+
     /// Network protocol to use for the server
     #[arg(long, global = true, default_value_t = String::from("http"))]
     protocol: String,
 
+    /// Print neither the banner nor logs below error level
+    #[arg(long, global = true)]
+    quiet: bool,
+
     /// The command to execute
     #[command(subcommand)]
     command: Commands,
@@ -109,6 +212,11 @@ struct Cli {
 ///
 /// * `Init` - Initialize a new configuration file with default settings
 /// * `Serve` - Start the JSON Echo server with the specified configuration
+/// * `Bench` - Measure the in-process throughput of a configured route
+/// * `Selftest` - Request every GET route in-process and report those that fail
+/// * `Diff` - Compare the routes and server settings of two configuration files
+/// * `Merge` - Combine several configuration files into a single one
+/// * `Bundle` - Write the configuration as a single file with every response inline
 ///
 /// # Examples
 ///
@@ -134,11 +242,201 @@ enum Commands {
     /// according to the defined routes and settings. The server will listen
     /// on the configured hostname and port, serving mock responses based
     /// on the route definitions.
-    Serve,
+    Serve {
+        /// Read administrative commands (list, get, set, reset) from stdin while serving
+        #[arg(long)]
+        repl: bool,
+
+        /// Port to listen on, overriding the config file and the PORT variable; 0 picks a free port
+        #[arg(long)]
+        port: Option<u16>,
+
+        /// Write the port the server listens on to this file once it is bound
+        #[arg(long)]
+        port_file: Option<PathBuf>,
+
+        /// Open the default browser at the server URL once it is listening
+        #[arg(long)]
+        open: bool,
+
+        /// Serve only the routes carrying one of these comma-separated tags
+        #[arg(long, value_delimiter = ',')]
+        tags: Vec<String>,
+
+        /// Keep serving routes without tags when --tags is given
+        #[arg(long, requires = "tags")]
+        include_untagged: bool,
+
+        /// Wait this many milliseconds before accepting connections, overriding the config file
+        #[arg(long)]
+        startup_delay_ms: Option<u64>,
+
+        /// Log request and response bodies at debug level, redacted and truncated
+        #[arg(long)]
+        log_bodies: bool,
+
+        /// Maximum number of bytes logged per body with --log-bodies
+        #[arg(long, default_value_t = DEFAULT_LOG_BODY_LIMIT)]
+        log_body_limit: usize,
+
+        /// Print a summary of the effective configuration once listening, alone on stdout
+        #[arg(long, value_enum)]
+        print_summary: Option<SummaryFormat>,
+
+        /// Also accept HTTP/2 with prior knowledge (h2c), overriding the config file
+        #[arg(long)]
+        http2: bool,
+    },
+
+    /// Measure the in-process throughput of a route
+    ///
+    /// Loads the configuration, builds the router and fires sequential GET
+    /// requests at the route without opening a socket, then reports requests
+    /// per second and latency percentiles.
+    Bench {
+        /// The request path to measure, e.g. /users
+        #[arg(long)]
+        route: String,
+
+        /// The number of requests to send
+        #[arg(long, default_value_t = 1000)]
+        requests: usize,
+    },
+
+    /// Check that every GET route serves without failing
+    ///
+    /// Loads the configuration, builds the router and sends one in-process
+    /// request to every GET route, reporting the routes that panic, answer
+    /// an unconfigured 5xx or a malformed JSON body. Exits with an error if
+    /// any route fails.
+    Selftest,
+
+    /// Compare two configuration files
+    ///
+    /// Loads both files and prints the server settings that changed and the
+    /// routes that were added, removed or changed, compared on their
+    /// normalized `[METHOD] path` keys.
+    Diff {
+        /// The previous configuration file
+        old: PathBuf,
+
+        /// The configuration file to compare against it
+        new: PathBuf,
+
+        /// Print the differences as JSON instead of a readable report
+        #[arg(long)]
+        json: bool,
+    },
+
+    /// Combine configuration files into a single one
+    ///
+    /// Loads the files in order and writes one normalized configuration with
+    /// the routes of all of them, later files winning when a route is
+    /// defined twice. Server settings come from the first file unless a later
+    /// file sets them.
+    Merge {
+        /// The configuration files to merge, in order
+        #[arg(required = true)]
+        inputs: Vec<PathBuf>,
+
+        /// The file the merged configuration is written to
+        #[arg(short, long)]
+        output: PathBuf,
+    },
+
+    /// Write the configuration as a single self-contained file
+    ///
+    /// Loads the configuration as for serving and writes it with every
+    /// external response file inlined, lazy ones included, and the seed file
+    /// applied. SQLite databases and static files cannot be inlined and are
+    /// reported on stderr.
+    Bundle {
+        /// The file the bundled configuration is written to
+        #[arg(short, long)]
+        output: PathBuf,
+    },
+
+    /// Capture the responses of a running API into a configuration
+    ///
+    /// Sends a GET request to every path of the base URL and writes a
+    /// configuration with one route per path, answering with the captured
+    /// status, body and content type. Error statuses are captured as they are.
+    Capture {
+        /// The base URL of the running API, e.g. http://localhost:8080 or https://api.example.com
+        #[arg(long)]
+        url: String,
+
+        /// The comma-separated paths to capture, e.g. /users,/orders
+        #[arg(long, value_delimiter = ',', required = true)]
+        paths: Vec<String>,
+
+        /// The file the captured configuration is written to
+        #[arg(short, long, default_value = "json-echo.json")]
+        output: PathBuf,
+
+        /// Give up on a path after this many milliseconds, 30000 by default
+        #[arg(long)]
+        timeout_ms: Option<u64>,
+    },
+
+    /// Serve the requests recorded in a HAR file
+    ///
+    /// Builds one route per recorded method and path, answering with the
+    /// recorded status, body and headers. Requests recorded several times
+    /// answer with their responses in recorded order. The configuration file
+    /// is not read.
+    Replay {
+        /// The HAR file exported from the browser
+        har: PathBuf,
+
+        /// Port to listen on, overriding the PORT variable
+        #[arg(long)]
+        port: Option<u16>,
+    },
 }
 
 /// Main entry point for the JSON Echo CLI application.
 ///
+/// Parses the command-line arguments, builds the Tokio runtime with the
+/// requested number of worker threads and runs the application on it. The
+/// runtime is built by hand rather than through `#[tokio::main]` so that the
+/// worker count can be configured with `--workers`.
+///
+/// # Returns
+///
+/// * `Ok(())` - If the command executed successfully
+/// * `Err(FileSystemError)` - If the runtime cannot be built or the command fails
+fn main() -> FileSystemResult<()> {
+    let cli = Cli::parse();
+
+    build_runtime(cli.workers)?.block_on(run(cli))
+}
+
+/// Builds the multi-threaded Tokio runtime used by the application.
+///
+/// # Parameters
+///
+/// * `workers` - The number of worker threads, or `None` for one per CPU core
+///
+/// # Returns
+///
+/// * `Ok(Runtime)` - The runtime ready to drive the application
+/// * `Err(FileSystemError)` - If the runtime cannot be created
+fn build_runtime(workers: Option<NonZeroUsize>) -> FileSystemResult<Runtime> {
+    let mut builder = runtime::Builder::new_multi_thread();
+    builder.enable_all();
+
+    if let Some(workers) = workers {
+        builder.worker_threads(workers.get());
+    }
+
+    builder.build().map_err(|error| {
+        FileSystemError::Operation(format!("Failed to build the async runtime: {error}"))
+    })
+}
+
+/// Runs the JSON Echo CLI application.
+///
 /// This asynchronous function handles the complete lifecycle of the application,
 /// from command-line argument parsing to command execution. It sets up the
 /// necessary managers, handles path resolution, and coordinates between the
@@ -173,16 +471,48 @@ enum Commands {
 /// - Reports the created file path
 ///
 /// For the Serve command:
-/// - Loads the configuration file
+/// - Loads the configuration file, or reads it from stdin with `--config-stdin`
 /// - Populates the in-memory database with route definitions
 /// - Extracts server settings (hostname, port)
-/// - Starts the web server with the configured routes
+/// - Prints a summary of the effective configuration with `--print-summary`
+/// - Optionally starts the interactive console when `--repl` is given
+/// - On Unix, reloads the configuration file whenever `SIGHUP` is received
+/// - Loads the certificate and key when `tls` is configured, failing before binding if they are invalid
+/// - Starts the web server with the configured routes, writing the bound port to `--port-file`
+///
+/// For the Bench command:
+/// - Loads the configuration and populates the database as for Serve
+/// - Sends the requested number of in-process requests to the route
+/// - Prints throughput and latency percentiles
+///
+/// For the Selftest command:
+/// - Loads the configuration and populates the database as for Serve
+/// - Sends one in-process request to every GET route
+/// - Prints the failed and skipped routes, failing if any route failed
+///
+/// For the Diff command:
+/// - Loads both configuration files, resolving external files next to each
+/// - Prints the changed settings and routes, as JSON with `--json`
+///
+/// For the Merge command:
+/// - Loads every input file, resolving external files next to each
+/// - Reports routes defined more than once on stderr
+/// - Saves the merged configuration to the output file
+///
+/// For the Bundle command:
+/// - Loads the configuration as for Serve
+/// - Inlines the response files of lazy routes and drops the seed file
+/// - Reports the SQLite databases and static folder left external on stderr
+/// - Saves the bundled configuration to the output file
+///
+/// For the Capture command:
+/// - Sends a GET request to every path of the base URL, each bounded by the timeout
+/// - Saves a configuration with one route per captured response
 ///
 /// # Examples
 ///
-/// This function is called automatically by the Rust runtime and cannot
-/// be invoked directly. The application behavior is controlled through
-/// command-line arguments:
+/// This function is driven by `main` on the configured runtime. The
+/// application behavior is controlled through command-line arguments:
 ///
 /// ```bash
 /// # Initialize with default settings
@@ -192,19 +522,35 @@ enum Commands {
 /// json-echo --config /path/to/config.json serve
 /// ```
 #[allow(clippy::print_stdout)]
-#[tokio::main]
-async fn main() -> FileSystemResult<()> {
+#[allow(clippy::too_many_lines)]
+async fn run(cli: Cli) -> FileSystemResult<()> {
     const VERSION: &str = env!("CARGO_PKG_VERSION");
-    let cli = Cli::parse();
 
-    let stdout_layer = fmt::layer()
+    // Keep machine-readable output alone on stdout, without the banner or logs
+    let machine_output = matches!(
+        cli.command,
+        Commands::Diff { json: true, .. }
+            | Commands::Serve {
+                print_summary: Some(_),
+                ..
+            }
+    );
+
+    let log_level = if cli.quiet { "error" } else { &cli.log_level };
+    let log_layer = fmt::layer()
         .with_ansi(true)
-        .with_filter(EnvFilter::new(cli.log_level));
+        .with_writer(if machine_output || cli.quiet {
+            BoxMakeWriter::new(io::stderr)
+        } else {
+            BoxMakeWriter::new(io::stdout)
+        })
+        .with_filter(EnvFilter::new(log_level));
 
-    tracing_subscriber::registry().with(stdout_layer).init();
+    tracing_subscriber::registry().with(log_layer).init();
 
-    print!(
-        "
+    if !machine_output && !cli.quiet {
+        print!(
+            "
         ░█▀▀░█▀▀░█░█░█▀█░░░▀▀█░█▀▀░█▀█░█▀█░░░█▀▀░█▀▀░█▀▄░█░█░█▀▀░█▀▄
         ░█▀▀░█░░░█▀█░█░█░░░░░█░▀▀█░█░█░█░█░░░▀▀█░█▀▀░█▀▄░▀▄▀░█▀▀░█▀▄
         ░▀▀▀░▀▀▀░▀░▀░▀▀▀░░░▀▀░░▀▀▀░▀▀▀░▀░▀░░░▀▀▀░▀▀▀░▀░▀░░▀░░▀▀▀░▀░▀
@@ -213,7 +559,8 @@ async fn main() -> FileSystemResult<()> {
         ⎯⎯⎯⎯⎯⎯⎯⎯⎯⎯⎯⎯⎯⎯⎯⎯⎯⎯⎯⎯⎯⎯⎯⎯⎯⎯⎯⎯⎯⎯⎯⎯⎯⎯⎯⎯⎯⎯⎯⎯⎯⎯⎯⎯⎯⎯⎯⎯⎯⎯⎯⎯⎯⎯⎯⎯⎯⎯⎯
         \n
     "
-    );
+        );
+    }
 
     info!("Starting applying configuration");
 
@@ -241,6 +588,16 @@ async fn main() -> FileSystemResult<()> {
         current_directory = config_dir.to_path_buf();
     }
 
+    // Reading from stdin has no config file location, so resolve against the
+    // current directory unless a root is explicitly provided
+    if cli.config_stdin {
+        current_directory = PathUtils::current_dir()?;
+    }
+
+    if let Some(root) = cli.root {
+        current_directory = root;
+    }
+
     // Extract the config file name for loading
     let config_file_name = config_file.file_name().ok_or_else(|| {
         error!("Config file not available.");
@@ -249,7 +606,7 @@ async fn main() -> FileSystemResult<()> {
 
     // Set up the filesystem and configuration managers
     let file_system_manager = FileSystemManager::new(Some(current_directory))?;
-    let mut config_manager = ConfigManager::new(file_system_manager);
+    let mut config_manager = ConfigManager::new(file_system_manager.clone());
 
     // Execute the requested command
     match cli.command {
@@ -267,35 +624,1109 @@ async fn main() -> FileSystemResult<()> {
                 config_manager.get_root().join("json-echo.json").display()
             );
         }
-        Commands::Serve => {
-            info!("Loading config file.");
+        Commands::Serve {
+            repl,
+            port,
+            port_file,
+            open,
+            tags,
+            include_untagged,
+            startup_delay_ms,
+            log_bodies,
+            log_body_limit,
+            print_summary,
+            http2,
+        } => {
+            let filter = (!tags.is_empty()).then_some(TagFilter {
+                tags,
+                include_untagged,
+            });
+            let db = load_database(
+                &mut config_manager,
+                cli.config_stdin,
+                &config_file_name.display().to_string(),
+                filter.as_ref(),
+            )
+            .await?;
 
-            // Load the configuration file
-            config_manager
-                .load_config(config_file_name.display().to_string().as_str())
-                .await?;
+            let addresses = listen_addresses(port, &config_manager.config);
+            config_manager.config.http2 |= http2;
+
+            let state = Arc::new(
+                configured_state(db, &config_manager.config)
+                    .with_file_system(file_system_manager)
+                    .with_body_logging(log_bodies.then_some(log_body_limit)),
+            );
+
+            // Optionally expose the interactive console over stdin
+            if repl {
+                spawn_repl(Arc::clone(&state), config_manager.config.routes.clone());
+            }
+
+            // Reload the configuration file on SIGHUP; piped configs cannot be re-read
+            #[cfg(unix)]
+            if !cli.config_stdin {
+                spawn_sighup_reload(
+                    Arc::clone(&state),
+                    config_manager.clone(),
+                    config_file_name.display().to_string(),
+                    filter,
+                )?;
+            }
+
+            // Start the server with the configured routes and settings
+            let startup_delay = Duration::from_millis(
+                startup_delay_ms
+                    .or(config_manager.config.startup_delay_ms)
+                    .unwrap_or(0),
+            );
+            let tls = match config_manager.config.tls.as_ref() {
+                Some(tls) => Some(
+                    tls_acceptor(config_manager.get_root(), tls, config_manager.config.http2)
+                        .await?,
+                ),
+                None => None,
+            };
+            let router = create_router(state, &config_manager);
+            run_server(
+                &addresses,
+                router,
+                startup_delay,
+                config_manager.config.http2,
+                tls,
+                port_file.as_deref(),
+                |urls| {
+                    if let Some(format) = print_summary {
+                        print_startup_summary(urls, &config_manager.config, format);
+                    }
+                    if let (true, Some(url)) = (open, urls.first()) {
+                        open_browser(url, |url| opener::open_browser(url));
+                    }
+                },
+            )
+            .await?;
+        }
+        Commands::Bench { route, requests } => {
+            let db = load_database(
+                &mut config_manager,
+                cli.config_stdin,
+                &config_file_name.display().to_string(),
+                None,
+            )
+            .await?;
+
+            let state =
+                configured_state(db, &config_manager.config).with_file_system(file_system_manager);
+            let router = create_router(Arc::new(state), &config_manager);
+
+            info!("Benchmarking {} with {} requests.", route, requests);
+
+            let report = run_bench(router, &route, requests).await?;
+            println!("{report}");
+        }
+        Commands::Selftest => {
+            let db = load_database(
+                &mut config_manager,
+                cli.config_stdin,
+                &config_file_name.display().to_string(),
+                None,
+            )
+            .await?;
+
+            let state =
+                configured_state(db, &config_manager.config).with_file_system(file_system_manager);
+            let router = create_router(Arc::new(state), &config_manager);
+
+            let report = run_selftest(router, &config_manager.config.routes).await;
+            print!("{report}");
+
+            if !report.failures.is_empty() {
+                return Err(FileSystemError::Operation(format!(
+                    "{} of {} GET routes failed the self-test",
+                    report.failures.len(),
+                    report.checked
+                )));
+            }
+        }
+        Commands::Diff { old, new, json } => {
+            let diff = diff_configs(
+                &load_config_file(&old).await?,
+                &load_config_file(&new).await?,
+            );
+
+            if json {
+                let output = serde_json::to_string_pretty(&diff).map_err(FileSystemError::from)?;
+                println!("{output}");
+            } else {
+                print!("{diff}");
+            }
+        }
+        Commands::Merge { inputs, output } => {
+            let mut configs = Vec::with_capacity(inputs.len());
+            for input in &inputs {
+                configs.push((input.display().to_string(), load_config_file(input).await?));
+            }
+
+            let merged = merge_configs(configs);
+            for collision in &merged.collisions {
+                eprintln!("⚠︎ {collision}");
+            }
 
-            info!("Populating in-memory database.");
+            save_config_file(&output, &merged.config).await?;
+            info!(
+                "Merged {} routes from {} files into {}",
+                merged.config.routes.len(),
+                inputs.len(),
+                output.display()
+            );
+        }
+        Commands::Bundle { output } => {
+            load_database(
+                &mut config_manager,
+                cli.config_stdin,
+                &config_file_name.display().to_string(),
+                None,
+            )
+            .await?;
+
+            let bundle = bundle_config(&file_system_manager, config_manager.config.clone()).await?;
+            for external in &bundle.external {
+                eprintln!("⚠︎ {external}; the file is not part of the bundle");
+            }
+
+            save_config_file(&output, &bundle.config).await?;
+            info!(
+                "Bundled {} routes into {}",
+                bundle.config.routes.len(),
+                output.display()
+            );
+        }
+        Commands::Capture {
+            url,
+            paths,
+            output,
+            timeout_ms,
+        } => {
+            let timeout = timeout_ms.map_or(DEFAULT_TIMEOUT, Duration::from_millis);
+            let client = http_client(timeout).map_err(|error| {
+                FileSystemError::Operation(format!("Failed to build the HTTP client: {error}"))
+            })?;
+            let config = capture_config(&client, &url, &paths).await?;
+
+            save_config_file(&output, &config).await?;
+            info!(
+                "Captured {} routes from {} into {}",
+                config.routes.len(),
+                url,
+                output.display()
+            );
+        }
+        Commands::Replay { har, port } => {
+            let config = load_har_config(&har).await?;
+            let content = serde_json::to_vec(&config).map_err(FileSystemError::from)?;
+            config_manager.load_config_from_bytes(&content).await?;
+            info!(
+                "Replaying {} routes recorded in {}",
+                config_manager.config.routes.len(),
+                har.display()
+            );
 
-            // Populate the in-memory database with route configurations
             let mut db = Database::new();
             db.populate(config_manager.config.routes.clone());
 
-            // Extract server configuration with defaults
-            let hostname_string = config_manager
-                .config
+            let addresses = listen_addresses(port, &config_manager.config);
+            let state =
+                configured_state(db, &config_manager.config).with_file_system(file_system_manager);
+            let router = create_router(Arc::new(state), &config_manager);
+            run_server(
+                &addresses,
+                router,
+                Duration::ZERO,
+                false,
+                None,
+                None,
+                |_| {},
+            )
+            .await?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Resolves the port the server listens on.
+///
+/// The first of these that is set wins:
+/// 1. The `--port` flag of the `serve` command
+/// 2. The `port` of the configuration file
+/// 3. The `PORT` environment variable, as provided by most PaaS platforms
+/// 4. The built-in default, 3001
+///
+/// A `PORT` value that is not a valid port number is reported and skipped.
+///
+/// # Parameters
+///
+/// * `flag` - The `--port` flag, if given
+/// * `config` - The configured port, if any
+/// * `env_var` - Looks up an environment variable by name
+///
+/// # Returns
+///
+/// The port to listen on
+fn resolve_port(
+    flag: Option<u16>,
+    config: Option<u16>,
+    env_var: impl Fn(&str) -> Option<String>,
+) -> u16 {
+    flag.or(config)
+        .or_else(|| {
+            let value = env_var("PORT")?;
+            let port = value.trim().parse().ok();
+            if port.is_none() {
+                warn!("⚠︎ Ignoring invalid PORT value {:?}", value);
+            }
+            port
+        })
+        .unwrap_or(3001)
+}
+
+/// Returns the addresses the server listens on.
+///
+/// `listen` replaces `hostname` and the resolved port when it is set, in
+/// which case `--port` is reported as ignored.
+///
+/// # Parameters
+///
+/// * `port` - The `--port` flag, if given
+/// * `config` - The loaded configuration
+///
+/// # Returns
+///
+/// The configured `listen` addresses, or the single `hostname:port` address
+fn listen_addresses(port: Option<u16>, config: &Config) -> Vec<String> {
+    match config.listen.clone() {
+        Some(listen) if !listen.is_empty() => {
+            if port.is_some() {
+                warn!("⚠︎ --port is ignored, listening on the configured addresses");
+            }
+            listen
+        }
+        _ => {
+            let hostname = config
                 .hostname
                 .clone()
                 .unwrap_or_else(|| "localhost".to_string());
-            let hostname = hostname_string.as_str();
+            let port = resolve_port(port, config.port, |key| env::var(key).ok());
+            vec![format!("{hostname}:{port}")]
+        }
+    }
+}
 
-            let port_string = config_manager.config.port.unwrap_or(3001).to_string();
-            let port = port_string.as_str();
+/// Prints the startup summary of a listening server to stdout.
+///
+/// # Parameters
+///
+/// * `urls` - The URLs the server listens on, with their bound ports
+/// * `config` - The loaded configuration being served
+/// * `format` - The format requested with `--print-summary`
+#[allow(clippy::print_stdout)]
+fn print_startup_summary(urls: &[String], config: &Config, format: SummaryFormat) {
+    match StartupSummary::new(urls, config).render(format) {
+        Ok(summary) => println!("{summary}"),
+        Err(error) => error!("Failed to render the startup summary: {error}"),
+    }
+}
 
-            // Start the server with the configured routes and settings
-            run_server(hostname, port, create_router(db, &config_manager)).await?;
+/// Opens the server URL in the default browser.
+///
+/// Opening fails in headless and CI environments without a browser; the
+/// failure is reported as a warning and the server keeps running.
+///
+/// # Parameters
+///
+/// * `url` - The URL the server is listening on
+/// * `open` - Opens a URL in the browser, `opener::open_browser` outside tests
+///
+/// # Returns
+///
+/// `true` if the browser was opened, `false` if opening was skipped
+fn open_browser<E: Display>(url: &str, open: impl FnOnce(&str) -> Result<(), E>) -> bool {
+    match open(url) {
+        Ok(()) => {
+            info!("Opened {} in the browser", url);
+            true
+        }
+        Err(error) => {
+            warn!("⚠︎ Could not open a browser at {}: {}", url, error);
+            false
         }
     }
+}
 
-    Ok(())
+/// Loads the configuration and populates a fresh in-memory database from it.
+///
+/// # Parameters
+///
+/// * `config_manager` - The configuration manager to load into
+/// * `config_stdin` - Whether to read the configuration from standard input
+/// * `config_file_name` - The configuration file to load otherwise
+/// * `filter` - The tag filter selecting the routes to serve, if any
+///
+/// # Returns
+///
+/// * `Ok(Database)` - The database populated with the configured routes
+/// * `Err(FileSystemError)` - If the configuration cannot be read or processed
+async fn load_database(
+    config_manager: &mut ConfigManager,
+    config_stdin: bool,
+    config_file_name: &str,
+    filter: Option<&TagFilter>,
+) -> FileSystemResult<Database> {
+    info!("Loading config file.");
+
+    if config_stdin {
+        // Load the configuration piped through standard input
+        let mut content = Vec::new();
+        io::stdin().read_to_end(&mut content).map_err(|error| {
+            FileSystemError::Operation(format!("Failed to read config from stdin: {error}"))
+        })?;
+
+        config_manager.load_config_from_bytes(&content).await?;
+    } else {
+        // Load the configuration file
+        config_manager.load_config(config_file_name).await?;
+    }
+
+    if let Some(filter) = filter {
+        let removed = filter.retain(&mut config_manager.config);
+        info!(
+            "Serving {} routes tagged {}, skipping {}",
+            config_manager.config.routes.len(),
+            filter.tags.join(", "),
+            removed
+        );
+    }
+
+    info!("Populating in-memory database.");
+
+    // Populate the in-memory database with route configurations
+    let mut db = Database::new();
+    db.populate(config_manager.config.routes.clone());
+
+    Ok(db)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Tests that the configured worker count is applied to the runtime.
+    ///
+    /// Verifies both an explicit worker count and the CPU-based default.
+    #[test]
+    fn test_build_runtime_worker_count() {
+        let runtime = build_runtime(NonZeroUsize::new(2)).expect("Runtime should build");
+        assert_eq!(runtime.metrics().num_workers(), 2);
+
+        let cpus = std::thread::available_parallelism().map_or(1, NonZeroUsize::get);
+        let runtime = build_runtime(None).expect("Runtime should build");
+        assert_eq!(runtime.metrics().num_workers(), cpus);
+    }
+
+    /// Tests that a zero worker count is rejected when parsing arguments.
+    #[test]
+    fn test_workers_flag_rejects_zero() {
+        assert!(Cli::try_parse_from(["echo", "--workers", "0", "serve"]).is_err());
+
+        let cli = Cli::try_parse_from(["echo", "--workers", "3", "serve"])
+            .expect("Valid worker count should parse");
+        assert_eq!(cli.workers, NonZeroUsize::new(3));
+    }
+
+    /// Tests the precedence of the port sources.
+    ///
+    /// Verifies that the flag beats the config, the config beats `PORT`, and
+    /// that an invalid `PORT` falls back to the default.
+    #[test]
+    fn test_resolve_port_precedence() {
+        let port_env =
+            |value: &'static str| move |key: &str| (key == "PORT").then(|| value.to_string());
+        let no_env = |_: &str| None;
+
+        assert_eq!(resolve_port(Some(4000), Some(5000), port_env("6000")), 4000);
+        assert_eq!(resolve_port(None, Some(5000), port_env("6000")), 5000);
+        assert_eq!(resolve_port(None, None, port_env("6000")), 6000);
+        assert_eq!(resolve_port(None, None, port_env("not-a-port")), 3001);
+        assert_eq!(resolve_port(None, None, no_env), 3001);
+
+        let cli = Cli::try_parse_from(["echo", "serve", "--port", "4000"])
+            .expect("Valid port should parse");
+        assert!(matches!(
+            cli.command,
+            Commands::Serve {
+                port: Some(4000),
+                ..
+            }
+        ));
+    }
+
+    /// Tests that `--tags` limits the registered routes to the tagged ones.
+    ///
+    /// Verifies that only routes with a matching tag are served, and that
+    /// `--include-untagged` keeps routes without tags as well.
+    #[tokio::test]
+    async fn test_serve_tags_filter_routes() {
+        use axum::{body::Body, http::Request, http::StatusCode};
+        use tower::ServiceExt;
+
+        let dir = tempfile::tempdir().expect("Failed to create temporary directory");
+        let config = serde_json::json!({
+            "routes": {
+                "/login": {"tags": ["auth"], "response": {"body": {"token": "abc"}}},
+                "/users": {"tags": ["users", "admin"], "response": {"body": []}},
+                "/orders": {"tags": ["orders"], "response": {"body": []}},
+                "/health": {"response": {"body": "ok"}}
+            }
+        });
+        std::fs::write(dir.path().join("json-echo.json"), config.to_string())
+            .expect("Failed to write test config");
+
+        let serve = |args: &[&str]| {
+            let cli = Cli::try_parse_from([&["echo", "serve"], args].concat())
+                .expect("Serve flags should parse");
+            match cli.command {
+                Commands::Serve {
+                    tags,
+                    include_untagged,
+                    ..
+                } => TagFilter {
+                    tags,
+                    include_untagged,
+                },
+                _ => unreachable!("Parsed a serve command"),
+            }
+        };
+
+        for (args, served) in [
+            (&["--tags", "auth,users"][..], &["/login", "/users"][..]),
+            (
+                &["--tags", "auth", "--include-untagged"][..],
+                &["/login", "/health"][..],
+            ),
+        ] {
+            let filter = serve(args);
+            let mut config_manager = ConfigManager::new(
+                FileSystemManager::new(Some(dir.path().to_path_buf()))
+                    .expect("Failed to create filesystem manager"),
+            );
+            let db = load_database(&mut config_manager, false, "json-echo.json", Some(&filter))
+                .await
+                .expect("Tagged config should load");
+            let router = create_router(
+                Arc::new(configured_state(db, &config_manager.config)),
+                &config_manager,
+            );
+
+            for path in ["/login", "/users", "/orders", "/health"] {
+                let request = Request::builder()
+                    .uri(path)
+                    .body(Body::empty())
+                    .expect("Failed to build request");
+                let status = router
+                    .clone()
+                    .oneshot(request)
+                    .await
+                    .expect("Router should always respond")
+                    .status();
+                let expected = if served.contains(&path) {
+                    StatusCode::OK
+                } else {
+                    StatusCode::NOT_FOUND
+                };
+                assert_eq!(status, expected, "{path} with {args:?}");
+            }
+        }
+
+        assert!(Cli::try_parse_from(["echo", "serve", "--include-untagged"]).is_err());
+    }
+
+    /// Tests that the server binds to `PORT` when the config omits the port.
+    #[tokio::test]
+    async fn test_server_binds_to_port_env() {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        let mut config_manager =
+            ConfigManager::new(FileSystemManager::new(None).expect("Failed to create manager"));
+        config_manager
+            .load_config_from_bytes(br#"{"routes": {"/ping": {"response": {"body": "pong"}}}}"#)
+            .await
+            .expect("Failed to load test config");
+        assert_eq!(config_manager.config.port, None);
+
+        // Reserve a free port, then hand it over through the PORT variable
+        let free_port = std::net::TcpListener::bind("127.0.0.1:0")
+            .and_then(|listener| listener.local_addr())
+            .expect("Failed to reserve a port")
+            .port();
+        let port = resolve_port(None, config_manager.config.port, |key| {
+            (key == "PORT").then(|| free_port.to_string())
+        });
+        assert_eq!(port, free_port);
+
+        let mut db = Database::new();
+        db.populate(config_manager.config.routes.clone());
+        let router = create_router(
+            Arc::new(configured_state(db, &config_manager.config)),
+            &config_manager,
+        );
+        let server = tokio::spawn(async move {
+            run_server(
+                &[format!("127.0.0.1:{port}")],
+                router,
+                Duration::ZERO,
+                false,
+                None,
+                None,
+                |_| {},
+            )
+            .await
+        });
+
+        let mut stream = None;
+        for _ in 0..100 {
+            match tokio::net::TcpStream::connect(("127.0.0.1", free_port)).await {
+                Ok(connected) => {
+                    stream = Some(connected);
+                    break;
+                }
+                Err(_) => tokio::time::sleep(std::time::Duration::from_millis(10)).await,
+            }
+        }
+        let mut stream = stream.expect("Server should listen on the PORT value");
+
+        stream
+            .write_all(b"GET /ping HTTP/1.1\r\nHost: localhost\r\nConnection: close\r\n\r\n")
+            .await
+            .expect("Failed to send request");
+        let mut reply = String::new();
+        stream
+            .read_to_string(&mut reply)
+            .await
+            .expect("Failed to read response");
+        server.abort();
+
+        assert!(
+            reply.starts_with("HTTP/1.1 200"),
+            "Unexpected reply: {reply}"
+        );
+    }
+
+    /// Tests that the listener does not accept connections during the startup delay.
+    #[tokio::test]
+    async fn test_startup_delay_defers_listening() {
+        let cli = Cli::try_parse_from(["echo", "serve", "--startup-delay-ms", "300"])
+            .expect("Flag should parse");
+        let Commands::Serve {
+            startup_delay_ms, ..
+        } = cli.command
+        else {
+            panic!("Expected the serve command");
+        };
+        let delay = Duration::from_millis(startup_delay_ms.unwrap_or_default());
+
+        let mut config_manager =
+            ConfigManager::new(FileSystemManager::new(None).expect("Failed to create manager"));
+        config_manager
+            .load_config_from_bytes(br#"{"routes": {"/ping": {"response": {"body": "pong"}}}}"#)
+            .await
+            .expect("Failed to load test config");
+        let mut db = Database::new();
+        db.populate(config_manager.config.routes.clone());
+        let router = create_router(
+            Arc::new(configured_state(db, &config_manager.config)),
+            &config_manager,
+        );
+
+        let free_port = std::net::TcpListener::bind("127.0.0.1:0")
+            .and_then(|listener| listener.local_addr())
+            .expect("Failed to reserve a port")
+            .port();
+        let started = tokio::time::Instant::now();
+        let server = tokio::spawn(async move {
+            run_server(
+                &[format!("127.0.0.1:{free_port}")],
+                router,
+                delay,
+                false,
+                None,
+                None,
+                |_| {},
+            )
+            .await
+        });
+
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        assert!(
+            tokio::net::TcpStream::connect(("127.0.0.1", free_port))
+                .await
+                .is_err(),
+            "Server should not accept connections during the startup delay"
+        );
+
+        let mut connected = false;
+        for _ in 0..200 {
+            if tokio::net::TcpStream::connect(("127.0.0.1", free_port))
+                .await
+                .is_ok()
+            {
+                connected = true;
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        let elapsed = started.elapsed();
+        server.abort();
+
+        assert!(connected, "Server should listen once the delay is over");
+        assert!(
+            elapsed >= delay,
+            "Accepted after {elapsed:?}, before {delay:?}"
+        );
+    }
+
+    /// Tests that every listen address serves the router and that a bind failure names its address.
+    #[tokio::test]
+    async fn test_server_listens_on_every_address() {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        let mut config_manager =
+            ConfigManager::new(FileSystemManager::new(None).expect("Failed to create manager"));
+        config_manager
+            .load_config_from_bytes(br#"{"routes": {"/ping": {"response": {"body": "pong"}}}}"#)
+            .await
+            .expect("Failed to load test config");
+        let mut db = Database::new();
+        db.populate(config_manager.config.routes.clone());
+        let router = create_router(
+            Arc::new(configured_state(db, &config_manager.config)),
+            &config_manager,
+        );
+
+        let free_port = || {
+            std::net::TcpListener::bind("127.0.0.1:0")
+                .and_then(|listener| listener.local_addr())
+                .expect("Failed to reserve a port")
+                .port()
+        };
+        let ports = [free_port(), free_port()];
+        let addresses = ports.map(|port| format!("127.0.0.1:{port}"));
+
+        let served = router.clone();
+        let server = tokio::spawn(async move {
+            run_server(
+                &addresses,
+                served,
+                Duration::ZERO,
+                false,
+                None,
+                None,
+                |_| {},
+            )
+            .await
+        });
+
+        for port in ports {
+            let mut stream = None;
+            for _ in 0..100 {
+                match tokio::net::TcpStream::connect(("127.0.0.1", port)).await {
+                    Ok(connected) => {
+                        stream = Some(connected);
+                        break;
+                    }
+                    Err(_) => tokio::time::sleep(Duration::from_millis(10)).await,
+                }
+            }
+            let mut stream = stream.expect("Server should listen on every address");
+
+            stream
+                .write_all(b"GET /ping HTTP/1.1\r\nHost: localhost\r\nConnection: close\r\n\r\n")
+                .await
+                .expect("Failed to send request");
+            let mut reply = String::new();
+            stream
+                .read_to_string(&mut reply)
+                .await
+                .expect("Failed to read response");
+            assert!(
+                reply.starts_with("HTTP/1.1 200"),
+                "Unexpected reply on port {port}: {reply}"
+            );
+        }
+        server.abort();
+
+        let taken = std::net::TcpListener::bind("127.0.0.1:0").expect("Failed to take a port");
+        let taken_address = taken
+            .local_addr()
+            .expect("Listener has no address")
+            .to_string();
+        let addresses = [format!("127.0.0.1:{}", free_port()), taken_address.clone()];
+        let error = run_server(
+            &addresses,
+            router,
+            Duration::ZERO,
+            false,
+            None,
+            None,
+            |_| {},
+        )
+        .await
+        .expect_err("Binding a taken address should fail");
+        assert!(
+            error
+                .to_string()
+                .contains(&format!("Failed to bind {taken_address}")),
+            "Unexpected error: {error}"
+        );
+    }
+
+    /// Tests listening on port 0 and reporting the port the system picked.
+    ///
+    /// Verifies that the port written to `--port-file` and the URL handed to
+    /// the listening callback carry the same non-zero port, and that the
+    /// server answers on it.
+    #[tokio::test]
+    async fn test_port_zero_reports_bound_port() {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        let dir = tempfile::tempdir().expect("Failed to create temporary directory");
+        let port_file = dir.path().join("echo.port");
+        let cli = Cli::try_parse_from([
+            "echo",
+            "serve",
+            "--port",
+            "0",
+            "--port-file",
+            &port_file.display().to_string(),
+        ])
+        .expect("Flags should parse");
+        let Commands::Serve {
+            port: Some(port),
+            port_file: Some(port_file),
+            ..
+        } = cli.command
+        else {
+            panic!("Expected the serve command with a port and a port file");
+        };
+
+        let mut config_manager =
+            ConfigManager::new(FileSystemManager::new(None).expect("Failed to create manager"));
+        config_manager
+            .load_config_from_bytes(br#"{"routes": {"/ping": {"response": {"body": "pong"}}}}"#)
+            .await
+            .expect("Failed to load test config");
+        let mut db = Database::new();
+        db.populate(config_manager.config.routes.clone());
+        let router = create_router(
+            Arc::new(configured_state(db, &config_manager.config)),
+            &config_manager,
+        );
+
+        let (listening, url) = tokio::sync::oneshot::channel();
+        let written = port_file.clone();
+        let server = tokio::spawn(async move {
+            run_server(
+                &[format!("127.0.0.1:{port}")],
+                router,
+                Duration::ZERO,
+                false,
+                None,
+                Some(&written),
+                |urls| {
+                    let _ = listening.send(urls[0].clone());
+                },
+            )
+            .await
+        });
+
+        let url = url.await.expect("Server should report its URL");
+        let bound: u16 = std::fs::read_to_string(&port_file)
+            .expect("Port file should be written before listening is reported")
+            .trim()
+            .parse()
+            .expect("Port file should hold a port");
+        assert_ne!(bound, 0);
+        assert_eq!(url, format!("http://127.0.0.1:{bound}"));
+
+        let mut stream = tokio::net::TcpStream::connect(("127.0.0.1", bound))
+            .await
+            .expect("Server should listen on the reported port");
+        stream
+            .write_all(b"GET /ping HTTP/1.1\r\nHost: localhost\r\nConnection: close\r\n\r\n")
+            .await
+            .expect("Failed to send request");
+        let mut reply = String::new();
+        stream
+            .read_to_string(&mut reply)
+            .await
+            .expect("Failed to read response");
+        server.abort();
+
+        assert!(
+            reply.starts_with("HTTP/1.1 200"),
+            "Unexpected reply: {reply}"
+        );
+    }
+
+    /// Tests serving HTTP/2 with prior knowledge when `--http2` is given.
+    ///
+    /// Verifies that the flag parses, that an h2c request is answered over
+    /// HTTP/2, and that a server without it refuses the HTTP/2 preface.
+    #[tokio::test]
+    async fn test_http2_prior_knowledge() {
+        use hyper_util::rt::{TokioExecutor, TokioIo};
+
+        let cli = Cli::try_parse_from(["echo", "serve", "--http2"]).expect("Flag should parse");
+        assert!(matches!(cli.command, Commands::Serve { http2: true, .. }));
+
+        let mut config_manager =
+            ConfigManager::new(FileSystemManager::new(None).expect("Failed to create manager"));
+        config_manager
+            .load_config_from_bytes(br#"{"routes": {"/ping": {"response": {"body": "pong"}}}}"#)
+            .await
+            .expect("Failed to load test config");
+
+        // Sends GET /ping over h2c to a server started with or without HTTP/2
+        let h2c_get = |http2: bool| {
+            let mut db = Database::new();
+            db.populate(config_manager.config.routes.clone());
+            let router = create_router(
+                Arc::new(configured_state(db, &config_manager.config)),
+                &config_manager,
+            );
+            async move {
+                let (listening, url) = tokio::sync::oneshot::channel();
+                let server = tokio::spawn(async move {
+                    run_server(
+                        &["127.0.0.1:0".to_string()],
+                        router,
+                        Duration::ZERO,
+                        http2,
+                        None,
+                        None,
+                        |urls| {
+                            let _ = listening.send(urls[0].clone());
+                        },
+                    )
+                    .await
+                });
+                let url = url.await.expect("Server should report its URL");
+                let address = url.trim_start_matches("http://").to_string();
+
+                let stream = tokio::net::TcpStream::connect(address)
+                    .await
+                    .expect("Server should listen");
+                let result = async {
+                    let (mut sender, connection) = hyper::client::conn::http2::handshake(
+                        TokioExecutor::new(),
+                        TokioIo::new(stream),
+                    )
+                    .await?;
+                    tokio::spawn(connection);
+                    let request = axum::http::Request::builder()
+                        .uri(format!("{url}/ping"))
+                        .body(axum::body::Body::empty())
+                        .expect("Failed to build request");
+                    sender.send_request(request).await
+                }
+                .await;
+                server.abort();
+                result
+            }
+        };
+
+        let response = h2c_get(true).await.expect("An h2c request should succeed");
+        assert_eq!(response.version(), axum::http::Version::HTTP_2);
+        assert_eq!(response.status(), axum::http::StatusCode::OK);
+
+        assert!(
+            h2c_get(false).await.is_err(),
+            "An HTTP/1.1-only server should refuse the HTTP/2 preface"
+        );
+    }
+
+    /// Tests serving HTTPS with the configured certificate and key.
+    ///
+    /// Verifies that the listener reports an `https` URL and that a client
+    /// trusting the certificate is answered over TLS.
+    #[tokio::test]
+    async fn test_tls_serves_https() {
+        let dir = tempfile::tempdir().expect("Failed to create temporary directory");
+        let certified = rcgen::generate_simple_self_signed(vec!["127.0.0.1".to_string()])
+            .expect("Failed to generate a certificate");
+        let cert_pem = certified.cert.pem();
+        std::fs::write(dir.path().join("cert.pem"), &cert_pem).expect("Failed to write cert");
+        std::fs::write(
+            dir.path().join("key.pem"),
+            certified.signing_key.serialize_pem(),
+        )
+        .expect("Failed to write key");
+
+        let mut config_manager = ConfigManager::new(
+            FileSystemManager::new(Some(dir.path().to_path_buf()))
+                .expect("Failed to create manager"),
+        );
+        config_manager
+            .load_config_from_bytes(
+                br#"{
+                    "tls": {"cert_file": "cert.pem", "key_file": "key.pem"},
+                    "routes": {"/ping": {"response": {"body": "pong"}}}
+                }"#,
+            )
+            .await
+            .expect("Failed to load test config");
+        let tls = config_manager
+            .config
+            .tls
+            .as_ref()
+            .expect("TLS should parse");
+        let acceptor = tls_acceptor(config_manager.get_root(), tls, false)
+            .await
+            .expect("The certificate should load");
+
+        let mut db = Database::new();
+        db.populate(config_manager.config.routes.clone());
+        let router = create_router(
+            Arc::new(configured_state(db, &config_manager.config)),
+            &config_manager,
+        );
+        let (listening, url) = tokio::sync::oneshot::channel();
+        let server = tokio::spawn(async move {
+            run_server(
+                &["127.0.0.1:0".to_string()],
+                router,
+                Duration::ZERO,
+                false,
+                Some(acceptor),
+                None,
+                |urls| {
+                    let _ = listening.send(urls[0].clone());
+                },
+            )
+            .await
+        });
+        let url = url.await.expect("Server should report its URL");
+        assert!(url.starts_with("https://127.0.0.1:"), "{url}");
+
+        let client = reqwest::Client::builder()
+            .add_root_certificate(
+                reqwest::Certificate::from_pem(cert_pem.as_bytes())
+                    .expect("The certificate should parse"),
+            )
+            .build()
+            .expect("Failed to build client");
+        let response = client
+            .get(format!("{url}/ping"))
+            .send()
+            .await
+            .expect("An HTTPS request should succeed");
+        server.abort();
+
+        assert_eq!(response.status(), reqwest::StatusCode::OK);
+        assert_eq!(
+            response.text().await.expect("Failed to read body"),
+            r#""pong""#
+        );
+    }
+
+    /// Tests that `--open` parses and that a failing opener is skipped gracefully.
+    #[test]
+    fn test_open_flag_and_failed_open() {
+        let cli = Cli::try_parse_from(["echo", "serve", "--open"]).expect("Flag should parse");
+        assert!(matches!(cli.command, Commands::Serve { open: true, .. }));
+
+        let cli = Cli::try_parse_from(["echo", "serve"]).expect("Serve should parse");
+        assert!(matches!(cli.command, Commands::Serve { open: false, .. }));
+
+        let mut opened = None;
+        assert!(open_browser("http://localhost:3001", |url| {
+            opened = Some(url.to_string());
+            Ok::<(), io::Error>(())
+        }));
+        assert_eq!(opened.as_deref(), Some("http://localhost:3001"));
+
+        assert!(!open_browser("http://localhost:3001", |_| {
+            Err(io::Error::other("no browser available"))
+        }));
+    }
+
+    /// Tests the summary printed by `--print-summary json` for a loaded configuration.
+    ///
+    /// Verifies that the flag only accepts known formats and that the summary
+    /// built once the server listens reports the port bound for `--port 0`
+    /// and the loaded routes.
+    #[tokio::test]
+    async fn test_print_summary_describes_loaded_config() {
+        assert!(Cli::try_parse_from(["echo", "serve", "--print-summary", "yaml"]).is_err());
+
+        let cli = Cli::try_parse_from(["echo", "serve", "--port", "0", "--print-summary", "json"])
+            .expect("Flag should parse");
+        let Commands::Serve {
+            port,
+            print_summary: Some(format),
+            ..
+        } = cli.command
+        else {
+            panic!("Expected the serve command with a summary format");
+        };
+
+        let mut config_manager =
+            ConfigManager::new(FileSystemManager::new(None).expect("Failed to create manager"));
+        config_manager
+            .load_config_from_bytes(br#"{"hostname": "127.0.0.1", "routes": {"/ping": {"response": {"body": "pong"}}}}"#)
+            .await
+            .expect("Config should load");
+
+        let addresses = listen_addresses(port, &config_manager.config);
+        let mut db = Database::new();
+        db.populate(config_manager.config.routes.clone());
+        let router = create_router(
+            Arc::new(configured_state(db, &config_manager.config)),
+            &config_manager,
+        );
+
+        let (listening, rendered) = tokio::sync::oneshot::channel();
+        let config = config_manager.config.clone();
+        let server = tokio::spawn(async move {
+            run_server(
+                &addresses,
+                router,
+                Duration::ZERO,
+                false,
+                None,
+                None,
+                |urls| {
+                    let _ = listening.send(StartupSummary::new(urls, &config).render(format));
+                },
+            )
+            .await
+        });
+
+        let rendered = rendered
+            .await
+            .expect("Server should report its URLs")
+            .expect("Summary should render");
+        server.abort();
+        let summary: serde_json::Value =
+            serde_json::from_str(&rendered).expect("Summary should be JSON");
+
+        let address = summary["addresses"][0]
+            .as_str()
+            .expect("Summary should list the bound address");
+        assert!(address.starts_with("127.0.0.1:"), "{address}");
+        assert_ne!(address, "127.0.0.1:0");
+        assert_eq!(summary["addresses"].as_array().map(Vec::len), Some(1));
+        assert_eq!(summary["routes"], 1);
+        assert_eq!(summary["static_folder"], serde_json::Value::Null);
+        assert_eq!(summary["features"]["tls"], false);
+    }
 }
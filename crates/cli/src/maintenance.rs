@@ -0,0 +1,136 @@
+//! Runtime maintenance mode for JSON Echo.
+//!
+//! This module lets a running server be switched into maintenance, answering
+//! every configured route with `503 Service Unavailable`, so UIs can be tested
+//! against their maintenance banners and recover once the server is back.
+//!
+//! ## What
+//!
+//! The module defines:
+//! - `ADMIN_MAINTENANCE_PATH`: The admin route reading and toggling maintenance mode
+//! - `default_maintenance_body`: The body answered when `maintenance_body` is not configured
+//! - `MaintenanceToggle`: The payload accepted by the admin route
+//! - `maintenance_status`: Handler reporting whether maintenance mode is on
+//! - `toggle_maintenance`: Handler switching maintenance mode on or off
+//! - `maintenance_mode`: Middleware answering `503` while maintenance mode is on
+//!
+//! ## How
+//!
+//! Maintenance mode works by:
+//! 1. Keeping the flag in an `AtomicBool` of the shared application state
+//! 2. Flipping it with `POST /__admin/maintenance` and `{"enabled": true}` or `{"enabled": false}`
+//! 3. Checking it in a layer wrapped around every configured route, inside its CORS layer
+//!
+//! ## Why
+//!
+//! The check sits inside the CORS layer so browsers can still read the
+//! `503`, and preflight requests keep being answered. The admin route is not
+//! wrapped, so maintenance can always be turned off again.
+//!
+//! # Examples
+//!
+//! ```bash
+//! curl -X POST localhost:3001/__admin/maintenance -H 'Content-Type: application/json' -d '{"enabled": true}'
+//! ```
+
+use crate::server::AppState;
+use axum::{
+    Json,
+    extract::{Request, State},
+    http::{StatusCode, header::RETRY_AFTER},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use serde::Deserialize;
+use serde_json::{Value, json};
+use std::sync::Arc;
+use tracing::info;
+
+/// The admin route reading and toggling maintenance mode.
+pub const ADMIN_MAINTENANCE_PATH: &str = "/__admin/maintenance";
+
+/// Returns the body answered when `maintenance_body` is not configured.
+pub fn default_maintenance_body() -> Value {
+    json!({"error": "Service under maintenance"})
+}
+
+/// The payload accepted by the admin route.
+///
+/// # Fields
+///
+/// * `enabled` - Whether maintenance mode is switched on or off
+#[derive(Debug, Clone, Copy, Deserialize)]
+pub struct MaintenanceToggle {
+    /// Whether maintenance mode is switched on or off
+    pub enabled: bool,
+}
+
+/// Handler reporting whether maintenance mode is on.
+///
+/// # Returns
+///
+/// `{"maintenance": true}` or `{"maintenance": false}`
+pub async fn maintenance_status(State(state): State<Arc<AppState>>) -> Json<Value> {
+    Json(json!({"maintenance": state.in_maintenance()}))
+}
+
+/// Handler switching maintenance mode on or off.
+///
+/// # Parameters
+///
+/// * `State(state)` - Shared application state holding the maintenance flag
+/// * `Json(toggle)` - The requested state of maintenance mode
+///
+/// # Returns
+///
+/// The new state, as `{"maintenance": true}` or `{"maintenance": false}`
+pub async fn toggle_maintenance(
+    State(state): State<Arc<AppState>>,
+    Json(toggle): Json<MaintenanceToggle>,
+) -> Json<Value> {
+    state.set_maintenance(toggle.enabled);
+    info!(
+        "Maintenance mode {}",
+        if toggle.enabled {
+            "enabled"
+        } else {
+            "disabled"
+        }
+    );
+
+    Json(json!({"maintenance": toggle.enabled}))
+}
+
+/// Middleware answering `503 Service Unavailable` while maintenance mode is on.
+///
+/// # Parameters
+///
+/// * `State(state)` - Shared application state holding the maintenance flag and body
+/// * `request` - The incoming request
+/// * `next` - The remaining middleware and the route handler
+///
+/// # Returns
+///
+/// The configured maintenance body with a `503`, and the configured
+/// `Retry-After`, while maintenance mode is on, otherwise the response of the route
+pub async fn maintenance_mode(
+    State(state): State<Arc<AppState>>,
+    request: Request,
+    next: Next,
+) -> Response {
+    if state.in_maintenance() {
+        let mut response = (
+            StatusCode::SERVICE_UNAVAILABLE,
+            Json(state.maintenance_body().clone()),
+        )
+            .into_response();
+        if let Some(retry_after) = state.maintenance_retry_after() {
+            response
+                .headers_mut()
+                .insert(RETRY_AFTER, retry_after.clone());
+        }
+        return response;
+    }
+
+    next.run(request).await
+}
@@ -0,0 +1,231 @@
+//! Merging of several JSON Echo configurations into one.
+//!
+//! This module combines configuration files into a single normalized
+//! configuration, so a mock API split across files can be distributed as one
+//! artifact.
+//!
+//! ## What
+//!
+//! The module defines:
+//! - `Merged`: The merged configuration and the routes defined more than once
+//! - `Collision`: A route defined by several configurations
+//! - `merge_configs`: Merges loaded configurations in order
+//! - `save_config_file`: Saves a configuration to a file in any directory
+//!
+//! ## How
+//!
+//! A merge works by:
+//! 1. Taking the server settings of the first configuration
+//! 2. Letting every later configuration override the settings it sets explicitly
+//! 3. Adding the routes of every configuration in order, later definitions
+//!    replacing earlier ones under the same `[METHOD] path` key
+//! 4. Recording every replaced route as a collision
+//!
+//! ## Why
+//!
+//! Loaded configurations carry defaults for settings their file omits, so a
+//! setting only counts as set when it differs from the default; otherwise a
+//! later file without a `hostname` would reset the hostname of the first.
+//!
+//! # Examples
+//!
+//! ```bash
+//! json-echo merge users.json orders.json -o merged.json
+//! ```
+
+use crate::diff::{file_manager, settings};
+use indexmap::IndexMap;
+use json_echo_core::{Config, FileSystemResult};
+use serde_json::{Map, Value};
+use std::{fmt, path::Path};
+
+/// The result of merging configurations.
+///
+/// # Fields
+///
+/// * `config` - The merged configuration
+/// * `collisions` - The routes defined by more than one configuration
+#[derive(Debug, Clone)]
+pub struct Merged {
+    /// The merged configuration
+    pub config: Config,
+    /// The routes defined by more than one configuration
+    pub collisions: Vec<Collision>,
+}
+
+/// A route defined by more than one configuration.
+///
+/// # Fields
+///
+/// * `route` - The normalized `[METHOD] path` identifier
+/// * `replaced` - The configuration whose definition was replaced
+/// * `winner` - The later configuration whose definition was kept
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Collision {
+    /// The normalized `[METHOD] path` identifier
+    pub route: String,
+    /// The configuration whose definition was replaced
+    pub replaced: String,
+    /// The later configuration whose definition was kept
+    pub winner: String,
+}
+
+impl fmt::Display for Collision {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} is defined in {} and {}; keeping {}",
+            self.route, self.replaced, self.winner, self.winner
+        )
+    }
+}
+
+/// Merges configurations in order, later routes and settings winning.
+///
+/// # Parameters
+///
+/// * `configs` - The loaded configurations, each labeled with its source name
+///
+/// # Returns
+///
+/// The merged configuration along with the routes that collided
+///
+/// # Examples
+///
+/// ```rust
+/// let merged = merge_configs(vec![
+///     ("a.json".to_string(), first),
+///     ("b.json".to_string(), second),
+/// ]);
+/// assert!(merged.collisions.is_empty());
+/// ```
+pub fn merge_configs(configs: Vec<(String, Config)>) -> Merged {
+    let defaults = settings(&serde_json::from_str("{}").unwrap_or_default());
+
+    let mut merged_settings: Option<Map<String, Value>> = None;
+    let mut routes = IndexMap::new();
+    let mut sources: IndexMap<String, String> = IndexMap::new();
+    let mut collisions = Vec::new();
+
+    for (source, config) in configs {
+        let config_settings = settings(&config);
+
+        match merged_settings.as_mut() {
+            None => merged_settings = Some(config_settings),
+            Some(merged) => {
+                for (name, value) in config_settings {
+                    if defaults.get(&name) != Some(&value) {
+                        merged.insert(name, value);
+                    }
+                }
+            }
+        }
+
+        for (route, definition) in config.routes {
+            if let Some(replaced) = sources.insert(route.clone(), source.clone()) {
+                collisions.push(Collision {
+                    route: route.clone(),
+                    replaced,
+                    winner: source.clone(),
+                });
+            }
+            routes.insert(route, definition);
+        }
+    }
+
+    let mut config: Config =
+        serde_json::from_value(Value::Object(merged_settings.unwrap_or_default()))
+            .unwrap_or_default();
+    config.routes = routes;
+    // The routes of every routes_dir are already part of the merged routes
+    config.routes_dir = None;
+
+    Merged { config, collisions }
+}
+
+/// Saves a configuration through `ConfigManager::save_config`.
+///
+/// # Parameters
+///
+/// * `path` - The output file, absolute or relative to the current directory
+/// * `config` - The configuration to save
+///
+/// # Returns
+///
+/// * `Ok(())` - If the configuration was written
+/// * `Err(FileSystemError)` - If the file cannot be written
+pub async fn save_config_file(path: &Path, config: &Config) -> FileSystemResult<()> {
+    let (config_manager, file_name) = file_manager(path)?;
+    config_manager.save_config(&file_name, config).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::diff::load_config_file;
+    use serde_json::json;
+
+    /// Tests merging two configuration files into one saved file.
+    ///
+    /// Verifies that the saved output contains the union of routes, that the
+    /// later file wins and is reported on collisions, and that settings come
+    /// from the first file unless the later one sets them.
+    #[tokio::test]
+    async fn test_merge_configs() {
+        let dir = tempfile::tempdir().expect("Failed to create temporary directory");
+        let first = json!({
+            "port": 4000,
+            "hostname": "0.0.0.0",
+            "routes": {
+                "/users": {"response": {"status": 200, "body": [{"id": 1}]}},
+                "/health": {"response": {"status": 200, "body": "ok"}}
+            }
+        });
+        let second = json!({
+            "port": 5000,
+            "routes": {
+                "[GET] /users": {"response": {"status": 200, "body": [{"id": 2}]}},
+                "[POST] /orders": {"response": {"status": 201, "body": {}}}
+            }
+        });
+        std::fs::write(dir.path().join("a.json"), first.to_string()).expect("Failed to write a");
+        std::fs::write(dir.path().join("b.json"), second.to_string()).expect("Failed to write b");
+
+        let mut configs = Vec::new();
+        for name in ["a.json", "b.json"] {
+            let config = load_config_file(&dir.path().join(name))
+                .await
+                .expect("Failed to load config");
+            configs.push((name.to_string(), config));
+        }
+        let merged = merge_configs(configs);
+
+        assert_eq!(
+            merged.collisions,
+            [Collision {
+                route: "[GET] /users".to_string(),
+                replaced: "a.json".to_string(),
+                winner: "b.json".to_string(),
+            }]
+        );
+        assert_eq!(
+            merged.collisions[0].to_string(),
+            "[GET] /users is defined in a.json and b.json; keeping b.json"
+        );
+
+        save_config_file(&dir.path().join("merged.json"), &merged.config)
+            .await
+            .expect("Failed to save merged config");
+
+        let output = load_config_file(&dir.path().join("merged.json"))
+            .await
+            .expect("Merged config should load");
+        let routes: Vec<&str> = output.routes.keys().map(String::as_str).collect();
+        assert_eq!(routes, ["[GET] /users", "[GET] /health", "[POST] /orders"]);
+        assert_eq!(output.port, Some(5000));
+        assert_eq!(output.hostname.as_deref(), Some("0.0.0.0"));
+
+        let users = serde_json::to_value(&output.routes["[GET] /users"]).expect("Serializable");
+        assert_eq!(users["response"]["body"], json!([{"id": 2}]));
+    }
+}
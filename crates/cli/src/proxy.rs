@@ -0,0 +1,267 @@
+//! Route-level proxying of JSON Echo requests to real upstreams.
+//!
+//! This module forwards the requests of routes configured with `proxy` to an
+//! upstream service and answers with its response, so most endpoints of an
+//! API can be mocked while a few, such as payments, reach a real sandbox.
+//!
+//! ## What
+//!
+//! The module defines:
+//! - `ProxyError`: Why a request could not be forwarded
+//! - `upstream_url`: The upstream URL a request is forwarded to
+//! - `forward`: Sends a request to the upstream and builds the response to answer with
+//!
+//! ## How
+//!
+//! A request is forwarded by:
+//! 1. Joining the target with the request path, or with `path_rewrite` filled from
+//!    the path parameters, and the query string
+//! 2. Sending the method, the end-to-end headers and the body through the shared client
+//! 3. Answering with the upstream's status, headers and body, hop-by-hop headers left out
+//! 4. Answering `504 Gateway Timeout` when the upstream is too slow, `502 Bad Gateway`
+//!    when it cannot be reached
+//!
+//! ## Why
+//!
+//! The upstream is reached through the client of `crate::client`, which
+//! bounds every exchange by a timeout, speaks `https://` as well as
+//! `http://`, and passes redirects through rather than following them.
+//! Hop-by-hop headers describe a single connection, so they are dropped
+//! both ways and `Host` names the upstream.
+//!
+//! # Examples
+//!
+//! ```json
+//! {"/payments/{*path}": {"proxy": {"target": "http://localhost:9000", "path_rewrite": "/v1/{path}"}}}
+//! ```
+
+use axum::{
+    body::Bytes,
+    http::{HeaderMap, Method, StatusCode, Uri, header::HOST},
+    response::{IntoResponse, Response},
+};
+use json_echo_core::ProxyConfig;
+use reqwest::Client;
+use std::collections::HashMap;
+
+/// Headers describing a single connection, never forwarded either way.
+const HOP_BY_HOP_HEADERS: [&str; 9] = [
+    "connection",
+    "keep-alive",
+    "proxy-authenticate",
+    "proxy-authorization",
+    "te",
+    "trailer",
+    "transfer-encoding",
+    "upgrade",
+    "content-length",
+];
+
+/// Why a request could not be forwarded to the upstream.
+///
+/// # Variants
+///
+/// * `Upstream` - The upstream could not be reached, timed out, or the exchange failed
+#[derive(Debug, thiserror::Error)]
+pub enum ProxyError {
+    /// The upstream could not be reached, timed out, or the exchange failed
+    #[error("Failed to reach upstream {url}: {source}")]
+    Upstream {
+        /// The upstream URL
+        url: String,
+        /// The client failure
+        source: reqwest::Error,
+    },
+}
+
+impl ProxyError {
+    /// Returns the status answered to the client for the failure.
+    ///
+    /// # Returns
+    ///
+    /// `504 Gateway Timeout` if the upstream was too slow, `502 Bad Gateway` otherwise
+    pub fn status(&self) -> StatusCode {
+        match self {
+            Self::Upstream { source, .. } if source.is_timeout() => StatusCode::GATEWAY_TIMEOUT,
+            Self::Upstream { .. } => StatusCode::BAD_GATEWAY,
+        }
+    }
+}
+
+/// Returns the upstream URL a request is forwarded to.
+///
+/// # Parameters
+///
+/// * `proxy` - The `proxy` settings of the route
+/// * `uri` - The URI of the request
+/// * `params` - The path parameters of the request, filling `path_rewrite`
+///
+/// # Returns
+///
+/// The target joined with the rewritten or original path and the query string
+///
+/// # Examples
+///
+/// ```rust
+/// let proxy = ProxyConfig {
+///     target: "http://localhost:9000/".to_string(),
+///     path_rewrite: Some("/v1/{path}".to_string()),
+/// };
+/// let params = HashMap::from([("path".to_string(), "charges/1".to_string())]);
+/// let uri: Uri = "/payments/charges/1?expand=card".parse()?;
+/// assert_eq!(
+///     upstream_url(&proxy, &uri, &params),
+///     "http://localhost:9000/v1/charges/1?expand=card"
+/// );
+/// ```
+pub fn upstream_url(proxy: &ProxyConfig, uri: &Uri, params: &HashMap<String, String>) -> String {
+    let path = proxy.path_rewrite.as_ref().map_or_else(
+        || uri.path().to_string(),
+        |rewrite| {
+            params.iter().fold(rewrite.clone(), |path, (name, value)| {
+                path.replace(&format!("{{{name}}}"), value)
+            })
+        },
+    );
+    let path = if path.starts_with('/') {
+        path
+    } else {
+        format!("/{path}")
+    };
+    let query = uri
+        .query()
+        .map(|query| format!("?{query}"))
+        .unwrap_or_default();
+
+    format!("{}{path}{query}", proxy.target.trim_end_matches('/'))
+}
+
+/// Forwards a request to the upstream and builds the response to answer with.
+///
+/// # Parameters
+///
+/// * `client` - The client reaching the upstream, see `crate::client::http_client`
+/// * `url` - The upstream URL, see `upstream_url`
+/// * `method` - The method of the request
+/// * `headers` - The headers of the request
+/// * `body` - The body of the request
+///
+/// # Returns
+///
+/// * `Ok(Response)` - The upstream's status, end-to-end headers and body
+/// * `Err(ProxyError)` - If the upstream cannot be reached, times out or the URL is invalid
+pub async fn forward(
+    client: &Client,
+    url: &str,
+    method: &Method,
+    headers: &HeaderMap,
+    body: Bytes,
+) -> Result<Response, ProxyError> {
+    let failed = |source| ProxyError::Upstream {
+        url: url.to_string(),
+        source,
+    };
+
+    // The client names the upstream in Host
+    let mut headers = forwarded(headers);
+    headers.remove(HOST);
+
+    let upstream = client
+        .request(method.clone(), url)
+        .headers(headers)
+        .body(body)
+        .send()
+        .await
+        .map_err(failed)?;
+
+    let status = upstream.status();
+    let headers = forwarded(upstream.headers());
+    let body = upstream.bytes().await.map_err(failed)?;
+
+    Ok((status, headers, body).into_response())
+}
+
+/// Returns the end-to-end headers of a request or response.
+fn forwarded(headers: &HeaderMap) -> HeaderMap {
+    headers
+        .iter()
+        .filter(|(name, _)| !HOP_BY_HOP_HEADERS.contains(&name.as_str()))
+        .map(|(name, value)| (name.clone(), value.clone()))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::http_client;
+    use axum::{Router, routing::get};
+    use std::time::Duration;
+
+    /// Tests building upstream URLs with and without a path rewrite.
+    #[test]
+    fn test_upstream_url() {
+        let uri: Uri = "/payments/charges/1?expand=card"
+            .parse()
+            .expect("Valid URI");
+        let params = HashMap::from([("path".to_string(), "charges/1".to_string())]);
+
+        let mut proxy = ProxyConfig {
+            target: "http://localhost:9000/".to_string(),
+            path_rewrite: None,
+        };
+        assert_eq!(
+            upstream_url(&proxy, &uri, &params),
+            "http://localhost:9000/payments/charges/1?expand=card"
+        );
+
+        proxy.path_rewrite = Some("v1/{path}".to_string());
+        assert_eq!(
+            upstream_url(&proxy, &uri, &params),
+            "http://localhost:9000/v1/charges/1?expand=card"
+        );
+    }
+
+    /// Tests that slow and unreachable upstreams answer gateway errors.
+    ///
+    /// Verifies that an upstream holding its response past the timeout gives
+    /// a `504`, and that a closed port gives a `502`.
+    #[tokio::test]
+    async fn test_forward_gateway_errors() {
+        let stub = Router::new().route(
+            "/slow",
+            get(|| async {
+                tokio::time::sleep(Duration::from_secs(5)).await;
+                "late"
+            }),
+        );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0")
+            .await
+            .expect("Failed to bind stub server");
+        let address = listener.local_addr().expect("Stub server has no address");
+        tokio::spawn(async move { axum::serve(listener, stub).await });
+
+        let closed = tokio::net::TcpListener::bind("127.0.0.1:0")
+            .await
+            .and_then(|listener| listener.local_addr())
+            .expect("Failed to reserve a port");
+
+        let client = http_client(Duration::from_millis(100)).expect("Client should build");
+        let status = |url: String| {
+            let client = client.clone();
+            async move {
+                forward(&client, &url, &Method::GET, &HeaderMap::new(), Bytes::new())
+                    .await
+                    .map_or_else(|error| error.status(), |response| response.status())
+            }
+        };
+
+        assert_eq!(
+            status(format!("http://{address}/slow")).await,
+            StatusCode::GATEWAY_TIMEOUT
+        );
+        assert_eq!(
+            status(format!("http://{closed}/")).await,
+            StatusCode::BAD_GATEWAY
+        );
+    }
+}
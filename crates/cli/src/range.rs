@@ -0,0 +1,234 @@
+//! Byte range requests for JSON Echo route responses.
+//!
+//! This module answers GET requests carrying a `Range` header with `206
+//! Partial Content` and the requested slice of the response body, so clients
+//! implementing resumable downloads can be tested against mock routes. Static
+//! files already get the same treatment from `ServeDir`.
+//!
+//! ## What
+//!
+//! The module defines:
+//! - `RangeOutcome`: How a `Range` header applies to a body of a given length
+//! - `parse_range`: The RFC 7233 evaluation of a `bytes` range header
+//! - `partial_content`: Middleware answering range requests with 206 or 416
+//!
+//! ## How
+//!
+//! Range handling works by:
+//! 1. Letting the route handler build the full `200 OK` response
+//! 2. Advertising `Accept-Ranges: bytes` on it
+//! 3. Evaluating the request's `Range` header against the body length
+//! 4. Replacing the body with the requested slice and a `Content-Range` header,
+//!    or answering `416 Range Not Satisfiable` when no byte of it exists
+//!
+//! ## Why
+//!
+//! RFC 7233 lets a server ignore a `Range` header it does not support, so
+//! malformed headers, other units and multiple ranges get the full response
+//! instead of an error. Slicing after the handler keeps every route eligible
+//! and lets the entity tag describe the full representation.
+//!
+//! # Examples
+//!
+//! ```text
+//! Range: bytes=0-99     -> 206, Content-Range: bytes 0-99/1234
+//! Range: bytes=-100     -> 206, Content-Range: bytes 1134-1233/1234
+//! Range: bytes=5000-    -> 416, Content-Range: bytes */1234
+//! ```
+
+use crate::server::AppState;
+use crate::stream::is_streamed;
+use axum::{
+    body::{Body, to_bytes},
+    extract::{Request, State},
+    http::{
+        HeaderValue, Method, StatusCode,
+        header::{ACCEPT_RANGES, CONTENT_LENGTH, CONTENT_RANGE, RANGE},
+    },
+    middleware::Next,
+    response::Response,
+};
+use std::sync::Arc;
+use tracing::debug;
+
+/// How a `Range` header applies to a body.
+///
+/// # Variants
+///
+/// * `Full` - The header is absent or unsupported; the full body is sent
+/// * `Partial` - The inclusive byte positions `start..=end` are sent
+/// * `Unsatisfiable` - The range starts past the end of the body
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RangeOutcome {
+    /// The header is absent or unsupported; the full body is sent
+    Full,
+    /// The inclusive byte positions `start..=end` are sent
+    Partial {
+        /// The first byte position
+        start: usize,
+        /// The last byte position, inclusive
+        end: usize,
+    },
+    /// The range starts past the end of the body
+    Unsatisfiable,
+}
+
+/// Evaluates a `Range` header against a body length.
+///
+/// Supports a single range in any of the `first-last`, `first-` and
+/// `-suffix` forms. A last position past the end of the body is clamped.
+///
+/// # Parameters
+///
+/// * `header` - The raw `Range` value, e.g. `bytes=0-99`
+/// * `length` - The length of the full body in bytes
+///
+/// # Returns
+///
+/// The outcome, `RangeOutcome::Full` for anything that is not a single
+/// well-formed byte range
+///
+/// # Examples
+///
+/// ```rust
+/// assert_eq!(parse_range("bytes=2-4", 10), RangeOutcome::Partial { start: 2, end: 4 });
+/// assert_eq!(parse_range("bytes=-3", 10), RangeOutcome::Partial { start: 7, end: 9 });
+/// assert_eq!(parse_range("bytes=10-", 10), RangeOutcome::Unsatisfiable);
+/// assert_eq!(parse_range("bytes=0-1,4-5", 10), RangeOutcome::Full);
+/// ```
+pub fn parse_range(header: &str, length: usize) -> RangeOutcome {
+    let Some(spec) = header.trim().strip_prefix("bytes=") else {
+        return RangeOutcome::Full;
+    };
+    let Some((first, last)) = spec.trim().split_once('-') else {
+        return RangeOutcome::Full;
+    };
+    if spec.contains(',') {
+        return RangeOutcome::Full;
+    }
+
+    let parse = |position: &str| position.trim().parse::<usize>().ok();
+
+    let (start, end) = match (first.trim().is_empty(), parse(first), parse(last)) {
+        // A suffix range asks for the last `suffix` bytes
+        (true, _, Some(suffix)) => {
+            if suffix == 0 || length == 0 {
+                return RangeOutcome::Unsatisfiable;
+            }
+            (length.saturating_sub(suffix), length - 1)
+        }
+        (false, Some(start), None) if last.trim().is_empty() => (start, usize::MAX),
+        (false, Some(start), Some(end)) if start <= end => (start, end),
+        _ => return RangeOutcome::Full,
+    };
+
+    if start >= length {
+        return RangeOutcome::Unsatisfiable;
+    }
+
+    RangeOutcome::Partial {
+        start,
+        end: end.min(length - 1),
+    }
+}
+
+/// Middleware answering byte range requests for GET responses.
+///
+/// Only successful `200 OK` GET responses are considered; they advertise
+/// `Accept-Ranges: bytes` whether or not a range was requested.
+///
+/// # Parameters
+///
+/// * `State(state)` - Shared application state, used to report internal failures
+/// * `request` - The incoming request
+/// * `next` - The remaining middleware and the route handler
+///
+/// # Returns
+///
+/// The full response, a `206 Partial Content` carrying the requested slice,
+/// or an empty `416 Range Not Satisfiable`
+pub async fn partial_content(
+    State(state): State<Arc<AppState>>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let is_get = request.method() == Method::GET;
+    let range = request
+        .headers()
+        .get(RANGE)
+        .and_then(|value| value.to_str().ok())
+        .map(str::to_string);
+
+    let mut response = next.run(request).await;
+
+    if !is_get || response.status() != StatusCode::OK || is_streamed(&response) {
+        return response;
+    }
+
+    response
+        .headers_mut()
+        .insert(ACCEPT_RANGES, HeaderValue::from_static("bytes"));
+
+    let Some(range) = range else {
+        return response;
+    };
+
+    let (mut parts, body) = response.into_parts();
+    let bytes = match to_bytes(body, usize::MAX).await {
+        Ok(bytes) => bytes,
+        Err(error) => return state.internal_error(&format!("Failed to read body: {error}")),
+    };
+    let length = bytes.len();
+
+    let (content_range, body) = match parse_range(&range, length) {
+        RangeOutcome::Full => return Response::from_parts(parts, Body::from(bytes)),
+        RangeOutcome::Partial { start, end } => {
+            debug!("Range {} answered with bytes {}-{}", range, start, end);
+            parts.status = StatusCode::PARTIAL_CONTENT;
+            (
+                format!("bytes {start}-{end}/{length}"),
+                Body::from(bytes.slice(start..=end)),
+            )
+        }
+        RangeOutcome::Unsatisfiable => {
+            debug!("Range {} is not satisfiable for {} bytes", range, length);
+            parts.status = StatusCode::RANGE_NOT_SATISFIABLE;
+            (format!("bytes */{length}"), Body::empty())
+        }
+    };
+
+    parts.headers.remove(CONTENT_LENGTH);
+    if let Ok(value) = HeaderValue::from_str(&content_range) {
+        parts.headers.insert(CONTENT_RANGE, value);
+    }
+
+    Response::from_parts(parts, body)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Tests the evaluation of byte range headers.
+    ///
+    /// Verifies the three range forms, clamping, unsatisfiable ranges and the
+    /// headers that are ignored in favor of the full body.
+    #[test]
+    fn test_parse_range() {
+        let partial = |start, end| RangeOutcome::Partial { start, end };
+
+        assert_eq!(parse_range("bytes=0-3", 10), partial(0, 3));
+        assert_eq!(parse_range("bytes=4-", 10), partial(4, 9));
+        assert_eq!(parse_range("bytes=-4", 10), partial(6, 9));
+        assert_eq!(parse_range("bytes=8-100", 10), partial(8, 9));
+        assert_eq!(parse_range("bytes=-100", 10), partial(0, 9));
+
+        assert_eq!(parse_range("bytes=10-20", 10), RangeOutcome::Unsatisfiable);
+        assert_eq!(parse_range("bytes=-0", 10), RangeOutcome::Unsatisfiable);
+
+        assert_eq!(parse_range("bytes=5-2", 10), RangeOutcome::Full);
+        assert_eq!(parse_range("bytes=0-1, 4-5", 10), RangeOutcome::Full);
+        assert_eq!(parse_range("items=0-1", 10), RangeOutcome::Full);
+        assert_eq!(parse_range("bytes=abc", 10), RangeOutcome::Full);
+    }
+}
@@ -0,0 +1,303 @@
+//! In-memory recording of the requests served by JSON Echo.
+//!
+//! This module keeps the most recent requests a server received and exposes
+//! them on an admin route when `record_requests` is set, so tests can assert
+//! that their client made exactly the calls they expect, then clear the record
+//! before the next scenario.
+//!
+//! ## What
+//!
+//! The module defines:
+//! - `ADMIN_REQUESTS_PATH`: The admin route listing and clearing the recorded requests
+//! - `RECORDED_REQUESTS_LIMIT`: The number of requests kept before the oldest are dropped
+//! - `RECORDED_BODY_LIMIT`: The largest request body recorded, in bytes
+//! - `RecordedRequest`: A request as it was received, redacted, with the status it was answered with
+//! - `RequestRecorder`: The bounded ring buffer holding the recorded requests
+//! - `recorded_requests`: Handler listing the recorded requests, oldest first
+//! - `clear_recorded_requests`: Handler forgetting every recorded request
+//! - `record_requests`: Middleware recording every request but the admin ones
+//!
+//! ## How
+//!
+//! Recording works by:
+//! 1. Reading the request body whole when it declares a `Content-Length` of at most
+//!    `RECORDED_BODY_LIMIT`, then handing the request on unchanged
+//! 2. Answering `413 Payload Too Large` when the body turns out larger than declared
+//! 3. Storing the method, path, query, headers and body, redacted like the logs,
+//!    once the response is built, together with its status
+//! 4. Dropping the oldest request once `RECORDED_REQUESTS_LIMIT` are kept
+//! 5. Listing them with `GET /__admin/requests` and clearing them with `DELETE /__admin/requests`
+//!
+//! ## Why
+//!
+//! Requests are recorded before they are routed, so calls to unknown paths
+//! show up too, and with the method the client sent, before any method
+//! override. Larger or streamed bodies are not recorded, so uploads keep
+//! streaming to disk instead of being held in memory. Recording is off unless
+//! configured, and the headers and fields masked in logs are masked in the
+//! record too, so credentials such as `Authorization` or `Cookie` are never
+//! served back. Admin requests are left out, so reading the record does not
+//! change it.
+//!
+//! # Examples
+//!
+//! ```bash
+//! curl localhost:3001/__admin/requests
+//! # [{"method":"GET","path":"/api/users","query":null,"headers":{...},"body":null,"status":200}]
+//! curl -X DELETE localhost:3001/__admin/requests
+//! ```
+
+use crate::server::AppState;
+use axum::{
+    Json,
+    body::{Body, to_bytes},
+    extract::{Request, State},
+    http::{StatusCode, header::CONTENT_LENGTH},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use http_body_util::LengthLimitError;
+use serde::Serialize;
+use serde_json::{Value, json};
+use std::{
+    collections::{BTreeMap, VecDeque},
+    sync::{Arc, Mutex},
+};
+
+/// The admin route listing and clearing the recorded requests.
+pub const ADMIN_REQUESTS_PATH: &str = "/__admin/requests";
+
+/// The number of requests kept before the oldest are dropped.
+pub const RECORDED_REQUESTS_LIMIT: usize = 1000;
+
+/// The largest request body recorded, in bytes.
+pub const RECORDED_BODY_LIMIT: usize = 64 * 1024;
+
+/// A request as it was received, with the status it was answered with.
+///
+/// # Fields
+///
+/// * `method` - The request method
+/// * `path` - The request path
+/// * `query` - The raw query string, if any
+/// * `headers` - The request headers, names lowercase, repeated values joined with `, `,
+///   redacted headers recorded as `***`
+/// * `body` - The body, parsed as JSON when possible and as text otherwise, if any was
+///   recorded, redacted fields recorded as `***`
+/// * `status` - The status of the response
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct RecordedRequest {
+    /// The request method
+    pub method: String,
+    /// The request path
+    pub path: String,
+    /// The raw query string, if any
+    pub query: Option<String>,
+    /// The request headers
+    pub headers: BTreeMap<String, String>,
+    /// The body, parsed as JSON when possible
+    pub body: Option<Value>,
+    /// The status of the response
+    pub status: u16,
+}
+
+/// The bounded ring buffer holding the recorded requests.
+///
+/// # Fields
+///
+/// * `requests` - The recorded requests, oldest first
+/// * `limit` - The number of requests kept
+#[derive(Debug)]
+pub struct RequestRecorder {
+    /// The recorded requests, oldest first
+    requests: Mutex<VecDeque<RecordedRequest>>,
+    /// The number of requests kept
+    limit: usize,
+}
+
+impl Default for RequestRecorder {
+    fn default() -> Self {
+        Self::new(RECORDED_REQUESTS_LIMIT)
+    }
+}
+
+impl RequestRecorder {
+    /// Creates an empty recorder keeping at most `limit` requests.
+    pub fn new(limit: usize) -> Self {
+        Self {
+            requests: Mutex::new(VecDeque::with_capacity(limit.min(RECORDED_REQUESTS_LIMIT))),
+            limit,
+        }
+    }
+
+    /// Records a request, dropping the oldest one when the recorder is full.
+    pub fn record(&self, request: RecordedRequest) {
+        if let Ok(mut requests) = self.requests.lock() {
+            if requests.len() >= self.limit {
+                requests.pop_front();
+            }
+            requests.push_back(request);
+        }
+    }
+
+    /// Returns the recorded requests, oldest first.
+    pub fn list(&self) -> Vec<RecordedRequest> {
+        self.requests
+            .lock()
+            .map(|requests| requests.iter().cloned().collect())
+            .unwrap_or_default()
+    }
+
+    /// Forgets every recorded request.
+    pub fn clear(&self) {
+        if let Ok(mut requests) = self.requests.lock() {
+            requests.clear();
+        }
+    }
+}
+
+/// Handler listing the recorded requests, oldest first.
+///
+/// # Returns
+///
+/// A JSON array of the recorded requests
+pub async fn recorded_requests(State(state): State<Arc<AppState>>) -> Json<Vec<RecordedRequest>> {
+    Json(state.recorder().list())
+}
+
+/// Handler forgetting every recorded request.
+///
+/// # Returns
+///
+/// `204 No Content`
+pub async fn clear_recorded_requests(State(state): State<Arc<AppState>>) -> StatusCode {
+    state.recorder().clear();
+    StatusCode::NO_CONTENT
+}
+
+/// Middleware recording every request but the admin ones.
+///
+/// # Parameters
+///
+/// * `State(state)` - Shared application state holding the recorder
+/// * `request` - The incoming request
+/// * `next` - The router
+///
+/// # Returns
+///
+/// The response of the router, unchanged, `413 Payload Too Large` if the body
+/// is larger than `RECORDED_BODY_LIMIT` despite its `Content-Length`, or the
+/// internal error response if it cannot be read
+pub async fn record_requests(
+    State(state): State<Arc<AppState>>,
+    request: Request,
+    next: Next,
+) -> Response {
+    if request.uri().path().starts_with("/__admin/") {
+        return next.run(request).await;
+    }
+
+    let recorded_length = request
+        .headers()
+        .get(CONTENT_LENGTH)
+        .and_then(|value| value.to_str().ok()?.parse::<usize>().ok())
+        .filter(|length| *length <= RECORDED_BODY_LIMIT);
+
+    let (parts, body) = request.into_parts();
+    let (forwarded, bytes) = match recorded_length {
+        Some(_) => match to_bytes(body, RECORDED_BODY_LIMIT).await {
+            Ok(bytes) => (Body::from(bytes.clone()), bytes),
+            Err(error) if is_length_limit(&error) => {
+                return (
+                    StatusCode::PAYLOAD_TOO_LARGE,
+                    Json(json!({"error": "Request body is larger than its Content-Length"})),
+                )
+                    .into_response();
+            }
+            Err(error) => return state.internal_error(&format!("Failed to read body: {error}")),
+        },
+        None => (body, axum::body::Bytes::new()),
+    };
+
+    let redactor = state.redactor();
+    let headers = parts
+        .headers
+        .keys()
+        .map(|name| {
+            let values: Vec<String> = parts
+                .headers
+                .get_all(name)
+                .iter()
+                .map(|value| String::from_utf8_lossy(value.as_bytes()).into_owned())
+                .collect();
+            let value = redactor
+                .header(name.as_str(), &values.join(", "))
+                .to_string();
+            (name.as_str().to_string(), value)
+        })
+        .collect();
+    let body = (!bytes.is_empty()).then(|| {
+        serde_json::from_slice(&bytes).map_or_else(
+            |_| Value::String(String::from_utf8_lossy(&bytes).into_owned()),
+            |json| redactor.value(&json),
+        )
+    });
+    let mut recorded = RecordedRequest {
+        method: parts.method.to_string(),
+        path: parts.uri.path().to_string(),
+        query: parts.uri.query().map(str::to_string),
+        headers,
+        body,
+        status: 0,
+    };
+
+    let response = next.run(Request::from_parts(parts, forwarded)).await;
+    recorded.status = response.status().as_u16();
+    state.recorder().record(recorded);
+
+    response
+}
+
+/// Returns whether reading a body failed because it exceeded the read limit.
+fn is_length_limit(error: &axum::Error) -> bool {
+    let mut source: Option<&(dyn std::error::Error + 'static)> = Some(error);
+    while let Some(error) = source {
+        if error.is::<LengthLimitError>() {
+            return true;
+        }
+        source = error.source();
+    }
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Builds a recorded GET request to the given path.
+    fn recorded(path: &str) -> RecordedRequest {
+        RecordedRequest {
+            method: "GET".to_string(),
+            path: path.to_string(),
+            query: None,
+            headers: BTreeMap::new(),
+            body: None,
+            status: 200,
+        }
+    }
+
+    /// Tests that the recorder drops its oldest requests once full.
+    #[test]
+    fn test_recorder_is_bounded() {
+        let recorder = RequestRecorder::new(2);
+        for path in ["/a", "/b", "/c"] {
+            recorder.record(recorded(path));
+        }
+
+        let paths: Vec<String> = recorder.list().into_iter().map(|r| r.path).collect();
+        assert_eq!(paths, ["/b", "/c"]);
+
+        recorder.clear();
+        assert!(recorder.list().is_empty());
+    }
+}
@@ -0,0 +1,273 @@
+//! Redaction of sensitive values in JSON Echo logs.
+//!
+//! This module masks credentials and other secrets before requests are
+//! written to the debug logs, so running with `RUST_LOG=debug` does not leak
+//! tokens into terminals, CI output or shared log files.
+//!
+//! ## What
+//!
+//! The module defines:
+//! - `REDACTED`: The replacement written instead of a sensitive value
+//! - `DEFAULT_REDACT_HEADERS`: The headers masked when `redact_headers` is not configured
+//! - `Redactor`: The configured header and field names, and the masking functions
+//!
+//! ## How
+//!
+//! Redaction works by:
+//! 1. Lowercasing the configured header names once, when the server starts
+//! 2. Replacing the value of every matching header, whatever its case, with `***`
+//! 3. Walking JSON payloads and replacing the value of every matching field at any depth
+//! 4. Cutting logged bodies at the `--log-body-limit`, after their fields are masked
+//!
+//! ## Why
+//!
+//! Header names are case-insensitive in HTTP, so `authorization` must match
+//! `Authorization`. Field names are matched exactly, as JSON keys are.
+//! Credential headers are masked by default because forgetting to configure
+//! redaction is the common case; configuring `redact_headers` replaces the
+//! default list.
+//!
+//! # Examples
+//!
+//! ```json
+//! {
+//!   "redact_headers": ["authorization", "x-api-key"],
+//!   "redact_fields": ["password", "token"]
+//! }
+//! ```
+
+use axum::http::HeaderMap;
+use serde_json::Value;
+
+/// The number of bytes of a body logged by `--log-bodies` unless `--log-body-limit` is given.
+pub const DEFAULT_LOG_BODY_LIMIT: usize = 1024;
+
+/// The replacement written instead of a sensitive value.
+pub const REDACTED: &str = "***";
+
+/// The headers masked when `redact_headers` is not configured.
+pub const DEFAULT_REDACT_HEADERS: [&str; 4] = [
+    "authorization",
+    "proxy-authorization",
+    "cookie",
+    "set-cookie",
+];
+
+/// The header and field names whose values are masked in logs.
+///
+/// # Fields
+///
+/// * `headers` - The lowercased header names to mask
+/// * `fields` - The body field names to mask
+///
+/// # Examples
+///
+/// ```rust
+/// let redactor = Redactor::new(None, Some(&["password".to_string()]));
+///
+/// assert_eq!(redactor.header("Authorization", "Bearer abc"), REDACTED);
+/// assert_eq!(
+///     redactor.value(&json!({"user": "ada", "password": "secret"})),
+///     json!({"user": "ada", "password": "***"})
+/// );
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Redactor {
+    /// The lowercased header names to mask
+    headers: Vec<String>,
+    /// The body field names to mask
+    fields: Vec<String>,
+}
+
+impl Default for Redactor {
+    fn default() -> Self {
+        Self::new(None, None)
+    }
+}
+
+impl Redactor {
+    /// Creates a redactor from the configured names.
+    ///
+    /// # Parameters
+    ///
+    /// * `headers` - The configured `redact_headers`, or `None` for the default credential headers
+    /// * `fields` - The configured `redact_fields`, if any
+    ///
+    /// # Returns
+    ///
+    /// A redactor masking the given headers and fields
+    pub fn new(headers: Option<&[String]>, fields: Option<&[String]>) -> Self {
+        let headers = match headers {
+            Some(headers) => headers
+                .iter()
+                .map(|name| name.to_ascii_lowercase())
+                .collect(),
+            None => DEFAULT_REDACT_HEADERS.map(str::to_string).to_vec(),
+        };
+
+        Self {
+            headers,
+            fields: fields.map(<[String]>::to_vec).unwrap_or_default(),
+        }
+    }
+
+    /// Returns the value to log for a header.
+    ///
+    /// # Parameters
+    ///
+    /// * `name` - The header name, in any case
+    /// * `value` - The header value
+    ///
+    /// # Returns
+    ///
+    /// `***` when the header is redacted, otherwise the value itself
+    pub fn header<'a>(&self, name: &str, value: &'a str) -> &'a str {
+        if self
+            .headers
+            .iter()
+            .any(|redacted| redacted.eq_ignore_ascii_case(name))
+        {
+            REDACTED
+        } else {
+            value
+        }
+    }
+
+    /// Returns the headers of a request or response, ready to be logged.
+    ///
+    /// # Parameters
+    ///
+    /// * `headers` - The headers to log
+    ///
+    /// # Returns
+    ///
+    /// The `(name, value)` pairs in order, redacted values replaced with `***`
+    /// and non-text values shown as `<binary>`
+    pub fn headers(&self, headers: &HeaderMap) -> Vec<(String, String)> {
+        headers
+            .iter()
+            .map(|(name, value)| {
+                let value = value.to_str().unwrap_or("<binary>");
+                (
+                    name.as_str().to_string(),
+                    self.header(name.as_str(), value).to_string(),
+                )
+            })
+            .collect()
+    }
+
+    /// Returns a copy of a JSON value with every redacted field masked.
+    ///
+    /// # Parameters
+    ///
+    /// * `value` - The payload to log
+    ///
+    /// # Returns
+    ///
+    /// The payload with the value of every redacted field, at any depth, replaced with `***`
+    pub fn value(&self, value: &Value) -> Value {
+        match value {
+            Value::Object(map) => Value::Object(
+                map.iter()
+                    .map(|(key, value)| {
+                        let value = if self.fields.contains(key) {
+                            Value::String(REDACTED.to_string())
+                        } else {
+                            self.value(value)
+                        };
+                        (key.clone(), value)
+                    })
+                    .collect(),
+            ),
+            Value::Array(items) => items.iter().map(|item| self.value(item)).collect(),
+            other => other.clone(),
+        }
+    }
+
+    /// Returns a request or response body, ready to be logged.
+    ///
+    /// JSON bodies are logged with their redacted fields masked; any other
+    /// body is logged as text, invalid UTF-8 replaced.
+    ///
+    /// # Parameters
+    ///
+    /// * `bytes` - The body to log
+    /// * `limit` - The maximum number of bytes logged
+    ///
+    /// # Returns
+    ///
+    /// The body, cut at the last character boundary within `limit` and followed
+    /// by its full size when it is longer
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// let redactor = Redactor::default();
+    ///
+    /// assert_eq!(redactor.body(b"hello", 16), "hello");
+    /// assert_eq!(redactor.body(b"hello world", 5), "hello… (11 bytes)");
+    /// ```
+    pub fn body(&self, bytes: &[u8], limit: usize) -> String {
+        let text = match serde_json::from_slice::<Value>(bytes) {
+            Ok(json) => self.value(&json).to_string(),
+            Err(_) => String::from_utf8_lossy(bytes).into_owned(),
+        };
+
+        if text.len() <= limit {
+            return text;
+        }
+
+        let mut end = limit;
+        while !text.is_char_boundary(end) {
+            end -= 1;
+        }
+        format!("{}… ({} bytes)", &text[..end], text.len())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::http::HeaderValue;
+    use serde_json::json;
+
+    /// Tests header and field redaction.
+    ///
+    /// Verifies the default credential headers, case-insensitive matching of
+    /// configured headers and masking of nested fields.
+    #[test]
+    fn test_redactor() {
+        let redactor = Redactor::default();
+        assert_eq!(redactor.header("AUTHORIZATION", "Bearer abc"), REDACTED);
+        assert_eq!(redactor.header("x-api-key", "abc"), "abc");
+
+        let redactor = Redactor::new(
+            Some(&["X-Api-Key".to_string()]),
+            Some(&["password".to_string()]),
+        );
+        assert_eq!(redactor.header("x-api-key", "abc"), REDACTED);
+        assert_eq!(redactor.header("authorization", "Bearer abc"), "Bearer abc");
+
+        let mut headers = HeaderMap::new();
+        headers.insert("x-api-key", HeaderValue::from_static("abc"));
+        headers.insert("accept", HeaderValue::from_static("*/*"));
+        assert_eq!(
+            redactor.headers(&headers),
+            [
+                ("x-api-key".to_string(), REDACTED.to_string()),
+                ("accept".to_string(), "*/*".to_string())
+            ]
+        );
+
+        assert_eq!(
+            redactor.value(&json!({"users": [{"name": "ada", "password": "secret"}]})),
+            json!({"users": [{"name": "ada", "password": "***"}]})
+        );
+
+        assert_eq!(
+            redactor.body(br#"{"password": "secret"}"#, 64),
+            r#"{"password":"***"}"#
+        );
+        assert_eq!(redactor.body("héllo".as_bytes(), 2), "h… (6 bytes)");
+    }
+}
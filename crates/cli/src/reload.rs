@@ -0,0 +1,315 @@
+//! Configuration reloading for a running JSON Echo server.
+//!
+//! This module re-reads the configuration file of a running server and swaps
+//! the freshly populated database in, so fixture changes can be picked up
+//! without restarting the process.
+//!
+//! ## What
+//!
+//! The module defines:
+//! - `reload_database`: Reloads the configuration and swaps the database in
+//! - `spawn_sighup_reload`: Reloads on every `SIGHUP` received (Unix only)
+//!
+//! ## How
+//!
+//! A reload works by:
+//! 1. Loading the configuration file into a copy of the configuration manager
+//! 2. Keeping only the routes selected by the `serve --tags` filter, if any
+//! 3. Populating a fresh database from the loaded routes
+//! 4. Atomically replacing the database held by the application state, whose
+//!    call counters and jobs are rebuilt from the new routes
+//!
+//! ## Why
+//!
+//! Loading into a copy keeps the running server untouched when the new
+//! configuration is invalid: the error is reported and the previous data keeps
+//! being served. Routes are registered when the router is built, so a reload
+//! refreshes the data of existing routes but does not add or remove paths.
+//!
+//! # Examples
+//!
+//! ```bash
+//! json-echo serve &
+//! kill -HUP $!
+//! ```
+
+use crate::server::AppState;
+use json_echo_core::{ConfigManager, Database, FileSystemResult, TagFilter};
+
+/// Reloads the configuration file and swaps a freshly populated database in.
+///
+/// # Parameters
+///
+/// * `state` - The shared application state whose database is replaced
+/// * `config_manager` - The configuration manager used for the initial load
+/// * `config_file` - The configuration file, relative to the manager's root
+/// * `filter` - The tag filter selecting the served routes, if any
+///
+/// # Returns
+///
+/// * `Ok(usize)` - The number of routes loaded
+/// * `Err(FileSystemError)` - If the configuration cannot be loaded; the
+///   current database is left untouched
+pub async fn reload_database(
+    state: &AppState,
+    config_manager: &ConfigManager,
+    config_file: &str,
+    filter: Option<&TagFilter>,
+) -> FileSystemResult<usize> {
+    let mut config_manager = config_manager.clone();
+    config_manager.load_config(config_file).await?;
+
+    if let Some(filter) = filter {
+        filter.retain(&mut config_manager.config);
+    }
+
+    let mut db = Database::new();
+    db.populate(config_manager.config.routes);
+    let routes = db.get_routes().len();
+
+    state.replace(db);
+
+    Ok(routes)
+}
+
+#[cfg(unix)]
+pub use unix::spawn_sighup_reload;
+
+#[cfg(unix)]
+mod unix {
+    use super::reload_database;
+    use crate::server::AppState;
+    use json_echo_core::{ConfigManager, FileSystemError, FileSystemResult, TagFilter};
+    use std::sync::Arc;
+    use tokio::{
+        signal::unix::{SignalKind, signal},
+        task::JoinHandle,
+    };
+    use tracing::{error, info};
+
+    /// Reloads the configuration every time the process receives `SIGHUP`.
+    ///
+    /// The signal handler is installed before this function returns, so a
+    /// `SIGHUP` sent afterwards never falls back to the default action of
+    /// terminating the process.
+    ///
+    /// # Parameters
+    ///
+    /// * `state` - The shared application state whose database is replaced
+    /// * `config_manager` - The configuration manager used for the initial load
+    /// * `config_file` - The configuration file, relative to the manager's root
+    /// * `filter` - The tag filter selecting the served routes, if any
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(JoinHandle)` - The handle of the background reload task
+    /// * `Err(FileSystemError)` - If the signal handler cannot be installed
+    pub fn spawn_sighup_reload(
+        state: Arc<AppState>,
+        config_manager: ConfigManager,
+        config_file: String,
+        filter: Option<TagFilter>,
+    ) -> FileSystemResult<JoinHandle<()>> {
+        let mut hangup = signal(SignalKind::hangup()).map_err(|error| {
+            FileSystemError::Operation(format!("Failed to install SIGHUP handler: {error}"))
+        })?;
+
+        Ok(tokio::spawn(async move {
+            while hangup.recv().await.is_some() {
+                info!("SIGHUP received, reloading {}", config_file);
+
+                let reloaded =
+                    reload_database(&state, &config_manager, &config_file, filter.as_ref()).await;
+                match reloaded {
+                    Ok(routes) => info!("✔︎ Configuration reloaded with {} routes", routes),
+                    Err(e) => error!("⚠︎ Configuration reload failed: {}", e),
+                }
+            }
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use json_echo_core::FileSystemManager;
+    use serde_json::{Value, json};
+    use std::path::Path;
+
+    /// Writes a single-route configuration serving the given body.
+    fn write_config(dir: &Path, body: &Value) {
+        let config = json!({
+            "routes": {"/api/items": {"response": {"status": 200, "body": body}}}
+        });
+        std::fs::write(dir.join("json-echo.json"), config.to_string())
+            .expect("Failed to write test config");
+    }
+
+    /// Returns the data currently served for the test route.
+    fn served(state: &AppState) -> Value {
+        state
+            .read()
+            .get_model("[GET] /api/items")
+            .map(|model| model.get_value().into_owned())
+            .unwrap_or_default()
+    }
+
+    /// Loads the test configuration into a new application state.
+    async fn load_state(dir: &Path) -> (AppState, ConfigManager) {
+        let file_system_manager = FileSystemManager::new(Some(dir.to_path_buf()))
+            .expect("Failed to create filesystem manager");
+        let mut config_manager = ConfigManager::new(file_system_manager);
+        config_manager
+            .load_config("json-echo.json")
+            .await
+            .expect("Failed to load test config");
+
+        let mut db = Database::new();
+        db.populate(config_manager.config.routes.clone());
+        (AppState::new(db), config_manager)
+    }
+
+    /// Tests that a reload picks up changed fixtures and survives invalid ones.
+    ///
+    /// Verifies that new data is swapped in and that a broken configuration
+    /// leaves the previously served data in place.
+    #[tokio::test]
+    async fn test_reload_database() {
+        let dir = tempfile::tempdir().expect("Failed to create temporary directory");
+        write_config(dir.path(), &json!([1]));
+        let (state, config_manager) = load_state(dir.path()).await;
+
+        write_config(dir.path(), &json!([1, 2]));
+        let routes = reload_database(&state, &config_manager, "json-echo.json", None)
+            .await
+            .expect("Reload should succeed");
+        assert_eq!(routes, 1);
+        assert_eq!(served(&state), json!([1, 2]));
+
+        std::fs::write(dir.path().join("json-echo.json"), "{ broken")
+            .expect("Failed to write test config");
+        assert!(
+            reload_database(&state, &config_manager, "json-echo.json", None)
+                .await
+                .is_err()
+        );
+        assert_eq!(served(&state), json!([1, 2]), "Old data should be kept");
+    }
+
+    /// Tests that a reload rebuilds the call counters from the new routes.
+    ///
+    /// Verifies that a route added by the reload counts its calls, so its
+    /// sequence advances, that a route kept by the reload keeps its count, and
+    /// that a removed route stops being counted.
+    #[tokio::test]
+    async fn test_reload_rebuilds_call_counters() {
+        let dir = tempfile::tempdir().expect("Failed to create temporary directory");
+        write_config(dir.path(), &json!([1]));
+        let (state, config_manager) = load_state(dir.path()).await;
+        assert_eq!(state.next_call("[GET] /api/items"), 0);
+        assert_eq!(state.next_call("[GET] /api/flaky"), 0);
+
+        let sequence = json!([{"status": 503}, {"status": 200}]);
+        let config = json!({"routes": {
+            "/api/items": {"response": {"status": 200, "body": [1]}},
+            "/api/flaky": {"sequence": sequence}
+        }});
+        std::fs::write(dir.path().join("json-echo.json"), config.to_string())
+            .expect("Failed to write test config");
+        reload_database(&state, &config_manager, "json-echo.json", None)
+            .await
+            .expect("Reload should succeed");
+        assert_eq!(state.next_call("[GET] /api/items"), 1);
+        assert_eq!(state.next_call("[GET] /api/flaky"), 0);
+        assert_eq!(state.next_call("[GET] /api/flaky"), 1);
+
+        write_config(dir.path(), &json!([1]));
+        reload_database(&state, &config_manager, "json-echo.json", None)
+            .await
+            .expect("Reload should succeed");
+        assert_eq!(state.next_call("[GET] /api/flaky"), 0);
+        assert_eq!(state.next_call("[GET] /api/flaky"), 0);
+    }
+
+    /// Tests that a reload rebuilds the jobs from the new routes.
+    ///
+    /// Verifies that a job added by the reload can be started and polled,
+    /// that a later reload keeping it keeps its polls, and that removing it
+    /// stops its status route from reporting jobs.
+    #[tokio::test]
+    async fn test_reload_rebuilds_jobs() {
+        let write_jobs = |dir: &Path, job: Option<Value>| {
+            let mut export = json!({"response": {"status": 200, "body": {}}});
+            if let Some(job) = job {
+                export["job"] = job;
+            }
+            let config = json!({"routes": {
+                "[POST] /api/exports": export,
+                "/api/jobs/{id}": {"response": {"status": 200, "body": {}}}
+            }});
+            std::fs::write(dir.join("json-echo.json"), config.to_string())
+                .expect("Failed to write test config");
+        };
+        let dir = tempfile::tempdir().expect("Failed to create temporary directory");
+        write_jobs(dir.path(), None);
+        let (state, config_manager) = load_state(dir.path()).await;
+        assert!(!state.is_job_route("/api/jobs/{id}"));
+
+        let job = json!({"location": "/api/jobs/{id}"});
+        write_jobs(dir.path(), Some(job));
+        reload_database(&state, &config_manager, "json-echo.json", None)
+            .await
+            .expect("Reload should succeed");
+        let id = state.start_job("/api/jobs/{id}");
+        assert_eq!(state.next_job_poll("/api/jobs/{id}", &id), Some(0));
+
+        reload_database(&state, &config_manager, "json-echo.json", None)
+            .await
+            .expect("Reload should succeed");
+        assert_eq!(state.next_job_poll("/api/jobs/{id}", &id), Some(1));
+
+        write_jobs(dir.path(), None);
+        reload_database(&state, &config_manager, "json-echo.json", None)
+            .await
+            .expect("Reload should succeed");
+        assert!(!state.is_job_route("/api/jobs/{id}"));
+        assert_eq!(state.next_job_poll("/api/jobs/{id}", &id), None);
+    }
+
+    /// Tests that sending SIGHUP to the process reloads changed fixtures.
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_sighup_triggers_reload() {
+        use std::{sync::Arc, time::Duration};
+
+        let dir = tempfile::tempdir().expect("Failed to create temporary directory");
+        write_config(dir.path(), &json!({"version": 1}));
+        let (state, config_manager) = load_state(dir.path()).await;
+        let state = Arc::new(state);
+
+        let handle = spawn_sighup_reload(
+            Arc::clone(&state),
+            config_manager,
+            "json-echo.json".to_string(),
+            None,
+        )
+        .expect("SIGHUP handler should install");
+
+        write_config(dir.path(), &json!({"version": 2}));
+        let status = std::process::Command::new("kill")
+            .args(["-HUP", &std::process::id().to_string()])
+            .status()
+            .expect("Failed to send SIGHUP");
+        assert!(status.success());
+
+        let reloaded = tokio::time::timeout(Duration::from_secs(5), async {
+            while served(&state) != json!({"version": 2}) {
+                tokio::time::sleep(Duration::from_millis(10)).await;
+            }
+        })
+        .await;
+
+        handle.abort();
+        assert!(reloaded.is_ok(), "SIGHUP should reload the configuration");
+    }
+}
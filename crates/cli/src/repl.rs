@@ -0,0 +1,347 @@
+//! Interactive administration console for a running JSON Echo server.
+//!
+//! This module provides a small line-based REPL that runs alongside the HTTP
+//! server and operates on the same shared database. It is intended for
+//! debugging: inspecting what a route currently serves and forcing models
+//! into a known state without restarting the server.
+//!
+//! ## What
+//!
+//! The module defines:
+//! - `ReplCommand`: The set of commands understood by the console
+//! - `parse_command`: Parses a single input line into a `ReplCommand`
+//! - `execute_command`: Runs a command against the shared application state
+//! - `spawn_repl`: Starts the blocking stdin loop on a background thread
+//!
+//! ## How
+//!
+//! The console works by:
+//! 1. Reading lines from standard input on a blocking task
+//! 2. Parsing each line into a command, independently of any IO
+//! 3. Executing the command against the current or an updated database snapshot
+//! 4. Printing the textual result to standard output
+//!
+//! ## Why
+//!
+//! Keeping parsing and execution free of IO allows the command handling to be
+//! tested directly, while the loop itself stays a thin adapter over stdin.
+//!
+//! # Examples
+//!
+//! ```text
+//! > list
+//! [GET] /api/users
+//! > get [GET] /api/users
+//! [{"id": 1, "name": "John"}]
+//! > set /api/users [{"id": 2, "name": "Jane"}]
+//! ✔︎ Model data replaced: [GET] /api/users
+//! > reset
+//! ✔︎ Database reset to configured state
+//! ```
+
+use crate::server::AppState;
+use indexmap::IndexMap;
+use json_echo_core::{ConfigRoute, Database};
+use serde_json::Value;
+use std::{
+    io::{BufRead, stdin},
+    sync::Arc,
+};
+use tokio::task::JoinHandle;
+use tracing::info;
+
+/// Commands understood by the interactive console.
+///
+/// # Variants
+///
+/// * `List` - Lists every route identifier registered in the database
+/// * `Get` - Prints the data currently served by a model
+/// * `Set` - Replaces the data served by a model with the given JSON
+/// * `Reset` - Restores every model to the state loaded from configuration
+/// * `Help` - Prints the list of available commands
+#[derive(Debug, Clone, PartialEq)]
+pub enum ReplCommand {
+    /// List every route identifier
+    List,
+    /// Print the data of the model with the given identifier
+    Get(String),
+    /// Replace the data of the model with the given identifier
+    Set(String, Value),
+    /// Restore the database to its configured state
+    Reset,
+    /// Print the available commands
+    Help,
+}
+
+/// Usage text printed by the `help` command and on parse errors.
+const USAGE: &str = "Commands: list | get <identifier> | set <identifier> <json> | reset | help";
+
+/// Parses a single console line into a `ReplCommand`.
+///
+/// Identifiers can be given either in the bracketed `[METHOD] /path` form or as
+/// a plain path, in which case the `GET` method is assumed.
+///
+/// # Parameters
+///
+/// * `line` - The raw input line
+///
+/// # Returns
+///
+/// * `Ok(ReplCommand)` - The parsed command
+/// * `Err(String)` - A message describing why the line could not be parsed
+///
+/// # Examples
+///
+/// ```rust
+/// assert_eq!(parse_command("list"), Ok(ReplCommand::List));
+/// assert_eq!(
+///     parse_command("get /users"),
+///     Ok(ReplCommand::Get("[GET] /users".to_string()))
+/// );
+/// ```
+pub fn parse_command(line: &str) -> Result<ReplCommand, String> {
+    let line = line.trim();
+    let (command, rest) = line.split_once(char::is_whitespace).unwrap_or((line, ""));
+
+    match command.to_lowercase().as_str() {
+        "list" => Ok(ReplCommand::List),
+        "reset" => Ok(ReplCommand::Reset),
+        "help" => Ok(ReplCommand::Help),
+        "get" => {
+            let (identifier, _) = split_identifier(rest)?;
+            Ok(ReplCommand::Get(identifier))
+        }
+        "set" => {
+            let (identifier, json) = split_identifier(rest)?;
+            if json.is_empty() {
+                return Err(format!("Missing JSON value. {USAGE}"));
+            }
+            let value = serde_json::from_str::<Value>(json)
+                .map_err(|error| format!("Invalid JSON value: {error}"))?;
+            Ok(ReplCommand::Set(identifier, value))
+        }
+        "" => Err(USAGE.to_string()),
+        other => Err(format!("Unknown command '{other}'. {USAGE}")),
+    }
+}
+
+/// Splits a route identifier from the beginning of the input.
+///
+/// Returns the normalized `[METHOD] /path` identifier and the remaining input.
+fn split_identifier(input: &str) -> Result<(String, &str), String> {
+    let input = input.trim_start();
+
+    let (method, rest) = if input.starts_with('[') {
+        let end = input
+            .find(']')
+            .ok_or_else(|| format!("Malformed identifier '{input}'"))?;
+        (
+            input[1..end].trim().to_uppercase(),
+            input[end + 1..].trim_start(),
+        )
+    } else {
+        (String::from("GET"), input)
+    };
+
+    let (path, rest) = rest.split_once(char::is_whitespace).unwrap_or((rest, ""));
+
+    if path.is_empty() {
+        return Err(format!("Missing route identifier. {USAGE}"));
+    }
+
+    Ok((format!("[{method}] {path}"), rest.trim()))
+}
+
+/// Executes a console command against the shared application state.
+///
+/// # Parameters
+///
+/// * `command` - The command to execute
+/// * `state` - The shared application state holding the database
+/// * `routes` - The configured routes, used to restore the database on `reset`
+///
+/// # Returns
+///
+/// The textual result of the command, ready to be printed
+pub fn execute_command(
+    command: ReplCommand,
+    state: &AppState,
+    routes: &IndexMap<String, ConfigRoute>,
+) -> String {
+    match command {
+        ReplCommand::List => {
+            let db = state.read();
+            let mut identifiers = db.get_routes();
+            identifiers.sort();
+            identifiers
+                .iter()
+                .map(|identifier| identifier.as_str())
+                .collect::<Vec<_>>()
+                .join("\n")
+        }
+        ReplCommand::Get(identifier) => {
+            let db = state.read();
+            match db.get_model(&identifier) {
+                Some(model) => serde_json::to_string_pretty(&model.get_value())
+                    .unwrap_or_else(|error| format!("⚠︎ Unable to serialize model: {error}")),
+                None => format!("⚠︎ Model not found: {identifier}"),
+            }
+        }
+        ReplCommand::Set(identifier, value) => {
+            match state.update(|db| db.set_model_data(&identifier, value.clone())) {
+                Ok(()) => format!("✔︎ Model data replaced: {identifier}"),
+                Err(error) => format!("⚠︎ {error}"),
+            }
+        }
+        ReplCommand::Reset => {
+            let mut fresh = Database::new();
+            fresh.populate(routes.clone());
+            state.replace(fresh);
+            String::from("✔︎ Database reset to configured state")
+        }
+        ReplCommand::Help => USAGE.to_string(),
+    }
+}
+
+/// Starts the interactive console on a blocking background task.
+///
+/// The task reads standard input line by line until it is closed, executing
+/// each command and printing its result.
+///
+/// # Parameters
+///
+/// * `state` - The shared application state also used by the HTTP server
+/// * `routes` - The configured routes, used to restore the database on `reset`
+///
+/// # Returns
+///
+/// The handle of the spawned blocking task
+#[allow(clippy::print_stdout)]
+pub fn spawn_repl(state: Arc<AppState>, routes: IndexMap<String, ConfigRoute>) -> JoinHandle<()> {
+    info!("Interactive console enabled. {}", USAGE);
+
+    tokio::task::spawn_blocking(move || {
+        for line in stdin().lock().lines() {
+            let Ok(line) = line else {
+                break;
+            };
+
+            if line.trim().is_empty() {
+                continue;
+            }
+
+            let output = match parse_command(&line) {
+                Ok(command) => execute_command(command, &state, &routes),
+                Err(error) => error,
+            };
+
+            println!("{output}");
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    /// Builds the configured routes used by the console tests.
+    fn test_routes() -> IndexMap<String, ConfigRoute> {
+        let route: ConfigRoute = serde_json::from_value(json!({
+            "method": "GET",
+            "response": {"status": 200, "body": [{"id": 1, "name": "John"}]}
+        }))
+        .expect("Failed to build test route");
+
+        IndexMap::from([("[GET] /api/users".to_string(), route)])
+    }
+
+    /// Builds application state populated with the test routes.
+    fn test_state(routes: &IndexMap<String, ConfigRoute>) -> AppState {
+        let mut db = Database::new();
+        db.populate(routes.clone());
+        AppState::new(db)
+    }
+
+    /// Tests parsing of every supported command.
+    ///
+    /// Verifies that identifiers are normalized and JSON payloads are parsed.
+    #[test]
+    fn test_parse_commands() {
+        assert_eq!(parse_command("list"), Ok(ReplCommand::List));
+        assert_eq!(parse_command("  RESET "), Ok(ReplCommand::Reset));
+        assert_eq!(
+            parse_command("get /api/users"),
+            Ok(ReplCommand::Get("[GET] /api/users".to_string()))
+        );
+        assert_eq!(
+            parse_command("get [post] /api/users"),
+            Ok(ReplCommand::Get("[POST] /api/users".to_string()))
+        );
+        assert_eq!(
+            parse_command(r#"set [GET] /api/users [{"id": 2}]"#),
+            Ok(ReplCommand::Set(
+                "[GET] /api/users".to_string(),
+                json!([{"id": 2}])
+            ))
+        );
+    }
+
+    /// Tests parse errors for malformed input.
+    ///
+    /// Verifies that unknown commands, missing identifiers and invalid JSON
+    /// are reported instead of silently ignored.
+    #[test]
+    fn test_parse_command_errors() {
+        assert!(
+            parse_command("drop").is_err(),
+            "Unknown command should fail"
+        );
+        assert!(
+            parse_command("get").is_err(),
+            "Missing identifier should fail"
+        );
+        assert!(
+            parse_command("set /api/users").is_err(),
+            "Missing JSON value should fail"
+        );
+        assert!(
+            parse_command("set /api/users {oops").is_err(),
+            "Invalid JSON should fail"
+        );
+    }
+
+    /// Tests a full list, get, set and reset session.
+    ///
+    /// Verifies that commands operate on the shared database and that reset
+    /// restores the configured data.
+    #[test]
+    fn test_execute_session() {
+        let routes = test_routes();
+        let state = test_state(&routes);
+
+        let run = |line: &str| {
+            execute_command(
+                parse_command(line).expect("Command should parse"),
+                &state,
+                &routes,
+            )
+        };
+
+        assert_eq!(run("list"), "[GET] /api/users");
+        assert!(run("get /api/users").contains("John"));
+
+        assert!(run(r#"set /api/users [{"id": 2, "name": "Jane"}]"#).starts_with('✔'));
+        let after_set = run("get /api/users");
+        assert!(after_set.contains("Jane"), "Set should replace the data");
+        assert!(!after_set.contains("John"), "Set should not merge the data");
+
+        assert!(run("get /missing").contains("not found"));
+
+        run("reset");
+        assert!(
+            run("get /api/users").contains("John"),
+            "Reset should restore configured data"
+        );
+    }
+}
@@ -0,0 +1,107 @@
+//! Request id propagation for JSON Echo.
+//!
+//! This module gives every request an id, taken from the incoming
+//! `X-Request-Id` header or freshly generated, so a request can be followed
+//! from the client through the server logs and back in the response.
+//!
+//! ## What
+//!
+//! The module defines:
+//! - `DEFAULT_REQUEST_ID_HEADER`: The header used when `request_id_header` is not configured
+//! - `RequestId`: The id of the current request, stored in the request extensions
+//! - `propagate_request_id`: Middleware assigning the id and echoing it in the response
+//!
+//! ## How
+//!
+//! Propagation works by:
+//! 1. Reading the configured header from the request, generating a v4 UUID when it is absent
+//! 2. Writing the id back to the request headers and storing it as a `RequestId` extension
+//! 3. Copying it to the response headers, unless the route already set that header
+//!
+//! ## Why
+//!
+//! Writing the id to the request headers lets header templates such as
+//! `{{request.header.X-Request-Id}}` see generated ids too, and the extension
+//! lets inner middleware, like the request logging, attach it to their events
+//! without parsing headers again.
+//!
+//! # Examples
+//!
+//! ```text
+//! GET /api/users  X-Request-Id: abc   -> 200, X-Request-Id: abc
+//! GET /api/users                      -> 200, X-Request-Id: 1b4e28ba-2fa1-4d3b-a3f5-ef19b5a7633b
+//! ```
+
+use crate::server::AppState;
+use axum::{
+    extract::{Request, State},
+    http::HeaderValue,
+    middleware::Next,
+    response::Response,
+};
+use std::sync::Arc;
+use uuid::Uuid;
+
+/// The header used when `request_id_header` is not configured.
+pub const DEFAULT_REQUEST_ID_HEADER: &str = "x-request-id";
+
+/// The id of the current request, stored in the request extensions.
+///
+/// # Examples
+///
+/// ```rust
+/// let id = request.extensions().get::<RequestId>().map(RequestId::as_str);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RequestId(String);
+
+impl RequestId {
+    /// Returns the id as a string slice.
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+/// Middleware assigning every request an id and echoing it in the response.
+///
+/// A non-empty incoming header is kept as is; otherwise a v4 UUID is generated.
+///
+/// # Parameters
+///
+/// * `State(state)` - Shared application state holding the request id header name
+/// * `request` - The incoming request
+/// * `next` - The remaining middleware and the route handler
+///
+/// # Returns
+///
+/// The response of the inner layers, carrying the request id header
+pub async fn propagate_request_id(
+    State(state): State<Arc<AppState>>,
+    mut request: Request,
+    next: Next,
+) -> Response {
+    let header = state.request_id_header().clone();
+
+    let provided = request
+        .headers()
+        .get(&header)
+        .filter(|value| !value.as_bytes().trim_ascii().is_empty())
+        .cloned();
+    let value = match provided {
+        Some(value) => value,
+        None => {
+            let value = HeaderValue::from_str(&Uuid::new_v4().to_string())
+                .unwrap_or_else(|_| HeaderValue::from_static("unknown"));
+            request.headers_mut().insert(header.clone(), value.clone());
+            value
+        }
+    };
+
+    let id = String::from_utf8_lossy(value.as_bytes()).into_owned();
+    request.extensions_mut().insert(RequestId(id));
+
+    let mut response = next.run(request).await;
+    response.headers_mut().entry(header).or_insert(value);
+
+    response
+}
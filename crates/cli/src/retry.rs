@@ -0,0 +1,106 @@
+//! `Retry-After` headers for JSON Echo routes and maintenance mode.
+//!
+//! This module renders a configured `retry_after`, a number of seconds or an
+//! absolute date, into the `Retry-After` header, so clients can be tested
+//! against both forms their backoff logic has to understand.
+//!
+//! ## What
+//!
+//! The module defines:
+//! - `retry_after_value`: Renders a configured wait as a `Retry-After` value
+//! - `retry_after_header`: Middleware adding a route's `retry_after` to its `429` and `503` responses
+//!
+//! ## How
+//!
+//! A wait is rendered by:
+//! 1. Sending a number of seconds as is, e.g. `Retry-After: 120`
+//! 2. Sending a date in the IMF-fixdate form of RFC 9110, e.g. `Retry-After: Wed, 14 Oct 2026 09:30:00 GMT`,
+//!    as `RetryAfter::header_value` rendered it when the configuration loaded
+//!
+//! ## Why
+//!
+//! Dates are always re-rendered, so an RFC 3339 time, easier to write, still
+//! reaches the client as the only date form HTTP allows. The header is only
+//! added to `429 Too Many Requests` and `503 Service Unavailable`, the
+//! statuses clients retry, and a `Retry-After` set through the route's
+//! `headers` or by maintenance mode is kept.
+//!
+//! # Examples
+//!
+//! ```json
+//! {"/api/search": {"retry_after": "2026-10-14T09:30:00Z", "error": {"status": 503}}}
+//! ```
+
+use crate::server::AppState;
+use axum::{
+    extract::{MatchedPath, Request, State},
+    http::{HeaderValue, StatusCode, header::RETRY_AFTER},
+    middleware::Next,
+    response::Response,
+};
+use json_echo_core::RetryAfter;
+use std::sync::Arc;
+
+/// Turns a configured wait into a `Retry-After` header value.
+///
+/// Dates are rendered as HTTP dates when the configuration loads, which
+/// rejects the invalid ones, so they are sent as they are.
+///
+/// # Parameters
+///
+/// * `retry` - The configured `retry_after`
+///
+/// # Returns
+///
+/// The header value, or `None` for a date that is not a valid header value
+///
+/// # Examples
+///
+/// ```rust,ignore
+/// let value = retry_after_value(&RetryAfter::Seconds(120));
+/// assert_eq!(value, Some(HeaderValue::from_static("120")));
+/// ```
+pub fn retry_after_value(retry: &RetryAfter) -> Option<HeaderValue> {
+    match retry {
+        RetryAfter::Seconds(seconds) => Some(HeaderValue::from(*seconds)),
+        RetryAfter::Date(date) => HeaderValue::from_str(date).ok(),
+    }
+}
+
+/// Middleware adding a route's `retry_after` to its `429` and `503` responses.
+///
+/// # Parameters
+///
+/// * `State(state)` - Shared application state containing the route configurations
+/// * `request` - The incoming request, matched against a configured route
+/// * `next` - The remaining middleware and the route handler
+///
+/// # Returns
+///
+/// The response of the inner layers, carrying `Retry-After` when it is a
+/// `429` or `503` of a route with a `retry_after` and has none yet
+pub async fn retry_after_header(
+    State(state): State<Arc<AppState>>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let retry = request.extensions().get::<MatchedPath>().and_then(|path| {
+        state
+            .read()
+            .get_route(path.as_str(), Some(request.method().to_string()))
+            .and_then(|route| route.retry_after.clone())
+    });
+
+    let mut response = next.run(request).await;
+
+    let status = response.status();
+    if status != StatusCode::TOO_MANY_REQUESTS && status != StatusCode::SERVICE_UNAVAILABLE {
+        return response;
+    }
+
+    if let Some(value) = retry.as_ref().and_then(retry_after_value) {
+        response.headers_mut().entry(RETRY_AFTER).or_insert(value);
+    }
+
+    response
+}
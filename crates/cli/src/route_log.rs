@@ -0,0 +1,206 @@
+//! Per-route logging verbosity for JSON Echo.
+//!
+//! This module lets a noisy route be quietened, or silenced with `"off"`,
+//! through its `log_level`, while every other route keeps logging at the
+//! global level set with `--log-level`.
+//!
+//! ## What
+//!
+//! The module defines:
+//! - `route_log_level`: Looks up the `log_level` of the route a request was matched to
+//! - `RouteLogs`: The request log's state, with one capped subscriber per level
+//!
+//! ## How
+//!
+//! Per-route levels work by:
+//! 1. Wrapping the current subscriber, once per level when the router is
+//!    built, in one that drops the events and spans more verbose than the level
+//! 2. Reading the route a request was matched to from its `MatchedPath`, so
+//!    method override, case folding and slash trimming have already applied
+//! 3. Running the request logging, the route layers and the handler under the
+//!    subscriber of the route's level
+//!
+//! ## Why
+//!
+//! The request logging layer runs inside the router, after routing, so the
+//! route is the one the router picked rather than a second guess. Its level
+//! is read from the database on every request, so reloaded levels apply to
+//! the next one. The capped subscribers are built up front: building a
+//! dispatcher for every request would make tracing rebuild its callsite
+//! interest each time. The level only ever lowers the verbosity: events the
+//! global filter drops are never let through.
+//!
+//! # Examples
+//!
+//! ```json
+//! {"/api/health": {"log_level": "off", "response": {"status": 200, "body": {"ok": true}}}}
+//! ```
+
+use crate::server::AppState;
+use axum::{
+    extract::{MatchedPath, Request},
+    http::Method,
+};
+use json_echo_core::{Database, LogLevel};
+use std::sync::Arc;
+use tracing::{
+    Dispatch, Event, Metadata, Subscriber,
+    level_filters::LevelFilter,
+    span::{Attributes, Id, Record},
+    subscriber::Interest,
+};
+
+/// Looks up the `log_level` of the route a request was matched to.
+///
+/// HEAD requests are looked up as GET, since the GET route answers them.
+///
+/// # Parameters
+///
+/// * `db` - The database holding the configured routes
+/// * `request` - The request, after routing
+///
+/// # Returns
+///
+/// The level of the route of the request's `MatchedPath` and method, or
+/// `None` if that route sets no level or the request matched no route
+pub fn route_log_level(db: &Database, request: &Request) -> Option<LogLevel> {
+    let path = request.extensions().get::<MatchedPath>()?;
+    let method = if request.method() == Method::HEAD {
+        Method::GET
+    } else {
+        request.method().clone()
+    };
+
+    db.get_route(path.as_str(), Some(method.to_string()))?
+        .log_level
+}
+
+/// The levels a route can cap its logging at, in the order of `RouteLogs::dispatches`.
+const LEVELS: [LogLevel; 6] = [
+    LogLevel::Off,
+    LogLevel::Error,
+    LogLevel::Warn,
+    LogLevel::Info,
+    LogLevel::Debug,
+    LogLevel::Trace,
+];
+
+/// The state of the request log, with the subscribers routes are capped with.
+///
+/// # Fields
+///
+/// * `state` - Shared application state holding the routes and the redactor
+/// * `dispatches` - The current subscriber capped at each of `LEVELS`, in order
+pub struct RouteLogs {
+    /// Shared application state holding the routes and the redactor
+    pub state: Arc<AppState>,
+    /// The current subscriber capped at each of `LEVELS`, in order
+    dispatches: [Dispatch; LEVELS.len()],
+}
+
+impl RouteLogs {
+    /// Wraps the current subscriber once for every level.
+    ///
+    /// # Parameters
+    ///
+    /// * `state` - Shared application state holding the routes
+    ///
+    /// # Returns
+    ///
+    /// The request log's state, forwarding to the subscriber current when it was built
+    pub fn new(state: Arc<AppState>) -> Self {
+        let inner = tracing::dispatcher::get_default(Dispatch::clone);
+        let dispatches = LEVELS.map(|level| {
+            Dispatch::new(LevelCapped {
+                inner: inner.clone(),
+                max: level_filter(level),
+            })
+        });
+
+        Self { state, dispatches }
+    }
+
+    /// Returns the subscriber dropping events more verbose than `level`.
+    ///
+    /// # Returns
+    ///
+    /// A dispatcher forwarding the events and spans at `level` or below to the
+    /// wrapped subscriber, which still applies its own filter to them
+    pub fn dispatch(&self, level: LogLevel) -> &Dispatch {
+        let index = LEVELS
+            .iter()
+            .position(|candidate| *candidate == level)
+            .unwrap_or_default();
+        &self.dispatches[index]
+    }
+}
+
+/// Returns the tracing filter letting through `level` and less verbose events.
+fn level_filter(level: LogLevel) -> LevelFilter {
+    match level {
+        LogLevel::Off => LevelFilter::OFF,
+        LogLevel::Error => LevelFilter::ERROR,
+        LogLevel::Warn => LevelFilter::WARN,
+        LogLevel::Info => LevelFilter::INFO,
+        LogLevel::Debug => LevelFilter::DEBUG,
+        LogLevel::Trace => LevelFilter::TRACE,
+    }
+}
+
+/// A subscriber forwarding to another one only what is at most as verbose as `max`.
+struct LevelCapped {
+    /// The subscriber the allowed events and spans are forwarded to
+    inner: Dispatch,
+    /// The most verbose level forwarded
+    max: LevelFilter,
+}
+
+impl Subscriber for LevelCapped {
+    fn register_callsite(&self, metadata: &'static Metadata<'static>) -> Interest {
+        if self.max >= *metadata.level() {
+            self.inner.register_callsite(metadata)
+        } else {
+            Interest::never()
+        }
+    }
+
+    fn max_level_hint(&self) -> Option<LevelFilter> {
+        Some(self.max)
+    }
+
+    fn enabled(&self, metadata: &Metadata<'_>) -> bool {
+        self.max >= *metadata.level() && self.inner.enabled(metadata)
+    }
+
+    fn new_span(&self, span: &Attributes<'_>) -> Id {
+        self.inner.new_span(span)
+    }
+
+    fn record(&self, span: &Id, values: &Record<'_>) {
+        self.inner.record(span, values);
+    }
+
+    fn record_follows_from(&self, span: &Id, follows: &Id) {
+        self.inner.record_follows_from(span, follows);
+    }
+
+    fn event(&self, event: &Event<'_>) {
+        self.inner.event(event);
+    }
+
+    fn enter(&self, span: &Id) {
+        self.inner.enter(span);
+    }
+
+    fn exit(&self, span: &Id) {
+        self.inner.exit(span);
+    }
+
+    fn clone_span(&self, id: &Id) -> Id {
+        self.inner.clone_span(id)
+    }
+
+    fn try_close(&self, id: Id) -> bool {
+        self.inner.try_close(id)
+    }
+}
@@ -0,0 +1,346 @@
+//! In-process self-test of every GET route of a configuration.
+//!
+//! This module sends one request to each GET route through the application
+//! router, without opening a socket, and reports the routes that fail to
+//! serve, so CI can catch fixtures that crash a handler before anyone calls
+//! the mock.
+//!
+//! ## What
+//!
+//! The module defines:
+//! - `SelftestFailure`: A route that failed, with the path requested and why
+//! - `SelftestReport`: The routes checked, skipped and failed in a run
+//! - `run_selftest`: Requests every GET route once and checks the responses
+//!
+//! ## How
+//!
+//! A run works by:
+//! 1. Filling the path parameters of each route, from its first entry when its
+//!    inline body is a collection and with `1` otherwise
+//! 2. Dispatching the request through `tower::ServiceExt::oneshot` on its own
+//!    task, so a panicking handler is reported instead of ending the run
+//! 3. Reading the full body and parsing it when the response declares JSON
+//! 4. Failing the route on a panic, an unreadable or malformed body, or a `5xx`
+//!    status the route is not configured to answer with
+//!
+//! ## Why
+//!
+//! Routes configured to answer a `5xx`, as a response, an `error` or one of
+//! their alternatives, are mocking a failure and pass when they do. Disabled
+//! routes are not served, and proxied routes would reach their upstream, so
+//! both are skipped.
+//!
+//! # Examples
+//!
+//! ```bash
+//! json-echo --config db.json selftest
+//! ```
+
+use axum::{
+    Router,
+    body::{Body, to_bytes},
+    http::{Request, header::CONTENT_TYPE},
+};
+use indexmap::IndexMap;
+use json_echo_core::{ConfigResponse, ConfigRoute, PathSegment, path_segments};
+use serde_json::Value;
+use std::fmt;
+use tower::ServiceExt;
+
+/// A route that failed the self-test.
+///
+/// # Fields
+///
+/// * `route` - The normalized `[GET] path` identifier of the route
+/// * `uri` - The URI that was requested
+/// * `reason` - Why the route failed
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SelftestFailure {
+    /// The normalized `[GET] path` identifier of the route
+    pub route: String,
+    /// The URI that was requested
+    pub uri: String,
+    /// Why the route failed
+    pub reason: String,
+}
+
+/// The outcome of a self-test run.
+///
+/// # Fields
+///
+/// * `checked` - The number of routes requested
+/// * `skipped` - The routes not requested, disabled or proxied
+/// * `failures` - The routes that failed
+#[derive(Debug, Clone, Default)]
+pub struct SelftestReport {
+    /// The number of routes requested
+    pub checked: usize,
+    /// The routes not requested, disabled or proxied
+    pub skipped: Vec<String>,
+    /// The routes that failed
+    pub failures: Vec<SelftestFailure>,
+}
+
+impl fmt::Display for SelftestReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for failure in &self.failures {
+            writeln!(
+                f,
+                "✗ {} ({}): {}",
+                failure.route, failure.uri, failure.reason
+            )?;
+        }
+        for route in &self.skipped {
+            writeln!(f, "- {route}: skipped")?;
+        }
+        writeln!(
+            f,
+            "Checked {} GET routes, {} failed, {} skipped",
+            self.checked,
+            self.failures.len(),
+            self.skipped.len()
+        )
+    }
+}
+
+/// Requests every GET route once through the router and checks the responses.
+///
+/// # Parameters
+///
+/// * `router` - The fully configured application router
+/// * `routes` - The loaded routes, keyed by their normalized `[METHOD] path` identifier
+///
+/// # Returns
+///
+/// The routes checked, skipped and failed, in configuration order
+///
+/// # Examples
+///
+/// ```rust
+/// let report = run_selftest(router, &config_manager.config.routes).await;
+/// assert!(report.failures.is_empty());
+/// ```
+pub async fn run_selftest(
+    router: Router,
+    routes: &IndexMap<String, ConfigRoute>,
+) -> SelftestReport {
+    let mut report = SelftestReport::default();
+
+    for (key, route) in routes {
+        let Some(path) = key.strip_prefix("[GET] ") else {
+            continue;
+        };
+        if !route.is_enabled() || route.proxy.is_some() {
+            report.skipped.push(key.clone());
+            continue;
+        }
+
+        report.checked += 1;
+        let uri = sample_uri(path, route);
+        if let Err(reason) = check_route(router.clone(), &uri, route).await {
+            report.failures.push(SelftestFailure {
+                route: key.clone(),
+                uri,
+                reason,
+            });
+        }
+    }
+
+    report
+}
+
+/// Requests a route and returns why its response fails the self-test, if it does.
+async fn check_route(router: Router, uri: &str, route: &ConfigRoute) -> Result<(), String> {
+    let request = Request::builder()
+        .uri(uri)
+        .body(Body::empty())
+        .map_err(|error| format!("invalid request URI: {error}"))?;
+
+    // Handlers run on their own task, so a panic is reported like any failure
+    let response = tokio::spawn(router.oneshot(request))
+        .await
+        .map_err(|error| {
+            if error.is_panic() {
+                "the handler panicked".to_string()
+            } else {
+                error.to_string()
+            }
+        })?
+        .map_err(|error| error.to_string())?;
+
+    let status = response.status();
+    let is_json = response
+        .headers()
+        .get(CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|value| value.contains("json"));
+    let body = to_bytes(response.into_body(), usize::MAX)
+        .await
+        .map_err(|error| format!("{status}: the body could not be read: {error}"))?;
+
+    if status.is_server_error() && !configured_statuses(route).contains(&status.as_u16()) {
+        let detail = String::from_utf8_lossy(&body);
+        return Err(format!("{status} {}", detail.trim()).trim_end().to_string());
+    }
+
+    if is_json
+        && !body.is_empty()
+        && let Err(error) = serde_json::from_slice::<Value>(&body)
+    {
+        return Err(format!("{status}: the body is not valid JSON: {error}"));
+    }
+
+    Ok(())
+}
+
+/// Returns the statuses a route is configured to answer with.
+fn configured_statuses(route: &ConfigRoute) -> Vec<u16> {
+    let mut statuses: Vec<u16> = route
+        .sequence
+        .iter()
+        .flatten()
+        .chain(
+            route
+                .variants
+                .iter()
+                .flatten()
+                .map(|variant| &variant.response),
+        )
+        .chain(
+            route
+                .responses
+                .iter()
+                .flatten()
+                .map(|weighted| &weighted.response),
+        )
+        .filter_map(|response| response.status)
+        .collect();
+
+    if let ConfigResponse::ConfigRouteResponse(response) = &route.response {
+        statuses.extend(response.status);
+    }
+    statuses.extend(route.error.as_ref().map(|error| error.status));
+
+    statuses
+}
+
+/// Fills the path parameters of a route, from its first entry when it has one.
+///
+/// # Parameters
+///
+/// * `path` - The route path, e.g. `/users/{id}`
+/// * `route` - The route, whose inline body may be a collection
+///
+/// # Returns
+///
+/// The path with each `{name}` replaced by the first entry's `name` or id
+/// field, or `1`, and each `{*name}` by `selftest`
+fn sample_uri(path: &str, route: &ConfigRoute) -> String {
+    let first = match &route.response {
+        ConfigResponse::ConfigRouteResponse(response) => {
+            let body = response.body.as_value();
+            route
+                .results_field
+                .as_deref()
+                .map_or(Some(&body), |field| body.get(field))
+                .and_then(Value::as_array)
+                .and_then(|items| items.first().cloned())
+        }
+        _ => None,
+    };
+    let id_field = route.id_field.as_deref().unwrap_or("id");
+
+    path_segments(path)
+        .map(|segment| {
+            let name = match segment {
+                PathSegment::Static(segment) => return segment.to_string(),
+                PathSegment::Wildcard(_) => return "selftest".to_string(),
+                PathSegment::Param(name) => name,
+            };
+
+            first
+                .as_ref()
+                .and_then(|entry| entry.get(name).or_else(|| entry.get(id_field)))
+                .map_or_else(
+                    || "1".to_string(),
+                    |value| match value {
+                        Value::String(text) => text.clone(),
+                        other => other.to_string(),
+                    },
+                )
+        })
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::server::{AppState, create_router};
+    use json_echo_core::{ConfigManager, Database, FileSystemManager};
+    use std::sync::Arc;
+
+    /// Tests a self-test run over healthy, broken and deliberately failing routes.
+    ///
+    /// Verifies that a lazy route whose file is not valid JSON is reported,
+    /// that a route mocking a `503` and a parameterized lookup pass, and
+    /// that disabled routes are skipped.
+    #[tokio::test]
+    async fn test_selftest_reports_broken_route() {
+        let dir = tempfile::tempdir().expect("Failed to create temporary directory");
+        std::fs::write(
+            dir.path().join("orders.json"),
+            "{\"status\": 200, \"body\": [",
+        )
+        .expect("Failed to write broken file");
+        let file_system_manager = FileSystemManager::new(Some(dir.path().to_path_buf()))
+            .expect("Failed to create filesystem manager");
+        let mut config_manager = ConfigManager::new(file_system_manager.clone());
+        config_manager
+            .load_config_from_bytes(
+                br#"{"routes": {
+                    "/users": {"response": {"status": 200, "body": [{"id": "u7"}]}},
+                    "/users/{id}": {"response": {"status": 200, "body": [{"id": "u7"}]}},
+                    "/orders": {"lazy": true, "response": "orders.json"},
+                    "/down": {"response": {"status": 503, "body": {"error": "down"}}},
+                    "/hidden": {"enabled": false, "response": {"status": 200, "body": {}}},
+                    "[POST] /users": {"response": {"status": 201, "body": {}}}
+                }}"#,
+            )
+            .await
+            .expect("Failed to load test config");
+
+        let mut db = Database::new();
+        db.populate(config_manager.config.routes.clone());
+        let state = AppState::new(db).with_file_system(file_system_manager);
+        let router = create_router(Arc::new(state), &config_manager);
+
+        let report = run_selftest(router, &config_manager.config.routes).await;
+
+        assert_eq!(report.checked, 4);
+        assert_eq!(report.skipped, ["[GET] /hidden"]);
+        assert_eq!(report.failures.len(), 1, "{report}");
+        assert_eq!(report.failures[0].route, "[GET] /orders");
+        assert!(report.failures[0].reason.starts_with("500"));
+        assert!(report.to_string().contains("1 failed"));
+    }
+
+    /// Tests filling path parameters from the first entry of a collection.
+    #[test]
+    fn test_sample_uri() {
+        let route: ConfigRoute = serde_json::from_value(serde_json::json!({
+            "id_field": "slug",
+            "results_field": "data",
+            "response": {"status": 200, "body": {"data": [{"slug": "intro", "tag": 3}]}}
+        }))
+        .expect("Valid route");
+
+        assert_eq!(sample_uri("/posts/{slug}", &route), "/posts/intro");
+        assert_eq!(sample_uri("/tags/{tag}/{other}", &route), "/tags/3/intro");
+        assert_eq!(sample_uri("/files/{*path}", &route), "/files/selftest");
+        assert_eq!(
+            sample_uri("/users/{id}", &ConfigRoute::default()),
+            "/users/1"
+        );
+    }
+}
@@ -12,6 +12,8 @@
 //! - `run_server`: Function to start the HTTP server
 //! - `create_router`: Function to build the Axum router with all routes
 //! - Request handlers for different HTTP scenarios (GET, 404)
+//! - `delay_response`: Middleware holding responses back by their configured delay
+//! - `debug_headers`: Middleware adding request count and timing headers
 //!
 //! ## How
 //!
@@ -37,24 +39,91 @@
 //! use json_echo_core::Database;
 //! // This would typically be called from main.rs
 //! // let router = create_router(database);
-//! // run_server("localhost", "3000", router).await?;
+//! // run_server(&["localhost:3000".to_string()], router, Duration::ZERO, false, None, None, |_| {}).await?;
 //! ```
 
+use crate::body_log::LoggedBody;
+use crate::cache::cache_headers;
+use crate::charset::{Charset, encode_json, encode_text, with_charset};
+use crate::client::{DEFAULT_TIMEOUT, http_client};
+use crate::collection::{CollectionQuery, Slice, TOTAL_COUNT_HEADER};
+use crate::envelope::{media_type, wrap_envelope};
+use crate::etag::{conditional_get, precondition_failed, write_precondition};
+use crate::lazy::load_lazy_fixture;
+use crate::maintenance::{
+    ADMIN_MAINTENANCE_PATH, default_maintenance_body, maintenance_mode, maintenance_status,
+    toggle_maintenance,
+};
+use crate::proxy::{forward, upstream_url};
+use crate::range::partial_content;
+use crate::recorder::{
+    ADMIN_REQUESTS_PATH, RequestRecorder, clear_recorded_requests, record_requests,
+    recorded_requests,
+};
+use crate::redact::Redactor;
+use crate::request_id::{DEFAULT_REQUEST_ID_HEADER, RequestId, propagate_request_id};
+use crate::retry::{retry_after_header, retry_after_value};
+use crate::route_log::{RouteLogs, route_log_level};
+use crate::sql::{SqlError, query_rows};
+use crate::stream::is_streamed;
+use crate::stream::{NDJSON, ndjson_response, stream_items};
+use crate::template::render_header_value;
+use crate::timestamp::{TimedRoutes, has_time_tokens, render_timestamps};
+use crate::upload::{UploadError, save_body, save_multipart};
+use arc_swap::{ArcSwap, Guard};
 use axum::{
     Router,
-    extract::{Json, MatchedPath, Path, State},
-    http::{HeaderMap, HeaderName, HeaderValue, Method, StatusCode, Uri},
+    body::{Body, Bytes},
+    error_handling::HandleErrorLayer,
+    extract::{
+        DefaultBodyLimit, Form, FromRequest, Json, MatchedPath, Multipart, OriginalUri, Path,
+        Query, Request, State, multipart::MultipartError,
+    },
+    http::{
+        HeaderMap, HeaderName, HeaderValue, Method, StatusCode, Uri,
+        header::{
+            ACCEPT_LANGUAGE, CONNECTION, CONTENT_LANGUAGE, CONTENT_TYPE, IF_MATCH, LOCATION, VARY,
+        },
+    },
+    middleware::{self, Next},
     response::{IntoResponse, Response},
-    routing::{get, patch, post, put},
+    routing::{MethodFilter, MethodRouter, get},
+};
+use hyper_util::{
+    rt::{TokioExecutor, TokioIo},
+    server::{conn::auto, graceful::GracefulShutdown},
+    service::TowerToHyperService,
+};
+use json_echo_core::{
+    Config, ConfigManager, ConfigRouteResponse, CorsConfig, Database, EnvelopeSpec, ErrorConfig,
+    FallbackConfig, FileSystemManager, Model, PathSegment, RedirectConfig, RetryAfter,
+    TrailingSlash, path_segments, route_shape,
 };
-use json_echo_core::{ConfigManager, Database};
+use rand::{SeedableRng, rngs::StdRng};
 use serde_json::{Value, json};
-use std::sync::{Arc, RwLock};
-use std::{collections::HashMap, io::Error as IOError};
+use std::sync::{
+    Arc, Mutex,
+    atomic::{AtomicBool, AtomicU64, Ordering},
+};
+use std::{
+    borrow::Cow,
+    collections::{BTreeMap, HashMap, HashSet},
+    io::Error as IOError,
+    time::Duration,
+};
 use tokio::signal;
-use tower_http::cors::{Any, CorsLayer};
+use tokio_rustls::TlsAcceptor;
+use tower::{
+    BoxError, ServiceBuilder, limit::GlobalConcurrencyLimitLayer, load_shed::error::Overloaded,
+};
+use tower_http::compression::{
+    CompressionLayer,
+    predicate::{DefaultPredicate, NotForContentType, Predicate},
+};
+use tower_http::cors::{AllowHeaders, AllowMethods, AllowOrigin, Any, CorsLayer};
 use tower_http::services::ServeDir;
-use tracing::{debug, info};
+use tracing::{Instrument, debug, error, info, instrument::WithSubscriber, warn};
+use uuid::Uuid;
 
 /// Application state container that holds shared data across request handlers.
 ///
@@ -62,47 +131,688 @@ use tracing::{debug, info};
 /// in-memory database containing route configurations and mock data. This
 /// state is shared across all request handlers through Axum's state system.
 ///
+/// The database is held in an `ArcSwap`, so readers never take a lock: they
+/// load the current snapshot and keep observing it for as long as they hold
+/// it. Writers clone the snapshot, which is cheap because routes and model
+/// data are shared, mutate the copy and atomically swap it in.
+///
 /// # Fields
 ///
-/// * `db` - The in-memory database containing route definitions and mock data
+/// * `db` - The current snapshot of the in-memory database
+/// * `calls` - Per-route call counters driving response sequences
+/// * `samples` - Per-route counters of the latencies sampled, driving seeded latencies
+/// * `jobs` - Per-job poll counters, keyed by job status route and job id
+/// * `internal_error` - The configured response for internal failures, if any
+/// * `charset` - The global response charset, if one is configured
+/// * `requests` - The number of requests served by this instance
+/// * `debug_headers` - Whether debug headers are added to every response
+/// * `envelope` - The wrapper applied to JSON response bodies, if one is configured
+/// * `redactor` - The header and field names masked in logs
+/// * `request_id_header` - The header carrying the request id
+/// * `files` - The filesystem manager uploads are saved through, if any
+/// * `log_bodies` - The number of body bytes logged per request and response, if enabled
+/// * `maintenance` - Whether every configured route answers `503`, toggled at runtime
+/// * `maintenance_body` - The body answered while maintenance mode is on
+/// * `maintenance_retry_after` - The `Retry-After` sent while maintenance mode is on, if any
+/// * `fallbacks` - The responses answered to unmatched paths under a prefix
+/// * `plain_text_errors` - Whether unmatched paths and methods are answered in plain text
+/// * `record_requests` - Whether requests are recorded and listed on the admin route
+/// * `recorder` - The most recent requests received, listed on the admin route
 ///
 /// # Examples
 ///
 /// ```rust
 /// use json_echo_core::Database;
-/// use std::sync::RwLock;
+/// use serde_json::json;
 ///
-/// let db = Database::new();
-/// let app_state = AppState { db: RwLock::new(db) };
+/// let app_state = AppState::new(Database::new());
+/// let routes = app_state.read().get_routes().len();
+/// app_state.update(|db| db.set_model_data("[GET] /users", json!([])));
 /// ```
-struct AppState {
-    /// The in-memory database containing all route configurations and data
-    db: RwLock<Database>,
+pub struct AppState {
+    /// The current snapshot of the in-memory database
+    db: ArcSwap<Database>,
+    /// The number of calls served so far by every route, keyed by route identifier,
+    /// rebuilt whenever the database is replaced so reloaded routes are counted
+    calls: Mutex<HashMap<String, usize>>,
+    /// The number of latencies sampled so far by every route, keyed by route identifier
+    /// and counted from the first sample, so routes given a latency by a reload count too
+    samples: Mutex<HashMap<String, usize>>,
+    /// The number of polls served so far for every started job, keyed by status route and job id,
+    /// rebuilt whenever the database is replaced so reloaded jobs take effect
+    jobs: Mutex<HashMap<String, HashMap<String, usize>>>,
+    /// The configured response for internal failures, if any
+    internal_error: Option<ConfigRouteResponse>,
+    /// The global response charset, if one is configured
+    charset: Option<Charset>,
+    /// The number of requests served by this instance
+    requests: AtomicU64,
+    /// Whether request count and timing headers are added to every response
+    debug_headers: bool,
+    /// The wrapper applied to JSON response bodies, if one is configured
+    envelope: Option<EnvelopeSpec>,
+    /// The header and field names masked in logs
+    redactor: Redactor,
+    /// The header carrying the request id
+    request_id_header: HeaderName,
+    /// The filesystem manager uploads are saved through, if any
+    files: Option<FileSystemManager>,
+    /// The number of body bytes logged per request and response, if body logging is enabled
+    log_bodies: Option<usize>,
+    /// Whether maintenance mode is on, every configured route answering `503`
+    maintenance: AtomicBool,
+    /// The body answered while maintenance mode is on
+    maintenance_body: Value,
+    /// The `Retry-After` sent while maintenance mode is on, if one is configured
+    maintenance_retry_after: Option<HeaderValue>,
+    /// The responses answered to unmatched paths under a prefix
+    fallbacks: Vec<FallbackConfig>,
+    /// Whether unmatched paths and methods are answered in plain text instead of JSON
+    plain_text_errors: bool,
+    /// Whether requests are recorded and listed on the admin route
+    record_requests: bool,
+    /// The most recent requests received, listed on the admin route
+    recorder: RequestRecorder,
+}
+
+impl AppState {
+    /// Creates a new application state wrapping the given database.
+    ///
+    /// # Parameters
+    ///
+    /// * `db` - The populated in-memory database to share across handlers
+    ///
+    /// # Returns
+    ///
+    /// A new `AppState` ready to be wrapped in an `Arc` and handed to the router
+    pub fn new(db: Database) -> Self {
+        let calls = Mutex::new(
+            db.get_routes()
+                .into_iter()
+                .map(|route| (route.clone(), 0))
+                .collect(),
+        );
+        let jobs = Mutex::new(
+            Self::job_locations(&db)
+                .map(|location| (location, HashMap::new()))
+                .collect(),
+        );
+
+        Self {
+            db: ArcSwap::from_pointee(db),
+            calls,
+            samples: Mutex::new(HashMap::new()),
+            jobs,
+            internal_error: None,
+            charset: None,
+            requests: AtomicU64::new(0),
+            debug_headers: false,
+            envelope: None,
+            redactor: Redactor::default(),
+            request_id_header: HeaderName::from_static(DEFAULT_REQUEST_ID_HEADER),
+            files: None,
+            log_bodies: None,
+            maintenance: AtomicBool::new(false),
+            maintenance_body: default_maintenance_body(),
+            maintenance_retry_after: None,
+            fallbacks: Vec::new(),
+            plain_text_errors: false,
+            record_requests: false,
+            recorder: RequestRecorder::default(),
+        }
+    }
+
+    /// Sets the response sent when serving a request fails internally.
+    ///
+    /// # Parameters
+    ///
+    /// * `internal_error` - The configured `internal_error` response, if any
+    ///
+    /// # Returns
+    ///
+    /// The state, answering internal failures with the given response
+    #[must_use]
+    pub fn with_internal_error(mut self, internal_error: Option<ConfigRouteResponse>) -> Self {
+        self.internal_error = internal_error;
+        self
+    }
+
+    /// Sets the charset declared and used for every response.
+    ///
+    /// Loading the configuration rejects unsupported charsets, so only a
+    /// state built some other way can be given one; it is then left unset.
+    ///
+    /// # Parameters
+    ///
+    /// * `charset` - The configured global `charset`, if any
+    ///
+    /// # Returns
+    ///
+    /// The state, labeling and encoding responses with the given charset
+    #[must_use]
+    pub fn with_charset(mut self, charset: Option<&str>) -> Self {
+        self.charset = charset.and_then(Charset::parse);
+        self
+    }
+
+    /// Enables the `X-Echo-Request-Count` and `X-Echo-Response-Time-Ms` headers.
+    ///
+    /// # Parameters
+    ///
+    /// * `debug_headers` - The configured `debug_headers` flag
+    ///
+    /// # Returns
+    ///
+    /// The state, adding debug headers to every response when enabled
+    #[must_use]
+    pub fn with_debug_headers(mut self, debug_headers: bool) -> Self {
+        self.debug_headers = debug_headers;
+        self
+    }
+
+    /// Sets the wrapper applied to every successful JSON response body.
+    ///
+    /// # Parameters
+    ///
+    /// * `envelope` - The configured `envelope`, if any
+    ///
+    /// # Returns
+    ///
+    /// The state, wrapping response bodies in the given envelope
+    #[must_use]
+    pub fn with_envelope(mut self, envelope: Option<EnvelopeSpec>) -> Self {
+        self.envelope = envelope;
+        self
+    }
+
+    /// Returns the wrapper applied to JSON response bodies, if one is configured.
+    pub fn envelope(&self) -> Option<&EnvelopeSpec> {
+        self.envelope.as_ref()
+    }
+
+    /// Sets the header and field names masked in logged requests.
+    ///
+    /// # Parameters
+    ///
+    /// * `headers` - The configured `redact_headers`, or `None` for the default credential headers
+    /// * `fields` - The configured `redact_fields`, if any
+    ///
+    /// # Returns
+    ///
+    /// The state, masking the given headers and fields in logs
+    #[must_use]
+    pub fn with_redaction(mut self, headers: Option<&[String]>, fields: Option<&[String]>) -> Self {
+        self.redactor = Redactor::new(headers, fields);
+        self
+    }
+
+    /// Returns the redactor applied to every logged header and payload.
+    pub fn redactor(&self) -> &Redactor {
+        &self.redactor
+    }
+
+    /// Sets the header carrying the request id.
+    ///
+    /// # Parameters
+    ///
+    /// * `header` - The configured `request_id_header`, if any; the id is
+    ///   carried by `X-Request-Id` without one
+    ///
+    /// # Returns
+    ///
+    /// The state, reading and echoing the request id in the given header
+    #[must_use]
+    pub fn with_request_id_header(mut self, header: Option<HeaderName>) -> Self {
+        if let Some(header) = header {
+            self.request_id_header = header;
+        }
+        self
+    }
+
+    /// Returns the header carrying the request id.
+    pub fn request_id_header(&self) -> &HeaderName {
+        &self.request_id_header
+    }
+
+    /// Sets the filesystem manager uploads are saved through.
+    ///
+    /// Without one, routes configured with `save_uploads_to` describe their
+    /// uploads without saving them.
+    ///
+    /// # Parameters
+    ///
+    /// * `files` - The filesystem manager of the project root
+    ///
+    /// # Returns
+    ///
+    /// The state, saving uploads below the manager's root
+    #[must_use]
+    pub fn with_file_system(mut self, files: FileSystemManager) -> Self {
+        self.files = Some(files);
+        self
+    }
+
+    /// Returns the filesystem manager uploads are saved through, if any.
+    pub fn files(&self) -> Option<&FileSystemManager> {
+        self.files.as_ref()
+    }
+
+    /// Enables logging request and response bodies at debug level.
+    ///
+    /// # Parameters
+    ///
+    /// * `limit` - The number of bytes logged per body, or `None` to log no bodies
+    ///
+    /// # Returns
+    ///
+    /// The state, logging bodies cut at the given limit
+    #[must_use]
+    pub fn with_body_logging(mut self, limit: Option<usize>) -> Self {
+        self.log_bodies = limit;
+        self
+    }
+
+    /// Sets the body answered while maintenance mode is on.
+    ///
+    /// # Parameters
+    ///
+    /// * `body` - The configured `maintenance_body`, or `None` for the default error body
+    ///
+    /// # Returns
+    ///
+    /// The state, answering the given body during maintenance
+    #[must_use]
+    pub fn with_maintenance_body(mut self, body: Option<Value>) -> Self {
+        if let Some(body) = body {
+            self.maintenance_body = body;
+        }
+        self
+    }
+
+    /// Returns the body answered while maintenance mode is on.
+    pub fn maintenance_body(&self) -> &Value {
+        &self.maintenance_body
+    }
+
+    /// Sets the `Retry-After` sent while maintenance mode is on.
+    ///
+    /// # Parameters
+    ///
+    /// * `retry` - The configured `maintenance_retry_after`, if any
+    ///
+    /// # Returns
+    ///
+    /// The state, sending the rendered wait with the maintenance `503`
+    #[must_use]
+    pub fn with_maintenance_retry_after(mut self, retry: Option<&RetryAfter>) -> Self {
+        self.maintenance_retry_after = retry.and_then(retry_after_value);
+        self
+    }
+
+    /// Returns the `Retry-After` sent while maintenance mode is on, if any.
+    pub fn maintenance_retry_after(&self) -> Option<&HeaderValue> {
+        self.maintenance_retry_after.as_ref()
+    }
+
+    /// Sets the responses answered to unmatched paths under a prefix.
+    ///
+    /// # Parameters
+    ///
+    /// * `fallbacks` - The configured `fallbacks`, if any
+    ///
+    /// # Returns
+    ///
+    /// The state, answering unmatched paths with the fallback of their longest matching prefix
+    #[must_use]
+    pub fn with_fallbacks(mut self, fallbacks: Option<Vec<FallbackConfig>>) -> Self {
+        self.fallbacks = fallbacks.unwrap_or_default();
+        self
+    }
+
+    /// Answers unmatched paths and methods in plain text instead of JSON.
+    ///
+    /// # Parameters
+    ///
+    /// * `plain_text_errors` - The configured `plain_text_errors` flag
+    ///
+    /// # Returns
+    ///
+    /// The state, answering `404` with `No route defined` and `405` with an
+    /// empty body when enabled
+    #[must_use]
+    pub fn with_plain_text_errors(mut self, plain_text_errors: bool) -> Self {
+        self.plain_text_errors = plain_text_errors;
+        self
+    }
+
+    /// Records the requests received and lists them on the admin route.
+    ///
+    /// # Parameters
+    ///
+    /// * `record_requests` - The configured `record_requests` flag
+    ///
+    /// # Returns
+    ///
+    /// The state, recording requests when enabled
+    #[must_use]
+    pub fn with_request_recording(mut self, record_requests: bool) -> Self {
+        self.record_requests = record_requests;
+        self
+    }
+
+    /// Returns the fallback of an unmatched path, the longest matching prefix winning.
+    pub fn fallback(&self, path: &str) -> Option<&FallbackConfig> {
+        self.fallbacks
+            .iter()
+            .filter(|fallback| fallback.matches(path))
+            .max_by_key(|fallback| fallback.prefix.trim_end_matches('/').len())
+    }
+
+    /// Returns the recorder of the requests received.
+    pub fn recorder(&self) -> &RequestRecorder {
+        &self.recorder
+    }
+
+    /// Switches maintenance mode on or off.
+    pub fn set_maintenance(&self, enabled: bool) {
+        self.maintenance.store(enabled, Ordering::Relaxed);
+    }
+
+    /// Returns whether maintenance mode is on.
+    pub fn in_maintenance(&self) -> bool {
+        self.maintenance.load(Ordering::Relaxed)
+    }
+
+    /// Counts a served request and returns the total, this request included.
+    pub fn record_request(&self) -> u64 {
+        self.requests.fetch_add(1, Ordering::Relaxed) + 1
+    }
+
+    /// Resolves the charset of a route's responses.
+    ///
+    /// # Parameters
+    ///
+    /// * `route_charset` - The `charset` configured on the route, if any
+    ///
+    /// # Returns
+    ///
+    /// The route's charset when it is supported, otherwise the global one
+    pub fn charset(&self, route_charset: Option<&str>) -> Option<Charset> {
+        route_charset.and_then(Charset::parse).or(self.charset)
+    }
+
+    /// Builds the response for a request that failed internally.
+    ///
+    /// Every handler and middleware reports internal failures through this
+    /// method, so they are answered uniformly: with the configured
+    /// `internal_error` response, or a `500` with a JSON error body.
+    ///
+    /// # Parameters
+    ///
+    /// * `reason` - A description of the failure, logged but not sent
+    ///
+    /// # Returns
+    ///
+    /// The error response to send to the client
+    pub fn internal_error(&self, reason: &str) -> Response {
+        error!("⚠︎ Internal error: {}", reason);
+
+        let headers = response_headers(None, None, &HeaderMap::new(), self.charset);
+
+        let Some(internal_error) = &self.internal_error else {
+            return response(
+                headers,
+                StatusCode::INTERNAL_SERVER_ERROR,
+                &json!({"error": "Internal server error"}),
+            );
+        };
+
+        let status = internal_error
+            .status
+            .and_then(|status| StatusCode::from_u16(status).ok())
+            .unwrap_or(StatusCode::INTERNAL_SERVER_ERROR);
+
+        response(headers, status, &internal_error.body.as_value())
+    }
+
+    /// Counts a call to a route and returns how many calls preceded it.
+    ///
+    /// # Parameters
+    ///
+    /// * `route` - The `[METHOD] path` identifier of the called route
+    ///
+    /// # Returns
+    ///
+    /// The zero-based number of this call, always `0` for unknown routes
+    pub fn next_call(&self, route: &str) -> usize {
+        let mut calls = self.calls();
+        calls.get_mut(route).map_or(0, |count| {
+            *count += 1;
+            *count - 1
+        })
+    }
+
+    /// Returns the call counters, recovering them from a panicked holder.
+    fn calls(&self) -> std::sync::MutexGuard<'_, HashMap<String, usize>> {
+        self.calls
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+    }
+
+    /// Counts a latency sampled for a route and returns how many preceded it.
+    ///
+    /// Latencies keep their own count, so sampling them leaves the sequence
+    /// and weighted responses of the route where they were.
+    ///
+    /// # Parameters
+    ///
+    /// * `route` - The `[METHOD] path` identifier of the route
+    ///
+    /// # Returns
+    ///
+    /// The zero-based number of this sample, counted from the route's first one
+    pub fn next_sample(&self, route: &str) -> usize {
+        let mut samples = self
+            .samples
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner);
+        let count = samples.entry(route.to_string()).or_default();
+        *count += 1;
+        *count - 1
+    }
+
+    /// Returns the status routes of the jobs configured in a database.
+    fn job_locations(db: &Database) -> impl Iterator<Item = String> + '_ {
+        db.get_routes()
+            .into_iter()
+            .filter_map(|route| db.get_route(route, None)?.job.as_ref())
+            .map(|job| job.location.clone())
+    }
+
+    /// Returns the poll counters, recovering them from a panicked holder.
+    fn jobs(&self) -> std::sync::MutexGuard<'_, HashMap<String, HashMap<String, usize>>> {
+        self.jobs
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+    }
+
+    /// Starts a job and returns its generated id.
+    ///
+    /// # Parameters
+    ///
+    /// * `location` - The status route of the job, as configured in its `job.location`
+    ///
+    /// # Returns
+    ///
+    /// A fresh v4 UUID identifying the job on its status route
+    pub fn start_job(&self, location: &str) -> String {
+        let id = Uuid::new_v4().to_string();
+        if let Some(jobs) = self.jobs().get_mut(location) {
+            jobs.insert(id.clone(), 0);
+        }
+        id
+    }
+
+    /// Returns whether a route reports the status of started jobs.
+    pub fn is_job_route(&self, route_path: &str) -> bool {
+        self.jobs().contains_key(route_path)
+    }
+
+    /// Counts a poll of a job and returns how many polls preceded it.
+    ///
+    /// # Parameters
+    ///
+    /// * `route_path` - The status route being polled
+    /// * `id` - The job id taken from the polled path
+    ///
+    /// # Returns
+    ///
+    /// * `Some(usize)` - The zero-based number of this poll of the job
+    /// * `None` - If no job with this id was started on the route
+    pub fn next_job_poll(&self, route_path: &str, id: &str) -> Option<usize> {
+        let mut jobs = self.jobs();
+        let polls = jobs.get_mut(route_path)?.get_mut(id)?;
+        let poll = *polls;
+        *polls += 1;
+        Some(poll)
+    }
+
+    /// Returns the current database snapshot without taking a lock.
+    ///
+    /// The guard should be dropped once the lookups are done; data that has to
+    /// outlive it can be cloned cheaply.
+    pub fn read(&self) -> Guard<Arc<Database>> {
+        self.db.load()
+    }
+
+    /// Applies a mutation to a copy of the database and swaps the copy in.
+    ///
+    /// When another writer swaps in a database concurrently, the mutation is
+    /// retried on top of the newer snapshot so no write is lost. The closure may
+    /// therefore run more than once and should only touch the given database.
+    ///
+    /// # Parameters
+    ///
+    /// * `mutate` - The mutation to apply, returning a value to hand back
+    ///
+    /// # Returns
+    ///
+    /// The value returned by the last, successful run of `mutate`
+    pub fn update<R>(&self, mut mutate: impl FnMut(&mut Database) -> R) -> R {
+        let mut result = None;
+
+        self.db.rcu(|current| {
+            let mut next = Database::clone(current);
+            result = Some(mutate(&mut next));
+            next
+        });
+
+        result.unwrap_or_else(|| unreachable!("rcu always runs the update at least once"))
+    }
+
+    /// Replaces the whole database with the given one.
+    ///
+    /// The call counters and jobs are rebuilt from the new database: routes
+    /// and job status routes it still configures keep their counts, new ones
+    /// start from zero and the others are dropped.
+    pub fn replace(&self, db: Database) {
+        {
+            let mut calls = self.calls();
+            let mut previous = std::mem::take(&mut *calls);
+            *calls = db
+                .get_routes()
+                .into_iter()
+                .map(|route| {
+                    let count = previous.remove(route).unwrap_or_default();
+                    (route.clone(), count)
+                })
+                .collect();
+        }
+        {
+            let mut jobs = self.jobs();
+            let mut previous = std::mem::take(&mut *jobs);
+            *jobs = Self::job_locations(&db)
+                .map(|location| {
+                    let started = previous.remove(&location).unwrap_or_default();
+                    (location, started)
+                })
+                .collect();
+        }
+        self.db.store(Arc::new(db));
+    }
+}
+
+/// Builds the application state from the server settings of a configuration.
+///
+/// Settings that come from command-line flags, the file system and body
+/// logging, are left for the caller to add.
+///
+/// # Parameters
+///
+/// * `db` - The database populated with the configured routes
+/// * `config` - The loaded configuration
+///
+/// # Returns
+///
+/// The state of the server, ready to be shared with the router
+pub fn configured_state(db: Database, config: &Config) -> AppState {
+    AppState::new(db)
+        .with_internal_error(config.internal_error.clone())
+        .with_charset(config.charset.as_deref())
+        .with_debug_headers(config.debug_headers)
+        .with_envelope(config.envelope.clone())
+        .with_redaction(
+            config.redact_headers.as_deref(),
+            config.redact_fields.as_deref(),
+        )
+        .with_request_id_header(config.request_id_header.clone())
+        .with_maintenance_body(config.maintenance_body.clone())
+        .with_maintenance_retry_after(config.maintenance_retry_after.as_ref())
+        .with_fallbacks(config.fallbacks.clone())
+        .with_plain_text_errors(config.plain_text_errors)
+        .with_request_recording(config.record_requests)
 }
 
-/// Starts the HTTP server on the specified host and port with the given router.
+/// Starts the HTTP server on the specified addresses with the given router.
 ///
-/// This asynchronous function creates a TCP listener and starts serving HTTP
-/// requests using the provided Axum router. It handles the low-level server
-/// setup and request dispatching.
+/// This asynchronous function creates a TCP listener for every address and
+/// serves the same Axum router on all of them concurrently, each listener in
+/// its own tokio task. It handles the low-level server setup and request
+/// dispatching.
+///
+/// An address with port `0` is bound to a port picked by the operating
+/// system. The ports actually bound are logged, handed to `on_listening`
+/// and, for the first address, written to `port_file`, so a test harness
+/// starting the server can learn where to reach it.
+///
+/// Connections speak HTTP/1.1, or with `http2` either HTTP/1.1 or HTTP/2
+/// with prior knowledge (h2c), detected from the connection preface. With
+/// `tls`, every connection is served over TLS once its handshake completes,
+/// HTTP/2 being offered through ALPN when `http2` is set.
 ///
 /// # Parameters
 ///
-/// * `host` - The hostname or IP address to bind the server to
-/// * `port` - The port number to listen on
+/// * `addresses` - The `host:port` addresses to bind, e.g. `127.0.0.1:3000` and `[::1]:3000`
 /// * `router` - The configured Axum router with all routes and middleware
+/// * `startup_delay` - Time to wait before binding, simulating a slow-booting service
+/// * `http2` - Whether connections may speak HTTP/2 with prior knowledge
+/// * `tls` - Optional acceptor serving every connection over TLS, see `tls_acceptor`
+/// * `port_file` - Optional file the bound port of the first address is written to
+/// * `on_listening` - Called with the URL of every address, with its bound port, once every listener is bound
 ///
 /// # Returns
 ///
 /// * `Ok(())` - If the server started and ran successfully
-/// * `Err(IOError)` - If the server failed to bind or encountered network errors
+/// * `Err(IOError)` - If an address failed to bind, named in the error, or a
+///   listener encountered network errors
 ///
 /// # Errors
 ///
 /// This function can fail if:
-/// - The specified host/port combination is already in use
-/// - The host address is invalid or unreachable
-/// - Network permissions prevent binding to the specified port
+/// - One of the addresses is already in use
+/// - The port file cannot be written
+/// - A host address is invalid or unreachable
+/// - Network permissions prevent binding to one of the ports
 /// - System resource limits are exceeded
 ///
 /// # Examples
@@ -113,20 +823,210 @@ struct AppState {
 ///
 /// # async fn example() -> Result<(), Error> {
 /// let router = Router::new();
-/// run_server("localhost", "3000", router).await?;
+/// let addresses = ["127.0.0.1:3000".to_string(), "[::1]:3000".to_string()];
+/// run_server(&addresses, router, Duration::ZERO, false, None, None, |urls| println!("Listening on {urls:?}")).await?;
 /// # Ok(())
 /// # }
 /// ```
-pub async fn run_server(host: &str, port: &str, router: Router) -> Result<(), IOError> {
-    info!("Starting server at: http://{}:{}", host, port);
+pub async fn run_server(
+    addresses: &[String],
+    router: Router,
+    startup_delay: Duration,
+    http2: bool,
+    tls: Option<TlsAcceptor>,
+    port_file: Option<&std::path::Path>,
+    on_listening: impl FnOnce(&[String]),
+) -> Result<(), IOError> {
+    if !startup_delay.is_zero() {
+        info!(
+            "Startup delay: waiting {} ms before accepting connections",
+            startup_delay.as_millis()
+        );
+        tokio::time::sleep(startup_delay).await;
+    }
+
+    // Bind every address before serving, so one failure aborts the whole server
+    let mut listeners = Vec::with_capacity(addresses.len());
+    let mut urls = Vec::with_capacity(addresses.len());
+    let mut first_port = None;
+    for address in addresses {
+        let listener = tokio::net::TcpListener::bind(address.as_str())
+            .await
+            .map_err(|error| {
+                IOError::new(error.kind(), format!("Failed to bind {address}: {error}"))
+            })?;
+        let port = listener.local_addr()?.port();
+
+        // Keep the host as configured, only the port may have been picked by the system
+        let host = address
+            .rsplit_once(':')
+            .map_or(address.as_str(), |(host, _)| host);
+        let scheme = if tls.is_some() { "https" } else { "http" };
+        let url = format!("{scheme}://{host}:{port}");
+        info!("Starting server at: {}", url);
+
+        listeners.push(listener);
+        urls.push(url);
+        first_port.get_or_insert(port);
+    }
+
+    if let Some(port) = first_port {
+        if let Some(port_file) = port_file {
+            write_port_file(port_file, port).await?;
+        }
+        on_listening(&urls);
+    }
+
+    let mut servers = tokio::task::JoinSet::new();
+    for listener in listeners {
+        let router = router.clone();
+        servers.spawn(serve_listener(listener, router, http2, tls.clone()));
+    }
+
+    while let Some(served) = servers.join_next().await {
+        served.map_err(IOError::other)??;
+    }
+    Ok(())
+}
+
+/// Returns the client shared by proxied routes, building it on first use.
+///
+/// # Parameters
+///
+/// * `slot` - The client built for an earlier route, if any
+///
+/// # Returns
+///
+/// A clone of the client, sharing its connection pool, or `None` if it cannot
+/// be built, which is logged
+fn shared_client(slot: &mut Option<reqwest::Client>) -> Option<reqwest::Client> {
+    if slot.is_none() {
+        match http_client(DEFAULT_TIMEOUT) {
+            Ok(client) => *slot = Some(client),
+            Err(error) => error!("✗ Failed to build the upstream client: {}", error),
+        }
+    }
+    slot.clone()
+}
+
+/// Serves the router on every connection accepted by a listener until shutdown.
+///
+/// Each connection is served on its own task by hyper's automatic builder,
+/// restricted to HTTP/1.1 unless `http2` allows h2c. With `tls`, the TLS
+/// handshake runs on the connection's task, so a slow client never holds up
+/// the others, and a failed handshake only closes its connection. On shutdown
+/// the listener stops accepting and the open connections finish their
+/// requests in flight.
+///
+/// # Parameters
+///
+/// * `listener` - The bound listener
+/// * `router` - The configured Axum router
+/// * `http2` - Whether connections may speak HTTP/2 with prior knowledge
+/// * `tls` - Optional acceptor serving every connection over TLS
+///
+/// # Returns
+///
+/// * `Ok(())` - Once the server is shut down and its connections closed
+/// * `Err(IOError)` - Never for now; accept failures are logged and retried
+async fn serve_listener(
+    listener: tokio::net::TcpListener,
+    router: Router,
+    http2: bool,
+    tls: Option<TlsAcceptor>,
+) -> Result<(), IOError> {
+    let mut builder = auto::Builder::new(TokioExecutor::new());
+    if !http2 {
+        builder = builder.http1_only();
+    }
+    let graceful = GracefulShutdown::new();
+    let shutdown = shutdown_signal();
+    tokio::pin!(shutdown);
+
+    loop {
+        tokio::select! {
+            accepted = listener.accept() => {
+                let stream = match accepted {
+                    Ok((stream, _)) => stream,
+                    Err(error) => {
+                        // Running out of file descriptors is transient, so avoid a busy loop
+                        warn!("⚠︎ Failed to accept a connection: {}", error);
+                        tokio::time::sleep(Duration::from_millis(50)).await;
+                        continue;
+                    }
+                };
+                if let Some(acceptor) = tls.clone() {
+                    let builder = builder.clone();
+                    let service = TowerToHyperService::new(router.clone());
+                    let watcher = graceful.watcher();
+                    tokio::spawn(async move {
+                        let stream = match acceptor.accept(stream).await {
+                            Ok(stream) => stream,
+                            Err(error) => {
+                                debug!("TLS handshake failed: {}", error);
+                                return;
+                            }
+                        };
+                        let connection = builder.serve_connection(TokioIo::new(stream), service);
+                        if let Err(error) = watcher.watch(connection.into_owned()).await {
+                            debug!("Connection closed with an error: {}", error);
+                        }
+                    });
+                    continue;
+                }
+                // No route upgrades its connection, and only plain connections honor http1_only
+                let connection = builder
+                    .serve_connection(
+                        TokioIo::new(stream),
+                        TowerToHyperService::new(router.clone()),
+                    )
+                    .into_owned();
+                let connection = graceful.watch(connection);
+                tokio::spawn(async move {
+                    if let Err(error) = connection.await {
+                        debug!("Connection closed with an error: {}", error);
+                    }
+                });
+            }
+            () = &mut shutdown => break,
+        }
+    }
 
-    let listener = tokio::net::TcpListener::bind(format!("{host}:{port}")).await?;
-    axum::serve(listener, router)
-        .with_graceful_shutdown(shutdown_signal())
-        .await?;
+    drop(listener);
+    graceful.shutdown().await;
     Ok(())
 }
 
+/// Writes the bound port to a file, replacing it at once.
+///
+/// The port is written to a temporary file renamed over `path`, so a
+/// harness polling for the file never reads it half-written.
+///
+/// # Parameters
+///
+/// * `path` - The port file
+/// * `port` - The bound port
+///
+/// # Returns
+///
+/// * `Ok(())` - If the file holds the port followed by a newline
+/// * `Err(IOError)` - If the file cannot be written, naming it
+async fn write_port_file(path: &std::path::Path, port: u16) -> Result<(), IOError> {
+    let failed = |error: IOError| {
+        IOError::new(
+            error.kind(),
+            format!("Failed to write port file {}: {error}", path.display()),
+        )
+    };
+
+    let mut temporary = path.as_os_str().to_owned();
+    temporary.push(".tmp");
+    tokio::fs::write(&temporary, format!("{port}\n"))
+        .await
+        .map_err(failed)?;
+    tokio::fs::rename(&temporary, path).await.map_err(failed)
+}
+
 /// Creates and configures the Axum router with all routes and middleware.
 ///
 /// This function builds the complete router configuration by iterating through
@@ -136,7 +1036,8 @@ pub async fn run_server(host: &str, port: &str, router: Router) -> Result<(), IO
 ///
 /// # Parameters
 ///
-/// * `db` - The database containing route configurations and mock data
+/// * `state` - The shared application state holding the populated database
+/// * `config_manager` - The configuration manager providing server settings
 ///
 /// # Returns
 ///
@@ -146,19 +1047,22 @@ pub async fn run_server(host: &str, port: &str, router: Router) -> Result<(), IO
 ///
 /// The function performs the following setup:
 /// 1. Iterates through all models in the database
-/// 2. Creates HTTP routes based on model identifiers and methods
+/// 2. Groups the configured methods of each path into a single method router
 /// 3. Currently supports GET requests (extensible for other methods)
 /// 4. Configures CORS middleware for cross-origin requests
 /// 5. Sets up a 404 fallback handler for undefined routes
-/// 6. Wraps the database in shared application state
+/// 6. Attaches the shared application state to every handler
 ///
 /// # CORS Configuration
 ///
-/// The router includes permissive CORS settings:
-/// - Allows all HTTP methods (GET, POST, PUT, PATCH, DELETE, OPTIONS)
+/// Unless `cors` is configured, the router includes permissive CORS settings:
+/// - Allows the methods configured for the path, plus OPTIONS
 /// - Allows all headers and origins
 /// - Disables credentials for security
 ///
+/// A route carrying its own `cors` block overrides the global settings for
+/// its whole path through a per-path layer.
+///
 /// # Examples
 ///
 /// ```rust
@@ -166,566 +1070,7445 @@ pub async fn run_server(host: &str, port: &str, router: Router) -> Result<(), IO
 ///
 /// let mut db = Database::new();
 /// // db would be populated with route configurations
-/// let router = create_router(db);
+/// let state = Arc::new(AppState::new(db));
+/// let router = create_router(Arc::clone(&state), &config_manager);
 /// // Router is now ready to handle requests
 /// ```
-pub fn create_router(db: Database, config_manager: &ConfigManager) -> Router {
+#[allow(clippy::too_many_lines)]
+pub fn create_router(state: Arc<AppState>, config_manager: &ConfigManager) -> Router {
     info!("Getting models from config");
-    // First get the routes before moving db
-    let routes = db.get_routes();
     let config = &config_manager.config;
+    let db = state.read();
 
-    // Create a router with all the routes (no state yet)
-    let router_with_routes = routes.iter().fold(Router::new(), |router, route| {
-        let route_config = db.get_route(route, None);
+    // Group the configured methods by path so every path gets a single method router
+    let mut paths: BTreeMap<String, PathRoutes> = BTreeMap::new();
+    // Proxied routes share one client, and so its connection pool
+    let mut upstream_client = None;
+    // Only the paths of routes holding a time token have their bodies rendered
+    let mut timed = HashSet::new();
+    let timed_envelope = state
+        .envelope()
+        .is_some_and(|envelope| has_time_tokens(&envelope.template));
 
-        if route_config.is_none() {
+    for route in db.get_routes() {
+        let Some(route_config) = db.get_route(route, None) else {
             info!("⚠︎ Route {} as no configuration associated", route);
-            return router;
-        }
+            continue;
+        };
 
-        let route_method = route_config.unwrap().method.as_deref();
         let route_path = extract_path(route);
+        let entry = paths.remove(route_path).unwrap_or_default();
+
+        if timed_envelope
+            || route_config.lazy
+            || serde_json::to_value(route_config).is_ok_and(|value| has_time_tokens(&value))
+            || db
+                .get_model(route)
+                .is_some_and(|model| has_time_tokens(&model.get_value()))
+        {
+            timed.insert(route_path.to_string());
+        }
+
+        // Redirects answer every configured method without touching the database
+        if let Some(redirect) = route_config.redirect.clone()
+            && let Some(method) = route_config.method.as_deref()
+            && let Ok(http_method) = method.parse::<Method>()
+            && let Ok(filter) = MethodFilter::try_from(http_method.clone())
+        {
+            info!(
+                "[{}] redirect defined: {} -> {}",
+                method, route_path, redirect.to
+            );
+            paths.insert(
+                route_path.to_string(),
+                PathRoutes {
+                    method_router: entry
+                        .method_router
+                        .on(filter, move || async move { redirect_response(&redirect) }),
+                    methods: with_method(entry.methods, http_method),
+                    cors: entry.cors.or_else(|| route_config.cors.clone()),
+                    close_connection: entry.close_connection || route_config.disable_keep_alive,
+                },
+            );
+            continue;
+        }
+
+        // Error routes answer their configured method with the error envelope
+        if let Some(error) = route_config.error.clone()
+            && let Some(method) = route_config.method.as_deref()
+            && let Ok(http_method) = method.parse::<Method>()
+            && let Ok(filter) = MethodFilter::try_from(http_method.clone())
+        {
+            info!(
+                "[{}] error route defined: {} -> {}",
+                method, route_path, error.status
+            );
+            let methods = with_method(entry.methods, http_method);
+            let route_headers = route_config.headers.clone();
+            let content_type = route_config.content_type.clone();
+            let charset = state.charset(route_config.charset.as_deref());
+            paths.insert(
+                route_path.to_string(),
+                PathRoutes {
+                    method_router: entry.method_router.on(
+                        filter,
+                        move |request_headers: HeaderMap| async move {
+                            let headers = response_headers(
+                                route_headers.as_ref(),
+                                content_type.as_deref(),
+                                &request_headers,
+                                charset,
+                            );
+                            error_response(&error, headers)
+                        },
+                    ),
+                    methods,
+                    cors: entry.cors.or_else(|| route_config.cors.clone()),
+                    close_connection: entry.close_connection || route_config.disable_keep_alive,
+                },
+            );
+            continue;
+        }
+
+        // Proxy routes forward their configured method to the upstream
+        if let Some(proxy) = route_config.proxy.clone()
+            && let Some(method) = route_config.method.as_deref()
+            && let Ok(http_method) = method.parse::<Method>()
+            && let Ok(filter) = MethodFilter::try_from(http_method.clone())
+            && let Some(client) = shared_client(&mut upstream_client)
+        {
+            info!(
+                "[{}] proxy route defined: {} -> {}",
+                method, route_path, proxy.target
+            );
+            let methods = with_method(entry.methods, http_method);
+            paths.insert(
+                route_path.to_string(),
+                PathRoutes {
+                    method_router: entry.method_router.on(
+                        filter,
+                        move |Path(params): Path<HashMap<String, String>>,
+                              method: Method,
+                              uri: Uri,
+                              request_headers: HeaderMap,
+                              body: Bytes| async move {
+                            let url = upstream_url(&proxy, &uri, &params);
+                            match forward(&client, &url, &method, &request_headers, body).await {
+                                Ok(response) => response,
+                                Err(error) => {
+                                    warn!("⚠︎ {}", error);
+                                    response(
+                                        HeaderMap::new(),
+                                        error.status(),
+                                        &json!({"error": error.to_string()}),
+                                    )
+                                }
+                            }
+                        },
+                    ),
+                    methods,
+                    cors: entry.cors.or_else(|| route_config.cors.clone()),
+                    close_connection: entry.close_connection || route_config.disable_keep_alive,
+                },
+            );
+            continue;
+        }
+
+        // SQL routes answer their configured method with the rows of their query
+        if let Some(sql) = route_config.sql.clone()
+            && let Some(method) = route_config.method.as_deref()
+            && let Ok(http_method) = method.parse::<Method>()
+            && let Ok(filter) = MethodFilter::try_from(http_method.clone())
+        {
+            info!(
+                "[{}] SQL route defined: {} -> {}",
+                method, route_path, sql.file
+            );
+            let methods = with_method(entry.methods, http_method);
+            let route_headers = route_config.headers.clone();
+            let content_type = route_config.content_type.clone();
+            let charset = state.charset(route_config.charset.as_deref());
+            let sql_state = Arc::clone(&state);
+            paths.insert(
+                route_path.to_string(),
+                PathRoutes {
+                    method_router: entry.method_router.on(
+                        filter,
+                        move |Path(params): Path<HashMap<String, String>>,
+                              Query(query): Query<HashMap<String, String>>,
+                              request_headers: HeaderMap| async move {
+                            let headers = response_headers(
+                                route_headers.as_ref(),
+                                content_type.as_deref(),
+                                &request_headers,
+                                charset,
+                            );
+                            // Path parameters win over query parameters of the same name
+                            let values = query.into_iter().chain(params).collect();
+                            match query_rows(&sql, values).await {
+                                Ok(rows) => response(headers, StatusCode::OK, &rows),
+                                Err(error @ SqlError::MissingParameter(_)) => response(
+                                    headers,
+                                    StatusCode::BAD_REQUEST,
+                                    &json!({"error": error.to_string()}),
+                                ),
+                                Err(error) => sql_state.internal_error(&error.to_string()),
+                            }
+                        },
+                    ),
+                    methods,
+                    cors: entry.cors.or_else(|| route_config.cors.clone()),
+                    close_connection: entry.close_connection || route_config.disable_keep_alive,
+                },
+            );
+            continue;
+        }
 
-        match route_method {
+        let (method_router, http_method) = match route_config.method.as_deref() {
             Some("GET") => {
                 info!("[GET] route defined: {}", route_path);
-                router.route(route_path, get(get_handler))
+                (entry.method_router.get(get_handler), Method::GET)
             }
             Some("POST") => {
                 info!("[POST] route defined: {}", route_path);
-                router.route(route_path, post(add_update_handler))
+                (entry.method_router.post(add_update_handler), Method::POST)
             }
             Some("PUT") => {
                 info!("[PUT] route defined: {}", route_path);
-                router.route(route_path, put(add_update_handler))
+                (entry.method_router.put(add_update_handler), Method::PUT)
             }
             Some("PATCH") => {
                 info!("[PATCH] route defined: {}", route_path);
-                router.route(route_path, patch(add_update_handler))
+                (entry.method_router.patch(add_update_handler), Method::PATCH)
+            }
+            _ => {
+                if !entry.methods.is_empty() {
+                    paths.insert(route_path.to_string(), entry);
+                }
+                continue;
             }
-            _ => router,
+        };
+
+        paths.insert(
+            route_path.to_string(),
+            PathRoutes {
+                method_router,
+                methods: with_method(entry.methods, http_method),
+                cors: entry.cors.or_else(|| route_config.cors.clone()),
+                close_connection: entry.close_connection || route_config.disable_keep_alive,
+            },
+        );
+    }
+
+    // Release the read lock before handing the state to the router
+    drop(db);
+
+    // The global layer only covers the fallback, so unknown paths still answer preflights
+    let router = Router::new()
+        .fallback(handler_404)
+        .layer(cors_layer(config.cors.as_ref(), &DEFAULT_CORS_METHODS))
+        .layer(middleware::from_fn(no_content_options));
+
+    // The router panics on paths differing only in parameter names; loading
+    // rejects them, so this only skips routes populated some other way
+    let mut shapes: HashMap<String, String> = HashMap::new();
+    paths.retain(|path, _| {
+        let shape = route_shape(path);
+        if let Some(other) = shapes.get(&shape) {
+            error!(
+                "✗ Path {} differs from {} only in parameter names, skipped",
+                path, other
+            );
+            return false;
         }
+        shapes.insert(shape, path.clone());
+        true
     });
 
-    let cors = CorsLayer::new()
-        .allow_methods([
-            Method::GET,
-            Method::POST,
-            Method::PUT,
-            Method::PATCH,
-            Method::DELETE,
-            Method::OPTIONS,
-        ])
-        .allow_headers(Any)
-        .allow_origin(Any)
-        .allow_credentials(false);
-
-    // Move db into state after we're done using routes
-    let state = Arc::new(AppState {
-        db: RwLock::new(db),
-    });
+    // Paths declared with a trailing slash are matched before merge mode trims it
+    let mut slashed = matchit::Router::new();
+    for path in paths
+        .keys()
+        .filter(|path| path.len() > 1 && path.ends_with('/'))
+    {
+        if let Err(error) = slashed.insert(path.as_str(), ()) {
+            warn!("⚠︎ Path {} is trimmed in merge mode: {}", path, error);
+        }
+    }
 
-    // Add CORS and state
-    let router = router_with_routes
-        .fallback(handler_404)
-        .layer(cors)
-        .with_state(state);
+    // Every path gets its own layer, added after the global one so it doesn't
+    // overwrite their headers or answer their preflight requests
+    let layers = Arc::clone(&state);
+
+    let router = paths
+        .into_iter()
+        .fold(router, |router, (path, routes)| {
+            if routes.cors.is_some() {
+                info!("Route {} uses its own CORS settings", path);
+            }
+            let cors = cors_layer(
+                routes.cors.as_ref().or(config.cors.as_ref()),
+                &routes.methods,
+            );
+            // Maintenance answers inside the CORS layer so browsers can read the 503,
+            // and before lazy fixtures are loaded
+            let lazy = middleware::from_fn_with_state(Arc::clone(&state), load_lazy_fixture);
+            let maintenance = middleware::from_fn_with_state(Arc::clone(&state), maintenance_mode);
+            let method_router = routes
+                .method_router
+                .fallback(handler_405)
+                .route_layer(lazy)
+                .route_layer(maintenance);
+            let method_router = if routes.close_connection && !config.disable_keep_alive {
+                info!("Route {} disables keep-alive", path);
+                method_router.route_layer(middleware::from_fn(close_connection))
+            } else {
+                method_router
+            };
+            router.route(&path, method_router.layer(cors))
+        })
+        .route_layer(middleware::from_fn_with_state(
+            Arc::clone(&state),
+            wrap_envelope,
+        ));
+    let router = if timed.is_empty() {
+        router
+    } else {
+        router.route_layer(middleware::from_fn_with_state(
+            Arc::new(TimedRoutes {
+                state: Arc::clone(&state),
+                paths: timed,
+            }),
+            render_timestamps,
+        ))
+    };
+    let router = router
+        .route_layer(middleware::from_fn_with_state(
+            Arc::clone(&state),
+            conditional_get,
+        ))
+        .route_layer(middleware::from_fn_with_state(
+            Arc::clone(&state),
+            partial_content,
+        ))
+        .route_layer(middleware::from_fn_with_state(
+            Arc::clone(&state),
+            delay_response,
+        ))
+        .route_layer(middleware::from_fn_with_state(
+            Arc::clone(&state),
+            cache_headers,
+        ))
+        .route_layer(middleware::from_fn_with_state(
+            Arc::clone(&state),
+            retry_after_header,
+        ))
+        .route(
+            ADMIN_MAINTENANCE_PATH,
+            get(maintenance_status).post(toggle_maintenance),
+        );
+    let router = if state.record_requests {
+        info!("Recording requests on {}", ADMIN_REQUESTS_PATH);
+        router.route(
+            ADMIN_REQUESTS_PATH,
+            get(recorded_requests).delete(clear_recorded_requests),
+        )
+    } else {
+        router
+    };
+    let router = router.with_state(state);
 
-    if let Some(static_folder) = config.static_folder.as_ref() {
+    let router = if let Some(static_folder) = config.static_folder.as_ref() {
         let static_route = config.static_route.as_str();
 
         info!(
             "Serving static files from: {}, on route {}",
             static_folder, static_route
         );
-        let serve_dir = ServeDir::new(config_manager.get_root().join(static_folder));
-        return router.nest_service(static_route, serve_dir);
+        let static_path = config_manager.get_root().join(static_folder);
+        if let Err(problem) = check_static_folder(&static_path) {
+            warn!("⚠︎ {}; {} will answer 404", problem, static_route);
+        }
+
+        let serve_dir = ServeDir::new(static_path);
+        router.nest_service(static_route, serve_dir)
+    } else {
+        router
+    };
+    // The request log runs after routing, so it knows the route a request was matched to
+    let router = router
+        .layer(middleware::from_fn_with_state(
+            Arc::new(RouteLogs::new(Arc::clone(&layers))),
+            log_request,
+        ))
+        .layer(DefaultBodyLimit::max(REQUEST_BODY_LIMIT));
+
+    let router = with_trailing_slash(router, config.trailing_slash, &config.static_route, slashed);
+    let router = with_case_insensitive_paths(router, config.case_insensitive_paths, &layers);
+    let router = with_concurrency_limit(
+        with_method_override(router, config.method_override),
+        config.max_concurrency,
+    );
+    let router = with_compression(with_router_layers(router, layers), config.compression);
+
+    if config.disable_keep_alive {
+        info!("Keep-alive disabled, every connection serves a single request");
+        return router.layer(middleware::from_fn(close_connection));
     }
 
     router
 }
 
-/// Fallback handler for undefined routes (404 Not Found).
+/// Applies the configured trailing slash handling to the whole router.
 ///
-/// This handler is called when a request is made to a route that is not
-/// defined in the router configuration. It returns a simple 404 status
-/// with a descriptive message.
+/// In `merge` mode the router is nested behind a layer trimming trailing
+/// slashes, so the path is rewritten before any route is matched. Paths
+/// matching a route declared with a trailing slash are left as they are.
+///
+/// # Parameters
+///
+/// * `router` - The router with every route, the fallback and the static files
+/// * `mode` - The configured `trailing_slash`, `merge` when unset
+/// * `static_route` - The static file route, whose paths are left untouched
+/// * `slashed` - The routes declared with a trailing slash
 ///
 /// # Returns
 ///
-/// An HTTP response with status 404 and explanatory text
+/// The router, answering `/users/` like `/users` unless the mode is `strict`
+fn with_trailing_slash(
+    router: Router,
+    mode: Option<TrailingSlash>,
+    static_route: &str,
+    slashed: matchit::Router<()>,
+) -> Router {
+    match mode.unwrap_or_default() {
+        TrailingSlash::Strict => router,
+        TrailingSlash::Merge => {
+            let untrimmed = Arc::new(Untrimmed {
+                static_route: static_route.trim_end_matches('/').to_string(),
+                slashed,
+            });
+            Router::new()
+                .fallback_service(router)
+                .layer(middleware::from_fn_with_state(
+                    untrimmed,
+                    trim_trailing_slash,
+                ))
+        }
+    }
+}
+
+/// The paths merge mode leaves with their trailing slash.
+struct Untrimmed {
+    /// The static file route, without a trailing slash
+    static_route: String,
+    /// The routes declared with a trailing slash
+    slashed: matchit::Router<()>,
+}
+
+/// Returns the path without its trailing slashes, or `None` if it keeps its form.
+///
+/// The root path and paths under the static file route are kept as they are,
+/// since static directories are addressed with their trailing slash.
 ///
-/// # Examples
+/// # Parameters
 ///
-/// When a client requests a non-existent route like `/undefined-path`,
-/// this handler will return:
-/// ```
-/// HTTP/1.1 404 Not Found
-/// Content-Type: text/plain
+/// * `path` - The request path
+/// * `static_route` - The static file route, without a trailing slash
 ///
-/// No route defined
+/// # Examples
+///
+/// ```rust
+/// assert_eq!(trimmed_path("/users/", "/static"), Some("/users"));
+/// assert_eq!(trimmed_path("/users", "/static"), None);
+/// assert_eq!(trimmed_path("/static/docs/", "/static"), None);
 /// ```
-async fn handler_404() -> impl IntoResponse {
-    (StatusCode::NOT_FOUND, "No route defined")
+fn trimmed_path<'a>(path: &'a str, static_route: &str) -> Option<&'a str> {
+    let trimmed = path.trim_end_matches('/');
+    let is_static = !static_route.is_empty()
+        && path
+            .strip_prefix(static_route)
+            .is_some_and(|rest| rest.is_empty() || rest.starts_with('/'));
+
+    (trimmed.len() < path.len() && !trimmed.is_empty() && !is_static).then_some(trimmed)
 }
 
-/// HTTP GET request handler that serves mock data based on route configuration.
+/// Header naming the method a POST request stands for when `method_override` is set.
+const METHOD_OVERRIDE: HeaderName = HeaderName::from_static("x-http-method-override");
+
+/// The methods a POST request can be overridden with.
+const OVERRIDABLE_METHODS: [Method; 3] = [Method::PUT, Method::PATCH, Method::DELETE];
+
+/// Applies the configured method override to the whole router.
 ///
-/// This handler processes GET requests by looking up the corresponding model
-/// in the database and returning appropriate mock data. It supports both
-/// parameterized requests (returning specific entries) and general requests
-/// (returning all data).
+/// When enabled, the router is nested behind a layer replacing the method of
+/// overridden POST requests, so the route of the overriding method is
+/// matched instead of the POST route.
 ///
 /// # Parameters
 ///
-/// * `Path(params)` - Path parameters extracted from the URL
-/// * `State(state)` - Shared application state containing the database
-/// * `Query(query_params)` - Query string parameters from the request
-/// * `uri` - The full URI of the request
-/// * `req` - The complete HTTP request object
+/// * `router` - The router with every route, the fallback and the static files
+/// * `enabled` - The configured `method_override`
 ///
 /// # Returns
 ///
-/// An HTTP response containing:
-/// - JSON data from the model if found
-/// - Specific entry if path parameters match
-/// - All model data if no specific parameters
-/// - 404 error if route or model not found
-///
-/// # Behavior
-///
-/// The handler follows this logic:
-/// 1. Extracts the matched route path from request extensions
-/// 2. Looks up the corresponding model in the database
-/// 3. If path parameters are provided, searches for a specific entry
-/// 4. Returns the specific entry if found, or all model data otherwise
-/// 5. Returns appropriate error responses for missing routes/models
+/// The router, serving `POST` with `X-HTTP-Method-Override: DELETE` like a
+/// `DELETE` when enabled
+fn with_method_override(router: Router, enabled: bool) -> Router {
+    if !enabled {
+        return router;
+    }
+
+    info!("Method override enabled through {}", METHOD_OVERRIDE);
+    Router::new()
+        .fallback_service(router)
+        .layer(middleware::from_fn(override_method))
+}
+
+/// Middleware replacing the method of POST requests before routing.
 ///
-/// # Response Format
+/// Only `PUT`, `PATCH` and `DELETE`, in any case, override a POST; other
+/// values and other methods are left as they are.
 ///
-/// Successful responses return JSON data with status 200:
-/// ```json
-/// {
-///   "id": 1,
-///   "name": "example",
-///   "data": "value"
-/// }
-/// ```
+/// # Parameters
 ///
-/// Error responses return JSON with appropriate status codes:
-/// ```json
-/// {
-///   "error": "Route is not defined"
-/// }
-/// ```
+/// * `request` - The incoming request
+/// * `next` - The router
 ///
-/// # Examples
+/// # Returns
 ///
-/// ```
-/// GET /users -> Returns all users
-/// GET /users/123 -> Returns user with ID 123 (if found)
-/// GET /undefined -> Returns 404 error
-/// ```
-async fn get_handler(
-    State(state): State<Arc<AppState>>,
-    Path(params): Path<HashMap<String, String>>,
-    uri_path: Uri,
-    path: MatchedPath,
-) -> Response {
-    info!("[GET] request called: {}", uri_path.path());
-    let state_reader = state.db.read();
+/// The response of the route matching the overriding method
+async fn override_method(mut request: Request, next: Next) -> Response {
+    if request.method() == Method::POST
+        && let Some(value) = request.headers().get(&METHOD_OVERRIDE)
+    {
+        let method = value
+            .to_str()
+            .ok()
+            .and_then(|value| value.trim().to_ascii_uppercase().parse::<Method>().ok())
+            .filter(|method| OVERRIDABLE_METHODS.contains(method));
 
-    if state_reader.is_err() {
-        return response(
-            HeaderMap::new(),
-            StatusCode::EXPECTATION_FAILED,
-            &json!({"error": "Unable to read database"}),
-        );
+        match method {
+            Some(method) => {
+                debug!("Overriding POST {} with {}", request.uri(), method);
+                *request.method_mut() = method;
+            }
+            None => debug!("Ignoring method override {:?}", value),
+        }
     }
 
-    let state_reader = state_reader.unwrap();
+    next.run(request).await
+}
 
-    let route_path = path.as_str();
-    let model = state_reader.get_model(&format!("[GET] {route_path}"));
-    let route = state_reader.get_route(route_path, Some(String::from("GET")));
+/// Compresses the response bodies of the whole router, when configured.
+///
+/// Bodies are encoded with gzip, deflate or brotli, as negotiated through the
+/// request's `Accept-Encoding`. Small bodies, images and event streams are
+/// sent as they are, and so are streamed NDJSON responses, whose items the
+/// encoder would otherwise hold back until it fills a block.
+///
+/// # Parameters
+///
+/// * `router` - The router with every route, the fallback and the static files
+/// * `compression` - The configured `compression` flag
+///
+/// # Returns
+///
+/// The router, compressing its responses if `compression` is set
+fn with_compression(router: Router, compression: bool) -> Router {
+    if !compression {
+        return router;
+    }
 
-    debug!("Model: {:?}", model);
-    debug!("Route Config: {:?}", route);
+    info!("Compressing responses for clients accepting it");
+    let predicate = DefaultPredicate::new().and(NotForContentType::const_new(NDJSON));
+    router.layer(CompressionLayer::new().compress_when(predicate))
+}
 
-    let mut headers = HeaderMap::new();
-    headers.insert("Content-Type", HeaderValue::from_static("application/json"));
+/// Applies the configured concurrency limit to the whole router.
+///
+/// The router is nested behind one limit shared by every route, and requests
+/// arriving while every slot is taken are shed instead of waiting for one.
+/// Delayed routes keep their slot for the whole delay, so long delays fill
+/// the server the way slow handlers fill a real backend.
+///
+/// # Parameters
+///
+/// * `router` - The router with every route, the fallback and the static files
+/// * `max_concurrency` - The configured `max_concurrency`, unlimited when unset
+///
+/// # Returns
+///
+/// The router, answering `503 Service Unavailable` to requests beyond the limit
+fn with_concurrency_limit(router: Router, max_concurrency: Option<usize>) -> Router {
+    let Some(max) = max_concurrency else {
+        return router;
+    };
 
-    if let Some(route) = route {
-        if let Some(route_headers) = &route.headers {
-            for (key, value) in route_headers {
-                if let Ok(header_name) = key.parse::<HeaderName>() {
-                    if let Ok(header_value) = value.parse() {
-                        headers.insert(header_name, header_value);
-                    }
-                }
-            }
-        }
-    }
+    info!("Serving at most {} requests at once", max);
+    Router::new().fallback_service(router).layer(
+        ServiceBuilder::new()
+            .layer(HandleErrorLayer::new(shed_request))
+            .load_shed()
+            .layer(GlobalConcurrencyLimitLayer::new(max)),
+    )
+}
 
-    debug!("Headers Config: {:?}", headers);
+/// Answers a request shed by the concurrency limit.
+///
+/// # Parameters
+///
+/// * `error` - The reason the request was not served, normally an overload
+///
+/// # Returns
+///
+/// `503 Service Unavailable` for an overload, otherwise `500 Internal Server Error`
+async fn shed_request(error: BoxError) -> Response {
+    if error.is::<Overloaded>() {
+        debug!("Concurrency limit reached, shedding request");
+        return (
+            StatusCode::SERVICE_UNAVAILABLE,
+            Json(json!({"error": "Server overloaded"})),
+        )
+            .into_response();
+    }
 
-    if let Some(model) = model {
-        let http_status = model.get_status().unwrap_or(StatusCode::OK.as_u16());
-        let status = StatusCode::from_u16(http_status).unwrap_or(StatusCode::OK);
+    error!("Unhandled service error: {}", error);
+    (
+        StatusCode::INTERNAL_SERVER_ERROR,
+        Json(json!({"error": "Internal server error"})),
+    )
+        .into_response()
+}
 
-        if !params.is_empty() {
-            let model_data = model.find_entry_by_hashmap(params);
+/// Middleware trimming trailing slashes from request paths before routing.
+///
+/// # Parameters
+///
+/// * `State(untrimmed)` - The static file route and the routes declared with a
+///   trailing slash, whose paths are left untouched
+/// * `request` - The incoming request, its query string preserved
+/// * `next` - The router
+///
+/// # Returns
+///
+/// The response of the route matching the trimmed path
+async fn trim_trailing_slash(
+    State(untrimmed): State<Arc<Untrimmed>>,
+    mut request: Request,
+    next: Next,
+) -> Response {
+    if untrimmed.slashed.at(request.uri().path()).is_err()
+        && let Some(path) = trimmed_path(request.uri().path(), &untrimmed.static_route)
+    {
+        let path_and_query = match request.uri().query() {
+            Some(query) => format!("{path}?{query}"),
+            None => path.to_string(),
+        };
 
-            if let Some(data) = model_data {
-                return response(headers, status, &data);
+        let mut parts = request.uri().clone().into_parts();
+        if let Ok(path_and_query) = path_and_query.parse() {
+            parts.path_and_query = Some(path_and_query);
+            if let Ok(uri) = Uri::from_parts(parts) {
+                debug!("Trimmed trailing slash: {} -> {}", request.uri(), uri);
+                *request.uri_mut() = uri;
             }
         }
-
-        let response_body = model.get_data();
-
-        return response(headers, status, &response_body.as_value());
     }
 
-    response(
-        headers,
-        StatusCode::NOT_FOUND,
-        &json!({"error": "Model not found"}),
-    )
+    next.run(request).await
 }
 
-/// HTTP POST request handler that processes incoming data and serves mock responses.
+/// Applies the configured case-insensitive path matching to the whole router.
 ///
-/// This handler processes POST requests by accepting JSON payloads and returning
-/// appropriate mock data based on route configuration. It supports payload
-/// validation and dynamic response generation.
+/// When enabled, the router is nested behind a layer rewriting the path to
+/// the case of the route it matches, before any route is matched. The route
+/// patterns are split into segments once, here, so requests only compare
+/// them. The URI as sent is kept in the `OriginalUri` extension, which the
+/// request log reports instead of the folded one.
 ///
 /// # Parameters
 ///
-/// * `Path(params)` - Path parameters extracted from the URL
-/// * `State(state)` - Shared application state containing the database
-/// * `payload` - Optional JSON payload from the request body
-/// * `req` - The complete HTTP request object
+/// * `router` - The router with every route, the fallback and the static files
+/// * `enabled` - The configured `case_insensitive_paths`
+/// * `state` - Shared application state holding the configured routes
 ///
 /// # Returns
 ///
-/// An HTTP response containing:
-/// - JSON data from the model if found
-/// - Confirmation of data processing if successful
-/// - 404 error if route or model not found
+/// The router, answering `/API/Users` like `/api/users` when enabled
+fn with_case_insensitive_paths(router: Router, enabled: bool, state: &Arc<AppState>) -> Router {
+    if !enabled {
+        return router;
+    }
+
+    info!("Case-insensitive path matching enabled");
+    let patterns = CasedPatterns::new(
+        state
+            .read()
+            .get_routes()
+            .into_iter()
+            .filter_map(|identifier| Some(identifier.split_once("] ")?.1)),
+    );
+    Router::new()
+        .fallback_service(router)
+        .layer(middleware::from_fn_with_state(
+            Arc::new(patterns),
+            fold_path_case,
+        ))
+}
+
+/// A segment of a route pattern matched ignoring case.
 ///
-/// # Behavior
+/// # Variants
 ///
-/// The handler follows this logic:
-/// 1. Extracts the matched route path from request extensions
-/// 2. Looks up the corresponding model in the database
-/// 3. Processes the incoming JSON payload if provided
-/// 4. Returns appropriate mock response based on configuration
-/// 5. Returns error responses for missing routes/models
+/// * `Static` - A segment matched ignoring case, sent on in the case of the pattern
+/// * `Param` - A `{name}` segment, matching any non-empty segment kept as sent
+/// * `Wildcard` - A `{*name}` segment, matching the rest of the path kept as sent
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+enum CasedSegment {
+    /// A static segment, in the case of the pattern
+    Static(String),
+    /// A path parameter
+    Param,
+    /// A catch-all parameter
+    Wildcard,
+}
+
+impl CasedSegment {
+    /// Returns how strongly the segment binds, static segments first as in the router.
+    fn precedence(&self) -> u8 {
+        match self {
+            CasedSegment::Static(_) => 0,
+            CasedSegment::Param => 1,
+            CasedSegment::Wildcard => 2,
+        }
+    }
+}
+
+/// The route patterns a request path is folded to, split into segments once.
+///
+/// Patterns are kept in the order the router prefers them: compared segment
+/// by segment from the left, a static segment wins over a parameter, which
+/// wins over a wildcard. The first pattern matching a path is therefore the
+/// one the router would pick for it.
 ///
 /// # Examples
 ///
+/// ```rust
+/// let patterns = CasedPatterns::new(["/api/{kind}/all", "/api/users/{id}"].into_iter());
+/// assert_eq!(patterns.fold("/API/Users/all"), Some("/api/users/all".to_string()));
+/// assert_eq!(patterns.fold("/api/orders"), None);
 /// ```
-/// POST /users -> Creates/processes user data
-/// POST /api/data -> Processes API data submission
-/// ```
-#[allow(clippy::manual_let_else)]
-#[allow(clippy::ignored_unit_patterns)]
-#[allow(clippy::too_many_lines)]
-async fn add_update_handler(
-    State(state): State<Arc<AppState>>,
-    Path(params): Path<HashMap<String, String>>,
-    method: Method,
-    uri_path: Uri,
-    path: MatchedPath,
-    payload: Option<Json<Value>>,
-) -> Response {
-    let http_method = &method.as_str().to_uppercase();
+struct CasedPatterns(Vec<Vec<CasedSegment>>);
 
-    info!("[{}] request called: {}", http_method, uri_path.path());
+impl CasedPatterns {
+    /// Splits and orders the given route patterns.
+    ///
+    /// # Parameters
+    ///
+    /// * `patterns` - The path patterns of the routes, repeated once per method
+    fn new<'a>(patterns: impl Iterator<Item = &'a str>) -> Self {
+        let mut patterns: Vec<Vec<CasedSegment>> = patterns
+            .map(|pattern| {
+                path_segments(pattern.trim_end_matches('/'))
+                    .map(|segment| match segment {
+                        PathSegment::Static(segment) => CasedSegment::Static(segment.to_string()),
+                        PathSegment::Param(_) => CasedSegment::Param,
+                        PathSegment::Wildcard(_) => CasedSegment::Wildcard,
+                    })
+                    .collect()
+            })
+            .collect();
+        patterns.sort_by(|pattern, other| {
+            let precedence = |segments: &Vec<CasedSegment>| {
+                segments
+                    .iter()
+                    .map(CasedSegment::precedence)
+                    .collect::<Vec<_>>()
+            };
+            precedence(pattern)
+                .cmp(&precedence(other))
+                .then_with(|| pattern.cmp(other))
+        });
+        patterns.dedup();
+        Self(patterns)
+    }
 
-    let body_payload = payload.unwrap_or(axum::Json(json!({})));
-    let route_path = path.as_str();
-    let route_identifier = format!("[{http_method}] {route_path}");
+    /// Returns the path in the case of the first pattern it matches, or `None`.
+    ///
+    /// Static segments take the case of the pattern; parameters keep the case
+    /// of the request, so they reach the handler as sent. Trailing slashes are
+    /// ignored on both sides and kept on the returned path.
+    ///
+    /// # Parameters
+    ///
+    /// * `path` - The request path
+    fn fold(&self, path: &str) -> Option<String> {
+        let trimmed = path.trim_end_matches('/');
+        let pattern = self
+            .0
+            .iter()
+            .find(|pattern| Self::matches(pattern, trimmed))?;
 
-    // First, get the route configuration and model info without holding the lock
-    let (model_exists, route_headers, model_status) = {
-        let state_reader = match state.db.read() {
-            Ok(reader) => reader,
-            Err(_) => {
-                return response(
-                    HeaderMap::new(),
-                    StatusCode::EXPECTATION_FAILED,
-                    &json!({"error": "Unable to read database"}),
-                );
+        let mut folded = String::with_capacity(path.len());
+        // A wildcard is the last segment, so its piece holds the rest of the path
+        for (index, (segment, sent)) in pattern
+            .iter()
+            .zip(trimmed.splitn(pattern.len(), '/'))
+            .enumerate()
+        {
+            if index > 0 {
+                folded.push('/');
             }
-        };
-
-        let model = state_reader
-            .get_model(&format!("[GET] {route_path}"))
-            .or_else(|| state_reader.get_model(&route_identifier));
-        let route_config = state_reader
-            .get_route(route_path, Some(String::from("GET")))
-            .or_else(|| state_reader.get_route(&route_identifier, None));
-
-        debug!("Route Config: {:?}", route_config);
-        debug!("Payload: {:?}", body_payload);
-
-        let model_exists = model.is_some();
-        let route_headers = route_config.and_then(|rc| rc.headers.clone());
-        let model_status = model.map(|m| m.get_status().unwrap_or(StatusCode::OK.as_u16()));
-
-        (model_exists, route_headers, model_status)
-    }; // Read lock drop
-
-    if !model_exists {
-        return response(
-            HeaderMap::new(),
-            StatusCode::NOT_FOUND,
-            &json!({"error": "Model not found"}),
-        );
+            match segment {
+                CasedSegment::Static(segment) => folded.push_str(segment),
+                CasedSegment::Param | CasedSegment::Wildcard => folded.push_str(sent),
+            }
+        }
+        folded.push_str(&path[trimmed.len()..]);
+        Some(folded)
     }
 
-    // Configure headers
-    let mut headers = HeaderMap::new();
-    headers.insert("Content-Type", HeaderValue::from_static("application/json"));
-
-    if let Some(route_headers) = route_headers {
-        for (key, value) in route_headers {
-            if let Ok(header_name) = key.parse::<HeaderName>() {
-                if let Ok(header_value) = value.parse() {
-                    headers.insert(header_name, header_value);
-                }
+    /// Returns whether a path, its trailing slashes trimmed, matches a pattern ignoring case.
+    fn matches(pattern: &[CasedSegment], trimmed: &str) -> bool {
+        let mut segments = trimmed.split('/');
+        for segment in pattern {
+            match (segment, segments.next()) {
+                (CasedSegment::Wildcard, Some(_)) => return true,
+                (CasedSegment::Param, Some(sent)) if !sent.is_empty() => {}
+                (CasedSegment::Static(segment), Some(sent))
+                    if segment.eq_ignore_ascii_case(sent) => {}
+                _ => return false,
             }
         }
+        segments.next().is_none()
     }
+}
 
-    debug!("Headers Config: {:?}", headers);
-
-    let http_status = model_status.unwrap_or(StatusCode::OK.as_u16());
-    let status = StatusCode::from_u16(http_status).unwrap_or(StatusCode::OK);
-    let payload_data = body_payload.0;
+/// Middleware rewriting the request path to the case of the route it matches.
+///
+/// The URI as sent is stored in the `OriginalUri` extension first, unless an
+/// outer router already did, so the request log keeps reporting it. Paths
+/// matching no route, static files included, are left as they are.
+///
+/// # Parameters
+///
+/// * `State(patterns)` - The route patterns, split when the router was built
+/// * `request` - The incoming request, its query string preserved
+/// * `next` - The router
+///
+/// # Returns
+///
+/// The response of the route matching the rewritten path
+async fn fold_path_case(
+    State(patterns): State<Arc<CasedPatterns>>,
+    mut request: Request,
+    next: Next,
+) -> Response {
+    let folded = patterns.fold(request.uri().path());
 
-    // Phase 2: Update data (write lock)
-    {
-        let mut state_writer = match state.db.write() {
-            Ok(writer) => writer,
-            Err(_) => {
-                return response(
-                    HeaderMap::new(),
-                    StatusCode::EXPECTATION_FAILED,
-                    &json!({"error": "Unable to write to database"}),
-                );
-            }
+    if let Some(path) = folded.filter(|path| path != request.uri().path()) {
+        let path_and_query = match request.uri().query() {
+            Some(query) => format!("{path}?{query}"),
+            None => path,
         };
 
-        match state_writer.update_model_data(&route_identifier, payload_data.clone()) {
-            Ok(_) => {
-                info!("✔︎ Model data updated: {route_identifier}");
-
-                // Sync with GET model
-                let get_identifier = format!("[GET] {route_path}");
-                if state_writer
-                    .update_model_data(&get_identifier, payload_data)
-                    .is_ok()
-                {
-                    info!("✔︎ GET Model data updated: {get_identifier}");
+        let mut parts = request.uri().clone().into_parts();
+        if let Ok(path_and_query) = path_and_query.parse() {
+            parts.path_and_query = Some(path_and_query);
+            if let Ok(uri) = Uri::from_parts(parts) {
+                debug!("Folded path case: {} -> {}", request.uri(), uri);
+                if request.extensions().get::<OriginalUri>().is_none() {
+                    let original = OriginalUri(request.uri().clone());
+                    request.extensions_mut().insert(original);
                 }
-            }
-            Err(e) => {
-                info!("⚠︎ Failed to update model data: {route_identifier}");
-                debug!("Update model error: {:?}", e);
+                *request.uri_mut() = uri;
             }
         }
-    } // Write lock dropped
+    }
 
-    // Phase 3: Get response data (new read lock)
-    let state_reader = match state.db.read() {
-        Ok(reader) => reader,
-        Err(_) => {
-            return response(
-                headers,
-                StatusCode::INTERNAL_SERVER_ERROR,
-                &json!({"error": "Unable to read updated data"}),
-            );
-        }
-    };
+    next.run(request).await
+}
 
-    if let Some(model) = state_reader.get_model(&route_identifier) {
-        if !params.is_empty() {
-            if let Some(data) = model.find_entry_by_hashmap(params) {
-                return response(headers, status, &data);
-            }
-        }
+/// Wraps the whole router, static files and fallback included, in the request
+/// recorder when `record_requests` is set, the gRPC guard, the request id and,
+/// when it is enabled, the `debug_headers` middleware.
+fn with_router_layers(router: Router, state: Arc<AppState>) -> Router {
+    let router = if state.record_requests {
+        router.layer(middleware::from_fn_with_state(
+            Arc::clone(&state),
+            record_requests,
+        ))
+    } else {
+        router
+    };
+    let router =
+        router
+            .layer(middleware::from_fn(reject_grpc))
+            .layer(middleware::from_fn_with_state(
+                Arc::clone(&state),
+                propagate_request_id,
+            ));
 
-        let response_body = model.get_data();
-        return response(headers, status, &response_body.as_value());
+    if state.debug_headers {
+        info!("Debug headers enabled");
+        return router.layer(middleware::from_fn_with_state(state, debug_headers));
     }
 
-    response(
-        headers,
-        StatusCode::NOT_FOUND,
-        &json!({"error": "Model not found"}),
-    )
+    router
 }
 
-/// Creates an HTTP response with the appropriate content type and format.
-///
-/// This function generates HTTP responses by examining the provided headers
-/// to determine the appropriate response format (JSON, Form, HTML, or plain text).
-/// It handles different content types and serializes the data accordingly.
+/// Checks that the configured static folder exists and is a directory.
 ///
 /// # Parameters
 ///
-/// * `headers` - HTTP headers that will be included in the response
-/// * `status` - HTTP status code for the response
-/// * `data` - JSON value containing the response data to be serialized
+/// * `path` - The static folder, resolved against the configuration root
 ///
 /// # Returns
 ///
-/// A configured `Response` object ready to be sent to the client
+/// * `Ok(())` - If the path is an existing directory
+/// * `Err(String)` - A message naming the absolute path and what is wrong with it
+fn check_static_folder(path: &std::path::Path) -> Result<(), String> {
+    let absolute = std::path::absolute(path).unwrap_or_else(|_| path.to_path_buf());
+
+    match std::fs::metadata(&absolute) {
+        Ok(metadata) if metadata.is_dir() => Ok(()),
+        Ok(_) => Err(format!(
+            "Static folder {} is not a directory",
+            absolute.display()
+        )),
+        Err(_) => Err(format!(
+            "Static folder {} does not exist",
+            absolute.display()
+        )),
+    }
+}
+
+/// Methods and settings collected for a single path while building the router.
+#[derive(Default)]
+struct PathRoutes {
+    /// The handlers registered for every configured method of the path
+    method_router: MethodRouter<Arc<AppState>>,
+    /// The methods registered for the path, advertised to CORS preflight requests
+    methods: Vec<Method>,
+    /// CORS settings overriding the global ones for this path, if any
+    cors: Option<CorsConfig>,
+    /// Whether a route of the path sets `disable_keep_alive`
+    close_connection: bool,
+}
+
+/// The largest request body read whole, in bytes, by the handlers.
+const REQUEST_BODY_LIMIT: usize = 2 * 1024 * 1024;
+
+/// Header carrying the number of requests served, added when `debug_headers` is set.
+const ECHO_REQUEST_COUNT: HeaderName = HeaderName::from_static("x-echo-request-count");
+
+/// Header carrying the handler duration in milliseconds, added when `debug_headers` is set.
+const ECHO_RESPONSE_TIME: HeaderName = HeaderName::from_static("x-echo-response-time-ms");
+
+/// HTTP methods advertised to CORS preflight requests for paths that are not configured.
+const DEFAULT_CORS_METHODS: [Method; 6] = [
+    Method::GET,
+    Method::POST,
+    Method::PUT,
+    Method::PATCH,
+    Method::DELETE,
+    Method::OPTIONS,
+];
+
+/// Adds a method to the methods registered for a path, once.
+fn with_method(mut methods: Vec<Method>, method: Method) -> Vec<Method> {
+    if !methods.contains(&method) {
+        methods.push(method);
+    }
+    methods
+}
+
+/// Builds a CORS layer from the given settings.
 ///
-/// # Behavior
+/// Without settings the layer is permissive: the path's methods, any header
+/// and any origin, with credentials disabled. Omitted fields of the settings
+/// fall back to those same defaults.
 ///
-/// The function examines the `content-type` header to determine output format:
-/// - `application/x-www-form-urlencoded` - Returns form-encoded data
-/// - `text/html` - Returns HTML content (extracts string from JSON)
-/// - `text/plain` - Returns plain text (extracts string from JSON)
-/// - Default - Returns JSON-encoded data
+/// # Parameters
 ///
-/// # Content Type Handling
+/// * `cors` - Optional CORS settings from the global or route configuration
+/// * `methods` - The methods configured for the path, advertised together with
+///   `OPTIONS` unless `allowed_methods` is set
 ///
-/// - **Form Data**: Wraps the JSON value in `axum::extract::Form`
-/// - **HTML**: Extracts string content and wraps in `axum::response::Html`
-/// - **Plain Text**: Extracts string content and returns as plain text
-/// - **JSON**: Default format using `axum::Json` wrapper
+/// # Returns
 ///
-/// # Examples
+/// A `CorsLayer` ready to be applied to a router or method router
 ///
-/// ```rust
-/// use axum::http::{HeaderMap, StatusCode};
-/// use serde_json::json;
+/// # Credentials
 ///
-/// let headers = HeaderMap::new();
-/// let data = json!({"message": "Hello World"});
-/// let response = response(headers, StatusCode::OK, &data);
-/// // Returns JSON response with status 200
-/// ```
+/// Wildcards cannot be combined with credentials, so when credentials are
+/// allowed a wildcard origin, method or header list mirrors the request
+/// instead of answering `*`.
 ///
-/// For HTML responses:
-/// ```rust
-/// let mut headers = HeaderMap::new();
-/// headers.insert("content-type", "text/html".parse().unwrap());
-/// let data = json!("<h1>Hello World</h1>");
-/// let response = response(headers, StatusCode::OK, &data);
-/// // Returns HTML response
-/// ```
-fn response(headers: HeaderMap, status: StatusCode, data: &Value) -> Response {
-    // Check header content type and use axum (Json, Form or simple text)
-    if let Some(content_type) = headers.get("content-type") {
-        if let Ok(header_type) = content_type.to_str() {
-            if header_type.starts_with("application/x-www-form-urlencoded") {
-                let response_data = axum::extract::Form(data.clone());
-                debug!("Model Data: {:?}", response_data);
-                info!("Response Status: {}", status);
-                return (status, headers, response_data).into_response();
-            } else if header_type.starts_with("text/html") {
-                let string_data = match data {
-                    Value::String(value) => value,
-                    _ => "",
-                };
+/// # Preflight Caching
+///
+/// `Access-Control-Max-Age` is only sent when `max_age_secs` is set, so
+/// browsers keep re-checking preflights, and pick up edited settings, unless
+/// asked to cache them.
+fn cors_layer(cors: Option<&CorsConfig>, methods: &[Method]) -> CorsLayer {
+    let path_methods = with_method(methods.to_vec(), Method::OPTIONS);
 
-                let response_data = axum::response::Html(string_data.to_string());
-                debug!("Model Data: {:?}", response_data);
-                info!("Response Status: {}", status);
-                return (status, headers, response_data).into_response();
-            } else if header_type.starts_with("text/plain") {
-                let response_data = match data {
-                    Value::String(value) => value,
-                    _ => "",
-                };
+    let Some(cors) = cors else {
+        return CorsLayer::new()
+            .allow_methods(path_methods)
+            .allow_headers(Any)
+            .allow_origin(Any)
+            .allow_credentials(false);
+    };
 
-                debug!("Model Data: {:?}", response_data.to_string());
-                info!("Response Status: {}", status);
-                return (status, headers, response_data.to_string()).into_response();
-            }
+    let credentials = cors.allow_credentials;
+    let is_wildcard = |values: &Vec<String>| values.iter().any(|value| value == "*");
+
+    let origins = match &cors.allowed_origins {
+        Some(origins) if !is_wildcard(origins) => AllowOrigin::list(
+            origins
+                .iter()
+                .filter_map(|origin| origin.parse::<HeaderValue>().ok()),
+        ),
+        _ if credentials => {
+            warn!("CORS credentials enabled with any origin, mirroring the request origin");
+            AllowOrigin::mirror_request()
         }
+        _ => AllowOrigin::any(),
+    };
+
+    let methods = match &cors.allowed_methods {
+        None => AllowMethods::list(path_methods),
+        Some(methods) if !is_wildcard(methods) => AllowMethods::list(
+            methods
+                .iter()
+                .filter_map(|method| method.to_uppercase().parse::<Method>().ok()),
+        ),
+        Some(_) if credentials => AllowMethods::mirror_request(),
+        Some(_) => AllowMethods::any(),
+    };
+
+    let headers = match &cors.allowed_headers {
+        Some(headers) if !is_wildcard(headers) => AllowHeaders::list(
+            headers
+                .iter()
+                .filter_map(|header| header.parse::<HeaderName>().ok()),
+        ),
+        _ if credentials => AllowHeaders::mirror_request(),
+        _ => AllowHeaders::any(),
+    };
+
+    let layer = CorsLayer::new()
+        .allow_origin(origins)
+        .allow_methods(methods)
+        .allow_headers(headers)
+        .allow_credentials(credentials);
+
+    match cors.max_age_secs {
+        Some(seconds) => layer.max_age(Duration::from_secs(seconds)),
+        None => layer,
     }
+}
 
-    (status, headers, axum::Json(data)).into_response()
+/// Middleware delaying a route's response according to its `delay` and `latency` settings.
+///
+/// The request is handled first so the delay can be picked from the status
+/// the handler computed; the response is then held back for that long, plus
+/// a latency sampled from the route's distribution. Routes without either,
+/// and preflight requests answered by the CORS layer, are passed through
+/// untouched.
+///
+/// # Parameters
+///
+/// * `State(state)` - Shared application state containing the route configurations
+/// * `request` - The incoming request, matched against a configured route
+/// * `next` - The remaining middleware and the route handler
+///
+/// # Returns
+///
+/// The handler's response, sent after the configured delay
+async fn delay_response(
+    State(state): State<Arc<AppState>>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let (delay, latency) = request
+        .extensions()
+        .get::<MatchedPath>()
+        .and_then(|path| {
+            let db = state.read();
+            let route = db.get_route(path.as_str(), Some(request.method().to_string()))?;
+            let latency = route.latency.map(|latency| {
+                let identifier = format!("[{}] {}", request.method(), path.as_str());
+                latency.sample(&mut seeded_rng(
+                    latency.seed,
+                    state.next_sample(&identifier),
+                ))
+            });
+            Some((route.delay.clone(), latency))
+        })
+        .unwrap_or_default();
+
+    let response = next.run(request).await;
+
+    let delay = delay.and_then(|delay| delay.for_status(response.status().as_u16()));
+    if delay.is_some() || latency.is_some() {
+        let duration = delay.unwrap_or_default() + latency.unwrap_or_default();
+        debug!("Delaying {} response by {:?}", response.status(), duration);
+        tokio::time::sleep(duration).await;
+    }
+
+    response
 }
 
-/// Extracts the URL path from a route pattern string.
+/// Returns whether a content type belongs to gRPC or gRPC-Web.
 ///
-/// This function parses route pattern strings that may contain HTTP method
-/// prefixes in square brackets and returns the clean URL path portion.
-/// It handles both bracketed patterns and plain path strings.
+/// Matches `application/grpc`, `application/grpc-web`, `application/grpc-web-text`
+/// and their `+proto` or `+json` variants, case-insensitively.
+fn is_grpc(content_type: &str) -> bool {
+    let essence = media_type(content_type);
+    let base = essence.split('+').next().unwrap_or_default();
+
+    matches!(
+        base,
+        "application/grpc" | "application/grpc-web" | "application/grpc-web-text"
+    )
+}
+
+/// Middleware rejecting gRPC and gRPC-Web requests with `501 Not Implemented`.
+///
+/// gRPC clients frame their messages in a binary envelope that JSON handlers
+/// cannot read, so they are answered with a clear error instead of a route
+/// match attempting JSON handling. Every other request is passed through.
 ///
 /// # Parameters
 ///
-/// * `pattern` - The route pattern string to parse, may include method prefix
+/// * `request` - The incoming request
+/// * `next` - The remaining middleware and the route handler
 ///
 /// # Returns
 ///
-/// A string slice containing the extracted URL path
+/// A `501` with a JSON error body for gRPC requests, otherwise the inner response
+async fn reject_grpc(request: Request, next: Next) -> Response {
+    let grpc = request
+        .headers()
+        .get(CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(is_grpc);
+
+    if grpc {
+        warn!(
+            "⚠︎ Rejected gRPC request to {}: gRPC is not supported",
+            request.uri().path()
+        );
+        return (
+            StatusCode::NOT_IMPLEMENTED,
+            axum::Json(json!({"error": "gRPC and gRPC-Web requests are not supported"})),
+        )
+            .into_response();
+    }
+
+    next.run(request).await
+}
+
+/// Middleware logging every request at debug level, sensitive values redacted.
 ///
-/// # Pattern Format
+/// The method, URI and headers are logged before the request is handled, with
+/// the values of the configured `redact_headers` replaced with `***`. Every
+/// event emitted while the request is handled carries its `request_id`. The
+/// layer runs after routing, so the method is the one the route was matched
+/// with, after any method override; the URI is the one the client sent, read
+/// from the `OriginalUri` extension, before case folding or slash trimming.
 ///
-/// The function expects patterns in one of these formats:
-/// - `[METHOD] /path/to/endpoint` - Method prefix with path
-/// - `/path/to/endpoint` - Plain path without method prefix
+/// With body logging enabled, the request and response bodies are logged too,
+/// cut at the configured limit and with the configured `redact_fields`
+/// masked. The bodies are captured as they stream through, so logging never
+/// refuses or holds back a request; a body over `CAPTURE_LIMIT` is logged by
+/// its size alone, and streamed NDJSON responses are not logged.
 ///
-/// # Behavior
+/// # Parameters
 ///
-/// The function:
-/// 1. Searches for the closing bracket `]` in the pattern
-/// 2. If found, extracts everything after the bracket and trims whitespace
-/// 3. If no bracket found, returns the original pattern unchanged
+/// * `State(logs)` - The request log's state, holding the application state
+/// * `request` - The incoming request, matched to a route
+/// * `next` - The remaining middleware and the route handler
+///
+/// # Returns
+///
+/// The response of the inner layers, unchanged
+async fn log_request(State(logs): State<Arc<RouteLogs>>, request: Request, next: Next) -> Response {
+    // A route's log_level caps every event of its requests, the layers' and the handler's
+    let level = route_log_level(&logs.state.read(), &request);
+    let state = Arc::clone(&logs.state);
+
+    match level {
+        Some(level) => {
+            log_request_events(state, request, next)
+                .with_subscriber(logs.dispatch(level).clone())
+                .await
+        }
+        None => log_request_events(state, request, next).await,
+    }
+}
+
+/// Logs a request and its bodies, see `log_request`.
+async fn log_request_events(state: Arc<AppState>, request: Request, next: Next) -> Response {
+    let request_id = request
+        .extensions()
+        .get::<RequestId>()
+        .map(RequestId::as_str)
+        .unwrap_or_default()
+        .to_string();
+    let span = tracing::debug_span!("request", request_id = %request_id);
+
+    if tracing::enabled!(tracing::Level::DEBUG) {
+        debug!(
+            parent: &span,
+            request_id = %request_id,
+            "Request {} {} headers: {:?}",
+            request.method(),
+            request
+                .extensions()
+                .get::<OriginalUri>()
+                .map_or(request.uri(), |original| &original.0),
+            state.redactor().headers(request.headers())
+        );
+    }
+
+    let Some(limit) = state
+        .log_bodies
+        .filter(|_| tracing::enabled!(tracing::Level::DEBUG))
+    else {
+        return next.run(request).instrument(span).await;
+    };
+
+    let (parts, body) = request.into_parts();
+    let body = LoggedBody::request(body, Arc::clone(&state), limit, span.clone());
+    let request = Request::from_parts(parts, Body::new(body));
+
+    let response = next.run(request).instrument(span.clone()).await;
+    if is_streamed(&response) {
+        return response;
+    }
+
+    let (parts, body) = response.into_parts();
+    let body = LoggedBody::response(body, parts.status, state, limit, span);
+
+    Response::from_parts(parts, Body::new(body))
+}
+
+/// Middleware asking the client to close the connection after the response.
+///
+/// Sets `Connection: close`, which hyper honours by shutting the connection
+/// down once the response is written instead of keeping it alive for the
+/// next request.
+///
+/// # Parameters
+///
+/// * `request` - The incoming request
+/// * `next` - The remaining middleware and the route handler
+///
+/// # Returns
+///
+/// The response of the inner layers, carrying `Connection: close`
+async fn close_connection(request: Request, next: Next) -> Response {
+    let mut response = next.run(request).await;
+    response
+        .headers_mut()
+        .insert(CONNECTION, HeaderValue::from_static("close"));
+
+    response
+}
+
+/// Middleware adding request count and timing headers to a response.
+///
+/// Adds `X-Echo-Request-Count`, the number of requests served by this
+/// instance including this one, and `X-Echo-Response-Time-Ms`, the time the
+/// inner layers took to produce the response, configured delays included.
+///
+/// # Parameters
+///
+/// * `State(state)` - Shared application state holding the request counter
+/// * `request` - The incoming request
+/// * `next` - The remaining middleware and the route handler
+///
+/// # Returns
+///
+/// The response of the inner layers, carrying the debug headers
+async fn debug_headers(
+    State(state): State<Arc<AppState>>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let count = state.record_request();
+    let started = tokio::time::Instant::now();
+
+    let mut response = next.run(request).await;
+
+    // The handler time excludes streaming the body to the client
+    let elapsed = started.elapsed().as_secs_f64() * 1000.0;
+    let headers = response.headers_mut();
+    headers.insert(ECHO_REQUEST_COUNT, HeaderValue::from(count));
+    if let Ok(value) = HeaderValue::from_str(&format!("{elapsed:.3}")) {
+        headers.insert(ECHO_RESPONSE_TIME, value);
+    }
+
+    response
+}
+
+/// Middleware answering OPTIONS requests to undefined routes with `204 No Content`.
+///
+/// Preflights are answered by the global CORS layer, with its headers and
+/// a `200`; other OPTIONS requests reach `handler_404`, which already answers
+/// them with `204`. Both end up alike, so clients probing arbitrary paths
+/// see the same response whether or not they send a preflight.
+///
+/// # Parameters
+///
+/// * `request` - The unmatched request
+/// * `next` - The global CORS layer and `handler_404`
+///
+/// # Returns
+///
+/// The response of the inner layers, with a `204` status for successful OPTIONS requests
+async fn no_content_options(request: Request, next: Next) -> Response {
+    let is_options = request.method() == Method::OPTIONS;
+    let mut response = next.run(request).await;
+
+    if is_options && response.status() == StatusCode::OK {
+        *response.status_mut() = StatusCode::NO_CONTENT;
+    }
+
+    response
+}
+
+/// Fallback handler for undefined routes (404 Not Found).
+///
+/// This handler is called when a request is made to a route that is not
+/// defined in the router configuration. OPTIONS requests are answered with an
+/// empty `204`, to which the global CORS layer adds its headers. A path under
+/// a configured fallback prefix is answered with that fallback's JSON
+/// response; any other path gets a simple 404 status with a descriptive
+/// message.
+///
+/// # Parameters
+///
+/// * `State(state)` - Shared application state holding the fallbacks
+/// * `method` - The method of the unmatched request
+/// * `uri` - The URI of the unmatched request
+///
+/// # Returns
+///
+/// `204 No Content` for OPTIONS requests, the fallback response of the
+/// longest matching prefix, otherwise an HTTP response with status 404 and
+/// a JSON error naming the path, or the text `No route defined` with
+/// `plain_text_errors`
 ///
 /// # Examples
 ///
-/// ```rust
-/// assert_eq!(extract_path("[GET] /users"), "/users");
-/// assert_eq!(extract_path("[POST] /api/data"), "/api/data");
-/// assert_eq!(extract_path("/simple/path"), "/simple/path");
-/// assert_eq!(extract_path("[DELETE] /users/:id"), "/users/:id");
+/// When a client requests a non-existent route like `/undefined-path`,
+/// this handler will return:
 /// ```
+/// HTTP/1.1 404 Not Found
+/// Content-Type: application/json
 ///
-/// # Use Cases
-///
-/// This function is typically used when:
-/// - Processing route configurations that include HTTP methods
-/// - Converting internal route representations to Axum-compatible paths
-/// - Cleaning route patterns for router registration
-fn extract_path(pattern: &str) -> &str {
-    if let Some(end) = pattern.find(']') {
-        let path = &pattern[end + 1..].trim();
-        return path;
+/// {"error":"Not Found","path":"/undefined-path"}
+/// ```
+async fn handler_404(State(state): State<Arc<AppState>>, method: Method, uri: Uri) -> Response {
+    if method == Method::OPTIONS {
+        return StatusCode::NO_CONTENT.into_response();
     }
-    pattern
+
+    if let Some(fallback) = state.fallback(uri.path()) {
+        let status =
+            StatusCode::from_u16(fallback.response.status).unwrap_or(StatusCode::NOT_FOUND);
+        let headers = response_headers(None, None, &HeaderMap::new(), state.charset(None));
+        return response(headers, status, &fallback.response.body);
+    }
+
+    if state.plain_text_errors {
+        return (StatusCode::NOT_FOUND, "No route defined").into_response();
+    }
+
+    let headers = response_headers(None, None, &HeaderMap::new(), state.charset(None));
+    response(
+        headers,
+        StatusCode::NOT_FOUND,
+        &json!({"error": "Not Found", "path": uri.path()}),
+    )
 }
 
-#[allow(clippy::ignored_unit_patterns)]
-async fn shutdown_signal() {
-    let ctrl_c = async {
-        signal::ctrl_c()
-            .await
-            .expect("failed to install Ctrl+C handler");
-    };
+/// Fallback handler for methods a configured path has no route for (405 Method Not Allowed).
+///
+/// The router adds the `Allow` header, listing the methods the path is
+/// configured with, to the response of this handler.
+///
+/// # Parameters
+///
+/// * `State(state)` - Shared application state holding the error format
+/// * `method` - The method of the unmatched request
+/// * `uri` - The URI of the unmatched request
+///
+/// # Returns
+///
+/// An HTTP response with status 405 and a JSON error naming the method and
+/// path, or an empty body with `plain_text_errors`
+///
+/// # Examples
+///
+/// ```
+/// HTTP/1.1 405 Method Not Allowed
+/// Allow: GET,HEAD
+/// Content-Type: application/json
+///
+/// {"error":"Method Not Allowed","method":"DELETE","path":"/api/users"}
+/// ```
+async fn handler_405(State(state): State<Arc<AppState>>, method: Method, uri: Uri) -> Response {
+    if state.plain_text_errors {
+        return StatusCode::METHOD_NOT_ALLOWED.into_response();
+    }
 
-    #[cfg(unix)]
-    let terminate = async {
-        signal::unix::signal(signal::unix::SignalKind::terminate())
-            .expect("failed to install signal handler")
-            .recv()
-            .await;
-    };
+    let headers = response_headers(None, None, &HeaderMap::new(), state.charset(None));
+    response(
+        headers,
+        StatusCode::METHOD_NOT_ALLOWED,
+        &json!({"error": "Method Not Allowed", "method": method.as_str(), "path": uri.path()}),
+    )
+}
 
-    #[cfg(not(unix))]
-    let terminate = std::future::pending::<()>();
+/// HTTP GET request handler that serves mock data based on route configuration.
+///
+/// This handler processes GET requests by looking up the corresponding model
+/// in the database and returning appropriate mock data. It supports both
+/// parameterized requests (returning specific entries) and general requests
+/// (returning all data).
+///
+/// # Parameters
+///
+/// * `Path(params)` - Path parameters extracted from the URL
+/// * `State(state)` - Shared application state containing the database
+/// * `Query(query_params)` - Query string parameters from the request
+/// * `uri` - The full URI of the request
+/// * `req` - The complete HTTP request object
+/// * `request_headers` - Headers of the incoming request, used to render header templates
+///
+/// # Returns
+///
+/// An HTTP response containing:
+/// - JSON data from the model if found
+/// - Specific entry if path parameters match
+/// - All model data if no specific parameters
+/// - 404 error if route or model not found
+///
+/// # Behavior
+///
+/// The handler follows this logic:
+/// 1. Extracts the matched route path from request extensions
+/// 2. Looks up the corresponding model in the database
+/// 3. Validates query parameters against the route's `query_params`, answering 400 on failure
+/// 4. Serves the next `sequence` entry instead of the model data, when configured,
+///    counting polls per job on job status routes, or else a response of the
+///    weighted `responses` pool, picked reproducibly when `_seed` is given
+/// 5. Serves the `localized` body of the best language of `Accept-Language`,
+///    with `Content-Language` set, when one is accepted
+/// 6. If path parameters are provided, searches for a specific entry
+/// 7. Returns the specific entry if found, or all model data otherwise, cut to
+///    as many items as calls served on `grow` routes, then filtered, sorted and
+///    paged by the collection query, wrapped back in its body with `wrap_results`
+/// 8. Returns appropriate error responses for missing routes/models
+///
+/// # Response Format
+///
+/// Successful responses return JSON data with status 200:
+/// ```json
+/// {
+///   "id": 1,
+///   "name": "example",
+///   "data": "value"
+/// }
+/// ```
+///
+/// Error responses return JSON with appropriate status codes:
+/// ```json
+/// {
+///   "error": "Route is not defined"
+/// }
+/// ```
+///
+/// # Examples
+///
+/// ```
+/// GET /users -> Returns all users
+/// GET /users/123 -> Returns user with ID 123 (if found)
+/// GET /undefined -> Returns 404 error
+/// ```
+#[allow(clippy::too_many_lines)]
+async fn get_handler(
+    State(state): State<Arc<AppState>>,
+    Path(params): Path<HashMap<String, String>>,
+    uri_path: Uri,
+    path: MatchedPath,
+    Query(mut query_params): Query<HashMap<String, String>>,
+    request_headers: HeaderMap,
+) -> Response {
+    info!("[GET] request called: {}", uri_path.path());
+
+    let route_path = path.as_str();
+
+    // Relation parameters are reserved, so they never fail query validation
+    let embed = query_params.remove("_embed");
+    let expand = query_params.remove("_expand");
+    let seed = match query_params.remove("_seed").map(|seed| seed.parse::<u64>()) {
+        Some(Ok(seed)) => Some(seed),
+        Some(Err(_)) => {
+            return response(
+                HeaderMap::new(),
+                StatusCode::BAD_REQUEST,
+                &json!({"error": "_seed must be a non-negative integer"}),
+            );
+        }
+        None => None,
+    };
+
+    // Snapshot the model and headers so the database guard is released before
+    // the response is serialized; cloning a model only clones a pointer to its data
+    let (
+        model,
+        headers,
+        query_check,
+        job_poll,
+        sequenced,
+        localized,
+        grown,
+        stream,
+        collection,
+        wrap,
+    ) = {
+        let state_reader = state.read();
+
+        let identifier = format!("[GET] {route_path}");
+        let model = state_reader
+            .get_model(state_reader.resolve_alias(&identifier))
+            .cloned();
+        let route = state_reader.get_route(route_path, Some(String::from("GET")));
+
+        debug!("Model: {:?}", model);
+        debug!("Route Config: {:?}", route);
+
+        let mut headers = response_headers(
+            route.and_then(|route| route.headers.as_ref()),
+            route.and_then(|route| route.content_type.as_deref()),
+            &request_headers,
+            state.charset(route.and_then(|route| route.charset.as_deref())),
+        );
+        // Localized routes answer differently per language, so caches must key on it
+        if route.is_some_and(|route| route.localized.is_some()) {
+            add_vary(&mut headers, "accept-language");
+        }
+        // Collection parameters are taken out first, so they never fail query validation
+        let collection = CollectionQuery::from_params(&mut query_params, |name| {
+            route
+                .and_then(|route| route.query_params.as_ref())
+                .is_some_and(|declared| declared.contains_key(name))
+        });
+        let query_check = route.map_or(Ok(()), |route| route.validate_query(&query_params));
+        let wrap = route.is_some_and(|route| route.wrap_results);
+
+        // Job status routes count polls per job instead of per route; an
+        // unknown job id has no poll to serve
+        let job_poll = (state.is_job_route(route_path) && query_check.is_ok()).then(|| {
+            params
+                .values()
+                .next()
+                .and_then(|id| state.next_job_poll(route_path, id))
+        });
+
+        // Sequenced routes serve their next entry instead of the model data;
+        // rejected queries do not count as a call
+        let sequenced = route
+            .filter(|route| route.sequence.is_some() && query_check.is_ok())
+            .and_then(|route| {
+                let call = match job_poll {
+                    Some(poll) => poll?,
+                    None => state.next_call(&identifier),
+                };
+                route.sequence_response(call)
+            })
+            .cloned()
+            .or_else(|| {
+                // Weighted pools pick a response per call, rolled from the seed and call count
+                route
+                    .filter(|route| {
+                        route.responses.is_some() && route.sequence.is_none() && query_check.is_ok()
+                    })
+                    .and_then(|route| {
+                        let roll = seeded_roll(seed, state.next_call(&identifier));
+                        route.weighted_response(roll)
+                    })
+                    .cloned()
+            });
+
+        // Localized routes serve the body of the best accepted language instead of the model data
+        let accept_language = request_headers
+            .get(ACCEPT_LANGUAGE)
+            .and_then(|value| value.to_str().ok())
+            .unwrap_or_default();
+        let localized = route
+            .filter(|_| query_check.is_ok())
+            .and_then(|route| route.localized_body(accept_language))
+            .map(|(tag, body)| (tag.to_string(), body.as_value()));
+
+        // Growing collections serve one more item per call; sequences keep their own count
+        let grown = route
+            .filter(|route| route.grow && route.sequence.is_none() && query_check.is_ok())
+            .map(|_| state.next_call(&identifier) + 1);
+
+        let stream = route
+            .filter(|route| route.stream)
+            .map(|route| StreamSettings {
+                results_field: route.results_field.clone(),
+                pause: Duration::from_millis(route.stream_interval_ms.unwrap_or(0)),
+            });
+
+        (
+            model,
+            headers,
+            query_check,
+            job_poll,
+            sequenced,
+            localized,
+            grown,
+            stream,
+            collection,
+            wrap,
+        )
+    }; // Snapshot guard dropped
+
+    debug!("Headers Config: {:?}", state.redactor().headers(&headers));
+
+    // Reject queries that do not match the route's parameter specification
+    if let Err(error) = query_check {
+        info!("⚠︎ Invalid query for {}: {}", route_path, error);
+        return response(headers, StatusCode::BAD_REQUEST, &json!({"error": error}));
+    }
+
+    let collection = match collection {
+        Ok(collection) => collection,
+        Err(error) => {
+            info!("⚠︎ Invalid collection query for {}: {}", route_path, error);
+            return response(headers, StatusCode::BAD_REQUEST, &json!({"error": error}));
+        }
+    };
+
+    if job_poll == Some(None) {
+        info!("⚠︎ Unknown job polled: {}", uri_path.path());
+        return response(
+            headers,
+            StatusCode::NOT_FOUND,
+            &json!({"error": "Job not found"}),
+        );
+    }
+
+    if let Some(entry) = sequenced {
+        let status = entry
+            .status
+            .and_then(|status| StatusCode::from_u16(status).ok())
+            .unwrap_or(StatusCode::OK);
+
+        return response(headers, status, &entry.body.as_value());
+    }
+
+    if let Some((language, body)) = localized {
+        let status = model
+            .as_ref()
+            .and_then(Model::get_status)
+            .and_then(|status| StatusCode::from_u16(status).ok())
+            .unwrap_or(StatusCode::OK);
+        let mut headers = headers;
+        if let Ok(value) = HeaderValue::from_str(&language) {
+            headers.insert(CONTENT_LANGUAGE, value);
+        }
+
+        return response(headers, status, &body);
+    }
+
+    if let Some(model) = model {
+        let http_status = model.get_status().unwrap_or(StatusCode::OK.as_u16());
+        let status = StatusCode::from_u16(http_status).unwrap_or(StatusCode::OK);
+
+        let entry = if params.is_empty() {
+            None
+        } else {
+            model.find_entry_by_hashmap(params)
+        };
+
+        if embed.is_some() || expand.is_some() {
+            let is_collection = entry.is_none();
+            let data = entry.unwrap_or_else(|| match grown {
+                Some(count) => first_items(&model.get_value(), count),
+                None => model.get_value().into_owned(),
+            });
+            let identifier = format!("[GET] {route_path}");
+
+            return match join_relations(
+                &state,
+                &identifier,
+                data,
+                embed.as_deref(),
+                expand.as_deref(),
+            ) {
+                Ok(joined) if is_collection => {
+                    let (shaped, slice) =
+                        shaped_collection(&model, Cow::Owned(joined), &collection, wrap);
+                    let mut headers = headers;
+                    let status = slice_status(&mut headers, status, slice);
+                    get_response(headers, status, &shaped, stream.as_ref())
+                }
+                Ok(joined) => get_response(headers, status, &joined, stream.as_ref()),
+                Err(error) => {
+                    info!("⚠︎ Invalid relation for {}: {}", route_path, error);
+                    response(headers, StatusCode::BAD_REQUEST, &json!({"error": error}))
+                }
+            };
+        }
+
+        if let Some(data) = entry {
+            return get_response(headers, status, &data, stream.as_ref());
+        }
+
+        // Serialize straight from the shared data instead of cloning the body
+        let data = match grown {
+            Some(count) => Cow::Owned(first_items(&model.get_value(), count)),
+            None => model.get_value(),
+        };
+        let (response_body, slice) = shaped_collection(&model, data, &collection, wrap);
+        let mut headers = headers;
+        let status = slice_status(&mut headers, status, slice);
+
+        return get_response(headers, status, &response_body, stream.as_ref());
+    }
+
+    response(
+        headers,
+        StatusCode::NOT_FOUND,
+        &json!({"error": "Model not found"}),
+    )
+}
+
+/// Returns a collection filtered, sorted and paged by a query, wrapped back in its body if asked.
+///
+/// Collections an empty query leaves alone are passed through borrowed, as
+/// is data that is not an array.
+///
+/// # Parameters
+///
+/// * `model` - The model serving the collection, holding its body and `results_field`
+/// * `data` - The collection, its `results_field` already extracted
+/// * `query` - The filters, sort, page and slice of the request
+/// * `wrap` - Whether the collection goes back under `results_field` in the whole body
+///
+/// # Returns
+///
+/// The shaped collection, or the whole body holding it when `wrap` is set and
+/// the body has the `results_field`, along with the `Slice` served when the
+/// query asks for one
+fn shaped_collection<'a>(
+    model: &Model,
+    data: Cow<'a, Value>,
+    query: &CollectionQuery,
+    wrap: bool,
+) -> (Cow<'a, Value>, Option<Slice>) {
+    let (data, slice) = if query.is_empty() {
+        (data, None)
+    } else {
+        match query.apply(&data) {
+            Some((items, slice)) => (Cow::Owned(items), slice),
+            None => (data, None),
+        }
+    };
+
+    let Some(results_field) = model.get_results_field().filter(|_| wrap) else {
+        return (data, slice);
+    };
+    let mut body = model.get_shared_data().body.as_value();
+    match body.as_object_mut() {
+        Some(object) if object.contains_key(results_field) => {
+            object.insert(results_field.clone(), data.into_owned());
+            (Cow::Owned(body), slice)
+        }
+        _ => (data, slice),
+    }
+}
+
+/// Adds the `X-Total-Count` of a sliced collection, answering `206` when the slice is partial.
+///
+/// # Parameters
+///
+/// * `headers` - The headers of the collection response
+/// * `status` - The status configured for the route
+/// * `slice` - The slice served, if the query asked for one
+///
+/// # Returns
+///
+/// The status to answer with: `206 Partial Content` for a `200` route whose
+/// slice served some but not all matching items, otherwise `status`
+fn slice_status(headers: &mut HeaderMap, status: StatusCode, slice: Option<Slice>) -> StatusCode {
+    let Some(slice) = slice else {
+        return status;
+    };
+
+    headers.insert(TOTAL_COUNT_HEADER, HeaderValue::from(slice.total));
+    if slice.partial && status == StatusCode::OK {
+        StatusCode::PARTIAL_CONTENT
+    } else {
+        status
+    }
+}
+
+/// Returns the first items of a collection, or the data itself when it is not an array.
+///
+/// # Parameters
+///
+/// * `data` - The collection served by the route, its `results_field` already extracted
+/// * `count` - The number of items to keep
+///
+/// # Examples
+///
+/// ```rust
+/// assert_eq!(first_items(&json!([1, 2, 3]), 2), json!([1, 2]));
+/// assert_eq!(first_items(&json!([1, 2, 3]), 5), json!([1, 2, 3]));
+/// ```
+fn first_items(data: &Value, count: usize) -> Value {
+    match data {
+        Value::Array(items) => Value::Array(items.iter().take(count).cloned().collect()),
+        other => other.clone(),
+    }
+}
+
+/// Returns the roll of a call, in `[0, 1)`, picking weighted responses.
+///
+/// The roll is the SplitMix64 hash of the seed and the route's call count, so
+/// a fresh server given the same seed rolls the same values in the same
+/// order. Calls without a seed roll from a random one.
+///
+/// # Parameters
+///
+/// * `seed` - The `_seed` query parameter of the request or the `latency.seed` of the route, if any
+/// * `call` - The number of calls the route served before this one
+///
+/// # Examples
+///
+/// ```rust
+/// assert_eq!(seeded_roll(Some(7), 0), seeded_roll(Some(7), 0));
+/// assert_ne!(seeded_roll(Some(7), 0), seeded_roll(Some(7), 1));
+/// ```
+#[allow(clippy::cast_precision_loss)]
+fn seeded_roll(seed: Option<u64>, call: usize) -> f64 {
+    // The top 53 bits fill the mantissa of a float in [0, 1)
+    (seeded_hash(seed, call) >> 11) as f64 / (1_u64 << 53) as f64
+}
+
+/// Returns the random number generator latencies of a call are sampled with.
+///
+/// The generator is seeded like `seeded_roll`, so a fresh server given the
+/// same seed samples the same latencies in the same order.
+///
+/// # Parameters
+///
+/// * `seed` - The `latency.seed` of the route, if any
+/// * `sample` - The number of latencies the route sampled before this one
+fn seeded_rng(seed: Option<u64>, sample: usize) -> StdRng {
+    StdRng::seed_from_u64(seeded_hash(seed, sample))
+}
+
+/// Returns the SplitMix64 hash of a seed, random when unset, and a call count.
+fn seeded_hash(seed: Option<u64>, call: usize) -> u64 {
+    let seed = seed.unwrap_or_else(|| Uuid::new_v4().as_u64_pair().0);
+
+    let mut z = seed.wrapping_add(
+        (call as u64)
+            .wrapping_add(1)
+            .wrapping_mul(0x9E37_79B9_7F4A_7C15),
+    );
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^ (z >> 31)
+}
+
+/// How a streaming route yields its data.
+///
+/// # Fields
+///
+/// * `results_field` - The field holding the streamed array when the data is an object
+/// * `pause` - The time between two streamed items
+struct StreamSettings {
+    /// The field holding the streamed array when the data is an object
+    results_field: Option<String>,
+    /// The time between two streamed items
+    pause: Duration,
+}
+
+/// Answers GET data, streamed as NDJSON when the route streams an array.
+///
+/// # Parameters
+///
+/// * `headers` - The route's response headers
+/// * `status` - The status code of the response
+/// * `data` - The entry, collection or joined data to serve
+/// * `stream` - The route's stream settings, if it streams
+///
+/// # Returns
+///
+/// A streaming NDJSON response, or the regular response for the data
+fn get_response(
+    headers: HeaderMap,
+    status: StatusCode,
+    data: &Value,
+    stream: Option<&StreamSettings>,
+) -> Response {
+    let streamed = stream.and_then(|settings| {
+        stream_items(data, settings.results_field.as_deref()).map(|items| (items, settings.pause))
+    });
+
+    match streamed {
+        Some((items, pause)) => {
+            debug!("Streaming {} items every {:?}", items.len(), pause);
+            ndjson_response(headers, status, items, pause)
+        }
+        None => response(headers, status, data),
+    }
+}
+
+/// Attaches the relations requested through `_embed` and `_expand` to GET data.
+///
+/// Both parameters take a comma separated list of relation names declared in
+/// the route's `relations`. The join reads a single database snapshot, so the
+/// related collections are consistent with each other.
+///
+/// # Parameters
+///
+/// * `state` - Shared application state containing the database
+/// * `identifier` - The `[GET] path` identifier of the requested route
+/// * `data` - The entry or collection about to be served
+/// * `embed` - The `_embed` query parameter, if present
+/// * `expand` - The `_expand` query parameter, if present
+///
+/// # Returns
+///
+/// * `Ok(Value)` - The data with the related entries attached
+/// * `Err(String)` - If a requested relation is not declared by the route
+fn join_relations(
+    state: &AppState,
+    identifier: &str,
+    data: Value,
+    embed: Option<&str>,
+    expand: Option<&str>,
+) -> Result<Value, String> {
+    state.read().join_relations(
+        identifier,
+        data,
+        &relation_names(embed),
+        &relation_names(expand),
+    )
+}
+
+/// Splits a comma separated `_embed` or `_expand` value into relation names.
+fn relation_names(list: Option<&str>) -> Vec<&str> {
+    list.into_iter()
+        .flat_map(|list| list.split(','))
+        .map(str::trim)
+        .filter(|name| !name.is_empty())
+        .collect()
+}
+
+/// HTTP POST request handler that processes incoming data and serves mock responses.
+///
+/// This handler processes POST requests by accepting JSON payloads and returning
+/// appropriate mock data based on route configuration. It supports payload
+/// validation and dynamic response generation.
+///
+/// # Parameters
+///
+/// * `Path(params)` - Path parameters extracted from the URL
+/// * `State(state)` - Shared application state containing the database
+/// * `request` - The request, whose body is read as JSON, as
+///   `application/x-www-form-urlencoded` or as `multipart/form-data`
+/// * `req` - The complete HTTP request object
+/// * `request_headers` - Headers of the incoming request, used to render header templates
+///
+/// # Returns
+///
+/// An HTTP response containing:
+/// - JSON data from the model if found
+/// - Confirmation of data processing if successful
+/// - 404 error if route or model not found
+///
+/// # Behavior
+///
+/// The handler follows this logic:
+/// 1. Extracts the matched route path from request extensions, following an
+///    alias to the route it points at
+/// 2. Looks up the corresponding model in the database
+/// 3. Processes the incoming JSON payload if provided, reading form-urlencoded
+///    bodies as an object of string fields
+/// 4. Saves the body under `save_uploads_to`, or answers multipart uploads with
+///    a description of their fields and files
+/// 5. Fills `fields_spec` defaults, answering 400 when a required field is missing
+/// 6. Answers with the first `variants` entry whose `when_body` matches the payload
+/// 7. Answers `job` routes with `202 Accepted` and the `Location` of the started job
+/// 8. Answers `echo` routes with the received payload, leaving the model untouched
+/// 9. Generates a missing id with the `id_strategy` of the write route, or else the GET route
+/// 10. Returns appropriate mock response based on configuration
+/// 11. Returns error responses for missing routes/models
+///
+/// # Examples
+///
+/// ```
+/// POST /users -> Creates/processes user data
+/// POST /api/data -> Processes API data submission
+/// ```
+#[allow(clippy::too_many_lines)]
+async fn add_update_handler(
+    State(state): State<Arc<AppState>>,
+    Path(params): Path<HashMap<String, String>>,
+    method: Method,
+    uri_path: Uri,
+    path: MatchedPath,
+    request_headers: HeaderMap,
+    request: Request,
+) -> Response {
+    let http_method = &method.as_str().to_uppercase();
+
+    info!("[{}] request called: {}", http_method, uri_path.path());
+
+    // Writes through an alias are stored as writes to the route it points at
+    let route_identifier = {
+        let requested = format!("[{http_method}] {}", path.as_str());
+        let state_reader = state.read();
+        let target = state_reader.resolve_alias(&requested);
+        if target == requested {
+            requested
+        } else {
+            target.to_string()
+        }
+    };
+    let route_path = route_identifier
+        .split_once("] ")
+        .map_or(path.as_str(), |(_, route_path)| route_path);
+
+    let save_uploads_to = state
+        .read()
+        .get_route(&route_identifier, None)
+        .and_then(|route| route.save_uploads_to.clone());
+    if save_uploads_to.is_some() && state.files().is_none() {
+        warn!("⚠︎ No file system to save uploads of {}", route_identifier);
+    }
+
+    // Uploads are saved under `save_uploads_to`, or described back to the client
+    let (upload, body_payload) = if let Some(directory) = save_uploads_to
+        && let Some(files) = state.files()
+    {
+        let saved = if is_multipart(&request_headers) {
+            match Multipart::from_request(request, &state).await {
+                Ok(multipart) => save_multipart(files, &directory, multipart).await,
+                Err(rejection) => return rejection.into_response(),
+            }
+        } else {
+            save_body(files, &directory, request.into_body()).await
+        };
+
+        match saved {
+            Ok(metadata) => (Some(metadata), Json(json!({}))),
+            Err(error @ UploadError::FileSystem(_)) => {
+                return state.internal_error(&error.to_string());
+            }
+            Err(error) => {
+                return response(
+                    HeaderMap::new(),
+                    StatusCode::BAD_REQUEST,
+                    &json!({"error": error.to_string()}),
+                );
+            }
+        }
+    } else if is_multipart(&request_headers) {
+        let upload = match Multipart::from_request(request, &state).await {
+            Ok(multipart) => upload_metadata(multipart).await,
+            Err(rejection) => return rejection.into_response(),
+        };
+
+        match upload {
+            Ok(metadata) => (Some(metadata), Json(json!({}))),
+            Err(error) => {
+                return response(
+                    HeaderMap::new(),
+                    StatusCode::BAD_REQUEST,
+                    &json!({"error": error.body_text()}),
+                );
+            }
+        }
+    } else if is_form_urlencoded(&request_headers) {
+        match Form::<Vec<(String, String)>>::from_request(request, &state).await {
+            Ok(Form(fields)) => (None, Json(form_object(fields))),
+            Err(rejection) => return rejection.into_response(),
+        }
+    } else {
+        match Option::<Json<Value>>::from_request(request, &state).await {
+            Ok(payload) => (None, payload.unwrap_or(Json(json!({})))),
+            Err(rejection) => return rejection.into_response(),
+        }
+    };
+
+    // First, get the route configuration and model info from the current snapshot
+    let (
+        model_exists,
+        route_headers,
+        content_type,
+        charset,
+        model_status,
+        prepared,
+        variant,
+        echoed,
+        job,
+        results_field,
+        wrapper,
+    ) = {
+        let state_reader = state.read();
+
+        let model = state_reader
+            .get_model(&format!("[GET] {route_path}"))
+            .or_else(|| state_reader.get_model(&route_identifier));
+        let route_config = state_reader
+            .get_route(route_path, Some(String::from("GET")))
+            .or_else(|| state_reader.get_route(&route_identifier, None));
+
+        debug!("Route Config: {:?}", route_config);
+        debug!("Payload: {}", state.redactor().value(&body_payload));
+
+        let model_exists = model.is_some();
+        let route_headers = route_config.and_then(|rc| rc.headers.clone());
+        let content_type = route_config.and_then(|rc| rc.content_type.clone());
+        let charset = state.charset(route_config.and_then(|rc| rc.charset.as_deref()));
+        let model_status = model.map(|m| m.get_status().unwrap_or(StatusCode::OK.as_u16()));
+
+        // Unwrap the stored part, then fill field defaults and check required
+        // fields before anything is merged
+        let write_route = state_reader.get_route(&route_identifier, None);
+        let prepared = match write_route {
+            Some(route) => route
+                .unwrap_request(body_payload.0)
+                .and_then(|payload| route.apply_fields_spec(payload)),
+            None => Ok(body_payload.0),
+        };
+        let variant = match (write_route, &prepared) {
+            (Some(route), Ok(payload)) => route.variant_response(payload).cloned(),
+            _ => None,
+        };
+        let echoed = match (write_route, &prepared) {
+            (Some(route), Ok(payload)) if route.echo => Some(route.echo_body(payload.clone())),
+            _ => None,
+        };
+        let job = write_route.and_then(|route| route.job.clone());
+        // Kept past the snapshot only when the stored data is answered wrapped
+        let wrapper = write_route
+            .filter(|route| route.response_wrap.is_some())
+            .cloned();
+
+        // Both synced models write into the same collection: the write route's
+        // results_field, or the GET route's when the write route declares none
+        let results_field = write_route
+            .and_then(|route| route.results_field.clone())
+            .or_else(|| {
+                state_reader
+                    .get_route(route_path, Some(String::from("GET")))
+                    .and_then(|route| route.results_field.clone())
+            });
+
+        (
+            model_exists,
+            route_headers,
+            content_type,
+            charset,
+            model_status,
+            prepared,
+            variant,
+            echoed,
+            job,
+            results_field,
+            wrapper,
+        )
+    };
+
+    if !model_exists {
+        return response(
+            HeaderMap::new(),
+            StatusCode::NOT_FOUND,
+            &json!({"error": "Model not found"}),
+        );
+    }
+
+    // Configure headers
+    let headers = response_headers(
+        route_headers.as_ref(),
+        content_type.as_deref(),
+        &request_headers,
+        charset,
+    );
+
+    debug!("Headers Config: {:?}", state.redactor().headers(&headers));
+
+    let http_status = model_status.unwrap_or(StatusCode::OK.as_u16());
+    let status = StatusCode::from_u16(http_status).unwrap_or(StatusCode::OK);
+
+    if let Some(metadata) = upload {
+        info!("✔︎ Upload received: {route_identifier}");
+        return response(headers, status, &metadata);
+    }
+
+    let payload_data = match prepared {
+        Ok(payload) => payload,
+        Err(error) => {
+            info!("⚠︎ Invalid payload for {}: {}", route_identifier, error);
+            return response(headers, StatusCode::BAD_REQUEST, &json!({"error": error}));
+        }
+    };
+
+    // Variants answer with the response matching the payload, leaving the model untouched
+    if let Some(variant) = variant {
+        let status = variant
+            .status
+            .and_then(|status| StatusCode::from_u16(status).ok())
+            .unwrap_or(StatusCode::OK);
+        info!("✔︎ Variant {} answered: {route_identifier}", status.as_u16());
+
+        return response(headers, status, &variant.body.as_value());
+    }
+
+    // Job routes hand out a status URL instead of storing the payload
+    if let Some(job) = job {
+        let id = state.start_job(&job.location);
+        let location = job.location_for(&id);
+        info!("✔︎ Job started: {} at {}", route_identifier, location);
+
+        let mut headers = headers;
+        if let Ok(value) = HeaderValue::from_str(&location) {
+            headers.insert(LOCATION, value);
+        }
+        return response(headers, StatusCode::ACCEPTED, &json!({"id": id}));
+    }
+
+    // Echo routes reflect the payload without touching the stored data
+    if let Some(echoed) = echoed {
+        info!("✔︎ Echoing payload: {route_identifier}");
+        return response(headers, status, &echoed);
+    }
+    let get_identifier = format!("[GET] {route_path}");
+    // Writes to a single entry carrying If-Match only apply to the entry they validated
+    let preconditions = (matches!(method, Method::PUT | Method::PATCH) && !params.is_empty())
+        .then(|| request_headers.get(IF_MATCH)?.to_str().ok())
+        .flatten();
+    // A PUT creates a missing results_field as the collection holding the entry
+    let write = if method == Method::PUT {
+        Database::put_model_data_in
+    } else {
+        Database::update_model_data_in
+    };
+
+    // Phase 2: Update a copy of the database and swap it in, keeping a snapshot
+    // of the updated model so the response reflects exactly this write. The
+    // precondition is checked on the same copy, so no write slips in between
+    let updated = state.update(|db| {
+        if let Some(header) = preconditions
+            && !write_precondition(db, route_path, &params, header)
+        {
+            return None;
+        }
+
+        // Generate a missing id once, so both synced models store the same entry
+        let payload_data = db.assign_id(
+            &route_identifier,
+            payload_data.clone(),
+            results_field.as_deref(),
+        );
+        let payload_data = db.assign_id(&get_identifier, payload_data, results_field.as_deref());
+
+        let update_result = write(
+            db,
+            &route_identifier,
+            payload_data.clone(),
+            results_field.as_deref(),
+        );
+
+        // Sync with GET model
+        let synced = update_result.is_ok()
+            && write(
+                db,
+                &get_identifier,
+                payload_data.clone(),
+                results_field.as_deref(),
+            )
+            .is_ok();
+
+        Some((
+            update_result,
+            synced,
+            db.get_model(&route_identifier).cloned(),
+        ))
+    });
+    let Some((update_result, synced, model)) = updated else {
+        return precondition_failed();
+    };
+
+    match update_result {
+        Ok(()) => {
+            info!("✔︎ Model data updated: {route_identifier}");
+            if synced {
+                info!("✔︎ GET Model data updated: {get_identifier}");
+            }
+        }
+        Err(e) => {
+            info!("⚠︎ Failed to update model data: {route_identifier}");
+            debug!("Update model error: {:?}", e);
+        }
+    }
+
+    if let Some(model) = model {
+        let entry = if params.is_empty() {
+            None
+        } else {
+            model.find_entry_by_hashmap(params)
+        };
+        let data = entry.map_or_else(|| model.get_value(), Cow::Owned);
+
+        if let Some(route) = &wrapper {
+            return response(headers, status, &route.wrap_response(data.into_owned()));
+        }
+        return response(headers, status, &data);
+    }
+
+    response(
+        headers,
+        StatusCode::NOT_FOUND,
+        &json!({"error": "Model not found"}),
+    )
+}
+
+/// Returns whether the request carries a `multipart/form-data` body.
+fn is_multipart(request_headers: &HeaderMap) -> bool {
+    has_content_type(request_headers, "multipart/form-data")
+}
+
+/// Returns whether the request carries an `application/x-www-form-urlencoded` body.
+fn is_form_urlencoded(request_headers: &HeaderMap) -> bool {
+    has_content_type(request_headers, "application/x-www-form-urlencoded")
+}
+
+/// Builds the JSON object merged for a form-urlencoded body.
+///
+/// Fields are kept as strings, as a browser submits them, in the order they
+/// were sent. A field sent more than once, such as `tag=a&tag=b`, becomes an
+/// array of its values in order.
+///
+/// # Parameters
+///
+/// * `fields` - The decoded name and value pairs, in body order
+///
+/// # Returns
+///
+/// The object of the fields
+fn form_object(fields: Vec<(String, String)>) -> Value {
+    let mut object = serde_json::Map::new();
+
+    for (name, value) in fields {
+        let value = Value::String(value);
+        match object.get_mut(&name) {
+            Some(Value::Array(values)) => values.push(value),
+            Some(first) => *first = Value::Array(vec![first.take(), value]),
+            None => {
+                object.insert(name, value);
+            }
+        }
+    }
+
+    Value::Object(object)
+}
+
+/// Returns whether the request's `Content-Type` is the given media type, parameters aside.
+fn has_content_type(request_headers: &HeaderMap, media_type: &str) -> bool {
+    request_headers
+        .get(CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|value| {
+            value
+                .trim_start()
+                .to_ascii_lowercase()
+                .starts_with(media_type)
+        })
+}
+
+/// Reads every part of a multipart body and describes it as JSON.
+///
+/// Text fields are echoed with their value, while file parts are reported by
+/// their field name, file name, content type and size in bytes. File contents
+/// are read to measure them and then discarded.
+///
+/// # Parameters
+///
+/// * `multipart` - The multipart body of the request
+///
+/// # Returns
+///
+/// * `Ok(Value)` - An object with `fields` and `files` entries
+/// * `Err(MultipartError)` - If the body is not valid multipart data
+///
+/// # Response Format
+///
+/// ```json
+/// {
+///   "fields": {"title": "Holiday"},
+///   "files": [
+///     {"field": "photo", "filename": "beach.png", "content_type": "image/png", "size": 2048}
+///   ]
+/// }
+/// ```
+async fn upload_metadata(mut multipart: Multipart) -> Result<Value, MultipartError> {
+    let mut fields = serde_json::Map::new();
+    let mut files = Vec::new();
+
+    while let Some(field) = multipart.next_field().await? {
+        let name = field.name().unwrap_or_default().to_string();
+
+        if let Some(filename) = field.file_name().map(str::to_string) {
+            let content_type = field.content_type().map(str::to_string);
+            let size = field.bytes().await?.len();
+
+            files.push(json!({
+                "field": name,
+                "filename": filename,
+                "content_type": content_type,
+                "size": size,
+            }));
+        } else {
+            fields.insert(name, Value::String(field.text().await?));
+        }
+    }
+
+    Ok(json!({"fields": fields, "files": files}))
+}
+
+/// Builds the response of a route configured as a redirect.
+///
+/// # Parameters
+///
+/// * `redirect` - The redirect settings of the route
+///
+/// # Returns
+///
+/// An empty response with the redirect status and a `Location` header
+fn redirect_response(redirect: &RedirectConfig) -> Response {
+    let status = StatusCode::from_u16(redirect.status).unwrap_or(StatusCode::FOUND);
+
+    let mut headers = HeaderMap::new();
+    if let Ok(location) = HeaderValue::from_str(&redirect.to) {
+        headers.insert(LOCATION, location);
+    }
+
+    (status, headers).into_response()
+}
+
+/// Builds the response of a route configured as an error.
+///
+/// Every error route renders the same envelope. The code and message default
+/// to the status reason, e.g. `SERVICE_UNAVAILABLE` and `Service Unavailable`,
+/// and `details` are only included when configured:
+///
+/// ```json
+/// {"error": {"status": 503, "code": "MAINTENANCE", "message": "Back soon"}}
+/// ```
+///
+/// # Parameters
+///
+/// * `error` - The error settings of the route
+/// * `headers` - The response headers of the route
+///
+/// # Returns
+///
+/// The error response with the configured status and the error envelope
+fn error_response(error: &ErrorConfig, headers: HeaderMap) -> Response {
+    let status = StatusCode::from_u16(error.status).unwrap_or(StatusCode::INTERNAL_SERVER_ERROR);
+    let reason = status.canonical_reason().unwrap_or("Error");
+
+    let mut envelope = json!({
+        "status": status.as_u16(),
+        "code": error
+            .code
+            .clone()
+            .unwrap_or_else(|| reason.to_uppercase().replace([' ', '-'], "_")),
+        "message": error.message.as_deref().unwrap_or(reason),
+    });
+    if let Some(details) = &error.details {
+        envelope["details"] = details.clone();
+    }
+
+    response(headers, status, &json!({"error": envelope}))
+}
+
+/// Builds the response headers for a route.
+///
+/// Starts from the route's content type, JSON unless one is configured, and
+/// applies the configured route headers, rendering any template tokens against
+/// the incoming request headers. Headers whose name or rendered value is
+/// invalid are skipped. A configured charset is then set as the `charset`
+/// parameter of the resulting content type.
+///
+/// # Parameters
+///
+/// * `route_headers` - The headers configured for the route, if any
+/// * `content_type` - The `content_type` configured for the route, if any
+/// * `request_headers` - The headers of the incoming request
+/// * `charset` - The charset of the response, if one is configured
+///
+/// # Returns
+///
+/// The `HeaderMap` to send with the response
+fn response_headers(
+    route_headers: Option<&HashMap<String, String>>,
+    content_type: Option<&str>,
+    request_headers: &HeaderMap,
+    charset: Option<Charset>,
+) -> HeaderMap {
+    let mut headers = HeaderMap::new();
+    headers.insert(
+        CONTENT_TYPE,
+        content_type
+            .and_then(|content_type| HeaderValue::from_str(content_type).ok())
+            .unwrap_or(HeaderValue::from_static("application/json")),
+    );
+
+    for (key, value) in route_headers.into_iter().flatten() {
+        if let Ok(header_name) = key.parse::<HeaderName>()
+            && let Ok(header_value) = render_header_value(value, request_headers).parse()
+        {
+            headers.insert(header_name, header_value);
+        }
+    }
+
+    if let Some(charset) = charset
+        && let Some(content_type) = headers.get(CONTENT_TYPE).and_then(|v| v.to_str().ok())
+        && let Ok(labeled) = HeaderValue::from_str(&with_charset(content_type, charset))
+    {
+        headers.insert(CONTENT_TYPE, labeled);
+    }
+
+    headers
+}
+
+/// Adds a request header to the `Vary` header of a response.
+///
+/// A `Vary` configured in the route's `headers` is kept, with the header
+/// appended to its list unless it is already named there.
+///
+/// # Parameters
+///
+/// * `headers` - The response headers to update
+/// * `name` - The lowercase name of the request header the response depends on
+fn add_vary(headers: &mut HeaderMap, name: &'static str) {
+    let Some(existing) = headers.get(VARY) else {
+        headers.insert(VARY, HeaderValue::from_static(name));
+        return;
+    };
+    let Ok(existing) = existing.to_str() else {
+        return;
+    };
+
+    let named = existing
+        .split(',')
+        .map(str::trim)
+        .any(|listed| listed == "*" || listed.eq_ignore_ascii_case(name));
+    if !named && let Ok(vary) = HeaderValue::from_str(&format!("{existing}, {name}")) {
+        headers.insert(VARY, vary);
+    }
+}
+
+/// Creates an HTTP response with the appropriate content type and format.
+///
+/// This function generates HTTP responses by examining the provided headers
+/// to determine the appropriate response format (JSON, Form, HTML, or plain text).
+/// It handles different content types and serializes the data accordingly.
+///
+/// # Parameters
+///
+/// * `headers` - HTTP headers that will be included in the response
+/// * `status` - HTTP status code for the response
+/// * `data` - JSON value containing the response data to be serialized
+///
+/// # Returns
+///
+/// A configured `Response` object ready to be sent to the client
+///
+/// # Behavior
+///
+/// The function examines the `content-type` header to determine output format:
+/// - `application/x-www-form-urlencoded` - Returns form-encoded data
+/// - `text/html` - Returns HTML content (extracts string from JSON, stringifies scalars)
+/// - `text/plain` - Returns plain text (extracts string from JSON, stringifies scalars)
+/// - Default - Returns JSON-encoded data, bare scalars such as `42` or `true` included
+///
+/// The content type header is sent exactly as given. When its `charset`
+/// parameter names latin-1, text bodies are transcoded and JSON bodies have
+/// their non-ASCII characters escaped.
+///
+/// # Content Type Handling
+///
+/// - **Form Data**: Wraps the JSON value in `axum::extract::Form`
+/// - **HTML**: Extracts string content, or a stringified number or boolean, and wraps in `axum::response::Html`
+/// - **Plain Text**: Extracts string content, or a stringified number or boolean, and returns as plain text
+/// - **JSON**: Default format using `axum::Json` wrapper
+///
+/// # Examples
+///
+/// ```rust
+/// use axum::http::{HeaderMap, StatusCode};
+/// use serde_json::json;
+///
+/// let headers = HeaderMap::new();
+/// let data = json!({"message": "Hello World"});
+/// let response = response(headers, StatusCode::OK, &data);
+/// // Returns JSON response with status 200
+/// ```
+///
+/// For HTML responses:
+/// ```rust
+/// let mut headers = HeaderMap::new();
+/// headers.insert("content-type", "text/html".parse().unwrap());
+/// let data = json!("<h1>Hello World</h1>");
+/// let response = response(headers, StatusCode::OK, &data);
+/// // Returns HTML response
+/// ```
+fn response(headers: HeaderMap, status: StatusCode, data: &Value) -> Response {
+    // Check header content type and use axum (Json, Form or simple text)
+    if let Some(content_type) = headers.get("content-type")
+        && let Ok(header_type) = content_type.to_str()
+    {
+        let charset = Charset::from_content_type(header_type);
+
+        if header_type.starts_with("application/x-www-form-urlencoded") {
+            let response_data = axum::extract::Form(data.clone());
+            debug!("Model Data: {:?}", response_data);
+            info!("Response Status: {}", status);
+            return (status, headers, response_data).into_response();
+        } else if header_type.starts_with("text/html") {
+            let response_data = axum::response::Html(text_body(data));
+            debug!("Model Data: {:?}", response_data);
+            info!("Response Status: {}", status);
+            if charset == Some(Charset::Latin1) {
+                return (
+                    status,
+                    headers,
+                    encode_text(&response_data.0, Charset::Latin1),
+                )
+                    .into_response();
+            }
+            return (status, headers, response_data).into_response();
+        } else if header_type.starts_with("text/plain") {
+            let response_data = text_body(data);
+
+            debug!("Model Data: {:?}", response_data);
+            info!("Response Status: {}", status);
+            if charset == Some(Charset::Latin1) {
+                return (
+                    status,
+                    headers,
+                    encode_text(&response_data, Charset::Latin1),
+                )
+                    .into_response();
+            }
+            return (status, headers, response_data).into_response();
+        } else if charset == Some(Charset::Latin1) {
+            return (status, headers, encode_json(data, Charset::Latin1)).into_response();
+        }
+    }
+
+    (status, headers, axum::Json(data)).into_response()
+}
+
+/// Returns the text served for a body under a `text/*` content type.
+///
+/// Strings are served as they are and bare numbers and booleans are
+/// stringified, so `42` is served as `42`. Other bodies have no text form and
+/// are served empty.
+fn text_body(data: &Value) -> String {
+    match data {
+        Value::String(value) => value.clone(),
+        Value::Number(_) | Value::Bool(_) => data.to_string(),
+        Value::Null | Value::Array(_) | Value::Object(_) => String::new(),
+    }
+}
+
+/// Extracts the URL path from a route pattern string.
+///
+/// This function parses route pattern strings that may contain HTTP method
+/// prefixes in square brackets and returns the clean URL path portion.
+/// It handles both bracketed patterns and plain path strings.
+///
+/// # Parameters
+///
+/// * `pattern` - The route pattern string to parse, may include method prefix
+///
+/// # Returns
+///
+/// A string slice containing the extracted URL path
+///
+/// # Pattern Format
+///
+/// The function expects patterns in one of these formats:
+/// - `[METHOD] /path/to/endpoint` - Method prefix with path
+/// - `/path/to/endpoint` - Plain path without method prefix
+///
+/// # Behavior
+///
+/// The function:
+/// 1. Searches for the closing bracket `]` in the pattern
+/// 2. If found, extracts everything after the bracket and trims whitespace
+/// 3. If no bracket found, returns the original pattern unchanged
+///
+/// # Examples
+///
+/// ```rust
+/// assert_eq!(extract_path("[GET] /users"), "/users");
+/// assert_eq!(extract_path("[POST] /api/data"), "/api/data");
+/// assert_eq!(extract_path("/simple/path"), "/simple/path");
+/// assert_eq!(extract_path("[DELETE] /users/:id"), "/users/:id");
+/// ```
+///
+/// # Use Cases
+///
+/// This function is typically used when:
+/// - Processing route configurations that include HTTP methods
+/// - Converting internal route representations to Axum-compatible paths
+/// - Cleaning route patterns for router registration
+fn extract_path(pattern: &str) -> &str {
+    if let Some(end) = pattern.find(']') {
+        let path = &pattern[end + 1..].trim();
+        return path;
+    }
+    pattern
+}
+
+#[allow(clippy::ignored_unit_patterns)]
+async fn shutdown_signal() {
+    let ctrl_c = async {
+        signal::ctrl_c()
+            .await
+            .expect("failed to install Ctrl+C handler");
+    };
+
+    #[cfg(unix)]
+    let terminate = async {
+        signal::unix::signal(signal::unix::SignalKind::terminate())
+            .expect("failed to install signal handler")
+            .recv()
+            .await;
+    };
+
+    #[cfg(not(unix))]
+    let terminate = std::future::pending::<()>();
 
     tokio::select! {
         _ = ctrl_c => {},
         _ = terminate => {},
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{
+        body::{Body, to_bytes},
+        http::Request,
+    };
+    use json_echo_core::FileSystemManager;
+    use tower::ServiceExt;
+
+    /// Builds a router from an inline configuration.
+    ///
+    /// The configuration is written to a temporary directory and loaded through
+    /// the `ConfigManager` so route keys are normalized exactly as in `serve`.
+    async fn test_router(config: Value) -> Router {
+        test_router_with(config, |state| state).await
+    }
+
+    /// Builds a router from an inline configuration, adjusting the state first.
+    ///
+    /// Used for settings that come from command-line flags rather than the
+    /// configuration file.
+    async fn test_router_with(
+        config: Value,
+        customize: impl FnOnce(AppState) -> AppState,
+    ) -> Router {
+        let dir = tempfile::tempdir().expect("Failed to create temporary directory");
+        std::fs::write(dir.path().join("json-echo.json"), config.to_string())
+            .expect("Failed to write test config");
+
+        let file_system_manager = FileSystemManager::new(Some(dir.path().to_path_buf()))
+            .expect("Failed to create filesystem manager");
+        let mut config_manager = ConfigManager::new(file_system_manager);
+        config_manager
+            .load_config("json-echo.json")
+            .await
+            .expect("Failed to load test config");
+
+        let mut db = Database::new();
+        db.populate(config_manager.config.routes.clone());
+
+        let state = configured_state(db, &config_manager.config);
+        create_router(Arc::new(customize(state)), &config_manager)
+    }
+
+    /// Sends a request through the router without any network.
+    async fn send(router: &Router, request: Request<Body>) -> Response {
+        router
+            .clone()
+            .oneshot(request)
+            .await
+            .expect("Router should always respond")
+    }
+
+    /// Reads a response body as JSON.
+    async fn body_json(response: Response) -> Value {
+        let bytes = to_bytes(response.into_body(), usize::MAX)
+            .await
+            .expect("Failed to read response body");
+        serde_json::from_slice(&bytes).expect("Response body should be JSON")
+    }
+
+    /// Sends a GET request to the given URI.
+    async fn get(router: &Router, uri: &str) -> Response {
+        get_with(router, uri, &[]).await
+    }
+
+    /// Sends a GET request carrying the given headers.
+    async fn get_with(router: &Router, uri: &str, headers: &[(HeaderName, &str)]) -> Response {
+        let mut request = Request::builder().uri(uri);
+        for (name, value) in headers {
+            request = request.header(name, *value);
+        }
+        send(
+            router,
+            request
+                .body(Body::empty())
+                .expect("Failed to build request"),
+        )
+        .await
+    }
+
+    /// Sends a request without a body using the given method.
+    async fn call(router: &Router, method: &str, uri: &str) -> Response {
+        let request = Request::builder()
+            .method(method)
+            .uri(uri)
+            .body(Body::empty())
+            .expect("Failed to build request");
+        send(router, request).await
+    }
+
+    /// Builds a request carrying a JSON payload.
+    fn json_request(method: &str, uri: &str, payload: &Value) -> Request<Body> {
+        Request::builder()
+            .method(method)
+            .uri(uri)
+            .header(CONTENT_TYPE, "application/json")
+            .body(Body::from(payload.to_string()))
+            .expect("Failed to build request")
+    }
+
+    /// Posts a JSON payload to the given URI.
+    async fn post_json(router: &Router, uri: &str, payload: &Value) -> Response {
+        send(router, json_request("POST", uri, payload)).await
+    }
+
+    /// Sends `calls` GET requests and collects the response statuses.
+    async fn statuses(router: &Router, uri: &str, calls: usize) -> Vec<u16> {
+        let mut statuses = Vec::with_capacity(calls);
+        for _ in 0..calls {
+            statuses.push(get(router, uri).await.status().as_u16());
+        }
+        statuses
+    }
+
+    /// Returns the content type of a response, empty when it has none.
+    fn content_type(response: &Response) -> &str {
+        response
+            .headers()
+            .get(CONTENT_TYPE)
+            .and_then(|value| value.to_str().ok())
+            .unwrap_or_default()
+    }
+
+    /// Reads a response body as text.
+    async fn body_text(response: Response) -> String {
+        let bytes = to_bytes(response.into_body(), usize::MAX)
+            .await
+            .expect("Failed to read response body");
+        String::from_utf8(bytes.to_vec()).expect("Response body should be UTF-8")
+    }
+
+    /// Reads a response status and JSON body.
+    async fn status_json(response: Response) -> (StatusCode, Value) {
+        (response.status(), body_json(response).await)
+    }
+
+    /// Sends a GET request and returns the content type and text body.
+    async fn fetch_text(router: &Router, uri: &str) -> (String, String) {
+        let response = get(router, uri).await;
+        let content_type = content_type(&response).to_string();
+        (content_type, body_text(response).await)
+    }
+
+    /// Log writer collecting formatted events into a shared buffer.
+    #[derive(Clone, Default)]
+    struct CapturedLogs(Arc<std::sync::Mutex<Vec<u8>>>);
+
+    impl std::io::Write for CapturedLogs {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            if let Ok(mut logs) = self.0.lock() {
+                logs.extend_from_slice(buf);
+            }
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    mod cors_tests {
+        use super::*;
+
+        /// Builds a config with a permissive global CORS and one restricted route.
+        fn cors_config() -> Value {
+            json!({
+                "routes": {
+                    "/api/public": {
+                        "response": {"status": 200, "body": {"scope": "public"}}
+                    },
+                    "/api/private": {
+                        "cors": {
+                            "allowed_origins": ["https://allowed.example"],
+                            "allow_credentials": true
+                        },
+                        "response": {"status": 200, "body": {"scope": "private"}}
+                    }
+                }
+            })
+        }
+
+        /// Builds a GET request carrying an Origin header.
+        fn request_from(path: &str, origin: &str) -> Request<Body> {
+            Request::builder()
+                .uri(path)
+                .header("Origin", origin)
+                .body(Body::empty())
+                .expect("Failed to build request")
+        }
+
+        /// Tests that the global permissive CORS applies to regular routes.
+        ///
+        /// Verifies that any origin receives a wildcard allow-origin header.
+        #[tokio::test]
+        async fn test_global_cors_allows_any_origin() {
+            let router = test_router(cors_config()).await;
+
+            let response = send(
+                &router,
+                request_from("/api/public", "https://other.example"),
+            )
+            .await;
+
+            assert_eq!(response.status(), StatusCode::OK);
+            assert_eq!(
+                response.headers().get("access-control-allow-origin"),
+                Some(&HeaderValue::from_static("*")),
+                "Public route should allow any origin"
+            );
+            assert_eq!(body_json(response).await, json!({"scope": "public"}));
+        }
+
+        /// Tests that a route-level CORS block overrides the global one.
+        ///
+        /// Verifies that the restricted route echoes only its allowed origin,
+        /// enables credentials, and omits the header for other origins.
+        #[tokio::test]
+        async fn test_route_cors_overrides_global() {
+            let router = test_router(cors_config()).await;
+
+            let allowed = send(
+                &router,
+                request_from("/api/private", "https://allowed.example"),
+            )
+            .await;
+            assert_eq!(allowed.status(), StatusCode::OK);
+            assert_eq!(
+                allowed.headers().get("access-control-allow-origin"),
+                Some(&HeaderValue::from_static("https://allowed.example")),
+                "Private route should allow its configured origin"
+            );
+            assert_eq!(
+                allowed.headers().get("access-control-allow-credentials"),
+                Some(&HeaderValue::from_static("true")),
+                "Private route should allow credentials"
+            );
+
+            let denied = send(
+                &router,
+                request_from("/api/private", "https://other.example"),
+            )
+            .await;
+            assert!(
+                denied
+                    .headers()
+                    .get("access-control-allow-origin")
+                    .is_none(),
+                "Private route should not allow other origins"
+            );
+        }
+
+        /// Tests that preflight requests are answered by the route-level layer.
+        ///
+        /// Verifies that an OPTIONS preflight to the restricted route reflects
+        /// the route's origin rather than the global wildcard.
+        #[tokio::test]
+        async fn test_route_cors_preflight() {
+            let router = test_router(cors_config()).await;
+
+            let request = Request::builder()
+                .method(Method::OPTIONS)
+                .uri("/api/private")
+                .header("Origin", "https://allowed.example")
+                .header("Access-Control-Request-Method", "GET")
+                .body(Body::empty())
+                .expect("Failed to build request");
+            let response = send(&router, request).await;
+
+            assert_eq!(
+                response.headers().get("access-control-allow-origin"),
+                Some(&HeaderValue::from_static("https://allowed.example")),
+                "Preflight should use the route's CORS settings"
+            );
+        }
+
+        /// Tests that preflight responses carry the configured max age.
+        ///
+        /// Verifies the global setting on a route without its own CORS block,
+        /// and that no max age is sent when none is configured.
+        #[tokio::test]
+        async fn test_preflight_max_age() {
+            let preflight = || {
+                Request::builder()
+                    .method(Method::OPTIONS)
+                    .uri("/api/users")
+                    .header("Origin", "https://app.example")
+                    .header("Access-Control-Request-Method", "GET")
+                    .body(Body::empty())
+                    .expect("Failed to build request")
+            };
+            let routes = json!({"/api/users": {"response": {"status": 200, "body": []}}});
+
+            let router = test_router(json!({
+                "cors": {"max_age_secs": 600},
+                "routes": routes
+            }))
+            .await;
+            let response = send(&router, preflight()).await;
+            assert_eq!(
+                response.headers().get("access-control-max-age"),
+                Some(&HeaderValue::from_static("600"))
+            );
+
+            let router = test_router(json!({"routes": routes})).await;
+            let response = send(&router, preflight()).await;
+            assert!(!response.headers().contains_key("access-control-max-age"));
+        }
+
+        /// Tests that OPTIONS requests to undefined paths answer 204 with CORS headers.
+        ///
+        /// Verifies both a preflight and a plain OPTIONS request, and that other
+        /// methods on the same path still answer 404.
+        #[tokio::test]
+        async fn test_options_on_undefined_path() {
+            let router = test_router(cors_config()).await;
+
+            let preflight = Request::builder()
+                .method(Method::OPTIONS)
+                .uri("/api/undefined")
+                .header("Origin", "https://other.example")
+                .header("Access-Control-Request-Method", "GET")
+                .body(Body::empty())
+                .expect("Failed to build request");
+            let response = send(&router, preflight).await;
+            assert_eq!(response.status(), StatusCode::NO_CONTENT);
+            assert_eq!(
+                response.headers().get("access-control-allow-origin"),
+                Some(&HeaderValue::from_static("*"))
+            );
+            assert!(
+                response
+                    .headers()
+                    .contains_key("access-control-allow-methods")
+            );
+
+            let options = Request::builder()
+                .method(Method::OPTIONS)
+                .uri("/api/undefined")
+                .header("Origin", "https://other.example")
+                .body(Body::empty())
+                .expect("Failed to build request");
+            let response = send(&router, options).await;
+            assert_eq!(response.status(), StatusCode::NO_CONTENT);
+            assert_eq!(
+                response.headers().get("access-control-allow-origin"),
+                Some(&HeaderValue::from_static("*"))
+            );
+
+            let response = send(
+                &router,
+                request_from("/api/undefined", "https://other.example"),
+            )
+            .await;
+            assert_eq!(response.status(), StatusCode::NOT_FOUND);
+        }
+
+        /// Tests that preflight requests advertise only the path's methods.
+        ///
+        /// Verifies that a GET-only path answers with `GET,OPTIONS`, while a
+        /// path serving several methods lists each of them.
+        #[tokio::test]
+        async fn test_preflight_advertises_path_methods() {
+            let router = test_router(json!({
+                "routes": {
+                    "/api/users": {
+                        "response": {"status": 200, "body": []}
+                    },
+                    "[POST] /api/orders": {
+                        "response": {"status": 201, "body": {}}
+                    },
+                    "[GET] /api/orders": {
+                        "response": {"status": 200, "body": []}
+                    }
+                }
+            }))
+            .await;
+
+            let preflight = |path: &str| {
+                Request::builder()
+                    .method(Method::OPTIONS)
+                    .uri(path)
+                    .header("Origin", "https://other.example")
+                    .header("Access-Control-Request-Method", "GET")
+                    .body(Body::empty())
+                    .expect("Failed to build request")
+            };
+
+            let response = send(&router, preflight("/api/users")).await;
+            assert_eq!(
+                response.headers().get("access-control-allow-methods"),
+                Some(&HeaderValue::from_static("GET,OPTIONS")),
+                "GET-only path should advertise GET and OPTIONS"
+            );
+
+            let response = send(&router, preflight("/api/orders")).await;
+            let methods = response
+                .headers()
+                .get("access-control-allow-methods")
+                .and_then(|value| value.to_str().ok())
+                .unwrap_or_default()
+                .split(',')
+                .collect::<std::collections::BTreeSet<_>>();
+            assert_eq!(methods, ["GET", "OPTIONS", "POST"].into());
+        }
+    }
+
+    mod header_template_tests {
+        use super::*;
+
+        /// Tests that configured header templates are rendered per request.
+        ///
+        /// Verifies that a request header is echoed back and that a UUID is
+        /// generated as the fallback when the request does not carry it.
+        #[tokio::test]
+        async fn test_request_id_is_echoed_or_generated() {
+            let router = test_router(json!({
+                "routes": {
+                    "/api/ping": {
+                        "headers": {
+                            "X-Request-Id": "{{request.header.X-Request-Id | uuid}}",
+                            "X-Client": "client-{{request.header.X-Client}}"
+                        },
+                        "response": {"status": 200, "body": {"pong": true}}
+                    }
+                }
+            }))
+            .await;
+
+            let echoed = send(
+                &router,
+                Request::builder()
+                    .uri("/api/ping")
+                    .header("X-Request-Id", "req-42")
+                    .body(Body::empty())
+                    .expect("Failed to build request"),
+            )
+            .await;
+
+            assert_eq!(echoed.status(), StatusCode::OK);
+            assert_eq!(
+                echoed.headers().get("x-request-id"),
+                Some(&HeaderValue::from_static("req-42")),
+                "Incoming request id should be echoed"
+            );
+            assert_eq!(
+                echoed.headers().get("x-client"),
+                Some(&HeaderValue::from_static("client-")),
+                "Missing request header should resolve to empty"
+            );
+
+            let generated = send(
+                &router,
+                Request::builder()
+                    .uri("/api/ping")
+                    .body(Body::empty())
+                    .expect("Failed to build request"),
+            )
+            .await;
+
+            let request_id = generated
+                .headers()
+                .get("x-request-id")
+                .and_then(|value| value.to_str().ok())
+                .expect("Request id header should be present");
+            assert!(
+                uuid::Uuid::parse_str(request_id).is_ok(),
+                "Absent request id should be generated: {request_id}"
+            );
+        }
+    }
+
+    mod concurrency_tests {
+        use super::*;
+
+        /// Tests many simultaneous GET requests against a large array route.
+        ///
+        /// Verifies that every concurrent request, interleaved with writes,
+        /// receives a complete and consistent body.
+        #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+        async fn test_concurrent_gets_are_consistent() {
+            let items: Vec<Value> = (0..500).map(|id| json!({"id": id})).collect();
+            let router = test_router(json!({
+                "routes": {
+                    "/api/items": {"response": {"status": 200, "body": items}},
+                    "[POST] /api/items": {"response": {"status": 201, "body": items}}
+                }
+            }))
+            .await;
+
+            let mut tasks = Vec::new();
+            for index in 0..64 {
+                let router = router.clone();
+                tasks.push(tokio::spawn(async move {
+                    if index % 16 == 0 {
+                        let request = Request::builder()
+                            .method("POST")
+                            .uri("/api/items")
+                            .header("Content-Type", "application/json")
+                            .body(Body::from(json!({"id": 1000 + index}).to_string()))
+                            .expect("Failed to build request");
+                        send(&router, request).await;
+                    }
+
+                    body_json(get(&router, "/api/items").await).await
+                }));
+            }
+
+            for task in tasks {
+                let body = task.await.expect("Request task should not panic");
+                let items = body.as_array().expect("Body should be an array");
+                assert!(
+                    (500..=504).contains(&items.len()),
+                    "Unexpected item count {}",
+                    items.len()
+                );
+                assert_eq!(items[..500], body_items_prefix()[..]);
+            }
+        }
+
+        /// Stress test mixing concurrent writers and readers.
+        ///
+        /// Verifies that writes swapped in concurrently are never lost and that
+        /// readers always observe a complete snapshot between the initial and
+        /// final state.
+        #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+        async fn test_concurrent_writes_are_not_lost() {
+            let router = test_router(json!({
+                "routes": {
+                    "/api/events": {"response": {"status": 200, "body": []}},
+                    "[POST] /api/events": {"response": {"status": 201, "body": []}}
+                }
+            }))
+            .await;
+
+            let get = || {
+                Request::builder()
+                    .uri("/api/events")
+                    .body(Body::empty())
+                    .expect("Failed to build request")
+            };
+
+            let mut writers = Vec::new();
+            for writer in 0..8 {
+                let router = router.clone();
+                writers.push(tokio::spawn(async move {
+                    for event in 0..25 {
+                        let request = Request::builder()
+                            .method("POST")
+                            .uri("/api/events")
+                            .header("Content-Type", "application/json")
+                            .body(Body::from(json!({"id": writer * 100 + event}).to_string()))
+                            .expect("Failed to build request");
+                        assert!(send(&router, request).await.status().is_success());
+                    }
+                }));
+            }
+
+            let mut readers = Vec::new();
+            for _ in 0..16 {
+                let router = router.clone();
+                readers.push(tokio::spawn(async move {
+                    let mut last_seen = 0;
+                    for _ in 0..25 {
+                        let body = body_json(send(&router, get()).await).await;
+                        let seen = body.as_array().map_or(usize::MAX, Vec::len);
+                        assert!(seen <= 200, "Reader saw an invalid snapshot: {body}");
+                        assert!(seen >= last_seen, "Snapshots should never go back");
+                        last_seen = seen;
+                    }
+                }));
+            }
+
+            for task in writers.into_iter().chain(readers) {
+                task.await.expect("Stress task should not panic");
+            }
+
+            let body = body_json(send(&router, get()).await).await;
+            let mut ids: Vec<u64> = body
+                .as_array()
+                .expect("Body should be an array")
+                .iter()
+                .filter_map(|event| event["id"].as_u64())
+                .collect();
+            ids.sort_unstable();
+            ids.dedup();
+            assert_eq!(ids.len(), 200, "Every write should be kept");
+        }
+
+        /// Returns the configured items every response must start with.
+        fn body_items_prefix() -> Vec<Value> {
+            (0..500).map(|id| json!({"id": id})).collect()
+        }
+    }
+
+    mod query_spec_tests {
+        use super::*;
+
+        /// Builds a router with a typed query specification on one route.
+        async fn query_router() -> Router {
+            test_router(json!({
+                "routes": {
+                    "/api/users": {
+                        "query_params": {
+                            "page": "int",
+                            "active": {"type": "bool", "required": true}
+                        },
+                        "response": {"status": 200, "body": [{"id": 1}]}
+                    }
+                }
+            }))
+            .await
+        }
+
+        /// Tests that a value failing type coercion is answered with a 400.
+        #[tokio::test]
+        async fn test_bad_int_is_rejected() {
+            let router = query_router().await;
+
+            let response = get(&router, "/api/users?active=true&page=two").await;
+            assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+            let body = body_json(response).await;
+            assert!(
+                body["error"]
+                    .as_str()
+                    .is_some_and(|error| error.contains("'page'")),
+                "Error should name the failing param: {body}"
+            );
+
+            let response = get(&router, "/api/users?active=true&page=2").await;
+            assert_eq!(response.status(), StatusCode::OK);
+        }
+
+        /// Tests that a missing required parameter is answered with a 400.
+        #[tokio::test]
+        async fn test_missing_required_param_is_rejected() {
+            let router = query_router().await;
+
+            let response = get(&router, "/api/users?page=1").await;
+            assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+            let body = body_json(response).await;
+            assert!(
+                body["error"]
+                    .as_str()
+                    .is_some_and(|error| error.contains("'active'")),
+                "Error should name the missing param: {body}"
+            );
+        }
+    }
+
+    mod delay_tests {
+        use super::*;
+        use json_echo_core::LatencyConfig;
+        use std::time::Duration;
+        use tokio::time::Instant;
+
+        /// Builds a router with a success and an error route sharing one delay config.
+        async fn delay_router() -> Router {
+            let delay = json!({"2xx": 200, "5xx": 20});
+            test_router(json!({
+                "routes": {
+                    "/api/ok": {
+                        "delay": delay,
+                        "response": {"status": 200, "body": {"ok": true}}
+                    },
+                    "/api/broken": {
+                        "delay": delay,
+                        "response": {"status": 500, "body": {"error": "boom"}}
+                    },
+                    "/api/fast": {
+                        "response": {"status": 200, "body": []}
+                    }
+                }
+            }))
+            .await
+        }
+
+        /// Sends a GET request and returns its status and the time it took.
+        ///
+        /// Tokio's clock is paused by the callers, so the elapsed time is the
+        /// time spent sleeping rather than wall-clock time.
+        async fn timed_get(router: &Router, uri: &str) -> (StatusCode, Duration) {
+            let started = Instant::now();
+            let response = get(router, uri).await;
+            (response.status(), started.elapsed())
+        }
+
+        /// Asserts the elapsed time matches a delay, allowing for the timer's
+        /// millisecond rounding.
+        fn assert_delayed(elapsed: Duration, delay: Duration) {
+            assert!(
+                elapsed >= delay && elapsed <= delay + Duration::from_millis(1),
+                "Expected a delay of {delay:?}, got {elapsed:?}"
+            );
+        }
+
+        /// Tests that a 200 response sleeps the success delay.
+        #[tokio::test]
+        async fn test_success_uses_success_delay() {
+            let router = delay_router().await;
+            tokio::time::pause();
+
+            let (status, elapsed) = timed_get(&router, "/api/ok").await;
+            assert_eq!(status, StatusCode::OK);
+            assert_delayed(elapsed, Duration::from_millis(200));
+        }
+
+        /// Tests that a 500 response sleeps the error delay.
+        #[tokio::test]
+        async fn test_error_uses_error_delay() {
+            let router = delay_router().await;
+            tokio::time::pause();
+
+            let (status, elapsed) = timed_get(&router, "/api/broken").await;
+            assert_eq!(status, StatusCode::INTERNAL_SERVER_ERROR);
+            assert_delayed(elapsed, Duration::from_millis(20));
+        }
+
+        /// Tests that routes without a delay respond immediately.
+        #[tokio::test]
+        async fn test_route_without_delay_is_immediate() {
+            let router = delay_router().await;
+            tokio::time::pause();
+
+            let (status, elapsed) = timed_get(&router, "/api/fast").await;
+            assert_eq!(status, StatusCode::OK);
+            assert_eq!(elapsed, Duration::ZERO);
+        }
+
+        /// Builds a router whose route samples latencies from the given distribution.
+        async fn latency_router(latency: Value) -> Router {
+            test_router(json!({
+                "routes": {
+                    "/api/slow": {
+                        "delay": 10,
+                        "latency": latency,
+                        "response": {"status": 200, "body": []}
+                    }
+                }
+            }))
+            .await
+        }
+
+        /// Tests that the sampled latency is slept on top of the delay.
+        #[tokio::test]
+        async fn test_latency_adds_to_delay() {
+            let router =
+                latency_router(json!({"distribution": "log_normal", "mean": 100, "stddev": 0}))
+                    .await;
+            tokio::time::pause();
+
+            let (status, elapsed) = timed_get(&router, "/api/slow").await;
+            assert_eq!(status, StatusCode::OK);
+            assert_delayed(elapsed, Duration::from_millis(110));
+        }
+
+        /// Tests that seeded latencies vary per request and repeat on a fresh server.
+        #[tokio::test]
+        async fn test_seeded_latency_repeats() {
+            let latency = json!({"distribution": "percentiles", "p50": 80, "p90": 200, "p99": 800, "seed": 7});
+            let mut runs = Vec::new();
+            for _ in 0..2 {
+                let router = latency_router(latency.clone()).await;
+                tokio::time::pause();
+                let mut elapsed = Vec::new();
+                for _ in 0..5 {
+                    elapsed.push(timed_get(&router, "/api/slow").await.1);
+                }
+                tokio::time::resume();
+                runs.push(elapsed);
+            }
+
+            // The timer rounds each sleep to the millisecond
+            for (first, second) in runs[0].iter().zip(&runs[1]) {
+                assert!(
+                    first.abs_diff(*second) <= Duration::from_millis(1),
+                    "{runs:?}"
+                );
+            }
+            assert!(
+                runs[0]
+                    .windows(2)
+                    .any(|pair| pair[0].abs_diff(pair[1]) > Duration::from_millis(1))
+            );
+        }
+
+        /// Tests that latency samples are counted for routes unknown when the state was built.
+        ///
+        /// Routes given a latency by a reload advance their seeded sampling
+        /// like routes configured from the start.
+        #[test]
+        fn test_samples_are_counted_on_demand() {
+            let state = AppState::new(Database::new());
+
+            assert_eq!(state.next_sample("[GET] /api/reloaded"), 0);
+            assert_eq!(state.next_sample("[GET] /api/reloaded"), 1);
+            assert_eq!(state.next_sample("[GET] /api/other"), 0);
+        }
+
+        /// Tests that latencies sampled over many calls match the configured percentiles.
+        #[test]
+        fn test_sampled_latencies_match_percentiles() {
+            let latency: LatencyConfig = serde_json::from_value(json!({
+                "distribution": "percentiles", "p50": 80, "p90": 200, "p99": 800
+            }))
+            .expect("Latency should parse");
+
+            let mut samples: Vec<f64> = (0..20_000)
+                .map(|call| {
+                    latency
+                        .sample(&mut seeded_rng(Some(42), call))
+                        .as_secs_f64()
+                        * 1000.0
+                })
+                .collect();
+            samples.sort_by(f64::total_cmp);
+
+            for (quantile, expected) in [(0.5, 80.0), (0.9, 200.0), (0.99, 800.0)] {
+                #[allow(
+                    clippy::cast_possible_truncation,
+                    clippy::cast_sign_loss,
+                    clippy::cast_precision_loss
+                )]
+                let sampled = samples[(quantile * samples.len() as f64) as usize];
+                assert!(
+                    (sampled - expected).abs() / expected < 0.1,
+                    "p{} was {sampled}, expected about {expected}",
+                    quantile * 100.0
+                );
+            }
+        }
+    }
+
+    mod alias_tests {
+        use super::*;
+
+        /// Builds a router with an aliased route and two redirects.
+        async fn alias_router() -> Router {
+            test_router(json!({
+                "routes": {
+                    "/users": {"response": {"status": 200, "body": [{"id": 1, "name": "Ada"}]}},
+                    "/v1/users": {"alias": "/users"},
+                    "/old-users": {"redirect": {"to": "/users"}},
+                    "/moved": {"redirect": {"to": "https://example.com/users", "status": 301}}
+                }
+            }))
+            .await
+        }
+
+        /// Tests that an alias serves the same body as its target.
+        #[tokio::test]
+        async fn test_alias_serves_target_body() {
+            let router = alias_router().await;
+
+            let target = body_json(get(&router, "/users").await).await;
+            let response = get(&router, "/v1/users").await;
+
+            assert_eq!(response.status(), StatusCode::OK);
+            assert_eq!(body_json(response).await, target);
+        }
+
+        /// Tests that writes and HEAD requests through an alias reach its target.
+        ///
+        /// Verifies that a POST to the alias is stored in the target's data,
+        /// answered like a POST to the target, and that HEAD on the alias
+        /// answers as GET does.
+        #[tokio::test]
+        async fn test_alias_writes_and_heads_reach_target() {
+            let router = test_router(json!({
+                "routes": {
+                    "/users": {"response": {"status": 200, "body": [{"id": 1}]}},
+                    "[POST] /users": {"response": {"body": [{"id": 1}]}},
+                    "/v1/users": {"alias": "/users"},
+                    "[POST] /v1/users": {"alias": "/users"}
+                }
+            }))
+            .await;
+
+            let request = Request::builder()
+                .method("POST")
+                .uri("/v1/users")
+                .header(CONTENT_TYPE, "application/json")
+                .body(Body::from(r#"{"id": 2}"#))
+                .expect("Failed to build request");
+            let response = send(&router, request).await;
+            assert_eq!(response.status(), StatusCode::OK);
+            assert_eq!(
+                body_json(get(&router, "/users").await).await,
+                json!([{"id": 1}, {"id": 2}])
+            );
+
+            let request = Request::builder()
+                .method("HEAD")
+                .uri("/v1/users")
+                .body(Body::empty())
+                .expect("Failed to build request");
+            assert_eq!(send(&router, request).await.status(), StatusCode::OK);
+        }
+
+        /// Tests that redirects answer their status with a Location header.
+        #[tokio::test]
+        async fn test_redirect_sets_location() {
+            let router = alias_router().await;
+
+            let response = get(&router, "/old-users").await;
+            assert_eq!(response.status(), StatusCode::FOUND);
+            assert_eq!(response.headers()[LOCATION], "/users");
+
+            let response = get(&router, "/moved").await;
+            assert_eq!(response.status(), StatusCode::MOVED_PERMANENTLY);
+            assert_eq!(response.headers()[LOCATION], "https://example.com/users");
+        }
+    }
+
+    mod multipart_tests {
+        use super::*;
+
+        /// Tests that a multipart upload is echoed back as metadata.
+        ///
+        /// Verifies that text fields are echoed with their value and that file
+        /// parts are described by name, filename, content type and size.
+        #[tokio::test]
+        async fn test_multipart_upload_echoes_metadata() {
+            let router = test_router(json!({
+                "routes": {
+                    "[POST] /api/uploads": {"response": {"status": 201, "body": {}}}
+                }
+            }))
+            .await;
+
+            let boundary = "json-echo-boundary";
+            let body = format!(
+                "--{boundary}\r\n\
+                 Content-Disposition: form-data; name=\"title\"\r\n\r\n\
+                 Holiday\r\n\
+                 --{boundary}\r\n\
+                 Content-Disposition: form-data; name=\"photo\"; filename=\"beach.txt\"\r\n\
+                 Content-Type: text/plain\r\n\r\n\
+                 sand and sea\r\n\
+                 --{boundary}--\r\n"
+            );
+
+            let request = Request::builder()
+                .method("POST")
+                .uri("/api/uploads")
+                .header(
+                    "content-type",
+                    format!("multipart/form-data; boundary={boundary}"),
+                )
+                .body(Body::from(body))
+                .expect("Failed to build request");
+
+            let response = send(&router, request).await;
+            assert_eq!(response.status(), StatusCode::CREATED);
+            assert_eq!(
+                body_json(response).await,
+                json!({
+                    "fields": {"title": "Holiday"},
+                    "files": [{
+                        "field": "photo",
+                        "filename": "beach.txt",
+                        "content_type": "text/plain",
+                        "size": 12
+                    }]
+                })
+            );
+        }
+    }
+
+    mod form_tests {
+        use super::*;
+
+        /// Tests that a form-urlencoded POST updates the model like JSON.
+        ///
+        /// Verifies that `a=1&b=2` is merged as an object of string fields,
+        /// answered by the write route and served by the GET route.
+        #[tokio::test]
+        async fn test_form_body_updates_model() {
+            let router = test_router(json!({
+                "routes": {
+                    "/api/entries": {"response": {"status": 200, "body": []}},
+                    "[POST] /api/entries": {"response": {"status": 200, "body": []}}
+                }
+            }))
+            .await;
+
+            let request = Request::builder()
+                .method("POST")
+                .uri("/api/entries")
+                .header(CONTENT_TYPE, "application/x-www-form-urlencoded")
+                .body(Body::from("a=1&b=2"))
+                .expect("Failed to build request");
+            let response = send(&router, request).await;
+            assert_eq!(response.status(), StatusCode::OK);
+            assert_eq!(body_json(response).await, json!([{"a": "1", "b": "2"}]));
+
+            let served = body_json(get(&router, "/api/entries").await).await;
+            assert_eq!(served, json!([{"a": "1", "b": "2"}]));
+        }
+
+        /// Tests that form fields keep their order and repeated keys become arrays.
+        #[tokio::test]
+        async fn test_form_fields_keep_order_and_repeats() {
+            let router = test_router(json!({
+                "routes": {
+                    "/api/entries": {"response": {"status": 200, "body": []}},
+                    "[POST] /api/entries": {"response": {"status": 200, "body": []}}
+                }
+            }))
+            .await;
+
+            let request = Request::builder()
+                .method("POST")
+                .uri("/api/entries")
+                .header(CONTENT_TYPE, "application/x-www-form-urlencoded")
+                .body(Body::from("z=1&tag=a&a=2&tag=b&tag=c"))
+                .expect("Failed to build request");
+            let response = send(&router, request).await;
+            assert_eq!(response.status(), StatusCode::OK);
+            assert_eq!(
+                body_text(response).await,
+                r#"[{"z":"1","tag":["a","b","c"],"a":"2"}]"#
+            );
+        }
+    }
+
+    mod etag_tests {
+        use super::*;
+        use axum::http::header::{ETAG, IF_NONE_MATCH};
+
+        /// Builds a router with a generated and a configured weak ETag route.
+        async fn etag_router() -> Router {
+            test_router(json!({
+                "routes": {
+                    "/api/items": {"response": {"status": 200, "body": [{"id": 1}]}},
+                    "/api/weak": {
+                        "headers": {"ETag": "W/\"v1\""},
+                        "response": {"status": 200, "body": {"version": 1}}
+                    }
+                }
+            }))
+            .await
+        }
+
+        /// Tests that GET responses carry a strong ETag that revalidates weakly.
+        ///
+        /// Verifies that a weak validator matches the strong tag (strong-weak),
+        /// that a mismatching validator gets the full body, and that the 304
+        /// has no body but keeps the tag.
+        #[tokio::test]
+        async fn test_strong_etag_matches_weak_validator() {
+            let router = etag_router().await;
+
+            let response = get(&router, "/api/items").await;
+            assert_eq!(response.status(), StatusCode::OK);
+            let etag = response.headers()[ETAG]
+                .to_str()
+                .expect("ETag should be ASCII")
+                .to_string();
+            assert!(
+                etag.starts_with('"'),
+                "Generated ETag should be strong: {etag}"
+            );
+
+            let response = get_with(
+                &router,
+                "/api/items",
+                &[(IF_NONE_MATCH, &format!("W/{etag}"))],
+            )
+            .await;
+            assert_eq!(response.status(), StatusCode::NOT_MODIFIED);
+            assert_eq!(response.headers()[ETAG], etag.as_str());
+            let bytes = to_bytes(response.into_body(), usize::MAX)
+                .await
+                .expect("Failed to read response body");
+            assert!(bytes.is_empty(), "304 should not carry a body");
+
+            let response = get_with(&router, "/api/items", &[(IF_NONE_MATCH, "\"stale\"")]).await;
+            assert_eq!(response.status(), StatusCode::OK);
+            assert_eq!(body_json(response).await, json!([{"id": 1}]));
+        }
+
+        /// Tests that a configured weak ETag matches a weak validator (weak-weak).
+        #[tokio::test]
+        async fn test_weak_etag_matches_weak_validator() {
+            let router = etag_router().await;
+
+            let response = get_with(&router, "/api/weak", &[(IF_NONE_MATCH, "W/\"v1\"")]).await;
+            assert_eq!(response.status(), StatusCode::NOT_MODIFIED);
+            assert_eq!(response.headers()[ETAG], "W/\"v1\"");
+
+            let response = get_with(&router, "/api/weak", &[(IF_NONE_MATCH, "W/\"v2\"")]).await;
+            assert_eq!(response.status(), StatusCode::OK);
+        }
+
+        /// Tests that the `*` validator matches any current representation.
+        #[tokio::test]
+        async fn test_wildcard_matches_any_representation() {
+            let router = etag_router().await;
+
+            let response = get_with(&router, "/api/items", &[(IF_NONE_MATCH, "*")]).await;
+            assert_eq!(response.status(), StatusCode::NOT_MODIFIED);
+
+            let response = get_with(&router, "/api/missing", &[(IF_NONE_MATCH, "*")]).await;
+            assert_eq!(
+                response.status(),
+                StatusCode::NOT_FOUND,
+                "Missing routes have no current representation"
+            );
+        }
+    }
+
+    mod sequence_tests {
+        use super::*;
+
+        /// Builds a router with a one-shot and a looping response sequence.
+        async fn sequence_router() -> Router {
+            let sequence = json!([
+                {"status": 503, "body": {"error": "unavailable"}},
+                {"status": 503, "body": {"error": "unavailable"}},
+                {"status": 200, "body": {"ok": true}}
+            ]);
+
+            test_router(json!({
+                "routes": {
+                    "/api/flaky": {"sequence": sequence},
+                    "/api/cycle": {"sequence": sequence, "sequence_mode": "loop"}
+                }
+            }))
+            .await
+        }
+
+        /// Tests that a one-shot sequence repeats its last entry.
+        #[tokio::test]
+        async fn test_once_sequence_repeats_last_entry() {
+            let router = sequence_router().await;
+
+            assert_eq!(
+                statuses(&router, "/api/flaky", 5).await,
+                vec![503, 503, 200, 200, 200]
+            );
+        }
+
+        /// Tests that a looping sequence wraps around to its first entry.
+        #[tokio::test]
+        async fn test_loop_sequence_wraps_around() {
+            let router = sequence_router().await;
+
+            assert_eq!(
+                statuses(&router, "/api/cycle", 5).await,
+                vec![503, 503, 200, 503, 503]
+            );
+            assert_eq!(
+                statuses(&router, "/api/flaky", 1).await,
+                vec![503],
+                "Every route keeps its own counter"
+            );
+        }
+    }
+
+    mod echo_tests {
+        use super::*;
+
+        /// Builds a router with a bare and a wrapped echo route.
+        async fn echo_router() -> Router {
+            test_router(json!({
+                "routes": {
+                    "[POST] /api/echo": {
+                        "echo": true,
+                        "response": {"status": 201, "body": {"stored": true}}
+                    },
+                    "[POST] /api/wrapped": {
+                        "echo": true,
+                        "echo_key": "data",
+                        "response": {"status": 200, "body": {}}
+                    }
+                }
+            }))
+            .await
+        }
+
+        /// Tests that an echo route reflects the payload without storing it.
+        #[tokio::test]
+        async fn test_echo_returns_payload() {
+            let router = echo_router().await;
+            let payload = json!({"name": "Ada", "tags": ["admin"]});
+
+            let response = post_json(&router, "/api/echo", &payload).await;
+            assert_eq!(response.status(), StatusCode::CREATED);
+            assert_eq!(body_json(response).await, payload);
+
+            let response = post_json(&router, "/api/echo", &json!({"other": 1})).await;
+            assert_eq!(
+                body_json(response).await,
+                json!({"other": 1}),
+                "Previous payloads should not be merged in"
+            );
+        }
+
+        /// Tests that an echo route nests the payload under its `echo_key`.
+        #[tokio::test]
+        async fn test_echo_wraps_payload() {
+            let router = echo_router().await;
+
+            let response = post_json(&router, "/api/wrapped", &json!([1, 2])).await;
+            assert_eq!(response.status(), StatusCode::OK);
+            assert_eq!(body_json(response).await, json!({"data": [1, 2]}));
+        }
+    }
+
+    mod internal_error_tests {
+        use super::*;
+
+        /// Tests that internal failures default to a JSON 500.
+        #[tokio::test]
+        async fn test_internal_error_defaults_to_500() {
+            let state = AppState::new(Database::new());
+
+            let response = state.internal_error("simulated failure");
+            assert_eq!(response.status(), StatusCode::INTERNAL_SERVER_ERROR);
+            assert_eq!(
+                body_json(response).await,
+                json!({"error": "Internal server error"})
+            );
+        }
+
+        /// Tests that a configured `internal_error` replaces the default response.
+        ///
+        /// Simulates a failure through the shared helper and verifies that the
+        /// configured status and body are sent, without the failure reason.
+        #[tokio::test]
+        async fn test_internal_error_uses_configured_response() {
+            let internal_error: ConfigRouteResponse = serde_json::from_value(json!({
+                "status": 503,
+                "body": {"code": "MOCK_FAILURE", "retry": true}
+            }))
+            .expect("Should deserialize internal error");
+            let state = AppState::new(Database::new()).with_internal_error(Some(internal_error));
+
+            let response = state.internal_error("simulated failure");
+            assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+            assert_eq!(response.headers()[CONTENT_TYPE], "application/json");
+            assert_eq!(
+                body_json(response).await,
+                json!({"code": "MOCK_FAILURE", "retry": true})
+            );
+        }
+    }
+
+    mod fields_spec_tests {
+        use super::*;
+
+        /// Builds a router whose write route declares field defaults and requirements.
+        async fn fields_router() -> Router {
+            test_router(json!({
+                "routes": {
+                    "/api/users": {"response": {"status": 200, "body": []}},
+                    "[POST] /api/users": {
+                        "fields_spec": {
+                            "name": {"required": true},
+                            "role": {"default": "user"},
+                            "active": {"default": true}
+                        },
+                        "response": {"status": 200, "body": []}
+                    }
+                }
+            }))
+            .await
+        }
+
+        /// Tests that absent fields are filled with their defaults before merging.
+        ///
+        /// Verifies that present values, even falsy ones, are kept as sent.
+        #[tokio::test]
+        async fn test_defaults_fill_absent_fields() {
+            let router = fields_router().await;
+
+            let response = post_json(
+                &router,
+                "/api/users",
+                &json!({"name": "Ada", "active": false}),
+            )
+            .await;
+            assert!(response.status().is_success());
+
+            assert_eq!(
+                body_json(get(&router, "/api/users").await).await,
+                json!([{"name": "Ada", "role": "user", "active": false}])
+            );
+        }
+
+        /// Tests that a missing required field is rejected without merging.
+        #[tokio::test]
+        async fn test_missing_required_field_is_rejected() {
+            let router = fields_router().await;
+
+            let response = post_json(&router, "/api/users", &json!({"role": "admin"})).await;
+            assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+            let body = body_json(response).await;
+            assert!(
+                body["error"]
+                    .as_str()
+                    .is_some_and(|error| error.contains("'name'")),
+                "Error should name the missing field: {body}"
+            );
+
+            assert_eq!(
+                body_json(get(&router, "/api/users").await).await,
+                json!([]),
+                "Nothing should be merged"
+            );
+        }
+    }
+
+    mod static_folder_tests {
+        use super::*;
+
+        /// Tests that a missing or non-directory static folder is reported.
+        ///
+        /// Verifies that the message names the absolute path that was checked.
+        #[test]
+        fn test_check_static_folder() {
+            let dir = tempfile::tempdir().expect("Failed to create temporary directory");
+            assert_eq!(check_static_folder(dir.path()), Ok(()));
+
+            let missing = dir.path().join("missing");
+            let problem = check_static_folder(&missing).expect_err("Missing folder should fail");
+            assert!(
+                problem.contains("does not exist"),
+                "Unexpected message: {problem}"
+            );
+            assert!(
+                problem.contains(&missing.display().to_string()),
+                "Message should name the absolute path: {problem}"
+            );
+
+            let file = dir.path().join("file.txt");
+            std::fs::write(&file, "not a folder").expect("Failed to write test file");
+            let problem = check_static_folder(&file).expect_err("File should fail");
+            assert!(
+                problem.contains("is not a directory"),
+                "Unexpected message: {problem}"
+            );
+        }
+
+        /// Tests that a missing static folder is warned about when building the router.
+        ///
+        /// Verifies that the warning names the absolute path and that the API
+        /// routes are still served.
+        #[tokio::test]
+        async fn test_missing_static_folder_warns() {
+            let logs = CapturedLogs::default();
+            let writer = logs.clone();
+            let subscriber = tracing_subscriber::fmt()
+                .with_writer(move || writer.clone())
+                .with_ansi(false)
+                .with_max_level(tracing::Level::WARN)
+                .finish();
+            let _guard = tracing::subscriber::set_default(subscriber);
+
+            let router = test_router(json!({
+                "static_folder": "does-not-exist",
+                "routes": {"/api/ping": {"response": {"status": 200, "body": {"ok": true}}}}
+            }))
+            .await;
+
+            let output = String::from_utf8(logs.0.lock().expect("Log buffer poisoned").clone())
+                .expect("Logs should be UTF-8");
+            assert!(
+                output.contains("WARN") && output.contains("does-not-exist does not exist"),
+                "Expected a static folder warning, got: {output}"
+            );
+
+            assert_eq!(get(&router, "/api/ping").await.status(), StatusCode::OK);
+
+            assert_eq!(
+                get(&router, "/static/index.html").await.status(),
+                StatusCode::NOT_FOUND
+            );
+        }
+    }
+
+    mod results_field_tests {
+        use super::*;
+
+        /// Tests that a POST appends into the nested collection served by GET.
+        ///
+        /// Verifies that the envelope around the collection is preserved and that
+        /// the write route's results_field is honored for the synced GET model.
+        #[tokio::test]
+        async fn test_post_appends_into_results_field() {
+            let router = test_router(json!({
+                "routes": {
+                    "/api/users": {
+                        "response": {"status": 200, "body": {"page": 1, "users": [{"id": 1}]}}
+                    },
+                    "[POST] /api/users": {
+                        "results_field": "users",
+                        "response": {"status": 201, "body": {"users": []}}
+                    }
+                }
+            }))
+            .await;
+
+            let response = post_json(&router, "/api/users", &json!({"id": 2, "name": "Ada"})).await;
+            assert!(response.status().is_success());
+
+            assert_eq!(
+                body_json(get(&router, "/api/users").await).await,
+                json!({"page": 1, "users": [{"id": 1}, {"id": 2, "name": "Ada"}]})
+            );
+
+            post_json(&router, "/api/users", &json!({"id": 1, "name": "Bob"})).await;
+            assert_eq!(
+                body_json(get(&router, "/api/users").await).await["users"],
+                json!([{"id": 1, "name": "Bob"}, {"id": 2, "name": "Ada"}]),
+                "Entries with a matching id should be merged in place"
+            );
+        }
+
+        /// Tests that a PUT creates the results_field missing from the body.
+        ///
+        /// Verifies that the field is created as a collection holding the
+        /// entry, so both the PUT response and a later GET find it by id.
+        #[tokio::test]
+        async fn test_put_creates_missing_results_field() {
+            let router = test_router(json!({
+                "routes": {
+                    "/api/users/{id}": {
+                        "results_field": "users",
+                        "response": {"status": 200, "body": {"total": 0}}
+                    },
+                    "[PUT] /api/users/{id}": {
+                        "results_field": "users",
+                        "response": {"status": 200, "body": {"total": 0}}
+                    }
+                }
+            }))
+            .await;
+
+            let request = Request::builder()
+                .method("PUT")
+                .uri("/api/users/1")
+                .header("content-type", "application/json")
+                .body(Body::from(json!({"id": 1, "name": "Ada"}).to_string()))
+                .expect("Failed to build request");
+            let response = send(&router, request).await;
+            assert_eq!(response.status(), StatusCode::OK);
+            assert_eq!(body_json(response).await, json!({"id": 1, "name": "Ada"}));
+
+            assert_eq!(
+                body_json(get(&router, "/api/users/1").await).await,
+                json!({"id": 1, "name": "Ada"})
+            );
+        }
+
+        /// Tests that a GET route's results_field is honored when the write route has none.
+        #[tokio::test]
+        async fn test_post_uses_get_results_field() {
+            let router = test_router(json!({
+                "routes": {
+                    "/api/items": {
+                        "results_field": "data",
+                        "response": {"status": 200, "body": {"data": []}}
+                    },
+                    "[POST] /api/items": {"response": {"status": 201, "body": {}}}
+                }
+            }))
+            .await;
+
+            post_json(&router, "/api/items", &json!([{"id": 7}])).await;
+            assert_eq!(
+                body_json(get(&router, "/api/items").await).await,
+                json!([{"id": 7}])
+            );
+        }
+
+        /// Builds a router serving a nested collection, wrapped back in its body or not.
+        async fn nested_router(wrap_results: bool) -> Router {
+            test_router(json!({
+                "routes": {
+                    "/api/items": {
+                        "results_field": "data",
+                        "wrap_results": wrap_results,
+                        "response": {"status": 200, "body": {
+                            "total": 4,
+                            "data": [
+                                {"id": 1, "kind": "a"},
+                                {"id": 2, "kind": "b"},
+                                {"id": 3, "kind": "a"},
+                                {"id": 4, "kind": "a"}
+                            ]
+                        }}
+                    }
+                }
+            }))
+            .await
+        }
+
+        /// Tests that the nested collection is filtered, sorted and paged.
+        ///
+        /// Verifies that the transformed array is served alone by default, and
+        /// in place within its body with `wrap_results`.
+        #[tokio::test]
+        async fn test_query_shapes_nested_collection() {
+            let router = nested_router(false).await;
+            assert_eq!(
+                body_json(get(&router, "/api/items?_sort=id&_order=desc&_page=1&_limit=2").await)
+                    .await,
+                json!([{"id": 4, "kind": "a"}, {"id": 3, "kind": "a"}])
+            );
+            assert_eq!(
+                body_json(get(&router, "/api/items?kind=a&_sort=id&_page=2&_limit=2").await).await,
+                json!([{"id": 4, "kind": "a"}])
+            );
+            // Parameters naming no field of the items are not filters
+            assert_eq!(
+                body_json(get(&router, "/api/items?t=123&x=1").await)
+                    .await
+                    .as_array()
+                    .map(Vec::len),
+                Some(4)
+            );
+
+            let router = nested_router(true).await;
+            assert_eq!(
+                body_json(get(&router, "/api/items?kind=b").await).await,
+                json!({"total": 4, "data": [{"id": 2, "kind": "b"}]})
+            );
+
+            assert_eq!(
+                get(&router, "/api/items?_page=0").await.status(),
+                StatusCode::BAD_REQUEST
+            );
+        }
+
+        /// Tests slicing a nested collection with `_start` and `_end`.
+        ///
+        /// Verifies that a slice leaving items out answers `206`, that a slice
+        /// covering the collection answers `200`, and that a start past the end
+        /// answers an empty array, all with the total in `X-Total-Count`.
+        #[tokio::test]
+        async fn test_query_slices_collection() {
+            let router = nested_router(false).await;
+            let slice = |uri: &'static str| {
+                let router = router.clone();
+                async move {
+                    let response = get(&router, uri).await;
+                    let status = response.status();
+                    let total = response.headers()["x-total-count"].clone();
+                    (status, total, body_json(response).await)
+                }
+            };
+
+            assert_eq!(
+                slice("/api/items?_sort=id&_start=1&_end=3").await,
+                (
+                    StatusCode::PARTIAL_CONTENT,
+                    HeaderValue::from(4),
+                    json!([{"id": 2, "kind": "b"}, {"id": 3, "kind": "a"}])
+                )
+            );
+
+            let (status, total, body) = slice("/api/items?_start=0&_end=4").await;
+            assert_eq!((status, total), (StatusCode::OK, HeaderValue::from(4)));
+            assert_eq!(body.as_array().map(Vec::len), Some(4));
+
+            assert_eq!(
+                slice("/api/items?_start=10").await,
+                (StatusCode::OK, HeaderValue::from(4), json!([]))
+            );
+        }
+    }
+
+    mod charset_tests {
+        use super::*;
+
+        /// Tests that the global charset is declared and routes can override it.
+        ///
+        /// Verifies the parameter is appended to the default JSON content type,
+        /// replaces a charset set in the route headers, and that latin-1 JSON
+        /// stays decodable through escapes.
+        #[tokio::test]
+        async fn test_charset_parameter() {
+            let router = test_router(json!({
+                "charset": "utf-8",
+                "routes": {
+                    "/api/users": {"response": {"status": 200, "body": [{"name": "Zoë"}]}},
+                    "/api/legacy": {
+                        "charset": "latin-1",
+                        "headers": {"Content-Type": "application/json; charset=utf-8"},
+                        "response": {"status": 200, "body": {"name": "Zoë"}}
+                    }
+                }
+            }))
+            .await;
+
+            let response = get(&router, "/api/users").await;
+            assert_eq!(content_type(&response), "application/json; charset=utf-8");
+            assert_eq!(body_json(response).await, json!([{"name": "Zoë"}]));
+
+            let response = get(&router, "/api/legacy").await;
+            assert_eq!(
+                content_type(&response),
+                "application/json; charset=iso-8859-1"
+            );
+            let bytes = to_bytes(response.into_body(), usize::MAX)
+                .await
+                .expect("Failed to read body");
+            assert!(bytes.is_ascii(), "Non-ASCII JSON should be escaped");
+            assert_eq!(
+                serde_json::from_slice::<Value>(&bytes).expect("Body should be JSON"),
+                json!({"name": "Zoë"})
+            );
+        }
+
+        /// Tests that a latin-1 text route transcodes its body.
+        #[tokio::test]
+        async fn test_latin1_text_transcoding() {
+            let router = test_router(json!({
+                "routes": {
+                    "/greeting": {
+                        "charset": "ISO-8859-1",
+                        "headers": {"Content-Type": "text/plain"},
+                        "response": {"status": 200, "body": "Olá, café"}
+                    },
+                    "/plain": {
+                        "headers": {"Content-Type": "text/plain"},
+                        "response": {"status": 200, "body": "Olá"}
+                    }
+                }
+            }))
+            .await;
+
+            let response = get(&router, "/greeting").await;
+            assert_eq!(content_type(&response), "text/plain; charset=iso-8859-1");
+            let bytes = to_bytes(response.into_body(), usize::MAX)
+                .await
+                .expect("Failed to read body");
+            assert_eq!(bytes.as_ref(), b"Ol\xe1, caf\xe9");
+
+            let response = get(&router, "/plain").await;
+            assert_eq!(content_type(&response), "text/plain");
+            let bytes = to_bytes(response.into_body(), usize::MAX)
+                .await
+                .expect("Failed to read body");
+            assert_eq!(bytes.as_ref(), "Olá".as_bytes(), "UTF-8 stays the default");
+        }
+    }
+
+    mod debug_headers_tests {
+        use super::*;
+
+        /// Builds a router with a delayed route, optionally enabling debug headers.
+        async fn debug_router(debug_headers: bool) -> Router {
+            test_router(json!({
+                "debug_headers": debug_headers,
+                "routes": {
+                    "/api/slow": {"delay": 40, "response": {"status": 200, "body": []}}
+                }
+            }))
+            .await
+        }
+
+        /// Reads a debug header of a response as a number.
+        fn header_number(response: &Response, name: &str) -> Option<f64> {
+            response
+                .headers()
+                .get(name)
+                .and_then(|value| value.to_str().ok())
+                .and_then(|value| value.parse().ok())
+        }
+
+        /// Tests that enabled debug headers count requests and time the handler.
+        ///
+        /// Verifies the count increases with every request, unknown routes
+        /// included, and that the timing covers the configured delay.
+        #[tokio::test]
+        async fn test_debug_headers_enabled() {
+            let router = debug_router(true).await;
+            tokio::time::pause();
+
+            let response = get(&router, "/api/slow").await;
+            assert_eq!(header_number(&response, "x-echo-request-count"), Some(1.0));
+            let elapsed = header_number(&response, "x-echo-response-time-ms")
+                .expect("Response time header should be present");
+            assert!(
+                (40.0..=41.0).contains(&elapsed),
+                "Expected about 40ms, got {elapsed}"
+            );
+
+            let response = get(&router, "/undefined").await;
+            assert_eq!(response.status(), StatusCode::NOT_FOUND);
+            assert_eq!(header_number(&response, "x-echo-request-count"), Some(2.0));
+        }
+
+        /// Tests that no debug headers are sent unless the flag is set.
+        #[tokio::test]
+        async fn test_debug_headers_disabled() {
+            let router = debug_router(false).await;
+            tokio::time::pause();
+
+            let response = get(&router, "/api/slow").await;
+            assert!(response.headers().get("x-echo-request-count").is_none());
+            assert!(response.headers().get("x-echo-response-time-ms").is_none());
+        }
+    }
+
+    mod relation_tests {
+        use super::*;
+
+        /// Builds a router with posts, their comments and their authors.
+        async fn relation_router() -> Router {
+            test_router(json!({
+                "routes": {
+                    "/api/posts/{id}": {
+                        "relations": {
+                            "comments": {"route": "/api/comments", "foreign_key": "postId"},
+                            "author": {"route": "/api/users", "foreign_key": "userId"}
+                        },
+                        "response": {"status": 200, "body": [
+                            {"id": 1, "title": "Hello", "userId": 7},
+                            {"id": 2, "title": "Again", "userId": 8}
+                        ]}
+                    },
+                    "/api/comments": {
+                        "relations": {
+                            "post": {"route": "/api/posts/{id}", "foreign_key": "postId"}
+                        },
+                        "response": {"status": 200, "body": [
+                            {"id": 10, "postId": 1, "text": "First"},
+                            {"id": 11, "postId": "1", "text": "Second"},
+                            {"id": 12, "postId": 2, "text": "Other"}
+                        ]}
+                    },
+                    "/api/users": {
+                        "results_field": "data",
+                        "response": {"status": 200, "body": {"data": [{"id": 7, "name": "Ada"}]}}
+                    }
+                }
+            }))
+            .await
+        }
+
+        /// Tests that `_embed` attaches the children referencing an entry.
+        ///
+        /// Verifies that ids match foreign keys stored as strings and that
+        /// several relations can be requested at once.
+        #[tokio::test]
+        async fn test_embed_attaches_children() {
+            let router = relation_router().await;
+
+            let (status, post) =
+                status_json(get(&router, "/api/posts/1?_embed=comments").await).await;
+            assert_eq!(status, StatusCode::OK);
+            assert_eq!(
+                post["comments"],
+                json!([
+                    {"id": 10, "postId": 1, "text": "First"},
+                    {"id": 11, "postId": "1", "text": "Second"}
+                ])
+            );
+
+            let (_, post) =
+                status_json(get(&router, "/api/posts/2?_embed=comments&_expand=author").await)
+                    .await;
+            assert_eq!(
+                post["comments"],
+                json!([{"id": 12, "postId": 2, "text": "Other"}])
+            );
+            assert!(post.get("author").is_none(), "Missing parents are omitted");
+        }
+
+        /// Tests that `_expand` attaches the parent of every entry.
+        ///
+        /// Verifies collections are joined entry by entry and that parents are
+        /// found under the related route's `results_field`.
+        #[tokio::test]
+        async fn test_expand_attaches_parent() {
+            let router = relation_router().await;
+
+            let (status, comments) =
+                status_json(get(&router, "/api/comments?_expand=post").await).await;
+            assert_eq!(status, StatusCode::OK);
+            assert_eq!(comments[0]["post"]["title"], json!("Hello"));
+            assert_eq!(comments[1]["post"]["title"], json!("Hello"));
+            assert_eq!(comments[2]["post"]["title"], json!("Again"));
+
+            let (_, post) = status_json(get(&router, "/api/posts/1?_expand=author").await).await;
+            assert_eq!(post["author"], json!({"id": 7, "name": "Ada"}));
+        }
+
+        /// Tests that undeclared relations are answered with 400.
+        #[tokio::test]
+        async fn test_unknown_relation_is_rejected() {
+            let router = relation_router().await;
+
+            let (status, body) =
+                status_json(get(&router, "/api/comments?_embed=likes").await).await;
+            assert_eq!(status, StatusCode::BAD_REQUEST);
+            assert_eq!(body, json!({"error": "Unknown relation: likes"}));
+        }
+    }
+
+    mod error_route_tests {
+        use super::*;
+
+        /// Builds a router with a maintenance GET error and a validation POST error.
+        async fn error_router() -> Router {
+            test_router(json!({
+                "routes": {
+                    "/api/status": {
+                        "headers": {"Retry-After": "120"},
+                        "error": {"status": 503, "code": "MAINTENANCE", "message": "Back soon"}
+                    },
+                    "[POST] /api/orders": {
+                        "error": {
+                            "status": 422,
+                            "details": {"field": "quantity"}
+                        }
+                    },
+                    "/api/broken": {"error": {}}
+                }
+            }))
+            .await
+        }
+
+        /// Tests that configured errors render the envelope with their status.
+        ///
+        /// Verifies configured codes and messages, route headers and details.
+        #[tokio::test]
+        async fn test_error_envelope() {
+            let router = error_router().await;
+
+            let response = call(&router, "GET", "/api/status").await;
+            let headers = response.headers().clone();
+            let (status, body) = status_json(response).await;
+            assert_eq!(status, StatusCode::SERVICE_UNAVAILABLE);
+            assert_eq!(headers["retry-after"], "120");
+            assert_eq!(
+                body,
+                json!({"error": {"status": 503, "code": "MAINTENANCE", "message": "Back soon"}})
+            );
+
+            let (status, body) = status_json(call(&router, "POST", "/api/orders").await).await;
+            assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+            assert_eq!(
+                body,
+                json!({"error": {
+                    "status": 422,
+                    "code": "UNPROCESSABLE_ENTITY",
+                    "message": "Unprocessable Entity",
+                    "details": {"field": "quantity"}
+                }})
+            );
+        }
+
+        /// Tests that an empty error defaults to a 500 envelope.
+        #[tokio::test]
+        async fn test_error_defaults() {
+            let router = error_router().await;
+
+            let (status, body) = status_json(call(&router, "GET", "/api/broken").await).await;
+            assert_eq!(status, StatusCode::INTERNAL_SERVER_ERROR);
+            assert_eq!(
+                body,
+                json!({"error": {
+                    "status": 500,
+                    "code": "INTERNAL_SERVER_ERROR",
+                    "message": "Internal Server Error"
+                }})
+            );
+        }
+    }
+
+    mod range_tests {
+        use super::*;
+        use axum::http::header::{ACCEPT_RANGES, CONTENT_RANGE, RANGE};
+
+        /// Sends a GET request with a `Range` header.
+        async fn get_range(router: &Router, uri: &str, range: &str) -> Response {
+            let request = Request::builder()
+                .uri(uri)
+                .header(RANGE, range)
+                .body(Body::empty())
+                .expect("Failed to build request");
+            send(router, request).await
+        }
+
+        /// Reads a response body as raw bytes.
+        async fn body_bytes(response: Response) -> Vec<u8> {
+            to_bytes(response.into_body(), usize::MAX)
+                .await
+                .expect("Failed to read response body")
+                .to_vec()
+        }
+
+        /// Tests that a route answers a byte range with 206 Partial Content.
+        ///
+        /// Verifies the status, the `Content-Range` and `Accept-Ranges` headers
+        /// and that the body is the requested slice of the full response.
+        #[tokio::test]
+        async fn test_route_byte_range() {
+            let router = test_router(json!({
+                "routes": {"/api/file": {"response": {"status": 200, "body": "0123456789"}}}
+            }))
+            .await;
+
+            let full = get(&router, "/api/file").await;
+            assert_eq!(full.status(), StatusCode::OK);
+            assert_eq!(full.headers()[ACCEPT_RANGES], "bytes");
+            let full = body_bytes(full).await;
+
+            let response = get_range(&router, "/api/file", "bytes=2-5").await;
+            assert_eq!(response.status(), StatusCode::PARTIAL_CONTENT);
+            assert_eq!(
+                response.headers()[CONTENT_RANGE],
+                format!("bytes 2-5/{}", full.len())
+            );
+            assert_eq!(body_bytes(response).await, full[2..=5]);
+
+            let response = get_range(&router, "/api/file", "bytes=-3").await;
+            assert_eq!(response.status(), StatusCode::PARTIAL_CONTENT);
+            assert_eq!(body_bytes(response).await, full[full.len() - 3..]);
+        }
+
+        /// Tests that a range past the end of the body answers 416.
+        ///
+        /// Verifies that the `Content-Range` header reports the full length and
+        /// that unsupported range headers fall back to the full response.
+        #[tokio::test]
+        async fn test_unsatisfiable_range() {
+            let router = test_router(json!({
+                "routes": {"/api/file": {"response": {"status": 200, "body": {"id": 1}}}}
+            }))
+            .await;
+
+            let response = get_range(&router, "/api/file", "bytes=100-").await;
+            assert_eq!(response.status(), StatusCode::RANGE_NOT_SATISFIABLE);
+            assert_eq!(response.headers()[CONTENT_RANGE], "bytes */8");
+            assert!(body_bytes(response).await.is_empty());
+
+            let response = get_range(&router, "/api/file", "bytes=0-1,4-5").await;
+            assert_eq!(response.status(), StatusCode::OK);
+            assert_eq!(body_json(response).await, json!({"id": 1}));
+        }
+
+        /// Tests that static files answer byte ranges through `ServeDir`.
+        #[tokio::test]
+        async fn test_static_file_byte_range() {
+            let dir = tempfile::tempdir().expect("Failed to create temporary directory");
+            std::fs::write(dir.path().join("data.bin"), b"abcdefghij")
+                .expect("Failed to write static file");
+
+            let router = test_router(json!({
+                "static_folder": dir.path().display().to_string(),
+                "routes": {"/api/ping": {"response": {"status": 200, "body": "pong"}}}
+            }))
+            .await;
+
+            let response = get_range(&router, "/static/data.bin", "bytes=3-6").await;
+            assert_eq!(response.status(), StatusCode::PARTIAL_CONTENT);
+            assert_eq!(response.headers()[CONTENT_RANGE], "bytes 3-6/10");
+            assert_eq!(body_bytes(response).await, b"defg");
+        }
+    }
+
+    mod enabled_tests {
+        use super::*;
+
+        /// Tests that a disabled route is not registered and answers 404.
+        #[tokio::test]
+        async fn test_disabled_route_is_not_found() {
+            let router = test_router(json!({
+                "routes": {
+                    "/users": {"response": {"status": 200, "body": []}},
+                    "/debug": {"enabled": false, "response": {"status": 200, "body": {}}}
+                }
+            }))
+            .await;
+
+            for (uri, status) in [
+                ("/users", StatusCode::OK),
+                ("/debug", StatusCode::NOT_FOUND),
+            ] {
+                assert_eq!(get(&router, uri).await.status(), status, "{uri}");
+            }
+        }
+    }
+
+    mod envelope_tests {
+        use super::*;
+
+        /// Builds a router wrapping bodies in a `data`/`meta` envelope.
+        async fn envelope_router(include_errors: bool) -> Router {
+            test_router(json!({
+                "envelope": {
+                    "template": {"data": "{{body}}", "meta": {"version": 1}},
+                    "include_errors": include_errors
+                },
+                "routes": {
+                    "[POST] /api/users": {
+                        "headers": {"x-request-source": "mock"},
+                        "response": {"status": 201, "body": {"id": 1, "name": "Ada"}}
+                    },
+                    "/api/text": {
+                        "headers": {"content-type": "text/plain"},
+                        "response": {"status": 200, "body": "plain"}
+                    },
+                    "/api/down": {"error": {"status": 503, "code": "MAINTENANCE"}}
+                }
+            }))
+            .await
+        }
+
+        /// Tests that successful JSON bodies are wrapped in the envelope.
+        ///
+        /// Verifies that the status and route headers are preserved and that
+        /// non-JSON bodies are sent unchanged.
+        #[tokio::test]
+        async fn test_envelope_wraps_successful_bodies() {
+            let router = envelope_router(false).await;
+
+            let response = call(&router, "POST", "/api/users").await;
+            assert_eq!(response.status(), StatusCode::CREATED);
+            assert_eq!(response.headers()["x-request-source"], "mock");
+            assert_eq!(
+                body_json(response).await,
+                json!({"data": {"id": 1, "name": "Ada"}, "meta": {"version": 1}})
+            );
+
+            let response = call(&router, "GET", "/api/text").await;
+            let bytes = to_bytes(response.into_body(), usize::MAX)
+                .await
+                .expect("Failed to read response body");
+            assert_eq!(&bytes[..], b"plain");
+        }
+
+        /// Tests that error responses are only wrapped when `include_errors` is set.
+        #[tokio::test]
+        async fn test_envelope_error_opt_out() {
+            let response = call(&envelope_router(false).await, "GET", "/api/down").await;
+            assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+            assert_eq!(body_json(response).await["error"]["code"], "MAINTENANCE");
+
+            let response = call(&envelope_router(true).await, "GET", "/api/down").await;
+            assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+            let body = body_json(response).await;
+            assert_eq!(body["data"]["error"]["code"], "MAINTENANCE");
+            assert_eq!(body["meta"], json!({"version": 1}));
+        }
+    }
+
+    mod grpc_tests {
+        use super::*;
+
+        /// Tests that gRPC-Web requests are rejected with 501 Not Implemented.
+        ///
+        /// Verifies that a configured route is not matched for gRPC content
+        /// types, unknown paths included, while JSON requests are still served.
+        #[tokio::test]
+        async fn test_grpc_web_is_not_implemented() {
+            let router = test_router(json!({
+                "routes": {"[POST] /api.Users/List": {"response": {"status": 200, "body": []}}}
+            }))
+            .await;
+
+            let post = |content_type: &'static str, uri: &'static str| {
+                Request::builder()
+                    .method("POST")
+                    .uri(uri)
+                    .header(CONTENT_TYPE, content_type)
+                    .body(Body::from("\0\0\0\0\0"))
+                    .expect("Failed to build request")
+            };
+
+            for content_type in [
+                "application/grpc-web",
+                "application/grpc-web+proto",
+                "application/grpc-web-text",
+                "application/grpc",
+            ] {
+                let response = send(&router, post(content_type, "/api.Users/List")).await;
+                assert_eq!(
+                    response.status(),
+                    StatusCode::NOT_IMPLEMENTED,
+                    "{content_type}"
+                );
+                assert!(
+                    body_json(response).await["error"]
+                        .as_str()
+                        .is_some_and(|error| error.contains("gRPC")),
+                );
+            }
+
+            let response = send(&router, post("application/grpc-web", "/unknown")).await;
+            assert_eq!(response.status(), StatusCode::NOT_IMPLEMENTED);
+
+            let request = Request::builder()
+                .method("POST")
+                .uri("/api.Users/List")
+                .header(CONTENT_TYPE, "application/json")
+                .body(Body::from("{}"))
+                .expect("Failed to build request");
+            assert_eq!(send(&router, request).await.status(), StatusCode::OK);
+        }
+    }
+
+    mod redaction_tests {
+        use super::*;
+
+        /// Tests that sensitive headers and fields are redacted in the request logs.
+        ///
+        /// Verifies that the `Authorization` header, matched case-insensitively,
+        /// and configured payload fields are logged as `***` and never in clear.
+        #[tokio::test]
+        async fn test_logged_requests_are_redacted() {
+            let logs = CapturedLogs::default();
+            let writer = logs.clone();
+            let subscriber = tracing_subscriber::fmt()
+                .with_writer(move || writer.clone())
+                .with_ansi(false)
+                .with_max_level(tracing::Level::DEBUG)
+                .finish();
+            let _guard = tracing::subscriber::set_default(subscriber);
+
+            let router = test_router(json!({
+                "redact_headers": ["Authorization", "X-Api-Key"],
+                "redact_fields": ["password"],
+                "routes": {"[POST] /api/login": {"response": {"status": 200, "body": {}}}}
+            }))
+            .await;
+
+            let request = Request::builder()
+                .method("POST")
+                .uri("/api/login")
+                .header("authorization", "Bearer secret-token")
+                .header("x-api-key", "secret-key")
+                .header(CONTENT_TYPE, "application/json")
+                .body(Body::from(
+                    json!({"user": "ada", "password": "secret-password"}).to_string(),
+                ))
+                .expect("Failed to build request");
+            assert_eq!(send(&router, request).await.status(), StatusCode::OK);
+
+            let output = String::from_utf8(logs.0.lock().expect("Log buffer poisoned").clone())
+                .expect("Logs should be UTF-8");
+            assert!(
+                output.contains(r#"("authorization", "***")"#),
+                "Authorization should be logged redacted: {output}"
+            );
+            assert!(output.contains(r#""password":"***""#), "{output}");
+            assert!(output.contains(r#""user":"ada""#), "{output}");
+            for secret in ["secret-token", "secret-key", "secret-password"] {
+                assert!(!output.contains(secret), "{secret} leaked: {output}");
+            }
+        }
+
+        /// Tests that `--log-bodies` logs redacted bodies truncated past the limit.
+        ///
+        /// Verifies that the request body is logged with its redacted field
+        /// masked, and that a response body longer than the limit is cut and
+        /// followed by its full size.
+        #[tokio::test]
+        async fn test_logged_bodies_are_truncated() {
+            let logs = CapturedLogs::default();
+            let writer = logs.clone();
+            let subscriber = tracing_subscriber::fmt()
+                .with_writer(move || writer.clone())
+                .with_ansi(false)
+                .with_max_level(tracing::Level::DEBUG)
+                .finish();
+            let _guard = tracing::subscriber::set_default(subscriber);
+
+            let long_name = "x".repeat(100);
+            let router = test_router_with(
+                json!({
+                    "redact_fields": ["password"],
+                    "routes": {
+                        "[POST] /api/login": {"response": {"status": 200, "body": {}}},
+                        "/api/users": {"response": {"status": 200, "body": [{"name": long_name}]}}
+                    }
+                }),
+                |state| state.with_body_logging(Some(40)),
+            )
+            .await;
+
+            let request = Request::builder()
+                .method("POST")
+                .uri("/api/login")
+                .header(CONTENT_TYPE, "application/json")
+                .body(Body::from(r#"{"password":"hunter2"}"#))
+                .expect("Failed to build request");
+            assert_eq!(send(&router, request).await.status(), StatusCode::OK);
+
+            let response = get(&router, "/api/users").await;
+            assert_eq!(
+                body_json(response).await,
+                json!([{"name": long_name}]),
+                "The logged response should still reach the client whole"
+            );
+
+            let output = String::from_utf8(logs.0.lock().expect("Log buffer poisoned").clone())
+                .expect("Logs should be UTF-8");
+            assert!(
+                output.contains(r#"Request body: {"password":"***"}"#),
+                "Request body should be logged redacted: {output}"
+            );
+            assert!(!output.contains("hunter2"), "Password leaked: {output}");
+
+            let logged = format!(
+                r#"Response 200 body: [{{"name":"{}… (113 bytes)"#,
+                "x".repeat(30)
+            );
+            assert!(
+                output.contains(&logged),
+                "Response body should be truncated: {output}"
+            );
+        }
+
+        /// Tests that `--log-bodies` logs request bodies over the body limit by size.
+        ///
+        /// Verifies that the oversized write is answered `413 Payload Too Large`
+        /// by the handler without reaching the model, as it is without body
+        /// logging, and that the request log leaves its content out.
+        #[tokio::test]
+        async fn test_logged_request_body_is_limited() {
+            let logs = CapturedLogs::default();
+            let writer = logs.clone();
+            let subscriber = tracing_subscriber::fmt()
+                .with_writer(move || writer.clone())
+                .with_ansi(false)
+                .with_max_level(tracing::Level::DEBUG)
+                .finish();
+            let _guard = tracing::subscriber::set_default(subscriber);
+
+            let config = json!({
+                "routes": {
+                    "/api/notes": {"response": {"status": 200, "body": []}},
+                    "[POST] /api/notes": {"response": {"status": 201, "body": []}}
+                }
+            });
+            let payload = json!({"text": "x".repeat(REQUEST_BODY_LIMIT)}).to_string();
+
+            for logged in [true, false] {
+                let router = test_router_with(config.clone(), |state| {
+                    state.with_body_logging(logged.then_some(40))
+                })
+                .await;
+
+                let request = Request::builder()
+                    .method("POST")
+                    .uri("/api/notes")
+                    .header(CONTENT_TYPE, "application/json")
+                    .body(Body::from(payload.clone()))
+                    .expect("Failed to build request");
+                assert_eq!(
+                    send(&router, request).await.status(),
+                    StatusCode::PAYLOAD_TOO_LARGE
+                );
+                assert_eq!(body_json(get(&router, "/api/notes").await).await, json!([]));
+            }
+
+            let output = String::from_utf8(logs.0.lock().expect("Log buffer poisoned").clone())
+                .expect("Logs should be UTF-8");
+            assert!(
+                output.contains("bytes read before the body was dropped, not logged"),
+                "{output}"
+            );
+            assert!(!output.contains("xxxxxxxx"), "{output}");
+        }
+    }
+
+    mod stream_tests {
+        use super::*;
+        use axum::http::header::ETAG;
+        use futures_util::StreamExt;
+        use tokio::time::Instant;
+
+        /// Builds a router streaming a collection of three items every 100 ms.
+        async fn stream_router() -> Router {
+            test_router(json!({
+                "routes": {
+                    "/api/feed": {
+                        "stream": true,
+                        "stream_interval_ms": 100,
+                        "response": {"status": 200, "body": [{"id": 1}, {"id": 2}, {"id": 3}]}
+                    },
+                    "/api/feed/{id}": {
+                        "stream": true,
+                        "response": {"status": 200, "body": [{"id": 1}, {"id": 2}, {"id": 3}]}
+                    }
+                }
+            }))
+            .await
+        }
+
+        /// Tests that streamed items arrive spaced at least the interval apart.
+        ///
+        /// Verifies the NDJSON content type, one item per line in order, and that
+        /// the body is not buffered by the entity tag middleware.
+        #[tokio::test(start_paused = true)]
+        async fn test_stream_interval_spaces_items() {
+            let router = stream_router().await;
+
+            let response = get(&router, "/api/feed").await;
+            assert_eq!(response.status(), StatusCode::OK);
+            assert_eq!(response.headers()[CONTENT_TYPE], "application/x-ndjson");
+            assert!(response.headers().get(ETAG).is_none());
+
+            let mut chunks = response.into_body().into_data_stream();
+            let mut arrivals = Vec::new();
+            let mut items = Vec::new();
+            while let Some(chunk) = chunks.next().await {
+                let chunk = chunk.expect("Failed to read chunk");
+                arrivals.push(Instant::now());
+                for line in std::str::from_utf8(&chunk)
+                    .expect("Chunk should be UTF-8")
+                    .lines()
+                {
+                    items.push(serde_json::from_str::<Value>(line).expect("Line should be JSON"));
+                }
+            }
+
+            assert_eq!(
+                items,
+                [json!({"id": 1}), json!({"id": 2}), json!({"id": 3})]
+            );
+            assert_eq!(arrivals.len(), 3, "Each item should arrive on its own");
+            for gap in arrivals.windows(2) {
+                assert!(
+                    gap[1] - gap[0] >= Duration::from_millis(100),
+                    "Items arrived {:?} apart",
+                    gap[1] - gap[0]
+                );
+            }
+        }
+
+        /// Tests that a single entry of a streaming route is served as plain JSON.
+        #[tokio::test]
+        async fn test_stream_entry_is_not_streamed() {
+            let router = stream_router().await;
+
+            let response = get(&router, "/api/feed/2").await;
+            assert_eq!(response.headers()[CONTENT_TYPE], "application/json");
+            assert_eq!(body_json(response).await, json!({"id": 2}));
+        }
+    }
+
+    mod request_id_tests {
+        use super::*;
+
+        /// Sends a GET to `/api/ping`, with the given request id header if any.
+        async fn ping(router: &Router, header: Option<(&str, &str)>) -> Response {
+            let mut request = Request::builder().uri("/api/ping");
+            if let Some((name, value)) = header {
+                request = request.header(name, value);
+            }
+            send(
+                router,
+                request
+                    .body(Body::empty())
+                    .expect("Failed to build request"),
+            )
+            .await
+        }
+
+        /// Tests that a provided request id is echoed and carried by the log events.
+        #[tokio::test]
+        async fn test_request_id_is_echoed_and_logged() {
+            let logs = CapturedLogs::default();
+            let writer = logs.clone();
+            let subscriber = tracing_subscriber::fmt()
+                .with_writer(move || writer.clone())
+                .with_ansi(false)
+                .with_max_level(tracing::Level::DEBUG)
+                .finish();
+            let _guard = tracing::subscriber::set_default(subscriber);
+
+            let router = test_router(json!({
+                "routes": {"/api/ping": {"response": {"status": 200, "body": {"pong": true}}}}
+            }))
+            .await;
+
+            let response = ping(&router, Some(("X-Request-Id", "trace-42"))).await;
+            assert_eq!(response.status(), StatusCode::OK);
+            assert_eq!(response.headers()["x-request-id"], "trace-42");
+
+            let output = String::from_utf8(logs.0.lock().expect("Log buffer poisoned").clone())
+                .expect("Logs should be UTF-8");
+            assert!(
+                output.contains("request_id=trace-42"),
+                "The request log should carry the id: {output}"
+            );
+        }
+
+        /// Tests that a request id is generated when the request has none.
+        ///
+        /// Verifies that the generated id is a UUID and differs between requests.
+        #[tokio::test]
+        async fn test_request_id_is_generated_when_omitted() {
+            let router = test_router(json!({
+                "routes": {"/api/ping": {"response": {"status": 200, "body": {"pong": true}}}}
+            }))
+            .await;
+
+            let first = ping(&router, None).await;
+            let second = ping(&router, None).await;
+            let first = first.headers()["x-request-id"]
+                .to_str()
+                .expect("Id should be text");
+            let second = second.headers()["x-request-id"]
+                .to_str()
+                .expect("Id should be text");
+
+            assert!(
+                uuid::Uuid::parse_str(first).is_ok(),
+                "{first} is not a UUID"
+            );
+            assert_ne!(first, second);
+        }
+
+        /// Tests that the request id header can be configured.
+        #[tokio::test]
+        async fn test_request_id_header_is_configurable() {
+            let router = test_router(json!({
+                "request_id_header": "X-Correlation-Id",
+                "routes": {"/api/ping": {"response": {"status": 200, "body": {"pong": true}}}}
+            }))
+            .await;
+
+            let response = ping(&router, Some(("x-correlation-id", "corr-7"))).await;
+            assert_eq!(response.headers()["x-correlation-id"], "corr-7");
+            assert!(response.headers().get("x-request-id").is_none());
+        }
+    }
+
+    mod job_tests {
+        use super::*;
+
+        /// Builds a router whose export jobs are pending for two polls, then done.
+        async fn job_router() -> Router {
+            test_router(json!({
+                "routes": {
+                    "[POST] /api/exports": {
+                        "job": {"location": "/api/jobs/{id}"},
+                        "response": {"status": 200, "body": {}}
+                    },
+                    "/api/jobs/{id}": {
+                        "sequence": [
+                            {"status": 200, "body": {"state": "pending"}},
+                            {"status": 200, "body": {"state": "pending"}},
+                            {"status": 200, "body": {"state": "done"}}
+                        ],
+                        "response": {"status": 200, "body": {}}
+                    }
+                }
+            }))
+            .await
+        }
+
+        /// Starts an export job and returns its status location.
+        async fn start_export(router: &Router) -> String {
+            let request = Request::builder()
+                .method("POST")
+                .uri("/api/exports")
+                .header(CONTENT_TYPE, "application/json")
+                .body(Body::from(json!({"format": "csv"}).to_string()))
+                .expect("Failed to build request");
+            let response = send(router, request).await;
+            assert_eq!(response.status(), StatusCode::ACCEPTED);
+
+            let location = response.headers()[LOCATION]
+                .to_str()
+                .expect("Location should be text")
+                .to_string();
+            let body = body_json(response).await;
+            assert_eq!(
+                location,
+                format!("/api/jobs/{}", body["id"].as_str().unwrap_or_default())
+            );
+            location
+        }
+
+        /// Polls a job status location and returns the reported state.
+        async fn poll(router: &Router, location: &str) -> Value {
+            body_json(get(router, location).await).await["state"].clone()
+        }
+
+        /// Tests that a job started with POST eventually reports completion.
+        ///
+        /// Verifies the `202 Accepted` and `Location` of the POST, and that a
+        /// second job progresses from the first state on its own.
+        #[tokio::test]
+        async fn test_job_progresses_until_done() {
+            let router = job_router().await;
+            let first = start_export(&router).await;
+
+            assert_eq!(poll(&router, &first).await, "pending");
+            assert_eq!(poll(&router, &first).await, "pending");
+
+            let second = start_export(&router).await;
+            assert_ne!(first, second);
+            assert_eq!(poll(&router, &second).await, "pending");
+
+            assert_eq!(poll(&router, &first).await, "done");
+            assert_eq!(poll(&router, &first).await, "done");
+        }
+
+        /// Tests that polling a job that was never started answers 404.
+        #[tokio::test]
+        async fn test_unknown_job_is_not_found() {
+            let router = job_router().await;
+
+            let response = get(&router, "/api/jobs/unknown").await;
+            assert_eq!(response.status(), StatusCode::NOT_FOUND);
+            assert_eq!(body_json(response).await, json!({"error": "Job not found"}));
+        }
+    }
+
+    mod trailing_slash_tests {
+        use super::*;
+
+        /// Sends a GET request and returns the response status.
+        async fn status(router: &Router, uri: &str) -> StatusCode {
+            get(router, uri).await.status()
+        }
+
+        /// Tests that both forms of a path are served in the default merge mode.
+        ///
+        /// Verifies the collection, an entry and the query string of a path
+        /// requested with a trailing slash.
+        #[tokio::test]
+        async fn test_merge_serves_both_forms() {
+            let router = test_router(json!({
+                "routes": {
+                    "/users": {"response": {"status": 200, "body": [{"id": 1}, {"id": 2}]}},
+                    "/users/{id}": {"response": {"status": 200, "body": [{"id": 1}, {"id": 2}]}}
+                }
+            }))
+            .await;
+
+            assert_eq!(status(&router, "/users").await, StatusCode::OK);
+            assert_eq!(status(&router, "/users/").await, StatusCode::OK);
+
+            assert_eq!(
+                body_json(get(&router, "/users/2/").await).await,
+                json!({"id": 2})
+            );
+        }
+
+        /// Tests that strict mode keeps the trailing slash significant.
+        #[tokio::test]
+        async fn test_strict_keeps_forms_distinct() {
+            let router = test_router(json!({
+                "trailing_slash": "strict",
+                "routes": {"/users": {"response": {"status": 200, "body": []}}}
+            }))
+            .await;
+
+            assert_eq!(status(&router, "/users").await, StatusCode::OK);
+            assert_eq!(status(&router, "/users/").await, StatusCode::NOT_FOUND);
+        }
+
+        /// Tests that static directories keep their trailing slash in merge mode.
+        #[tokio::test]
+        async fn test_merge_leaves_static_paths_alone() {
+            let dir = tempfile::tempdir().expect("Failed to create temporary directory");
+            std::fs::create_dir(dir.path().join("docs")).expect("Failed to create directory");
+            std::fs::write(dir.path().join("docs").join("index.html"), "<h1>Docs</h1>")
+                .expect("Failed to write static file");
+
+            let router = test_router(json!({
+                "static_folder": dir.path().display().to_string(),
+                "routes": {"/api/ping": {"response": {"status": 200, "body": "pong"}}}
+            }))
+            .await;
+
+            assert_eq!(status(&router, "/static/docs/").await, StatusCode::OK);
+            assert_eq!(status(&router, "/api/ping/").await, StatusCode::OK);
+        }
+
+        /// Tests that routes declared with a trailing slash stay reachable in merge mode.
+        ///
+        /// Verifies that a declared `/users/` and `/users/{id}/` answer their
+        /// own bodies while `/teams/` is still served by `/teams`.
+        #[tokio::test]
+        async fn test_merge_serves_declared_trailing_slash() {
+            let router = test_router(json!({
+                "routes": {
+                    "/users/": {"response": {"status": 200, "body": "slashed"}},
+                    "/users/{id}/": {"response": {"status": 200, "body": "entry"}},
+                    "/teams": {"response": {"status": 200, "body": []}}
+                }
+            }))
+            .await;
+
+            for uri in ["/users/", "/users/1/"] {
+                let response = get(&router, uri).await;
+                assert_eq!(response.status(), StatusCode::OK, "{uri}");
+            }
+            assert_eq!(status(&router, "/teams/").await, StatusCode::OK);
+        }
+
+        /// Tests which paths are trimmed.
+        #[test]
+        fn test_trimmed_path() {
+            assert_eq!(trimmed_path("/users/", "/static"), Some("/users"));
+            assert_eq!(trimmed_path("/users//", "/static"), Some("/users"));
+            assert_eq!(trimmed_path("/users", "/static"), None);
+            assert_eq!(trimmed_path("/", "/static"), None);
+            assert_eq!(trimmed_path("/static/docs/", "/static"), None);
+            assert_eq!(trimmed_path("/statics/", "/static"), Some("/statics"));
+        }
+    }
+
+    mod variant_tests {
+        use super::*;
+
+        /// Posts a login payload and returns the response.
+        async fn login(router: &Router, payload: &Value) -> Response {
+            let request = Request::builder()
+                .method("POST")
+                .uri("/login")
+                .header(CONTENT_TYPE, "application/json")
+                .body(Body::from(payload.to_string()))
+                .expect("Failed to build request");
+            send(router, request).await
+        }
+
+        /// Tests that the request body selects the variant answered.
+        ///
+        /// Verifies that a partial match ignores extra fields, that a body not
+        /// matching falls through to the variant without `when_body`, and that
+        /// nested fields are compared.
+        #[tokio::test]
+        async fn test_body_selects_variant() {
+            let router = test_router(json!({
+                "routes": {
+                    "[POST] /login": {
+                        "variants": [
+                            {"when_body": {"user": "admin"}, "status": 200, "body": {"token": "abc"}},
+                            {"when_body": {"profile": {"role": "support"}}, "status": 403, "body": {"error": "read only"}},
+                            {"status": 401, "body": {"error": "invalid credentials"}}
+                        ],
+                        "response": {"status": 200, "body": {}}
+                    }
+                }
+            }))
+            .await;
+
+            let response = login(&router, &json!({"user": "admin", "password": "x"})).await;
+            assert_eq!(response.status(), StatusCode::OK);
+            assert_eq!(body_json(response).await, json!({"token": "abc"}));
+
+            let response = login(&router, &json!({"user": "guest"})).await;
+            assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+            assert_eq!(
+                body_json(response).await,
+                json!({"error": "invalid credentials"})
+            );
+
+            let response = login(
+                &router,
+                &json!({"user": "sam", "profile": {"role": "support", "team": "eu"}}),
+            )
+            .await;
+            assert_eq!(response.status(), StatusCode::FORBIDDEN);
+        }
+
+        /// Tests that a payload matching no variant gets the regular response.
+        #[tokio::test]
+        async fn test_unmatched_body_falls_back_to_route_response() {
+            let router = test_router(json!({
+                "routes": {
+                    "[POST] /login": {
+                        "variants": [{"when_body": {"user": "admin"}, "status": 200, "body": {}}],
+                        "response": {"status": 401, "body": {"error": "denied"}}
+                    }
+                }
+            }))
+            .await;
+
+            let response = login(&router, &json!({"user": "guest"})).await;
+            assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+        }
+    }
+
+    mod timestamp_tests {
+        use super::*;
+        use crate::timestamp::parse_now;
+        use std::time::{SystemTime, UNIX_EPOCH};
+
+        /// Builds a router with a strict route whose body carries time tokens.
+        async fn status_router() -> Router {
+            test_router(json!({
+                "routes": {
+                    "/api/status": {
+                        "strict_query": true,
+                        "response": {
+                            "status": 200,
+                            "body": {"checkedAt": "{{now:iso8601}}", "epoch": "{{timestamp}}"}
+                        }
+                    }
+                }
+            }))
+            .await
+        }
+
+        /// Sends a GET request and returns the JSON body.
+        async fn get_json(router: &Router, uri: &str) -> Value {
+            let response = get(router, uri).await;
+            assert_eq!(response.status(), StatusCode::OK);
+            body_json(response).await
+        }
+
+        /// Tests that an iso8601 token renders the current time as RFC 3339.
+        #[tokio::test]
+        async fn test_now_token_renders_current_time() {
+            let router = status_router().await;
+            let before = SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .expect("Clock before epoch")
+                .as_secs();
+
+            let body = get_json(&router, "/api/status").await;
+            let checked_at = body["checkedAt"]
+                .as_str()
+                .expect("checkedAt should be text");
+            let parsed = parse_now(checked_at).expect("checkedAt should be RFC 3339");
+            let seconds = parsed
+                .duration_since(UNIX_EPOCH)
+                .expect("Time before epoch")
+                .as_secs();
+
+            assert!(seconds >= before && seconds <= before + 5, "{checked_at}");
+            assert_eq!(body["epoch"], json!(seconds));
+        }
+
+        /// Tests that `_now` pins the time without tripping strict query validation.
+        #[tokio::test]
+        async fn test_now_override_is_honored() {
+            let router = status_router().await;
+
+            let body = get_json(&router, "/api/status?_now=2026-10-14T09%3A30%3A00Z").await;
+            assert_eq!(
+                body,
+                json!({"checkedAt": "2026-10-14T09:30:00Z", "epoch": 1_791_970_200})
+            );
+
+            let body = get_json(&router, "/api/status?_now=0").await;
+            assert_eq!(body["checkedAt"], "1970-01-01T00:00:00Z");
+        }
+
+        /// Tests that only routes configured with a time token are rendered.
+        ///
+        /// Verifies that a token posted to a route configured without one is
+        /// stored and served as sent.
+        #[tokio::test]
+        async fn test_untimed_routes_are_not_rendered() {
+            let router = test_router(json!({
+                "routes": {
+                    "/api/notes": {"response": {"status": 200, "body": []}},
+                    "[POST] /api/notes": {"response": {"status": 201, "body": []}}
+                }
+            }))
+            .await;
+
+            let request = Request::builder()
+                .method("POST")
+                .uri("/api/notes")
+                .header("Content-Type", "application/json")
+                .body(Body::from(json!({"id": 1, "text": "{{now}}"}).to_string()))
+                .expect("Failed to build request");
+            assert!(send(&router, request).await.status().is_success());
+
+            assert_eq!(
+                get_json(&router, "/api/notes?_now=0").await,
+                json!([{"id": 1, "text": "{{now}}"}])
+            );
+        }
+    }
+
+    mod upload_tests {
+        use super::*;
+
+        /// Builds a router whose uploads are saved below the given root.
+        async fn upload_router(root: &std::path::Path) -> Router {
+            upload_router_with(root, |state| state).await
+        }
+
+        /// Builds an upload router whose state is adjusted by `customize` first.
+        async fn upload_router_with(
+            root: &std::path::Path,
+            customize: impl FnOnce(AppState) -> AppState,
+        ) -> Router {
+            let config = json!({
+                "routes": {
+                    "[POST] /api/uploads": {
+                        "save_uploads_to": "uploads/",
+                        "response": {"status": 201, "body": {}}
+                    }
+                }
+            });
+            std::fs::write(root.join("json-echo.json"), config.to_string())
+                .expect("Failed to write test config");
+
+            let file_system_manager = FileSystemManager::new(Some(root.to_path_buf()))
+                .expect("Failed to create filesystem manager");
+            let mut config_manager = ConfigManager::new(file_system_manager.clone());
+            config_manager
+                .load_config("json-echo.json")
+                .await
+                .expect("Failed to load test config");
+
+            let mut db = Database::new();
+            db.populate(config_manager.config.routes.clone());
+
+            let state = customize(AppState::new(db).with_file_system(file_system_manager));
+            create_router(Arc::new(state), &config_manager)
+        }
+
+        /// Tests that a raw upload is streamed to a file below the root.
+        ///
+        /// Verifies that the directory is created, that the response reports
+        /// the stored path and size, and that the file holds the uploaded bytes.
+        #[tokio::test]
+        async fn test_upload_is_saved_to_disk() {
+            let dir = tempfile::tempdir().expect("Failed to create temporary directory");
+            let router = upload_router(dir.path()).await;
+
+            let request = Request::builder()
+                .method("POST")
+                .uri("/api/uploads")
+                .header("content-type", "application/octet-stream")
+                .body(Body::from(&b"\x00binary\xffdata"[..]))
+                .expect("Failed to build request");
+
+            let response = send(&router, request).await;
+            assert_eq!(response.status(), StatusCode::CREATED);
+
+            let metadata = body_json(response).await;
+            assert_eq!(metadata["size"], 12);
+            let path = metadata["path"]
+                .as_str()
+                .expect("Response should carry a path");
+            assert!(path.starts_with("uploads/"), "Unexpected path {path}");
+
+            let saved = std::fs::read(dir.path().join(path)).expect("Upload should be on disk");
+            assert_eq!(saved, b"\x00binary\xffdata");
+        }
+
+        /// Tests that `--log-bodies` lets a raw upload over the body limit through.
+        ///
+        /// Verifies that the upload is still streamed to disk whole and that the
+        /// request log records its size instead of its content.
+        #[tokio::test]
+        async fn test_logged_upload_is_saved_to_disk() {
+            let logs = CapturedLogs::default();
+            let writer = logs.clone();
+            let subscriber = tracing_subscriber::fmt()
+                .with_writer(move || writer.clone())
+                .with_ansi(false)
+                .with_max_level(tracing::Level::DEBUG)
+                .finish();
+            let _guard = tracing::subscriber::set_default(subscriber);
+
+            let dir = tempfile::tempdir().expect("Failed to create temporary directory");
+            let router =
+                upload_router_with(dir.path(), |state| state.with_body_logging(Some(40))).await;
+            let size = REQUEST_BODY_LIMIT + 1024;
+
+            let request = Request::builder()
+                .method("POST")
+                .uri("/api/uploads")
+                .header("content-type", "application/octet-stream")
+                .body(Body::from(vec![b'x'; size]))
+                .expect("Failed to build request");
+
+            let response = send(&router, request).await;
+            assert_eq!(response.status(), StatusCode::CREATED);
+
+            let metadata = body_json(response).await;
+            assert_eq!(metadata["size"], size);
+            let path = metadata["path"]
+                .as_str()
+                .expect("Response should carry a path");
+            let saved = std::fs::read(dir.path().join(path)).expect("Upload should be on disk");
+            assert_eq!(saved.len(), size);
+
+            let output = String::from_utf8(logs.0.lock().expect("Log buffer poisoned").clone())
+                .expect("Logs should be UTF-8");
+            assert!(
+                output.contains(&format!("Request body: {size} bytes, too large to log")),
+                "{output}"
+            );
+        }
+
+        /// Tests that multipart file parts are saved under a safe name.
+        ///
+        /// Verifies that a file name climbing out of the directory is reduced
+        /// to its last segment and that the part's contents are saved.
+        #[tokio::test]
+        async fn test_multipart_parts_are_saved_to_disk() {
+            let dir = tempfile::tempdir().expect("Failed to create temporary directory");
+            let router = upload_router(dir.path()).await;
+
+            let boundary = "json-echo-boundary";
+            let body = format!(
+                "--{boundary}\r\n\
+                 Content-Disposition: form-data; name=\"photo\"; filename=\"../../beach.txt\"\r\n\
+                 Content-Type: text/plain\r\n\r\n\
+                 sand and sea\r\n\
+                 --{boundary}--\r\n"
+            );
+            let request = Request::builder()
+                .method("POST")
+                .uri("/api/uploads")
+                .header(
+                    "content-type",
+                    format!("multipart/form-data; boundary={boundary}"),
+                )
+                .body(Body::from(body))
+                .expect("Failed to build request");
+
+            let response = send(&router, request).await;
+            assert_eq!(response.status(), StatusCode::CREATED);
+
+            let metadata = body_json(response).await;
+            let file = &metadata["files"][0];
+            assert_eq!(file["filename"], "../../beach.txt");
+            assert_eq!(file["size"], 12);
+            let path = file["path"].as_str().expect("File should carry a path");
+            assert!(
+                path.starts_with("uploads/") && path.ends_with("-beach.txt"),
+                "Unexpected path {path}"
+            );
+
+            let saved = std::fs::read(dir.path().join(path)).expect("Upload should be on disk");
+            assert_eq!(saved, b"sand and sea");
+        }
+    }
+
+    mod maintenance_tests {
+        use super::*;
+
+        /// Switches maintenance mode through the admin route.
+        async fn toggle(router: &Router, enabled: bool) -> Value {
+            let request = Request::builder()
+                .method("POST")
+                .uri(ADMIN_MAINTENANCE_PATH)
+                .header(CONTENT_TYPE, "application/json")
+                .body(Body::from(json!({"enabled": enabled}).to_string()))
+                .expect("Failed to build request");
+            let response = send(router, request).await;
+            assert_eq!(response.status(), StatusCode::OK);
+            body_json(response).await
+        }
+
+        /// Requests a normal route.
+        async fn get_users(router: &Router) -> Response {
+            get(router, "/api/users").await
+        }
+
+        /// Tests that maintenance mode answers 503 until it is switched off.
+        ///
+        /// Verifies that the configured maintenance body is served while the
+        /// flag is on, that the admin route keeps answering, and that switching
+        /// it off restores the route.
+        #[tokio::test]
+        async fn test_maintenance_mode_toggles_at_runtime() {
+            let router = test_router(json!({
+                "maintenance_body": {"message": "Back soon"},
+                "routes": {"/api/users": {"response": {"status": 200, "body": [{"id": 1}]}}}
+            }))
+            .await;
+
+            assert_eq!(get_users(&router).await.status(), StatusCode::OK);
+
+            assert_eq!(toggle(&router, true).await, json!({"maintenance": true}));
+            let response = get_users(&router).await;
+            assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+            assert_eq!(body_json(response).await, json!({"message": "Back soon"}));
+
+            let response = get(&router, ADMIN_MAINTENANCE_PATH).await;
+            assert_eq!(body_json(response).await, json!({"maintenance": true}));
+
+            assert_eq!(toggle(&router, false).await, json!({"maintenance": false}));
+            let response = get_users(&router).await;
+            assert_eq!(response.status(), StatusCode::OK);
+            assert_eq!(body_json(response).await, json!([{"id": 1}]));
+        }
+    }
+
+    mod scalar_body_tests {
+        use super::*;
+
+        /// Builds a router serving bare scalar bodies.
+        async fn scalar_router() -> Router {
+            test_router(json!({
+                "routes": {
+                    "/api/count": {"response": {"status": 200, "body": 42}},
+                    "/api/enabled": {"response": {"status": 200, "body": true}},
+                    "/api/status": {"response": {"status": 200, "body": "ok"}},
+                    "/api/count.txt": {
+                        "headers": {"Content-Type": "text/plain"},
+                        "response": {"status": 200, "body": 42.5}
+                    },
+                    "/api/enabled.html": {
+                        "headers": {"Content-Type": "text/html"},
+                        "response": {"status": 200, "body": false}
+                    }
+                }
+            }))
+            .await
+        }
+
+        /// Tests that bare numbers, booleans and strings are served as JSON.
+        #[tokio::test]
+        async fn test_scalar_bodies_are_json() {
+            let router = scalar_router().await;
+
+            for (path, expected) in [
+                ("/api/count", "42"),
+                ("/api/enabled", "true"),
+                ("/api/status", r#""ok""#),
+            ] {
+                let (content_type, body) = fetch_text(&router, path).await;
+                assert!(
+                    content_type.starts_with("application/json"),
+                    "{path} served as {content_type}"
+                );
+                assert_eq!(body, expected, "{path} body");
+            }
+        }
+
+        /// Tests that text content types stringify scalar bodies.
+        #[tokio::test]
+        async fn test_scalar_bodies_as_text() {
+            let router = scalar_router().await;
+
+            assert_eq!(fetch_text(&router, "/api/count.txt").await.1, "42.5");
+            assert_eq!(fetch_text(&router, "/api/enabled.html").await.1, "false");
+        }
+    }
+
+    mod compression_tests {
+        use super::*;
+        use axum::http::header::{ACCEPT_ENCODING, CONTENT_ENCODING};
+
+        /// Builds a router compressing a list of users and a streamed feed.
+        async fn compression_router() -> Router {
+            let users: Vec<Value> = (1..=20)
+                .map(|id| json!({"id": id, "name": "user"}))
+                .collect();
+            test_router(json!({
+                "compression": true,
+                "routes": {
+                    "/api/users": {"response": {"status": 200, "body": users}},
+                    "/api/feed": {"stream": true, "response": {"status": 200, "body": users}}
+                }
+            }))
+            .await
+        }
+
+        /// Tests that `compression` encodes bodies for clients accepting it.
+        ///
+        /// Verifies that a gzip client receives a gzip body smaller than the
+        /// original, and that a client without `Accept-Encoding` receives the
+        /// plain JSON.
+        #[tokio::test]
+        async fn test_compression_follows_accept_encoding() {
+            let router = compression_router().await;
+
+            let plain = get(&router, "/api/users").await;
+            assert!(plain.headers().get(CONTENT_ENCODING).is_none());
+            let plain = body_text(plain).await;
+            assert!(plain.starts_with("[{"), "{plain}");
+
+            let compressed = get_with(&router, "/api/users", &[(ACCEPT_ENCODING, "gzip")]).await;
+            assert_eq!(compressed.headers()[CONTENT_ENCODING], "gzip");
+            let bytes = to_bytes(compressed.into_body(), usize::MAX)
+                .await
+                .expect("Failed to read body");
+            assert!(bytes.len() < plain.len());
+        }
+
+        /// Tests that streamed NDJSON responses are never compressed.
+        #[tokio::test]
+        async fn test_streamed_responses_are_not_compressed() {
+            let router = compression_router().await;
+
+            let response = get_with(&router, "/api/feed", &[(ACCEPT_ENCODING, "gzip")]).await;
+            assert_eq!(response.headers()[CONTENT_TYPE], NDJSON);
+            assert!(response.headers().get(CONTENT_ENCODING).is_none());
+        }
+    }
+
+    mod keep_alive_tests {
+        use super::*;
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        /// Serves the router on a local port and sends one keep-alive request.
+        ///
+        /// Returns the raw response once the server closed the connection;
+        /// fails if the connection is kept open.
+        async fn request_once(router: Router, path: &str) -> String {
+            let listener = tokio::net::TcpListener::bind("127.0.0.1:0")
+                .await
+                .expect("Failed to bind test server");
+            let address = listener.local_addr().expect("Test server has no address");
+            tokio::spawn(async move { axum::serve(listener, router).await });
+
+            let mut stream = tokio::net::TcpStream::connect(address)
+                .await
+                .expect("Failed to connect to test server");
+            let request = format!("GET {path} HTTP/1.1\r\nHost: {address}\r\n\r\n");
+            stream
+                .write_all(request.as_bytes())
+                .await
+                .expect("Failed to send request");
+
+            let mut raw = Vec::new();
+            tokio::time::timeout(Duration::from_secs(5), stream.read_to_end(&mut raw))
+                .await
+                .expect("The server should close the connection")
+                .expect("Failed to read response");
+            String::from_utf8_lossy(&raw).to_ascii_lowercase()
+        }
+
+        /// Tests that the global flag closes every connection after one response.
+        #[tokio::test]
+        async fn test_disable_keep_alive_closes_connections() {
+            let router = test_router(json!({
+                "disable_keep_alive": true,
+                "routes": {"/api/users": {"response": {"status": 200, "body": [{"id": 1}]}}}
+            }))
+            .await;
+
+            let response = request_once(router.clone(), "/api/users").await;
+            assert!(response.starts_with("http/1.1 200"), "{response}");
+            assert!(response.contains("connection: close"), "{response}");
+
+            let response = request_once(router, "/api/missing").await;
+            assert!(response.starts_with("http/1.1 404"), "{response}");
+            assert!(response.contains("connection: close"), "{response}");
+        }
+
+        /// Tests that the route flag only closes connections on its own path.
+        #[tokio::test]
+        async fn test_route_disable_keep_alive() {
+            let router = test_router(json!({
+                "routes": {
+                    "/api/users": {
+                        "disable_keep_alive": true,
+                        "response": {"status": 200, "body": []}
+                    },
+                    "/api/orders": {"response": {"status": 200, "body": []}}
+                }
+            }))
+            .await;
+
+            assert!(
+                get(&router, "/api/orders")
+                    .await
+                    .headers()
+                    .get(CONNECTION)
+                    .is_none()
+            );
+
+            let response = request_once(router, "/api/users").await;
+            assert!(response.contains("connection: close"), "{response}");
+        }
+    }
+
+    mod id_strategy_tests {
+        use super::*;
+
+        /// Tests that a generated id is shared by the write route and its GET route.
+        ///
+        /// Verifies that a POST without an id stores the entry with one UUID in
+        /// both synced models, so the created entry can be fetched by that id.
+        #[tokio::test]
+        async fn test_generated_id_is_synced() {
+            let router = test_router(json!({
+                "routes": {
+                    "/api/users": {"response": {"status": 200, "body": [{"id": "a", "name": "John"}]}},
+                    "[POST] /api/users": {
+                        "id_strategy": "uuid",
+                        "response": {"status": 201, "body": [{"id": "a", "name": "John"}]}
+                    }
+                }
+            }))
+            .await;
+
+            let request = Request::builder()
+                .method("POST")
+                .uri("/api/users")
+                .header(CONTENT_TYPE, "application/json")
+                .body(Body::from(json!({"name": "Jane"}).to_string()))
+                .expect("Failed to build request");
+            let created = body_json(send(&router, request).await).await;
+            let id = created[1]["id"]
+                .as_str()
+                .expect("The id should be a string");
+            assert!(uuid::Uuid::parse_str(id).is_ok(), "{id} should be a UUID");
+
+            let served = body_json(get(&router, "/api/users").await).await;
+            assert_eq!(served[1], json!({"name": "Jane", "id": id}));
+        }
+    }
+
+    mod status_tests {
+        use super::*;
+
+        /// Tests that non-standard statuses are answered unchanged.
+        ///
+        /// Verifies that a configured `299`, on a route and in a write route's
+        /// variants, reaches the client instead of falling back to `200`.
+        #[tokio::test]
+        async fn test_non_standard_status_passes_through() {
+            let router = test_router(json!({
+                "routes": {
+                    "/api/warning": {"response": {"status": 299, "body": {"warning": true}}},
+                    "[POST] /api/warning": {
+                        "variants": [{"status": 420, "body": {"calm": "down"}}],
+                        "response": {"status": 200, "body": {}}
+                    }
+                }
+            }))
+            .await;
+
+            let response = get(&router, "/api/warning").await;
+            assert_eq!(response.status().as_u16(), 299);
+            assert_eq!(body_json(response).await, json!({"warning": true}));
+
+            let request = Request::builder()
+                .method("POST")
+                .uri("/api/warning")
+                .header(CONTENT_TYPE, "application/json")
+                .body(Body::from("{}"))
+                .expect("Failed to build request");
+            let response = send(&router, request).await;
+            assert_eq!(response.status().as_u16(), 420);
+        }
+    }
+
+    mod cache_tests {
+        use super::*;
+        use axum::http::header::{CACHE_CONTROL, EXPIRES};
+
+        /// Returns a response header as a string, or an empty string when absent.
+        fn header(response: &Response, name: HeaderName) -> String {
+            response
+                .headers()
+                .get(name)
+                .and_then(|value| value.to_str().ok())
+                .unwrap_or_default()
+                .to_string()
+        }
+
+        /// Tests that every preset expands into its `Cache-Control` value.
+        ///
+        /// Verifies the `no-store`, `immutable` and `max_age` presets, the far-future
+        /// `Expires` of immutable responses, and that a raw header overrides the preset.
+        #[tokio::test]
+        async fn test_cache_presets() {
+            let router = test_router(json!({
+                "routes": {
+                    "/api/session": {"cache": "no-store", "response": {"body": {}}},
+                    "/api/logo": {"cache": "immutable", "response": {"body": {}}},
+                    "/api/users": {"cache": {"max_age": 60}, "response": {"body": []}},
+                    "/api/orders": {
+                        "cache": "no-store",
+                        "headers": {"Cache-Control": "private"},
+                        "response": {"body": []}
+                    },
+                    "/api/plain": {"response": {"body": []}}
+                }
+            }))
+            .await;
+
+            let response = get(&router, "/api/session").await;
+            assert_eq!(header(&response, CACHE_CONTROL), "no-store");
+            assert!(response.headers().get(EXPIRES).is_none());
+
+            let response = get(&router, "/api/logo").await;
+            assert_eq!(
+                header(&response, CACHE_CONTROL),
+                "public, max-age=31536000, immutable"
+            );
+            let expires = header(&response, EXPIRES);
+            let next_year = crate::timestamp::format_http_date(
+                std::time::SystemTime::now() + Duration::from_secs(31_536_000),
+            );
+            assert_eq!(
+                expires.get(..16),
+                next_year.get(..16),
+                "Expires is a year away"
+            );
+
+            let response = get(&router, "/api/users").await;
+            assert_eq!(header(&response, CACHE_CONTROL), "max-age=60");
+
+            let response = get(&router, "/api/orders").await;
+            assert_eq!(header(&response, CACHE_CONTROL), "private");
+
+            let response = get(&router, "/api/plain").await;
+            assert!(response.headers().get(CACHE_CONTROL).is_none());
+        }
+
+        /// Tests that error responses of a cached route are not marked cacheable.
+        #[tokio::test]
+        async fn test_cache_preset_skips_errors() {
+            let router = test_router(json!({
+                "routes": {
+                    "/api/logo": {"cache": "immutable", "response": {"status": 503, "body": {}}}
+                }
+            }))
+            .await;
+
+            let response = get(&router, "/api/logo").await;
+            assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+            assert!(response.headers().get(CACHE_CONTROL).is_none());
+            assert!(response.headers().get(EXPIRES).is_none());
+        }
+    }
+
+    mod fallback_tests {
+        use super::*;
+        use axum::http::header::ALLOW;
+
+        /// Tests the JSON bodies of the default 404 and 405 responses.
+        ///
+        /// Verifies the shape and content type of both errors, that the 405
+        /// keeps listing the path's methods in `Allow`, and that
+        /// `plain_text_errors` restores the earlier plain answers.
+        #[tokio::test]
+        async fn test_routing_errors_are_json() {
+            let routes = json!({"/api/users": {"response": {"status": 200, "body": []}}});
+            let delete = || {
+                Request::builder()
+                    .method(Method::DELETE)
+                    .uri("/api/users")
+                    .body(Body::empty())
+                    .expect("Failed to build request")
+            };
+
+            let router = test_router(json!({"routes": routes})).await;
+            let response = get(&router, "/x").await;
+            assert_eq!(response.status(), StatusCode::NOT_FOUND);
+            assert_eq!(
+                response.headers()[CONTENT_TYPE],
+                HeaderValue::from_static("application/json")
+            );
+            assert_eq!(
+                body_json(response).await,
+                json!({"error": "Not Found", "path": "/x"})
+            );
+
+            let response = send(&router, delete()).await;
+            assert_eq!(response.status(), StatusCode::METHOD_NOT_ALLOWED);
+            assert_eq!(
+                response.headers()[CONTENT_TYPE],
+                HeaderValue::from_static("application/json")
+            );
+            assert_eq!(
+                response.headers()[ALLOW],
+                HeaderValue::from_static("GET,HEAD")
+            );
+            assert_eq!(
+                body_json(response).await,
+                json!({"error": "Method Not Allowed", "method": "DELETE", "path": "/api/users"})
+            );
+
+            let router = test_router(json!({"plain_text_errors": true, "routes": routes})).await;
+            let body = to_bytes(get(&router, "/x").await.into_body(), usize::MAX)
+                .await
+                .expect("Failed to read body");
+            assert_eq!(&body[..], b"No route defined");
+
+            let response = send(&router, delete()).await;
+            assert_eq!(response.status(), StatusCode::METHOD_NOT_ALLOWED);
+            let body = to_bytes(response.into_body(), usize::MAX)
+                .await
+                .expect("Failed to read body");
+            assert!(body.is_empty());
+        }
+
+        /// Tests that unmatched paths get the fallback of their prefix.
+        ///
+        /// Verifies that `/api/missing` is answered with the API error body,
+        /// that the longest prefix wins, and that paths outside every prefix,
+        /// `/apis` included, keep the default 404.
+        #[tokio::test]
+        async fn test_fallbacks_by_prefix() {
+            let router = test_router(json!({
+                "fallbacks": [
+                    {"prefix": "/api", "response": {"status": 404, "body": {"error": "Unknown API route"}}},
+                    {"prefix": "/api/admin/", "response": {"status": 403, "body": {"error": "Forbidden"}}}
+                ],
+                "routes": {"/api/users": {"response": {"status": 200, "body": []}}}
+            }))
+            .await;
+
+            let response = get(&router, "/api/missing").await;
+            assert_eq!(response.status(), StatusCode::NOT_FOUND);
+            assert_eq!(
+                response.headers()[CONTENT_TYPE],
+                HeaderValue::from_static("application/json")
+            );
+            assert_eq!(
+                body_json(response).await,
+                json!({"error": "Unknown API route"})
+            );
+
+            let response = get(&router, "/api/admin/settings").await;
+            assert_eq!(response.status(), StatusCode::FORBIDDEN);
+            assert_eq!(body_json(response).await, json!({"error": "Forbidden"}));
+
+            for uri in ["/missing", "/apis"] {
+                let response = get(&router, uri).await;
+                assert_eq!(response.status(), StatusCode::NOT_FOUND);
+                assert_eq!(
+                    body_json(response).await,
+                    json!({"error": "Not Found", "path": uri})
+                );
+            }
+
+            assert_eq!(get(&router, "/api/users").await.status(), StatusCode::OK);
+        }
+    }
+
+    mod lazy_tests {
+        use super::*;
+
+        /// Loads the routes from a configuration file in `root` and builds a router.
+        async fn lazy_router(root: &std::path::Path, routes: Value) -> (Arc<AppState>, Router) {
+            std::fs::write(
+                root.join("json-echo.json"),
+                json!({"routes": routes}).to_string(),
+            )
+            .expect("Failed to write test config");
+            let file_system_manager = FileSystemManager::new(Some(root.to_path_buf()))
+                .expect("Failed to create filesystem manager");
+            let mut config_manager = ConfigManager::new(file_system_manager.clone());
+            config_manager
+                .load_config("json-echo.json")
+                .await
+                .expect("Failed to load test config");
+
+            let mut db = Database::new();
+            db.populate(config_manager.config.routes.clone());
+            let state = Arc::new(AppState::new(db).with_file_system(file_system_manager));
+            let router = create_router(Arc::clone(&state), &config_manager);
+            (state, router)
+        }
+
+        /// Requests the lazy catalog route and returns its body.
+        async fn get_catalog(router: &Router) -> Value {
+            let response = get(router, "/api/catalog").await;
+            assert_eq!(response.status(), StatusCode::OK);
+            body_json(response).await
+        }
+
+        /// Tests that a lazy fixture is read on first request, then cached.
+        ///
+        /// Verifies that the file is still pending once the configuration has
+        /// loaded, so a change made before the first request is served, and
+        /// that later changes are not, as the loaded response is kept.
+        #[tokio::test]
+        async fn test_lazy_fixture_loads_on_first_request() {
+            let dir = tempfile::tempdir().expect("Failed to create temporary directory");
+            let fixture = dir.path().join("catalog.json");
+            let write_fixture = |name: &str| {
+                let content = json!({"status": 200, "body": [{"id": 1, "name": name}]});
+                std::fs::write(&fixture, content.to_string()).expect("Failed to write fixture");
+            };
+            write_fixture("startup");
+
+            let (state, router) = lazy_router(
+                dir.path(),
+                json!({"/api/catalog": {"lazy": true, "response": "catalog.json"}}),
+            )
+            .await;
+
+            let pending = |state: &AppState| {
+                state
+                    .read()
+                    .get_model("[GET] /api/catalog")
+                    .and_then(|model| model.get_pending_file().map(std::path::Path::to_path_buf))
+            };
+            assert!(
+                pending(&state).is_some(),
+                "Fixture should not be loaded yet"
+            );
+
+            write_fixture("first request");
+            assert_eq!(
+                get_catalog(&router).await,
+                json!([{"id": 1, "name": "first request"}])
+            );
+            assert!(pending(&state).is_none(), "Fixture should be loaded");
+
+            write_fixture("second request");
+            assert_eq!(
+                get_catalog(&router).await,
+                json!([{"id": 1, "name": "first request"}])
+            );
+        }
+
+        /// Tests that a write sent before the first read is kept.
+        ///
+        /// Verifies that the write loads the fixture it is synced into, so
+        /// the read that follows serves the fixture with the written entry.
+        #[tokio::test]
+        async fn test_write_before_first_read_is_kept() {
+            let dir = tempfile::tempdir().expect("Failed to create temporary directory");
+            let fixture = json!({"status": 200, "body": [{"id": 1, "name": "fixture"}]});
+            std::fs::write(dir.path().join("catalog.json"), fixture.to_string())
+                .expect("Failed to write fixture");
+
+            let (_, router) = lazy_router(
+                dir.path(),
+                json!({
+                    "/api/catalog": {"lazy": true, "response": "catalog.json"},
+                    "[POST] /api/catalog": {"response": {"status": 201, "body": []}}
+                }),
+            )
+            .await;
+
+            let request = Request::builder()
+                .method("POST")
+                .uri("/api/catalog")
+                .header(CONTENT_TYPE, "application/json")
+                .body(Body::from(json!({"id": 2, "name": "written"}).to_string()))
+                .expect("Failed to build request");
+            assert!(send(&router, request).await.status().is_success());
+
+            assert_eq!(
+                get_catalog(&router).await,
+                json!([{"id": 1, "name": "fixture"}, {"id": 2, "name": "written"}])
+            );
+        }
+    }
+
+    mod method_override_tests {
+        use super::*;
+        use axum::http::header::LOCATION;
+
+        /// Builds a router answering POST, PUT and DELETE differently on one path.
+        async fn override_router(method_override: bool) -> Router {
+            test_router(json!({
+                "method_override": method_override,
+                "routes": {
+                    "/api/users/1": {"response": {"status": 200, "body": {"id": 1, "name": "John"}}},
+                    "[POST] /api/users/1": {"echo": true, "response": {"status": 200, "body": {}}},
+                    "[PUT] /api/users/1": {"response": {"status": 200, "body": {}}},
+                    "[DELETE] /api/users/1": {"redirect": {"to": "/api/users", "status": 303}}
+                }
+            }))
+            .await
+        }
+
+        /// Posts to the user with an optional method override.
+        async fn post(router: &Router, method_override: Option<&str>) -> Response {
+            let mut request = json_request("POST", "/api/users/1", &json!({"name": "Jane"}));
+            if let Some(method) = method_override {
+                request.headers_mut().insert(
+                    "X-HTTP-Method-Override",
+                    HeaderValue::from_str(method).expect("Override should be a header value"),
+                );
+            }
+            send(router, request).await
+        }
+
+        /// Reads the user as served by the GET route.
+        async fn get_user(router: &Router) -> Value {
+            body_json(get(router, "/api/users/1").await).await
+        }
+
+        /// Tests that an overridden POST is served by the overriding method's route.
+        ///
+        /// Verifies that `DELETE` reaches the DELETE route, that a lowercase
+        /// `put` is merged by the PUT route, and that unknown values keep POST.
+        #[tokio::test]
+        async fn test_post_is_routed_as_override() {
+            let router = override_router(true).await;
+
+            let response = post(&router, Some("DELETE")).await;
+            assert_eq!(response.status(), StatusCode::SEE_OTHER);
+            assert_eq!(response.headers()[LOCATION], "/api/users");
+
+            // The POST route echoes without touching the user, the PUT route merges
+            post(&router, Some("GET")).await;
+            assert_eq!(get_user(&router).await["name"], "John");
+
+            post(&router, Some("put")).await;
+            assert_eq!(get_user(&router).await, json!({"id": 1, "name": "Jane"}));
+        }
+
+        /// Tests that an overridden POST has the effect of a DELETE.
+        ///
+        /// Verifies that a POST overridden to `DELETE` on the admin requests
+        /// route clears the recorded requests, as a DELETE does, so a
+        /// follow-up GET lists none.
+        #[tokio::test]
+        async fn test_override_applies_delete_semantics() {
+            let router = test_router(json!({
+                "method_override": true,
+                "record_requests": true,
+                "routes": {"/api/users": {"response": {"status": 200, "body": []}}}
+            }))
+            .await;
+
+            get(&router, "/api/users").await;
+            let recorded = body_json(get(&router, ADMIN_REQUESTS_PATH).await).await;
+            assert_eq!(recorded.as_array().map(Vec::len), Some(1), "{recorded}");
+
+            let request = Request::builder()
+                .method("POST")
+                .uri(ADMIN_REQUESTS_PATH)
+                .header("X-HTTP-Method-Override", "DELETE")
+                .body(Body::empty())
+                .expect("Failed to build request");
+            assert_eq!(
+                send(&router, request).await.status(),
+                StatusCode::NO_CONTENT
+            );
+            assert_eq!(
+                body_json(get(&router, ADMIN_REQUESTS_PATH).await).await,
+                json!([]),
+                "The overridden POST should have cleared the recorded requests"
+            );
+        }
+
+        /// Tests that the header is ignored unless `method_override` is set.
+        #[tokio::test]
+        async fn test_override_requires_setting() {
+            let router = override_router(false).await;
+
+            let response = post(&router, Some("DELETE")).await;
+            assert_eq!(response.status(), StatusCode::OK);
+            assert_eq!(get_user(&router).await["name"], "John");
+        }
+    }
+
+    mod grow_tests {
+        use super::*;
+
+        /// Tests that a growing collection serves one more item per GET.
+        ///
+        /// Verifies that three GETs answer 1, 2 and 3 items, and that the full
+        /// collection is kept once every item is served.
+        #[tokio::test]
+        async fn test_collection_grows_per_call() {
+            let router = test_router(json!({
+                "routes": {
+                    "/api/feed": {
+                        "grow": true,
+                        "response": {"status": 200, "body": [{"id": 1}, {"id": 2}, {"id": 3}]}
+                    }
+                }
+            }))
+            .await;
+
+            let mut lengths = Vec::new();
+            for _ in 0..4 {
+                let feed = body_json(get(&router, "/api/feed").await).await;
+                lengths.push(feed.as_array().map(Vec::len));
+            }
+
+            assert_eq!(lengths, [Some(1), Some(2), Some(3), Some(3)]);
+        }
+
+        /// Tests cutting collections and leaving other data alone.
+        #[test]
+        fn test_first_items() {
+            assert_eq!(first_items(&json!([1, 2, 3]), 2), json!([1, 2]));
+            assert_eq!(first_items(&json!([1, 2, 3]), 5), json!([1, 2, 3]));
+            assert_eq!(first_items(&json!({"id": 1}), 1), json!({"id": 1}));
+        }
+    }
+
+    mod if_match_tests {
+        use super::*;
+        use axum::http::header::{ETAG, IF_MATCH};
+
+        /// Builds a router serving a user collection updated by PUT.
+        async fn users_router() -> Router {
+            test_router(json!({
+                "routes": {
+                    "/api/users/{id}": {
+                        "response": {"status": 200, "body": [{"id": 1, "name": "John"}]}
+                    },
+                    "[PUT] /api/users/{id}": {"response": {"status": 200, "body": []}}
+                }
+            }))
+            .await
+        }
+
+        /// Reads the user and its ETag.
+        async fn get_user(router: &Router) -> (String, Value) {
+            let response = get(router, "/api/users/1").await;
+            assert_eq!(response.status(), StatusCode::OK);
+            let etag = response.headers()[ETAG]
+                .to_str()
+                .expect("ETag should be ASCII")
+                .to_string();
+            (etag, body_json(response).await)
+        }
+
+        /// Renames the user, sending the given If-Match validators.
+        async fn put_user(router: &Router, name: &str, validators: &str) -> Response {
+            let mut request = json_request("PUT", "/api/users/1", &json!({"id": 1, "name": name}));
+            request.headers_mut().insert(
+                IF_MATCH,
+                HeaderValue::from_str(validators).expect("Validators should be a header value"),
+            );
+            send(router, request).await
+        }
+
+        /// Tests that writes succeed with the current ETag and fail with a stale one.
+        ///
+        /// Verifies that a matching write updates the entry and changes its
+        /// tag, and that replaying the old tag is refused with 412 without
+        /// touching the entry.
+        #[tokio::test]
+        async fn test_stale_if_match_is_refused() {
+            let router = users_router().await;
+            let (etag, user) = get_user(&router).await;
+            assert_eq!(user["name"], "John");
+
+            let response = put_user(&router, "Jane", &etag).await;
+            assert_eq!(response.status(), StatusCode::OK);
+            let (current, user) = get_user(&router).await;
+            assert_eq!(user["name"], "Jane");
+            assert_ne!(current, etag, "The tag should change with the entry");
+
+            let response = put_user(&router, "Jack", &etag).await;
+            assert_eq!(response.status(), StatusCode::PRECONDITION_FAILED);
+            assert_eq!(get_user(&router).await.1["name"], "Jane");
+        }
+
+        /// Tests that concurrent writes sent with the same ETag apply only once.
+        ///
+        /// Verifies that exactly one of the writes validated against the same
+        /// tag succeeds and that every other one is refused with 412.
+        #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+        async fn test_concurrent_if_match_applies_once() {
+            let router = users_router().await;
+            let (etag, _) = get_user(&router).await;
+
+            let mut tasks = Vec::new();
+            for index in 0..16 {
+                let router = router.clone();
+                let etag = etag.clone();
+                tasks.push(tokio::spawn(async move {
+                    put_user(&router, &format!("Writer {index}"), &etag)
+                        .await
+                        .status()
+                }));
+            }
+
+            let mut applied = 0;
+            for task in tasks {
+                match task.await.expect("Request task should not panic") {
+                    StatusCode::OK => applied += 1,
+                    status => assert_eq!(status, StatusCode::PRECONDITION_FAILED),
+                }
+            }
+            assert_eq!(applied, 1);
+        }
+
+        /// Tests that `*` requires the entry to exist.
+        #[tokio::test]
+        async fn test_wildcard_requires_entry() {
+            let router = users_router().await;
+
+            let response = put_user(&router, "Jane", "*").await;
+            assert_eq!(response.status(), StatusCode::OK);
+
+            let request = Request::builder()
+                .method("PUT")
+                .uri("/api/users/2")
+                .header(CONTENT_TYPE, "application/json")
+                .header(IF_MATCH, "*")
+                .body(Body::from(json!({"id": 2}).to_string()))
+                .expect("Failed to build request");
+            let response = send(&router, request).await;
+            assert_eq!(response.status(), StatusCode::PRECONDITION_FAILED);
+        }
+    }
+
+    mod max_concurrency_tests {
+        use super::*;
+
+        /// Builds a router serving two requests at once, with a slow route.
+        async fn limited_router() -> Router {
+            test_router(json!({
+                "max_concurrency": 2,
+                "routes": {
+                    "/api/slow": {"delay": 100, "response": {"status": 200, "body": []}}
+                }
+            }))
+            .await
+        }
+
+        /// Sends a GET request to the slow route and returns its status.
+        async fn get_slow(router: &Router) -> StatusCode {
+            get(router, "/api/slow").await.status()
+        }
+
+        /// Tests that requests beyond the limit are shed while delays hold the slots.
+        ///
+        /// Verifies that of four concurrent delayed requests two are served and
+        /// two answer 503 immediately, and that the slots free up afterwards.
+        #[tokio::test(start_paused = true)]
+        async fn test_requests_beyond_limit_are_shed() {
+            let router = limited_router().await;
+
+            let statuses = tokio::join!(
+                get_slow(&router),
+                get_slow(&router),
+                get_slow(&router),
+                get_slow(&router)
+            );
+            let statuses = [statuses.0, statuses.1, statuses.2, statuses.3];
+            let served = statuses.iter().filter(|s| **s == StatusCode::OK).count();
+            let shed = statuses
+                .iter()
+                .filter(|s| **s == StatusCode::SERVICE_UNAVAILABLE)
+                .count();
+            assert_eq!((served, shed), (2, 2), "Unexpected statuses {statuses:?}");
+
+            assert_eq!(get_slow(&router).await, StatusCode::OK);
+        }
+    }
+
+    mod sql_tests {
+        use super::*;
+
+        /// Builds a router over a SQLite fixture kept in the given directory.
+        async fn sql_router(dir: &tempfile::TempDir) -> Router {
+            let connection = rusqlite::Connection::open(dir.path().join("fixtures.db"))
+                .expect("Failed to create database");
+            connection
+                .execute_batch(
+                    "CREATE TABLE users (id INTEGER PRIMARY KEY, name TEXT);
+                     INSERT INTO users VALUES (1, 'John'), (2, 'Jane');",
+                )
+                .expect("Failed to fill database");
+
+            let config = json!({
+                "routes": {
+                    "/api/users": {
+                        "sql": {"file": "fixtures.db", "query": "SELECT * FROM users ORDER BY id"}
+                    },
+                    "/api/users/{id}": {
+                        "sql": {"file": "fixtures.db", "query": "SELECT name FROM users WHERE id = :id"}
+                    }
+                }
+            });
+            std::fs::write(dir.path().join("json-echo.json"), config.to_string())
+                .expect("Failed to write test config");
+            let file_system_manager = FileSystemManager::new(Some(dir.path().to_path_buf()))
+                .expect("Failed to create filesystem manager");
+            let mut config_manager = ConfigManager::new(file_system_manager);
+            config_manager
+                .load_config("json-echo.json")
+                .await
+                .expect("Failed to load test config");
+
+            let mut db = Database::new();
+            db.populate(config_manager.config.routes.clone());
+            create_router(Arc::new(AppState::new(db)), &config_manager)
+        }
+
+        /// Tests that a SQL route answers the rows of its table as JSON.
+        ///
+        /// Verifies a static query and one bound from a path parameter.
+        #[tokio::test]
+        async fn test_sql_route_returns_rows() {
+            let dir = tempfile::tempdir().expect("Failed to create temporary directory");
+            let router = sql_router(&dir).await;
+
+            let response = get(&router, "/api/users").await;
+            assert_eq!(response.status(), StatusCode::OK);
+            assert_eq!(
+                body_json(response).await,
+                json!([{"id": 1, "name": "John"}, {"id": 2, "name": "Jane"}])
+            );
+
+            let response = get(&router, "/api/users/2").await;
+            assert_eq!(response.status(), StatusCode::OK);
+            assert_eq!(body_json(response).await, json!([{"name": "Jane"}]));
+        }
+    }
+
+    mod route_log_level_tests {
+        use super::*;
+
+        /// Tests that a route with `log_level: "off"` logs nothing while others still do.
+        ///
+        /// Verifies that neither the access log nor the handler events of the
+        /// silenced route are emitted, and that a `warn` route drops its debug
+        /// and info events.
+        #[tokio::test]
+        async fn test_silenced_route_emits_no_events() {
+            let logs = CapturedLogs::default();
+            let writer = logs.clone();
+            let subscriber = tracing_subscriber::fmt()
+                .with_writer(move || writer.clone())
+                .with_ansi(false)
+                .with_max_level(tracing::Level::DEBUG)
+                .finish();
+            let _guard = tracing::subscriber::set_default(subscriber);
+
+            let router = test_router(json!({
+                "routes": {
+                    "/api/health": {"log_level": "off", "response": {"status": 200, "body": {}}},
+                    "/api/metrics": {"log_level": "warn", "response": {"status": 200, "body": {}}},
+                    "/api/users": {"response": {"status": 200, "body": []}}
+                }
+            }))
+            .await;
+
+            for uri in ["/api/health", "/api/metrics", "/api/users"] {
+                assert_eq!(get(&router, uri).await.status(), StatusCode::OK);
+            }
+
+            let output = String::from_utf8(logs.0.lock().expect("Log buffer poisoned").clone())
+                .expect("Logs should be UTF-8");
+            assert!(
+                output.contains("Request GET /api/users headers"),
+                "Other routes should keep their access log: {output}"
+            );
+            for silenced in ["/api/health", "/api/metrics"] {
+                assert!(
+                    !output
+                        .lines()
+                        .any(|line| !line.contains("route defined") && line.contains(silenced)),
+                    "{silenced} should not be logged: {output}"
+                );
+            }
+        }
+
+        /// Tests that the level is that of the route the request was routed to.
+        ///
+        /// Verifies that a POST overridden to a silenced DELETE route, sent
+        /// with a path in another case, logs nothing, while the POST route of
+        /// the same path keeps its access log.
+        #[tokio::test]
+        async fn test_level_follows_the_matched_route() {
+            let logs = CapturedLogs::default();
+            let writer = logs.clone();
+            let subscriber = tracing_subscriber::fmt()
+                .with_writer(move || writer.clone())
+                .with_ansi(false)
+                .with_max_level(tracing::Level::DEBUG)
+                .finish();
+            let _guard = tracing::subscriber::set_default(subscriber);
+
+            let router = test_router(json!({
+                "method_override": true,
+                "case_insensitive_paths": true,
+                "routes": {
+                    "[POST] /api/sessions": {"response": {"status": 201, "body": {}}},
+                    "[DELETE] /api/sessions": {
+                        "log_level": "off",
+                        "redirect": {"to": "/login", "status": 303}
+                    }
+                }
+            }))
+            .await;
+
+            let request = Request::builder()
+                .method("POST")
+                .uri("/API/Sessions")
+                .header("x-http-method-override", "DELETE")
+                .body(Body::empty())
+                .expect("Failed to build request");
+            assert_eq!(send(&router, request).await.status(), StatusCode::SEE_OTHER);
+            let request = Request::builder()
+                .method("POST")
+                .uri("/API/Sessions")
+                .body(Body::empty())
+                .expect("Failed to build request");
+            assert_eq!(send(&router, request).await.status(), StatusCode::CREATED);
+
+            let output = String::from_utf8(logs.0.lock().expect("Log buffer poisoned").clone())
+                .expect("Logs should be UTF-8");
+            assert!(
+                output.contains("Request POST /API/Sessions headers"),
+                "The POST route should keep its access log: {output}"
+            );
+            assert!(
+                !output.contains("Request DELETE"),
+                "The silenced DELETE route should not be logged: {output}"
+            );
+        }
+    }
+
+    mod weighted_tests {
+        use super::*;
+
+        /// Builds a router with a route answering 200 or 500 at random.
+        async fn weighted_router() -> Router {
+            test_router(json!({
+                "routes": {
+                    "/api/flaky": {
+                        "responses": [
+                            {"weight": 80, "response": {"status": 200, "body": {"ok": true}}},
+                            {"weight": 20, "response": {"status": 500, "body": {"error": "boom"}}},
+                            {"weight": 0, "response": {"status": 418, "body": {}}}
+                        ]
+                    }
+                }
+            }))
+            .await
+        }
+
+        /// Tests that a fixed seed replays the same selection sequence.
+        ///
+        /// Verifies the picks of a fresh server seeded with `_seed=42`, that a
+        /// second fresh server repeats them, and that a zero weight is never picked.
+        #[tokio::test]
+        async fn test_seeded_picks_are_deterministic() {
+            let picks = statuses(&weighted_router().await, "/api/flaky?_seed=42", 10).await;
+            assert_eq!(picks, [200, 200, 200, 200, 200, 500, 200, 500, 200, 200]);
+            assert_eq!(
+                picks,
+                statuses(&weighted_router().await, "/api/flaky?_seed=42", 10).await
+            );
+        }
+
+        /// Tests that an invalid seed is rejected.
+        #[tokio::test]
+        async fn test_invalid_seed_is_rejected() {
+            let router = weighted_router().await;
+            assert_eq!(
+                get(&router, "/api/flaky?_seed=abc").await.status(),
+                StatusCode::BAD_REQUEST
+            );
+        }
+    }
+
+    mod recorder_tests {
+        use super::*;
+
+        /// Reads the recorded requests from the admin route.
+        async fn recorded(router: &Router) -> Value {
+            let response = get(router, ADMIN_REQUESTS_PATH).await;
+            assert_eq!(response.status(), StatusCode::OK);
+            body_json(response).await
+        }
+
+        /// Tests that requests are listed in order on the admin route, then cleared.
+        ///
+        /// Verifies the method, path, query, headers, body and status of every
+        /// request, unknown paths included, that credentials and redacted fields
+        /// are masked, and that admin requests are left out.
+        #[tokio::test]
+        async fn test_requests_are_recorded_and_cleared() {
+            let router = test_router(json!({
+                "record_requests": true,
+                "redact_fields": ["password"],
+                "routes": {
+                    "/api/users": {"response": {"status": 200, "body": []}},
+                    "[POST] /api/users": {"response": {"status": 201, "body": []}}
+                }
+            }))
+            .await;
+
+            let request = Request::builder()
+                .uri("/api/users?page=2")
+                .header("x-client", "tests")
+                .header("authorization", "Bearer secret")
+                .header("cookie", "session=secret")
+                .body(Body::empty())
+                .expect("Failed to build request");
+            send(&router, request).await;
+            let payload = json!({"name": "Ada", "password": "hunter2"}).to_string();
+            let request = Request::builder()
+                .method("POST")
+                .uri("/api/users")
+                .header(CONTENT_TYPE, "application/json")
+                .header("content-length", payload.len())
+                .body(Body::from(payload))
+                .expect("Failed to build request");
+            send(&router, request).await;
+            get(&router, "/api/missing").await;
+
+            let requests = recorded(&router).await;
+            let requests = requests
+                .as_array()
+                .expect("Recorded requests should be an array");
+            assert_eq!(requests.len(), 3, "{requests:?}");
+            assert_eq!(requests[0]["method"], "GET");
+            assert_eq!(requests[0]["path"], "/api/users");
+            assert_eq!(requests[0]["query"], "page=2");
+            assert_eq!(requests[0]["headers"]["x-client"], "tests");
+            assert_eq!(requests[0]["headers"]["authorization"], "***");
+            assert_eq!(requests[0]["headers"]["cookie"], "***");
+            assert_eq!(requests[0]["body"], Value::Null);
+            assert_eq!(requests[1]["method"], "POST");
+            assert_eq!(
+                requests[1]["body"],
+                json!({"name": "Ada", "password": "***"})
+            );
+            assert_eq!(requests[2]["path"], "/api/missing");
+            assert_eq!(requests[2]["status"], 404);
+
+            let request = Request::builder()
+                .method("DELETE")
+                .uri(ADMIN_REQUESTS_PATH)
+                .body(Body::empty())
+                .expect("Failed to build request");
+            assert_eq!(
+                send(&router, request).await.status(),
+                StatusCode::NO_CONTENT
+            );
+            assert_eq!(recorded(&router).await, json!([]));
+        }
+
+        /// Tests that nothing is recorded unless `record_requests` is set.
+        #[tokio::test]
+        async fn test_recording_is_opt_in() {
+            let router = test_router(json!({
+                "routes": {"/api/users": {"response": {"status": 200, "body": []}}}
+            }))
+            .await;
+
+            assert_eq!(
+                get(&router, ADMIN_REQUESTS_PATH).await.status(),
+                StatusCode::NOT_FOUND
+            );
+        }
+
+        /// Tests that a body larger than its `Content-Length` is refused with 413.
+        #[tokio::test]
+        async fn test_oversized_body_is_refused() {
+            let router = test_router(json!({
+                "record_requests": true,
+                "routes": {"[POST] /api/users": {"response": {"status": 201, "body": []}}}
+            }))
+            .await;
+
+            let request = Request::builder()
+                .method("POST")
+                .uri("/api/users")
+                .header("content-length", 2)
+                .body(Body::from(vec![
+                    b'a';
+                    crate::recorder::RECORDED_BODY_LIMIT + 1
+                ]))
+                .expect("Failed to build request");
+            assert_eq!(
+                send(&router, request).await.status(),
+                StatusCode::PAYLOAD_TOO_LARGE
+            );
+            assert_eq!(recorded(&router).await, json!([]));
+        }
+    }
+
+    mod case_insensitive_tests {
+        use super::*;
+
+        /// Builds a router with lowercase routes, matching paths ignoring case or not.
+        async fn cased_router(case_insensitive_paths: bool) -> Router {
+            test_router(json!({
+                "case_insensitive_paths": case_insensitive_paths,
+                "routes": {
+                    "/api/users": {"response": {"status": 200, "body": [{"id": "AbC"}]}},
+                    "/api/users/{id}": {"response": {"status": 200, "body": [{"id": "AbC"}]}}
+                }
+            }))
+            .await
+        }
+
+        /// Tests that mixed-case paths reach lowercase routes when enabled.
+        ///
+        /// Verifies that the path parameter keeps its case, so the entry
+        /// with a mixed-case id is found, and that the query is preserved.
+        #[tokio::test]
+        async fn test_mixed_case_path_matches_when_enabled() {
+            let router = cased_router(true).await;
+
+            assert_eq!(
+                get(&router, "/API/Users?x=1").await.status(),
+                StatusCode::OK
+            );
+
+            let response = get(&router, "/Api/USERS/AbC").await;
+            assert_eq!(response.status(), StatusCode::OK);
+            assert_eq!(body_json(response).await, json!({"id": "AbC"}));
+        }
+
+        /// Tests that mixed-case paths are not found when disabled.
+        #[tokio::test]
+        async fn test_mixed_case_path_not_found_when_disabled() {
+            let router = cased_router(false).await;
+
+            assert_eq!(get(&router, "/api/users").await.status(), StatusCode::OK);
+            assert_eq!(
+                get(&router, "/API/Users").await.status(),
+                StatusCode::NOT_FOUND
+            );
+        }
+
+        /// Tests rewriting paths to the case of a pattern.
+        #[test]
+        fn test_folded_path() {
+            let fold =
+                |pattern: &str, path: &str| CasedPatterns::new([pattern].into_iter()).fold(path);
+
+            assert_eq!(
+                fold("/api/users/{id}", "/API/Users/AbC"),
+                Some("/api/users/AbC".to_string())
+            );
+            assert_eq!(
+                fold("/files/{*path}", "/FILES/A/b.txt"),
+                Some("/files/A/b.txt".to_string())
+            );
+            assert_eq!(
+                fold("/api/users", "/API/users/"),
+                Some("/api/users/".to_string())
+            );
+            assert_eq!(fold("/api/users", "/api/orders"), None);
+            assert_eq!(fold("/api/users/{id}", "/api/users"), None);
+        }
+
+        /// Tests that static segments win from the left, as in the router.
+        ///
+        /// Verifies that patterns with as many parameters are told apart by
+        /// where their first parameter is, whatever order they came in, and
+        /// that a parameter wins over a wildcard.
+        #[test]
+        fn test_folded_path_prefers_static_segments() {
+            for patterns in [
+                ["/api/{kind}/all", "/api/users/{id}", "/api/{*rest}"],
+                ["/api/{*rest}", "/api/users/{id}", "/api/{kind}/all"],
+            ] {
+                let patterns = CasedPatterns::new(patterns.into_iter());
+                assert_eq!(
+                    patterns.fold("/API/Users/ALL"),
+                    Some("/api/users/ALL".to_string())
+                );
+                assert_eq!(
+                    patterns.fold("/API/Orders/ALL"),
+                    Some("/api/Orders/all".to_string())
+                );
+                assert_eq!(
+                    patterns.fold("/API/Orders/1/Items"),
+                    Some("/api/Orders/1/Items".to_string())
+                );
+            }
+        }
+
+        /// Tests that the request log shows the path as sent, not the folded one.
+        #[tokio::test]
+        async fn test_log_keeps_original_path() {
+            let logs = CapturedLogs::default();
+            let writer = logs.clone();
+            let subscriber = tracing_subscriber::fmt()
+                .with_writer(move || writer.clone())
+                .with_ansi(false)
+                .with_max_level(tracing::Level::DEBUG)
+                .finish();
+            let _guard = tracing::subscriber::set_default(subscriber);
+
+            let router = cased_router(true).await;
+            assert_eq!(
+                get(&router, "/API/Users/AbC").await.status(),
+                StatusCode::OK
+            );
+
+            let output = String::from_utf8(logs.0.lock().expect("Log buffer poisoned").clone())
+                .expect("Logs should be UTF-8");
+            assert!(output.contains("Request GET /API/Users/AbC"), "{output}");
+        }
+    }
+
+    mod retry_after_tests {
+        use super::*;
+
+        /// Returns the `Retry-After` header of a response, if any.
+        fn retry_after(response: &Response) -> Option<&str> {
+            response
+                .headers()
+                .get("retry-after")
+                .and_then(|value| value.to_str().ok())
+        }
+
+        /// Tests that both forms of `retry_after` produce valid headers on 429 and 503.
+        ///
+        /// Verifies seconds, an RFC 3339 time rendered as an HTTP date, and that
+        /// successful responses of a route with `retry_after` carry no header.
+        #[tokio::test]
+        async fn test_route_retry_after_forms() {
+            let router = test_router(json!({
+                "routes": {
+                    "/api/busy": {"retry_after": 120, "error": {"status": 503}},
+                    "/api/limited": {
+                        "retry_after": "2026-10-14T09:30:00Z",
+                        "error": {"status": 429}
+                    },
+                    "/api/fine": {"retry_after": 30, "response": {"status": 200, "body": {}}}
+                }
+            }))
+            .await;
+
+            let response = get(&router, "/api/busy").await;
+            assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+            assert_eq!(retry_after(&response), Some("120"));
+
+            let response = get(&router, "/api/limited").await;
+            assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+            assert_eq!(
+                retry_after(&response),
+                Some("Wed, 14 Oct 2026 09:30:00 GMT")
+            );
+
+            assert_eq!(retry_after(&get(&router, "/api/fine").await), None);
+        }
+
+        /// Tests that maintenance mode sends its configured `Retry-After`.
+        #[tokio::test]
+        async fn test_maintenance_retry_after() {
+            let router = test_router(json!({
+                "maintenance_retry_after": "Wed, 14 Oct 2026 09:30:00 GMT",
+                "routes": {"/api/users": {"response": {"status": 200, "body": []}}}
+            }))
+            .await;
+
+            let request = Request::builder()
+                .method("POST")
+                .uri(ADMIN_MAINTENANCE_PATH)
+                .header(CONTENT_TYPE, "application/json")
+                .body(Body::from(json!({"enabled": true}).to_string()))
+                .expect("Failed to build request");
+            send(&router, request).await;
+
+            let response = get(&router, "/api/users").await;
+            assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+            assert_eq!(
+                retry_after(&response),
+                Some("Wed, 14 Oct 2026 09:30:00 GMT")
+            );
+        }
+    }
+
+    mod proxy_tests {
+        use super::*;
+        use axum::routing::post;
+
+        /// Tests forwarding a declared route to a local stub upstream.
+        ///
+        /// Verifies that the method, a request header, the query and the body
+        /// reach the rewritten upstream path, that the upstream's status,
+        /// headers and body are answered, and that other routes stay mocked.
+        #[tokio::test]
+        async fn test_proxy_forwards_to_upstream() {
+            let stub = Router::new().route(
+                "/v1/charges/{id}",
+                post(
+                    |Path(id): Path<String>,
+                     Query(query): Query<HashMap<String, String>>,
+                     headers: HeaderMap,
+                     body: String| async move {
+                        (
+                            StatusCode::CREATED,
+                            [("x-upstream", "sandbox")],
+                            axum::Json(json!({
+                                "id": id,
+                                "query": query,
+                                "key": headers["x-api-key"].to_str().unwrap_or_default(),
+                                "body": body
+                            })),
+                        )
+                    },
+                ),
+            );
+            let listener = tokio::net::TcpListener::bind("127.0.0.1:0")
+                .await
+                .expect("Failed to bind stub server");
+            let address = listener.local_addr().expect("Stub server has no address");
+            tokio::spawn(async move { axum::serve(listener, stub).await });
+
+            let router = test_router(json!({
+                "routes": {
+                    "[POST] /payments/{*path}": {
+                        "proxy": {"target": format!("http://{address}"), "path_rewrite": "/v1/{path}"}
+                    },
+                    "/api/users": {"response": {"status": 200, "body": [{"id": 1}]}}
+                }
+            }))
+            .await;
+
+            let request = Request::builder()
+                .method(Method::POST)
+                .uri("/payments/charges/ch_1?capture=true")
+                .header("x-api-key", "test-key")
+                .body(Body::from("amount=100"))
+                .expect("Failed to build request");
+            let response = send(&router, request).await;
+            assert_eq!(response.status(), StatusCode::CREATED);
+            assert_eq!(
+                response.headers()["x-upstream"],
+                HeaderValue::from_static("sandbox")
+            );
+            assert_eq!(
+                body_json(response).await,
+                json!({
+                    "id": "ch_1",
+                    "query": {"capture": "true"},
+                    "key": "test-key",
+                    "body": "amount=100"
+                })
+            );
+
+            assert_eq!(
+                body_json(get(&router, "/api/users").await).await,
+                json!([{"id": 1}])
+            );
+        }
+    }
+
+    mod number_tests {
+        use super::*;
+
+        /// The raw values of the test collection, past what a float holds.
+        const ACCOUNTS: &str = r#"[{"id":9007199254740993,"balance":12345678901234567890.10},{"id":9007199254740992,"balance":0.1}]"#;
+
+        /// Tests that large integer ids and precise decimals round-trip exactly.
+        ///
+        /// Verifies that the collection is served byte-identically, that ids
+        /// past 2^53, equal as floats, are each looked up to their own entry,
+        /// and that such ids sort exactly.
+        #[tokio::test]
+        async fn test_large_numbers_round_trip() {
+            let accounts: Value = serde_json::from_str(ACCOUNTS).expect("Valid JSON");
+            let router = test_router(json!({
+                "routes": {
+                    "/api/accounts": {"response": {"status": 200, "body": accounts}},
+                    "/api/accounts/{id}": {"response": {"status": 200, "body": accounts}}
+                }
+            }))
+            .await;
+
+            let response = get(&router, "/api/accounts").await;
+            assert_eq!(response.status(), StatusCode::OK);
+            assert_eq!(body_text(response).await, ACCOUNTS);
+
+            let response = get(&router, "/api/accounts/9007199254740993").await;
+            assert_eq!(response.status(), StatusCode::OK);
+            assert_eq!(
+                body_text(response).await,
+                r#"{"id":9007199254740993,"balance":12345678901234567890.10}"#
+            );
+
+            let response = get(&router, "/api/accounts/9007199254740992").await;
+            assert_eq!(
+                body_text(response).await,
+                r#"{"id":9007199254740992,"balance":0.1}"#
+            );
+
+            let response = get(&router, "/api/accounts?_sort=id").await;
+            let ids: Vec<String> = body_json(response)
+                .await
+                .as_array()
+                .expect("Array")
+                .iter()
+                .map(|account| account["id"].to_string())
+                .collect();
+            assert_eq!(ids, ["9007199254740992", "9007199254740993"]);
+        }
+    }
+
+    mod payload_transform_tests {
+        use super::*;
+
+        /// Tests that a write route stores the unwrapped payload and answers it wrapped.
+        ///
+        /// Verifies that the GET route serves the inner object, that the
+        /// response nests it back under `response_wrap`, and that a payload
+        /// without the unwrapped part is rejected without merging.
+        #[tokio::test]
+        async fn test_request_unwrap_and_response_wrap() {
+            let router = test_router(json!({
+                "routes": {
+                    "/api/profile": {"response": {"status": 200, "body": {"name": "Ada"}}},
+                    "[POST] /api/profile": {
+                        "request_unwrap": "/user",
+                        "response_wrap": "/data/user",
+                        "response": {"status": 200, "body": {"name": "Ada"}}
+                    }
+                }
+            }))
+            .await;
+
+            let response = post_json(
+                &router,
+                "/api/profile",
+                &json!({"user": {"role": "admin"}, "meta": 1}),
+            )
+            .await;
+            assert_eq!(response.status(), StatusCode::OK);
+            assert_eq!(
+                body_json(response).await,
+                json!({"data": {"user": {"name": "Ada", "role": "admin"}}})
+            );
+
+            assert_eq!(
+                body_json(get(&router, "/api/profile").await).await,
+                json!({"name": "Ada", "role": "admin"})
+            );
+
+            let response = post_json(&router, "/api/profile", &json!({"role": "guest"})).await;
+            assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+            assert_eq!(
+                body_json(response).await,
+                json!({"error": "Payload has no value at /user"})
+            );
+        }
+    }
+
+    mod conflict_tests {
+        use super::*;
+
+        /// Tests that paths differing only in parameter names do not panic the router.
+        ///
+        /// Loading rejects such routes, so they are populated directly here.
+        /// Verifies that the first path is served and the conflicting one skipped.
+        #[tokio::test]
+        async fn test_conflicting_param_names_are_skipped() {
+            let mut config_manager = ConfigManager::new(
+                FileSystemManager::new(None).expect("Failed to create filesystem manager"),
+            );
+            config_manager
+                .load_config_from_bytes(
+                    br#"{"routes": {"/users/{id}": {"response": {"status": 200, "body": [{"id": "1"}]}}}}"#,
+                )
+                .await
+                .expect("Failed to load test config");
+
+            let mut routes = config_manager.config.routes.clone();
+            let mut conflicting = routes["[GET] /users/{id}"].clone();
+            conflicting.method = Some("DELETE".to_string());
+            routes.insert("[DELETE] /users/{userId}".to_string(), conflicting);
+            let mut db = Database::new();
+            db.populate(routes);
+
+            let router = create_router(Arc::new(AppState::new(db)), &config_manager);
+
+            assert_eq!(get(&router, "/users/1").await.status(), StatusCode::OK);
+
+            let request = Request::builder()
+                .method(Method::DELETE)
+                .uri("/users/1")
+                .body(Body::empty())
+                .expect("Failed to build request");
+            assert_eq!(
+                send(&router, request).await.status(),
+                StatusCode::METHOD_NOT_ALLOWED
+            );
+        }
+    }
+
+    mod content_type_tests {
+        use super::*;
+
+        /// Tests serving routes as HTML and plain text through `content_type`.
+        ///
+        /// Verifies that string bodies are served unquoted under the configured
+        /// type, that a charset is added to it, and that a `Content-Type` in
+        /// the route headers takes precedence.
+        #[tokio::test]
+        async fn test_content_type_field() {
+            let router = test_router(json!({
+                "routes": {
+                    "/about": {
+                        "content_type": "text/html",
+                        "response": {"status": 200, "body": "<h1>About</h1>"}
+                    },
+                    "/version": {
+                        "content_type": "text/plain",
+                        "charset": "utf-8",
+                        "response": {"status": 200, "body": "1.4.2"}
+                    },
+                    "/status": {
+                        "content_type": "text/plain",
+                        "headers": {"Content-Type": "application/json"},
+                        "response": {"status": 200, "body": "ok"}
+                    }
+                }
+            }))
+            .await;
+
+            assert_eq!(
+                fetch_text(&router, "/about").await,
+                ("text/html".to_string(), "<h1>About</h1>".to_string())
+            );
+            assert_eq!(
+                fetch_text(&router, "/version").await,
+                ("text/plain; charset=utf-8".to_string(), "1.4.2".to_string())
+            );
+            assert_eq!(
+                fetch_text(&router, "/status").await,
+                ("application/json".to_string(), "\"ok\"".to_string())
+            );
+        }
+    }
+
+    mod localized_tests {
+        use super::*;
+
+        /// Requests `/greeting` with an optional `Accept-Language` header.
+        async fn get_greeting(router: &Router, accept_language: Option<&str>) -> Response {
+            let mut request = Request::builder().uri("/greeting");
+            if let Some(accept_language) = accept_language {
+                request = request.header(ACCEPT_LANGUAGE, accept_language);
+            }
+            send(
+                router,
+                request
+                    .body(Body::empty())
+                    .expect("Failed to build request"),
+            )
+            .await
+        }
+
+        /// Tests picking localized bodies by `Accept-Language`.
+        ///
+        /// Verifies an exact match, that a quality-ordered list serves its best
+        /// configured language, and that unknown languages, or no header, fall
+        /// back to the default response.
+        #[tokio::test]
+        async fn test_localized_bodies() {
+            let router = test_router(json!({
+                "routes": {
+                    "/greeting": {
+                        "localized": {
+                            "fr": {"message": "Bonjour"},
+                            "pt-BR": {"message": "Olá"}
+                        },
+                        "response": {"status": 200, "body": {"message": "Hello"}}
+                    }
+                }
+            }))
+            .await;
+
+            let response = get_greeting(&router, Some("fr")).await;
+            assert_eq!(response.headers()[CONTENT_LANGUAGE], "fr");
+            assert_eq!(response.headers()[VARY], "accept-language");
+            assert_eq!(body_json(response).await, json!({"message": "Bonjour"}));
+
+            let response =
+                get_greeting(&router, Some("de;q=0.9, fr-CA;q=0.5, pt-br, en;q=0.8")).await;
+            assert_eq!(response.headers()[CONTENT_LANGUAGE], "pt-BR");
+            assert_eq!(body_json(response).await, json!({"message": "Olá"}));
+
+            let response = get_greeting(&router, Some("en-US;q=1, fr-CA;q=0.3")).await;
+            assert_eq!(body_json(response).await, json!({"message": "Bonjour"}));
+
+            for accept_language in [Some("de, es;q=0.5"), Some("fr;q=0, *"), None] {
+                let response = get_greeting(&router, accept_language).await;
+                assert_eq!(response.status(), StatusCode::OK);
+                assert!(response.headers().get(CONTENT_LANGUAGE).is_none());
+                assert_eq!(body_json(response).await, json!({"message": "Hello"}));
+            }
+        }
+
+        /// Tests that `Accept-Language` is appended to a `Vary` the route configures.
+        #[tokio::test]
+        async fn test_localized_vary_appended() {
+            let router = test_router(json!({
+                "routes": {
+                    "/greeting": {
+                        "headers": {"Vary": "Origin"},
+                        "localized": {"fr": {"message": "Bonjour"}},
+                        "response": {"status": 200, "body": {"message": "Hello"}}
+                    }
+                }
+            }))
+            .await;
+
+            let response = get_greeting(&router, Some("fr")).await;
+            assert_eq!(response.headers()[VARY], "Origin, accept-language");
+        }
+    }
+}
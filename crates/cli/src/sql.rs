@@ -0,0 +1,187 @@
+//! SQLite backed routes for JSON Echo.
+//!
+//! This module answers routes configured with `sql` by running their query
+//! against a SQLite file, so teams that already keep fixtures in a database
+//! can serve them without exporting every table to JSON first.
+//!
+//! ## What
+//!
+//! The module defines:
+//! - `SqlError`: Why a query could not be answered
+//! - `query_rows`: Runs the query of a route and returns its rows as JSON
+//!
+//! ## How
+//!
+//! A query is answered by:
+//! 1. Opening the database file read-only, on a blocking thread of the runtime
+//! 2. Binding every named parameter, `:id`, `@id` or `$id`, from the request parameters
+//! 3. Converting each row into an object keyed by column name, in column order
+//! 4. Answering the rows as a JSON array
+//!
+//! ## Why
+//!
+//! Every request opens its own read-only connection instead of sharing one
+//! behind a lock: SQLite allows any number of concurrent readers, opening a
+//! local file is cheap next to serving a request, and edits made to the file
+//! while the server runs are picked up by the next request. Parameters are
+//! always bound, never formatted into the statement, so request values cannot
+//! change the query.
+//!
+//! # Examples
+//!
+//! ```json
+//! {"/api/users/{id}": {"sql": {"file": "fixtures.db", "query": "SELECT * FROM users WHERE id = :id"}}}
+//! ```
+
+use json_echo_core::SqlConfig;
+use rusqlite::{Connection, OpenFlags, types::ValueRef};
+use serde_json::{Map, Number, Value};
+use std::collections::HashMap;
+
+/// Why the query of a route could not be answered.
+///
+/// # Variants
+///
+/// * `MissingParameter` - The query names a parameter the request does not carry
+/// * `Sqlite` - The database could not be opened or the query failed
+/// * `Task` - The blocking task running the query did not complete
+#[derive(Debug, thiserror::Error)]
+pub enum SqlError {
+    /// The query names a parameter the request does not carry
+    #[error("Missing SQL parameter {0}")]
+    MissingParameter(String),
+    /// The database could not be opened or the query failed
+    #[error("SQL query failed: {0}")]
+    Sqlite(#[from] rusqlite::Error),
+    /// The blocking task running the query did not complete
+    #[error("SQL query did not complete: {0}")]
+    Task(#[from] tokio::task::JoinError),
+}
+
+/// Runs the query of a route and returns its rows as a JSON array.
+///
+/// # Parameters
+///
+/// * `sql` - The database file, already resolved against the root, and query of the route
+/// * `params` - The values named parameters are bound from, keyed without their prefix
+///
+/// # Returns
+///
+/// * `Ok(Value)` - An array holding one object per row, keyed by column name
+/// * `Err(SqlError)` - If a parameter is missing or the query fails
+///
+/// # Examples
+///
+/// ```rust
+/// let sql = SqlConfig {
+///     file: "/project/fixtures.db".to_string(),
+///     query: "SELECT id, name FROM users WHERE id = :id".to_string(),
+/// };
+/// let params = HashMap::from([("id".to_string(), "1".to_string())]);
+///
+/// let rows = query_rows(&sql, params).await?;
+/// // [{"id": 1, "name": "John"}]
+/// ```
+pub async fn query_rows(
+    sql: &SqlConfig,
+    params: HashMap<String, String>,
+) -> Result<Value, SqlError> {
+    let sql = sql.clone();
+    tokio::task::spawn_blocking(move || run_query(&sql, &params)).await?
+}
+
+/// Runs a query on the calling thread, see `query_rows`.
+fn run_query(sql: &SqlConfig, params: &HashMap<String, String>) -> Result<Value, SqlError> {
+    let connection = Connection::open_with_flags(
+        &sql.file,
+        OpenFlags::SQLITE_OPEN_READ_ONLY | OpenFlags::SQLITE_OPEN_NO_MUTEX,
+    )?;
+    let mut statement = connection.prepare(&sql.query)?;
+
+    for index in 1..=statement.parameter_count() {
+        let name = statement
+            .parameter_name(index)
+            .map_or_else(|| format!("?{index}"), str::to_string);
+        let value = params
+            .get(name.trim_start_matches([':', '@', '$']))
+            .ok_or_else(|| SqlError::MissingParameter(name.clone()))?;
+        statement.raw_bind_parameter(index, value)?;
+    }
+
+    let columns: Vec<String> = statement
+        .column_names()
+        .into_iter()
+        .map(str::to_string)
+        .collect();
+    let mut rows = statement.raw_query();
+    let mut objects = Vec::new();
+
+    while let Some(row) = rows.next()? {
+        let mut object = Map::new();
+        for (index, column) in columns.iter().enumerate() {
+            object.insert(column.clone(), column_value(row.get_ref(index)?));
+        }
+        objects.push(Value::Object(object));
+    }
+
+    Ok(Value::Array(objects))
+}
+
+/// Converts a SQLite value into JSON.
+///
+/// Integers and reals become numbers, text becomes a string and blobs an
+/// array of their bytes. Reals that JSON cannot represent, such as NaN,
+/// become `null`.
+fn column_value(value: ValueRef<'_>) -> Value {
+    match value {
+        ValueRef::Null => Value::Null,
+        ValueRef::Integer(integer) => Value::from(integer),
+        ValueRef::Real(real) => Number::from_f64(real).map_or(Value::Null, Value::Number),
+        ValueRef::Text(text) => Value::String(String::from_utf8_lossy(text).into_owned()),
+        ValueRef::Blob(bytes) => Value::from(bytes.to_vec()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    /// Creates a database with a small users table in a temporary directory.
+    fn users_database(dir: &tempfile::TempDir) -> SqlConfig {
+        let file = dir.path().join("fixtures.db");
+        let connection = Connection::open(&file).expect("Failed to create database");
+        connection
+            .execute_batch(
+                "CREATE TABLE users (id INTEGER PRIMARY KEY, name TEXT, score REAL, avatar BLOB);
+                 INSERT INTO users VALUES (1, 'John', 9.5, x'0102');
+                 INSERT INTO users VALUES (2, 'Jane', NULL, NULL);",
+            )
+            .expect("Failed to fill database");
+
+        SqlConfig {
+            file: file.to_string_lossy().into_owned(),
+            query: "SELECT * FROM users WHERE id = :id".to_string(),
+        }
+    }
+
+    /// Tests that every column type is converted and parameters are bound.
+    #[tokio::test]
+    async fn test_query_rows_converts_columns() {
+        let dir = tempfile::tempdir().expect("Failed to create temp dir");
+        let sql = users_database(&dir);
+
+        let rows = query_rows(&sql, HashMap::from([("id".to_string(), "1".to_string())]))
+            .await
+            .expect("Query should succeed");
+        assert_eq!(
+            rows,
+            json!([{"id": 1, "name": "John", "score": 9.5, "avatar": [1, 2]}])
+        );
+
+        let error = query_rows(&sql, HashMap::new())
+            .await
+            .expect_err("The id parameter is missing");
+        assert!(matches!(error, SqlError::MissingParameter(name) if name == ":id"));
+    }
+}
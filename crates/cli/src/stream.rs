@@ -0,0 +1,174 @@
+//! Progressive NDJSON responses for JSON Echo.
+//!
+//! This module streams the items of an array body one JSON document per line,
+//! optionally pausing between them, so UIs can be tested against collections
+//! that load progressively instead of all at once.
+//!
+//! ## What
+//!
+//! The module defines:
+//! - `NDJSON`: The content type of streamed responses
+//! - `is_ndjson`: Whether a content type belongs to a streamed response
+//! - `is_streamed`: Whether a response is a streamed NDJSON response
+//! - `stream_items`: Picks the items a route streams out of its data
+//! - `ndjson_response`: Builds the streaming response for a list of items
+//!
+//! ## How
+//!
+//! Streaming works by:
+//! 1. Taking the array the handler would have served, or the one under `results_field`
+//! 2. Yielding each item as a line of serialized JSON
+//! 3. Waiting for a tick of a tokio interval of `stream_interval_ms` before each item,
+//!    the first tick completing immediately
+//!
+//! ## Why
+//!
+//! The body is produced lazily, so the client receives the first items before
+//! the last ones are written. Middleware that needs the whole body, such as
+//! the entity tag and range support, skips NDJSON responses so it does not
+//! buffer away the pauses.
+//!
+//! # Examples
+//!
+//! ```json
+//! {"/api/feed": {"stream": true, "stream_interval_ms": 250, "response": {"body": [{"id": 1}, {"id": 2}]}}}
+//! ```
+
+use crate::charset::{Charset, encode_json};
+use axum::{
+    body::{Body, Bytes},
+    http::{
+        HeaderMap, HeaderValue, StatusCode,
+        header::{CONTENT_LENGTH, CONTENT_TYPE},
+    },
+    response::{IntoResponse, Response},
+};
+use serde_json::Value;
+use std::{convert::Infallible, time::Duration};
+use tokio::time::{MissedTickBehavior, interval};
+
+/// The content type of streamed responses.
+pub const NDJSON: &str = "application/x-ndjson";
+
+/// Returns whether a content type belongs to a streamed NDJSON response.
+pub fn is_ndjson(content_type: &str) -> bool {
+    content_type
+        .split(';')
+        .next()
+        .is_some_and(|essence| essence.trim().eq_ignore_ascii_case(NDJSON))
+}
+
+/// Returns whether a response streams NDJSON, so middleware must not buffer its body.
+pub fn is_streamed(response: &Response) -> bool {
+    response
+        .headers()
+        .get(CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(is_ndjson)
+}
+
+/// Picks the items a streaming route yields out of the data it serves.
+///
+/// # Parameters
+///
+/// * `data` - The data the handler would have answered with
+/// * `results_field` - The route's `results_field`, if any
+///
+/// # Returns
+///
+/// * `Some(Vec<Value>)` - The array items, from the data or from its `results_field`
+/// * `None` - If there is no array to stream, in which case the data is served as is
+///
+/// # Examples
+///
+/// ```rust
+/// assert_eq!(stream_items(&json!([1, 2]), None), Some(vec![json!(1), json!(2)]));
+/// assert_eq!(stream_items(&json!({"data": [1]}), Some("data")), Some(vec![json!(1)]));
+/// assert_eq!(stream_items(&json!({"id": 1}), None), None);
+/// ```
+pub fn stream_items(data: &Value, results_field: Option<&str>) -> Option<Vec<Value>> {
+    match (data, results_field) {
+        (Value::Array(items), _) => Some(items.clone()),
+        (Value::Object(map), Some(field)) => map.get(field)?.as_array().cloned(),
+        _ => None,
+    }
+}
+
+/// Builds a response streaming items as newline-delimited JSON.
+///
+/// # Parameters
+///
+/// * `headers` - The route's response headers; the content type is replaced with NDJSON
+/// * `status` - The status code of the response
+/// * `items` - The items to stream, one per line
+/// * `pause` - The time between two items, `Duration::ZERO` for none
+///
+/// # Returns
+///
+/// A response whose body yields each item as soon as its interval tick fires
+pub fn ndjson_response(
+    mut headers: HeaderMap,
+    status: StatusCode,
+    items: Vec<Value>,
+    pause: Duration,
+) -> Response {
+    let charset = headers
+        .get(CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .and_then(Charset::from_content_type);
+    let content_type = match charset {
+        Some(charset) => format!("{NDJSON}; charset={}", charset.label()),
+        None => NDJSON.to_string(),
+    };
+    if let Ok(value) = HeaderValue::from_str(&content_type) {
+        headers.insert(CONTENT_TYPE, value);
+    }
+    headers.remove(CONTENT_LENGTH);
+
+    let ticks = (!pause.is_zero()).then(|| {
+        let mut ticks = interval(pause);
+        // Keep the items spaced even when the client reads slowly
+        ticks.set_missed_tick_behavior(MissedTickBehavior::Delay);
+        ticks
+    });
+
+    let lines = futures_util::stream::unfold(
+        (items.into_iter(), ticks),
+        move |(mut items, mut ticks)| async move {
+            let item = items.next()?;
+            if let Some(ticks) = ticks.as_mut() {
+                ticks.tick().await;
+            }
+
+            let mut line = encode_json(&item, charset.unwrap_or(Charset::Utf8));
+            line.push(b'\n');
+            Some((Ok::<_, Infallible>(Bytes::from(line)), (items, ticks)))
+        },
+    );
+
+    (status, headers, Body::from_stream(lines)).into_response()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    /// Tests picking the streamed items and detecting NDJSON content types.
+    #[test]
+    fn test_stream_items() {
+        assert_eq!(
+            stream_items(&json!([{"id": 1}, {"id": 2}]), Some("data")),
+            Some(vec![json!({"id": 1}), json!({"id": 2})])
+        );
+        assert_eq!(
+            stream_items(&json!({"data": [1, 2], "total": 2}), Some("data")),
+            Some(vec![json!(1), json!(2)])
+        );
+        assert_eq!(stream_items(&json!({"data": [1]}), None), None);
+        assert_eq!(stream_items(&json!("text"), None), None);
+
+        assert!(is_ndjson("application/x-ndjson; charset=utf-8"));
+        assert!(!is_ndjson("application/json"));
+    }
+}
@@ -0,0 +1,213 @@
+//! Machine-readable startup summary of a JSON Echo server.
+//!
+//! This module describes the effective configuration of a server once it is
+//! listening, so test harnesses can learn what was configured without parsing
+//! logs or the configuration file themselves.
+//!
+//! ## What
+//!
+//! The module defines:
+//! - `SummaryFormat`: The formats the summary can be printed in
+//! - `StartupSummary`: The addresses, routes, static folder and features of a server
+//! - `Features`: The optional features and whether they are enabled
+//!
+//! ## How
+//!
+//! The summary is built by:
+//! 1. Taking the addresses the server bound, so `--port`, `listen`, `PORT` and
+//!    ports picked by the system for port `0` are accounted for
+//! 2. Counting the routes left after `--tags` filtered the loaded configuration
+//! 3. Reading the static folder and the feature settings from the configuration
+//! 4. Rendering everything as a single line of JSON, printed once all listeners are bound
+//!
+//! ## Why
+//!
+//! The summary is the only output on stdout: the banner is not printed and
+//! the logs go to stderr when it is requested, so a harness can parse stdout
+//! as it is. Every feature is listed, enabled or not, so harnesses can check
+//! for one without guessing which keys exist.
+//!
+//! # Examples
+//!
+//! ```bash
+//! json-echo serve --print-summary json
+//! # {"addresses":["localhost:3001"],"routes":3,"static_folder":null,...}
+//! ```
+
+use clap::ValueEnum;
+use json_echo_core::{Config, FileSystemError, FileSystemResult};
+use serde::Serialize;
+
+/// The formats the startup summary can be printed in.
+///
+/// # Variants
+///
+/// * `Json` - A single line of JSON
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum SummaryFormat {
+    /// A single line of JSON
+    Json,
+}
+
+/// The effective configuration of a listening server.
+///
+/// # Fields
+///
+/// * `version` - The version of JSON Echo serving the configuration
+/// * `addresses` - The `host:port` addresses the server binds
+/// * `routes` - The number of routes served
+/// * `static_folder` - The folder served as static files, if any
+/// * `static_route` - The path static files are served under
+/// * `features` - The optional features and whether they are enabled
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct StartupSummary {
+    /// The version of JSON Echo serving the configuration
+    pub version: &'static str,
+    /// The `host:port` addresses the server binds
+    pub addresses: Vec<String>,
+    /// The number of routes served
+    pub routes: usize,
+    /// The folder served as static files, if any
+    pub static_folder: Option<String>,
+    /// The path static files are served under
+    pub static_route: String,
+    /// The optional features and whether they are enabled
+    pub features: Features,
+}
+
+/// The optional features of a server and whether they are enabled.
+///
+/// # Fields
+///
+/// * `cors` - Whether CORS settings are configured, globally or on a route,
+///   instead of the permissive defaults
+/// * `compression` - Whether responses are compressed for clients accepting it
+/// * `tls` - Whether connections are served over TLS, with the configured certificate
+/// * `debug_headers` - Whether debug headers are added to every response
+/// * `envelope` - Whether JSON bodies are wrapped in an envelope
+/// * `disable_keep_alive` - Whether every response closes its connection
+/// * `http2` - Whether connections may speak HTTP/2 with prior knowledge
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[allow(clippy::struct_excessive_bools)]
+pub struct Features {
+    /// Whether CORS settings are configured instead of the permissive defaults
+    pub cors: bool,
+    /// Whether responses are compressed for clients accepting it
+    pub compression: bool,
+    /// Whether connections are served over TLS
+    pub tls: bool,
+    /// Whether debug headers are added to every response
+    pub debug_headers: bool,
+    /// Whether JSON bodies are wrapped in an envelope
+    pub envelope: bool,
+    /// Whether every response closes its connection
+    pub disable_keep_alive: bool,
+    /// Whether connections may speak HTTP/2 with prior knowledge
+    pub http2: bool,
+}
+
+impl StartupSummary {
+    /// Describes a loaded configuration being served.
+    ///
+    /// # Parameters
+    ///
+    /// * `urls` - The URLs the server listens on, with their bound ports
+    /// * `config` - The loaded configuration, filtered by `--tags` if given
+    ///
+    /// # Returns
+    ///
+    /// The summary of the server, listing the `host:port` of every URL
+    pub fn new(urls: &[String], config: &Config) -> Self {
+        Self {
+            version: env!("CARGO_PKG_VERSION"),
+            addresses: urls
+                .iter()
+                .map(|url| {
+                    url.split_once("://")
+                        .map_or(url.as_str(), |(_, address)| address)
+                })
+                .map(str::to_string)
+                .collect(),
+            routes: config.routes.len(),
+            static_folder: config.static_folder.clone(),
+            static_route: config.static_route.clone(),
+            features: Features {
+                cors: config.cors.is_some()
+                    || config.routes.values().any(|route| route.cors.is_some()),
+                compression: config.compression,
+                tls: config.tls.is_some(),
+                debug_headers: config.debug_headers,
+                envelope: config.envelope.is_some(),
+                disable_keep_alive: config.disable_keep_alive,
+                http2: config.http2,
+            },
+        }
+    }
+
+    /// Renders the summary in the requested format.
+    ///
+    /// # Parameters
+    ///
+    /// * `format` - The format to render
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(String)` - The summary, on a single line
+    /// * `Err(FileSystemError)` - If the summary cannot be serialized
+    pub fn render(&self, format: SummaryFormat) -> FileSystemResult<String> {
+        match format {
+            SummaryFormat::Json => serde_json::to_string(self).map_err(FileSystemError::from),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::{Value, json};
+
+    /// Tests rendering and parsing back the summary of a configuration.
+    ///
+    /// Verifies that the summary is a single line of JSON describing the
+    /// addresses, route count, static folder and features.
+    #[test]
+    fn test_summary_renders_single_json_line() {
+        let config: Config = serde_json::from_value(json!({
+            "static_folder": "public",
+            "debug_headers": true,
+            "compression": true,
+            "tls": {"cert_file": "cert.pem", "key_file": "key.pem"},
+            "routes": {
+                "/api/users": {"response": {"body": []}},
+                "/api/orders": {
+                    "cors": {"allowed_origins": ["https://app.example.com"]},
+                    "response": {"body": []}
+                }
+            }
+        }))
+        .expect("Config should parse");
+
+        let summary = StartupSummary::new(&["http://127.0.0.1:4000".to_string()], &config);
+        let rendered = summary
+            .render(SummaryFormat::Json)
+            .expect("Summary should render");
+        assert!(!rendered.contains('\n'), "The summary should be one line");
+
+        let parsed: Value = serde_json::from_str(&rendered).expect("Summary should be JSON");
+        assert_eq!(parsed["addresses"], json!(["127.0.0.1:4000"]));
+        assert_eq!(parsed["routes"], 2);
+        assert_eq!(parsed["static_folder"], "public");
+        assert_eq!(
+            parsed["features"],
+            json!({
+                "cors": true,
+                "compression": true,
+                "tls": true,
+                "debug_headers": true,
+                "envelope": false,
+                "disable_keep_alive": false,
+                "http2": false
+            })
+        );
+    }
+}
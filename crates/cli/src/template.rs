@@ -0,0 +1,165 @@
+//! Template rendering for values derived from the incoming request.
+//!
+//! This module resolves `{{...}}` tokens embedded in configured response values
+//! so that mock responses can reflect the request that produced them, such as
+//! echoing a correlation header back to the client.
+//!
+//! ## What
+//!
+//! The module defines:
+//! - `render_header_value`: Resolves every token in a configured header value
+//!
+//! ## How
+//!
+//! Rendering works by:
+//! 1. Scanning the value for `{{` and `}}` delimited tokens
+//! 2. Resolving `request.header.<Name>` tokens against the request headers
+//! 3. Generating a random v4 UUID for `uuid` tokens
+//! 4. Falling back through `|` separated alternatives until one is non-empty
+//! 5. Leaving unknown tokens untouched so they remain visible in responses
+//!
+//! ## Why
+//!
+//! Keeping rendering as a pure function over the request headers allows the
+//! same behavior to be shared by every handler and tested without a server.
+//!
+//! # Examples
+//!
+//! ```text
+//! "X-Request-Id": "{{request.header.X-Request-Id}}"
+//! "X-Trace": "trace-{{uuid}}"
+//! "X-Correlation-Id": "{{request.header.X-Correlation-Id | uuid}}"
+//! ```
+
+use axum::http::HeaderMap;
+use uuid::Uuid;
+
+/// Prefix of tokens that resolve to an incoming request header.
+const REQUEST_HEADER_PREFIX: &str = "request.header.";
+
+/// Resolves the template tokens contained in a configured header value.
+///
+/// Header lookups are case-insensitive and resolve to an empty string when the
+/// request does not carry the header. A token may list `|` separated alternatives,
+/// in which case the first non-empty one is used. Values without tokens are
+/// returned as-is.
+///
+/// # Parameters
+///
+/// * `template` - The configured header value, possibly containing tokens
+/// * `request_headers` - The headers of the incoming request
+///
+/// # Returns
+///
+/// The header value with every known token replaced
+///
+/// # Examples
+///
+/// ```rust
+/// let mut request_headers = HeaderMap::new();
+/// request_headers.insert("x-request-id", HeaderValue::from_static("abc"));
+///
+/// assert_eq!(
+///     render_header_value("id-{{request.header.X-Request-Id}}", &request_headers),
+///     "id-abc"
+/// );
+/// ```
+pub fn render_header_value(template: &str, request_headers: &HeaderMap) -> String {
+    let mut rendered = String::with_capacity(template.len());
+    let mut rest = template;
+
+    while let Some(start) = rest.find("{{") {
+        let Some(length) = rest[start + 2..].find("}}") else {
+            break;
+        };
+
+        let token = &rest[start + 2..start + 2 + length];
+        rendered.push_str(&rest[..start]);
+
+        match resolve_token(token.trim(), request_headers) {
+            Some(value) => rendered.push_str(&value),
+            None => rendered.push_str(&rest[start..start + length + 4]),
+        }
+
+        rest = &rest[start + length + 4..];
+    }
+
+    rendered.push_str(rest);
+    rendered
+}
+
+/// Resolves a token and its fallbacks, returning `None` when any part is unknown.
+fn resolve_token(token: &str, request_headers: &HeaderMap) -> Option<String> {
+    let mut resolved = String::new();
+
+    for alternative in token.split('|') {
+        let value = resolve_expression(alternative.trim(), request_headers)?;
+        if resolved.is_empty() {
+            resolved = value;
+        }
+    }
+
+    Some(resolved)
+}
+
+/// Resolves a single token expression, returning `None` when it is unknown.
+fn resolve_expression(token: &str, request_headers: &HeaderMap) -> Option<String> {
+    if token == "uuid" {
+        return Some(Uuid::new_v4().to_string());
+    }
+
+    token.strip_prefix(REQUEST_HEADER_PREFIX).map(|name| {
+        request_headers
+            .get(name)
+            .and_then(|value| value.to_str().ok())
+            .unwrap_or_default()
+            .to_string()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::http::HeaderValue;
+
+    /// Tests resolution of request header and uuid tokens.
+    ///
+    /// Verifies case-insensitive header lookups, empty resolution of missing
+    /// headers, uuid generation and that unknown tokens are preserved.
+    #[test]
+    fn test_render_header_value() {
+        let mut request_headers = HeaderMap::new();
+        request_headers.insert("x-request-id", HeaderValue::from_static("abc-123"));
+
+        assert_eq!(
+            render_header_value("{{request.header.X-Request-Id}}", &request_headers),
+            "abc-123"
+        );
+        assert_eq!(
+            render_header_value("id={{ request.header.x-missing }};", &request_headers),
+            "id=;"
+        );
+        assert_eq!(render_header_value("static", &request_headers), "static");
+        assert_eq!(
+            render_header_value("{{unknown}} {{open", &request_headers),
+            "{{unknown}} {{open"
+        );
+
+        let generated = render_header_value("{{uuid}}", &request_headers);
+        assert!(
+            Uuid::parse_str(&generated).is_ok(),
+            "uuid token should generate a valid UUID: {generated}"
+        );
+
+        assert_eq!(
+            render_header_value("{{request.header.X-Request-Id | uuid}}", &request_headers),
+            "abc-123",
+            "Present header should win over the fallback"
+        );
+        let fallback = render_header_value("{{request.header.X-Missing | uuid}}", &request_headers);
+        assert!(
+            Uuid::parse_str(&fallback).is_ok(),
+            "Missing header should fall back to a UUID: {fallback}"
+        );
+    }
+}
@@ -0,0 +1,471 @@
+//! Current time tokens in JSON Echo response bodies.
+//!
+//! This module resolves `{{now}}` and `{{timestamp}}` tokens in the string
+//! leaves of JSON response bodies when the response is sent, so mocks can
+//! return fields such as `createdAt` that carry the current time.
+//!
+//! ## What
+//!
+//! The module defines:
+//! - `NOW_PARAM`: The query parameter pinning the current time
+//! - `format_rfc3339`: Formats a UTC time as an RFC 3339 string
+//! - `format_http_date`: Formats a UTC time as an HTTP date, as sent in `Expires`
+//! - `parse_now`: Reads a pinned time from unix seconds or an RFC 3339 string
+//! - `has_time_tokens`: Whether a JSON value holds a time token
+//! - `TimedRoutes`: The routes whose response bodies are rendered
+//! - `render_times`: Resolves the time tokens of a JSON value
+//! - `render_timestamps`: Middleware resolving the tokens of the JSON responses of timed routes
+//!
+//! ## How
+//!
+//! Rendering works by:
+//! 1. Marking, when the router is built, the routes whose configuration, data or
+//!    envelope holds a time token; lazy routes are marked as their file is unread
+//! 2. Taking the current time, or the one pinned by the `_now` query parameter,
+//!    which is removed before the route sees the query
+//! 3. Skipping responses that are not JSON or contain no `{{`
+//! 4. Replacing the tokens of every string leaf, at any depth, in the requested format
+//! 5. Turning a leaf made of a single `{{timestamp}}` token into a number
+//!
+//! | Token | Resolves to |
+//! |-------|-------------|
+//! | `{{now}}`, `{{now:iso8601}}` | `2026-10-14T09:30:00Z` |
+//! | `{{now:date}}` | `2026-10-14` |
+//! | `{{timestamp}}`, `{{now:unix}}` | `1791970200` |
+//! | `{{timestamp_ms}}`, `{{now:unix_ms}}` | `1791970200000` |
+//!
+//! ## Why
+//!
+//! Rendering at response time keeps the stored data unchanged, so every
+//! request gets a fresh time while the configured body stays a template.
+//! Times are always rendered in UTC, which RFC 3339 expresses without a
+//! timezone database, and `_now` makes the output deterministic in tests.
+//! Marking the routes up front keeps every other response streaming through
+//! unbuffered; like the paths themselves, the marks are not changed by a reload.
+//!
+//! # Examples
+//!
+//! ```json
+//! {"/api/status": {"response": {"body": {"checkedAt": "{{now:iso8601}}", "epoch": "{{timestamp}}"}}}}
+//! ```
+
+use crate::charset::{Charset, encode_json};
+use crate::envelope::is_json;
+use crate::server::AppState;
+use crate::stream::is_streamed;
+use axum::{
+    body::{Body, to_bytes},
+    extract::{MatchedPath, Query, Request, State},
+    http::{
+        Uri,
+        header::{CONTENT_LENGTH, CONTENT_TYPE},
+    },
+    middleware::Next,
+    response::Response,
+};
+use serde_json::Value;
+use std::{
+    collections::{HashMap, HashSet},
+    sync::Arc,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+use time::{
+    OffsetDateTime,
+    format_description::{BorrowedFormatItem, well_known::Rfc3339},
+    macros::format_description,
+};
+
+/// The query parameter pinning the current time, e.g. `?_now=2026-10-14T09:30:00Z`.
+pub const NOW_PARAM: &str = "_now";
+
+/// The HTTP date form of RFC 9110, e.g. `Wed, 14 Oct 2026 09:30:00 GMT`.
+const HTTP_DATE: &[BorrowedFormatItem<'static>] = format_description!(
+    "[weekday repr:short], [day] [month repr:short] [year] [hour]:[minute]:[second] GMT"
+);
+
+/// Returns a time as UTC, truncated to the second; times before the epoch become the epoch.
+fn utc_seconds(time: SystemTime) -> OffsetDateTime {
+    let seconds = time
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs();
+    OffsetDateTime::from_unix_timestamp(i64::try_from(seconds).unwrap_or_default())
+        .unwrap_or(OffsetDateTime::UNIX_EPOCH)
+}
+
+/// Formats a time as an RFC 3339 UTC string with second precision.
+///
+/// # Parameters
+///
+/// * `time` - The time to format; times before the epoch are formatted as the epoch
+///
+/// # Returns
+///
+/// The time as `YYYY-MM-DDTHH:MM:SSZ`
+///
+/// # Examples
+///
+/// ```rust
+/// let time = UNIX_EPOCH + Duration::from_secs(1_791_970_200);
+/// assert_eq!(format_rfc3339(time), "2026-10-14T09:30:00Z");
+/// ```
+pub fn format_rfc3339(time: SystemTime) -> String {
+    utc_seconds(time).format(&Rfc3339).unwrap_or_default()
+}
+
+/// Formats a time as an HTTP date, the IMF-fixdate form of RFC 9110.
+///
+/// # Parameters
+///
+/// * `time` - The time to format; times before the epoch are formatted as the epoch
+///
+/// # Returns
+///
+/// The time as `Day, DD Mon YYYY HH:MM:SS GMT`
+///
+/// # Examples
+///
+/// ```rust
+/// let time = UNIX_EPOCH + Duration::from_secs(1_791_970_200);
+/// assert_eq!(format_http_date(time), "Wed, 14 Oct 2026 09:30:00 GMT");
+/// ```
+pub fn format_http_date(time: SystemTime) -> String {
+    utc_seconds(time).format(HTTP_DATE).unwrap_or_default()
+}
+
+/// Reads a pinned time from a `_now` value.
+///
+/// # Parameters
+///
+/// * `value` - Unix seconds, e.g. `1791970200`, or an RFC 3339 time such as
+///   `2026-10-14T09:30:00Z` or `2026-10-14T11:30:00+02:00`, fractional seconds ignored
+///
+/// # Returns
+///
+/// The time, or `None` if the value is in neither form or before the epoch
+///
+/// # Examples
+///
+/// ```rust
+/// assert_eq!(parse_now("1791970200"), parse_now("2026-10-14T11:30:00+02:00"));
+/// assert_eq!(parse_now("yesterday"), None);
+/// ```
+pub fn parse_now(value: &str) -> Option<SystemTime> {
+    let value = value.trim();
+
+    let seconds = match value.parse::<u64>() {
+        Ok(seconds) => seconds,
+        Err(_) => u64::try_from(
+            OffsetDateTime::parse(value, &Rfc3339)
+                .ok()?
+                .unix_timestamp(),
+        )
+        .ok()?,
+    };
+
+    Some(UNIX_EPOCH + Duration::from_secs(seconds))
+}
+
+/// Resolves a single time token, returning `None` when it is unknown.
+fn resolve(token: &str, now: SystemTime) -> Option<String> {
+    let since_epoch = now.duration_since(UNIX_EPOCH).unwrap_or_default();
+
+    match token {
+        "now" | "now:iso8601" | "now:rfc3339" => Some(format_rfc3339(now)),
+        "now:date" => format_rfc3339(now).get(..10).map(str::to_string),
+        "timestamp" | "now:unix" => Some(since_epoch.as_secs().to_string()),
+        "timestamp_ms" | "now:unix_ms" => Some(since_epoch.as_millis().to_string()),
+        _ => None,
+    }
+}
+
+/// Resolves the time tokens of a string, leaving other tokens untouched.
+fn render_string(template: &str, now: SystemTime) -> String {
+    let mut rendered = String::with_capacity(template.len());
+    let mut rest = template;
+
+    while let Some(start) = rest.find("{{") {
+        let Some(length) = rest[start + 2..].find("}}") else {
+            break;
+        };
+
+        rendered.push_str(&rest[..start]);
+        match resolve(rest[start + 2..start + 2 + length].trim(), now) {
+            Some(value) => rendered.push_str(&value),
+            None => rendered.push_str(&rest[start..start + length + 4]),
+        }
+
+        rest = &rest[start + length + 4..];
+    }
+
+    rendered.push_str(rest);
+    rendered
+}
+
+/// Returns whether a string holds a known time token.
+fn has_time_token(text: &str) -> bool {
+    let mut rest = text;
+
+    while let Some(start) = rest.find("{{") {
+        let Some(length) = rest[start + 2..].find("}}") else {
+            return false;
+        };
+        if resolve(rest[start + 2..start + 2 + length].trim(), UNIX_EPOCH).is_some() {
+            return true;
+        }
+        rest = &rest[start + length + 4..];
+    }
+
+    false
+}
+
+/// Returns whether a JSON value holds a time token in one of its string leaves.
+///
+/// # Parameters
+///
+/// * `value` - A configured body, route or envelope template
+///
+/// # Examples
+///
+/// ```rust
+/// assert!(has_time_tokens(&json!({"meta": {"at": "{{now:date}}"}})));
+/// assert!(!has_time_tokens(&json!({"name": "{{unknown}}"})));
+/// ```
+pub fn has_time_tokens(value: &Value) -> bool {
+    match value {
+        Value::String(text) => has_time_token(text),
+        Value::Array(items) => items.iter().any(has_time_tokens),
+        Value::Object(map) => map.values().any(has_time_tokens),
+        _ => false,
+    }
+}
+
+/// The routes whose response bodies are rendered.
+///
+/// # Fields
+///
+/// * `state` - Shared application state, used to report internal failures
+/// * `paths` - The route paths configured with a time token
+pub struct TimedRoutes {
+    /// Shared application state, used to report internal failures
+    pub state: Arc<AppState>,
+    /// The route paths configured with a time token
+    pub paths: HashSet<String>,
+}
+
+/// Resolves the time tokens in every string leaf of a JSON value.
+///
+/// A leaf made only of a `{{timestamp}}` or `{{timestamp_ms}}` token becomes
+/// a number; every other token is replaced within its string.
+///
+/// # Parameters
+///
+/// * `value` - The response body to render in place
+/// * `now` - The time the tokens resolve to
+///
+/// # Examples
+///
+/// ```rust
+/// let mut body = json!({"at": "{{now}}", "epoch": "{{timestamp}}"});
+/// render_times(&mut body, UNIX_EPOCH);
+/// assert_eq!(body, json!({"at": "1970-01-01T00:00:00Z", "epoch": 0}));
+/// ```
+pub fn render_times(value: &mut Value, now: SystemTime) {
+    match value {
+        Value::String(text) if text.contains("{{") => {
+            let rendered = render_string(text, now);
+            let trimmed = text.trim();
+            let numeric = trimmed == "{{timestamp}}" || trimmed == "{{timestamp_ms}}";
+
+            *value = match rendered.parse::<u128>() {
+                Ok(number) if numeric => {
+                    u64::try_from(number).map_or_else(|_| Value::String(rendered), Value::from)
+                }
+                _ => Value::String(rendered),
+            };
+        }
+        Value::Array(items) => items.iter_mut().for_each(|item| render_times(item, now)),
+        Value::Object(map) => map.values_mut().for_each(|item| render_times(item, now)),
+        _ => {}
+    }
+}
+
+/// Removes the `_now` parameter from a request URI.
+///
+/// # Returns
+///
+/// The pinned time when the parameter was present and valid, along with the
+/// URI without it, or `None` when the query has no `_now` parameter
+fn take_now_param(uri: &Uri) -> Option<(Option<SystemTime>, Uri)> {
+    let query = uri.query()?;
+    let Query(params) = Query::<HashMap<String, String>>::try_from_uri(uri).ok()?;
+    let pinned = params.get(NOW_PARAM)?;
+
+    let kept: Vec<&str> = query
+        .split('&')
+        .filter(|pair| pair.split_once('=').map_or(*pair, |(name, _)| name) != NOW_PARAM)
+        .collect();
+
+    let path_and_query = if kept.is_empty() {
+        uri.path().to_string()
+    } else {
+        format!("{}?{}", uri.path(), kept.join("&"))
+    };
+    let mut parts = uri.clone().into_parts();
+    parts.path_and_query = path_and_query.parse().ok();
+
+    Some((parse_now(pinned), Uri::from_parts(parts).ok()?))
+}
+
+/// Middleware resolving the time tokens of JSON response bodies.
+///
+/// Only routes configured with a time token are rendered, so other responses
+/// are never buffered. The `_now` query parameter pins the time, as unix
+/// seconds or an RFC 3339 string, and is removed from the query before the
+/// handler reads it. Streamed responses and bodies without `{{` are sent unchanged.
+///
+/// # Parameters
+///
+/// * `State(timed)` - The rendered routes and the state reporting internal failures
+/// * `request` - The incoming request
+/// * `next` - The remaining middleware and the route handler
+///
+/// # Returns
+///
+/// The response, time tokens in its JSON body resolved
+pub async fn render_timestamps(
+    State(timed): State<Arc<TimedRoutes>>,
+    mut request: Request,
+    next: Next,
+) -> Response {
+    let is_timed = request
+        .extensions()
+        .get::<MatchedPath>()
+        .is_some_and(|path| timed.paths.contains(path.as_str()));
+    if !is_timed {
+        return next.run(request).await;
+    }
+
+    let mut now = None;
+    if let Some((pinned, uri)) = take_now_param(request.uri()) {
+        now = pinned;
+        *request.uri_mut() = uri;
+    }
+
+    let response = next.run(request).await;
+
+    let content_type = response
+        .headers()
+        .get(CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .map(str::to_string);
+    let Some(content_type) = content_type.filter(|content_type| is_json(content_type)) else {
+        return response;
+    };
+    if is_streamed(&response) {
+        return response;
+    }
+
+    let (mut parts, body) = response.into_parts();
+    let bytes = match to_bytes(body, usize::MAX).await {
+        Ok(bytes) => bytes,
+        Err(error) => {
+            return timed
+                .state
+                .internal_error(&format!("Failed to read body: {error}"));
+        }
+    };
+
+    if !bytes.windows(2).any(|window| window == b"{{") {
+        return Response::from_parts(parts, Body::from(bytes));
+    }
+    let Ok(mut data) = serde_json::from_slice::<Value>(&bytes) else {
+        return Response::from_parts(parts, Body::from(bytes));
+    };
+
+    render_times(&mut data, now.unwrap_or_else(SystemTime::now));
+
+    let charset = Charset::from_content_type(&content_type).unwrap_or(Charset::Utf8);
+    parts.headers.remove(CONTENT_LENGTH);
+
+    Response::from_parts(parts, Body::from(encode_json(&data, charset)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    /// Tests formatting and parsing RFC 3339 times.
+    ///
+    /// Verifies leap years, the first and last second of a day, that both
+    /// `_now` forms read back the same time, UTC offsets included, and that
+    /// invalid values are rejected.
+    #[test]
+    fn test_format_and_parse() {
+        let at = |seconds| UNIX_EPOCH + Duration::from_secs(seconds);
+
+        assert_eq!(format_rfc3339(UNIX_EPOCH), "1970-01-01T00:00:00Z");
+        assert_eq!(format_rfc3339(at(951_782_400)), "2000-02-29T00:00:00Z");
+        assert_eq!(format_rfc3339(at(1_791_970_200)), "2026-10-14T09:30:00Z");
+        assert_eq!(format_rfc3339(at(4_102_444_799)), "2099-12-31T23:59:59Z");
+
+        for seconds in [0, 951_782_400, 1_791_970_200, 4_102_444_799] {
+            assert_eq!(parse_now(&format_rfc3339(at(seconds))), Some(at(seconds)));
+        }
+        assert_eq!(parse_now("1791970200"), Some(at(1_791_970_200)));
+        assert_eq!(
+            parse_now("2026-10-14T09:30:00.250Z"),
+            Some(at(1_791_970_200))
+        );
+        assert_eq!(
+            parse_now("2026-10-14T11:30:00+02:00"),
+            Some(at(1_791_970_200))
+        );
+        assert_eq!(parse_now("2026-13-01T00:00:00Z"), None);
+        assert_eq!(parse_now("2026-02-30T00:00:00Z"), None);
+        assert_eq!(parse_now("1969-12-31T23:59:59Z"), None);
+        assert_eq!(parse_now("soon"), None);
+    }
+
+    /// Tests formatting HTTP dates, weekday included.
+    #[test]
+    fn test_format_http_date() {
+        let at = |seconds| UNIX_EPOCH + Duration::from_secs(seconds);
+
+        assert_eq!(
+            format_http_date(UNIX_EPOCH),
+            "Thu, 01 Jan 1970 00:00:00 GMT"
+        );
+        assert_eq!(
+            format_http_date(at(951_782_400)),
+            "Tue, 29 Feb 2000 00:00:00 GMT"
+        );
+        assert_eq!(
+            format_http_date(at(1_791_970_200)),
+            "Wed, 14 Oct 2026 09:30:00 GMT"
+        );
+    }
+
+    /// Tests resolving time tokens in nested bodies.
+    #[test]
+    fn test_render_times() {
+        let mut body = json!({
+            "at": "{{now}}",
+            "log": ["on {{now:date}}", "{{unknown}}"],
+            "epoch": "{{timestamp}}",
+            "label": "t={{timestamp}}"
+        });
+        render_times(&mut body, UNIX_EPOCH + Duration::from_secs(1_791_970_200));
+
+        assert_eq!(
+            body,
+            json!({
+                "at": "2026-10-14T09:30:00Z",
+                "log": ["on 2026-10-14", "{{unknown}}"],
+                "epoch": 1_791_970_200,
+                "label": "t=1791970200"
+            })
+        );
+    }
+}
@@ -0,0 +1,133 @@
+//! HTTPS support for the JSON Echo server.
+//!
+//! This module turns the `tls` setting of a configuration into the acceptor
+//! wrapping every accepted connection, so a mock can stand in for a service
+//! reached over `https://`.
+//!
+//! ## What
+//!
+//! The module defines:
+//! - `tls_acceptor`: Loads the configured certificate and key into a TLS acceptor
+//!
+//! ## How
+//!
+//! An acceptor is built by:
+//! 1. Reading the PEM certificate chain and private key, resolved against the root
+//! 2. Building a rustls server configuration without client authentication
+//! 3. Advertising `http/1.1` through ALPN, and `h2` first when HTTP/2 is enabled
+//!
+//! ## Why
+//!
+//! The files are loaded before any address is bound, so a missing or invalid
+//! certificate stops the server at startup, naming the file, instead of
+//! failing every handshake.
+//!
+//! # Examples
+//!
+//! ```rust
+//! let acceptor = tls_acceptor(config_manager.get_root(), &tls, config.http2).await?;
+//! run_server(&addresses, router, Duration::ZERO, false, Some(acceptor), None, |_| {}).await?;
+//! ```
+
+use json_echo_core::TlsConfig;
+use std::{io::Error as IOError, path::Path, sync::Arc};
+use tokio_rustls::{
+    TlsAcceptor,
+    rustls::{
+        ServerConfig,
+        pki_types::{CertificateDer, PrivateKeyDer, pem::PemObject},
+    },
+};
+
+/// Loads the configured certificate and key into a TLS acceptor.
+///
+/// # Parameters
+///
+/// * `root` - The directory the certificate and key paths are resolved against
+/// * `tls` - The configured certificate and key files
+/// * `http2` - Whether HTTP/2 is offered through ALPN besides HTTP/1.1
+///
+/// # Returns
+///
+/// * `Ok(TlsAcceptor)` - The acceptor wrapping accepted connections
+/// * `Err(IOError)` - If a file cannot be read or parsed, or the key does not
+///   suit the certificate, naming the file
+pub async fn tls_acceptor(
+    root: &Path,
+    tls: &TlsConfig,
+    http2: bool,
+) -> Result<TlsAcceptor, IOError> {
+    let invalid = |file: &str, error: &dyn std::fmt::Display| {
+        IOError::new(
+            std::io::ErrorKind::InvalidData,
+            format!("Invalid TLS file {file}: {error}"),
+        )
+    };
+
+    let cert_pem = read_file(root, &tls.cert_file).await?;
+    let certs = CertificateDer::pem_slice_iter(&cert_pem)
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|error| invalid(&tls.cert_file, &error))?;
+    if certs.is_empty() {
+        return Err(invalid(&tls.cert_file, &"no certificate found"));
+    }
+
+    let key_pem = read_file(root, &tls.key_file).await?;
+    let key =
+        PrivateKeyDer::from_pem_slice(&key_pem).map_err(|error| invalid(&tls.key_file, &error))?;
+
+    let mut config = ServerConfig::builder()
+        .with_no_client_auth()
+        .with_single_cert(certs, key)
+        .map_err(|error| invalid(&tls.key_file, &error))?;
+    config.alpn_protocols = if http2 {
+        vec![b"h2".to_vec(), b"http/1.1".to_vec()]
+    } else {
+        vec![b"http/1.1".to_vec()]
+    };
+
+    Ok(TlsAcceptor::from(Arc::new(config)))
+}
+
+/// Reads a TLS file resolved against the root, naming it in the error.
+async fn read_file(root: &Path, file: &str) -> Result<Vec<u8>, IOError> {
+    tokio::fs::read(root.join(file)).await.map_err(|error| {
+        IOError::new(
+            error.kind(),
+            format!("Failed to read TLS file {file}: {error}"),
+        )
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Tests that unreadable and invalid files are reported by name.
+    ///
+    /// Verifies that a missing certificate and a certificate file holding no
+    /// certificate both fail with an error naming the file.
+    #[tokio::test]
+    async fn test_invalid_files_are_named() {
+        let dir = tempfile::tempdir().expect("Failed to create temporary directory");
+        let tls = TlsConfig {
+            cert_file: "cert.pem".to_string(),
+            key_file: "key.pem".to_string(),
+        };
+
+        let Err(error) = tls_acceptor(dir.path(), &tls, false).await else {
+            panic!("A missing certificate should fail");
+        };
+        assert!(error.to_string().contains("cert.pem"), "{error}");
+
+        std::fs::write(dir.path().join("cert.pem"), "not a certificate")
+            .expect("Failed to write certificate");
+        let Err(error) = tls_acceptor(dir.path(), &tls, false).await else {
+            panic!("An empty certificate file should fail");
+        };
+        assert_eq!(
+            error.to_string(),
+            "Invalid TLS file cert.pem: no certificate found"
+        );
+    }
+}
@@ -0,0 +1,224 @@
+//! Upload persistence for JSON Echo write routes.
+//!
+//! This module saves the bodies sent to routes configured with
+//! `save_uploads_to`, so upload endpoints can be mocked by tests that check
+//! the files actually reached the disk.
+//!
+//! ## What
+//!
+//! The module defines:
+//! - `UploadError`: Why an upload could not be saved
+//! - `save_body`: Streams a raw request body to a new file
+//! - `save_multipart`: Streams every file part of a multipart body to its own file
+//!
+//! ## How
+//!
+//! Saving works by:
+//! 1. Creating the configured directory below the project root through the `FileSystemManager`,
+//!    which refuses directories outside of it
+//! 2. Naming every file with a fresh v4 UUID, followed by the uploaded file name for multipart parts
+//! 3. Writing the body chunk by chunk as it arrives, so large uploads are never held in memory
+//! 4. Describing the stored files by their path, relative to the root, and their size
+//!
+//! ## Why
+//!
+//! Uploaded file names are reduced to their last path segment and prefixed
+//! with a UUID, so a client can neither write outside the directory nor
+//! overwrite an earlier upload by reusing its name.
+//!
+//! # Examples
+//!
+//! ```json
+//! {"[POST] /api/uploads": {"save_uploads_to": "uploads/", "response": {"status": 201, "body": {}}}}
+//! ```
+
+use axum::{
+    body::Body,
+    extract::{Multipart, multipart::MultipartError},
+};
+use futures_util::StreamExt;
+use json_echo_core::{FileSystemError, FileSystemManager};
+use serde_json::{Value, json};
+use std::path::{Path, PathBuf};
+use tokio::{fs::File, io::AsyncWriteExt};
+use uuid::Uuid;
+
+/// Why an upload could not be saved.
+///
+/// # Variants
+///
+/// * `Multipart` - The body is not valid multipart data
+/// * `Body` - The request body could not be read
+/// * `FileSystem` - The directory or a file could not be created or written
+#[derive(Debug, thiserror::Error)]
+pub enum UploadError {
+    /// The body is not valid multipart data
+    #[error("{}", .0.body_text())]
+    Multipart(#[from] MultipartError),
+    /// The request body could not be read
+    #[error("Failed to read upload: {0}")]
+    Body(#[from] axum::Error),
+    /// The directory or a file could not be created or written
+    #[error("Failed to save upload: {0}")]
+    FileSystem(#[from] FileSystemError),
+}
+
+/// A file being written in the upload directory.
+struct StoredFile {
+    /// The open file
+    file: File,
+    /// The absolute path of the file, used to report failures
+    path: PathBuf,
+    /// The path of the file relative to the project root, as reported to the client
+    relative: String,
+    /// The number of bytes written so far
+    size: usize,
+}
+
+impl StoredFile {
+    /// Creates a new file named `name` in the upload directory.
+    async fn create(dir: &Path, directory: &str, name: &str) -> Result<Self, UploadError> {
+        let path = dir.join(name);
+        let file = File::create(&path)
+            .await
+            .map_err(|error| FileSystemError::from_io(&error, &path))?;
+
+        Ok(Self {
+            file,
+            path,
+            relative: format!("{}/{name}", directory.trim_end_matches('/')),
+            size: 0,
+        })
+    }
+
+    /// Appends a chunk of the upload to the file.
+    async fn write(&mut self, chunk: &[u8]) -> Result<(), UploadError> {
+        self.file
+            .write_all(chunk)
+            .await
+            .map_err(|error| FileSystemError::from_io(&error, &self.path))?;
+        self.size += chunk.len();
+        Ok(())
+    }
+
+    /// Flushes the file and returns its stored path and size.
+    async fn finish(mut self) -> Result<(String, usize), UploadError> {
+        // Tokio files write in the background; flush so the data is on disk on return
+        self.file
+            .flush()
+            .await
+            .map_err(|error| FileSystemError::from_io(&error, &self.path))?;
+        Ok((self.relative, self.size))
+    }
+}
+
+/// Reduces an uploaded file name to its last path segment.
+///
+/// # Examples
+///
+/// ```rust
+/// assert_eq!(file_name("../../etc/passwd"), "passwd");
+/// assert_eq!(file_name("C:\\photos\\beach.png"), "beach.png");
+/// assert_eq!(file_name(".."), "upload");
+/// ```
+fn file_name(uploaded: &str) -> &str {
+    match uploaded.rsplit(['/', '\\']).next() {
+        Some(name) if !name.is_empty() && name != "." && name != ".." => name,
+        _ => "upload",
+    }
+}
+
+/// Streams a raw request body to a new file in the upload directory.
+///
+/// # Parameters
+///
+/// * `files` - The filesystem manager of the project root
+/// * `directory` - The route's `save_uploads_to`, relative to the root
+/// * `body` - The request body
+///
+/// # Returns
+///
+/// * `Ok(Value)` - The stored file, as `{"path": "uploads/<uuid>", "size": 12}`
+/// * `Err(UploadError)` - If the body cannot be read or the file cannot be saved
+pub async fn save_body(
+    files: &FileSystemManager,
+    directory: &str,
+    body: Body,
+) -> Result<Value, UploadError> {
+    let dir = files.create_dir_within_root(directory).await?;
+    let mut stored = StoredFile::create(&dir, directory, &Uuid::new_v4().to_string()).await?;
+
+    let mut chunks = body.into_data_stream();
+    while let Some(chunk) = chunks.next().await {
+        stored.write(&chunk?).await?;
+    }
+
+    let (path, size) = stored.finish().await?;
+    Ok(json!({"path": path, "size": size}))
+}
+
+/// Streams every file part of a multipart body to its own file in the upload directory.
+///
+/// Text fields are echoed with their value, as for uploads that are not saved.
+///
+/// # Parameters
+///
+/// * `files` - The filesystem manager of the project root
+/// * `directory` - The route's `save_uploads_to`, relative to the root
+/// * `multipart` - The multipart body of the request
+///
+/// # Returns
+///
+/// * `Ok(Value)` - An object with `fields` and `files` entries, every file carrying its stored `path`
+/// * `Err(UploadError)` - If the body is not valid multipart data or a file cannot be saved
+pub async fn save_multipart(
+    files: &FileSystemManager,
+    directory: &str,
+    mut multipart: Multipart,
+) -> Result<Value, UploadError> {
+    let dir = files.create_dir_within_root(directory).await?;
+    let mut fields = serde_json::Map::new();
+    let mut stored_files = Vec::new();
+
+    while let Some(mut field) = multipart.next_field().await? {
+        let name = field.name().unwrap_or_default().to_string();
+
+        if let Some(filename) = field.file_name().map(str::to_string) {
+            let content_type = field.content_type().map(str::to_string);
+            let stored_name = format!("{}-{}", Uuid::new_v4(), file_name(&filename));
+
+            let mut stored = StoredFile::create(&dir, directory, &stored_name).await?;
+            while let Some(chunk) = field.chunk().await? {
+                stored.write(&chunk).await?;
+            }
+            let (path, size) = stored.finish().await?;
+
+            stored_files.push(json!({
+                "field": name,
+                "filename": filename,
+                "content_type": content_type,
+                "size": size,
+                "path": path,
+            }));
+        } else {
+            fields.insert(name, Value::String(field.text().await?));
+        }
+    }
+
+    Ok(json!({"fields": fields, "files": stored_files}))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Tests reducing uploaded file names to a safe last segment.
+    #[test]
+    fn test_file_name() {
+        assert_eq!(file_name("beach.png"), "beach.png");
+        assert_eq!(file_name("../../etc/passwd"), "passwd");
+        assert_eq!(file_name("C:\\photos\\beach.png"), "beach.png");
+        assert_eq!(file_name("photos/"), "upload");
+        assert_eq!(file_name(".."), "upload");
+    }
+}
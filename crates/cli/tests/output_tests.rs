@@ -0,0 +1,124 @@
+//! Test suite for the output of the `echo` binary.
+//!
+//! This module runs the built binary and reads what it writes, checking that
+//! machine-readable output can be parsed as it is and that `--quiet` keeps
+//! the terminal silent.
+//!
+//! ## What
+//!
+//! The test suite covers:
+//! - The startup summary printed by `serve --print-summary json`
+//! - The differences printed by `diff --json`
+//! - The banner and logs left out by `--quiet`
+//!
+//! ## How
+//!
+//! Tests write a configuration into a temporary directory, run the binary
+//! against it with piped output and parse stdout without skipping any line.
+//!
+//! ## Why
+//!
+//! Harnesses read the output of the process, not of the functions behind
+//! it, so a log line or banner on stdout only shows up when the binary runs.
+use serde_json::Value;
+use std::io::{BufRead, BufReader};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+/// Writes a configuration serving `/ping` and returns its path.
+fn write_config(dir: &Path) -> PathBuf {
+    let path = dir.join("json-echo.json");
+    std::fs::write(
+        &path,
+        r#"{"hostname": "127.0.0.1", "routes": {"/ping": {"response": {"body": "pong"}}}}"#,
+    )
+    .expect("Failed to write test config");
+    path
+}
+
+/// Tests that `serve --print-summary json` writes the summary alone on stdout.
+///
+/// Verifies that the first line on stdout is the summary, with no banner or
+/// log before it, and that it reports the port bound for `--port 0`.
+#[test]
+fn test_print_summary_is_alone_on_stdout() {
+    let dir = tempfile::tempdir().expect("Failed to create temporary directory");
+    let config = write_config(dir.path());
+
+    let mut server = Command::new(env!("CARGO_BIN_EXE_echo"))
+        .arg("--config")
+        .arg(&config)
+        .args(["serve", "--port", "0", "--print-summary", "json"])
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()
+        .expect("Failed to start the server");
+
+    let mut line = String::new();
+    let read =
+        BufReader::new(server.stdout.take().expect("Stdout should be piped")).read_line(&mut line);
+    server.kill().expect("Failed to stop the server");
+    server.wait().expect("Failed to wait for the server");
+    read.expect("Failed to read the summary");
+
+    let summary: Value = serde_json::from_str(&line).expect("Stdout should start with the summary");
+    let address = summary["addresses"][0]
+        .as_str()
+        .expect("Summary should list the bound address");
+    assert!(address.starts_with("127.0.0.1:"), "{address}");
+    assert_ne!(address, "127.0.0.1:0");
+    assert_eq!(summary["routes"], 1);
+}
+
+/// Tests that `diff --json` writes nothing but the JSON report on stdout.
+///
+/// Verifies that the whole of stdout parses as one JSON document listing the
+/// added route, while the logs are written to stderr.
+#[test]
+fn test_diff_json_is_alone_on_stdout() {
+    let dir = tempfile::tempdir().expect("Failed to create temporary directory");
+    let old = write_config(dir.path());
+    let new = dir.path().join("new.json");
+    std::fs::write(
+        &new,
+        r#"{"routes": {"/ping": {"response": {"body": "pong"}}, "/health": {"response": {"body": "ok"}}}}"#,
+    )
+    .expect("Failed to write test config");
+
+    let output = Command::new(env!("CARGO_BIN_EXE_echo"))
+        .arg("diff")
+        .args([&old, &new])
+        .arg("--json")
+        .output()
+        .expect("Failed to run diff");
+
+    assert!(output.status.success(), "{output:?}");
+    let diff: Value = serde_json::from_slice(&output.stdout).expect("Stdout should be JSON");
+    assert_eq!(diff["added"], serde_json::json!(["[GET] /health"]));
+    assert!(
+        String::from_utf8_lossy(&output.stderr).contains("Starting applying configuration"),
+        "{output:?}"
+    );
+}
+
+/// Tests that `--quiet` prints neither the banner nor informational logs.
+///
+/// Verifies that `init` still writes its configuration while leaving both
+/// stdout and stderr empty.
+#[test]
+fn test_quiet_prints_nothing() {
+    let dir = tempfile::tempdir().expect("Failed to create temporary directory");
+
+    let output = Command::new(env!("CARGO_BIN_EXE_echo"))
+        .arg("--quiet")
+        .arg("--config")
+        .arg(dir.path().join("json-echo.json"))
+        .arg("init")
+        .output()
+        .expect("Failed to run init");
+
+    assert!(output.status.success(), "{output:?}");
+    assert!(output.stdout.is_empty(), "{output:?}");
+    assert!(output.stderr.is_empty(), "{output:?}");
+    assert!(dir.path().join("json-echo.json").exists());
+}
@@ -52,14 +52,22 @@
 //! # }
 //! ```
 
-use std::{collections::HashMap, path::PathBuf};
+use std::{
+    collections::{HashMap, HashSet},
+    path::{Path, PathBuf},
+    time::Duration,
+};
 
+use indexmap::IndexMap;
+use jsonschema::paths::{Location, LocationSegment};
+use rand::Rng;
+use rand_distr::{Distribution, LogNormal, StandardUniform};
 use serde::{Deserialize, Serialize};
 // The json! macro is used in documentation examples
 #[allow(unused_imports)]
 use serde_json::{Map, Value, json};
 
-use crate::{FileSystemManager, FileSystemResult, errors::FileSystemError};
+use crate::{FileSystemManager, FileSystemResult, PathUtils, errors::FileSystemError};
 
 /// Represents different types of response configurations for routes.
 ///
@@ -109,11 +117,37 @@ pub enum ConfigResponse {
 ///
 /// # Fields
 ///
-/// * `port` - Optional server port number (defaults to 3001)
+/// * `port` - Optional server port number (when omitted, `serve` falls back to `$PORT`, then 3001)
 /// * `hostname` - Optional server hostname (defaults to "localhost")
 /// * `static_folder` - Optional folder path for serving static files (relative to application root)
 /// * `static_route` - Base route path for static file serving (defaults to "/static")
-/// * `routes` - HashMap of route configurations indexed by route path
+/// * `cors` - Optional CORS settings applied to every route (permissive when omitted)
+/// * `internal_error` - Optional response for internal failures (a JSON 500 when omitted)
+/// * `charset` - Optional charset declared and used for every response (defaults to "utf-8")
+/// * `debug_headers` - Whether request count and timing headers are added to every response
+/// * `record_requests` - Whether received requests are recorded and listed on `/__admin/requests`
+/// * `definitions` - Optional named bodies that response bodies reference with `{"$ref": "#/definitions/name"}`
+/// * `envelope` - Optional wrapper applied to every successful JSON response body
+/// * `seed_file` - Optional JSON file of initial bodies keyed by route, overriding inline bodies
+/// * `routes_dir` - Optional directory of route files, one route per file, added to `routes`
+/// * `redact_headers` - Optional header names masked in logs (defaults to the credential headers)
+/// * `redact_fields` - Optional body field names masked in logs
+/// * `request_id_header` - Optional header carrying the request id (defaults to "X-Request-Id")
+/// * `startup_delay_ms` - Optional wait, in milliseconds, before the server accepts connections
+/// * `trailing_slash` - Whether `/users/` is served by the `/users` route (`merge`, default) or not (`strict`)
+/// * `listen` - Optional `host:port` addresses served together, replacing `hostname` and `port`
+/// * `maintenance_body` - Optional body answered with `503` while maintenance mode is on
+/// * `maintenance_retry_after` - Optional wait sent as `Retry-After` while maintenance mode is on
+/// * `disable_keep_alive` - Whether every response carries `Connection: close` and ends its connection
+/// * `http2` - Whether connections may speak HTTP/2 with prior knowledge (h2c) besides HTTP/1.1
+/// * `compression` - Whether response bodies are compressed for clients sending `Accept-Encoding`
+/// * `tls` - Optional certificate and key served over HTTPS instead of plain HTTP
+/// * `fallbacks` - Optional responses for unmatched paths under a prefix, instead of the default 404
+/// * `method_override` - Whether POST requests are served by the method named in `X-HTTP-Method-Override`
+/// * `max_concurrency` - Optional number of requests served at once, further requests answering `503`
+/// * `case_insensitive_paths` - Whether request paths match routes ignoring the case of their static segments
+/// * `plain_text_errors` - Whether unmatched paths and methods are answered in plain text instead of JSON
+/// * `routes` - Ordered map of route configurations indexed by route path
 ///
 /// # Examples
 ///
@@ -143,9 +177,10 @@ pub enum ConfigResponse {
 /// assert_eq!(parsed.static_route, "/assets".to_string());
 /// ```
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[allow(clippy::struct_excessive_bools)]
 pub struct Config {
-    /// The port on which the server will listen
-    #[serde(default = "default_port")]
+    /// The port on which the server will listen, resolved at serve time when omitted
+    #[serde(default)]
     pub port: Option<u16>,
     /// The hostname for the server
     #[serde(default = "default_host")]
@@ -156,151 +191,2504 @@ pub struct Config {
     /// The base route path for serving static files (defaults to "/static")
     #[serde(default = "default_static_route")]
     pub static_route: String,
-    /// A map of routes, where the key is the route path and the value is the route configuration
-    #[serde(default = "HashMap::new")]
-    pub routes: HashMap<String, ConfigRoute>,
+    /// Optional CORS settings applied to every route (permissive when omitted)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cors: Option<CorsConfig>,
+    /// Optional response sent by every handler when serving a request fails internally
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub internal_error: Option<ConfigRouteResponse>,
+    /// Optional charset declared in the content type of every response, e.g. `iso-8859-1`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub charset: Option<String>,
+    /// Whether `X-Echo-Request-Count` and `X-Echo-Response-Time-Ms` are added to every response
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub debug_headers: bool,
+    /// Whether received requests are recorded and listed on `/__admin/requests`
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub record_requests: bool,
+    /// Optional named bodies shared by routes through `{"$ref": "#/definitions/name"}`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub definitions: Option<IndexMap<String, Value>>,
+    /// Optional wrapper applied to every successful JSON response body
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub envelope: Option<EnvelopeSpec>,
+    /// Optional JSON file (relative to the root) mapping route identifiers to initial bodies
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub seed_file: Option<String>,
+    /// Optional directory (relative to the root) holding one route per file, added to `routes`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub routes_dir: Option<String>,
+    /// Optional header names, matched case-insensitively, whose values are masked in logs
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub redact_headers: Option<Vec<String>>,
+    /// Optional body field names whose values are masked in logs, at any depth
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub redact_fields: Option<Vec<String>>,
+    /// Optional header carrying the id propagated from the request to the response and logs,
+    /// checked to be a valid header name when the configuration is parsed
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        with = "request_id_header"
+    )]
+    pub request_id_header: Option<http::HeaderName>,
+    /// Optional wait, in milliseconds, before the server binds its listener
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub startup_delay_ms: Option<u64>,
+    /// Whether paths differing only by a trailing slash are served by the same route
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub trailing_slash: Option<TrailingSlash>,
+    /// Optional `host:port` addresses the server listens on at once, e.g. `127.0.0.1:3001` and `[::1]:3001`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub listen: Option<Vec<String>>,
+    /// Optional body every route answers with a `503` while maintenance mode is switched on
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub maintenance_body: Option<Value>,
+    /// Optional wait sent as `Retry-After` with the maintenance `503`, seconds or a date
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub maintenance_retry_after: Option<RetryAfter>,
+    /// Whether every response carries `Connection: close`, so clients open a new connection per request
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub disable_keep_alive: bool,
+    /// Whether connections may speak HTTP/2 with prior knowledge (h2c), detected per connection
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub http2: bool,
+    /// Whether response bodies are compressed with gzip, deflate or brotli, as negotiated
+    /// through `Accept-Encoding`
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub compression: bool,
+    /// Optional PEM certificate and key; when set, every listener serves HTTPS
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tls: Option<TlsConfig>,
+    /// Optional responses answered to unmatched paths under a prefix, the longest matching prefix winning
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fallbacks: Option<Vec<FallbackConfig>>,
+    /// Whether a POST carrying `X-HTTP-Method-Override: PUT`, `PATCH` or `DELETE` is routed as that method
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub method_override: bool,
+    /// Optional number of requests served at once; requests beyond it are answered `503` instead of queuing
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_concurrency: Option<usize>,
+    /// Whether request paths match routes ignoring the case of their static segments,
+    /// path parameters keeping the case they were sent with
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub case_insensitive_paths: bool,
+    /// Whether unmatched paths answer `404` with the text `No route defined` and unmatched
+    /// methods `405` with an empty body, as before JSON error bodies
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub plain_text_errors: bool,
+    /// A map of routes, where the key is the route path and the value is the route configuration,
+    /// kept in configuration order so saving a configuration does not reshuffle it
+    #[serde(default)]
+    pub routes: IndexMap<String, ConfigRoute>,
+}
+
+/// Returns the default port number for the server.
+///
+/// Provides a default port value of 3001 for generated configurations. A
+/// port missing from a loaded configuration stays `None`, so the `serve`
+/// command can fall back to the `PORT` environment variable first.
+///
+/// # Returns
+///
+/// `Some(3001)` - The default port number wrapped in an Option
+#[allow(clippy::unnecessary_wraps)]
+fn default_port() -> Option<u16> {
+    Some(3001)
+}
+
+/// Returns the default static route path for serving static files.
+///
+/// Provides a default static route value of "/static" for serving static files.
+/// This function is used by serde as the default value provider when
+/// the static_route field is missing from the configuration JSON.
+///
+/// # Returns
+///
+/// `"/static"` - The default static route path as a String
+///
+/// # Examples
+///
+/// ```rust
+/// use json_echo_core::Config;
+///
+/// let config = Config::default();
+/// assert_eq!(config.static_route, "/static");
+/// ```
+#[allow(clippy::unnecessary_wraps)]
+fn default_static_route() -> String {
+    String::from("/static")
+}
+
+/// Returns the default hostname for the server.
+///
+/// Provides a default hostname value of "localhost" for server configuration.
+/// This function is used by serde as the default value provider when
+/// the hostname field is missing from the configuration JSON.
+///
+/// # Returns
+///
+/// `Some("localhost")` - The default hostname string wrapped in an Option
+#[allow(clippy::unnecessary_wraps)]
+fn default_host() -> Option<String> {
+    Some(String::from("localhost"))
+}
+
+/// Reads and writes the `request_id_header` setting as a header name string.
+///
+/// A name that is not a valid header name fails parsing with an error naming
+/// it, so a loaded configuration only ever holds a usable header.
+mod request_id_header {
+    use http::HeaderName;
+    use serde::{Deserialize, Deserializer, Serializer, de::Error};
+
+    #[allow(clippy::ref_option)]
+    pub fn serialize<S: Serializer>(
+        header: &Option<HeaderName>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        match header {
+            Some(header) => serializer.serialize_some(header.as_str()),
+            None => serializer.serialize_none(),
+        }
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Option<HeaderName>, D::Error> {
+        Option::<String>::deserialize(deserializer)?
+            .map(|name| {
+                HeaderName::from_bytes(name.as_bytes()).map_err(|_| {
+                    D::Error::custom(format!(
+                        "request_id_header {name:?} is not a valid header name"
+                    ))
+                })
+            })
+            .transpose()
+    }
+}
+
+impl Default for Config {
+    /// Creates a new Config instance with default values.
+    ///
+    /// Provides a default configuration suitable for development environments
+    /// with localhost hostname, port 3001, and no predefined routes.
+    ///
+    /// # Returns
+    ///
+    /// A new `Config` instance with default server settings
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use json_echo_core::Config;
+    ///
+    /// let config = Config::default();
+    /// assert_eq!(config.port, Some(3001));
+    /// assert_eq!(config.hostname, Some("localhost".to_string()));
+    /// assert!(config.static_folder.is_none());
+    /// assert_eq!(config.static_route, "/static");
+    /// assert!(config.routes.is_empty());
+    /// ```
+    fn default() -> Self {
+        Self {
+            port: default_port(),
+            hostname: default_host(),
+            static_folder: None,
+            static_route: default_static_route(),
+            cors: None,
+            internal_error: None,
+            charset: None,
+            debug_headers: false,
+            record_requests: false,
+            definitions: None,
+            envelope: None,
+            seed_file: None,
+            routes_dir: None,
+            redact_headers: None,
+            redact_fields: None,
+            request_id_header: None,
+            startup_delay_ms: None,
+            trailing_slash: None,
+            listen: None,
+            maintenance_body: None,
+            maintenance_retry_after: None,
+            disable_keep_alive: false,
+            http2: false,
+            compression: false,
+            tls: None,
+            fallbacks: None,
+            method_override: false,
+            max_concurrency: None,
+            case_insensitive_paths: false,
+            plain_text_errors: false,
+            routes: IndexMap::new(),
+        }
+    }
+}
+
+/// Configuration for an individual route including HTTP method, headers, and response.
+///
+/// The `ConfigRoute` struct defines how a specific API endpoint should behave,
+/// including the HTTP method it responds to, any custom headers, and the response
+/// data. It supports flexible response definitions through the ConfigResponse enum.
+///
+/// # Fields
+///
+/// * `method` - Optional HTTP method (defaults to "GET")
+/// * `description` - Optional human-readable description of the route
+/// * `headers` - Optional custom HTTP headers to include in responses
+/// * `content_type` - Optional content type of the responses, overridden by a `Content-Type` header
+/// * `id_field` - Optional field name to use as unique identifier (defaults to "id")
+/// * `results_field` - Optional field name containing results when data is nested
+/// * `wrap_results` - Whether GETs serve the whole body around `results_field` instead of only its array
+/// * `cors` - Optional CORS settings overriding the global ones for this route's path
+/// * `query_params` - Optional typed specification of the accepted query parameters
+/// * `strict_query` - Whether query parameters missing from the specification are rejected
+/// * `delay` - Optional delay before responding, fixed or keyed by response status
+/// * `latency` - Optional latency sampled per request from a distribution, added to any delay
+/// * `alias` - Optional key of another route whose data is served by this route
+/// * `redirect` - Optional HTTP redirect answered instead of a response body
+/// * `sequence` - Optional responses served in turn, one per call
+/// * `sequence_mode` - Whether the sequence repeats its last entry (`once`) or wraps (`loop`)
+/// * `variants` - Optional responses to write requests, picked by matching the request body
+/// * `responses` - Optional weighted pool of responses, one picked at random per GET
+/// * `localized` - Optional bodies keyed by language tag, picked by the request's `Accept-Language`
+/// * `echo` - Whether write requests are answered with their payload instead of being stored
+/// * `echo_key` - Optional key the echoed payload is nested under
+/// * `request_unwrap` - Optional JSON pointer to the part of write payloads that is stored
+/// * `response_wrap` - Optional JSON pointer the stored data is nested under in write responses
+/// * `fields_spec` - Optional per-field defaults and required flags for written objects
+/// * `charset` - Optional charset overriding the global one for this route's responses
+/// * `relations` - Optional related collections attached on `_embed` and `_expand` queries
+/// * `error` - Optional error answered with a standard envelope instead of a response body
+/// * `tags` - Optional labels used to serve a subset of the routes
+/// * `enabled` - Whether the route is served; `false` turns it off without deleting it
+/// * `stream` - Whether array bodies are streamed item by item as NDJSON
+/// * `grow` - Whether successive GETs serve one more item of the collection each, up to all of them
+/// * `stream_interval_ms` - Optional pause, in milliseconds, between streamed items
+/// * `job` - Optional job started by write requests, answered with `202 Accepted`
+/// * `save_uploads_to` - Optional directory, below the root, write request bodies are saved to
+/// * `response_schema` - Optional JSON Schema the configured body must satisfy when loaded
+/// * `disable_keep_alive` - Whether responses on this route's path carry `Connection: close`
+/// * `id_strategy` - Optional way ids are generated for written objects that carry none
+/// * `cache` - Optional caching preset expanded into `Cache-Control` on successful responses
+/// * `retry_after` - Optional wait, in seconds or until a date, sent as `Retry-After` on `429` and `503` responses
+/// * `lazy` - Whether a file referenced by `response` is loaded on the first request instead of at startup
+/// * `sql` - Optional SQLite query whose rows are answered instead of a response body
+/// * `proxy` - Optional upstream the route's requests are forwarded to instead of being mocked
+/// * `log_level` - Optional cap on the events logged for this route, below the global log level
+/// * `response` - The response configuration for this route (optional for aliases and redirects)
+///
+/// # Examples
+///
+/// ```rust
+/// use json_echo_core::{ConfigRoute, ConfigResponse, ConfigRouteResponse, BodyResponse};
+/// use serde_json::Value;
+/// use std::collections::HashMap;
+///
+/// let mut headers = HashMap::new();
+/// headers.insert("Content-Type".to_string(), "application/json".to_string());
+///
+/// let route = ConfigRoute {
+///     method: Some("GET".to_string()),
+///     description: Some("Get user list".to_string()),
+///     headers: Some(headers),
+///     id_field: Some("user_id".to_string()),
+///     results_field: Some("data".to_string()),
+///     response: ConfigResponse::ConfigRouteResponse(ConfigRouteResponse {
+///         status: Some(200),
+///         body: BodyResponse::Value(Value::Null),
+///     }),
+///     ..ConfigRoute::default()
+/// };
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[allow(clippy::struct_excessive_bools)]
+pub struct ConfigRoute {
+    /// The HTTP method for the route (e.g., GET, POST)
+    #[serde(default = "default_method")]
+    pub method: Option<String>,
+    /// Optional human-readable description of the route
+    #[serde(default)]
+    pub description: Option<String>,
+    /// Optional custom HTTP headers to include in responses
+    #[serde(default)]
+    pub headers: Option<HashMap<String, String>>,
+    /// Optional content type of the responses, e.g. `text/html`, used unless `headers` sets one
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub content_type: Option<String>,
+    /// The field in the response body to use as the unique identifier, if applicable
+    #[serde(default = "default_id_field")]
+    pub id_field: Option<String>,
+    /// The field in the response body that contains the results array, if applicable
+    #[serde(default)]
+    pub results_field: Option<String>,
+    /// Optional CORS settings overriding the global ones for this route's path
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cors: Option<CorsConfig>,
+    /// Optional typed specification of the query parameters accepted by the route
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub query_params: Option<HashMap<String, QueryParamSpec>>,
+    /// Whether query parameters not listed in `query_params` are rejected
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub strict_query: bool,
+    /// Optional delay, in milliseconds, applied before the response is sent
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub delay: Option<ResponseDelay>,
+    /// Optional latency sampled per request from a distribution, slept on top of any `delay`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub latency: Option<LatencyConfig>,
+    /// Optional key of another route whose data this route serves
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub alias: Option<String>,
+    /// Optional HTTP redirect answered instead of a response body
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub redirect: Option<RedirectConfig>,
+    /// Optional responses served in turn, one per call, instead of the model data
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sequence: Option<Vec<ConfigRouteResponse>>,
+    /// How the sequence continues once its last response was served
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sequence_mode: Option<SequenceMode>,
+    /// Optional responses to write requests, the first whose `when_body` matches the payload
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub variants: Option<Vec<ResponseVariant>>,
+    /// Optional responses served at random instead of the model data, in proportion to their weights
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub responses: Option<Vec<WeightedResponse>>,
+    /// Optional GET bodies keyed by language tag, e.g. `fr` or `pt-BR`, served instead of the model data
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub localized: Option<HashMap<String, BodyResponse>>,
+    /// Whether write requests are answered with their own payload instead of being stored
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub echo: bool,
+    /// Optional key the echoed payload is nested under, e.g. `data`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub echo_key: Option<String>,
+    /// Optional JSON pointer, e.g. `/user`, to the part of write payloads that is stored
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub request_unwrap: Option<String>,
+    /// Optional JSON pointer, e.g. `/user`, the stored data is nested under in write responses
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub response_wrap: Option<String>,
+    /// Optional defaults and required flags applied to written objects before merging
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fields_spec: Option<HashMap<String, FieldSpec>>,
+    /// Optional charset overriding the global one for this route's responses
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub charset: Option<String>,
+    /// Optional related collections, keyed by the name used in `_embed` and `_expand`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub relations: Option<HashMap<String, RelationConfig>>,
+    /// Optional error answered with a standard envelope instead of a response body
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<ErrorConfig>,
+    /// Optional labels used to serve a subset of the routes, e.g. `auth`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tags: Option<Vec<String>>,
+    /// Whether the route is served, `true` when unset
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub enabled: Option<bool>,
+    /// Whether GET array bodies are streamed as newline-delimited JSON, one item per line
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub stream: bool,
+    /// Whether the Nth GET of the collection serves its first N items, then the full collection
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub grow: bool,
+    /// Whether GETs serve the whole body, its `results_field` array filtered, sorted and paged
+    /// in place, instead of only that array
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub wrap_results: bool,
+    /// Optional pause, in milliseconds, between two streamed items
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub stream_interval_ms: Option<u64>,
+    /// Optional job started by write requests, answered with `202 Accepted` and its status route
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub job: Option<JobConfig>,
+    /// Optional directory, relative to the project root, write request bodies are saved to
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub save_uploads_to: Option<String>,
+    /// Optional JSON Schema the configured body is checked against when the configuration loads
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub response_schema: Option<Value>,
+    /// Whether responses on this route's path carry `Connection: close` and end their connection
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub disable_keep_alive: bool,
+    /// Optional way ids are generated for objects written without one: `sequential`, `uuid` or `ulid`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id_strategy: Option<IdStrategy>,
+    /// Optional caching preset, `no-store`, `immutable` or `{"max_age": 60}`, for successful responses
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cache: Option<CachePreset>,
+    /// Optional wait sent as `Retry-After` on `429` and `503` responses, seconds or a date
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub retry_after: Option<RetryAfter>,
+    /// Whether the file referenced by a string `response` is only loaded, then kept, on the first request
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub lazy: bool,
+    /// Optional SQLite query whose rows are answered as a JSON array instead of a response body
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sql: Option<SqlConfig>,
+    /// Optional upstream the route's requests are forwarded to, answering with its response
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub proxy: Option<ProxyConfig>,
+    /// Optional most verbose level logged while this route handles a request, `off` silencing it
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub log_level: Option<LogLevel>,
+    /// The response configuration for this route
+    #[serde(default = "default_response")]
+    pub response: ConfigResponse,
+}
+
+impl ConfigRoute {
+    /// Validates incoming query parameters against the route's specification.
+    ///
+    /// Every declared parameter present in the query must coerce to its type,
+    /// and required parameters must be present. Parameters that are not
+    /// declared are accepted unless `strict_query` is enabled. Routes without
+    /// a specification accept any query.
+    ///
+    /// # Parameters
+    ///
+    /// * `query` - The query parameters of the incoming request
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - If the query satisfies the specification
+    /// * `Err(String)` - A message naming the parameter that failed validation
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use json_echo_core::ConfigRoute;
+    /// use std::collections::HashMap;
+    ///
+    /// let route: ConfigRoute = serde_json::from_str(r#"
+    /// {"query_params": {"page": "int"}, "response": {"status": 200, "body": []}}
+    /// "#).unwrap();
+    ///
+    /// let query = HashMap::from([("page".to_string(), "two".to_string())]);
+    /// assert!(route.validate_query(&query).is_err());
+    /// ```
+    pub fn validate_query(&self, query: &HashMap<String, String>) -> Result<(), String> {
+        let Some(specs) = &self.query_params else {
+            return Ok(());
+        };
+
+        let mut declared: Vec<(&String, &QueryParamSpec)> = specs.iter().collect();
+        declared.sort_by_key(|(name, _)| *name);
+
+        for (name, spec) in declared {
+            match query.get(name) {
+                Some(value) if !spec.param_type().accepts(value) => {
+                    return Err(format!(
+                        "Query parameter '{name}' must be of type {}, got '{value}'",
+                        spec.param_type()
+                    ));
+                }
+                None if spec.is_required() => {
+                    return Err(format!("Missing required query parameter '{name}'"));
+                }
+                _ => {}
+            }
+        }
+
+        if self.strict_query {
+            let mut unknown: Vec<&String> = query
+                .keys()
+                .filter(|name| !specs.contains_key(*name))
+                .collect();
+            unknown.sort();
+
+            if let Some(name) = unknown.first() {
+                return Err(format!("Unknown query parameter '{name}'"));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Prepares a written payload according to the route's `fields_spec`.
+    ///
+    /// Required fields must be present in every written object, and fields
+    /// with a default are filled in when absent; present values are never
+    /// replaced. Arrays are processed object by object. Routes without a
+    /// specification return the payload unchanged.
+    ///
+    /// # Parameters
+    ///
+    /// * `payload` - The JSON payload of the request
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Value)` - The payload with defaults filled in
+    /// * `Err(String)` - A message naming the missing required field
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use json_echo_core::ConfigRoute;
+    /// use serde_json::json;
+    ///
+    /// let route: ConfigRoute = serde_json::from_value(json!({
+    ///     "fields_spec": {"name": {"required": true}, "role": {"default": "user"}}
+    /// })).unwrap();
+    ///
+    /// assert_eq!(
+    ///     route.apply_fields_spec(json!({"name": "Ada"})),
+    ///     Ok(json!({"name": "Ada", "role": "user"}))
+    /// );
+    /// assert!(route.apply_fields_spec(json!({"role": "admin"})).is_err());
+    /// ```
+    pub fn apply_fields_spec(&self, payload: Value) -> Result<Value, String> {
+        let Some(specs) = &self.fields_spec else {
+            return Ok(payload);
+        };
+
+        let mut names: Vec<&String> = specs.keys().collect();
+        names.sort();
+
+        let apply = |entry: Value| -> Result<Value, String> {
+            let Value::Object(mut object) = entry else {
+                return match names.iter().find(|name| specs[**name].required) {
+                    Some(name) => Err(format!("Missing required field '{name}'")),
+                    None => Ok(entry),
+                };
+            };
+
+            for name in &names {
+                let spec = &specs[*name];
+
+                if object.contains_key(name.as_str()) {
+                    continue;
+                }
+                if spec.required {
+                    return Err(format!("Missing required field '{name}'"));
+                }
+                if let Some(default) = &spec.default {
+                    object.insert((*name).clone(), default.clone());
+                }
+            }
+
+            Ok(Value::Object(object))
+        };
+
+        match payload {
+            Value::Array(entries) => entries
+                .into_iter()
+                .map(apply)
+                .collect::<Result<Vec<_>, _>>()
+                .map(Value::Array),
+            entry => apply(entry),
+        }
+    }
+
+    /// Builds the body of an echo response from the received payload.
+    ///
+    /// # Parameters
+    ///
+    /// * `payload` - The JSON payload of the request
+    ///
+    /// # Returns
+    ///
+    /// The payload itself, or an object holding it under `echo_key` when set
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use json_echo_core::ConfigRoute;
+    /// use serde_json::json;
+    ///
+    /// let route = ConfigRoute {
+    ///     echo: true,
+    ///     echo_key: Some("data".to_string()),
+    ///     ..ConfigRoute::default()
+    /// };
+    ///
+    /// assert_eq!(route.echo_body(json!({"id": 1})), json!({"data": {"id": 1}}));
+    /// ```
+    pub fn echo_body(&self, payload: Value) -> Value {
+        match &self.echo_key {
+            Some(key) => json!({ key.as_str(): payload }),
+            None => payload,
+        }
+    }
+
+    /// Extracts the part of a write payload that is stored, at `request_unwrap`.
+    ///
+    /// # Parameters
+    ///
+    /// * `payload` - The JSON payload of the request
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Value)` - The value at the pointer, or the payload itself when none is set
+    /// * `Err(String)` - A message naming the pointer when the payload has nothing there
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use json_echo_core::ConfigRoute;
+    /// use serde_json::json;
+    ///
+    /// let route = ConfigRoute {
+    ///     request_unwrap: Some("/user".to_string()),
+    ///     ..ConfigRoute::default()
+    /// };
+    ///
+    /// assert_eq!(route.unwrap_request(json!({"user": {"id": 1}})), Ok(json!({"id": 1})));
+    /// assert!(route.unwrap_request(json!({"id": 1})).is_err());
+    /// ```
+    pub fn unwrap_request(&self, mut payload: Value) -> Result<Value, String> {
+        match &self.request_unwrap {
+            Some(pointer) => payload
+                .pointer_mut(pointer)
+                .map(Value::take)
+                .ok_or_else(|| format!("Payload has no value at {pointer}")),
+            None => Ok(payload),
+        }
+    }
+
+    /// Nests the data answered to a write request under `response_wrap`.
+    ///
+    /// Every token of the pointer becomes an object key, `~1` and `~0`
+    /// standing for `/` and `~` as in any JSON pointer.
+    ///
+    /// # Parameters
+    ///
+    /// * `body` - The stored data answered to the request
+    ///
+    /// # Returns
+    ///
+    /// The body nested under the pointer, or the body itself when none is set
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use json_echo_core::ConfigRoute;
+    /// use serde_json::json;
+    ///
+    /// let route = ConfigRoute {
+    ///     response_wrap: Some("/data/user".to_string()),
+    ///     ..ConfigRoute::default()
+    /// };
+    ///
+    /// assert_eq!(route.wrap_response(json!({"id": 1})), json!({"data": {"user": {"id": 1}}}));
+    /// ```
+    pub fn wrap_response(&self, body: Value) -> Value {
+        // The empty pointer refers to the whole body
+        let Some(tokens) = self
+            .response_wrap
+            .as_deref()
+            .and_then(|pointer| pointer.strip_prefix('/'))
+        else {
+            return body;
+        };
+
+        tokens.rsplit('/').fold(
+            body,
+            |wrapped, token| json!({ token.replace("~1", "/").replace("~0", "~"): wrapped }),
+        )
+    }
+
+    /// Returns the sequence response to serve for the given call.
+    ///
+    /// # Parameters
+    ///
+    /// * `call` - The zero-based number of the call to the route
+    ///
+    /// # Returns
+    ///
+    /// * `Some(&ConfigRouteResponse)` - The entry picked by the `sequence_mode`
+    /// * `None` - If the route has no sequence or the sequence is empty
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use json_echo_core::ConfigRoute;
+    ///
+    /// let route: ConfigRoute = serde_json::from_str(r#"
+    /// {"sequence": [{"status": 503, "body": {}}, {"status": 200, "body": []}]}
+    /// "#).unwrap();
+    ///
+    /// assert_eq!(route.sequence_response(0).and_then(|r| r.status), Some(503));
+    /// assert_eq!(route.sequence_response(5).and_then(|r| r.status), Some(200));
+    /// ```
+    pub fn sequence_response(&self, call: usize) -> Option<&ConfigRouteResponse> {
+        let sequence = self.sequence.as_ref()?;
+        let index = self
+            .sequence_mode
+            .unwrap_or_default()
+            .index(call, sequence.len())?;
+
+        sequence.get(index)
+    }
+
+    /// Returns the variant response to serve for a written payload.
+    ///
+    /// # Parameters
+    ///
+    /// * `payload` - The parsed request body
+    ///
+    /// # Returns
+    ///
+    /// * `Some(&ConfigRouteResponse)` - The response of the first variant matching the payload
+    /// * `None` - If the route has no variants or none of them matches
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use json_echo_core::ConfigRoute;
+    /// # use serde_json::json;
+    /// let route: ConfigRoute = serde_json::from_value(json!({"variants": [
+    ///     {"when_body": {"user": "admin"}, "status": 200, "body": {}},
+    ///     {"status": 401, "body": {}}
+    /// ]})).unwrap();
+    /// assert_eq!(route.variant_response(&json!({"user": "admin", "pass": "x"})).and_then(|r| r.status), Some(200));
+    /// assert_eq!(route.variant_response(&json!({"user": "guest"})).and_then(|r| r.status), Some(401));
+    /// ```
+    pub fn variant_response(&self, payload: &Value) -> Option<&ConfigRouteResponse> {
+        self.variants
+            .as_ref()?
+            .iter()
+            .find(|variant| variant.matches(payload))
+            .map(|variant| &variant.response)
+    }
+
+    /// Returns the response of the weighted pool selected by a random roll.
+    ///
+    /// The pool is laid out on `[0, 1)` with every response taking a share
+    /// proportional to its weight, and the response whose share holds the roll
+    /// is picked. Responses with a zero weight are never picked.
+    ///
+    /// # Parameters
+    ///
+    /// * `roll` - A number in `[0, 1)`, drawn at random for every request
+    ///
+    /// # Returns
+    ///
+    /// * `Some(&ConfigRouteResponse)` - The picked response
+    /// * `None` - If the route has no pool or every weight is zero
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use json_echo_core::ConfigRoute;
+    /// # use serde_json::json;
+    /// let route: ConfigRoute = serde_json::from_value(json!({"responses": [
+    ///     {"weight": 80, "response": {"status": 200, "body": {}}},
+    ///     {"weight": 20, "response": {"status": 500, "body": {}}}
+    /// ]})).unwrap();
+    /// assert_eq!(route.weighted_response(0.5).and_then(|r| r.status), Some(200));
+    /// assert_eq!(route.weighted_response(0.9).and_then(|r| r.status), Some(500));
+    /// ```
+    pub fn weighted_response(&self, roll: f64) -> Option<&ConfigRouteResponse> {
+        let pool = self.responses.as_ref()?;
+        let total: f64 = pool.iter().map(|weighted| weighted.weight).sum();
+        if total <= 0.0 {
+            return None;
+        }
+
+        let mut remaining = roll * total;
+        pool.iter()
+            .filter(|weighted| weighted.weight > 0.0)
+            .find(|weighted| {
+                remaining -= weighted.weight;
+                remaining < 0.0
+            })
+            // Rounding can leave the roll past the last share
+            .or_else(|| pool.iter().rfind(|weighted| weighted.weight > 0.0))
+            .map(|weighted| &weighted.response)
+    }
+
+    /// Returns the localized body to serve for an `Accept-Language` header.
+    ///
+    /// The language ranges of the header are tried from the highest quality
+    /// down, ranges of equal quality in the order they are listed, and ranges
+    /// with a zero or malformed quality are ignored. Each range is looked up
+    /// as in RFC 4647: an exact tag first, ignoring case, then the range with
+    /// its last subtag dropped, so `fr-CA` falls back to `fr`. A `*` range
+    /// accepts the default body.
+    ///
+    /// # Parameters
+    ///
+    /// * `accept_language` - The `Accept-Language` header of the request, empty if it has none
+    ///
+    /// # Returns
+    ///
+    /// * `Some((&str, &BodyResponse))` - The configured tag and body of the best accepted language
+    /// * `None` - If the route has no localized bodies or the default body is served
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use json_echo_core::ConfigRoute;
+    /// # use serde_json::json;
+    /// let route: ConfigRoute =
+    ///     serde_json::from_value(json!({"localized": {"fr": "Bonjour", "de": "Hallo"}})).unwrap();
+    /// assert_eq!(route.localized_body("fr-CA").map(|(tag, _)| tag), Some("fr"));
+    /// assert_eq!(route.localized_body("es;q=1, de;q=0.8").map(|(tag, _)| tag), Some("de"));
+    /// assert!(route.localized_body("es").is_none());
+    /// ```
+    pub fn localized_body(&self, accept_language: &str) -> Option<(&str, &BodyResponse)> {
+        let localized = self.localized.as_ref()?;
+
+        let mut ranges: Vec<(&str, f64)> = accept_language
+            .split(',')
+            .filter_map(|range| {
+                let mut parts = range.split(';');
+                let tag = parts.next()?.trim();
+                let quality = parts
+                    .find_map(|param| param.trim().strip_prefix("q="))
+                    .map_or(Some(1.0), |quality| quality.trim().parse::<f64>().ok())?;
+                (!tag.is_empty() && quality > 0.0).then_some((tag, quality))
+            })
+            .collect();
+        // The sort is stable, so ranges of equal quality keep their order
+        ranges.sort_by(|(_, left), (_, right)| right.total_cmp(left));
+
+        for (range, _) in ranges {
+            if range == "*" {
+                return None;
+            }
+
+            let mut candidate = range;
+            loop {
+                if let Some((tag, body)) = localized
+                    .iter()
+                    .find(|(tag, _)| tag.eq_ignore_ascii_case(candidate))
+                {
+                    return Some((tag.as_str(), body));
+                }
+                match candidate.rsplit_once('-') {
+                    Some((prefix, _)) => candidate = prefix,
+                    None => break,
+                }
+            }
+        }
+
+        None
+    }
+
+    /// Returns whether the route is served.
+    ///
+    /// Routes are enabled unless `enabled` is explicitly `false`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use json_echo_core::ConfigRoute;
+    ///
+    /// let route: ConfigRoute = serde_json::from_str(r#"{"enabled": false}"#).unwrap();
+    ///
+    /// assert!(!route.is_enabled());
+    /// assert!(ConfigRoute::default().is_enabled());
+    /// ```
+    pub fn is_enabled(&self) -> bool {
+        self.enabled.unwrap_or(true)
+    }
+}
+
+/// A selection of routes by their tags.
+///
+/// # Fields
+///
+/// * `tags` - The tags selecting a route; a route matches when it has any of them
+/// * `include_untagged` - Whether routes without tags are kept as well
+///
+/// # Examples
+///
+/// ```rust
+/// use json_echo_core::{ConfigRoute, TagFilter};
+///
+/// let filter = TagFilter {
+///     tags: vec!["auth".to_string()],
+///     include_untagged: false,
+/// };
+/// let route = ConfigRoute {
+///     tags: Some(vec!["auth".to_string(), "users".to_string()]),
+///     ..ConfigRoute::default()
+/// };
+///
+/// assert!(filter.matches(&route));
+/// assert!(!filter.matches(&ConfigRoute::default()));
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TagFilter {
+    /// The tags selecting a route; a route matches when it has any of them
+    pub tags: Vec<String>,
+    /// Whether routes without tags are kept as well
+    pub include_untagged: bool,
+}
+
+impl TagFilter {
+    /// Returns whether a route is selected by the filter.
+    ///
+    /// # Parameters
+    ///
+    /// * `route` - The route to check
+    ///
+    /// # Returns
+    ///
+    /// `true` if the route has one of the tags, or has no tags and untagged
+    /// routes are included
+    pub fn matches(&self, route: &ConfigRoute) -> bool {
+        match route.tags.as_deref() {
+            None | Some([]) => self.include_untagged,
+            Some(tags) => tags.iter().any(|tag| self.tags.contains(tag)),
+        }
+    }
+
+    /// Removes the routes the filter does not select from a configuration.
+    ///
+    /// # Parameters
+    ///
+    /// * `config` - The loaded configuration whose routes are filtered in place
+    ///
+    /// # Returns
+    ///
+    /// The number of routes removed
+    pub fn retain(&self, config: &mut Config) -> usize {
+        let before = config.routes.len();
+        config.routes.retain(|_, route| self.matches(route));
+        before - config.routes.len()
+    }
+}
+
+/// Processing applied to one field of objects written to a route.
+///
+/// # Fields
+///
+/// * `default` - The value filled in when the field is absent
+/// * `required` - Whether writes without the field are rejected
+///
+/// # Examples
+///
+/// ```json
+/// {
+///   "name": {"required": true},
+///   "role": {"default": "user"}
+/// }
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct FieldSpec {
+    /// The value filled in when the field is absent
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub default: Option<Value>,
+    /// Whether writes without the field are rejected
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub required: bool,
+}
+
+/// A relationship between a route's entries and another collection route.
+///
+/// The same declaration serves both directions: `_embed` attaches the related
+/// entries whose `foreign_key` equals the entry's id, while `_expand` attaches
+/// the related entry whose id equals the entry's `foreign_key`.
+///
+/// # Fields
+///
+/// * `route` - The GET route holding the related collection, e.g. `/api/comments`
+/// * `foreign_key` - The field referencing the parent's id, e.g. `postId`
+///
+/// # Examples
+///
+/// ```json
+/// {
+///   "/api/posts": {
+///     "relations": {"comments": {"route": "/api/comments", "foreign_key": "postId"}}
+///   },
+///   "/api/comments": {
+///     "relations": {"post": {"route": "/api/posts", "foreign_key": "postId"}}
+///   }
+/// }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RelationConfig {
+    /// The GET route holding the related collection
+    pub route: String,
+    /// The field referencing the parent's id
+    pub foreign_key: String,
+}
+
+/// A response of a weighted pool, picked at random in proportion to its weight.
+///
+/// # Fields
+///
+/// * `weight` - The relative chance of the response, a non-negative number
+/// * `response` - The status and body answered when the response is picked
+///
+/// # Examples
+///
+/// ```rust
+/// use json_echo_core::WeightedResponse;
+/// use serde_json::json;
+///
+/// let weighted: WeightedResponse = serde_json::from_value(json!({
+///     "weight": 20,
+///     "response": {"status": 500, "body": {"error": "boom"}}
+/// })).unwrap();
+/// assert_eq!(weighted.response.status, Some(500));
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WeightedResponse {
+    /// The relative chance of the response
+    pub weight: f64,
+    /// The response answered when picked
+    pub response: ConfigRouteResponse,
+}
+
+/// A response picked when the request body matches a pattern.
+///
+/// # Fields
+///
+/// * `when_body` - Optional JSON the body must contain; a variant without one always matches
+/// * `response` - The status and body answered, flattened into the variant
+///
+/// # Examples
+///
+/// ```rust
+/// use json_echo_core::ResponseVariant;
+/// use serde_json::json;
+///
+/// let variant: ResponseVariant = serde_json::from_value(json!({
+///     "when_body": {"user": {"role": "admin"}},
+///     "status": 200,
+///     "body": {"token": "abc"}
+/// })).unwrap();
+/// assert!(variant.matches(&json!({"user": {"role": "admin", "name": "ada"}})));
+/// assert!(!variant.matches(&json!({"user": {"role": "guest"}})));
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ResponseVariant {
+    /// Optional JSON the request body must contain for this variant to be picked
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub when_body: Option<Value>,
+    /// The response answered when the variant is picked
+    #[serde(flatten)]
+    pub response: ConfigRouteResponse,
+}
+
+impl ResponseVariant {
+    /// Returns whether the variant applies to a request body.
+    pub fn matches(&self, payload: &Value) -> bool {
+        self.when_body
+            .as_ref()
+            .is_none_or(|pattern| contains_json(payload, pattern))
+    }
+}
+
+/// Returns whether a JSON value contains a pattern.
+///
+/// Objects match when every key of the pattern is present and matches, at
+/// any depth, so extra fields in the value are ignored. Arrays match element
+/// by element and must have the same length; other values must be equal.
+fn contains_json(value: &Value, pattern: &Value) -> bool {
+    match (value, pattern) {
+        (Value::Object(value), Value::Object(pattern)) => pattern.iter().all(|(key, expected)| {
+            value
+                .get(key)
+                .is_some_and(|actual| contains_json(actual, expected))
+        }),
+        (Value::Array(values), Value::Array(patterns)) => {
+            values.len() == patterns.len()
+                && values
+                    .iter()
+                    .zip(patterns)
+                    .all(|(value, pattern)| contains_json(value, pattern))
+        }
+        _ => value == pattern,
+    }
+}
+
+/// How a response sequence continues after its last entry.
+///
+/// # Variants
+///
+/// * `Once` - The last entry is repeated for every further call (default)
+/// * `Loop` - The sequence starts over from its first entry
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SequenceMode {
+    /// Repeat the last entry once the sequence is exhausted
+    #[default]
+    Once,
+    /// Wrap around to the first entry
+    Loop,
+}
+
+impl SequenceMode {
+    /// Returns the index of the entry served for a call, or `None` for an empty sequence.
+    pub fn index(self, call: usize, len: usize) -> Option<usize> {
+        let last = len.checked_sub(1)?;
+
+        Some(match self {
+            SequenceMode::Once => call.min(last),
+            SequenceMode::Loop => call % len,
+        })
+    }
+}
+
+/// How request paths ending with a slash are matched against the routes.
+///
+/// # Variants
+///
+/// * `Merge` - `/users/` is served by the `/users` route (default)
+/// * `Strict` - `/users/` only matches a route declared with the trailing slash
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TrailingSlash {
+    /// Trim trailing slashes before routing
+    #[default]
+    Merge,
+    /// Match paths exactly as they are requested
+    Strict,
+}
+
+/// The most verbose events logged while a route handles a request.
+///
+/// # Variants
+///
+/// * `Off` - Nothing is logged for the route
+/// * `Error` - Only errors are logged
+/// * `Warn` - Warnings and errors are logged
+/// * `Info` - Informational events and above are logged
+/// * `Debug` - Debug events and above are logged, such as the request headers
+/// * `Trace` - Every event is logged
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LogLevel {
+    /// Log nothing
+    Off,
+    /// Log errors only
+    Error,
+    /// Log warnings and errors
+    Warn,
+    /// Log informational events and above
+    Info,
+    /// Log debug events and above
+    Debug,
+    /// Log every event
+    Trace,
+}
+
+/// How ids are generated for objects written to a collection without one.
+///
+/// # Variants
+///
+/// * `Sequential` - One more than the highest integer id of the collection, e.g. `4`
+/// * `Uuid` - A random v4 UUID string, e.g. `"1b4e28ba-2fa1-4d3b-a3f5-ef19b5a7633b"`
+/// * `Ulid` - A time-ordered ULID string, e.g. `"01J9ZQ3V5Y8N2K4M6P7R9S0T1V"`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum IdStrategy {
+    /// Integer ids, one more than the highest one
+    Sequential,
+    /// Random v4 UUID strings
+    Uuid,
+    /// Time-ordered ULID strings
+    Ulid,
+}
+
+impl IdStrategy {
+    /// Generates the id of an entry added to a collection.
+    ///
+    /// # Parameters
+    ///
+    /// * `entries` - The entries already in the collection
+    /// * `id_field` - The field holding the id of every entry
+    ///
+    /// # Returns
+    ///
+    /// An integer for `Sequential`, where entries without an integer id are
+    /// ignored and an empty collection starts at `1`, otherwise a string
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use json_echo_core::IdStrategy;
+    /// use serde_json::json;
+    ///
+    /// let entries = [json!({"id": 3}), json!({"id": "abc"})];
+    /// assert_eq!(IdStrategy::Sequential.next_id(&entries, "id"), json!(4));
+    /// assert!(IdStrategy::Uuid.next_id(&entries, "id").is_string());
+    /// ```
+    pub fn next_id(self, entries: &[Value], id_field: &str) -> Value {
+        match self {
+            IdStrategy::Sequential => {
+                let highest = entries
+                    .iter()
+                    .filter_map(|entry| entry.get(id_field).and_then(Value::as_u64))
+                    .max()
+                    .unwrap_or(0);
+                Value::from(highest.saturating_add(1))
+            }
+            IdStrategy::Uuid => Value::String(uuid::Uuid::new_v4().to_string()),
+            IdStrategy::Ulid => Value::String(ulid::Ulid::generate().to_string()),
+        }
+    }
+}
+
+/// The type a query parameter value must coerce to.
+///
+/// # Variants
+///
+/// * `Int` - A signed integer, e.g. `42`
+/// * `Number` - Any number, e.g. `4.2`
+/// * `Bool` - `true` or `false`
+/// * `String` - Any value
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum QueryParamType {
+    /// A signed integer
+    Int,
+    /// Any number, integer or floating point
+    Number,
+    /// A boolean, `true` or `false`
+    Bool,
+    /// Any string value
+    String,
+}
+
+impl QueryParamType {
+    /// Returns whether the raw query value coerces to this type.
+    pub fn accepts(self, value: &str) -> bool {
+        match self {
+            QueryParamType::Int => value.parse::<i64>().is_ok(),
+            QueryParamType::Number => value.parse::<f64>().is_ok_and(f64::is_finite),
+            QueryParamType::Bool => value.parse::<bool>().is_ok(),
+            QueryParamType::String => true,
+        }
+    }
+}
+
+impl std::fmt::Display for QueryParamType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            QueryParamType::Int => "int",
+            QueryParamType::Number => "number",
+            QueryParamType::Bool => "bool",
+            QueryParamType::String => "string",
+        };
+        f.write_str(name)
+    }
+}
+
+/// Specification of a single query parameter accepted by a route.
+///
+/// The untagged representation accepts either a bare type name for optional
+/// parameters or an object that can also mark the parameter as required.
+///
+/// # Variants
+///
+/// * `Type` - An optional parameter given by its type alone, e.g. `"int"`
+/// * `Detailed` - A parameter with its type and whether it is required
+///
+/// # Examples
+///
+/// ```json
+/// {
+///   "page": "int",
+///   "active": {"type": "bool", "required": true}
+/// }
+/// ```
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum QueryParamSpec {
+    /// An optional parameter of the given type
+    Type(QueryParamType),
+    /// A parameter with an explicit required flag
+    Detailed {
+        /// The type the parameter value must coerce to
+        #[serde(rename = "type")]
+        param_type: QueryParamType,
+        /// Whether the parameter must be present
+        #[serde(default)]
+        required: bool,
+    },
+}
+
+impl QueryParamSpec {
+    /// Returns the type the parameter value must coerce to.
+    pub fn param_type(&self) -> QueryParamType {
+        match self {
+            QueryParamSpec::Type(param_type) | QueryParamSpec::Detailed { param_type, .. } => {
+                *param_type
+            }
+        }
+    }
+
+    /// Returns whether the parameter must be present in the query.
+    pub fn is_required(&self) -> bool {
+        matches!(self, QueryParamSpec::Detailed { required: true, .. })
+    }
+}
+
+/// A named caching behavior, written as a plain string.
+///
+/// # Variants
+///
+/// * `NoStore` - Responses must never be stored
+/// * `Immutable` - Responses never change and may be cached for a year
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum CacheDirective {
+    /// `no-store`
+    NoStore,
+    /// `immutable`
+    Immutable,
+}
+
+/// The wait a client is asked for before retrying, sent as `Retry-After`.
+///
+/// # Variants
+///
+/// * `Seconds` - A number of seconds, sent as is
+/// * `Date` - An absolute time, as an HTTP date such as `Wed, 14 Oct 2026 09:30:00 GMT`
+///   or an RFC 3339 UTC time such as `2026-10-14T09:30:00Z`, sent as an HTTP date
+///
+/// # Examples
+///
+/// ```rust
+/// use json_echo_core::RetryAfter;
+///
+/// let retry: RetryAfter = serde_json::from_str("120").unwrap();
+/// assert_eq!(retry, RetryAfter::Seconds(120));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum RetryAfter {
+    /// A number of seconds
+    Seconds(u64),
+    /// An absolute time, rendered as an HTTP date when the configuration loads
+    Date(String),
+}
+
+/// The IMF-fixdate form of RFC 9110 dates, e.g. `Wed, 14 Oct 2026 09:30:00 GMT`.
+const HTTP_DATE: &[time::format_description::BorrowedFormatItem<'static>] = time::macros::format_description!(
+    "[weekday repr:short], [day] [month repr:short] [year] [hour]:[minute]:[second] GMT"
+);
+
+impl RetryAfter {
+    /// Renders the wait as a `Retry-After` header value.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(String)` - The seconds, or the date as an HTTP date
+    /// * `Err(String)` - If the date is neither an HTTP date nor an RFC 3339 time
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use json_echo_core::RetryAfter;
+    ///
+    /// let retry = RetryAfter::Date("2026-10-14T11:30:00+02:00".to_string());
+    /// assert_eq!(retry.header_value().unwrap(), "Wed, 14 Oct 2026 09:30:00 GMT");
+    /// assert!(RetryAfter::Date("tomorrow".to_string()).header_value().is_err());
+    /// ```
+    pub fn header_value(&self) -> Result<String, String> {
+        let date = match self {
+            RetryAfter::Seconds(seconds) => return Ok(seconds.to_string()),
+            RetryAfter::Date(date) => date.trim(),
+        };
+
+        // The day name is not checked against the date, which it is rendered from
+        time::PrimitiveDateTime::parse(date, HTTP_DATE)
+            .map(time::PrimitiveDateTime::assume_utc)
+            .or_else(|_| {
+                time::OffsetDateTime::parse(date, &time::format_description::well_known::Rfc3339)
+            })
+            .ok()
+            .and_then(|time| time.to_offset(time::UtcOffset::UTC).format(HTTP_DATE).ok())
+            .ok_or_else(|| format!("{date:?} is neither an HTTP date nor an RFC 3339 time"))
+    }
+}
+
+/// A charset response bodies can be encoded in.
+///
+/// # Variants
+///
+/// * `Utf8` - UTF-8, the default encoding of every response
+/// * `Latin1` - ISO-8859-1, for legacy text clients
+///
+/// # Examples
+///
+/// ```rust
+/// use json_echo_core::Charset;
+///
+/// assert_eq!(Charset::parse("latin-1"), Some(Charset::Latin1));
+/// assert_eq!(Charset::Latin1.label(), "iso-8859-1");
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Charset {
+    /// UTF-8
+    Utf8,
+    /// ISO-8859-1, also known as latin-1
+    Latin1,
+}
+
+impl Charset {
+    /// Parses a charset name, case-insensitively.
+    ///
+    /// # Parameters
+    ///
+    /// * `name` - The configured or received name, e.g. `utf-8` or `latin-1`
+    ///
+    /// # Returns
+    ///
+    /// * `Some(Charset)` - If the name is a supported charset or one of its aliases
+    /// * `None` - If the charset is not supported
+    pub fn parse(name: &str) -> Option<Self> {
+        match name.trim().trim_matches('"').to_ascii_lowercase().as_str() {
+            "utf-8" | "utf8" => Some(Charset::Utf8),
+            "iso-8859-1" | "iso8859-1" | "latin-1" | "latin1" => Some(Charset::Latin1),
+            _ => None,
+        }
+    }
+
+    /// Reads the charset parameter of a content type, if it names a supported charset.
+    pub fn from_content_type(content_type: &str) -> Option<Self> {
+        content_type.split(';').skip(1).find_map(|parameter| {
+            let (name, value) = parameter.split_once('=')?;
+            if name.trim().eq_ignore_ascii_case("charset") {
+                Charset::parse(value)
+            } else {
+                None
+            }
+        })
+    }
+
+    /// Returns the canonical name used in the `Content-Type` header.
+    pub fn label(self) -> &'static str {
+        match self {
+            Charset::Utf8 => "utf-8",
+            Charset::Latin1 => "iso-8859-1",
+        }
+    }
+}
+
+/// A caching preset expanded into the `Cache-Control` header of a route's responses.
+///
+/// The untagged representation accepts a named directive or an object setting
+/// how long responses stay fresh.
+///
+/// # Variants
+///
+/// * `Directive` - `"no-store"` or `"immutable"`
+/// * `MaxAge` - `{"max_age": 60}`, responses fresh for that many seconds
+///
+/// # Examples
+///
+/// ```rust
+/// use json_echo_core::CachePreset;
+///
+/// let preset: CachePreset = serde_json::from_str(r#"{"max_age": 60}"#).unwrap();
+/// assert_eq!(preset.cache_control(), "max-age=60");
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum CachePreset {
+    /// A named directive
+    Directive(CacheDirective),
+    /// Responses fresh for the given number of seconds
+    MaxAge {
+        /// Seconds the response stays fresh
+        max_age: u64,
+    },
+}
+
+impl CachePreset {
+    /// Seconds an `immutable` response stays fresh: one year.
+    pub const IMMUTABLE_MAX_AGE: u64 = 31_536_000;
+
+    /// Returns the `Cache-Control` value the preset expands into.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use json_echo_core::{CacheDirective, CachePreset};
+    ///
+    /// assert_eq!(CachePreset::Directive(CacheDirective::NoStore).cache_control(), "no-store");
+    /// assert_eq!(
+    ///     CachePreset::Directive(CacheDirective::Immutable).cache_control(),
+    ///     "public, max-age=31536000, immutable"
+    /// );
+    /// ```
+    pub fn cache_control(self) -> String {
+        match self {
+            CachePreset::Directive(CacheDirective::NoStore) => "no-store".to_string(),
+            CachePreset::Directive(CacheDirective::Immutable) => {
+                format!("public, max-age={}, immutable", Self::IMMUTABLE_MAX_AGE)
+            }
+            CachePreset::MaxAge { max_age } => format!("max-age={max_age}"),
+        }
+    }
+
+    /// Returns how long, in seconds, responses are cached with a far-future
+    /// `Expires` header, for the presets sending one.
+    pub fn expires_in(self) -> Option<u64> {
+        matches!(self, CachePreset::Directive(CacheDirective::Immutable))
+            .then_some(Self::IMMUTABLE_MAX_AGE)
+    }
+}
+
+/// Delay applied to a route's response before it is sent, in milliseconds.
+///
+/// The untagged representation accepts either a single number applied to every
+/// response or an object keyed by the status the handler is about to return.
+/// Keys may be an exact status such as `"404"`, a status class such as `"5xx"`,
+/// or `"default"`; the most specific matching key wins.
+///
+/// # Variants
+///
+/// * `Fixed` - The same delay for every response, e.g. `200`
+/// * `ByStatus` - Delays keyed by exact status, status class or `default`
+///
+/// # Examples
+///
+/// ```json
+/// {"2xx": 200, "5xx": 20, "404": 0, "default": 50}
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum ResponseDelay {
+    /// A delay applied to every response
+    Fixed(u64),
+    /// Delays keyed by the status of the response
+    ByStatus(HashMap<String, u64>),
+}
+
+impl ResponseDelay {
+    /// Returns the delay to apply to a response with the given status.
+    ///
+    /// # Parameters
+    ///
+    /// * `status` - The HTTP status code the handler computed for the response
+    ///
+    /// # Returns
+    ///
+    /// * `Some(Duration)` - The delay of the most specific matching entry
+    /// * `None` - If no entry matches the status
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use json_echo_core::ResponseDelay;
+    /// use std::time::Duration;
+    ///
+    /// let delay: ResponseDelay = serde_json::from_str(r#"{"2xx": 200, "5xx": 20}"#).unwrap();
+    ///
+    /// assert_eq!(delay.for_status(201), Some(Duration::from_millis(200)));
+    /// assert_eq!(delay.for_status(503), Some(Duration::from_millis(20)));
+    /// assert_eq!(delay.for_status(404), None);
+    /// ```
+    pub fn for_status(&self, status: u16) -> Option<Duration> {
+        let millis = match self {
+            ResponseDelay::Fixed(millis) => Some(*millis),
+            ResponseDelay::ByStatus(delays) => delays
+                .get(&status.to_string())
+                .or_else(|| delays.get(&format!("{}xx", status / 100)))
+                .or_else(|| delays.get("default"))
+                .copied(),
+        };
+
+        millis.map(Duration::from_millis)
+    }
+}
+
+/// A distribution response latencies are sampled from, one sample per request.
+///
+/// All values are in milliseconds.
+///
+/// # Variants
+///
+/// * `LogNormal` - A log-normal distribution with the given mean and standard deviation
+/// * `Percentiles` - A distribution through the given p50, p90 and p99 latencies
+///
+/// # Examples
+///
+/// ```rust
+/// use json_echo_core::LatencyDistribution;
+///
+/// let latency: LatencyDistribution =
+///     serde_json::from_str(r#"{"distribution": "percentiles", "p50": 80, "p90": 200, "p99": 800}"#)
+///         .unwrap();
+/// assert!(matches!(latency, LatencyDistribution::Percentiles { .. }));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "distribution", rename_all = "snake_case")]
+pub enum LatencyDistribution {
+    /// A log-normal distribution with the given mean and standard deviation
+    LogNormal {
+        /// The mean latency, in milliseconds
+        mean: f64,
+        /// The standard deviation of the latency, in milliseconds
+        stddev: f64,
+    },
+    /// A distribution through the given percentile latencies
+    Percentiles {
+        /// The median latency, in milliseconds
+        p50: f64,
+        /// The 90th percentile latency, in milliseconds
+        p90: f64,
+        /// The 99th percentile latency, in milliseconds
+        p99: f64,
+    },
+}
+
+/// Latencies sampled per request from a distribution, instead of a fixed delay.
+///
+/// # Fields
+///
+/// * `distribution` - The distribution latencies are sampled from
+/// * `seed` - Optional seed making the sampled latencies repeat from one run to the next
+///
+/// # Examples
+///
+/// ```rust
+/// use json_echo_core::LatencyConfig;
+///
+/// let latency: LatencyConfig =
+///     serde_json::from_str(r#"{"distribution": "log_normal", "mean": 120, "stddev": 40, "seed": 7}"#)
+///         .unwrap();
+/// assert_eq!(latency.seed, Some(7));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct LatencyConfig {
+    /// The distribution latencies are sampled from
+    #[serde(flatten)]
+    pub distribution: LatencyDistribution,
+    /// Optional seed making the sampled latencies repeat from one run to the next
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub seed: Option<u64>,
+}
+
+impl LatencyConfig {
+    /// Samples a latency from the distribution.
+    ///
+    /// Log-normal latencies are drawn by `rand_distr`. Percentile
+    /// distributions draw a uniform quantile and interpolate linearly
+    /// between `0`, `p50`, `p90` and `p99`, see `percentile`.
+    ///
+    /// # Parameters
+    ///
+    /// * `rng` - The random number generator the sample is drawn with
+    ///
+    /// # Returns
+    ///
+    /// The sampled latency, never negative
+    ///
+    /// # Examples
+    ///
+    /// ```rust,ignore
+    /// let latency: LatencyConfig =
+    ///     serde_json::from_str(r#"{"distribution": "log_normal", "mean": 120, "stddev": 40}"#)?;
+    /// let delay = latency.sample(&mut StdRng::seed_from_u64(7));
+    /// ```
+    pub fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> Duration {
+        let millis = match self.distribution {
+            LatencyDistribution::LogNormal { mean, stddev } => {
+                // The parameters of the underlying normal distribution
+                let sigma = (1.0 + (stddev / mean).powi(2)).ln().sqrt();
+                let mu = mean.ln() - sigma * sigma / 2.0;
+                // Validated configurations always have a finite, non-negative sigma
+                LogNormal::new(mu, sigma).map_or(mean, |distribution| distribution.sample(rng))
+            }
+            LatencyDistribution::Percentiles { .. } => {
+                return self.percentile(StandardUniform.sample(rng));
+            }
+        };
+
+        Duration::from_secs_f64(millis.max(0.0) / 1000.0)
+    }
+
+    /// Returns the latency at a quantile of a percentile distribution.
+    ///
+    /// The distribution passes through `0`, `p50`, `p90` and `p99`, linearly
+    /// between them, the last percent continuing the slope from `p90` to `p99`.
+    /// Log-normal distributions have no such points and answer their mean.
+    ///
+    /// # Parameters
+    ///
+    /// * `roll` - The quantile to read, in `[0, 1)`
+    ///
+    /// # Returns
+    ///
+    /// The latency at the quantile, never negative
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use json_echo_core::LatencyConfig;
+    /// use std::time::Duration;
+    ///
+    /// let latency: LatencyConfig =
+    ///     serde_json::from_str(r#"{"distribution": "percentiles", "p50": 80, "p90": 200, "p99": 800}"#)
+    ///         .unwrap();
+    ///
+    /// assert_eq!(latency.percentile(0.5), Duration::from_millis(80));
+    /// assert_eq!(latency.percentile(0.9), Duration::from_millis(200));
+    /// ```
+    pub fn percentile(&self, roll: f64) -> Duration {
+        let millis = match self.distribution {
+            LatencyDistribution::LogNormal { mean, .. } => mean,
+            LatencyDistribution::Percentiles { p50, p90, p99 } => {
+                let points = [(0.0, 0.0), (0.5, p50), (0.9, p90), (0.99, p99)];
+                let tail = (1.0, p99 + (p99 - p90) / 9.0);
+                let ((q0, v0), (q1, v1)) = points
+                    .windows(2)
+                    .map(|pair| (pair[0], pair[1]))
+                    .find(|(_, (q1, _))| roll <= *q1)
+                    .unwrap_or((points[3], tail));
+                v0 + (v1 - v0) * (roll - q0) / (q1 - q0)
+            }
+        };
+
+        Duration::from_secs_f64(millis.max(0.0) / 1000.0)
+    }
+
+    /// Returns why the distribution cannot be sampled, if it cannot.
+    fn invalid_reason(&self) -> Option<&'static str> {
+        match self.distribution {
+            LatencyDistribution::LogNormal { mean, stddev } => {
+                (!(mean.is_finite() && stddev.is_finite() && mean > 0.0 && stddev >= 0.0))
+                    .then_some("needs a positive mean and a non-negative stddev")
+            }
+            LatencyDistribution::Percentiles { p50, p90, p99 } => {
+                (!([p50, p90, p99].iter().all(|value| value.is_finite())
+                    && 0.0 <= p50
+                    && p50 <= p90
+                    && p90 <= p99))
+                    .then_some("needs non-negative percentiles with p50 <= p90 <= p99")
+            }
+        }
+    }
+}
+
+/// An HTTP redirect answered by a route instead of a response body.
+///
+/// # Fields
+///
+/// * `to` - The URL or path sent in the `Location` header
+/// * `status` - The redirect status code, a 3xx value (defaults to 302)
+///
+/// # Examples
+///
+/// ```rust
+/// use json_echo_core::RedirectConfig;
+///
+/// let redirect: RedirectConfig = serde_json::from_str(r#"{"to": "/users"}"#).unwrap();
+/// assert_eq!(redirect.status, 302);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RedirectConfig {
+    /// The URL or path sent in the `Location` header
+    pub to: String,
+    /// The redirect status code
+    #[serde(default = "default_redirect_status")]
+    pub status: u16,
+}
+
+/// Returns the default status code of redirects, `302 Found`.
+fn default_redirect_status() -> u16 {
+    302
+}
+
+/// A SQLite query answered by a route instead of a response body.
+///
+/// The query runs against the file on every request and its rows are served
+/// as a JSON array of objects keyed by column name. Named parameters such as
+/// `:id` are bound from the path parameters of the request, then from its
+/// query parameters.
+///
+/// # Fields
+///
+/// * `file` - The SQLite database file, relative to the configuration root
+/// * `query` - The SQL statement to run, e.g. `SELECT * FROM users WHERE id = :id`
+///
+/// # Examples
+///
+/// ```rust
+/// use json_echo_core::SqlConfig;
+///
+/// let sql: SqlConfig =
+///     serde_json::from_str(r#"{"file": "fixtures.db", "query": "SELECT * FROM users"}"#).unwrap();
+/// assert_eq!(sql.file, "fixtures.db");
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SqlConfig {
+    /// The SQLite database file, relative to the configuration root
+    pub file: String,
+    /// The SQL statement to run
+    pub query: String,
+}
+
+/// An upstream a route forwards its requests to instead of mocking them.
+///
+/// The request is sent on with its method, headers, query and body, and the
+/// upstream's status, headers and body are answered as they are, so a few
+/// endpoints can reach a real sandbox while the rest stay mocked.
+///
+/// # Fields
+///
+/// * `target` - The base `http://` or `https://` URL of the upstream, e.g. `http://localhost:9000`
+/// * `path_rewrite` - Optional upstream path, `{name}` filled from the route's path
+///   parameters; the request path is forwarded as is without it
+///
+/// # Examples
+///
+/// ```rust
+/// use json_echo_core::ProxyConfig;
+///
+/// let proxy: ProxyConfig = serde_json::from_str(
+///     r#"{"target": "http://localhost:9000", "path_rewrite": "/v1/{path}"}"#,
+/// )
+/// .unwrap();
+/// assert_eq!(proxy.path_rewrite.as_deref(), Some("/v1/{path}"));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProxyConfig {
+    /// The base `http://` or `https://` URL of the upstream
+    pub target: String,
+    /// Optional upstream path, `{name}` filled from the route's path parameters
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub path_rewrite: Option<String>,
+}
+
+/// A long-running job started by a write route.
+///
+/// The write route answers `202 Accepted` with a `Location` pointing at the
+/// job's status route, whose `sequence` is served in turn for every poll of
+/// that job, so each job progresses from its first state to its last on its own.
+///
+/// # Fields
+///
+/// * `location` - The status route, a path with a single parameter receiving the job id
+///
+/// # Examples
+///
+/// ```rust
+/// use json_echo_core::JobConfig;
+///
+/// let job: JobConfig = serde_json::from_str(r#"{"location": "/jobs/{id}"}"#).unwrap();
+/// assert_eq!(job.location_for("42"), "/jobs/42");
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct JobConfig {
+    /// The status route, e.g. `/jobs/{id}`, whose parameter receives the job id
+    pub location: String,
+}
+
+impl JobConfig {
+    /// Returns the status URL of a job.
+    ///
+    /// # Parameters
+    ///
+    /// * `id` - The generated job id
+    ///
+    /// # Returns
+    ///
+    /// The `location` with its `{parameter}` segment replaced by the id, or the
+    /// `location` followed by the id when it has no parameter
+    pub fn location_for(&self, id: &str) -> String {
+        let template = &self.location;
+        match (template.find('{'), template.find('}')) {
+            (Some(open), Some(close)) if open < close => {
+                format!("{}{}{}", &template[..open], id, &template[close + 1..])
+            }
+            _ => format!("{}/{}", template.trim_end_matches('/'), id),
+        }
+    }
+}
+
+/// An error answered by a route instead of a response body.
+///
+/// Error routes always render the same envelope, so clients can be tested
+/// against a uniform error shape whatever the status:
+///
+/// ```json
+/// {"error": {"status": 503, "code": "MAINTENANCE", "message": "Back soon"}}
+/// ```
+///
+/// # Fields
+///
+/// * `status` - The error status code, a 4xx or 5xx value (defaults to 500)
+/// * `code` - Optional machine-readable code (defaults to the status reason, e.g. `SERVICE_UNAVAILABLE`)
+/// * `message` - Optional human-readable message (defaults to the status reason)
+/// * `details` - Optional extra data included in the envelope as is
+///
+/// # Examples
+///
+/// ```rust
+/// use json_echo_core::ErrorConfig;
+///
+/// let error: ErrorConfig = serde_json::from_str(r#"{"code": "BOOM"}"#).unwrap();
+/// assert_eq!(error.status, 500);
+/// ```
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ErrorConfig {
+    /// The error status code
+    #[serde(default = "default_error_status")]
+    pub status: u16,
+    /// Optional machine-readable error code
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub code: Option<String>,
+    /// Optional human-readable error message
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+    /// Optional extra data included in the envelope as is
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub details: Option<Value>,
+}
+
+/// Returns the default status code of error routes, `500 Internal Server Error`.
+fn default_error_status() -> u16 {
+    500
 }
 
-/// Returns the default port number for the server.
+/// The certificate and private key a server serves HTTPS with.
 ///
-/// Provides a default port value of 3001 for server configuration.
-/// This function is used by serde as the default value provider when
-/// the port field is missing from the configuration JSON.
+/// Both files are PEM encoded and resolved against the configuration root.
+/// The certificate file may hold the whole chain, leaf first.
 ///
-/// # Returns
+/// # Fields
 ///
-/// `Some(3001)` - The default port number wrapped in an Option
-#[allow(clippy::unnecessary_wraps)]
-fn default_port() -> Option<u16> {
-    Some(3001)
+/// * `cert_file` - The certificate chain, e.g. `certs/localhost.pem`
+/// * `key_file` - The private key of the certificate, in PKCS#8, PKCS#1 or SEC1 form
+///
+/// # Examples
+///
+/// ```rust
+/// use json_echo_core::TlsConfig;
+///
+/// let tls: TlsConfig =
+///     serde_json::from_str(r#"{"cert_file": "cert.pem", "key_file": "key.pem"}"#).unwrap();
+/// assert_eq!(tls.cert_file, "cert.pem");
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TlsConfig {
+    /// The PEM certificate chain, leaf first
+    pub cert_file: String,
+    /// The PEM private key of the certificate
+    pub key_file: String,
 }
 
-/// Returns the default static route path for serving static files.
+/// A response answered to unmatched paths under a prefix.
 ///
-/// Provides a default static route value of "/static" for serving static files.
-/// This function is used by serde as the default value provider when
-/// the static_route field is missing from the configuration JSON.
+/// For an API mounted at `/api`, a fallback answers unknown `/api/*` paths
+/// with a JSON error while other unmatched paths keep the default 404.
 ///
-/// # Returns
+/// # Fields
 ///
-/// `"/static"` - The default static route path as a String
+/// * `prefix` - The path prefix, matched on whole segments, e.g. `/api`
+/// * `response` - The response answered (defaults to a `404` with `{"error": "Not found"}`)
 ///
 /// # Examples
 ///
 /// ```rust
-/// use json_echo_core::Config;
+/// use json_echo_core::FallbackConfig;
 ///
-/// let config = Config::default();
-/// assert_eq!(config.static_route, "/static");
+/// let fallback: FallbackConfig = serde_json::from_str(r#"{"prefix": "/api"}"#).unwrap();
+/// assert!(fallback.matches("/api/missing"));
+/// assert!(!fallback.matches("/apis"));
+/// assert_eq!(fallback.response.status, 404);
 /// ```
-#[allow(clippy::unnecessary_wraps)]
-fn default_static_route() -> String {
-    String::from("/static")
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FallbackConfig {
+    /// The path prefix, matched on whole segments
+    pub prefix: String,
+    /// The response answered to unmatched paths under the prefix
+    #[serde(default)]
+    pub response: FallbackResponse,
 }
 
-/// Returns the default hostname for the server.
+impl FallbackConfig {
+    /// Returns whether a request path falls under the prefix.
+    ///
+    /// The prefix matches the path itself and the paths below it, so `/api`
+    /// matches `/api` and `/api/users` but not `/apis`.
+    pub fn matches(&self, path: &str) -> bool {
+        let prefix = self.prefix.trim_end_matches('/');
+        path.strip_prefix(prefix)
+            .is_some_and(|rest| rest.is_empty() || rest.starts_with('/'))
+    }
+}
+
+/// The response of a prefix fallback.
 ///
-/// Provides a default hostname value of "localhost" for server configuration.
-/// This function is used by serde as the default value provider when
-/// the hostname field is missing from the configuration JSON.
+/// # Fields
 ///
-/// # Returns
+/// * `status` - The status code (defaults to 404)
+/// * `body` - The JSON body (defaults to `{"error": "Not found"}`)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FallbackResponse {
+    /// The status code
+    #[serde(default = "default_fallback_status")]
+    pub status: u16,
+    /// The JSON body
+    #[serde(default = "default_fallback_body")]
+    pub body: Value,
+}
+
+impl Default for FallbackResponse {
+    fn default() -> Self {
+        Self {
+            status: default_fallback_status(),
+            body: default_fallback_body(),
+        }
+    }
+}
+
+/// Returns the default status code of fallbacks, `404 Not Found`.
+fn default_fallback_status() -> u16 {
+    404
+}
+
+/// Returns the default body of fallbacks.
+fn default_fallback_body() -> Value {
+    json!({"error": "Not found"})
+}
+
+/// Placeholder replaced by the response body inside an envelope template.
+const ENVELOPE_BODY: &str = "{{body}}";
+
+/// A wrapper applied around response bodies.
 ///
-/// `Some("localhost")` - The default hostname string wrapped in an Option
-#[allow(clippy::unnecessary_wraps)]
-fn default_host() -> Option<String> {
-    Some(String::from("localhost"))
+/// The template is any JSON value; every string equal to `{{body}}` inside it
+/// is replaced by the response body, so conventions like a `data` and `meta`
+/// wrapper are declared once instead of in every route.
+///
+/// # Fields
+///
+/// * `template` - The wrapper, holding `"{{body}}"` where the body goes
+/// * `include_errors` - Whether 4xx and 5xx responses are wrapped as well (defaults to `false`)
+///
+/// # Examples
+///
+/// ```rust
+/// use json_echo_core::EnvelopeSpec;
+/// use serde_json::json;
+///
+/// let envelope: EnvelopeSpec = serde_json::from_value(json!({
+///     "template": {"data": "{{body}}", "meta": {"version": 1}}
+/// })).unwrap();
+///
+/// assert_eq!(
+///     envelope.wrap(&json!([1, 2])),
+///     json!({"data": [1, 2], "meta": {"version": 1}})
+/// );
+/// ```
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EnvelopeSpec {
+    /// The wrapper, holding `"{{body}}"` where the body goes
+    pub template: Value,
+    /// Whether 4xx and 5xx responses are wrapped as well
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub include_errors: bool,
 }
 
-impl Default for Config {
-    /// Creates a new Config instance with default values.
+impl EnvelopeSpec {
+    /// Returns whether responses with the given status are wrapped.
     ///
-    /// Provides a default configuration suitable for development environments
-    /// with localhost hostname, port 3001, and no predefined routes.
+    /// # Parameters
+    ///
+    /// * `status` - The status code of the response
     ///
     /// # Returns
     ///
-    /// A new `Config` instance with default server settings
+    /// `true` for every status below 400, and for errors when `include_errors` is set
+    pub fn applies_to(&self, status: u16) -> bool {
+        status < 400 || self.include_errors
+    }
+
+    /// Wraps a body in the envelope template.
     ///
-    /// # Examples
+    /// # Parameters
     ///
-    /// ```rust
-    /// use json_echo_core::Config;
+    /// * `body` - The response body
     ///
-    /// let config = Config::default();
-    /// assert_eq!(config.port, Some(3001));
-    /// assert_eq!(config.hostname, Some("localhost".to_string()));
-    /// assert!(config.static_folder.is_none());
-    /// assert_eq!(config.static_route, "/static");
-    /// assert!(config.routes.is_empty());
-    /// ```
-    fn default() -> Self {
-        Self {
-            port: default_port(),
-            hostname: default_host(),
-            static_folder: None,
-            static_route: default_static_route(),
-            routes: HashMap::new(),
+    /// # Returns
+    ///
+    /// The template with every `"{{body}}"` string replaced by the body
+    pub fn wrap(&self, body: &Value) -> Value {
+        fn fill(template: &Value, body: &Value) -> Value {
+            match template {
+                Value::String(text) if text == ENVELOPE_BODY => body.clone(),
+                Value::Array(items) => items.iter().map(|item| fill(item, body)).collect(),
+                Value::Object(map) => Value::Object(
+                    map.iter()
+                        .map(|(key, value)| (key.clone(), fill(value, body)))
+                        .collect(),
+                ),
+                other => other.clone(),
+            }
         }
+
+        fill(&self.template, body)
     }
 }
 
-/// Configuration for an individual route including HTTP method, headers, and response.
+/// Parses the content of an external response file.
 ///
-/// The `ConfigRoute` struct defines how a specific API endpoint should behave,
-/// including the HTTP method it responds to, any custom headers, and the response
-/// data. It supports flexible response definitions through the ConfigResponse enum.
+/// Files with a `.json5` extension are parsed as JSON5, which allows comments,
+/// unquoted keys and trailing commas in hand-authored bodies. Every other file
+/// is parsed as strict JSON.
 ///
-/// # Fields
+/// The content is parsed as a value first, so a file holding nothing, or
+/// holding a bare body instead of a `{"status", "body"}` object, is reported
+/// as such rather than with the first field serde failed to read. A bare
+/// object body such as `{"id": 1}` is caught by its fields other than
+/// `status` and `body`, instead of loading as an empty `200`.
 ///
-/// * `method` - Optional HTTP method (defaults to "GET")
-/// * `description` - Optional human-readable description of the route
-/// * `headers` - Optional custom HTTP headers to include in responses
-/// * `id_field` - Optional field name to use as unique identifier (defaults to "id")
-/// * `results_field` - Optional field name containing results when data is nested
-/// * `response` - The response configuration for this route
+/// # Parameters
+///
+/// * `path` - The path of the file, used to pick the format and report errors
+/// * `content` - The raw content of the file
+///
+/// # Returns
+///
+/// * `Ok(ConfigRouteResponse)` - The parsed response configuration
+/// * `Err(FileSystemError::ResponseFile)` - If the file is empty or does not describe a response
+/// * `Err(FileSystemError)` - If the content is not valid in the file's format
+fn parse_route_response(path: &Path, content: &[u8]) -> FileSystemResult<ConfigRouteResponse> {
+    let invalid = |reason: String| FileSystemError::ResponseFile {
+        path: path.to_path_buf(),
+        reason,
+    };
+
+    if content.iter().all(u8::is_ascii_whitespace) {
+        return Err(invalid("the file is empty".to_string()));
+    }
+
+    let is_json5 = path
+        .extension()
+        .is_some_and(|extension| extension.eq_ignore_ascii_case("json5"));
+
+    let value: Value = if is_json5 {
+        let content =
+            std::str::from_utf8(content).map_err(|error| FileSystemError::Utf8Decode {
+                path: path.to_path_buf(),
+                message: error.to_string(),
+            })?;
+        json5::from_str(content).map_err(|error| invalid(format!("not valid JSON5: {error}")))?
+    } else {
+        serde_json::from_slice(content)
+            .map_err(|error| FileSystemError::config_parse(path, &error))?
+    };
+
+    let Some(object) = value.as_object() else {
+        return Err(invalid(format!(
+            "expected an object with `status` and `body`, found a JSON {}",
+            json_type(&value)
+        )));
+    };
+
+    if let Some(key) = object
+        .keys()
+        .find(|key| !["status", "body"].contains(&key.as_str()))
+    {
+        return Err(invalid(format!(
+            "unexpected field `{key}`; expected an object with `status` and `body`, \
+             a bare body goes under `body`"
+        )));
+    }
+
+    serde_json::from_value(value)
+        .map_err(|error| invalid(format!("not a valid route response: {error}")))
+}
+
+/// The methods a route file name may end with, e.g. `users.post.json`.
+const ROUTE_FILE_METHODS: [&str; 7] = ["get", "post", "put", "patch", "delete", "head", "options"];
+
+/// Parses a file of the `routes_dir` into the key and definition of its route.
+///
+/// The file holds a single route, as written in `routes`, in JSON or, with a
+/// `.json5` extension, JSON5. Its key comes from an optional `path` field,
+/// written like a `routes` key, or from the file's location in the directory:
+/// `users/{id}.json` serves `/users/{id}`. A method before the extension, as
+/// in `users.post.json`, is used unless `path` names one.
+///
+/// # Parameters
+///
+/// * `dir` - The resolved `routes_dir`
+/// * `path` - The resolved path of the file, inside `dir`
+/// * `content` - The raw content of the file
+///
+/// # Returns
+///
+/// * `Ok(Some((String, ConfigRoute)))` - The route key, not yet normalized, and the route
+/// * `Ok(None)` - If the file holds no JSON object, such as a data array, and so no route
+/// * `Err(FileSystemError::RouteFile)` - If the object does not describe a route
+/// * `Err(FileSystemError)` - If the content is not valid in the file's format
+fn parse_route_file(
+    dir: &Path,
+    path: &Path,
+    content: &[u8],
+) -> FileSystemResult<Option<(String, ConfigRoute)>> {
+    let invalid = |reason: String| FileSystemError::RouteFile {
+        path: path.to_path_buf(),
+        reason,
+    };
+
+    let is_json5 = path
+        .extension()
+        .is_some_and(|extension| extension.eq_ignore_ascii_case("json5"));
+
+    let mut value: Value = if is_json5 {
+        let content =
+            std::str::from_utf8(content).map_err(|error| FileSystemError::Utf8Decode {
+                path: path.to_path_buf(),
+                message: error.to_string(),
+            })?;
+        json5::from_str(content).map_err(|error| invalid(format!("not valid JSON5: {error}")))?
+    } else {
+        serde_json::from_slice(content)
+            .map_err(|error| FileSystemError::config_parse(path, &error))?
+    };
+
+    let Some(fields) = value.as_object_mut() else {
+        return Ok(None);
+    };
+    let route_path = match fields.shift_remove("path") {
+        Some(Value::String(route_path)) => Some(route_path),
+        Some(other) => {
+            return Err(invalid(format!(
+                "`path` must be a string, found a JSON {}",
+                json_type(&other)
+            )));
+        }
+        None => None,
+    };
+
+    let relative = path.strip_prefix(dir).unwrap_or(path).with_extension("");
+    let relative = relative.to_string_lossy().replace('\\', "/");
+    let (location, method) = match relative.rsplit_once('.') {
+        Some((location, method))
+            if ROUTE_FILE_METHODS.contains(&method.to_lowercase().as_str()) =>
+        {
+            (location.to_string(), Some(method.to_uppercase()))
+        }
+        _ => (relative, None),
+    };
+
+    let route_path = route_path.unwrap_or_else(|| format!("/{location}"));
+    let key = match method {
+        Some(method) if !route_path.trim_start().starts_with('[') => {
+            format!("[{method}] {}", route_path.trim())
+        }
+        _ => route_path,
+    };
+
+    let route = serde_json::from_value(value)
+        .map_err(|error| invalid(format!("not a valid route: {error}")))?;
+
+    Ok(Some((key, route)))
+}
+
+/// Loads the response of a route whose external file is read on first request.
+///
+/// Lazy routes keep the file reference of their `response`, resolved against
+/// the filesystem root when the configuration loaded. The file is resolved
+/// and read again through the filesystem manager, so it is still refused if
+/// it has since been swapped for a symlink or moved out of the root, and
+/// parsed like eagerly loaded references.
+///
+/// # Parameters
+///
+/// * `files` - The filesystem manager the configuration was loaded with
+/// * `path` - The resolved path of the referenced file
+///
+/// # Returns
+///
+/// * `Ok(ConfigRouteResponse)` - The parsed response configuration
+/// * `Err(FileSystemError::Validation)` - If the file now lies outside the root
+/// * `Err(FileSystemError)` - If the file cannot be read or parsed
+pub async fn load_route_response(
+    files: &FileSystemManager,
+    path: &Path,
+) -> FileSystemResult<ConfigRouteResponse> {
+    let resolved = files.resolve_within_root(&path.to_string_lossy()).await?;
+    let content = files.load_file(&resolved.to_string_lossy()).await?;
+
+    parse_route_response(path, &content)
+}
+
+/// A segment of a route path, as the router reads it.
+///
+/// # Variants
+///
+/// * `Static` - A segment matched as written
+/// * `Param` - A `{name}` segment, matching any single non-empty segment
+/// * `Wildcard` - A `{*name}` segment, matching the rest of the path
 ///
 /// # Examples
 ///
 /// ```rust
-/// use json_echo_core::{ConfigRoute, ConfigResponse, ConfigRouteResponse, BodyResponse};
-/// use serde_json::Value;
-/// use std::collections::HashMap;
+/// use json_echo_core::{PathSegment, path_segments};
 ///
-/// let mut headers = HashMap::new();
-/// headers.insert("Content-Type".to_string(), "application/json".to_string());
+/// let segments: Vec<PathSegment> = path_segments("/files/{id}/{*rest}").collect();
+/// assert_eq!(
+///     segments,
+///     [
+///         PathSegment::Static(""),
+///         PathSegment::Static("files"),
+///         PathSegment::Param("id"),
+///         PathSegment::Wildcard("rest"),
+///     ]
+/// );
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PathSegment<'a> {
+    /// A segment matched as written
+    Static(&'a str),
+    /// A `{name}` segment, holding its name
+    Param(&'a str),
+    /// A `{*name}` segment, holding its name without the `*`
+    Wildcard(&'a str),
+}
+
+/// Splits a route path into its segments, the leading empty one included.
 ///
-/// let route = ConfigRoute {
-///     method: Some("GET".to_string()),
-///     description: Some("Get user list".to_string()),
-///     headers: Some(headers),
-///     id_field: Some("user_id".to_string()),
-///     results_field: Some("data".to_string()),
-///     response: ConfigResponse::ConfigRouteResponse(ConfigRouteResponse {
-///         status: Some(200),
-///         body: BodyResponse::Value(Value::Null),
-///     }),
-/// };
+/// # Parameters
+///
+/// * `path` - The route path, e.g. `/users/{id}`
+///
+/// # Returns
+///
+/// The segments between the slashes of the path, in order
+pub fn path_segments(path: &str) -> impl Iterator<Item = PathSegment<'_>> {
+    path.split('/').map(|segment| {
+        match segment
+            .strip_prefix('{')
+            .and_then(|segment| segment.strip_suffix('}'))
+        {
+            Some(name) => name
+                .strip_prefix('*')
+                .map_or(PathSegment::Param(name), PathSegment::Wildcard),
+            None => PathSegment::Static(segment),
+        }
+    })
+}
+
+/// Returns the shape of a route path, its parameter names left out.
+///
+/// The router cannot tell apart two paths of the same shape, such as
+/// `/users/{id}` and `/users/{userId}`, so they must be written alike.
+///
+/// # Parameters
+///
+/// * `path` - The route path, e.g. `/users/{id}`
+///
+/// # Returns
+///
+/// The path with every `{name}` written `{}` and every `{*name}` written `{*}`
+///
+/// # Examples
+///
+/// ```rust
+/// use json_echo_core::route_shape;
+///
+/// assert_eq!(route_shape("/users/{id}/posts"), route_shape("/users/{userId}/posts"));
+/// assert_eq!(route_shape("/files/{*path}"), "/files/{*}");
 /// ```
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct ConfigRoute {
-    /// The HTTP method for the route (e.g., GET, POST)
-    #[serde(default = "default_method")]
-    pub method: Option<String>,
-    /// Optional human-readable description of the route
-    #[serde(default)]
-    pub description: Option<String>,
-    /// Optional custom HTTP headers to include in responses
-    #[serde(default)]
-    pub headers: Option<HashMap<String, String>>,
-    /// The field in the response body to use as the unique identifier, if applicable
-    #[serde(default = "default_id_field")]
-    pub id_field: Option<String>,
-    /// The field in the response body that contains the results array, if applicable
-    #[serde(default)]
-    pub results_field: Option<String>,
-    /// The response configuration for this route
-    pub response: ConfigResponse,
+pub fn route_shape(path: &str) -> String {
+    path_segments(path)
+        .map(|segment| match segment {
+            PathSegment::Static(segment) => segment,
+            PathSegment::Param(_) => "{}",
+            PathSegment::Wildcard(_) => "{*}",
+        })
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+/// Resolves `{"$ref": "#/definitions/name"}` objects inside a JSON value.
+///
+/// # Parameters
+///
+/// * `value` - The value to resolve, searched recursively
+/// * `definitions` - The named definitions references may point at
+/// * `stack` - The definitions currently being resolved, used to detect cycles
+///
+/// # Returns
+///
+/// * `Ok(Value)` - The value with every reference replaced
+/// * `Err(String)` - If a reference is malformed, unknown or circular
+fn resolve_refs(
+    value: &Value,
+    definitions: &IndexMap<String, Value>,
+    stack: &mut Vec<String>,
+) -> Result<Value, String> {
+    match value {
+        Value::Object(map) => {
+            if let (1, Some(reference)) = (map.len(), map.get("$ref")) {
+                let name = reference
+                    .as_str()
+                    .and_then(|reference| reference.strip_prefix("#/definitions/"))
+                    .ok_or_else(|| format!("Unsupported reference {reference}"))?;
+
+                if stack.iter().any(|visited| visited == name) {
+                    return Err(format!(
+                        "Circular reference: {} -> {name}",
+                        stack.join(" -> ")
+                    ));
+                }
+
+                let definition = definitions
+                    .get(name)
+                    .ok_or_else(|| format!("Unknown definition {name}"))?;
+
+                stack.push(name.to_string());
+                let resolved = resolve_refs(definition, definitions, stack)?;
+                stack.pop();
+
+                return Ok(resolved);
+            }
+
+            map.iter()
+                .map(|(key, value)| Ok((key.clone(), resolve_refs(value, definitions, stack)?)))
+                .collect::<Result<Map<String, Value>, String>>()
+                .map(Value::Object)
+        }
+        Value::Array(items) => items
+            .iter()
+            .map(|item| resolve_refs(item, definitions, stack))
+            .collect::<Result<Vec<_>, _>>()
+            .map(Value::Array),
+        other => Ok(other.clone()),
+    }
+}
+
+/// Returns the JSON Schema type name of a value.
+fn json_type(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "boolean",
+        Value::Number(number) if number.is_i64() || number.is_u64() => "integer",
+        Value::Number(_) => "number",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    }
+}
+
+/// Writes a JSON Schema instance location as a path from the root `$`.
+///
+/// # Examples
+///
+/// ```rust,ignore
+/// // The location of the `email` of the first item, `/0/email` as a JSON pointer
+/// assert_eq!(json_path(error.instance_path()), "$[0].email");
+/// ```
+fn json_path(location: &Location) -> String {
+    location
+        .segments()
+        .map(|segment| match segment {
+            LocationSegment::Property(name) => format!(".{name}"),
+            LocationSegment::Index(index) => format!("[{index}]"),
+        })
+        .fold(String::from("$"), |path, segment| path + &segment)
 }
 
 /// Returns the default HTTP method for routes.
@@ -358,14 +2746,99 @@ impl Default for ConfigRoute {
             description: None,
             results_field: None,
             headers: None,
-            response: ConfigResponse::ConfigRouteResponse(ConfigRouteResponse {
-                status: default_status(),
-                body: default_body(),
-            }),
+            content_type: None,
+            cors: None,
+            query_params: None,
+            strict_query: false,
+            delay: None,
+            latency: None,
+            alias: None,
+            redirect: None,
+            sequence: None,
+            sequence_mode: None,
+            variants: None,
+            responses: None,
+            localized: None,
+            echo: false,
+            echo_key: None,
+            request_unwrap: None,
+            response_wrap: None,
+            fields_spec: None,
+            charset: None,
+            relations: None,
+            error: None,
+            tags: None,
+            enabled: None,
+            stream: false,
+            grow: false,
+            wrap_results: false,
+            stream_interval_ms: None,
+            job: None,
+            save_uploads_to: None,
+            response_schema: None,
+            disable_keep_alive: false,
+            id_strategy: None,
+            cache: None,
+            retry_after: None,
+            lazy: false,
+            sql: None,
+            proxy: None,
+            log_level: None,
+            response: default_response(),
         }
     }
 }
 
+/// Cross-origin resource sharing settings for the server or a single route.
+///
+/// When set on `Config`, these settings replace the permissive defaults for
+/// every route. When set on a `ConfigRoute`, they override the global settings
+/// for that route's path only. Omitted fields fall back to the permissive
+/// defaults (any origin, any header, the common HTTP methods).
+///
+/// # Fields
+///
+/// * `allowed_origins` - Origins allowed to access the resource (`"*"` for any)
+/// * `allowed_methods` - HTTP methods advertised to preflight requests
+/// * `allowed_headers` - Request headers allowed in cross-origin requests (`"*"` for any)
+/// * `allow_credentials` - Whether credentials (cookies, auth headers) are allowed
+/// * `max_age_secs` - Optional number of seconds browsers may cache preflight responses
+///
+/// Browsers reject wildcard origins and headers combined with credentials, so
+/// when `allow_credentials` is enabled the wildcards mirror the request instead.
+///
+/// # Examples
+///
+/// ```rust
+/// use json_echo_core::CorsConfig;
+///
+/// let cors: CorsConfig = serde_json::from_str(r#"
+/// {
+///     "allowed_origins": ["https://app.example.com"],
+///     "allow_credentials": true
+/// }
+/// "#).unwrap();
+/// assert!(cors.allow_credentials);
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct CorsConfig {
+    /// Origins allowed to access the resource, `"*"` meaning any origin
+    #[serde(default)]
+    pub allowed_origins: Option<Vec<String>>,
+    /// HTTP methods advertised to preflight requests
+    #[serde(default)]
+    pub allowed_methods: Option<Vec<String>>,
+    /// Request headers allowed in cross-origin requests, `"*"` meaning any header
+    #[serde(default)]
+    pub allowed_headers: Option<Vec<String>>,
+    /// Whether credentials are allowed in cross-origin requests
+    #[serde(default)]
+    pub allow_credentials: bool,
+    /// Optional number of seconds browsers may cache preflight responses, sent as `Access-Control-Max-Age`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_age_secs: Option<u64>,
+}
+
 /// Represents different types of response body content for route configurations.
 ///
 /// This enum allows route responses to contain different types of body content:
@@ -582,6 +3055,28 @@ fn default_status() -> Option<u16> {
     Some(200)
 }
 
+/// The path prefix the server keeps for its own admin routes, such as
+/// `/__admin/requests` and `/__admin/maintenance`.
+const ADMIN_PATH_PREFIX: &str = "/__admin";
+
+/// The status codes a response can be sent with: any three-digit code.
+const STATUS_RANGE: std::ops::RangeInclusive<u16> = 100..=999;
+
+/// Returns the default response of route configurations.
+///
+/// Aliases and redirects do not serve their own data, so they may omit the
+/// response, which then defaults to a `200` status with an empty JSON object.
+///
+/// # Returns
+///
+/// A `ConfigResponse` with the default status and body
+fn default_response() -> ConfigResponse {
+    ConfigResponse::ConfigRouteResponse(ConfigRouteResponse {
+        status: default_status(),
+        body: default_body(),
+    })
+}
+
 /// Returns the default response body for route configurations.
 ///
 /// Provides a default empty JSON object as the response body when no specific
@@ -728,18 +3223,110 @@ impl ConfigManager {
             .load_file(relative_file_path)
             .await?;
 
-        let config =
-            serde_json::from_slice::<Config>(&file_content).map_err(FileSystemError::from)?;
+        let path = self.get_root().join(relative_file_path);
+        self.load_config_from_source(&file_content, &path).await
+    }
+
+    /// Loads and processes a configuration from raw JSON bytes.
+    ///
+    /// This method behaves like `load_config` but skips the filesystem lookup of
+    /// the configuration file itself, which allows configurations to be supplied
+    /// from sources such as standard input. External file references in route
+    /// responses are still resolved relative to the filesystem root.
+    ///
+    /// # Parameters
+    ///
+    /// * `content` - The raw JSON content of the configuration
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - If the configuration was parsed and processed successfully
+    /// * `Err(FileSystemError)` - If parsing, validation, or external file loading fails
+    ///
+    /// # Errors
+    ///
+    /// This method can fail if:
+    /// - The JSON content is malformed or invalid, reported as a `ConfigParse`
+    ///   error for the `<stdin>` path
+    /// - The configuration contains no routes
+    /// - Referenced external files cannot be loaded
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use json_echo_core::{ConfigManager, FileSystemManager};
+    ///
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// let fs_manager = FileSystemManager::new(None)?;
+    /// let mut config_manager = ConfigManager::new(fs_manager);
+    ///
+    /// let content = br#"{"routes": {"/health": {"response": {"status": 200, "body": "ok"}}}}"#;
+    /// config_manager.load_config_from_bytes(content).await?;
+    /// assert!(config_manager.config.routes.contains_key("[GET] /health"));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn load_config_from_bytes(&mut self, content: &[u8]) -> FileSystemResult<()> {
+        self.load_config_from_source(content, Path::new("<stdin>"))
+            .await
+    }
+
+    /// Parses, normalizes and validates configuration content read from `path`.
+    ///
+    /// # Parameters
+    ///
+    /// * `content` - The raw JSON content of the configuration
+    /// * `path` - Where the content was read from, reported in parse errors
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - If the configuration was parsed and processed successfully
+    /// * `Err(FileSystemError)` - A `ConfigParse` error locating malformed JSON,
+    ///   or any validation or external file loading failure
+    async fn load_config_from_source(
+        &mut self,
+        content: &[u8],
+        path: &Path,
+    ) -> FileSystemResult<()> {
+        let config = serde_json::from_slice::<Config>(content)
+            .map_err(|error| FileSystemError::config_parse(path, &error))?;
         self.config = ConfigManager::setup_config(config);
 
+        self.load_route_files().await?;
+
         if self.config.routes.is_empty() {
             return Err(FileSystemError::Operation(
                 "Configuration routes are empty or invalid".into(),
             ));
         }
 
+        if self.config.max_concurrency == Some(0) {
+            return Err(FileSystemError::Operation(
+                "max_concurrency must allow at least one request".into(),
+            ));
+        }
+
+        if let Some(charset) = &self.config.charset
+            && Charset::parse(charset).is_none()
+        {
+            return Err(FileSystemError::Operation(format!(
+                "Unsupported charset {charset:?}; use utf-8 or iso-8859-1"
+            )));
+        }
+
+        ConfigManager::validate_routes(&self.config.routes)?;
+        ConfigManager::render_retry_after(&mut self.config)?;
+
         self.populate_config().await?;
 
+        self.apply_seed().await?;
+
+        ConfigManager::resolve_definitions(&mut self.config)?;
+
+        ConfigManager::validate_statuses(&self.config)?;
+
+        ConfigManager::validate_response_schemas(&self.config.routes)?;
+
         Ok(())
     }
 
@@ -752,26 +3339,60 @@ impl ConfigManager {
     /// # Returns
     ///
     /// * `Ok(())` - If all external files were processed successfully
+    /// * `Err(FileSystemError::Validation)` - If a reference escapes the filesystem root
     /// * `Err(FileSystemError)` - If any external file cannot be loaded or parsed
     ///
     /// # Behavior
     ///
     /// - Only processes routes with `ConfigResponse::String` responses
-    /// - Loads external files relative to the filesystem root
+    /// - Loads external files relative to the filesystem root, rejecting any
+    ///   reference that resolves outside of it
+    /// - Parses files with a `.json5` extension as JSON5 and any other file as JSON
     /// - Replaces string references with parsed `ConfigRouteResponse` objects
+    /// - Keeps the references of `lazy` routes, replaced by their resolved path,
+    ///   so the file is only read by `load_route_response` on first request
+    /// - Replaces the `file` of `sql` routes by its resolved path, rejecting
+    ///   databases outside the root like any other reference
     /// - Validates that referenced routes still exist after processing
     async fn populate_config(&mut self) -> FileSystemResult<()> {
         let routes = self.config.routes.clone();
 
         for (path, route) in routes {
+            if let Some(sql) = &route.sql {
+                let resolved = self
+                    .file_system_manager
+                    .resolve_within_root(&sql.file)
+                    .await?;
+                if let Some(sql) = self
+                    .config
+                    .routes
+                    .get_mut(&path)
+                    .and_then(|route| route.sql.as_mut())
+                {
+                    sql.file = resolved.to_string_lossy().into_owned();
+                }
+            }
+
             if let ConfigResponse::String(route_file) = route.response {
+                let resolved = self
+                    .file_system_manager
+                    .resolve_within_root(&route_file)
+                    .await?;
+
+                if route.lazy {
+                    if let Some(lazy) = self.config.routes.get_mut(&path) {
+                        lazy.response =
+                            ConfigResponse::String(resolved.to_string_lossy().into_owned());
+                    }
+                    continue;
+                }
+
                 let route_file = self.get_root().join(route_file);
                 let route_content = self
                     .file_system_manager
-                    .load_file(route_file.to_string_lossy().as_ref())
+                    .load_file(resolved.to_string_lossy().as_ref())
                     .await?;
-                let route_config: ConfigRouteResponse =
-                    serde_json::from_slice(&route_content).map_err(FileSystemError::from)?;
+                let route_config = parse_route_response(&route_file, &route_content)?;
                 self.config
                     .routes
                     .iter_mut()
@@ -785,6 +3406,602 @@ impl ConfigManager {
         Ok(())
     }
 
+    /// Adds the routes of the files in the configured `routes_dir`.
+    ///
+    /// Every `.json` and `.json5` file below the directory, at any depth,
+    /// holds one route keyed as described by `parse_route_file`. Files are
+    /// read in path order and their routes normalized like those of `routes`,
+    /// so `users.json` and `users.get.json` both define `[GET] /users`.
+    ///
+    /// Data files may sit next to the route files: a file referenced as a
+    /// route's `response` or as the `seed_file` is skipped, and so is a file
+    /// holding anything but a JSON object, which cannot be a route.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - If there is no routes directory or all its routes were added
+    /// * `Err(FileSystemError::RouteFile)` - If a file does not describe a route,
+    ///   or defines a route already defined by the configuration or another file
+    /// * `Err(FileSystemError)` - If the directory escapes the root or a file cannot be read
+    async fn load_route_files(&mut self) -> FileSystemResult<()> {
+        let Some(routes_dir) = self.config.routes_dir.clone() else {
+            return Ok(());
+        };
+
+        let dir = self
+            .file_system_manager
+            .resolve_within_root(&routes_dir)
+            .await?;
+        let files = self.file_system_manager.list_files(&routes_dir).await?;
+
+        let mut parsed = Vec::new();
+        for file in files.iter().filter(|file| {
+            file.extension().is_some_and(|extension| {
+                extension.eq_ignore_ascii_case("json") || extension.eq_ignore_ascii_case("json5")
+            })
+        }) {
+            let content = self
+                .file_system_manager
+                .load_file(file.to_string_lossy().as_ref())
+                .await?;
+            parsed.push((file, parse_route_file(&dir, file, &content)));
+        }
+
+        // References are resolved like `populate_config` will, failures left to it
+        let references = self
+            .config
+            .routes
+            .values()
+            .chain(parsed.iter().filter_map(|(_, parsed)| {
+                let (_, route) = parsed.as_ref().ok()?.as_ref()?;
+                Some(route)
+            }))
+            .filter_map(|route| match &route.response {
+                ConfigResponse::String(file) => Some(file.clone()),
+                _ => None,
+            })
+            .chain(self.config.seed_file.clone());
+        let mut data_files = HashSet::new();
+        for reference in references {
+            if let Ok(resolved) = self
+                .file_system_manager
+                .resolve_within_root(&reference)
+                .await
+            {
+                data_files.insert(resolved);
+            }
+        }
+
+        for (file, parsed) in parsed {
+            if data_files.contains(file) {
+                continue;
+            }
+            let Some((key, route)) = parsed? else {
+                log::warn!("Skipping {}, which holds no route object", file.display());
+                continue;
+            };
+
+            let routes = ConfigManager::setup_config(Config {
+                routes: IndexMap::from([(key, route)]),
+                ..Config::default()
+            })
+            .routes;
+            for (key, route) in routes {
+                if self.config.routes.contains_key(&key) {
+                    return Err(FileSystemError::RouteFile {
+                        path: file.clone(),
+                        reason: format!("route {key} is already defined"),
+                    });
+                }
+                self.config.routes.insert(key, route);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Replaces route bodies with the data of the configured `seed_file`.
+    ///
+    /// The seed file holds a JSON object mapping route identifiers, written as
+    /// `[METHOD] /path` or a plain `/path` (GET is assumed), to the body each
+    /// route starts with. Identifiers without a matching route are ignored.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - If there is no seed file or it was applied
+    /// * `Err(FileSystemError::Validation)` - If the seed file escapes the filesystem root
+    /// * `Err(FileSystemError)` - If the seed file cannot be loaded or is not a JSON object
+    async fn apply_seed(&mut self) -> FileSystemResult<()> {
+        let Some(seed_file) = self.config.seed_file.clone() else {
+            return Ok(());
+        };
+
+        let resolved = self
+            .file_system_manager
+            .resolve_within_root(&seed_file)
+            .await?;
+        let content = self
+            .file_system_manager
+            .load_file(resolved.to_string_lossy().as_ref())
+            .await?;
+        let seed =
+            serde_json::from_slice::<IndexMap<String, Value>>(&content).map_err(|error| {
+                FileSystemError::config_parse(self.get_root().join(&seed_file), &error)
+            })?;
+
+        for (identifier, body) in seed {
+            let key = ConfigManager::alias_key(identifier.trim(), "GET");
+
+            match self
+                .config
+                .routes
+                .get_mut(&key)
+                .map(|route| &mut route.response)
+            {
+                Some(ConfigResponse::ConfigRouteResponse(response)) => {
+                    response.body = BodyResponse::Value(body);
+                }
+                _ => log::debug!("Seed entry {identifier} matches no route, ignoring it"),
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Renders every configured `retry_after` date as an HTTP date.
+    ///
+    /// The dates of routes and of `maintenance_retry_after` are parsed once,
+    /// here, so the server sends them as they are and an unparsable date
+    /// fails the load instead of being dropped.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - If every date is an HTTP date or an RFC 3339 time
+    /// * `Err(FileSystemError)` - Naming the route, or `maintenance_retry_after`, with an invalid date
+    fn render_retry_after(config: &mut Config) -> FileSystemResult<()> {
+        let render = |retry: &mut RetryAfter, owner: &str| -> FileSystemResult<()> {
+            let rendered = retry.header_value().map_err(|error| {
+                FileSystemError::Operation(format!("{owner} has an invalid retry_after: {error}"))
+            })?;
+            if let RetryAfter::Date(date) = retry {
+                *date = rendered;
+            }
+            Ok(())
+        };
+
+        for (key, route) in &mut config.routes {
+            if let Some(retry) = &mut route.retry_after {
+                render(retry, &format!("Route {key}"))?;
+            }
+        }
+        if let Some(retry) = &mut config.maintenance_retry_after {
+            render(retry, "maintenance_retry_after")?;
+        }
+
+        Ok(())
+    }
+
+    /// Checks that no two paths differ only in their parameter names, and that
+    /// none is under the reserved `/__admin` prefix.
+    ///
+    /// The router registers `/users/{id}` and `/users/{userId}` as the same
+    /// path and cannot hold both, whatever their methods. Admin paths would
+    /// shadow, or be shadowed by, the server's own admin routes.
+    ///
+    /// # Parameters
+    ///
+    /// * `keys` - The sorted `[METHOD] path` identifiers of the routes
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - If equal shapes always use the same parameter names and no path is reserved
+    /// * `Err(FileSystemError)` - Naming the first two conflicting routes
+    fn validate_route_shapes(keys: &[&String]) -> FileSystemResult<()> {
+        let mut shapes: HashMap<String, (&String, &str)> = HashMap::new();
+        for key in keys {
+            let path = key.split_once("] ").map_or(key.as_str(), |(_, path)| path);
+            if path
+                .strip_prefix(ADMIN_PATH_PREFIX)
+                .is_some_and(|rest| rest.is_empty() || rest.starts_with('/'))
+            {
+                return Err(FileSystemError::Operation(format!(
+                    "Route {key} is under {ADMIN_PATH_PREFIX}/, which is reserved for the server's admin routes"
+                )));
+            }
+            let (other, other_path) = *shapes.entry(route_shape(path)).or_insert((key, path));
+            if other_path != path {
+                return Err(FileSystemError::Operation(format!(
+                    "Routes {other} and {key} register the same path with different parameter names; name them alike"
+                )));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Checks the values a route serves its responses with.
+    ///
+    /// A `content_type` must be a valid header value, so it is never dropped
+    /// in favour of `application/json` when the route is served, and a
+    /// `charset` must be one responses can be encoded in. Localized
+    /// tags are matched ignoring case, so no two of them may differ only in
+    /// case, which would leave the body served for them unspecified.
+    ///
+    /// # Parameters
+    ///
+    /// * `key` - The normalized `[METHOD] path` identifier of the route
+    /// * `route` - The route to check
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - If every served value is valid
+    /// * `Err(FileSystemError)` - Naming the route and its invalid value
+    fn validate_route_content(key: &str, route: &ConfigRoute) -> FileSystemResult<()> {
+        if let Some(content_type) = &route.content_type
+            && http::HeaderValue::from_str(content_type).is_err()
+        {
+            return Err(FileSystemError::Operation(format!(
+                "Route {key} has invalid content_type {content_type:?}"
+            )));
+        }
+
+        if let Some(charset) = &route.charset
+            && Charset::parse(charset).is_none()
+        {
+            return Err(FileSystemError::Operation(format!(
+                "Route {key} has unsupported charset {charset:?}; use utf-8 or iso-8859-1"
+            )));
+        }
+
+        let mut tags: Vec<&String> = route
+            .localized
+            .iter()
+            .flatten()
+            .map(|(tag, _)| tag)
+            .collect();
+        tags.sort_by_key(|tag| (tag.to_ascii_lowercase(), *tag));
+        if let Some([tag, other]) = tags
+            .windows(2)
+            .find(|pair| pair[0].eq_ignore_ascii_case(pair[1]))
+        {
+            return Err(FileSystemError::Operation(format!(
+                "Route {key} has localized tags {tag} and {other} differing only in case"
+            )));
+        }
+
+        Ok(())
+    }
+
+    /// Checks that the routes a route points at exist.
+    ///
+    /// A job must report its status on a GET route whose single parameter
+    /// receives the job id, and relations must target existing routes.
+    ///
+    /// # Parameters
+    ///
+    /// * `key` - The normalized `[METHOD] path` identifier of the route
+    /// * `route` - The route to check
+    /// * `routes` - Every route, keyed by its normalized identifier
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - If every target exists
+    /// * `Err(FileSystemError)` - Naming the route and its missing target
+    fn validate_route_targets(
+        key: &str,
+        route: &ConfigRoute,
+        routes: &IndexMap<String, ConfigRoute>,
+    ) -> FileSystemResult<()> {
+        if let Some(job) = &route.job
+            && (job.location.matches('{').count() != 1
+                || !routes.contains_key(&format!("[GET] {}", job.location)))
+        {
+            return Err(FileSystemError::Operation(format!(
+                "Route {key} job location {} is not a GET route with one parameter for the job id",
+                job.location
+            )));
+        }
+
+        let mut relations: Vec<_> = route.relations.iter().flatten().collect();
+        relations.sort_by_key(|(name, _)| name.as_str());
+        if let Some((name, relation)) = relations
+            .into_iter()
+            .find(|(_, relation)| !routes.contains_key(&relation.route))
+        {
+            return Err(FileSystemError::Operation(format!(
+                "Route {key} relation {name} targets unknown route {}",
+                relation.route
+            )));
+        }
+
+        Ok(())
+    }
+
+    /// Checks the alias, redirect, error and relation settings of normalized routes.
+    ///
+    /// Every alias must point at an existing route and following aliases must
+    /// never lead back to a route already visited. Redirects must use a 3xx
+    /// status code, errors a 4xx or 5xx one, proxies an `http://` or `https://` target,
+    /// and `request_unwrap` and `response_wrap` must be JSON pointers. Jobs and
+    /// relations must point at existing routes, see `validate_route_targets`.
+    /// Paths differing only in their parameter names are rejected as well, as
+    /// are paths under the `/__admin` prefix the server keeps for itself, see
+    /// `validate_route_shapes`, and invalid served values, see
+    /// `validate_route_content`.
+    ///
+    /// # Parameters
+    ///
+    /// * `routes` - The routes, keyed by their normalized `[METHOD] path` identifier
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - If every alias, redirect, error and relation is valid
+    /// * `Err(FileSystemError)` - Naming the first route with an invalid setting
+    fn validate_routes(routes: &IndexMap<String, ConfigRoute>) -> FileSystemResult<()> {
+        let mut keys: Vec<&String> = routes.keys().collect();
+        keys.sort();
+        Self::validate_route_shapes(&keys)?;
+
+        for key in keys {
+            let route = &routes[key];
+            Self::validate_route_content(key, route)?;
+            Self::validate_route_targets(key, route, routes)?;
+
+            if let Some(redirect) = &route.redirect
+                && !(300..400).contains(&redirect.status)
+            {
+                return Err(FileSystemError::Operation(format!(
+                    "Route {key} redirects with non-3xx status {}",
+                    redirect.status
+                )));
+            }
+
+            if let Some(error) = &route.error
+                && !(400..600).contains(&error.status)
+            {
+                return Err(FileSystemError::Operation(format!(
+                    "Route {key} errors with non-error status {}",
+                    error.status
+                )));
+            }
+
+            if let Some(proxy) = &route.proxy
+                && !["http://", "https://"]
+                    .iter()
+                    .any(|scheme| proxy.target.starts_with(scheme))
+            {
+                return Err(FileSystemError::Operation(format!(
+                    "Route {key} proxies to {}; only http:// and https:// targets are supported",
+                    proxy.target
+                )));
+            }
+
+            if let Some(pointer) = [&route.request_unwrap, &route.response_wrap]
+                .into_iter()
+                .flatten()
+                .find(|pointer| !pointer.is_empty() && !pointer.starts_with('/'))
+            {
+                return Err(FileSystemError::Operation(format!(
+                    "Route {key} has invalid JSON pointer {pointer:?}; pointers start with /"
+                )));
+            }
+
+            if let Some(reason) = route
+                .latency
+                .as_ref()
+                .and_then(LatencyConfig::invalid_reason)
+            {
+                return Err(FileSystemError::Operation(format!(
+                    "Route {key} latency {reason}"
+                )));
+            }
+
+            if let Some(pool) = &route.responses {
+                if let Some(weighted) = pool
+                    .iter()
+                    .find(|weighted| !weighted.weight.is_finite() || weighted.weight < 0.0)
+                {
+                    return Err(FileSystemError::Operation(format!(
+                        "Route {key} has invalid response weight {}; weights must be non-negative",
+                        weighted.weight
+                    )));
+                }
+                if pool.iter().all(|weighted| weighted.weight == 0.0) {
+                    return Err(FileSystemError::Operation(format!(
+                        "Route {key} responses need at least one non-zero weight"
+                    )));
+                }
+            }
+
+            let mut visited = vec![key.as_str()];
+            let mut next = route.alias.as_deref();
+
+            while let Some(target) = next {
+                if visited.contains(&target) {
+                    return Err(FileSystemError::Operation(format!(
+                        "Circular alias: {} -> {target}",
+                        visited.join(" -> ")
+                    )));
+                }
+
+                let Some(target_route) = routes.get(target) else {
+                    return Err(FileSystemError::Operation(format!(
+                        "Route {key} aliases unknown route {target}"
+                    )));
+                };
+
+                visited.push(target);
+                next = target_route.alias.as_deref();
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Replaces `{"$ref": "#/definitions/name"}` objects in response bodies.
+    ///
+    /// References may appear anywhere inside a body and definitions may
+    /// reference other definitions; each reference is replaced by a copy of
+    /// the fully resolved definition.
+    ///
+    /// # Parameters
+    ///
+    /// * `config` - The configuration whose route bodies are resolved in place
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - If every reference was resolved
+    /// * `Err(FileSystemError)` - Naming the route and reference that is unknown,
+    ///   malformed or part of a cycle
+    fn resolve_definitions(config: &mut Config) -> FileSystemResult<()> {
+        let definitions = config.definitions.clone().unwrap_or_default();
+
+        for (key, route) in &mut config.routes {
+            if let ConfigResponse::ConfigRouteResponse(response) = &mut route.response
+                && let BodyResponse::Value(body) = &mut response.body
+            {
+                *body = resolve_refs(body, &definitions, &mut Vec::new()).map_err(|error| {
+                    FileSystemError::Operation(format!("Route {key} body: {error}"))
+                })?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Checks that every configured response status can be sent over HTTP.
+    ///
+    /// Any three-digit code is accepted, so unusual ones such as `299` or `420`
+    /// reach the client unchanged; anything outside `100..=999` fails the load
+    /// instead of being silently answered as `200`. Route responses, sequence
+    /// entries, body variants, weighted responses, `internal_error` and
+    /// `fallbacks` are all checked.
+    ///
+    /// # Parameters
+    ///
+    /// * `config` - The configuration, once external response files are loaded
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - If every status is a three-digit code
+    /// * `Err(FileSystemError)` - Naming the first route, `internal_error` or
+    ///   fallback with an invalid status
+    fn validate_statuses(config: &Config) -> FileSystemResult<()> {
+        let invalid = |owner: &str, status: u16| {
+            FileSystemError::Operation(format!(
+                "{owner} answers with invalid status {status}; statuses must be between 100 and 999"
+            ))
+        };
+
+        if let Some(status) = config
+            .internal_error
+            .as_ref()
+            .and_then(|error| error.status)
+            && !STATUS_RANGE.contains(&status)
+        {
+            return Err(invalid("internal_error", status));
+        }
+
+        if let Some(fallback) = config
+            .fallbacks
+            .iter()
+            .flatten()
+            .find(|fallback| !STATUS_RANGE.contains(&fallback.response.status))
+        {
+            return Err(invalid(
+                &format!("Fallback {}", fallback.prefix),
+                fallback.response.status,
+            ));
+        }
+
+        for (key, route) in &config.routes {
+            let response = match &route.response {
+                ConfigResponse::ConfigRouteResponse(response) => Some(response),
+                ConfigResponse::String(_) | ConfigResponse::Str(_) => None,
+            };
+            let sequence = route.sequence.iter().flatten();
+            let variants = route
+                .variants
+                .iter()
+                .flatten()
+                .map(|variant| &variant.response);
+            let pool = route
+                .responses
+                .iter()
+                .flatten()
+                .map(|weighted| &weighted.response);
+
+            if let Some(status) = response
+                .into_iter()
+                .chain(sequence)
+                .chain(variants)
+                .chain(pool)
+                .filter_map(|response| response.status)
+                .find(|status| !STATUS_RANGE.contains(status))
+            {
+                return Err(invalid(&format!("Route {key}"), status));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Checks every configured body against its route's `response_schema`.
+    ///
+    /// Schemas are compiled with the `jsonschema` crate, every keyword of the
+    /// draft they declare enforced, the latest one when they declare none.
+    /// Bodies are checked once external files, the seed file and definitions
+    /// are applied, so the schema describes exactly what the route serves.
+    ///
+    /// # Parameters
+    ///
+    /// * `routes` - The routes, keyed by their normalized `[METHOD] path` identifier
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - If every schema is valid and every body satisfies its schema
+    /// * `Err(FileSystemError)` - Naming the first route whose schema is invalid, or
+    ///   whose body violates it along with the location of the violation
+    fn validate_response_schemas(routes: &IndexMap<String, ConfigRoute>) -> FileSystemResult<()> {
+        for (key, route) in routes {
+            let Some(schema) = &route.response_schema else {
+                continue;
+            };
+            let validator = jsonschema::validator_for(schema).map_err(|error| {
+                FileSystemError::Operation(format!(
+                    "Route {key} has an invalid response_schema: {error}"
+                ))
+            })?;
+
+            if let ConfigResponse::ConfigRouteResponse(response) = &route.response {
+                let body = response.body.as_value();
+                if let Err(error) = validator.validate(&body) {
+                    return Err(FileSystemError::Operation(format!(
+                        "Route {key} body does not match its response_schema: {}: {error}",
+                        json_path(error.instance_path())
+                    )));
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Normalizes an alias target into a `[METHOD] path` route identifier.
+    ///
+    /// Targets given as a plain path resolve against the aliasing route's own
+    /// method, so a GET alias of `/users` points at `[GET] /users`.
+    fn alias_key(alias: &str, method: &str) -> String {
+        match alias.find(']') {
+            Some(end_idx) if alias.starts_with('[') => format!(
+                "[{}] {}",
+                alias[1..end_idx].trim().to_uppercase(),
+                alias[end_idx + 1..].trim()
+            ),
+            _ => format!("[{method}] {alias}"),
+        }
+    }
+
     /// Normalizes and processes route configurations to ensure consistent key formatting.
     ///
     /// This method processes route configurations to standardize route identifiers by
@@ -846,6 +4063,7 @@ impl ConfigManager {
     ///     static_folder: None,
     ///     static_route: "/static".to_string(),
     ///     routes,
+    ///     ..Config::default()
     /// };
     ///
     /// let normalized = ConfigManager::setup_config(config);
@@ -864,7 +4082,7 @@ impl ConfigManager {
     /// - Providing reliable route lookup and matching
     /// - Maintaining backward compatibility with different configuration styles
     fn setup_config(config: Config) -> Config {
-        let mut new_routes: HashMap<String, ConfigRoute> = HashMap::new();
+        let mut new_routes: IndexMap<String, ConfigRoute> = IndexMap::new();
         for (key, mut route) in config.routes {
             let (method, path) = if key.starts_with('[') {
                 if let Some(end_idx) = key.find(']') {
@@ -885,16 +4103,23 @@ impl ConfigManager {
                 route.method = method.or_else(|| Some("GET".to_string()));
             }
 
-            let route_key = format!("[{}] {}", route.method.as_deref().unwrap_or("GET"), path);
+            let method = route.method.as_deref().unwrap_or("GET");
+            route.alias = route
+                .alias
+                .map(|alias| ConfigManager::alias_key(alias.trim(), method));
+
+            // Related collections are always read through their GET route
+            for relation in route.relations.iter_mut().flat_map(HashMap::values_mut) {
+                relation.route = ConfigManager::alias_key(relation.route.trim(), "GET");
+            }
+
+            let route_key = format!("[{method}] {path}");
             new_routes.insert(route_key, route);
         }
 
         Config {
-            port: config.port,
-            hostname: config.hostname,
-            static_folder: config.static_folder,
-            static_route: config.static_route,
             routes: new_routes,
+            ..config
         }
     }
 
@@ -904,6 +4129,10 @@ impl ConfigManager {
     /// the specified file path. This method can be used to persist configuration
     /// changes or create new configuration files.
     ///
+    /// The file is pretty-printed with two-space indentation and routes, like
+    /// the keys of every body, keep the order they were loaded or inserted in,
+    /// so saving a loaded configuration does not reshuffle it.
+    ///
     /// # Parameters
     ///
     /// * `relative_file_path` - Path where the configuration should be saved, relative to filesystem root
@@ -934,7 +4163,8 @@ impl ConfigManager {
         relative_file_path: &str,
         config: &Config,
     ) -> FileSystemResult<()> {
-        let file_content = serde_json::to_vec(config).map_err(FileSystemError::from)?;
+        let mut file_content = serde_json::to_vec_pretty(config).map_err(FileSystemError::from)?;
+        file_content.push(b'\n');
         self.file_system_manager
             .save_file(relative_file_path, file_content)
             .await
@@ -980,10 +4210,9 @@ impl ConfigManager {
     ///
     /// # Behavior
     ///
-    /// Checks for files in this order:
-    /// 1. `db.json`
-    /// 2. `.db.json`
-    /// 3. `json-echo.json`
+    /// Checks the names returned by `PathUtils::config_file_names` in order,
+    /// which are `db.json`, `.db.json` and `json-echo.json` unless
+    /// `JSON_ECHO_CONFIG_NAMES` adds more.
     ///
     /// # Examples
     ///
@@ -1002,8 +4231,7 @@ impl ConfigManager {
     /// # }
     /// ```
     pub fn get_config_file_path(&self) -> Option<PathBuf> {
-        let mock_files = ["db.json", ".db.json", "json-echo.json"];
-        for mock_file in &mock_files {
+        for mock_file in &PathUtils::config_file_names() {
             let path = self.file_system_manager.root.join(mock_file);
             if path.exists() {
                 return Some(path);
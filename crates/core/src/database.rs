@@ -45,11 +45,16 @@
 //! }
 //! ```
 
-use std::collections::HashMap;
+use std::{
+    borrow::Cow,
+    collections::HashMap,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
 
 use serde_json::{Map, Value, json};
 
-use crate::{ConfigRoute, ConfigRouteResponse, config::BodyResponse};
+use crate::{ConfigRoute, ConfigRouteResponse, IdStrategy, config::BodyResponse};
 
 /// An in-memory database that manages route configurations and their associated models.
 ///
@@ -57,10 +62,14 @@ use crate::{ConfigRoute, ConfigRouteResponse, config::BodyResponse};
 /// and provides methods to query and retrieve data. It maintains both the original
 /// route configurations and processed models for efficient data access.
 ///
+/// Cloning a database is cheap: routes and model data are shared behind `Arc`s,
+/// which allows callers to build a modified copy and swap it in atomically.
+///
 /// # Fields
 ///
 /// * `routes` - A HashMap containing route configurations indexed by their identifier
 /// * `models` - A vector of processed models derived from the route configurations
+/// * `model_index` - A HashMap mapping model identifiers to their position in `models`
 ///
 /// # Examples
 ///
@@ -78,10 +87,15 @@ use crate::{ConfigRoute, ConfigRouteResponse, config::BodyResponse};
 /// ```
 #[derive(Debug, Clone)]
 pub struct Database {
-    /// HashMap storing route configurations indexed by their string identifier
-    pub(crate) routes: HashMap<String, ConfigRoute>,
+    /// HashMap storing route configurations indexed by their string identifier.
+    ///
+    /// Routes never change after `populate`, so they are shared between clones
+    /// of the database instead of being copied.
+    pub(crate) routes: Arc<HashMap<String, ConfigRoute>>,
     /// Vector containing all processed models derived from route configurations
     pub(crate) models: Vec<Model>,
+    /// Position of each model in `models` keyed by identifier, for constant-time lookups
+    pub(crate) model_index: HashMap<String, usize>,
 }
 
 /// A model representing a processed route configuration with metadata for data access.
@@ -96,7 +110,9 @@ pub struct Database {
 /// * `id_field` - The field name used as the primary identifier in the data
 /// * `results_field` - Optional field name that contains the actual results data
 /// * `description` - Optional human-readable description of the model
-/// * `data` - The actual response data configuration
+/// * `id_strategy` - Optional way ids are generated for entries added without one
+/// * `pending` - The file of a lazy route, until its response is loaded on first request
+/// * `data` - The actual response data configuration, shared copy-on-write
 ///
 /// # Examples
 ///
@@ -128,8 +144,17 @@ pub struct Model {
     pub(crate) results_field: Option<String>,
     /// Optional human-readable description explaining the purpose of this model
     pub(crate) description: Option<String>,
-    /// The configuration response data associated with this model
-    pub(crate) data: ConfigRouteResponse,
+    /// Optional way ids are generated for entries added to the collection without one
+    pub(crate) id_strategy: Option<IdStrategy>,
+    /// The resolved file of a lazy route whose response has not been loaded yet
+    pub(crate) pending: Option<PathBuf>,
+    /// The configuration response data associated with this model.
+    ///
+    /// Shared behind an `Arc` so that cloning a model, for example to release
+    /// the database lock before serializing a response, only clones a pointer.
+    /// Mutations go through `Arc::make_mut` and copy the data only while an
+    /// older snapshot is still alive.
+    pub(crate) data: Arc<ConfigRouteResponse>,
 }
 
 impl Database {
@@ -154,8 +179,9 @@ impl Database {
     /// ```
     pub fn new() -> Self {
         Database {
-            routes: HashMap::new(),
+            routes: Arc::new(HashMap::new()),
             models: Vec::new(),
+            model_index: HashMap::new(),
         }
     }
 
@@ -167,13 +193,17 @@ impl Database {
     ///
     /// # Parameters
     ///
-    /// * `routes` - A HashMap where keys are route identifiers and values are `ConfigRoute` instances
+    /// * `routes` - Pairs of route identifiers and `ConfigRoute` instances, such as a
+    ///   `HashMap` or the `IndexMap` of a loaded configuration
     ///
     /// # Behavior
     ///
     /// - Replaces any existing routes and models
+    /// - Skips routes whose `enabled` flag is `false`, so they are never registered
+    /// - Appends new models in identifier order and indexes them for lookups
     /// - Generates models with default ID field "id" if not specified
     /// - Extracts response data or provides empty object as fallback
+    /// - Marks the models of `lazy` routes as pending on their referenced file
     /// - Preserves route descriptions and other metadata
     ///
     /// # Examples
@@ -192,24 +222,45 @@ impl Database {
     /// assert!(!db.get_routes().is_empty());
     /// ```
     #[allow(clippy::map_unwrap_or)]
-    pub fn populate(&mut self, routes: HashMap<String, ConfigRoute>) {
-        self.routes = routes;
+    pub fn populate(&mut self, routes: impl IntoIterator<Item = (String, ConfigRoute)>) {
+        self.routes = Arc::new(
+            routes
+                .into_iter()
+                .filter(|(_, route)| route.is_enabled())
+                .collect(),
+        );
+
+        let mut routes: Vec<(&String, &ConfigRoute)> = self.routes.iter().collect();
+        routes.sort_by_key(|(key, _)| *key);
 
-        for (key, route) in &self.routes {
+        for (key, route) in routes {
             let model = Model {
                 identifier: key.clone(),
                 id_field: route.id_field.clone().unwrap_or_else(|| String::from("id")),
                 description: route.description.clone(),
                 results_field: route.results_field.clone(),
-                data: match &route.response {
+                id_strategy: route.id_strategy,
+                pending: match &route.response {
+                    crate::ConfigResponse::String(file) | crate::ConfigResponse::Str(file)
+                        if route.lazy =>
+                    {
+                        Some(PathBuf::from(file))
+                    }
+                    _ => None,
+                },
+                data: Arc::new(match &route.response {
                     crate::ConfigResponse::ConfigRouteResponse(response) => response.clone(),
                     _ => ConfigRouteResponse {
                         status: Some(200),
                         body: BodyResponse::Value(Value::Object(Map::new())),
                     },
-                },
+                }),
             };
 
+            // Keep the first model registered for an identifier, as a linear scan would
+            self.model_index
+                .entry(key.clone())
+                .or_insert(self.models.len());
             self.models.push(model);
         }
     }
@@ -397,9 +448,9 @@ impl Database {
 
     /// Retrieves a specific model by its identifier.
     ///
-    /// Searches through the models vector to find a model with the specified
-    /// identifier. This method performs a linear search and returns the first
-    /// matching model.
+    /// Looks up the model with the specified identifier through the model index,
+    /// so the cost of a lookup does not grow with the number of routes. When
+    /// several models share an identifier, the first one registered is returned.
     ///
     /// # Parameters
     ///
@@ -424,9 +475,149 @@ impl Database {
     /// }
     /// ```
     pub fn get_model(&self, identifier: &str) -> Option<&Model> {
-        self.models
-            .iter()
-            .find(|model| model.identifier == identifier)
+        self.model_index
+            .get(identifier)
+            .and_then(|position| self.models.get(*position))
+    }
+
+    /// Resolves a route identifier through its `alias` settings.
+    ///
+    /// Follows the chain of aliases starting at `identifier` and returns the
+    /// identifier of the route that actually holds the data. Routes without an
+    /// alias resolve to themselves. Circular aliases are rejected when the
+    /// configuration is loaded; should one still be present, resolution stops
+    /// after visiting every route once.
+    ///
+    /// # Parameters
+    ///
+    /// * `identifier` - The `[METHOD] path` identifier of the requested route
+    ///
+    /// # Returns
+    ///
+    /// The identifier of the route at the end of the alias chain
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use json_echo_core::Database;
+    ///
+    /// let db = Database::new();
+    /// // Assuming "[GET] /v1/users" is an alias of "[GET] /users"
+    /// let model = db.get_model(db.resolve_alias("[GET] /v1/users"));
+    /// ```
+    pub fn resolve_alias<'a>(&'a self, identifier: &'a str) -> &'a str {
+        let mut resolved = identifier;
+
+        for _ in 0..self.routes.len() {
+            match self
+                .routes
+                .get(resolved)
+                .and_then(|route| route.alias.as_deref())
+            {
+                Some(target) => resolved = target,
+                None => break,
+            }
+        }
+
+        resolved
+    }
+
+    /// Attaches related entries to data served by a route.
+    ///
+    /// Relations are looked up in the route's `relations` settings. Every entry
+    /// of `data` (the entry itself, the items of an array, or the items under
+    /// the model's `results_field`) is joined with the related collections:
+    ///
+    /// - `embed`: the related entries whose `foreign_key` equals the entry's id
+    ///   are attached as an array under the relation name
+    /// - `expand`: the related entry whose id equals the entry's `foreign_key`
+    ///   is attached as an object under the relation name, when one exists
+    ///
+    /// Ids and foreign keys match when they are equal or render to the same
+    /// string, so `1` matches `"1"`.
+    ///
+    /// # Parameters
+    ///
+    /// * `identifier` - The `[METHOD] path` identifier of the route serving `data`
+    /// * `data` - The data about to be served
+    /// * `embed` - The relations whose children are attached
+    /// * `expand` - The relations whose parent is attached
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Value)` - The data with the related entries attached
+    /// * `Err(String)` - If a requested relation is not declared by the route
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use json_echo_core::Database;
+    /// use serde_json::json;
+    ///
+    /// let db = Database::new();
+    /// // Assuming "[GET] /posts" declares a "comments" relation
+    /// let post = json!({"id": 1, "title": "Hello"});
+    /// let joined = db.join_relations("[GET] /posts", post, &["comments"], &[]);
+    /// ```
+    pub fn join_relations(
+        &self,
+        identifier: &str,
+        mut data: Value,
+        embed: &[&str],
+        expand: &[&str],
+    ) -> Result<Value, String> {
+        let relations = self
+            .routes
+            .get(identifier)
+            .and_then(|route| route.relations.as_ref());
+        let model = self.get_model(self.resolve_alias(identifier));
+        let id_field = model.map_or("id", Model::get_id_field);
+
+        // Load every requested collection once, before walking the entries
+        let mut joins = Vec::with_capacity(embed.len() + expand.len());
+        for (names, embedding) in [(embed, true), (expand, false)] {
+            for name in names {
+                let relation = relations
+                    .and_then(|relations| relations.get(*name))
+                    .ok_or_else(|| format!("Unknown relation: {name}"))?;
+                let related = self.get_model(self.resolve_alias(&relation.route));
+
+                joins.push((
+                    *name,
+                    embedding,
+                    relation.foreign_key.as_str(),
+                    related.map(Model::get_value),
+                    related.map_or("id", Model::get_id_field),
+                    related.and_then(Model::get_results_field),
+                ));
+            }
+        }
+
+        let results_field = model.and_then(Model::get_results_field);
+        for entry in entries_mut(&mut data, results_field) {
+            for (name, embedding, foreign_key, related, related_id, related_results) in &joins {
+                let candidates = related
+                    .as_deref()
+                    .map(|related| entries(related, *related_results))
+                    .unwrap_or_default();
+
+                if *embedding {
+                    let children = candidates
+                        .into_iter()
+                        .filter(|child| same_key(entry.get(id_field), child.get(*foreign_key)))
+                        .cloned()
+                        .collect();
+                    entry.insert((*name).to_string(), Value::Array(children));
+                } else if let Some(parent) = candidates
+                    .into_iter()
+                    .find(|parent| same_key(entry.get(*foreign_key), parent.get(*related_id)))
+                {
+                    entry.insert((*name).to_string(), parent.clone());
+                }
+            }
+        }
+
+        Ok(data)
     }
 
     /// Updates the data of a specific model by merging new JSON data with existing data.
@@ -477,14 +668,207 @@ impl Database {
     /// - The underlying merge operation fails (invalid data structures, etc.)
     /// - The model's data format is incompatible with merging operations
     pub fn update_model_data(&mut self, identifier: &str, new_data: Value) -> Result<(), String> {
-        // Find the model and update its data
-        let model_position = self
-            .models
-            .iter()
-            .position(|model| model.identifier == identifier)
-            .ok_or_else(|| format!("Model with identifier '{identifier}' not found"))?;
+        self.get_model_mut(identifier)?.update_data(new_data)
+    }
+
+    /// Merges new JSON data into a model, targeting the given results field.
+    ///
+    /// Behaves like `update_model_data`, except that the collection written to
+    /// is chosen by the caller rather than by the model's own `results_field`.
+    /// This keeps writes synced across the models of one path consistent when
+    /// only some of their routes declare the field.
+    ///
+    /// # Parameters
+    ///
+    /// * `identifier` - The string identifier of the model to update
+    /// * `new_data` - The new JSON value to merge with the model's existing data
+    /// * `results_field` - The field holding the collection, or `None` for the whole body
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - If the model was found and updated successfully
+    /// * `Err(String)` - If the model was not found or the merge operation failed
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use json_echo_core::Database;
+    /// use serde_json::json;
+    ///
+    /// # fn example() -> Result<(), String> {
+    /// let mut db = Database::new();
+    /// # let route = serde_json::from_value(json!({"response": {"status": 200, "body": {"users": [{"id": 1}], "total": 1}}})).unwrap();
+    /// # db.populate([("[GET] /users".to_string(), route)]);
+    /// // "[GET] /users" serves {"users": [{"id": 1}], "total": 1}
+    /// db.update_model_data_in("[GET] /users", json!({"id": 2}), Some("users"))?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn update_model_data_in(
+        &mut self,
+        identifier: &str,
+        new_data: Value,
+        results_field: Option<&str>,
+    ) -> Result<(), String> {
+        self.get_model_mut(identifier)?
+            .update_data_in(new_data, results_field)
+    }
+
+    /// Writes a PUT payload into a model, targeting the given results field.
+    ///
+    /// Behaves like `update_model_data_in`, except that a missing results
+    /// field is created as the collection holding the entry, see
+    /// `Model::put_data_in`.
+    ///
+    /// # Parameters
+    ///
+    /// * `identifier` - The string identifier of the model to update
+    /// * `new_data` - The entry or entries put
+    /// * `results_field` - The field holding the collection, or `None` for the whole body
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - If the model was found and updated successfully
+    /// * `Err(String)` - If the model was not found or the merge operation failed
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use json_echo_core::Database;
+    /// use serde_json::json;
+    ///
+    /// # fn example() -> Result<(), String> {
+    /// let mut db = Database::new();
+    /// # let route = serde_json::from_value(json!({"response": {"status": 200, "body": {"total": 0}}})).unwrap();
+    /// # db.populate([("[GET] /users/{id}".to_string(), route)]);
+    /// // "[GET] /users/{id}" serves {"total": 0}
+    /// db.put_model_data_in("[GET] /users/{id}", json!({"id": 1}), Some("users"))?;
+    /// // The model now serves {"total": 0, "users": [{"id": 1}]}
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn put_model_data_in(
+        &mut self,
+        identifier: &str,
+        new_data: Value,
+        results_field: Option<&str>,
+    ) -> Result<(), String> {
+        self.get_model_mut(identifier)?
+            .put_data_in(new_data, results_field)
+    }
 
-        self.models[model_position].update_data(new_data)
+    /// Gives a new entry the id generated by a model's `id_strategy`.
+    ///
+    /// Callers writing the same entry to several models assign the id first,
+    /// so every model stores it with the same id instead of generating its own.
+    ///
+    /// # Parameters
+    ///
+    /// * `identifier` - The string identifier of the model whose collection receives the entry
+    /// * `new_data` - The entry about to be written
+    /// * `results_field` - The field holding the collection, or `None` for the whole body
+    ///
+    /// # Returns
+    ///
+    /// The entry, with a generated id when the model has a strategy, its
+    /// collection is an array and the entry is an object without an id
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use json_echo_core::{ConfigRoute, Database};
+    /// use serde_json::json;
+    ///
+    /// let route: ConfigRoute = serde_json::from_value(json!({
+    ///     "id_strategy": "sequential",
+    ///     "response": {"status": 200, "body": [{"id": 1}]}
+    /// }))
+    /// .unwrap();
+    /// let mut db = Database::new();
+    /// db.populate([("[GET] /users".to_string(), route)]);
+    ///
+    /// let user = db.assign_id("[GET] /users", json!({"name": "Jane"}), None);
+    /// assert_eq!(user, json!({"id": 2, "name": "Jane"}));
+    /// ```
+    pub fn assign_id(
+        &self,
+        identifier: &str,
+        mut new_data: Value,
+        results_field: Option<&str>,
+    ) -> Value {
+        if let Some(model) = self.get_model(identifier) {
+            model.assign_id(&mut new_data, results_field);
+        }
+        new_data
+    }
+
+    /// Replaces the data of a specific model with the provided JSON value.
+    ///
+    /// Unlike `update_model_data`, no merging takes place: the served data of the
+    /// model is overwritten as a whole. This is used by administrative tooling to
+    /// force a model into a known state.
+    ///
+    /// # Parameters
+    ///
+    /// * `identifier` - The string identifier of the model to replace
+    /// * `new_data` - The JSON value that becomes the model's data
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - If the model was found and its data replaced
+    /// * `Err(String)` - If no model exists with the given identifier
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use json_echo_core::Database;
+    /// use serde_json::json;
+    ///
+    /// # fn example() -> Result<(), String> {
+    /// let mut db = Database::new();
+    /// # let route = serde_json::from_value(json!({"response": {"status": 200, "body": [{"id": 1}]}})).unwrap();
+    /// # db.populate([("[GET] /users".to_string(), route)]);
+    /// // "[GET] /users" serves [{"id": 1}]
+    /// db.set_model_data("[GET] /users", json!([]))?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn set_model_data(&mut self, identifier: &str, new_data: Value) -> Result<(), String> {
+        self.get_model_mut(identifier)?.set_data(new_data);
+        Ok(())
+    }
+
+    /// Replaces the data of a lazy model with its loaded response.
+    ///
+    /// Only a model still pending on its file is changed, so when concurrent
+    /// requests load the same file the first response stored is kept.
+    ///
+    /// # Parameters
+    ///
+    /// * `identifier` - The string identifier of the lazy model
+    /// * `data` - The response loaded from the model's file
+    ///
+    /// # Returns
+    ///
+    /// * `true` - If the model was pending and now serves the loaded response
+    /// * `false` - If no such model exists or its response was already loaded
+    pub fn load_pending(&mut self, identifier: &str, data: ConfigRouteResponse) -> bool {
+        match self.get_model_mut(identifier) {
+            Ok(model) if model.pending.is_some() => {
+                model.pending = None;
+                model.data = Arc::new(data);
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Looks up a model for mutation through the model index.
+    fn get_model_mut(&mut self, identifier: &str) -> Result<&mut Model, String> {
+        self.model_index
+            .get(identifier)
+            .and_then(|position| self.models.get_mut(*position))
+            .ok_or_else(|| format!("Model with identifier '{identifier}' not found"))
     }
 }
 
@@ -536,7 +920,9 @@ impl Model {
             id_field,
             results_field,
             description,
-            data,
+            id_strategy: None,
+            pending: None,
+            data: Arc::new(data),
         }
     }
 
@@ -588,6 +974,16 @@ impl Model {
         self.description.as_ref()
     }
 
+    /// Returns the file of a lazy model whose response has not been loaded yet.
+    ///
+    /// # Returns
+    ///
+    /// * `Some(&Path)` - The resolved file to load the response from
+    /// * `None` - If the route is not lazy or its response is already loaded
+    pub fn get_pending_file(&self) -> Option<&Path> {
+        self.pending.as_deref()
+    }
+
     /// Returns the data associated with this model.
     ///
     /// Extracts and returns the actual data content from the model. If a results
@@ -623,18 +1019,91 @@ impl Model {
     pub fn get_data(&self) -> BodyResponse {
         if let Some(results_field) = &self.results_field {
             // Only if body is type Value
-            if let BodyResponse::Value(body) = &self.data.body {
-                if let Value::Object(map) = body {
-                    if let Some(value) = map.get(results_field) {
-                        return BodyResponse::Value(value.clone());
-                    }
-                }
+            if let BodyResponse::Value(body) = &self.data.body
+                && let Value::Object(map) = body
+                && let Some(value) = map.get(results_field)
+            {
+                return BodyResponse::Value(value.clone());
             }
         }
 
         self.data.body.clone()
     }
 
+    /// Returns the model's data as a JSON value, borrowing it where possible.
+    ///
+    /// This is the allocation-free counterpart of `get_data().as_value()`. JSON
+    /// bodies, including a configured results field, are borrowed directly from
+    /// the model; only string bodies are converted into an owned `Value::String`.
+    /// Request handlers should prefer this method so large bodies are serialized
+    /// straight from the database instead of being cloned on every request.
+    ///
+    /// # Returns
+    ///
+    /// A `Cow` holding either a borrowed or an owned `Value`
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use std::borrow::Cow;
+    /// # use json_echo_core::Model;
+    /// # use serde_json::json;
+    /// # let mut model = Model::new(
+    /// #     "[GET] /users".to_string(),
+    /// #     "id".to_string(),
+    /// #     None,
+    /// #     None,
+    /// #     serde_json::from_value(json!({"status": 200, "body": [{"id": 1}]})).unwrap(),
+    /// # );
+    ///
+    /// let value = model.get_value();
+    /// assert!(matches!(value, Cow::Borrowed(_)));
+    /// ```
+    pub fn get_value(&self) -> Cow<'_, Value> {
+        match &self.data.body {
+            BodyResponse::Value(body) => Cow::Borrowed(
+                self.results_field
+                    .as_ref()
+                    .and_then(|results_field| body.get(results_field))
+                    .unwrap_or(body),
+            ),
+            BodyResponse::Str(value) | BodyResponse::String(value) => {
+                Cow::Owned(Value::String(value.clone()))
+            }
+        }
+    }
+
+    /// Returns a shared handle to the model's response data.
+    ///
+    /// The handle only clones a pointer. It keeps observing the data as it was
+    /// when taken: later mutations of the model copy the data instead of
+    /// changing what the handle points to.
+    ///
+    /// # Returns
+    ///
+    /// An `Arc` pointing to the model's `ConfigRouteResponse`
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use std::sync::Arc;
+    /// # use json_echo_core::Model;
+    /// # use serde_json::json;
+    /// # let mut model = Model::new(
+    /// #     "[GET] /users".to_string(),
+    /// #     "id".to_string(),
+    /// #     None,
+    /// #     None,
+    /// #     serde_json::from_value(json!({"status": 200, "body": [{"id": 1}]})).unwrap(),
+    /// # );
+    ///
+    /// let snapshot = model.get_shared_data();
+    /// assert!(Arc::ptr_eq(&snapshot, &model.get_shared_data()));
+    /// ```
+    pub fn get_shared_data(&self) -> Arc<ConfigRouteResponse> {
+        Arc::clone(&self.data)
+    }
+
     /// Updates the model's data by merging new JSON data with existing data.
     ///
     /// This method performs intelligent merging of JSON data based on the structure
@@ -693,23 +1162,72 @@ impl Model {
     /// - JSON serialization/deserialization fails during the merge process
     /// - The merge operation would result in invalid data structure
     pub fn update_data(&mut self, new_data: Value) -> Result<(), String> {
+        let results_field = self.results_field.clone();
+        self.update_data_in(new_data, results_field.as_deref())
+    }
+
+    /// Merges new JSON data into the model, targeting the given results field.
+    ///
+    /// Follows the same merging rules as `update_data`, but writes into
+    /// `results_field` instead of the model's own field. When the body is a JSON
+    /// object, the data is merged into that field, which is created from the new
+    /// data when absent; otherwise the whole body is merged.
+    ///
+    /// # Parameters
+    ///
+    /// * `new_data` - The new JSON value to merge with the existing data
+    /// * `results_field` - The field holding the collection, or `None` for the whole body
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - If the merge completed successfully
+    /// * `Err(String)` - If the body is a string and cannot be merged
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # fn example() -> Result<(), String> {
+    /// # use json_echo_core::Model;
+    /// # use serde_json::json;
+    /// # let mut model = Model::new(
+    /// #     "[GET] /users".to_string(),
+    /// #     "id".to_string(),
+    /// #     None,
+    /// #     None,
+    /// #     serde_json::from_value(json!({"status": 200, "body": {"users": [{"id": 1}], "total": 1}})).unwrap(),
+    /// # );
+    /// // The model serves {"users": [{"id": 1}], "total": 1}
+    /// model.update_data_in(json!({"id": 2}), Some("users"))?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn update_data_in(
+        &mut self,
+        mut new_data: Value,
+        results_field: Option<&str>,
+    ) -> Result<(), String> {
+        self.assign_id(&mut new_data, results_field);
+
         // Handle the results_field case first
-        if let Some(results_field) = &self.results_field {
-            if let BodyResponse::Value(Value::Object(map)) = &mut self.data.body {
-                let results_field_clone = results_field.clone();
-                if let Some(existing_results) = map.get_mut(&results_field_clone) {
-                    let id_field = self.id_field.clone();
-                    Self::merge_json_values_static(existing_results, new_data, &id_field)?;
-                    return Ok(());
-                }
-                // If results_field doesn't exist, create it with the new data
-                map.insert(results_field_clone, new_data);
+        if let Some(results_field) = results_field
+            && let BodyResponse::Value(Value::Object(_)) = &self.data.body
+            && let BodyResponse::Value(Value::Object(map)) = &mut Arc::make_mut(&mut self.data).body
+        {
+            if let Some(existing_results) = map.get_mut(results_field) {
+                Self::merge_json_values_static(existing_results, new_data, &self.id_field)?;
                 return Ok(());
             }
+            // If results_field doesn't exist, create it with the new data
+            map.insert(results_field.to_string(), new_data);
+            return Ok(());
         }
 
         // Handle direct data update when no results_field is specified
-        match &mut self.data.body {
+        if !self.data.body.is_value() {
+            return Err("Cannot merge data with string-based responses".to_string());
+        }
+
+        match &mut Arc::make_mut(&mut self.data).body {
             BodyResponse::Value(existing_body) => {
                 let id_field = self.id_field.clone();
                 Self::merge_json_values_static(existing_body, new_data, &id_field)?;
@@ -721,6 +1239,143 @@ impl Model {
         }
     }
 
+    /// Writes a PUT payload into the model, targeting the given results field.
+    ///
+    /// When the body is a JSON object lacking `results_field`, the field is
+    /// created as an array holding the entry, or as the array put, so the entry
+    /// is found by id afterwards; a merge would store the entry itself as the
+    /// field. Otherwise the payload is merged as by `update_data_in`.
+    ///
+    /// # Parameters
+    ///
+    /// * `new_data` - The entry or entries put
+    /// * `results_field` - The field holding the collection, or `None` for the whole body
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - If the write completed successfully
+    /// * `Err(String)` - If the body is a string and cannot be merged
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # fn example() -> Result<(), String> {
+    /// # use json_echo_core::Model;
+    /// # use serde_json::json;
+    /// # let mut model = Model::new(
+    /// #     "[GET] /users".to_string(),
+    /// #     "id".to_string(),
+    /// #     None,
+    /// #     None,
+    /// #     serde_json::from_value(json!({"status": 200, "body": {"total": 0}})).unwrap(),
+    /// # );
+    /// // The model serves {"total": 0}
+    /// model.put_data_in(json!({"id": 1}), Some("users"))?;
+    /// assert_eq!(model.get_value().into_owned(), json!({"total": 0, "users": [{"id": 1}]}));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn put_data_in(
+        &mut self,
+        new_data: Value,
+        results_field: Option<&str>,
+    ) -> Result<(), String> {
+        if let Some(results_field) = results_field
+            && let BodyResponse::Value(Value::Object(map)) = &self.data.body
+            && !map.contains_key(results_field)
+        {
+            let collection = match new_data {
+                entries @ Value::Array(_) => entries,
+                entry => Value::Array(vec![entry]),
+            };
+            if let BodyResponse::Value(Value::Object(map)) = &mut Arc::make_mut(&mut self.data).body
+            {
+                map.insert(results_field.to_string(), collection);
+            }
+            return Ok(());
+        }
+
+        self.update_data_in(new_data, results_field)
+    }
+
+    /// Gives an entry added to the collection the id generated by `id_strategy`.
+    ///
+    /// Nothing changes without a strategy, when the collection is not an array,
+    /// or when the entry is not an object or already carries a non-null id.
+    ///
+    /// # Parameters
+    ///
+    /// * `new_data` - The entry about to be added
+    /// * `results_field` - The field holding the collection, or `None` for the whole body
+    pub fn assign_id(&self, new_data: &mut Value, results_field: Option<&str>) {
+        let Some(strategy) = self.id_strategy else {
+            return;
+        };
+        let Value::Object(entry) = new_data else {
+            return;
+        };
+        if entry.get(&self.id_field).is_some_and(|id| !id.is_null()) {
+            return;
+        }
+
+        let BodyResponse::Value(body) = &self.data.body else {
+            return;
+        };
+        let collection = match results_field {
+            Some(results_field) if body.is_object() => body.get(results_field),
+            _ => Some(body),
+        };
+
+        if let Some(Value::Array(entries)) = collection {
+            entry.insert(
+                self.id_field.clone(),
+                strategy.next_id(entries, &self.id_field),
+            );
+        }
+    }
+
+    /// Replaces the model's data with the provided JSON value.
+    ///
+    /// When a `results_field` is configured and the body is a JSON object, only
+    /// that field is replaced so the surrounding envelope is preserved. In every
+    /// other case the whole body is replaced.
+    ///
+    /// # Parameters
+    ///
+    /// * `new_data` - The JSON value that becomes the model's data
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use json_echo_core::Model;
+    /// # use serde_json::json;
+    /// # let mut model = Model::new(
+    /// #     "[GET] /users".to_string(),
+    /// #     "id".to_string(),
+    /// #     None,
+    /// #     None,
+    /// #     serde_json::from_value(json!({"status": 200, "body": []})).unwrap(),
+    /// # );
+    ///
+    /// model.set_data(json!([{"id": 1}]));
+    /// assert_eq!(model.get_data().as_value(), json!([{"id": 1}]));
+    /// ```
+    pub fn set_data(&mut self, new_data: Value) {
+        if let Some(results_field) = &self.results_field
+            && let BodyResponse::Value(Value::Object(_)) = &self.data.body
+            && let BodyResponse::Value(Value::Object(map)) = &mut Arc::make_mut(&mut self.data).body
+        {
+            map.insert(results_field.clone(), new_data);
+            return;
+        }
+
+        // The whole body is replaced, so there is nothing worth copying
+        self.data = Arc::new(ConfigRouteResponse {
+            status: self.data.status,
+            body: BodyResponse::Value(new_data),
+        });
+    }
+
     /// Performs the actual JSON value merging logic.
     ///
     /// This internal helper method handles the core merging logic between two JSON values.
@@ -798,19 +1453,18 @@ impl Model {
                         let mut found_existing = false;
 
                         for existing_item in existing_arr.iter_mut() {
-                            if let Value::Object(existing_item_map) = existing_item {
-                                if let Some(existing_id) = existing_item_map.get(id_field) {
-                                    if existing_id == new_id {
-                                        // Merge with existing item
-                                        Self::merge_json_values_static(
-                                            existing_item,
-                                            new_obj.clone(),
-                                            id_field,
-                                        )?;
-                                        found_existing = true;
-                                        break;
-                                    }
-                                }
+                            if let Value::Object(existing_item_map) = existing_item
+                                && let Some(existing_id) = existing_item_map.get(id_field)
+                                && existing_id == new_id
+                            {
+                                // Merge with existing item
+                                Self::merge_json_values_static(
+                                    existing_item,
+                                    new_obj.clone(),
+                                    id_field,
+                                )?;
+                                found_existing = true;
+                                break;
                             }
                         }
 
@@ -952,7 +1606,13 @@ impl Model {
     pub fn find_entry_by_hashmap(&self, map: HashMap<String, String>) -> Option<Value> {
         let id_field = self.get_id_field();
 
-        if let BodyResponse::Value(body) = &self.get_data() {
+        if let BodyResponse::Value(body) = &self.data.body {
+            let body = self
+                .results_field
+                .as_ref()
+                .and_then(|results_field| body.get(results_field))
+                .unwrap_or(body);
+
             if let Value::Object(obj) = body {
                 for (key, value) in &map {
                     if let Some(val) = obj.get(&key.replace(':', "")) {
@@ -987,3 +1647,56 @@ impl Model {
         None
     }
 }
+
+/// Returns the entries of a collection: the items under `results_field`, the
+/// items of an array, or a single object.
+fn entries<'a>(data: &'a Value, results_field: Option<&String>) -> Vec<&'a Value> {
+    let data = results_field
+        .and_then(|results_field| data.get(results_field))
+        .unwrap_or(data);
+
+    match data {
+        Value::Array(items) => items.iter().filter(|item| item.is_object()).collect(),
+        Value::Object(_) => vec![data],
+        _ => Vec::new(),
+    }
+}
+
+/// Returns the entries of a collection as mutable objects, like `entries`.
+fn entries_mut<'a>(
+    data: &'a mut Value,
+    results_field: Option<&String>,
+) -> Vec<&'a mut Map<String, Value>> {
+    let nested = results_field.filter(|results_field| {
+        data.get(results_field.as_str())
+            .is_some_and(Value::is_array)
+    });
+    let data = match nested {
+        Some(results_field) => &mut data[results_field.as_str()],
+        None => data,
+    };
+
+    match data {
+        Value::Array(items) => items.iter_mut().filter_map(Value::as_object_mut).collect(),
+        Value::Object(entry) => vec![entry],
+        _ => Vec::new(),
+    }
+}
+
+/// Returns whether an id and a foreign key reference the same entry.
+///
+/// Values match when they are equal or render to the same string, so ids
+/// stored as numbers match foreign keys stored as strings. Missing and null
+/// values never match.
+fn same_key(id: Option<&Value>, foreign_key: Option<&Value>) -> bool {
+    let (Some(id), Some(foreign_key)) = (id, foreign_key) else {
+        return false;
+    };
+
+    let render = |value: &Value| match value {
+        Value::String(value) => value.clone(),
+        other => other.to_string(),
+    };
+
+    !id.is_null() && (id == foreign_key || render(id) == render(foreign_key))
+}
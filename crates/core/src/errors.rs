@@ -100,6 +100,9 @@ pub type FileSystemResult<T> = CoreResult<T, FileSystemError>;
 /// * `NotADirectory` - Expected a directory but found a file
 /// * `NotAFile` - Expected a file but found a directory
 /// * `Utf8Decode` - Failed to decode UTF-8 content from a file
+/// * `ConfigParse` - A configuration file is not valid JSON or does not match the schema
+/// * `ResponseFile` - A referenced response file is empty or does not describe a route response
+/// * `RouteFile` - A file of the `routes_dir` does not describe a route
 /// * `Validation` - Path validation failed for security or format reasons
 /// * `Operation` - General operation failures
 ///
@@ -175,6 +178,37 @@ pub enum FileSystemError {
         message: String,
     },
 
+    /// A configuration file could not be parsed.
+    #[error("Invalid configuration in '{path}' at line {line}, column {column}: {message}")]
+    ConfigParse {
+        /// The path of the configuration file
+        path: PathBuf,
+        /// The one-based line of the problem
+        line: usize,
+        /// The one-based column of the problem
+        column: usize,
+        /// The parser's description of the problem, without its location
+        message: String,
+    },
+
+    /// A referenced response file is empty or does not describe a route response.
+    #[error("Invalid response file '{path}': {reason}")]
+    ResponseFile {
+        /// The path of the referenced file
+        path: PathBuf,
+        /// What is wrong with the content of the file
+        reason: String,
+    },
+
+    /// A file of the `routes_dir` does not describe a route.
+    #[error("Invalid route file '{path}': {reason}")]
+    RouteFile {
+        /// The path of the route file
+        path: PathBuf,
+        /// What is wrong with the content of the file
+        reason: String,
+    },
+
     /// Path validation failed (e.g., contains '..', absolute path, symlink).
     #[error("Path validation failed for '{path}': {reason}")]
     Validation {
@@ -275,6 +309,82 @@ impl Error {
     }
 }
 
+impl FileSystemError {
+    /// Creates the error matching an I/O failure on a known path.
+    ///
+    /// Prefer this over the `From<io::Error>` conversion whenever the path is
+    /// in scope, so the error tells which file could not be accessed.
+    ///
+    /// # Parameters
+    ///
+    /// * `error` - The I/O error to convert
+    /// * `path` - The path the failed operation was attempted on
+    ///
+    /// # Returns
+    ///
+    /// `NotFound`, `PermissionDenied` or `Io`, depending on the error kind
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use json_echo_core::FileSystemError;
+    /// use std::io;
+    ///
+    /// let error = io::Error::from(io::ErrorKind::NotFound);
+    /// let error = FileSystemError::from_io(&error, "config/app.json");
+    /// assert_eq!(error.to_string(), "Path not found: config/app.json");
+    /// ```
+    pub fn from_io(error: &io::Error, path: impl Into<PathBuf>) -> Self {
+        let path = path.into();
+        match error.kind() {
+            io::ErrorKind::NotFound => Self::NotFound { path },
+            io::ErrorKind::PermissionDenied => Self::PermissionDenied { path },
+            _ => Self::Io {
+                path,
+                message: error.to_string(),
+            },
+        }
+    }
+
+    /// Creates a `ConfigParse` error from a serde_json error and the parsed file.
+    ///
+    /// # Parameters
+    ///
+    /// * `path` - The path of the file that failed to parse
+    /// * `error` - The error reported by serde_json
+    ///
+    /// # Returns
+    ///
+    /// A `FileSystemError::ConfigParse` carrying the file, line and column
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use json_echo_core::FileSystemError;
+    ///
+    /// let error = serde_json::from_str::<serde_json::Value>("{\n  oops\n}").unwrap_err();
+    /// let error = FileSystemError::config_parse("json-echo.json", &error);
+    /// assert!(matches!(error, FileSystemError::ConfigParse { line: 2, .. }));
+    /// ```
+    pub fn config_parse(path: impl Into<PathBuf>, error: &serde_json::Error) -> Self {
+        let (line, column) = (error.line(), error.column());
+
+        // serde_json appends the location to its messages; it is reported separately
+        let message = error.to_string();
+        let message = message
+            .strip_suffix(&format!(" at line {line} column {column}"))
+            .unwrap_or(&message)
+            .to_string();
+
+        Self::ConfigParse {
+            path: path.into(),
+            line,
+            column,
+            message,
+        }
+    }
+}
+
 impl From<io::Error> for FileSystemError {
     /// Converts standard library I/O errors into FileSystemError.
     ///
@@ -298,19 +408,10 @@ impl From<io::Error> for FileSystemError {
     /// - Other kinds → `FileSystemError::Io`
     ///
     /// Note: Uses a placeholder path since the original path context is not
-    /// available in the I/O error. Applications should prefer creating
-    /// FileSystemError variants directly when path context is available.
+    /// available in the I/O error. Applications should prefer
+    /// `FileSystemError::from_io` when path context is available.
     fn from(error: io::Error) -> Self {
-        // Create a dummy path or indicate unknown path
-        let path = PathBuf::from("<unknown>");
-        match error.kind() {
-            io::ErrorKind::NotFound => Self::NotFound { path },
-            io::ErrorKind::PermissionDenied => Self::PermissionDenied { path },
-            _ => Self::Io {
-                path,
-                message: error.to_string(),
-            },
-        }
+        Self::from_io(&error, "<unknown>")
     }
 }
 
@@ -7,18 +7,23 @@
 //!
 //! ## What
 //!
-//! The module defines two main components:
+//! The module defines the following components:
 //! - `PathUtils`: A utility struct providing static methods for path operations and root discovery
 //! - `FileSystemManager`: A manager for performing async file I/O operations with proper error handling
+//! - `SymlinkPolicy`: Whether a manager follows or forbids symbolic links below its root
 //!
 //! ## How
 //!
 //! The filesystem module works by:
 //! 1. Providing path normalization and canonicalization utilities
-//! 2. Automatically discovering project roots based on configuration file presence
-//! 3. Managing async file operations with proper error propagation
+//! 2. Automatically discovering project roots based on configuration file presence,
+//!    unless the `JSON_ECHO_ROOT` environment variable pins one
+//! 3. Managing async file operations with proper error propagation, optionally
+//!    retrying transient I/O failures with exponential backoff
 //! 4. Abstracting filesystem operations behind a consistent interface
-//! 5. Supporting both absolute and relative path operations
+//! 5. Confining user-provided file references to the project root, optionally
+//!    refusing symbolic links for fixtures from untrusted directories
+//! 6. Supporting both absolute and relative path operations
 //!
 //! ## Why
 //!
@@ -58,8 +63,10 @@
 
 use crate::errors::{FileSystemError, FileSystemResult};
 use std::{
-    env::current_dir,
-    path::{Path, PathBuf},
+    collections::HashSet,
+    env::{current_dir, var_os},
+    path::{Component, Path, PathBuf},
+    time::Duration,
 };
 use tokio::{
     fs::File,
@@ -97,6 +104,15 @@ use tokio::{
 #[derive(Debug, Clone)]
 pub struct PathUtils;
 
+/// Environment variable pinning the project root instead of searching for it.
+pub const ROOT_ENV_VAR: &str = "JSON_ECHO_ROOT";
+
+/// Configuration file names that mark a project root, in lookup order.
+pub const CONFIG_FILE_NAMES: [&str; 3] = ["db.json", ".db.json", "json-echo.json"];
+
+/// Environment variable adding comma separated names to `CONFIG_FILE_NAMES`.
+pub const CONFIG_NAMES_ENV_VAR: &str = "JSON_ECHO_CONFIG_NAMES";
+
 impl PathUtils {
     /// Returns the current working directory.
     ///
@@ -149,10 +165,11 @@ impl PathUtils {
     ///
     /// # Behavior
     ///
-    /// Searches for these configuration files in order:
-    /// 1. `db.json`
-    /// 2. `.db.json`
-    /// 3. `json-echo.json`
+    /// When the `JSON_ECHO_ROOT` environment variable names an existing
+    /// directory, that directory is returned without searching. A value that
+    /// is not a directory is logged and ignored.
+    ///
+    /// Otherwise searches for the files listed by `config_file_names`.
     ///
     /// The search starts from the given path and moves up the directory tree
     /// until one of these files is found or the filesystem root is reached.
@@ -179,9 +196,20 @@ impl PathUtils {
     /// # }
     /// ```
     pub fn find_root(start: &Path) -> Option<PathBuf> {
+        if let Some(root) = var_os(ROOT_ENV_VAR).map(PathBuf::from) {
+            if root.is_dir() {
+                return Some(root);
+            }
+
+            log::warn!(
+                "{ROOT_ENV_VAR} is set to '{}', which is not a directory; searching for the root",
+                root.display()
+            );
+        }
+
         let mut current = Some(start);
 
-        let mock_files = ["db.json", ".db.json", "json-echo.json"];
+        let mock_files = Self::config_file_names();
 
         while let Some(path) = current {
             for mock_file in &mock_files {
@@ -196,6 +224,43 @@ impl PathUtils {
         None
     }
 
+    /// Returns the configuration file names used to discover a project.
+    ///
+    /// Names listed in the `JSON_ECHO_CONFIG_NAMES` environment variable,
+    /// separated by commas, come first so team conventions such as
+    /// `mocks.json` win, followed by `CONFIG_FILE_NAMES`. Both root discovery
+    /// and `ConfigManager::get_config_file_path` use this list.
+    ///
+    /// # Returns
+    ///
+    /// The file names in lookup order, without duplicates
+    ///
+    /// # Examples
+    ///
+    /// ```rust,ignore
+    /// use json_echo_core::PathUtils;
+    ///
+    /// // With JSON_ECHO_CONFIG_NAMES=mocks.json
+    /// assert_eq!(
+    ///     PathUtils::config_file_names(),
+    ///     ["mocks.json", "db.json", ".db.json", "json-echo.json"]
+    /// );
+    /// ```
+    pub fn config_file_names() -> Vec<String> {
+        let custom = var_os(CONFIG_NAMES_ENV_VAR)
+            .map(|names| names.to_string_lossy().into_owned())
+            .unwrap_or_default();
+
+        let mut names: Vec<String> = Vec::new();
+        for name in custom.split(',').map(str::trim).chain(CONFIG_FILE_NAMES) {
+            if !name.is_empty() && !names.iter().any(|known| known == name) {
+                names.push(name.to_string());
+            }
+        }
+
+        names
+    }
+
     /// Normalizes a path by resolving symbolic links and relative components.
     ///
     /// Attempts to canonicalize the path to resolve symbolic links, `.` and `..`
@@ -217,6 +282,8 @@ impl PathUtils {
     /// - Resolves symbolic links and relative path components
     /// - Converts relative paths to absolute paths when possible
     ///
+    /// Use `normalize_path_no_symlinks` when links must not be followed.
+    ///
     /// # Examples
     ///
     /// ```rust
@@ -235,6 +302,94 @@ impl PathUtils {
     pub fn normalize_path(path: &Path) -> PathBuf {
         path.canonicalize().unwrap_or_else(|_| path.to_path_buf())
     }
+
+    /// Normalizes a path lexically, without touching the filesystem.
+    ///
+    /// Unlike `normalize_path`, symbolic links are never followed: `.`
+    /// components are dropped and `..` removes the preceding component, so
+    /// the result names the link itself rather than its target.
+    ///
+    /// # Parameters
+    ///
+    /// * `path` - The path to normalize
+    ///
+    /// # Returns
+    ///
+    /// A `PathBuf` without `.` components and without `..` components except
+    /// leading ones of a relative path, or `.` if nothing remains
+    ///
+    /// # Behavior
+    ///
+    /// - `..` directly below the root is dropped, as `/..` is `/`
+    /// - Leading `..` components of relative paths are kept
+    /// - Relative paths stay relative
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use json_echo_core::PathUtils;
+    /// use std::path::{Path, PathBuf};
+    ///
+    /// assert_eq!(
+    ///     PathUtils::normalize_path_no_symlinks(Path::new("./fixtures/../data/./users.json")),
+    ///     PathBuf::from("data/users.json")
+    /// );
+    /// assert_eq!(
+    ///     PathUtils::normalize_path_no_symlinks(Path::new("/../etc")),
+    ///     PathBuf::from("/etc")
+    /// );
+    /// ```
+    pub fn normalize_path_no_symlinks(path: &Path) -> PathBuf {
+        let mut normalized = PathBuf::new();
+
+        for component in path.components() {
+            match component {
+                Component::CurDir => {}
+                Component::ParentDir => match normalized.components().next_back() {
+                    Some(Component::Normal(_)) => {
+                        normalized.pop();
+                    }
+                    Some(Component::RootDir | Component::Prefix(_)) => {}
+                    Some(Component::ParentDir | Component::CurDir) | None => {
+                        normalized.push(Component::ParentDir);
+                    }
+                },
+                other => normalized.push(other),
+            }
+        }
+
+        if normalized.as_os_str().is_empty() {
+            normalized.push(Component::CurDir);
+        }
+
+        normalized
+    }
+}
+
+/// Whether a `FileSystemManager` follows symbolic links below its root.
+///
+/// # Variants
+///
+/// * `Follow` - Symbolic links are followed like regular files (the default)
+/// * `Forbid` - Any symbolic link between the root and a file is rejected
+///
+/// # Examples
+///
+/// ```rust
+/// use json_echo_core::{FileSystemManager, SymlinkPolicy};
+///
+/// # fn example() -> Result<(), Box<dyn std::error::Error>> {
+/// let fs_manager = FileSystemManager::new(None)?.with_symlink_policy(SymlinkPolicy::Forbid);
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SymlinkPolicy {
+    /// Symbolic links are followed like regular files
+    #[default]
+    Follow,
+    /// Any symbolic link between the root and a file is rejected
+    Forbid,
 }
 
 /// Manager for performing async filesystem operations with error handling.
@@ -246,6 +401,9 @@ impl PathUtils {
 /// # Fields
 ///
 /// * `root` - The root directory for all file operations performed by this manager
+/// * `retries` - How many times a transient I/O failure is retried (none by default)
+/// * `retry_delay` - The delay before the first retry, doubled for every further one
+/// * `symlinks` - Whether symbolic links below the root are followed or rejected
 ///
 /// # Examples
 ///
@@ -271,6 +429,12 @@ impl PathUtils {
 pub struct FileSystemManager {
     /// The root directory path for all file operations
     pub root: PathBuf,
+    /// How many times a transient I/O failure is retried
+    retries: u32,
+    /// The delay before the first retry, doubled for every further one
+    retry_delay: Duration,
+    /// Whether symbolic links below the root are followed or rejected
+    symlinks: SymlinkPolicy,
 }
 
 impl FileSystemManager {
@@ -282,7 +446,8 @@ impl FileSystemManager {
     ///
     /// # Parameters
     ///
-    /// * `root` - Optional root directory path. If None, attempts automatic discovery
+    /// * `root` - Optional root directory path. If None, uses `JSON_ECHO_ROOT` or
+    ///   attempts automatic discovery
     ///
     /// # Returns
     ///
@@ -325,7 +490,279 @@ impl FileSystemManager {
             }
         };
 
-        Ok(Self { root })
+        Ok(Self {
+            root,
+            retries: 0,
+            retry_delay: Duration::ZERO,
+            symlinks: SymlinkPolicy::Follow,
+        })
+    }
+
+    /// Sets whether file operations follow symbolic links below the root.
+    ///
+    /// With `SymlinkPolicy::Forbid`, `load_file`, `save_file` and
+    /// `resolve_within_root` fail with `FileSystemError::Validation` when the
+    /// file, or any directory between the root and the file, is a symbolic
+    /// link. The root itself may still be reached through a link.
+    ///
+    /// # Parameters
+    ///
+    /// * `policy` - The symbolic link policy
+    ///
+    /// # Returns
+    ///
+    /// The manager, applying the policy to every file operation
+    #[must_use]
+    pub fn with_symlink_policy(mut self, policy: SymlinkPolicy) -> Self {
+        self.symlinks = policy;
+        self
+    }
+
+    /// Retries `load_file` and `save_file` on transient I/O failures.
+    ///
+    /// Only `FileSystemError::Io` failures are retried, as raised by flaky
+    /// network filesystems; missing files and permission errors fail at once.
+    /// The delay doubles after every retry: with a base of 50ms, retries wait
+    /// 50ms, 100ms, 200ms and so on.
+    ///
+    /// # Parameters
+    ///
+    /// * `retries` - How many times a failed operation is retried
+    /// * `base_delay` - The delay before the first retry
+    ///
+    /// # Returns
+    ///
+    /// The manager, retrying transient failures
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use json_echo_core::FileSystemManager;
+    /// use std::time::Duration;
+    ///
+    /// # fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// let fs_manager = FileSystemManager::new(None)?
+    ///     .with_retries(3, Duration::from_millis(50));
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[must_use]
+    pub fn with_retries(mut self, retries: u32, base_delay: Duration) -> Self {
+        self.retries = retries;
+        self.retry_delay = base_delay;
+        self
+    }
+
+    /// Runs a filesystem operation, retrying it on transient I/O failures.
+    ///
+    /// Follows the policy set with `with_retries`; without it the operation
+    /// runs exactly once.
+    ///
+    /// # Parameters
+    ///
+    /// * `operation` - Starts one attempt of the operation
+    ///
+    /// # Returns
+    ///
+    /// The result of the first attempt that does not fail with an `Io`
+    /// error, or the last failure once the retries are exhausted
+    pub async fn retry<T, F, Fut>(&self, mut operation: F) -> FileSystemResult<T>
+    where
+        F: FnMut() -> Fut,
+        Fut: Future<Output = FileSystemResult<T>>,
+    {
+        let mut attempt = 0;
+
+        loop {
+            match operation().await {
+                Err(error @ FileSystemError::Io { .. }) if attempt < self.retries => {
+                    let delay = self
+                        .retry_delay
+                        .saturating_mul(2u32.saturating_pow(attempt));
+                    log::warn!("Transient filesystem error, retrying in {delay:?}: {error}");
+
+                    tokio::time::sleep(delay).await;
+                    attempt += 1;
+                }
+                result => return result,
+            }
+        }
+    }
+
+    /// Resolves a user-provided file reference, refusing paths outside the root.
+    ///
+    /// The reference is joined to the root and canonicalized, so `..`
+    /// components, absolute paths and symlinks are all checked against where
+    /// the file actually lives. Use it for every path taken from a
+    /// configuration before reading it.
+    ///
+    /// # Parameters
+    ///
+    /// * `relative_file_path` - The file reference, relative to the root
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(PathBuf)` - The canonical path of the file, inside the root
+    /// * `Err(FileSystemError::Validation)` - If the file lies outside the root
+    /// * `Err(FileSystemError)` - If the file cannot be resolved, e.g. it does not exist
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use json_echo_core::{FileSystemError, FileSystemManager};
+    ///
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// let fs_manager = FileSystemManager::new(None)?;
+    ///
+    /// match fs_manager.resolve_within_root("../../etc/passwd").await {
+    ///     Err(FileSystemError::Validation { reason, .. }) => eprintln!("Rejected: {}", reason),
+    ///     other => println!("Resolved: {:?}", other),
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn resolve_within_root(&self, relative_file_path: &str) -> FileSystemResult<PathBuf> {
+        let file_path = self.root.as_path().join(relative_file_path);
+        self.check_symlinks(&file_path).await?;
+
+        let root = tokio::fs::canonicalize(&self.root)
+            .await
+            .map_err(|error| FileSystemError::from_io(&error, &self.root))?;
+        let resolved = tokio::fs::canonicalize(&file_path)
+            .await
+            .map_err(|error| FileSystemError::from_io(&error, &file_path))?;
+
+        if resolved.starts_with(&root) {
+            Ok(resolved)
+        } else {
+            Err(FileSystemError::Validation {
+                path: file_path,
+                reason: format!("Path escapes the project root '{}'", root.display()),
+            })
+        }
+    }
+
+    /// Lists the files below a directory within the root, at any depth.
+    ///
+    /// The directory is resolved like `resolve_within_root`, and so are the
+    /// files, so symlinks leading out of the root are refused. Each directory
+    /// is walked once, by its canonical path, so a symlink back to an ancestor
+    /// does not loop, and dangling symlinks are skipped.
+    ///
+    /// # Parameters
+    ///
+    /// * `relative_dir_path` - The directory reference, relative to the root
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Vec<PathBuf>)` - The canonical paths of the files, sorted
+    /// * `Err(FileSystemError::NotADirectory)` - If the reference is a file
+    /// * `Err(FileSystemError)` - If the directory lies outside the root or cannot be read
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use json_echo_core::FileSystemManager;
+    ///
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// let fs_manager = FileSystemManager::new(None)?;
+    ///
+    /// for file in fs_manager.list_files("routes").await? {
+    ///     println!("{}", file.display());
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn list_files(&self, relative_dir_path: &str) -> FileSystemResult<Vec<PathBuf>> {
+        let dir = self.resolve_within_root(relative_dir_path).await?;
+        if !dir.is_dir() {
+            return Err(FileSystemError::NotADirectory { path: dir });
+        }
+
+        let mut files = Vec::new();
+        let mut visited = HashSet::from([dir.clone()]);
+        let mut pending = vec![dir];
+        while let Some(dir) = pending.pop() {
+            let io_error = |error: std::io::Error| FileSystemError::from_io(&error, &dir);
+            let mut entries = tokio::fs::read_dir(&dir).await.map_err(io_error)?;
+            while let Some(entry) = entries.next_entry().await.map_err(io_error)? {
+                let path = match self
+                    .resolve_within_root(entry.path().to_string_lossy().as_ref())
+                    .await
+                {
+                    Ok(path) => path,
+                    Err(FileSystemError::NotFound { path }) => {
+                        log::warn!("Skipping {}, which does not resolve", path.display());
+                        continue;
+                    }
+                    Err(error) => return Err(error),
+                };
+                if path.is_dir() {
+                    if visited.insert(path.clone()) {
+                        pending.push(path);
+                    }
+                } else {
+                    files.push(path);
+                }
+            }
+        }
+
+        // A file linked from several directories is listed once
+        files.sort();
+        files.dedup();
+        Ok(files)
+    }
+
+    /// Creates a directory below the root, refusing paths outside of it.
+    ///
+    /// The reference is checked lexically before anything is created, so `..`
+    /// components and absolute paths never create directories outside the
+    /// root. Missing parents are created, and the result is canonicalized like
+    /// `resolve_within_root` to catch symlinks leading out of the root.
+    ///
+    /// # Parameters
+    ///
+    /// * `relative_dir_path` - The directory reference, relative to the root
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(PathBuf)` - The canonical path of the directory, inside the root
+    /// * `Err(FileSystemError::Validation)` - If the directory lies outside the root
+    /// * `Err(FileSystemError)` - If the directory cannot be created
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use json_echo_core::FileSystemManager;
+    ///
+    /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
+    /// let fs_manager = FileSystemManager::new(None)?;
+    ///
+    /// let uploads = fs_manager.create_dir_within_root("uploads/avatars").await?;
+    /// println!("Uploads go to {}", uploads.display());
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn create_dir_within_root(
+        &self,
+        relative_dir_path: &str,
+    ) -> FileSystemResult<PathBuf> {
+        let dir_path = self.root.as_path().join(relative_dir_path);
+
+        let root = PathUtils::normalize_path_no_symlinks(&self.root);
+        if !PathUtils::normalize_path_no_symlinks(&dir_path).starts_with(&root) {
+            return Err(FileSystemError::Validation {
+                path: dir_path,
+                reason: format!("Path escapes the project root '{}'", root.display()),
+            });
+        }
+        self.check_symlinks(&dir_path).await?;
+
+        tokio::fs::create_dir_all(&dir_path)
+            .await
+            .map_err(|error| FileSystemError::from_io(&error, &dir_path))?;
+
+        self.resolve_within_root(relative_dir_path).await
     }
 
     /// Asynchronously loads the contents of a file as a byte vector.
@@ -374,14 +811,9 @@ impl FileSystemManager {
     /// ```
     pub async fn load_file(&self, relative_file_path: &str) -> FileSystemResult<Vec<u8>> {
         let file_path = self.root.as_path().join(relative_file_path);
-        let mut file = File::open(file_path).await.map_err(FileSystemError::from)?;
+        self.check_symlinks(&file_path).await?;
 
-        let mut buffer = vec![];
-
-        file.read_to_end(&mut buffer)
-            .await
-            .map_err(FileSystemError::from)?;
-        Ok(buffer)
+        self.retry(|| read_file(&file_path)).await
     }
 
     /// Asynchronously saves byte data to a file.
@@ -438,13 +870,66 @@ impl FileSystemManager {
         content: Vec<u8>,
     ) -> FileSystemResult<()> {
         let file_path = self.root.as_path().join(relative_file_path);
-        let mut file = File::create(file_path)
-            .await
-            .map_err(FileSystemError::from)?;
+        self.check_symlinks(&file_path).await?;
+
+        self.retry(|| write_file(&file_path, &content)).await
+    }
+
+    /// Rejects a path crossing a symbolic link when the policy forbids them.
+    ///
+    /// Every component of the lexically normalized path below the root is
+    /// inspected without following links. Components that do not exist yet
+    /// are skipped, leaving the actual operation to report them.
+    async fn check_symlinks(&self, file_path: &Path) -> FileSystemResult<()> {
+        if self.symlinks == SymlinkPolicy::Follow {
+            return Ok(());
+        }
+
+        let normalized = PathUtils::normalize_path_no_symlinks(file_path);
+        let stop = normalized
+            .starts_with(&self.root)
+            .then_some(self.root.as_path());
+
+        for ancestor in normalized.ancestors() {
+            if Some(ancestor) == stop || ancestor.as_os_str().is_empty() {
+                break;
+            }
+
+            if let Ok(metadata) = tokio::fs::symlink_metadata(ancestor).await
+                && metadata.file_type().is_symlink()
+            {
+                return Err(FileSystemError::Validation {
+                    path: file_path.to_path_buf(),
+                    reason: format!("'{}' is a symbolic link", ancestor.display()),
+                });
+            }
+        }
 
-        file.write_all(&content)
-            .await
-            .map_err(FileSystemError::from)?;
         Ok(())
     }
 }
+
+/// Reads a whole file, reporting failures against its path.
+async fn read_file(file_path: &Path) -> FileSystemResult<Vec<u8>> {
+    let io_error = |error: std::io::Error| FileSystemError::from_io(&error, file_path);
+
+    let mut file = File::open(file_path).await.map_err(io_error)?;
+
+    let mut buffer = vec![];
+
+    file.read_to_end(&mut buffer).await.map_err(io_error)?;
+    Ok(buffer)
+}
+
+/// Creates or overwrites a file, reporting failures against its path.
+async fn write_file(file_path: &Path, content: &[u8]) -> FileSystemResult<()> {
+    let io_error = |error: std::io::Error| FileSystemError::from_io(&error, file_path);
+
+    let mut file = File::create(file_path).await.map_err(io_error)?;
+
+    file.write_all(content).await.map_err(io_error)?;
+
+    // Tokio files write in the background; flush so the data is on disk on return
+    file.flush().await.map_err(io_error)?;
+    Ok(())
+}
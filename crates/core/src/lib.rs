@@ -23,8 +23,16 @@ pub fn version() -> &'static str {
 }
 
 pub use config::{
-    BodyResponse, Config, ConfigManager, ConfigResponse, ConfigRoute, ConfigRouteResponse,
+    BodyResponse, CacheDirective, CachePreset, Charset, Config, ConfigManager, ConfigResponse,
+    ConfigRoute, ConfigRouteResponse, CorsConfig, EnvelopeSpec, ErrorConfig, FallbackConfig,
+    FallbackResponse, FieldSpec, IdStrategy, JobConfig, LatencyConfig, LatencyDistribution,
+    LogLevel, PathSegment, ProxyConfig, QueryParamSpec, QueryParamType, RedirectConfig,
+    RelationConfig, ResponseDelay, ResponseVariant, RetryAfter, SequenceMode, SqlConfig, TagFilter,
+    TlsConfig, TrailingSlash, WeightedResponse, load_route_response, path_segments, route_shape,
 };
 pub use database::{Database, Model};
 pub use errors::{Error, FileSystemError, FileSystemResult};
-pub use filesystem::{FileSystemManager, PathUtils};
+pub use filesystem::{
+    CONFIG_FILE_NAMES, CONFIG_NAMES_ENV_VAR, FileSystemManager, PathUtils, ROOT_ENV_VAR,
+    SymlinkPolicy,
+};
@@ -31,10 +31,12 @@
 //! - Correct handling of external file references
 //! - Robust error handling for various failure scenarios
 //! - Data integrity across configuration save/load cycles
+use indexmap::IndexMap;
 use json_echo_core::FileSystemError;
 use json_echo_core::{
     BodyResponse, Config, ConfigManager, ConfigResponse, ConfigRoute, ConfigRouteResponse,
-    FileSystemManager,
+    Database, FileSystemManager, JobConfig, LatencyConfig, ResponseDelay, RetryAfter, SequenceMode,
+    TagFilter,
 };
 use serde_json::{Value, json};
 use std::{
@@ -42,6 +44,7 @@ use std::{
     fs::{self, File},
     io::Write,
     path::Path,
+    time::Duration,
 };
 use tempfile::TempDir;
 
@@ -203,7 +206,10 @@ mod config_structure_tests {
 
         let config: Config = serde_json::from_value(json_config).expect("Should deserialize");
 
-        assert_eq!(config.port, Some(3001), "Should use default port");
+        assert_eq!(
+            config.port, None,
+            "Omitted port should be left for serve to resolve"
+        );
         assert_eq!(
             config.hostname,
             Some("localhost".to_string()),
@@ -226,7 +232,7 @@ mod config_structure_tests {
     /// and maintains all field values after serialization.
     #[test]
     fn test_config_serialization() {
-        let mut routes = HashMap::new();
+        let mut routes = IndexMap::new();
         routes.insert(
             "/test".to_string(),
             ConfigRoute {
@@ -239,6 +245,7 @@ mod config_structure_tests {
                     status: Some(201),
                     body: BodyResponse::Value(json!({"message": "created"})),
                 }),
+                ..ConfigRoute::default()
             },
         );
 
@@ -248,6 +255,7 @@ mod config_structure_tests {
             static_folder: Some("assets".to_string()),
             static_route: "/files".to_string(),
             routes,
+            ..Config::default()
         };
 
         let serialized = serde_json::to_value(&config).expect("Should serialize");
@@ -273,6 +281,27 @@ mod config_structure_tests {
             "Routes should be serialized as object"
         );
     }
+
+    /// Tests wrapping bodies in a response envelope.
+    ///
+    /// Verifies that every `{{body}}` placeholder is replaced, including inside
+    /// arrays, that other values are kept and that errors are opted out by default.
+    #[test]
+    fn test_envelope_wrap() {
+        let config: Config = serde_json::from_value(json!({
+            "envelope": {"template": {"data": "{{body}}", "meta": {"items": ["{{body}}", "x"]}}},
+            "routes": {}
+        }))
+        .expect("Should deserialize envelope");
+        let envelope = config.envelope.expect("Envelope should be set");
+
+        assert_eq!(
+            envelope.wrap(&json!({"id": 1})),
+            json!({"data": {"id": 1}, "meta": {"items": [{"id": 1}, "x"]}})
+        );
+        assert!(envelope.applies_to(204));
+        assert!(!envelope.applies_to(404));
+    }
 }
 
 mod config_route_tests {
@@ -394,6 +423,428 @@ mod config_route_tests {
             _ => panic!("Response should be ConfigRouteResponse"),
         }
     }
+
+    /// Tests ConfigRoute deserialization with a CORS override.
+    ///
+    /// Verifies that a route-level cors block is parsed and that omitted
+    /// CORS fields keep their defaults.
+    #[test]
+    fn test_config_route_cors_deserialization() {
+        let json_route = json!({
+            "cors": {
+                "allowed_origins": ["https://app.example.com"],
+                "allow_credentials": true
+            },
+            "response": {"status": 200, "body": {}}
+        });
+
+        let route: ConfigRoute = serde_json::from_value(json_route).expect("Should deserialize");
+        let cors = route.cors.expect("CORS settings should be present");
+
+        assert_eq!(
+            cors.allowed_origins,
+            Some(vec!["https://app.example.com".to_string()]),
+            "Allowed origins should match"
+        );
+        assert!(cors.allow_credentials, "Credentials should be allowed");
+        assert!(
+            cors.allowed_methods.is_none(),
+            "Omitted methods should fall back to defaults"
+        );
+        assert!(
+            ConfigRoute::default().cors.is_none(),
+            "Routes should not override CORS by default"
+        );
+    }
+
+    /// Tests query parameter specification parsing and validation.
+    ///
+    /// Verifies that bad values and missing required parameters are reported
+    /// by name, and that unknown parameters are only rejected in strict mode.
+    #[test]
+    fn test_config_route_validate_query() {
+        let mut route: ConfigRoute = serde_json::from_value(json!({
+            "query_params": {
+                "page": "int",
+                "ratio": "number",
+                "active": {"type": "bool", "required": true}
+            },
+            "response": {"status": 200, "body": []}
+        }))
+        .expect("Should deserialize query specification");
+
+        let query = |pairs: &[(&str, &str)]| -> HashMap<String, String> {
+            pairs
+                .iter()
+                .map(|(key, value)| ((*key).to_string(), (*value).to_string()))
+                .collect()
+        };
+
+        assert_eq!(
+            route.validate_query(&query(&[
+                ("active", "true"),
+                ("page", "2"),
+                ("ratio", "0.5")
+            ])),
+            Ok(())
+        );
+
+        let bad_int = route
+            .validate_query(&query(&[("active", "true"), ("page", "two")]))
+            .expect_err("Non-integer page should fail");
+        assert!(
+            bad_int.contains("'page'"),
+            "Error should name the param: {bad_int}"
+        );
+
+        let missing = route
+            .validate_query(&query(&[("page", "1")]))
+            .expect_err("Missing required param should fail");
+        assert!(
+            missing.contains("'active'"),
+            "Error should name the param: {missing}"
+        );
+
+        let extra = query(&[("active", "false"), ("debug", "1")]);
+        assert_eq!(
+            route.validate_query(&extra),
+            Ok(()),
+            "Unknown params allowed by default"
+        );
+
+        route.strict_query = true;
+        let unknown = route
+            .validate_query(&extra)
+            .expect_err("Unknown param should fail in strict mode");
+        assert!(
+            unknown.contains("'debug'"),
+            "Error should name the param: {unknown}"
+        );
+
+        assert_eq!(
+            ConfigRoute::default().validate_query(&query(&[("anything", "x")])),
+            Ok(()),
+            "Routes without a specification accept any query"
+        );
+    }
+
+    /// Tests filling field defaults and checking required fields of written payloads.
+    ///
+    /// Verifies that defaults only apply to absent keys, that arrays are processed
+    /// per object, and that missing required fields are reported by name.
+    #[test]
+    fn test_config_route_apply_fields_spec() {
+        let route: ConfigRoute = serde_json::from_value(json!({
+            "fields_spec": {
+                "name": {"required": true},
+                "role": {"default": "user"}
+            }
+        }))
+        .expect("Should deserialize fields spec");
+
+        assert_eq!(
+            route.apply_fields_spec(json!({"name": "Ada", "role": null})),
+            Ok(json!({"name": "Ada", "role": null})),
+            "Present keys keep their value, even null"
+        );
+        assert_eq!(
+            route.apply_fields_spec(json!([{"name": "Ada"}, {"name": "Bob", "role": "admin"}])),
+            Ok(json!([
+                {"name": "Ada", "role": "user"},
+                {"name": "Bob", "role": "admin"}
+            ]))
+        );
+
+        let missing = route
+            .apply_fields_spec(json!([{"name": "Ada"}, {"role": "admin"}]))
+            .expect_err("Missing required field should fail");
+        assert!(missing.contains("'name'"), "Unexpected error: {missing}");
+        assert!(route.apply_fields_spec(json!("text")).is_err());
+
+        let payload = json!({"anything": 1});
+        assert_eq!(
+            ConfigRoute::default().apply_fields_spec(payload.clone()),
+            Ok(payload),
+            "Routes without a specification keep the payload"
+        );
+    }
+
+    /// Tests picking sequence entries by call count.
+    ///
+    /// Verifies that `once` sequences repeat their last entry, `loop` sequences
+    /// wrap around, and routes without a sequence or with an empty one pick nothing.
+    #[test]
+    fn test_config_route_sequence_response() {
+        let mut route: ConfigRoute = serde_json::from_value(json!({
+            "sequence": [{"status": 503, "body": {}}, {"status": 200, "body": {}}]
+        }))
+        .expect("Should deserialize sequence");
+        let status =
+            |route: &ConfigRoute, call| route.sequence_response(call).and_then(|r| r.status);
+
+        assert_eq!(route.sequence_mode, None, "Mode should default to once");
+        assert_eq!(status(&route, 0), Some(503));
+        assert_eq!(status(&route, 1), Some(200));
+        assert_eq!(status(&route, 7), Some(200));
+
+        route.sequence_mode = Some(SequenceMode::Loop);
+        assert_eq!(status(&route, 2), Some(503));
+        assert_eq!(status(&route, 3), Some(200));
+
+        route.sequence = Some(Vec::new());
+        assert!(route.sequence_response(0).is_none());
+        assert!(ConfigRoute::default().sequence_response(0).is_none());
+    }
+
+    /// Tests resolving response delays by status.
+    ///
+    /// Verifies that exact statuses win over status classes, which win over
+    /// the default entry, and that a fixed delay applies to every status.
+    #[test]
+    fn test_response_delay_for_status() {
+        let route: ConfigRoute = serde_json::from_value(json!({
+            "delay": {"2xx": 200, "5xx": 20, "503": 5},
+            "response": {"status": 200, "body": []}
+        }))
+        .expect("Should deserialize delay map");
+        let delay = route.delay.expect("Delay should be set");
+
+        assert_eq!(delay.for_status(200), Some(Duration::from_millis(200)));
+        assert_eq!(delay.for_status(500), Some(Duration::from_millis(20)));
+        assert_eq!(delay.for_status(503), Some(Duration::from_millis(5)));
+        assert_eq!(
+            delay.for_status(404),
+            None,
+            "Unmatched status is not delayed"
+        );
+
+        let with_default: ResponseDelay =
+            serde_json::from_value(json!({"2xx": 100, "default": 50})).expect("Should deserialize");
+        assert_eq!(
+            with_default.for_status(404),
+            Some(Duration::from_millis(50))
+        );
+
+        let fixed: ResponseDelay = serde_json::from_value(json!(75)).expect("Should deserialize");
+        assert_eq!(fixed.for_status(201), Some(Duration::from_millis(75)));
+        assert_eq!(fixed.for_status(500), Some(Duration::from_millis(75)));
+    }
+
+    /// Tests selecting routes by their tags.
+    ///
+    /// Verifies that a route matches when it has any of the filter's tags and
+    /// that untagged routes are only kept when the filter includes them.
+    #[test]
+    fn test_tag_filter_retain() {
+        let mut config: Config = serde_json::from_value(json!({
+            "routes": {
+                "[GET] /login": {"tags": ["auth"], "response": {"body": {}}},
+                "[GET] /users": {"tags": ["users", "admin"], "response": {"body": []}},
+                "[GET] /orders": {"tags": ["orders"], "response": {"body": []}},
+                "[GET] /health": {"tags": [], "response": {"body": "ok"}}
+            }
+        }))
+        .expect("Should deserialize tagged routes");
+
+        let mut filter = TagFilter {
+            tags: vec!["admin".to_string(), "auth".to_string()],
+            include_untagged: true,
+        };
+        assert!(filter.matches(&config.routes["[GET] /health"]));
+
+        filter.include_untagged = false;
+        assert_eq!(filter.retain(&mut config), 2);
+
+        let routes: Vec<&str> = config.routes.keys().map(String::as_str).collect();
+        assert_eq!(routes, ["[GET] /login", "[GET] /users"]);
+    }
+    /// Tests picking variants by matching the request body.
+    ///
+    /// Verifies partial object matching at any depth, element-wise array
+    /// matching, and that a variant without `when_body` always matches.
+    #[test]
+    fn test_config_route_variant_response() {
+        let route: ConfigRoute = serde_json::from_value(json!({
+            "method": "POST",
+            "variants": [
+                {"when_body": {"user": {"role": "admin"}}, "status": 200, "body": {}},
+                {"when_body": {"tags": ["a", "b"]}, "status": 202, "body": {}},
+                {"status": 401, "body": {}}
+            ]
+        }))
+        .expect("Should deserialize variants");
+        let status = |payload: Value| route.variant_response(&payload).and_then(|r| r.status);
+
+        assert_eq!(
+            status(json!({"user": {"role": "admin", "id": 1}})),
+            Some(200)
+        );
+        assert_eq!(status(json!({"tags": ["a", "b"]})), Some(202));
+        assert_eq!(status(json!({"tags": ["a"]})), Some(401));
+        assert_eq!(status(json!({"user": "admin"})), Some(401));
+        assert!(
+            ConfigRoute::default()
+                .variant_response(&json!({}))
+                .is_none()
+        );
+    }
+
+    /// Tests picking responses of a weighted pool by roll.
+    ///
+    /// Verifies that every response takes a share proportional to its weight
+    /// and that zero weights are skipped.
+    #[test]
+    fn test_config_route_weighted_response() {
+        let route: ConfigRoute = serde_json::from_value(json!({
+            "responses": [
+                {"weight": 80, "response": {"status": 200, "body": {}}},
+                {"weight": 0, "response": {"status": 418, "body": {}}},
+                {"weight": 20, "response": {"status": 500, "body": {}}}
+            ]
+        }))
+        .expect("Should deserialize responses");
+        let status = |roll: f64| route.weighted_response(roll).and_then(|r| r.status);
+
+        assert_eq!(status(0.0), Some(200));
+        assert_eq!(status(0.79), Some(200));
+        assert_eq!(status(0.8), Some(500));
+        assert_eq!(status(0.999), Some(500));
+        assert!(ConfigRoute::default().weighted_response(0.5).is_none());
+    }
+
+    /// Tests picking localized bodies by `Accept-Language`.
+    ///
+    /// Verifies that ranges are tried by quality, then in order, that tags
+    /// match ignoring case and fall back to their prefix, and that zero
+    /// qualities and `*` are not matched.
+    #[test]
+    fn test_config_route_localized_body() {
+        let route: ConfigRoute = serde_json::from_value(json!({
+            "localized": {"fr": "Bonjour", "pt-BR": "Olá", "de": "Hallo"}
+        }))
+        .expect("Should deserialize localized bodies");
+        let tag = |accept_language: &str| route.localized_body(accept_language).map(|(tag, _)| tag);
+
+        assert_eq!(tag("fr"), Some("fr"));
+        assert_eq!(tag("FR-ca"), Some("fr"));
+        assert_eq!(tag("pt-br"), Some("pt-BR"));
+        assert_eq!(tag("fr;q=0.5, de;q=0.8"), Some("de"));
+        assert_eq!(tag("de, fr"), Some("de"));
+        assert_eq!(tag("es, fr;q=bad, pt-BR-x-test;q=0.1"), Some("pt-BR"));
+        assert_eq!(tag("de;q=0, es"), None);
+        assert_eq!(tag("*, fr;q=0.5"), None);
+        assert_eq!(tag(""), None);
+        assert!(ConfigRoute::default().localized_body("fr").is_none());
+    }
+
+    /// Tests sampling latencies from a distribution.
+    ///
+    /// Verifies that percentile distributions pass through their points, and
+    /// that log-normal samples keep the configured mean and standard
+    /// deviation, with a median below the mean and a long right tail.
+    #[test]
+    fn test_latency_config_sample() {
+        use rand::{SeedableRng, rngs::StdRng};
+
+        let percentiles: LatencyConfig = serde_json::from_value(json!({
+            "distribution": "percentiles", "p50": 80, "p90": 200, "p99": 800
+        }))
+        .expect("Should deserialize percentiles");
+        let millis = |roll: f64| percentiles.percentile(roll).as_secs_f64() * 1000.0;
+
+        assert!((millis(0.25) - 40.0).abs() < 1e-6);
+        assert!((millis(0.5) - 80.0).abs() < 1e-6);
+        assert!((millis(0.99) - 800.0).abs() < 1e-6);
+        assert!(millis(0.999) > 800.0);
+
+        let log_normal: LatencyConfig = serde_json::from_value(json!({
+            "distribution": "log_normal", "mean": 120, "stddev": 40
+        }))
+        .expect("Should deserialize log-normal");
+        let mut rng = StdRng::seed_from_u64(42);
+        let mut samples: Vec<f64> = (0..20_000)
+            .map(|_| log_normal.sample(&mut rng).as_secs_f64() * 1000.0)
+            .collect();
+        samples.sort_by(f64::total_cmp);
+
+        #[allow(clippy::cast_precision_loss)]
+        let count = samples.len() as f64;
+        let mean = samples.iter().sum::<f64>() / count;
+        let stddev = (samples
+            .iter()
+            .map(|sample| (sample - mean).powi(2))
+            .sum::<f64>()
+            / count)
+            .sqrt();
+        assert!((mean - 120.0).abs() < 2.0, "Mean was {mean}");
+        assert!(
+            (stddev - 40.0).abs() < 2.0,
+            "Standard deviation was {stddev}"
+        );
+        assert!(
+            samples[samples.len() / 2] < 120.0,
+            "The median is below the mean"
+        );
+        assert!(samples[0] > 0.0, "Log-normal latencies are positive");
+        assert!(
+            samples[samples.len() * 999 / 1000] - mean > mean - samples[samples.len() / 1000],
+            "The right tail is longer than the left one"
+        );
+    }
+
+    /// Tests rendering both forms of `retry_after` as a header value.
+    ///
+    /// Verifies that seconds are sent as is, that HTTP dates and RFC 3339
+    /// times are both rendered as HTTP dates in UTC, and that other dates,
+    /// Unix seconds included, are rejected.
+    #[test]
+    fn test_retry_after_header_value() {
+        assert_eq!(
+            RetryAfter::Seconds(120)
+                .header_value()
+                .expect("Seconds should render"),
+            "120"
+        );
+
+        for date in [
+            "2026-10-14T09:30:00Z",
+            "2026-10-14T11:30:00+02:00",
+            "Wed, 14 Oct 2026 09:30:00 GMT",
+        ] {
+            assert_eq!(
+                RetryAfter::Date(date.to_string())
+                    .header_value()
+                    .expect("Date should render"),
+                "Wed, 14 Oct 2026 09:30:00 GMT"
+            );
+        }
+
+        for date in ["tomorrow", "1791970200", "Wed, 14 Foo 2026 09:30:00 GMT"] {
+            assert!(RetryAfter::Date(date.to_string()).header_value().is_err());
+        }
+    }
+
+    /// Tests building the status URL of a job.
+    ///
+    /// Verifies that the location's parameter segment receives the id, and that
+    /// a location without a parameter gets the id appended.
+    #[test]
+    fn test_job_location_for() {
+        let route: ConfigRoute = serde_json::from_value(json!({
+            "method": "POST",
+            "job": {"location": "/api/jobs/{id}/status"},
+            "response": {"body": {}}
+        }))
+        .expect("Should deserialize job");
+        let job = route.job.expect("Job should be set");
+
+        assert_eq!(job.location_for("abc"), "/api/jobs/abc/status");
+
+        let job = JobConfig {
+            location: "/api/jobs/".to_string(),
+        };
+        assert_eq!(job.location_for("abc"), "/api/jobs/abc");
+    }
 }
 
 mod body_response_tests {
@@ -705,6 +1156,133 @@ mod config_manager_tests {
         }
     }
 
+    /// Tests configuration loading with a JSON5 external response file.
+    ///
+    /// Verifies that files with a `.json5` extension may contain comments,
+    /// unquoted keys and trailing commas, and that invalid JSON5 is reported.
+    #[tokio::test]
+    async fn test_config_manager_load_config_with_json5_file() {
+        let temp_dir = setup_test_dir();
+        let temp_path = temp_dir.path();
+
+        create_test_file(
+            temp_path,
+            "users_response.json5",
+            br#"{
+                // Served for the users listing
+                status: 200,
+                body: [
+                    {id: 1, name: 'John'},
+                    {id: 2, name: "Jane"}, // trailing comma
+                ],
+            }"#,
+        );
+
+        let config_json = json!({
+            "routes": {
+                "/api/users": {"response": "users_response.json5"}
+            }
+        });
+        create_test_file(temp_path, "config.json", config_json.to_string().as_bytes());
+
+        let fs_manager = FileSystemManager::new(Some(temp_path.to_path_buf()))
+            .expect("Should create filesystem manager");
+        let mut config_manager = ConfigManager::new(fs_manager);
+
+        config_manager
+            .load_config("config.json")
+            .await
+            .expect("Should load configuration with a JSON5 file");
+
+        let route = config_manager
+            .config
+            .routes
+            .get("[GET] /api/users")
+            .expect("Route should exist");
+
+        match &route.response {
+            ConfigResponse::ConfigRouteResponse(response) => {
+                assert_eq!(response.status, Some(200));
+                match &response.body {
+                    BodyResponse::Value(value) => assert_eq!(
+                        value,
+                        &json!([{"id": 1, "name": "John"}, {"id": 2, "name": "Jane"}])
+                    ),
+                    _ => panic!("Body should be JSON value"),
+                }
+            }
+            _ => panic!("Response should be resolved to ConfigRouteResponse"),
+        }
+
+        create_test_file(
+            temp_path,
+            "users_response.json5",
+            b"{ status: 200, body: [ }",
+        );
+        let result = config_manager.load_config("config.json").await;
+        assert!(result.is_err(), "Invalid JSON5 should fail to load");
+    }
+
+    /// Tests configuration loading from raw bytes.
+    ///
+    /// Verifies that ConfigManager can load an inline configuration without a
+    /// config file, normalizing routes and resolving external file references
+    /// against the filesystem root.
+    #[tokio::test]
+    async fn test_config_manager_load_config_from_bytes() {
+        let temp_dir = setup_test_dir();
+        let temp_path = temp_dir.path();
+
+        create_test_file(
+            temp_path,
+            "users_response.json",
+            json!({"status": 200, "body": [{"id": 1}]})
+                .to_string()
+                .as_bytes(),
+        );
+
+        let config_json = json!({
+            "port": 4000,
+            "routes": {
+                "/api/health": {
+                    "response": {"status": 200, "body": {"ok": true}}
+                },
+                "[POST] /api/users": {
+                    "response": "users_response.json"
+                }
+            }
+        });
+
+        let fs_manager = FileSystemManager::new(Some(temp_path.to_path_buf()))
+            .expect("Should create filesystem manager");
+        let mut config_manager = ConfigManager::new(fs_manager);
+
+        let result = config_manager
+            .load_config_from_bytes(config_json.to_string().as_bytes())
+            .await;
+        assert!(result.is_ok(), "Should load configuration from bytes");
+
+        assert_eq!(config_manager.config.port, Some(4000));
+        assert_eq!(config_manager.config.routes.len(), 2);
+        assert!(
+            config_manager
+                .config
+                .routes
+                .contains_key("[GET] /api/health"),
+            "Route without method should default to GET"
+        );
+
+        let route = config_manager
+            .config
+            .routes
+            .get("[POST] /api/users")
+            .expect("Route should exist");
+        assert!(
+            matches!(route.response, ConfigResponse::ConfigRouteResponse(_)),
+            "External file reference should be resolved"
+        );
+    }
+
     /// Tests configuration loading failure when file doesn't exist.
     ///
     /// Verifies that ConfigManager returns appropriate error when trying
@@ -743,6 +1321,101 @@ mod config_manager_tests {
         assert!(result.is_err(), "Should fail when JSON content is invalid");
     }
 
+    /// Tests that malformed JSON is reported with its file, line and column.
+    ///
+    /// Verifies the location of a syntax error in the configuration itself
+    /// and in an external response file it references.
+    #[tokio::test]
+    async fn test_config_manager_reports_parse_location() {
+        let temp_dir = setup_test_dir();
+        let temp_path = temp_dir.path();
+
+        create_test_file(
+            temp_path,
+            "broken.json",
+            b"{\n  \"routes\": {\n    \"/users\": {\"response\": 'users.json'}\n  }\n}\n",
+        );
+        create_test_file(
+            temp_path,
+            "config.json",
+            br#"{"routes": {"/users": {"response": "users.json"}}}"#,
+        );
+        create_test_file(
+            temp_path,
+            "users.json",
+            b"{\n  \"status\": 200,\n  \"body\": [,]\n}",
+        );
+
+        let fs_manager = FileSystemManager::new(Some(temp_path.to_path_buf()))
+            .expect("Should create filesystem manager");
+        let mut config_manager = ConfigManager::new(fs_manager);
+
+        match config_manager.load_config("broken.json").await {
+            Err(FileSystemError::ConfigParse {
+                path, line, column, ..
+            }) => {
+                assert_eq!(path, temp_path.join("broken.json"));
+                assert_eq!((line, column), (3, 28));
+            }
+            other => panic!("Expected a ConfigParse error, got {other:?}"),
+        }
+
+        match config_manager.load_config("config.json").await {
+            Err(FileSystemError::ConfigParse { path, line, .. }) => {
+                assert_eq!(path, temp_path.join("users.json"));
+                assert_eq!(line, 3);
+            }
+            other => panic!("Expected a ConfigParse error, got {other:?}"),
+        }
+    }
+
+    /// Tests that empty and mismatched response files are reported with their problem.
+    ///
+    /// Verifies that an empty file, a bare array or object body and an object
+    /// whose fields have the wrong types each name the referenced file and
+    /// what is wrong.
+    #[tokio::test]
+    async fn test_config_manager_reports_invalid_response_files() {
+        let temp_dir = setup_test_dir();
+        let temp_path = temp_dir.path();
+        let fs_manager = FileSystemManager::new(Some(temp_path.to_path_buf()))
+            .expect("Should create filesystem manager");
+        let mut config_manager = ConfigManager::new(fs_manager);
+
+        create_test_file(
+            temp_path,
+            "config.json",
+            br#"{"routes": {"/users": {"response": "users.json"}}}"#,
+        );
+
+        for (content, expected) in [
+            (&b"  \n"[..], "the file is empty"),
+            (
+                &br#"[{"id": 1}]"#[..],
+                "expected an object with `status` and `body`, found a JSON array",
+            ),
+            (&br#"{"id": 1}"#[..], "unexpected field `id`"),
+            (
+                &br#"{"status": 200, "body": [], "headers": {}}"#[..],
+                "unexpected field `headers`",
+            ),
+            (
+                &br#"{"status": "ok", "body": []}"#[..],
+                "not a valid route response",
+            ),
+        ] {
+            create_test_file(temp_path, "users.json", content);
+
+            match config_manager.load_config("config.json").await {
+                Err(FileSystemError::ResponseFile { path, reason }) => {
+                    assert_eq!(path, temp_path.join("users.json"));
+                    assert!(reason.contains(expected), "{reason}");
+                }
+                other => panic!("Expected a ResponseFile error, got {other:?}"),
+            }
+        }
+    }
+
     /// Tests configuration loading failure with empty routes.
     ///
     /// Verifies that ConfigManager returns appropriate error when trying
@@ -786,9 +1459,664 @@ mod config_manager_tests {
         }
     }
 
-    /// Tests configuration saving functionality.
+    /// Tests that routes under the reserved `/__admin` prefix are rejected at load.
     ///
-    /// Verifies that ConfigManager can serialize and save configuration
+    /// Verifies that admin paths are refused whatever their method, while paths
+    /// merely starting with the same letters still load.
+    #[tokio::test]
+    async fn test_config_manager_rejects_admin_routes() {
+        let fs_manager = FileSystemManager::new(None).expect("Should create filesystem manager");
+        let mut config_manager = ConfigManager::new(fs_manager);
+
+        for path in [
+            "/__admin/requests",
+            "[DELETE] /__admin/maintenance",
+            "/__admin",
+        ] {
+            let config = json!({"routes": {path: {"response": {"status": 200, "body": []}}}});
+            match config_manager
+                .load_config_from_bytes(config.to_string().as_bytes())
+                .await
+            {
+                Err(FileSystemError::Operation(msg)) => {
+                    assert!(msg.contains("reserved"), "Unexpected error: {msg}");
+                }
+                other => panic!("Expected {path} to be rejected, got {other:?}"),
+            }
+        }
+
+        let config = json!({"routes": {"/__administrators": {"response": {"status": 200}}}});
+        config_manager
+            .load_config_from_bytes(config.to_string().as_bytes())
+            .await
+            .expect("Paths only sharing the prefix's letters should load");
+    }
+
+    /// Tests validation of route aliases and redirects at load time.
+    ///
+    /// Verifies that aliases are normalized to route identifiers, and that
+    /// circular aliases, unknown alias targets and non-3xx redirects are rejected.
+    #[tokio::test]
+    async fn test_config_manager_validates_aliases_and_redirects() {
+        let fs_manager = FileSystemManager::new(None).expect("Should create filesystem manager");
+        let mut config_manager = ConfigManager::new(fs_manager);
+
+        let load = |routes: Value| json!({"routes": routes}).to_string();
+
+        config_manager
+            .load_config_from_bytes(
+                load(json!({
+                    "/users": {"response": {"status": 200, "body": []}},
+                    "/v1/users": {"alias": "/users"},
+                    "/v0/users": {"alias": "[get] /v1/users"},
+                    "/old": {"redirect": {"to": "/users", "status": 301}}
+                }))
+                .as_bytes(),
+            )
+            .await
+            .expect("Valid aliases and redirects should load");
+
+        let routes = &config_manager.config.routes;
+        assert_eq!(
+            routes["[GET] /v1/users"].alias.as_deref(),
+            Some("[GET] /users"),
+            "Plain alias paths should resolve against the route's method"
+        );
+        assert_eq!(
+            routes["[GET] /v0/users"].alias.as_deref(),
+            Some("[GET] /v1/users")
+        );
+
+        let circular = config_manager
+            .load_config_from_bytes(
+                load(json!({
+                    "/a": {"alias": "/b"},
+                    "/b": {"alias": "/c"},
+                    "/c": {"alias": "/a"}
+                }))
+                .as_bytes(),
+            )
+            .await;
+        match circular {
+            Err(FileSystemError::Operation(msg)) => {
+                assert!(msg.contains("Circular alias"), "Unexpected error: {msg}");
+            }
+            other => panic!("Expected a circular alias error, got {other:?}"),
+        }
+
+        let unknown = config_manager
+            .load_config_from_bytes(load(json!({"/a": {"alias": "/missing"}})).as_bytes())
+            .await;
+        match unknown {
+            Err(FileSystemError::Operation(msg)) => {
+                assert!(msg.contains("[GET] /missing"), "Unexpected error: {msg}");
+            }
+            other => panic!("Expected an unknown alias error, got {other:?}"),
+        }
+
+        let bad_redirect = config_manager
+            .load_config_from_bytes(
+                load(json!({"/a": {"redirect": {"to": "/b", "status": 200}}})).as_bytes(),
+            )
+            .await;
+        assert!(
+            bad_redirect.is_err(),
+            "Non-3xx redirects should be rejected"
+        );
+    }
+
+    /// Tests that relation targets are normalized to GET routes and validated.
+    #[tokio::test]
+    async fn test_config_manager_validates_relations() {
+        let fs_manager = FileSystemManager::new(None).expect("Should create filesystem manager");
+        let mut config_manager = ConfigManager::new(fs_manager);
+
+        let load = |routes: Value| json!({"routes": routes}).to_string();
+
+        config_manager
+            .load_config_from_bytes(
+                load(json!({
+                    "/posts": {
+                        "relations": {"comments": {"route": "/comments", "foreign_key": "postId"}},
+                        "response": {"status": 200, "body": []}
+                    },
+                    "/comments": {"response": {"status": 200, "body": []}}
+                }))
+                .as_bytes(),
+            )
+            .await
+            .expect("Valid relations should load");
+
+        let relations = config_manager.config.routes["[GET] /posts"]
+            .relations
+            .as_ref()
+            .expect("Relations should be kept");
+        assert_eq!(relations["comments"].route, "[GET] /comments");
+
+        let unknown = config_manager
+            .load_config_from_bytes(
+                load(json!({
+                    "/posts": {
+                        "relations": {"author": {"route": "/users", "foreign_key": "userId"}},
+                        "response": {"status": 200, "body": []}
+                    }
+                }))
+                .as_bytes(),
+            )
+            .await;
+        match unknown {
+            Err(FileSystemError::Operation(msg)) => {
+                assert!(msg.contains("[GET] /users"), "Unexpected error: {msg}");
+            }
+            other => panic!("Expected an unknown relation error, got {other:?}"),
+        }
+    }
+
+    /// Tests that error routes default to 500 and reject non-error statuses.
+    #[tokio::test]
+    async fn test_config_manager_validates_error_routes() {
+        let fs_manager = FileSystemManager::new(None).expect("Should create filesystem manager");
+        let mut config_manager = ConfigManager::new(fs_manager);
+
+        let load = |error: Value| json!({"routes": {"/down": {"error": error}}}).to_string();
+
+        config_manager
+            .load_config_from_bytes(load(json!({"code": "BOOM"})).as_bytes())
+            .await
+            .expect("Error routes without a response should load");
+        let error = config_manager.config.routes["[GET] /down"]
+            .error
+            .as_ref()
+            .expect("Error settings should be kept");
+        assert_eq!(error.status, 500);
+        assert_eq!(error.code.as_deref(), Some("BOOM"));
+
+        let result = config_manager
+            .load_config_from_bytes(load(json!({"status": 200})).as_bytes())
+            .await;
+        assert!(result.is_err(), "Non-error statuses should be rejected");
+    }
+
+    /// Tests that configured statuses are checked on load.
+    ///
+    /// Verifies that unusual three-digit statuses load unchanged and that
+    /// statuses outside 100..=999 are rejected wherever they are configured.
+    #[tokio::test]
+    async fn test_config_manager_validates_statuses() {
+        let fs_manager = FileSystemManager::new(None).expect("Should create filesystem manager");
+        let mut config_manager = ConfigManager::new(fs_manager);
+
+        config_manager
+            .load_config_from_bytes(
+                json!({"routes": {"/teapot": {"response": {"status": 299, "body": {}}}}})
+                    .to_string()
+                    .as_bytes(),
+            )
+            .await
+            .expect("A non-standard three-digit status should load");
+        match &config_manager.config.routes["[GET] /teapot"].response {
+            ConfigResponse::ConfigRouteResponse(response) => assert_eq!(response.status, Some(299)),
+            other => panic!("Expected an inline response, got {other:?}"),
+        }
+
+        let invalid = [
+            (
+                json!({"routes": {"/users": {"response": {"status": 1000, "body": []}}}}),
+                "Route [GET] /users answers with invalid status 1000",
+            ),
+            (
+                json!({"routes": {"/users": {"sequence": [{"status": 200}, {"status": 42}]}}}),
+                "Route [GET] /users answers with invalid status 42",
+            ),
+            (
+                json!({
+                    "routes": {"[POST] /users": {"variants": [{"status": 0, "body": {}}]}}
+                }),
+                "Route [POST] /users answers with invalid status 0",
+            ),
+            (
+                json!({"internal_error": {"status": 1500}, "routes": {"/users": {}}}),
+                "internal_error answers with invalid status 1500",
+            ),
+        ];
+        for (config, expected) in invalid {
+            match config_manager
+                .load_config_from_bytes(config.to_string().as_bytes())
+                .await
+            {
+                Err(FileSystemError::Operation(msg)) => {
+                    assert!(msg.contains(expected), "Unexpected error: {msg}");
+                }
+                other => panic!("Expected an invalid status error, got {other:?}"),
+            }
+        }
+    }
+
+    /// Tests that bodies are checked against their `response_schema` on load.
+    ///
+    /// Verifies that a body satisfying its schema loads, that a body violating
+    /// it, keywords such as `pattern` and `anyOf` included, fails the load with
+    /// the route and location of the violation, and that an invalid schema is
+    /// rejected.
+    #[tokio::test]
+    async fn test_config_manager_validates_response_schemas() {
+        let fs_manager = FileSystemManager::new(None).expect("Should create filesystem manager");
+        let mut config_manager = ConfigManager::new(fs_manager);
+
+        let schema = json!({
+            "type": "array",
+            "items": {
+                "type": "object",
+                "required": ["id", "email"],
+                "properties": {
+                    "id": {"type": "integer", "minimum": 1},
+                    "email": {"type": "string", "pattern": "@"},
+                    "role": {"enum": ["admin", "user"]},
+                    "tags": {"items": {"anyOf": [{"type": "string"}, {"type": "null"}]}}
+                }
+            }
+        });
+        let load = |body: Value| {
+            json!({
+                "routes": {
+                    "/users": {"response_schema": schema, "response": {"status": 200, "body": body}}
+                }
+            })
+            .to_string()
+        };
+
+        config_manager
+            .load_config_from_bytes(load(json!([{"id": 1, "email": "ada@example.com"}])).as_bytes())
+            .await
+            .expect("A body matching its schema should load");
+
+        let invalid = [
+            (
+                json!([{"id": 1, "email": 42}]),
+                "$[0].email: 42 is not of type \"string\"",
+            ),
+            (json!([{"id": 1}]), "$[0]: \"email\" is a required property"),
+            (
+                json!([{"id": 0, "email": "a@b.c"}]),
+                "$[0].id: 0 is less than",
+            ),
+            (
+                json!([{"id": 1, "email": "a@b.c", "role": "root"}]),
+                "$[0].role: \"root\" is not one of",
+            ),
+            (
+                json!([{"id": 1, "email": "ada"}]),
+                "$[0].email: \"ada\" does not match",
+            ),
+            (
+                json!([{"id": 1, "email": "a@b.c", "tags": ["x", 1]}]),
+                "$[0].tags[1]: 1 is not valid under any of the schemas",
+            ),
+        ];
+        for (body, expected) in invalid {
+            match config_manager
+                .load_config_from_bytes(load(body).as_bytes())
+                .await
+            {
+                Err(FileSystemError::Operation(msg)) => {
+                    assert!(
+                        msg.contains("Route [GET] /users body does not match its response_schema")
+                            && msg.contains(expected),
+                        "Unexpected error: {msg}"
+                    );
+                }
+                other => panic!("Expected a schema violation, got {other:?}"),
+            }
+        }
+
+        let invalid_schema = json!({
+            "routes": {
+                "/users": {
+                    "response_schema": {"type": "array", "minItems": "one"},
+                    "response": {"status": 200, "body": []}
+                }
+            }
+        });
+        match config_manager
+            .load_config_from_bytes(invalid_schema.to_string().as_bytes())
+            .await
+        {
+            Err(FileSystemError::Operation(msg)) => assert!(
+                msg.contains("Route [GET] /users has an invalid response_schema"),
+                "Unexpected error: {msg}"
+            ),
+            other => panic!("Expected an invalid schema, got {other:?}"),
+        }
+    }
+
+    /// Tests shared body definitions referenced with `$ref`.
+    ///
+    /// Verifies that a reference is replaced by its definition, that nested
+    /// references inside bodies and definitions are resolved, and that
+    /// circular and unknown references are rejected.
+    #[tokio::test]
+    async fn test_config_manager_resolves_definitions() {
+        let fs_manager = FileSystemManager::new(None).expect("Should create filesystem manager");
+        let mut config_manager = ConfigManager::new(fs_manager);
+
+        let body = |config_manager: &ConfigManager, route: &str| -> Value {
+            match &config_manager.config.routes[route].response {
+                ConfigResponse::ConfigRouteResponse(response) => response.body.as_value(),
+                other => panic!("Expected an inline response, got {other:?}"),
+            }
+        };
+
+        config_manager
+            .load_config_from_bytes(
+                json!({
+                    "definitions": {
+                        "address": {"city": "Lisbon"},
+                        "user": {"id": 1, "address": {"$ref": "#/definitions/address"}}
+                    },
+                    "routes": {
+                        "/users/1": {"response": {"status": 200, "body": {"$ref": "#/definitions/user"}}},
+                        "/users": {"response": {"status": 200, "body": {
+                            "data": [{"$ref": "#/definitions/user"}, {"id": 2}]
+                        }}}
+                    }
+                })
+                .to_string()
+                .as_bytes(),
+            )
+            .await
+            .expect("References should resolve");
+
+        let user = json!({"id": 1, "address": {"city": "Lisbon"}});
+        assert_eq!(body(&config_manager, "[GET] /users/1"), user);
+        assert_eq!(
+            body(&config_manager, "[GET] /users"),
+            json!({"data": [user, {"id": 2}]})
+        );
+
+        let result = config_manager
+            .load_config_from_bytes(
+                json!({
+                    "definitions": {
+                        "a": {"next": {"$ref": "#/definitions/b"}},
+                        "b": {"next": {"$ref": "#/definitions/a"}}
+                    },
+                    "routes": {"/loop": {"response": {"status": 200, "body": {"$ref": "#/definitions/a"}}}}
+                })
+                .to_string()
+                .as_bytes(),
+            )
+            .await;
+        match result {
+            Err(FileSystemError::Operation(message)) => {
+                assert!(
+                    message.contains("Circular reference: a -> b -> a"),
+                    "{message}"
+                );
+            }
+            other => panic!("Expected a circular reference error, got {other:?}"),
+        }
+
+        let result = config_manager
+            .load_config_from_bytes(
+                br##"{"routes": {"/x": {"response": {"status": 200, "body": {"$ref": "#/definitions/missing"}}}}}"##,
+            )
+            .await;
+        assert!(result.is_err(), "Unknown definitions should be rejected");
+    }
+
+    /// Tests seeding route bodies from a separate data file.
+    ///
+    /// Verifies that seeded bodies override inline ones in the populated
+    /// database, that plain and bracketed identifiers are accepted, and that
+    /// identifiers without a route are ignored.
+    #[tokio::test]
+    async fn test_config_manager_applies_seed_file() {
+        let temp_dir = setup_test_dir();
+        let config = json!({
+            "seed_file": "seeds/dataset-a.json",
+            "routes": {
+                "/users": {"response": {"status": 200, "body": [{"id": 1, "name": "inline"}]}},
+                "[POST] /orders": {"response": {"status": 201, "body": {"inline": true}}},
+                "/health": {"response": {"status": 200, "body": "ok"}}
+            }
+        });
+        let seed = json!({
+            "/users": [{"id": 7, "name": "seeded"}],
+            "[POST] /orders": {"seeded": true},
+            "/missing": {"ignored": true}
+        });
+        create_test_file(
+            temp_dir.path(),
+            "json-echo.json",
+            config.to_string().as_bytes(),
+        );
+        fs::create_dir(temp_dir.path().join("seeds")).expect("Failed to create seeds folder");
+        create_test_file(
+            temp_dir.path(),
+            "seeds/dataset-a.json",
+            seed.to_string().as_bytes(),
+        );
+
+        let fs_manager = FileSystemManager::new(Some(temp_dir.path().to_path_buf()))
+            .expect("Should create filesystem manager");
+        let mut config_manager = ConfigManager::new(fs_manager);
+        config_manager
+            .load_config("json-echo.json")
+            .await
+            .expect("Config with seed should load");
+
+        let mut db = Database::new();
+        db.populate(config_manager.config.routes.clone());
+        let served = |key: &str| {
+            db.get_model(key)
+                .map(|model| model.get_value().into_owned())
+                .expect("Route should have a model")
+        };
+
+        assert_eq!(served("[GET] /users"), json!([{"id": 7, "name": "seeded"}]));
+        assert_eq!(served("[POST] /orders"), json!({"seeded": true}));
+        assert_eq!(served("[GET] /health"), json!("ok"));
+        assert!(db.get_model("[GET] /missing").is_none());
+    }
+
+    /// Tests that paths differing only in parameter names are rejected.
+    ///
+    /// Verifies that the error names both routes, and that methods sharing a
+    /// path with the same parameter names still load.
+    #[tokio::test]
+    async fn test_config_manager_rejects_conflicting_param_names() {
+        let fs_manager = FileSystemManager::new(None).expect("Should create filesystem manager");
+        let mut config_manager = ConfigManager::new(fs_manager);
+
+        let content = json!({
+            "routes": {
+                "/users/{id}": {"response": {"body": {}}},
+                "[DELETE] /users/{id}": {"response": {"body": {}}},
+                "/users/{id}/posts": {"response": {"body": []}}
+            }
+        });
+        config_manager
+            .load_config_from_bytes(content.to_string().as_bytes())
+            .await
+            .expect("Paths with the same parameter names should load");
+
+        let content = json!({
+            "routes": {
+                "/users/{id}": {"response": {"body": {}}},
+                "[DELETE] /users/{userId}": {"response": {"body": {}}}
+            }
+        });
+        let error = config_manager
+            .load_config_from_bytes(content.to_string().as_bytes())
+            .await
+            .expect_err("Conflicting parameter names should be rejected");
+        assert!(
+            error.to_string().contains(
+                "Routes [DELETE] /users/{userId} and [GET] /users/{id} register the same path"
+            ),
+            "Unexpected error: {error}"
+        );
+    }
+
+    /// Tests that payload transforms must be JSON pointers.
+    #[tokio::test]
+    async fn test_config_manager_rejects_invalid_pointer() {
+        let fs_manager = FileSystemManager::new(None).expect("Should create filesystem manager");
+        let mut config_manager = ConfigManager::new(fs_manager);
+        let content = json!({
+            "routes": {"[POST] /users": {"request_unwrap": "user", "response": {"body": {}}}}
+        });
+
+        let error = config_manager
+            .load_config_from_bytes(content.to_string().as_bytes())
+            .await
+            .expect_err("A pointer without a leading slash should be rejected");
+        assert!(
+            error.to_string().contains("invalid JSON pointer \"user\""),
+            "Unexpected error: {error}"
+        );
+    }
+
+    /// Tests loading routes from a directory of route files.
+    ///
+    /// Verifies that a file is keyed by its location and method, that a
+    /// `path` field takes precedence over the location, that routes from
+    /// files join the inline ones, and that a route defined twice is rejected.
+    #[tokio::test]
+    async fn test_config_manager_loads_routes_dir() {
+        let temp_dir = setup_test_dir();
+        let config = json!({
+            "routes_dir": "routes",
+            "routes": {"/health": {"response": {"status": 200, "body": "ok"}}}
+        });
+        create_test_file(
+            temp_dir.path(),
+            "json-echo.json",
+            config.to_string().as_bytes(),
+        );
+        fs::create_dir_all(temp_dir.path().join("routes/users"))
+            .expect("Failed to create routes folder");
+        create_test_file(
+            temp_dir.path(),
+            "routes/users/{id}.delete.json",
+            br#"{"response": {"status": 204, "body": {}}}"#,
+        );
+        create_test_file(
+            temp_dir.path(),
+            "routes/login.json5",
+            br#"{path: "/auth/login", method: "POST", response: {status: 200, body: {token: "abc"}}}"#,
+        );
+
+        let load = || async {
+            let fs_manager = FileSystemManager::new(Some(temp_dir.path().to_path_buf()))
+                .expect("Should create filesystem manager");
+            let mut config_manager = ConfigManager::new(fs_manager);
+            config_manager
+                .load_config("json-echo.json")
+                .await
+                .map(|()| config_manager.config)
+        };
+
+        let loaded = load().await.expect("Config with route files should load");
+        let keys: Vec<&str> = loaded.routes.keys().map(String::as_str).collect();
+        assert_eq!(
+            keys,
+            [
+                "[GET] /health",
+                "[POST] /auth/login",
+                "[DELETE] /users/{id}"
+            ]
+        );
+        let login = &loaded.routes["[POST] /auth/login"];
+        assert_eq!(login.method.as_deref(), Some("POST"));
+        match &login.response {
+            ConfigResponse::ConfigRouteResponse(response) => {
+                assert_eq!(response.body.as_value(), json!({"token": "abc"}));
+            }
+            other => panic!("Expected an inline response, got {other:?}"),
+        }
+
+        create_test_file(
+            temp_dir.path(),
+            "routes/health.get.json",
+            br#"{"response": {"status": 200, "body": "duplicate"}}"#,
+        );
+        match load().await {
+            Err(FileSystemError::RouteFile { path, reason }) => {
+                assert!(path.ends_with("routes/health.get.json"));
+                assert!(reason.contains("[GET] /health"));
+            }
+            other => panic!("Expected a RouteFile error, got {other:?}"),
+        }
+    }
+
+    /// Tests that data files next to route files are not loaded as routes.
+    ///
+    /// Verifies that a response file referenced by a route, the seed file and
+    /// a file holding a JSON array are skipped, while a malformed route object
+    /// still fails the load.
+    #[tokio::test]
+    async fn test_config_manager_skips_routes_dir_data_files() {
+        let temp_dir = setup_test_dir();
+        let config = json!({"routes_dir": "routes", "seed_file": "routes/seed.json"});
+        create_test_file(
+            temp_dir.path(),
+            "json-echo.json",
+            config.to_string().as_bytes(),
+        );
+        fs::create_dir_all(temp_dir.path().join("routes/data"))
+            .expect("Failed to create routes folder");
+        create_test_file(
+            temp_dir.path(),
+            "routes/users.get.json",
+            br#"{"response": "routes/data/users.json"}"#,
+        );
+        create_test_file(
+            temp_dir.path(),
+            "routes/data/users.json",
+            br#"{"status": 200, "body": [{"id": 1}]}"#,
+        );
+        create_test_file(
+            temp_dir.path(),
+            "routes/seed.json",
+            br#"{"[GET] /users": [{"id": 2}]}"#,
+        );
+        create_test_file(
+            temp_dir.path(),
+            "routes/data/fixtures.json",
+            br#"[{"id": 3}]"#,
+        );
+
+        let load = || async {
+            let fs_manager = FileSystemManager::new(Some(temp_dir.path().to_path_buf()))
+                .expect("Should create filesystem manager");
+            let mut config_manager = ConfigManager::new(fs_manager);
+            config_manager
+                .load_config("json-echo.json")
+                .await
+                .map(|()| config_manager.config)
+        };
+
+        let loaded = load().await.expect("Data files should be skipped");
+        let keys: Vec<&str> = loaded.routes.keys().map(String::as_str).collect();
+        assert_eq!(keys, ["[GET] /users"]);
+
+        create_test_file(
+            temp_dir.path(),
+            "routes/broken.json",
+            br#"{"response": {"status": "ok"}}"#,
+        );
+        match load().await {
+            Err(FileSystemError::RouteFile { path, .. }) => {
+                assert!(path.ends_with("routes/broken.json"));
+            }
+            other => panic!("Expected a RouteFile error, got {other:?}"),
+        }
+    }
+
+    /// Tests configuration saving functionality.
+    ///
+    /// Verifies that ConfigManager can serialize and save configuration
     /// objects to JSON files.
     #[tokio::test]
     async fn test_config_manager_save_config() {
@@ -800,7 +2128,7 @@ mod config_manager_tests {
         let config_manager = ConfigManager::new(fs_manager);
 
         // Create test configuration
-        let mut routes = HashMap::new();
+        let mut routes = IndexMap::new();
         routes.insert(
             "/api/test".to_string(),
             ConfigRoute {
@@ -813,6 +2141,7 @@ mod config_manager_tests {
                     status: Some(201),
                     body: BodyResponse::Value(json!({"message": "success"})),
                 }),
+                ..ConfigRoute::default()
             },
         );
 
@@ -822,6 +2151,7 @@ mod config_manager_tests {
             static_folder: Some("assets".to_string()),
             static_route: "/files".to_string(),
             routes,
+            ..Config::default()
         };
 
         let result = config_manager
@@ -911,6 +2241,41 @@ mod config_manager_tests {
         );
     }
 
+    /// Tests that external file references cannot escape the project root.
+    ///
+    /// Verifies that a response referencing a file through `..` is rejected
+    /// with a validation error instead of being read.
+    #[tokio::test]
+    async fn test_config_manager_rejects_path_traversal() {
+        let temp_dir = setup_test_dir();
+        let project = temp_dir.path().join("project");
+        fs::create_dir_all(&project).expect("Should create project directory");
+        create_test_file(
+            temp_dir.path(),
+            "secret.json",
+            br#"{"status": 200, "body": {"secret": true}}"#,
+        );
+        create_test_file(
+            &project,
+            "config.json",
+            br#"{"routes": {"/api/secret": {"response": "../secret.json"}}}"#,
+        );
+
+        let fs_manager =
+            FileSystemManager::new(Some(project)).expect("Should create filesystem manager");
+        let mut config_manager = ConfigManager::new(fs_manager);
+
+        match config_manager.load_config("config.json").await {
+            Err(FileSystemError::Validation { path, .. }) => {
+                assert!(
+                    path.ends_with("../secret.json"),
+                    "Unexpected path: {path:?}"
+                );
+            }
+            other => panic!("Expected a Validation error, got {other:?}"),
+        }
+    }
+
     /// Tests configuration loading with missing external files.
     ///
     /// Verifies that ConfigManager returns appropriate error when external
@@ -947,6 +2312,61 @@ mod config_manager_tests {
         assert!(result.is_err(), "Should fail when external file is missing");
     }
 
+    /// Tests that saving a configuration keeps its layout readable and ordered.
+    ///
+    /// Verifies that the saved file is pretty-printed and that routes and body
+    /// keys keep their original, non-alphabetical order through a load and save.
+    #[tokio::test]
+    async fn test_config_manager_save_preserves_order() {
+        let temp_dir = setup_test_dir();
+        let temp_path = temp_dir.path();
+
+        create_test_file(
+            temp_path,
+            "config.json",
+            br#"{"routes": {
+                "/zeta": {"response": {"status": 200, "body": {"name": "z", "id": 1}}},
+                "/alpha": {"response": {"status": 200, "body": []}},
+                "/mid": {"response": {"status": 200, "body": []}}
+            }}"#,
+        );
+
+        let fs_manager = FileSystemManager::new(Some(temp_path.to_path_buf()))
+            .expect("Should create filesystem manager");
+        let mut config_manager = ConfigManager::new(fs_manager);
+        config_manager
+            .load_config("config.json")
+            .await
+            .expect("Should load config");
+
+        let keys: Vec<&str> = config_manager
+            .config
+            .routes
+            .keys()
+            .map(String::as_str)
+            .collect();
+        assert_eq!(keys, ["[GET] /zeta", "[GET] /alpha", "[GET] /mid"]);
+
+        config_manager
+            .save_config("saved.json", &config_manager.config)
+            .await
+            .expect("Should save config");
+        let saved =
+            fs::read_to_string(temp_path.join("saved.json")).expect("Should read saved config");
+
+        assert!(
+            saved.contains("\n  \"routes\": {\n"),
+            "Should be pretty-printed:\n{saved}"
+        );
+        assert!(saved.ends_with("}\n"), "Should end with a newline");
+        let position = |needle: &str| saved.find(needle).expect("Saved config should contain key");
+        assert!(position("/zeta") < position("/alpha") && position("/alpha") < position("/mid"));
+        assert!(
+            position("\"name\": \"z\"") < position("\"id\": 1"),
+            "Body keys should keep their order:\n{saved}"
+        );
+    }
+
     /// Tests configuration round-trip (save and load).
     ///
     /// Verifies that a configuration can be saved and then loaded back
@@ -966,7 +2386,7 @@ mod config_manager_tests {
         headers.insert("Authorization".to_string(), "Bearer token".to_string());
         headers.insert("Content-Type".to_string(), "application/json".to_string());
 
-        let mut routes = HashMap::new();
+        let mut routes = IndexMap::new();
         routes.insert(
             "/api/users".to_string(),
             ConfigRoute {
@@ -984,6 +2404,7 @@ mod config_manager_tests {
                         ]
                     })),
                 }),
+                ..ConfigRoute::default()
             },
         );
 
@@ -999,6 +2420,7 @@ mod config_manager_tests {
                     status: Some(200),
                     body: BodyResponse::Value(json!({"status": "ok"})),
                 }),
+                ..ConfigRoute::default()
             },
         );
 
@@ -1008,6 +2430,7 @@ mod config_manager_tests {
             static_folder: Some("public".to_string()),
             static_route: "/assets".to_string(),
             routes,
+            ..Config::default()
         };
 
         // Save configuration
@@ -1077,4 +2500,354 @@ mod config_manager_tests {
             "Route results_field should match"
         );
     }
+
+    /// Tests that negative and all-zero response weights are rejected when loading.
+    #[tokio::test]
+    async fn test_config_manager_rejects_invalid_weights() {
+        let temp_dir = setup_test_dir();
+        let temp_path = temp_dir.path();
+        let fs_manager = FileSystemManager::new(Some(temp_path.to_path_buf()))
+            .expect("Should create filesystem manager");
+        let mut config_manager = ConfigManager::new(fs_manager);
+
+        for (weights, message) in [
+            (json!([-1, 2]), "non-negative"),
+            (json!([0, 0]), "non-zero weight"),
+        ] {
+            let responses: Vec<Value> = weights
+                .as_array()
+                .expect("Weights should be an array")
+                .iter()
+                .map(|weight| json!({"weight": weight, "response": {"status": 200, "body": {}}}))
+                .collect();
+            let config = json!({"routes": {"/api/flaky": {"responses": responses}}});
+            create_test_file(temp_path, "config.json", config.to_string().as_bytes());
+
+            let error = config_manager
+                .load_config("config.json")
+                .await
+                .expect_err("Invalid weights should be rejected");
+            assert!(error.to_string().contains(message), "{error}");
+        }
+    }
+
+    /// Tests that latency distributions that cannot be sampled are rejected when loading.
+    #[tokio::test]
+    async fn test_config_manager_rejects_invalid_latency() {
+        let temp_dir = setup_test_dir();
+        let temp_path = temp_dir.path();
+        let fs_manager = FileSystemManager::new(Some(temp_path.to_path_buf()))
+            .expect("Should create filesystem manager");
+        let mut config_manager = ConfigManager::new(fs_manager);
+
+        for (latency, message) in [
+            (
+                json!({"distribution": "log_normal", "mean": 0, "stddev": 10}),
+                "positive mean",
+            ),
+            (
+                json!({"distribution": "percentiles", "p50": 300, "p90": 200, "p99": 800}),
+                "p50 <= p90 <= p99",
+            ),
+        ] {
+            let config = json!({"routes": {"/api/slow": {
+                "latency": latency,
+                "response": {"status": 200, "body": {}}
+            }}});
+            create_test_file(temp_path, "config.json", config.to_string().as_bytes());
+
+            let error = config_manager
+                .load_config("config.json")
+                .await
+                .expect_err("Invalid latency should be rejected");
+            assert!(error.to_string().contains(message), "{error}");
+        }
+    }
+
+    /// Tests that jobs reporting on anything but a configured parameterized route are rejected.
+    #[tokio::test]
+    async fn test_config_manager_rejects_invalid_job_location() {
+        let temp_dir = setup_test_dir();
+        let temp_path = temp_dir.path();
+        let fs_manager = FileSystemManager::new(Some(temp_path.to_path_buf()))
+            .expect("Should create filesystem manager");
+        let mut config_manager = ConfigManager::new(fs_manager);
+
+        for location in ["/api/tasks/{id}", "/api/jobs"] {
+            let config = json!({"routes": {
+                "[POST] /api/exports": {"job": {"location": location}, "response": {"status": 200, "body": {}}},
+                "/api/jobs": {"response": {"status": 200, "body": []}},
+                "/api/jobs/{id}": {"response": {"status": 200, "body": {}}}
+            }});
+            create_test_file(temp_path, "config.json", config.to_string().as_bytes());
+
+            let error = config_manager
+                .load_config("config.json")
+                .await
+                .expect_err("An invalid job location should be rejected");
+            assert!(
+                error.to_string().contains(&format!(
+                    "Route [POST] /api/exports job location {location} is not a GET route"
+                )),
+                "{error}"
+            );
+        }
+    }
+
+    /// Tests that a `content_type` that is not a valid header value is rejected when loading.
+    #[tokio::test]
+    async fn test_config_manager_rejects_invalid_content_type() {
+        let temp_dir = setup_test_dir();
+        let temp_path = temp_dir.path();
+
+        create_test_file(
+            temp_path,
+            "config.json",
+            json!({"routes": {"/about": {
+                "content_type": "text/html\nX-Injected: 1",
+                "response": {"status": 200, "body": "<h1>About</h1>"}
+            }}})
+            .to_string()
+            .as_bytes(),
+        );
+
+        let fs_manager = FileSystemManager::new(Some(temp_path.to_path_buf()))
+            .expect("Should create filesystem manager");
+        let mut config_manager = ConfigManager::new(fs_manager);
+        let error = config_manager
+            .load_config("config.json")
+            .await
+            .expect_err("An invalid content_type should be rejected");
+
+        assert!(
+            error
+                .to_string()
+                .contains("Route [GET] /about has invalid content_type"),
+            "{error}"
+        );
+    }
+
+    /// Tests that `request_id_header` is parsed as a header name.
+    ///
+    /// Verifies that a valid name is kept, and that an invalid one fails the
+    /// load with an error naming it.
+    #[tokio::test]
+    async fn test_config_manager_validates_request_id_header() {
+        let temp_dir = setup_test_dir();
+        let temp_path = temp_dir.path();
+        let routes = json!({"/health": {"response": {"status": 200, "body": {}}}});
+
+        let fs_manager = FileSystemManager::new(Some(temp_path.to_path_buf()))
+            .expect("Should create filesystem manager");
+        let mut config_manager = ConfigManager::new(fs_manager);
+
+        let config = json!({"request_id_header": "X-Correlation-Id", "routes": routes});
+        create_test_file(temp_path, "config.json", config.to_string().as_bytes());
+        config_manager
+            .load_config("config.json")
+            .await
+            .expect("A valid header name should load");
+        assert_eq!(
+            config_manager
+                .config
+                .request_id_header
+                .as_ref()
+                .map(http::HeaderName::as_str),
+            Some("x-correlation-id")
+        );
+
+        let config = json!({"request_id_header": "X Correlation", "routes": routes});
+        create_test_file(temp_path, "config.json", config.to_string().as_bytes());
+        let error = config_manager
+            .load_config("config.json")
+            .await
+            .expect_err("An invalid header name should be rejected");
+        assert!(
+            error
+                .to_string()
+                .contains("request_id_header \"X Correlation\" is not a valid header name"),
+            "{error}"
+        );
+    }
+
+    /// Tests that unsupported charsets are rejected when loading.
+    ///
+    /// Verifies both the global `charset` and a route's, and that a supported
+    /// alias such as `latin-1` loads.
+    #[tokio::test]
+    async fn test_config_manager_rejects_unsupported_charset() {
+        let temp_dir = setup_test_dir();
+        let temp_path = temp_dir.path();
+        let route = json!({"response": {"status": 200, "body": "hello"}});
+
+        let fs_manager = FileSystemManager::new(Some(temp_path.to_path_buf()))
+            .expect("Should create filesystem manager");
+        let mut config_manager = ConfigManager::new(fs_manager);
+        for (config, expected) in [
+            (
+                json!({"charset": "shift_jis", "routes": {"/greeting": route}}),
+                "Unsupported charset \"shift_jis\"",
+            ),
+            (
+                json!({"routes": {"/greeting": {"charset": "koi8-r", "response": route["response"]}}}),
+                "Route [GET] /greeting has unsupported charset \"koi8-r\"",
+            ),
+        ] {
+            create_test_file(temp_path, "config.json", config.to_string().as_bytes());
+            let error = config_manager
+                .load_config("config.json")
+                .await
+                .expect_err("An unsupported charset should be rejected");
+            assert!(error.to_string().contains(expected), "{error}");
+        }
+
+        let config = json!({"charset": "latin-1", "routes": {"/greeting": route}});
+        create_test_file(temp_path, "config.json", config.to_string().as_bytes());
+        config_manager
+            .load_config("config.json")
+            .await
+            .expect("A supported charset alias should load");
+    }
+
+    /// Tests that localized tags differing only in case are rejected when loading.
+    #[tokio::test]
+    async fn test_config_manager_rejects_case_duplicate_localized_tags() {
+        let temp_dir = setup_test_dir();
+        let temp_path = temp_dir.path();
+
+        create_test_file(
+            temp_path,
+            "config.json",
+            json!({"routes": {"/greeting": {
+                "localized": {"fr": "Bonjour", "de": "Hallo", "FR": "Salut"},
+                "response": {"status": 200, "body": "Hello"}
+            }}})
+            .to_string()
+            .as_bytes(),
+        );
+
+        let fs_manager = FileSystemManager::new(Some(temp_path.to_path_buf()))
+            .expect("Should create filesystem manager");
+        let mut config_manager = ConfigManager::new(fs_manager);
+        let error = config_manager
+            .load_config("config.json")
+            .await
+            .expect_err("Case-duplicate tags should be rejected");
+
+        assert!(
+            error.to_string().contains(
+                "Route [GET] /greeting has localized tags FR and fr differing only in case"
+            ),
+            "{error}"
+        );
+    }
+
+    /// Tests that `retry_after` dates are rendered when loading, and invalid ones rejected.
+    #[tokio::test]
+    async fn test_config_manager_validates_retry_after() {
+        let temp_dir = setup_test_dir();
+        let temp_path = temp_dir.path();
+        let fs_manager = FileSystemManager::new(Some(temp_path.to_path_buf()))
+            .expect("Should create filesystem manager");
+        let mut config_manager = ConfigManager::new(fs_manager);
+
+        let config = json!({
+            "maintenance_retry_after": "2026-10-14T09:30:00Z",
+            "routes": {"/api/search": {
+                "retry_after": "2026-10-14T11:30:00+02:00",
+                "error": {"status": 503}
+            }}
+        });
+        create_test_file(temp_path, "config.json", config.to_string().as_bytes());
+        config_manager
+            .load_config("config.json")
+            .await
+            .expect("Valid dates should load");
+        let rendered = Some(RetryAfter::Date(
+            "Wed, 14 Oct 2026 09:30:00 GMT".to_string(),
+        ));
+        assert_eq!(config_manager.config.maintenance_retry_after, rendered);
+        assert_eq!(
+            config_manager.config.routes["[GET] /api/search"].retry_after,
+            rendered
+        );
+
+        for (config, message) in [
+            (
+                json!({"routes": {"/api/search": {"retry_after": "tomorrow", "error": {"status": 503}}}}),
+                "/api/search has an invalid retry_after",
+            ),
+            (
+                json!({"maintenance_retry_after": "soon", "routes": {"/health": {"response": {"status": 200, "body": {}}}}}),
+                "maintenance_retry_after has an invalid retry_after",
+            ),
+        ] {
+            create_test_file(temp_path, "config.json", config.to_string().as_bytes());
+
+            let error = config_manager
+                .load_config("config.json")
+                .await
+                .expect_err("Invalid dates should be rejected");
+            assert!(error.to_string().contains(message), "{error}");
+        }
+    }
+
+    /// Tests that proxy routes to anything but an `http://` or `https://` target are rejected.
+    #[tokio::test]
+    async fn test_config_manager_rejects_non_http_proxy() {
+        let temp_dir = setup_test_dir();
+        let temp_path = temp_dir.path();
+
+        create_test_file(
+            temp_path,
+            "config.json",
+            br#"{"routes": {"/payments/{*path}": {"proxy": {"target": "ftp://sandbox.example"}}}}"#,
+        );
+        create_test_file(
+            temp_path,
+            "https.json",
+            br#"{"routes": {"/payments/{*path}": {"proxy": {"target": "https://sandbox.example"}}}}"#,
+        );
+
+        let fs_manager = FileSystemManager::new(Some(temp_path.to_path_buf()))
+            .expect("Should create filesystem manager");
+        let error = ConfigManager::new(fs_manager.clone())
+            .load_config("config.json")
+            .await
+            .expect_err("An ftp target should be rejected");
+        assert!(
+            error
+                .to_string()
+                .contains("only http:// and https:// targets"),
+            "{error}"
+        );
+
+        ConfigManager::new(fs_manager)
+            .load_config("https.json")
+            .await
+            .expect("An https target should load");
+    }
+
+    /// Tests that a `max_concurrency` of zero is rejected when the configuration loads.
+    #[tokio::test]
+    async fn test_config_manager_rejects_zero_concurrency() {
+        let temp_dir = setup_test_dir();
+        let temp_path = temp_dir.path();
+
+        create_test_file(
+            temp_path,
+            "config.json",
+            br#"{"max_concurrency": 0, "routes": {"/api/users": {"response": {"status": 200, "body": []}}}}"#,
+        );
+
+        let fs_manager = FileSystemManager::new(Some(temp_path.to_path_buf()))
+            .expect("Should create filesystem manager");
+        let mut config_manager = ConfigManager::new(fs_manager);
+
+        let error = config_manager
+            .load_config("config.json")
+            .await
+            .expect_err("A zero limit should be rejected");
+        assert!(error.to_string().contains("max_concurrency"));
+    }
 }
@@ -32,7 +32,7 @@
 //! - Data integrity across update and merge operations
 
 use json_echo_core::{
-    BodyResponse, ConfigResponse, ConfigRoute, ConfigRouteResponse, Database, Model,
+    BodyResponse, ConfigResponse, ConfigRoute, ConfigRouteResponse, Database, IdStrategy, Model,
 };
 use serde_json::{Value, json};
 use std::collections::HashMap;
@@ -70,6 +70,7 @@ fn create_test_route(
             status: Some(200),
             body: BodyResponse::Value(response_data),
         }),
+        ..ConfigRoute::default()
     }
 }
 
@@ -419,10 +420,157 @@ mod database_query_tests {
         let model = db.get_model("nonexistent");
         assert!(model.is_none(), "Should not find non-existent model");
     }
+
+    /// Tests indexed model lookups after repeated populate calls.
+    ///
+    /// Verifies that models are listed in identifier order, that lookups keep
+    /// resolving to the first registered model, and that updates through the
+    /// index reach the right model.
+    #[test]
+    fn test_database_get_model_after_populate() {
+        let mut db = create_test_database();
+
+        let identifiers: Vec<&str> = db.get_models().iter().map(Model::get_identifier).collect();
+        assert_eq!(
+            identifiers,
+            vec![
+                "[GET] /api/products",
+                "[GET] /api/status",
+                "[GET] /api/users"
+            ],
+            "Models should be ordered by identifier"
+        );
+
+        let mut routes = HashMap::new();
+        routes.insert(
+            "[GET] /api/status".to_string(),
+            create_test_route("GET", None, "id", None, json!({"status": "down"})),
+        );
+        db.populate(routes);
+
+        let status = db
+            .get_model("[GET] /api/status")
+            .expect("Status model should be found");
+        assert_eq!(
+            status.get_data().as_value()["status"],
+            json!("ok"),
+            "Lookup should resolve to the first registered model"
+        );
+        assert!(db.get_model("[GET] /api/users").is_some());
+
+        db.set_model_data("[GET] /api/products", json!([]))
+            .expect("Indexed model should be updatable");
+        assert_eq!(
+            db.get_model("[GET] /api/products")
+                .expect("Products model should be found")
+                .get_data()
+                .as_value(),
+            json!([])
+        );
+    }
+
+    /// Micro-benchmark of model lookups over many routes.
+    ///
+    /// Verifies that every one of many models is reachable through the index
+    /// and that the lookups stay well within a generous time budget.
+    #[test]
+    fn test_database_get_model_many_routes() {
+        let mut db = Database::new();
+        let routes: HashMap<String, ConfigRoute> = (0..2_000)
+            .map(|index| {
+                (
+                    format!("[GET] /api/resource/{index}"),
+                    create_test_route("GET", None, "id", None, json!({"index": index})),
+                )
+            })
+            .collect();
+        db.populate(routes);
+
+        let started = std::time::Instant::now();
+        for round in 0..10 {
+            for index in 0..2_000 {
+                let model = db
+                    .get_model(&format!("[GET] /api/resource/{index}"))
+                    .expect("Every model should be indexed");
+                if round == 0 {
+                    assert_eq!(model.get_data().as_value()["index"], json!(index));
+                }
+            }
+        }
+        assert!(
+            started.elapsed() < std::time::Duration::from_secs(5),
+            "20000 lookups over 2000 routes took {:?}",
+            started.elapsed()
+        );
+
+        assert!(db.get_model("[GET] /api/resource/2000").is_none());
+    }
+
+    /// Tests resolving route identifiers through aliases.
+    ///
+    /// Verifies that alias chains resolve to the route holding the data, that
+    /// routes without an alias resolve to themselves, and that a circular chain
+    /// still terminates.
+    #[test]
+    fn test_database_resolve_alias() {
+        let mut db = Database::new();
+        let alias = |target: &str| ConfigRoute {
+            alias: Some(target.to_string()),
+            ..ConfigRoute::default()
+        };
+
+        let mut routes = HashMap::new();
+        routes.insert(
+            "[GET] /users".to_string(),
+            create_test_route("GET", None, "id", None, json!([{"id": 1}])),
+        );
+        routes.insert("[GET] /v1/users".to_string(), alias("[GET] /users"));
+        routes.insert("[GET] /v0/users".to_string(), alias("[GET] /v1/users"));
+        routes.insert("[GET] /loop/a".to_string(), alias("[GET] /loop/b"));
+        routes.insert("[GET] /loop/b".to_string(), alias("[GET] /loop/a"));
+        db.populate(routes);
+
+        assert_eq!(db.resolve_alias("[GET] /v0/users"), "[GET] /users");
+        assert_eq!(db.resolve_alias("[GET] /users"), "[GET] /users");
+        assert_eq!(db.resolve_alias("[GET] /unknown"), "[GET] /unknown");
+
+        let model = db
+            .get_model(db.resolve_alias("[GET] /v1/users"))
+            .expect("Alias should resolve to the target model");
+        assert_eq!(model.get_value().as_ref(), &json!([{"id": 1}]));
+
+        let looped = db.resolve_alias("[GET] /loop/a");
+        assert!(looped == "[GET] /loop/a" || looped == "[GET] /loop/b");
+    }
+
+    /// Tests that disabled routes are skipped when populating.
+    ///
+    /// Verifies that a route with `enabled: false` has neither a route nor a
+    /// model, while explicitly and implicitly enabled routes are kept.
+    #[test]
+    fn test_database_populate_skips_disabled_routes() {
+        let mut db = Database::new();
+        let with_enabled = |enabled: Option<bool>| ConfigRoute {
+            enabled,
+            ..create_test_route("GET", None, "id", None, json!([]))
+        };
+
+        let mut routes = HashMap::new();
+        routes.insert("[GET] /users".to_string(), with_enabled(None));
+        routes.insert("[GET] /orders".to_string(), with_enabled(Some(true)));
+        routes.insert("[GET] /debug".to_string(), with_enabled(Some(false)));
+        db.populate(routes);
+
+        assert_eq!(db.get_routes().len(), 2);
+        assert!(db.get_route("[GET] /debug", None).is_none());
+        assert!(db.get_model("[GET] /debug").is_none());
+        assert!(db.get_route("[GET] /orders", None).is_some());
+    }
 }
 
 mod model_structure_tests {
     use super::*;
+    use std::{borrow::Cow, sync::Arc};
 
     /// Tests Model creation with all parameters.
     ///
@@ -577,6 +725,127 @@ mod model_structure_tests {
             panic!("Expected Value variant");
         }
     }
+
+    /// Tests borrowed data retrieval on a large array route.
+    ///
+    /// Verifies that get_value returns the same data as get_data for JSON
+    /// bodies, with and without a results field, while borrowing it from the
+    /// model instead of cloning it.
+    #[test]
+    fn test_model_get_value_borrows_large_array() {
+        let items: Vec<Value> = (0..10_000)
+            .map(|id| json!({"id": id, "name": format!("item-{id}")}))
+            .collect();
+
+        let plain = Model::new(
+            "plain".to_string(),
+            "id".to_string(),
+            None,
+            None,
+            ConfigRouteResponse {
+                status: Some(200),
+                body: BodyResponse::Value(Value::Array(items.clone())),
+            },
+        );
+        let nested = Model::new(
+            "nested".to_string(),
+            "id".to_string(),
+            Some("items".to_string()),
+            None,
+            ConfigRouteResponse {
+                status: Some(200),
+                body: BodyResponse::Value(json!({"items": items, "count": 10_000})),
+            },
+        );
+
+        for model in [&plain, &nested] {
+            let value = model.get_value();
+            assert!(
+                matches!(value, Cow::Borrowed(_)),
+                "JSON bodies should be borrowed"
+            );
+            assert_eq!(
+                *value,
+                model.get_data().as_value(),
+                "Borrowed data should match the cloned data"
+            );
+            assert_eq!(value.as_array().map(Vec::len), Some(10_000));
+        }
+
+        let mut search = HashMap::new();
+        search.insert("id".to_string(), "9999".to_string());
+        assert_eq!(
+            nested.find_entry_by_hashmap(search),
+            Some(json!({"id": 9999, "name": "item-9999"})),
+            "Entry lookup should still honor the results field"
+        );
+    }
+
+    /// Tests that model snapshots share data until one of them is mutated.
+    ///
+    /// Verifies that cloning a model only clones a pointer to its data and that
+    /// updates copy the data instead of changing earlier snapshots.
+    #[test]
+    fn test_model_shared_data_copy_on_write() {
+        let mut model = Model::new(
+            "users".to_string(),
+            "id".to_string(),
+            None,
+            None,
+            ConfigRouteResponse {
+                status: Some(200),
+                body: BodyResponse::Value(json!([{"id": 1, "name": "John"}])),
+            },
+        );
+
+        let snapshot = model.clone();
+        assert!(
+            Arc::ptr_eq(&snapshot.get_shared_data(), &model.get_shared_data()),
+            "Cloned model should share its data"
+        );
+
+        model
+            .update_data(json!({"id": 2, "name": "Jane"}))
+            .expect("Update should succeed");
+
+        assert!(
+            !Arc::ptr_eq(&snapshot.get_shared_data(), &model.get_shared_data()),
+            "Update should copy the shared data"
+        );
+        assert_eq!(snapshot.get_value().as_array().map(Vec::len), Some(1));
+        assert_eq!(model.get_value().as_array().map(Vec::len), Some(2));
+
+        let unshared = model.get_shared_data();
+        drop(unshared);
+        let before = Arc::as_ptr(&model.get_shared_data());
+        model
+            .update_data(json!({"id": 3, "name": "Bob"}))
+            .expect("Update should succeed");
+        assert_eq!(
+            before,
+            Arc::as_ptr(&model.get_shared_data()),
+            "Unshared data should be updated in place"
+        );
+    }
+
+    /// Tests borrowed data retrieval on a string body.
+    ///
+    /// Verifies that string bodies are converted into an owned JSON string.
+    #[test]
+    fn test_model_get_value_string_body() {
+        let model = Model::new(
+            "text".to_string(),
+            "id".to_string(),
+            None,
+            None,
+            ConfigRouteResponse {
+                status: Some(200),
+                body: BodyResponse::String("hello".to_string()),
+            },
+        );
+
+        assert_eq!(model.get_value().into_owned(), json!("hello"));
+    }
 }
 
 mod data_merging_tests {
@@ -764,17 +1033,48 @@ mod data_merging_tests {
 
         // Check that the users array was updated
         let updated_data = model.get_data();
-        if let BodyResponse::Value(users_data) = updated_data {
-            if let Value::Array(users_arr) = users_data {
-                assert_eq!(users_arr.len(), 2, "Users array should have two items");
-                assert_eq!(users_arr[1]["id"], 2, "New user should be added");
-            }
+        if let BodyResponse::Value(users_data) = updated_data
+            && let Value::Array(users_arr) = users_data
+        {
+            assert_eq!(users_arr.len(), 2, "Users array should have two items");
+            assert_eq!(users_arr[1]["id"], 2, "New user should be added");
         }
 
         // We can't directly access the full structure to verify count is unchanged
         // but we can verify that the results field functionality worked correctly
     }
 
+    /// Tests merging into an explicitly chosen results field.
+    ///
+    /// Verifies that the given field is targeted even without a model-level
+    /// results_field, and that `None` merges into the whole body.
+    #[test]
+    fn test_model_update_data_in_results_field() {
+        let mut model = Model::new(
+            "test".to_string(),
+            "id".to_string(),
+            None,
+            None,
+            ConfigRouteResponse {
+                status: Some(200),
+                body: BodyResponse::Value(json!({"page": 1, "users": [{"id": 1}]})),
+            },
+        );
+
+        model
+            .update_data_in(json!({"id": 2}), Some("users"))
+            .expect("Merge into users should succeed");
+        assert_eq!(
+            model.get_value().as_ref(),
+            &json!({"page": 1, "users": [{"id": 1}, {"id": 2}]})
+        );
+
+        model
+            .update_data_in(json!({"page": 2}), None)
+            .expect("Merge into the body should succeed");
+        assert_eq!(model.get_value()["page"], json!(2));
+    }
+
     /// Tests merging with non-existent results field.
     ///
     /// Verifies that update_data creates the results field when it doesn't
@@ -806,6 +1106,45 @@ mod data_merging_tests {
         }
     }
 
+    /// Tests putting into a non-existent results field.
+    ///
+    /// Verifies that put_data_in creates the results field as an array holding
+    /// the entry, keeps an array put as it is, and merges once the field exists.
+    #[test]
+    fn test_model_put_data_creates_results_collection() {
+        let config_data = ConfigRouteResponse {
+            status: Some(200),
+            body: BodyResponse::Value(json!({"count": 0})),
+        };
+        let mut model = Model::new(
+            "test".to_string(),
+            "id".to_string(),
+            Some("items".to_string()),
+            None,
+            config_data.clone(),
+        );
+
+        model
+            .put_data_in(json!({"id": 1}), Some("items"))
+            .expect("Creating the results field should succeed");
+        model
+            .put_data_in(json!({"id": 2}), Some("items"))
+            .expect("Putting into the results field should succeed");
+        assert_eq!(model.get_value().as_ref(), &json!([{"id": 1}, {"id": 2}]));
+
+        let mut model = Model::new(
+            "test".to_string(),
+            "id".to_string(),
+            None,
+            None,
+            config_data,
+        );
+        model
+            .put_data_in(json!([{"id": 1}]), Some("items"))
+            .expect("Creating the results field should succeed");
+        assert_eq!(model.get_value()["items"], json!([{"id": 1}]));
+    }
+
     /// Tests error handling for string-based responses.
     ///
     /// Verifies that update_data returns an appropriate error when trying
@@ -1028,14 +1367,13 @@ mod database_update_tests {
         // Verify the update
         let model = db.get_model("[GET] /api/users").unwrap();
         let data = model.get_data();
-        if let BodyResponse::Value(value) = data {
-            if let Value::Object(obj) = value {
-                if let Some(Value::Array(users)) = obj.get("users") {
-                    assert_eq!(users.len(), 3, "Should have three users now");
-                    assert_eq!(users[2]["id"], 3, "New user should be added");
-                    assert_eq!(users[2]["name"], "Bob", "New user name should match");
-                }
-            }
+        if let BodyResponse::Value(value) = data
+            && let Value::Object(obj) = value
+            && let Some(Value::Array(users)) = obj.get("users")
+        {
+            assert_eq!(users.len(), 3, "Should have three users now");
+            assert_eq!(users[2]["id"], 3, "New user should be added");
+            assert_eq!(users[2]["name"], "Bob", "New user name should match");
         }
     }
 
@@ -1079,28 +1417,190 @@ mod database_update_tests {
         // Verify the update
         let model = db.get_model("[GET] /api/users").unwrap();
         let data = model.get_data();
-        if let BodyResponse::Value(value) = data {
-            if let Value::Object(obj) = value {
-                if let Some(Value::Array(users)) = obj.get("users") {
-                    assert_eq!(users.len(), 2, "Should still have two users");
-                    let updated_user = &users[0];
-                    assert_eq!(updated_user["id"], 1, "User ID should be preserved");
-                    assert_eq!(
-                        updated_user["name"], "John Doe Updated",
-                        "User name should be updated"
-                    );
-                    assert_eq!(
-                        updated_user["email"], "john.updated@example.com",
-                        "User email should be updated"
-                    );
-                    assert_eq!(
-                        updated_user["status"], "active",
-                        "New status field should be added"
-                    );
-                }
-            }
+        if let BodyResponse::Value(value) = data
+            && let Value::Object(obj) = value
+            && let Some(Value::Array(users)) = obj.get("users")
+        {
+            assert_eq!(users.len(), 2, "Should still have two users");
+            let updated_user = &users[0];
+            assert_eq!(updated_user["id"], 1, "User ID should be preserved");
+            assert_eq!(
+                updated_user["name"], "John Doe Updated",
+                "User name should be updated"
+            );
+            assert_eq!(
+                updated_user["email"], "john.updated@example.com",
+                "User email should be updated"
+            );
+            assert_eq!(
+                updated_user["status"], "active",
+                "New status field should be added"
+            );
         }
     }
+
+    /// Tests replacing model data through database.
+    ///
+    /// Verifies that set_model_data overwrites the nested results field
+    /// instead of merging into it.
+    #[test]
+    fn test_database_set_model_data_replaces_results() {
+        let mut db = create_test_database();
+
+        let result = db.set_model_data("[GET] /api/users", json!([{"id": 9, "name": "Zed"}]));
+        assert!(result.is_ok(), "Model data replacement should succeed");
+
+        let model = db.get_model("[GET] /api/users").unwrap();
+        assert_eq!(
+            model.get_data().as_value(),
+            json!([{"id": 9, "name": "Zed"}]),
+            "Results field should be replaced as a whole"
+        );
+    }
+
+    /// Tests replacing model data without a results field.
+    ///
+    /// Verifies that set_model_data replaces the entire body when the model
+    /// has no results field, and fails for unknown identifiers.
+    #[test]
+    fn test_database_set_model_data_replaces_body() {
+        let mut db = create_test_database();
+
+        let result = db.set_model_data("[GET] /api/status", json!({"status": "down"}));
+        assert!(result.is_ok(), "Model data replacement should succeed");
+
+        let model = db.get_model("[GET] /api/status").unwrap();
+        assert_eq!(
+            model.get_data().as_value(),
+            json!({"status": "down"}),
+            "Body should be replaced as a whole"
+        );
+
+        let missing = db.set_model_data("nonexistent", json!({}));
+        assert!(missing.is_err(), "Replacing nonexistent model should fail");
+    }
+
+    /// Creates a database serving two users with the given id strategy.
+    fn database_with_strategy(strategy: IdStrategy) -> Database {
+        let mut route = create_test_route(
+            "GET",
+            None,
+            "id",
+            None,
+            json!([{"id": 3, "name": "John"}, {"id": 7, "name": "Jane"}]),
+        );
+        route.id_strategy = Some(strategy);
+
+        let mut db = Database::new();
+        db.populate(HashMap::from([("[GET] /api/users".to_string(), route)]));
+        db
+    }
+
+    /// Returns the entry named `name` served by the users model.
+    fn user_named(db: &Database, name: &str) -> Value {
+        let data = db
+            .get_model("[GET] /api/users")
+            .unwrap()
+            .get_data()
+            .as_value();
+        data.as_array()
+            .and_then(|users| users.iter().find(|user| user["name"] == name))
+            .cloned()
+            .expect("The user should have been added")
+    }
+
+    /// Tests generating sequential ids for added entries.
+    ///
+    /// Verifies that an entry without an id gets the highest id plus one,
+    /// while an entry carrying its own id keeps it.
+    #[test]
+    fn test_database_sequential_id_strategy() {
+        let mut db = database_with_strategy(IdStrategy::Sequential);
+
+        db.update_model_data("[GET] /api/users", json!({"name": "Ada"}))
+            .unwrap();
+        assert_eq!(user_named(&db, "Ada")["id"], 8, "Sequential ids are max+1");
+
+        db.update_model_data("[GET] /api/users", json!({"id": 42, "name": "Bob"}))
+            .unwrap();
+        assert_eq!(user_named(&db, "Bob")["id"], 42, "Given ids are kept");
+    }
+
+    /// Tests generating uuid and ulid ids for added entries.
+    ///
+    /// Verifies that both strategies produce string ids in their format.
+    #[test]
+    fn test_database_string_id_strategies() {
+        let mut db = database_with_strategy(IdStrategy::Uuid);
+        db.update_model_data("[GET] /api/users", json!({"name": "Ada"}))
+            .unwrap();
+        let id = user_named(&db, "Ada")["id"].clone();
+        let id = id.as_str().expect("Uuid ids are strings");
+        let parsed = uuid::Uuid::parse_str(id).expect("The id should be a valid UUID");
+        assert_eq!(parsed.get_version_num(), 4, "Uuid ids are random v4 UUIDs");
+
+        let mut db = database_with_strategy(IdStrategy::Ulid);
+        db.update_model_data("[GET] /api/users", json!({"name": "Ada"}))
+            .unwrap();
+        let id = user_named(&db, "Ada")["id"].clone();
+        let id = id.as_str().expect("Ulid ids are strings");
+        assert_eq!(id.len(), 26, "Ulids have 26 characters");
+        assert!(
+            id.chars()
+                .all(|c| "0123456789ABCDEFGHJKMNPQRSTVWXYZ".contains(c)),
+            "Ulids use the Crockford base32 alphabet: {id}"
+        );
+    }
+
+    /// Tests assigning an id once for entries written to several models.
+    ///
+    /// Verifies that assign_id fills a missing id and leaves entries that
+    /// already have one, or models without a strategy, untouched.
+    #[test]
+    fn test_database_assign_id() {
+        let db = database_with_strategy(IdStrategy::Sequential);
+
+        let user = db.assign_id("[GET] /api/users", json!({"name": "Ada"}), None);
+        assert_eq!(user, json!({"name": "Ada", "id": 8}));
+
+        let user = db.assign_id("[GET] /api/users", json!({"id": 1}), None);
+        assert_eq!(user, json!({"id": 1}));
+
+        let user = create_test_database().assign_id("[GET] /api/users", json!({}), None);
+        assert_eq!(user, json!({}), "Models without a strategy assign nothing");
+    }
+
+    /// Tests loading the response of a lazy route once.
+    ///
+    /// Verifies that a lazy route's model is pending on its file until
+    /// load_pending stores the response, and that later loads are ignored.
+    #[test]
+    fn test_database_load_pending() {
+        let route = ConfigRoute {
+            lazy: true,
+            response: ConfigResponse::String("/fixtures/catalog.json".to_string()),
+            ..ConfigRoute::default()
+        };
+        let mut db = Database::new();
+        db.populate([("[GET] /api/catalog".to_string(), route)]);
+
+        let model = db.get_model("[GET] /api/catalog").unwrap();
+        assert_eq!(
+            model.get_pending_file(),
+            Some(std::path::Path::new("/fixtures/catalog.json"))
+        );
+
+        let loaded = |name: &str| ConfigRouteResponse {
+            status: Some(200),
+            body: BodyResponse::Value(json!([{"name": name}])),
+        };
+        assert!(db.load_pending("[GET] /api/catalog", loaded("first")));
+        assert!(!db.load_pending("[GET] /api/catalog", loaded("second")));
+
+        let model = db.get_model("[GET] /api/catalog").unwrap();
+        assert_eq!(model.get_pending_file(), None);
+        assert_eq!(*model.get_value(), json!([{"name": "first"}]));
+    }
 }
 
 mod integration_tests {
@@ -0,0 +1,133 @@
+//! Tests for the environment variables that steer project discovery.
+//!
+//! ## What
+//!
+//! The test suite covers:
+//! - `JSON_ECHO_ROOT` pinning the root used by `PathUtils::find_root` and
+//!   `FileSystemManager::new(None)`, with a fallback to the search
+//! - `JSON_ECHO_CONFIG_NAMES` adding discovery names used by both root
+//!   discovery and `ConfigManager::get_config_file_path`
+//!
+//! ## How
+//!
+//! The environment is shared by every thread of a process, so these tests live
+//! in their own test binary, keeping the variables from leaking into the
+//! discovery tests of other suites. Within this binary every test holds
+//! `ENV_LOCK` while the environment is modified.
+//!
+//! ## Why
+//!
+//! CI layouts often run the tool from directories that contain unrelated
+//! `db.json` files, and teams name their mock files differently; both
+//! overrides must win over the built-in search.
+
+use json_echo_core::{
+    CONFIG_NAMES_ENV_VAR, ConfigManager, FileSystemManager, PathUtils, ROOT_ENV_VAR,
+};
+use std::{ffi::OsStr, fs, path::Path};
+use tokio::sync::Mutex;
+
+/// Serializes the tests of this binary, which all modify the environment.
+static ENV_LOCK: Mutex<()> = Mutex::const_new(());
+
+/// Sets or clears an environment variable for this process.
+fn set_env(key: &str, value: Option<&OsStr>) {
+    // SAFETY: callers hold ENV_LOCK, so no other test reads the environment concurrently
+    unsafe {
+        match value {
+            Some(value) => std::env::set_var(key, value),
+            None => std::env::remove_var(key),
+        }
+    }
+}
+
+/// Sets or clears `JSON_ECHO_ROOT` for this process.
+fn set_root_env(root: Option<&Path>) {
+    set_env(ROOT_ENV_VAR, root.map(Path::as_os_str));
+}
+
+/// Tests that `JSON_ECHO_ROOT` pins the root and falls back when invalid.
+///
+/// Verifies that an existing directory is used even though the starting
+/// directory contains its own `db.json`, that a manager without an explicit
+/// root loads files from it, and that a missing directory falls back to the
+/// regular search.
+#[tokio::test]
+async fn test_root_env_var_overrides_search() {
+    let _guard = ENV_LOCK.lock().await;
+    let pinned = tempfile::tempdir().expect("Failed to create temporary directory");
+    let searched = tempfile::tempdir().expect("Failed to create temporary directory");
+    fs::write(pinned.path().join("fixture.json"), b"[]").expect("Failed to write fixture");
+    fs::write(searched.path().join("db.json"), b"{}").expect("Failed to write db.json");
+
+    set_root_env(Some(pinned.path()));
+
+    assert_eq!(
+        PathUtils::find_root(searched.path()),
+        Some(pinned.path().to_path_buf()),
+        "The pinned root should win over the search"
+    );
+
+    let manager = FileSystemManager::new(None).expect("Pinned root should be used");
+    assert_eq!(manager.root, PathUtils::normalize_path(pinned.path()));
+    assert_eq!(
+        manager
+            .load_file("fixture.json")
+            .await
+            .expect("Fixture should load from the pinned root"),
+        b"[]"
+    );
+
+    set_root_env(Some(&pinned.path().join("missing")));
+
+    assert_eq!(
+        PathUtils::find_root(searched.path()),
+        Some(searched.path().to_path_buf()),
+        "A missing pinned root should fall back to the search"
+    );
+
+    set_root_env(None);
+}
+
+/// Tests discovery with a custom configuration file name.
+///
+/// Verifies that a name added through `JSON_ECHO_CONFIG_NAMES` is found by
+/// both `PathUtils::find_root` and `ConfigManager::get_config_file_path`, and
+/// that it takes precedence over the built-in names.
+#[tokio::test]
+async fn test_custom_config_file_name() {
+    let _guard = ENV_LOCK.lock().await;
+    let project = tempfile::tempdir().expect("Failed to create temporary directory");
+    let nested = project.path().join("src/api");
+    fs::create_dir_all(&nested).expect("Failed to create nested directory");
+    fs::write(project.path().join("mocks.json"), b"{}").expect("Failed to write mocks.json");
+
+    set_env(
+        CONFIG_NAMES_ENV_VAR,
+        Some(OsStr::new(" mocks.json , db.json")),
+    );
+
+    assert_eq!(
+        PathUtils::config_file_names(),
+        ["mocks.json", "db.json", ".db.json", "json-echo.json"]
+    );
+    assert_eq!(
+        PathUtils::find_root(&nested),
+        Some(project.path().to_path_buf()),
+        "The custom name should mark the project root"
+    );
+
+    fs::write(project.path().join("json-echo.json"), b"{}").expect("Failed to write config");
+    let fs_manager = FileSystemManager::new(Some(project.path().to_path_buf()))
+        .expect("Failed to create FileSystemManager");
+    let config_path = ConfigManager::new(fs_manager)
+        .get_config_file_path()
+        .expect("Should find a configuration file");
+    assert!(
+        config_path.ends_with("mocks.json"),
+        "Custom names should be checked first: {}",
+        config_path.display()
+    );
+
+    set_env(CONFIG_NAMES_ENV_VAR, None);
+}
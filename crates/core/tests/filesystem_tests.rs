@@ -339,6 +339,29 @@ mod path_utils_tests {
             normalized.display()
         );
     }
+
+    /// Tests lexical normalization without following symlinks.
+    ///
+    /// Verifies that `.` and `..` components are resolved purely from the
+    /// path text, including paths that do not exist.
+    #[test]
+    fn test_normalize_path_no_symlinks() {
+        let cases = [
+            ("./fixtures/../data/./users.json", "data/users.json"),
+            ("/srv/mocks/../../etc", "/etc"),
+            ("/../etc", "/etc"),
+            ("../outside/./file.json", "../outside/file.json"),
+            ("a/..", "."),
+        ];
+
+        for (path, expected) in cases {
+            assert_eq!(
+                PathUtils::normalize_path_no_symlinks(Path::new(path)),
+                PathBuf::from(expected),
+                "Unexpected normalization of {path}"
+            );
+        }
+    }
 }
 
 mod filesystem_manager_tests {
@@ -489,6 +512,194 @@ mod filesystem_manager_tests {
         }
     }
 
+    /// Tests that file errors name the attempted path.
+    ///
+    /// Verifies that missing files on load and missing parent directories on
+    /// save report the path relative to the root instead of `<unknown>`.
+    #[tokio::test]
+    async fn test_file_errors_report_path() {
+        let temp_dir = setup_test_dir();
+        let temp_path = temp_dir.path().to_path_buf();
+
+        let manager = FileSystemManager::new(Some(temp_path.clone()))
+            .expect("Failed to create FileSystemManager");
+
+        match manager.load_file("fixtures/missing.json").await {
+            Err(FileSystemError::NotFound { path }) => {
+                assert_eq!(path, temp_path.join("fixtures/missing.json"));
+            }
+            other => panic!("Expected a NotFound error, got: {other:?}"),
+        }
+
+        let error = manager
+            .save_file("missing_dir/out.json", b"{}".to_vec())
+            .await
+            .expect_err("Saving into a missing directory should fail");
+        let message = error.to_string();
+        assert!(
+            message.contains("missing_dir/out.json") && !message.contains("<unknown>"),
+            "Error should name the attempted path: {message}"
+        );
+    }
+
+    /// Tests that file references are confined to the root.
+    ///
+    /// Verifies that files inside the root resolve to their canonical path and
+    /// that `..` references and absolute paths leaving the root are rejected
+    /// with a validation error.
+    #[tokio::test]
+    async fn test_resolve_within_root() {
+        let temp_dir = setup_test_dir();
+        let project = temp_dir.path().join("project");
+        fs::create_dir_all(project.join("fixtures")).expect("Failed to create project");
+        create_test_file(&project, "fixtures/users.json", b"[]");
+        create_test_file(temp_dir.path(), "secret.json", b"{}");
+
+        let manager = FileSystemManager::new(Some(project.clone()))
+            .expect("Failed to create FileSystemManager");
+
+        let resolved = manager
+            .resolve_within_root("fixtures/../fixtures/users.json")
+            .await
+            .expect("Files inside the root should resolve");
+        assert_eq!(
+            resolved,
+            project
+                .join("fixtures/users.json")
+                .canonicalize()
+                .expect("Failed to canonicalize")
+        );
+
+        let outside = temp_dir.path().join("secret.json");
+        for reference in ["../secret.json", outside.to_str().expect("UTF-8 path")] {
+            match manager.resolve_within_root(reference).await {
+                Err(FileSystemError::Validation { .. }) => {}
+                other => panic!("Expected {reference} to be rejected, got: {other:?}"),
+            }
+        }
+    }
+
+    /// Tests that directories are created below the root only.
+    ///
+    /// Verifies that missing parents are created and that `..` references and
+    /// absolute paths are rejected without creating anything outside the root.
+    #[tokio::test]
+    async fn test_create_dir_within_root() {
+        let temp_dir = setup_test_dir();
+        let project = temp_dir.path().join("project");
+        fs::create_dir_all(&project).expect("Failed to create project");
+
+        let manager = FileSystemManager::new(Some(project.clone()))
+            .expect("Failed to create FileSystemManager");
+
+        let created = manager
+            .create_dir_within_root("uploads/avatars")
+            .await
+            .expect("Directories inside the root should be created");
+        assert!(created.is_dir());
+        assert!(created.ends_with("uploads/avatars"));
+
+        let outside = temp_dir.path().join("elsewhere");
+        for reference in ["../escaped", outside.to_str().expect("UTF-8 path")] {
+            match manager.create_dir_within_root(reference).await {
+                Err(FileSystemError::Validation { .. }) => {}
+                other => panic!("Expected {reference} to be rejected, got: {other:?}"),
+            }
+        }
+        assert!(!temp_dir.path().join("escaped").exists());
+        assert!(!outside.exists());
+    }
+
+    /// Tests that the strict symlink policy rejects symlinked files.
+    ///
+    /// Verifies that a symlinked file and a file inside a symlinked directory
+    /// load under the default policy but are rejected with a validation error
+    /// under `SymlinkPolicy::Forbid`, while regular files still load.
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_symlink_policy_forbid() {
+        use json_echo_core::SymlinkPolicy;
+        use std::os::unix::fs::symlink;
+
+        let temp_dir = setup_test_dir();
+        let temp_path = temp_dir.path().to_path_buf();
+        create_test_file(&temp_path, "real.json", b"{}");
+        fs::create_dir(temp_path.join("real_dir")).expect("Failed to create directory");
+        create_test_file(&temp_path, "real_dir/data.json", b"[]");
+        symlink(temp_path.join("real.json"), temp_path.join("link.json"))
+            .expect("Failed to create file symlink");
+        symlink(temp_path.join("real_dir"), temp_path.join("link_dir"))
+            .expect("Failed to create directory symlink");
+
+        let following = FileSystemManager::new(Some(temp_path.clone()))
+            .expect("Failed to create FileSystemManager");
+        assert!(following.load_file("link.json").await.is_ok());
+        assert!(following.load_file("link_dir/data.json").await.is_ok());
+
+        let strict = following.with_symlink_policy(SymlinkPolicy::Forbid);
+        assert!(strict.load_file("real.json").await.is_ok());
+        assert!(strict.load_file("real_dir/data.json").await.is_ok());
+
+        for path in ["link.json", "link_dir/data.json", "real_dir/../link.json"] {
+            match strict.load_file(path).await {
+                Err(FileSystemError::Validation { .. }) => {}
+                other => panic!("Expected {path} to be rejected, got: {other:?}"),
+            }
+        }
+        assert!(matches!(
+            strict.save_file("link_dir/new.json", b"{}".to_vec()).await,
+            Err(FileSystemError::Validation { .. })
+        ));
+        assert!(matches!(
+            strict.resolve_within_root("link.json").await,
+            Err(FileSystemError::Validation { .. })
+        ));
+    }
+
+    /// Tests that listing files survives symlink loops and dangling symlinks.
+    ///
+    /// Verifies that a link back to an ancestor directory is walked once, that
+    /// a link to an existing file does not list it twice, and that a dangling
+    /// link is skipped rather than failing the listing.
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_list_files_skips_loops_and_dangling_links() {
+        use std::os::unix::fs::symlink;
+
+        let temp_dir = setup_test_dir();
+        let temp_path = temp_dir.path().to_path_buf();
+        fs::create_dir_all(temp_path.join("routes/users")).expect("Failed to create directory");
+        create_test_file(&temp_path, "routes/users/list.json", b"{}");
+        symlink(
+            temp_path.join("routes"),
+            temp_path.join("routes/users/loop"),
+        )
+        .expect("Failed to create loop symlink");
+        symlink(
+            temp_path.join("routes/users/list.json"),
+            temp_path.join("routes/alias.json"),
+        )
+        .expect("Failed to create file symlink");
+        symlink(
+            temp_path.join("missing.json"),
+            temp_path.join("routes/dangling.json"),
+        )
+        .expect("Failed to create dangling symlink");
+
+        let manager =
+            FileSystemManager::new(Some(temp_path)).expect("Failed to create FileSystemManager");
+        let files = tokio::time::timeout(
+            std::time::Duration::from_secs(5),
+            manager.list_files("routes"),
+        )
+        .await
+        .expect("Listing should not loop")
+        .expect("Listing should succeed");
+
+        assert_eq!(files.len(), 1, "Unexpected files: {files:?}");
+        assert!(files[0].ends_with("routes/users/list.json"));
+    }
+
     /// Tests file loading with relative paths.
     ///
     /// Verifies that load_file correctly handles relative paths and
@@ -758,6 +969,58 @@ mod filesystem_manager_tests {
 mod error_handling_tests {
     use super::*;
 
+    /// Tests that transient I/O failures are retried with backoff.
+    ///
+    /// Verifies that an operation failing twice with an `Io` error succeeds on
+    /// the third attempt, that the waits double, and that `NotFound` errors
+    /// are returned without retrying.
+    #[tokio::test(start_paused = true)]
+    async fn test_retry_transient_errors() {
+        use std::{cell::Cell, time::Duration};
+
+        let temp_dir = setup_test_dir();
+        let manager = FileSystemManager::new(Some(temp_dir.path().to_path_buf()))
+            .expect("Failed to create FileSystemManager")
+            .with_retries(3, Duration::from_millis(100));
+
+        let attempts = Cell::new(0);
+        let started = tokio::time::Instant::now();
+        let result = manager
+            .retry(|| {
+                attempts.set(attempts.get() + 1);
+                let attempt = attempts.get();
+                async move {
+                    if attempt <= 2 {
+                        Err(FileSystemError::Io {
+                            path: PathBuf::from("flaky.txt"),
+                            message: "Resource temporarily unavailable".to_string(),
+                        })
+                    } else {
+                        Ok(attempt)
+                    }
+                }
+            })
+            .await;
+
+        assert_eq!(result.expect("Third attempt should succeed"), 3);
+        assert_eq!(started.elapsed(), Duration::from_millis(300));
+
+        attempts.set(0);
+        let result: Result<(), _> = manager
+            .retry(|| {
+                attempts.set(attempts.get() + 1);
+                async {
+                    Err(FileSystemError::NotFound {
+                        path: PathBuf::from("missing.txt"),
+                    })
+                }
+            })
+            .await;
+
+        assert!(matches!(result, Err(FileSystemError::NotFound { .. })));
+        assert_eq!(attempts.get(), 1, "NotFound should not be retried");
+    }
+
     /// Tests error handling when FileSystemManager cannot access files due to permissions.
     ///
     /// Note: This test may behave differently on different platforms due to